@@ -59,6 +59,11 @@ pub enum Subcommand {
 	/// capabilities of running a validator.
 	HostPerfCheck,
 
+	/// Check the connectivity of a running validator to the authorities of the current session.
+	///
+	/// Fails if the validator is connected to less than the given percentage of authorities.
+	ConnectivityReport(ConnectivityReportCmd),
+
 	/// Try some command against runtime state.
 	#[cfg(feature = "try-runtime")]
 	TryRuntime(try_runtime_cli::TryRuntimeCmd),
@@ -82,6 +87,20 @@ pub struct ValidationWorkerCommand {
 	pub socket_path: String,
 }
 
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct ConnectivityReportCmd {
+	/// Websocket RPC endpoint of the node to check.
+	///
+	/// The node needs to allow unsafe RPC methods.
+	#[arg(long, default_value = "ws://127.0.0.1:9944")]
+	pub url: String,
+
+	/// Minimum percentage of authorities the node needs to be connected to.
+	#[arg(long, default_value_t = 90)]
+	pub threshold: usize,
+}
+
 #[allow(missing_docs)]
 #[derive(Debug, Parser)]
 #[group(skip)]
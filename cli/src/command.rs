@@ -14,7 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::cli::{Cli, Subcommand};
+use crate::{
+	cli::{Cli, Subcommand},
+	connectivity_report::connectivity_report,
+};
 use frame_benchmarking_cli::{BenchmarkCmd, ExtrinsicFactory, SUBSTRATE_REFERENCE_HARDWARE};
 use futures::future::TryFutureExt;
 use log::info;
@@ -645,6 +648,10 @@ pub fn run() -> Result<()> {
 
 			host_perf_check()
 		},
+		Some(Subcommand::ConnectivityReport(cmd)) => {
+			let runtime = sc_cli::build_runtime().map_err(sc_cli::Error::from)?;
+			runtime.block_on(connectivity_report(cmd))
+		},
 		Some(Subcommand::Key(cmd)) => Ok(cmd.run(&cli)?),
 		#[cfg(feature = "try-runtime")]
 		Some(Subcommand::TryRuntime(cmd)) => {
@@ -0,0 +1,59 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{cli::ConnectivityReportCmd, error::Error};
+use jsonrpsee::ws_client::WsClientBuilder;
+use polkadot_rpc::connectivity::{AuthorityConnectivity, ConnectivityApiClient};
+
+/// Fetch the connectivity report of a running node and print it.
+///
+/// Fails if the node is connected to less than the configured percentage of authorities.
+pub async fn connectivity_report(cmd: &ConnectivityReportCmd) -> Result<(), Error> {
+	let client = WsClientBuilder::default().build(&cmd.url).await?;
+	let report = client.connectivity_report().await?;
+
+	match report.session_index {
+		Some(session_index) => println!("Session: {}", session_index),
+		None => println!("Session: unknown"),
+	}
+	println!(
+		"{:<50} {:<9} {:<11} {:<10} {:<5} {}",
+		"authority", "resolved", "validation", "collation", "grid", "last error"
+	);
+	for row in &report.authorities {
+		print_row(row);
+	}
+
+	let connected = report.connected_ratio();
+	println!("Connected to {}% of {} authorities.", connected, report.authorities.len());
+	if connected < cmd.threshold {
+		return Err(Error::LowConnectivity { connected, threshold: cmd.threshold })
+	}
+	Ok(())
+}
+
+fn print_row(row: &AuthorityConnectivity) {
+	let yes_no = |b: bool| if b { "yes" } else { "no" };
+	println!(
+		"{:<50} {:<9} {:<11} {:<10} {:<5} {}",
+		row.authority_id.to_string(),
+		yes_no(row.addresses.is_some()),
+		yes_no(row.validation_peer.is_some()),
+		yes_no(row.collation_peer.is_some()),
+		yes_no(row.is_grid_neighbor),
+		row.last_error.as_deref().unwrap_or("-"),
+	);
+}
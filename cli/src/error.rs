@@ -49,6 +49,12 @@ pub enum Error {
 	#[error("Command is not implemented")]
 	CommandNotImplemented,
 
+	#[error(transparent)]
+	Rpc(#[from] jsonrpsee::core::Error),
+
+	#[error("Connected to {connected}% of authorities, expected at least {threshold}%")]
+	LowConnectivity { connected: usize, threshold: usize },
+
 	#[error(transparent)]
 	Storage(#[from] sc_storage_monitor::Error),
 
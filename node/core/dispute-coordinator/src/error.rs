@@ -44,6 +44,10 @@ pub enum Error {
 	#[error("Participation worker receiver exhausted.")]
 	ParticipationWorkerReceiverExhausted,
 
+	#[fatal]
+	#[error("Slash report receiver exhausted.")]
+	SlashReportReceiverExhausted,
+
 	/// Receiving subsystem message from overseer failed.
 	#[fatal]
 	#[error("Receiving message from overseer failed: {0}")]
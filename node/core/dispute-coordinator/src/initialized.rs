@@ -16,11 +16,14 @@
 
 //! Dispute coordinator subsystem in initialized state (after first active leaf is received).
 
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+	collections::{BTreeMap, HashSet},
+	sync::Arc,
+};
 
 use futures::{
 	channel::{mpsc, oneshot},
-	FutureExt, StreamExt,
+	FutureExt, SinkExt, StreamExt,
 };
 
 use sc_keystore::LocalKeystore;
@@ -30,17 +33,19 @@ use polkadot_node_primitives::{
 };
 use polkadot_node_subsystem::{
 	messages::{
-		ApprovalVotingMessage, BlockDescription, ChainSelectionMessage, DisputeCoordinatorMessage,
-		DisputeDistributionMessage, ImportStatementsResult,
+		ApprovalVotingMessage, BlockDescription, ChainApiMessage, ChainSelectionMessage,
+		DisputeCoordinatorMessage, DisputeDistributionMessage, ImportStatementsResult,
 	},
 	overseer, ActivatedLeaf, ActiveLeavesUpdate, FromOrchestra, OverseerSignal,
 };
-use polkadot_node_subsystem_util::rolling_session_window::{
-	RollingSessionWindow, SessionWindowUpdate, SessionsUnavailable,
+use polkadot_node_subsystem_util::{
+	request_session_index_for_child,
+	rolling_session_window::{RollingSessionWindow, SessionWindowUpdate, SessionsUnavailable},
+	runtime::{get_unapplied_slashes, key_ownership_proof, submit_report_dispute_lost},
 };
 use polkadot_primitives::{
-	BlockNumber, CandidateHash, CandidateReceipt, CompactStatement, DisputeStatement,
-	DisputeStatementSet, Hash, ScrapedOnChainVotes, SessionIndex, SessionInfo,
+	vstaging::slashing, BlockNumber, CandidateHash, CandidateReceipt, CompactStatement,
+	DisputeStatement, DisputeStatementSet, Hash, ScrapedOnChainVotes, SessionIndex, SessionInfo,
 	ValidDisputeStatementKind, ValidatorId, ValidatorIndex,
 };
 
@@ -79,6 +84,16 @@ pub struct Initialized {
 	scraper: ChainScraper,
 	participation_receiver: WorkerMessageReceiver,
 	metrics: Metrics,
+	// The latest block we know of for each session in the window. Key ownership proofs for
+	// dispute slashing need to be generated at a block of the session of the offence. This is only
+	// a cache: blocks of sessions missing here are looked up on the finalized chain, `None` if
+	// that lookup failed already.
+	session_anchors: BTreeMap<SessionIndex, Option<Hash>>,
+	// The slashes we have reported already or are reporting in the background, so they are not
+	// reported again on every leaf while they are pending. Pruned along with the session window.
+	reported_slashes: HashSet<(SessionIndex, CandidateHash, ValidatorIndex)>,
+	slash_report_sender: mpsc::Sender<SlashReportOutcome>,
+	slash_report_receiver: mpsc::Receiver<SlashReportOutcome>,
 	// This tracks only rolling session window failures.
 	// It can be a `Vec` if the need to track more arises.
 	error: Option<SessionsUnavailable>,
@@ -97,6 +112,7 @@ impl Initialized {
 
 		let (participation_sender, participation_receiver) = mpsc::channel(1);
 		let participation = Participation::new(participation_sender);
+		let (slash_report_sender, slash_report_receiver) = mpsc::channel(1);
 		let highest_session = rolling_session_window.latest_session();
 
 		Self {
@@ -108,6 +124,10 @@ impl Initialized {
 			participation,
 			participation_receiver,
 			metrics,
+			session_anchors: BTreeMap::new(),
+			reported_slashes: HashSet::new(),
+			slash_report_sender,
+			slash_report_receiver,
 			error: None,
 		}
 	}
@@ -188,18 +208,27 @@ impl Initialized {
 		}
 
 		if let Some(first_leaf) = first_leaf.take() {
+			let leaf_hash = first_leaf.hash;
 			// Also provide first leaf to participation for good measure.
 			self.participation
 				.process_active_leaves_update(ctx, &ActiveLeavesUpdate::start_work(first_leaf))
 				.await?;
+
+			self.session_anchors
+				.insert(self.rolling_session_window.latest_session(), Some(leaf_hash));
+			self.process_unapplied_slashes(ctx, leaf_hash).await?;
 		}
 
 		loop {
 			gum::trace!(target: LOG_TARGET, "Waiting for message");
 			let mut overlay_db = OverlayedBackend::new(backend);
 			let default_confirm = Box::new(|| Ok(()));
-			let confirm_write = match MuxedMessage::receive(ctx, &mut self.participation_receiver)
-				.await?
+			let confirm_write = match MuxedMessage::receive(
+				ctx,
+				&mut self.participation_receiver,
+				&mut self.slash_report_receiver,
+			)
+			.await?
 			{
 				MuxedMessage::Participation(msg) => {
 					gum::trace!(target: LOG_TARGET, "MuxedMessage::Participation");
@@ -232,6 +261,11 @@ impl Initialized {
 					}
 					default_confirm
 				},
+				MuxedMessage::SlashReport(outcome) => {
+					gum::trace!(target: LOG_TARGET, "MuxedMessage::SlashReport");
+					self.on_slash_report_outcome(outcome);
+					default_confirm
+				},
 				MuxedMessage::Subsystem(msg) => match msg {
 					FromOrchestra::Signal(OverseerSignal::Conclude) => return Ok(()),
 					FromOrchestra::Signal(OverseerSignal::ActiveLeaves(update)) => {
@@ -309,10 +343,15 @@ impl Initialized {
 
 						db::v1::note_earliest_session(overlay_db, new_window_start)?;
 						self.spam_slots.prune_old(new_window_start);
+						self.session_anchors = self.session_anchors.split_off(&new_window_start);
+						self.reported_slashes
+							.retain(|(session, _, _)| *session >= new_window_start);
 					}
 				},
 				Ok(SessionWindowUpdate::Unchanged) => {},
 			};
+			self.session_anchors
+				.insert(self.rolling_session_window.latest_session(), Some(new_leaf.hash));
 
 			// The `runtime-api` subsystem has an internal queue which serializes the execution,
 			// so there is no point in running these in parallel.
@@ -327,11 +366,121 @@ impl Initialized {
 					},
 				);
 			}
+
+			self.process_unapplied_slashes(ctx, new_leaf.hash).await?;
 		}
 
 		Ok(())
 	}
 
+	/// For each unapplied (past-session) slash we have not reported yet, report an unsigned
+	/// extrinsic to the runtime.
+	///
+	/// The key ownership proof of a validator is generated at a block of the session of the
+	/// offence. Finding such a block and generating the proofs takes a number of runtime
+	/// requests, so the reports are done by a background task. Slashes for which no such block
+	/// with state is available are skipped and stay in the runtime until the session is pruned; a
+	/// node that still has that state (e.g. an archive node) can generate the key ownership proof
+	/// at such a block via RPC and submit the report itself.
+	async fn process_unapplied_slashes<Context>(
+		&mut self,
+		ctx: &mut Context,
+		relay_parent: Hash,
+	) -> FatalResult<()> {
+		let pending = match get_unapplied_slashes(ctx.sender(), relay_parent).await {
+			Ok(pending) => pending,
+			Err(error) => {
+				gum::debug!(
+					target: LOG_TARGET,
+					?error,
+					?relay_parent,
+					"Failed to get unapplied slashes",
+				);
+				return Ok(())
+			},
+		};
+
+		let mut reports = Vec::new();
+		for (session_index, candidate_hash, pending) in pending {
+			let keys = pending
+				.keys
+				.into_iter()
+				.filter(|(validator_index, _)| {
+					!self.reported_slashes.contains(&(
+						session_index,
+						candidate_hash,
+						*validator_index,
+					))
+				})
+				.collect::<Vec<_>>();
+			if keys.is_empty() {
+				continue
+			}
+
+			let anchor = match self.session_anchors.get(&session_index) {
+				Some(Some(anchor)) => Some(*anchor),
+				Some(None) => {
+					gum::trace!(
+						target: LOG_TARGET,
+						?session_index,
+						?candidate_hash,
+						"No block of the session to prove key ownership at",
+					);
+					continue
+				},
+				// Will be looked up by the background task:
+				None => None,
+			};
+
+			self.reported_slashes.extend(
+				keys.iter()
+					.map(|(validator_index, _)| (session_index, candidate_hash, *validator_index)),
+			);
+			reports.push(SlashReport {
+				session_index,
+				candidate_hash,
+				kind: pending.kind,
+				keys,
+				anchor,
+			});
+		}
+
+		if reports.is_empty() {
+			return Ok(())
+		}
+
+		let sender = ctx.sender().clone();
+		ctx.spawn(
+			"dispute-slash-reporter",
+			report_unapplied_slashes(
+				self.slash_report_sender.clone(),
+				sender,
+				relay_parent,
+				reports,
+			)
+			.boxed(),
+		)
+		.map_err(FatalError::SpawnFailed)?;
+		Ok(())
+	}
+
+	/// Note the outcome of a background slash reporting task.
+	///
+	/// Blocks found for sessions are cached, failed reports are retried on the next leaf.
+	fn on_slash_report_outcome(&mut self, outcome: SlashReportOutcome) {
+		let SlashReportOutcome { anchors, failed } = outcome;
+		let earliest_session = self.rolling_session_window.earliest_session();
+		for (session_index, anchor) in anchors {
+			if session_index >= earliest_session {
+				// A block of a leaf we have seen in the meantime takes precedence:
+				self.session_anchors.entry(session_index).or_insert(anchor);
+			}
+		}
+		for slash in failed {
+			self.reported_slashes.remove(&slash);
+		}
+	}
+
 	/// Scrapes on-chain votes (backing votes and concluded disputes) for a active leaf of the
 	/// relay chain.
 	async fn process_on_chain_votes<Context>(
@@ -1229,6 +1378,8 @@ enum MuxedMessage {
 	Subsystem(FromOrchestra<DisputeCoordinatorMessage>),
 	/// Messages from participation workers.
 	Participation(participation::WorkerMessage),
+	/// Outcome of a background slash reporting task.
+	SlashReport(SlashReportOutcome),
 }
 
 #[overseer::contextbounds(DisputeCoordinator, prefix = self::overseer)]
@@ -1236,14 +1387,16 @@ impl MuxedMessage {
 	async fn receive<Context>(
 		ctx: &mut Context,
 		from_sender: &mut participation::WorkerMessageReceiver,
+		from_slash_reporter: &mut mpsc::Receiver<SlashReportOutcome>,
 	) -> FatalResult<Self> {
 		// We are only fusing here to make `select` happy, in reality we will quit if the stream
 		// ends.
 		let from_overseer = ctx.recv().fuse();
-		futures::pin_mut!(from_overseer, from_sender);
+		futures::pin_mut!(from_overseer, from_sender, from_slash_reporter);
 		futures::select!(
 			msg = from_overseer => Ok(Self::Subsystem(msg.map_err(FatalError::SubsystemReceive)?)),
 			msg = from_sender.next() => Ok(Self::Participation(msg.ok_or(FatalError::ParticipationWorkerReceiverExhausted)?)),
+			msg = from_slash_reporter.next() => Ok(Self::SlashReport(msg.ok_or(FatalError::SlashReportReceiverExhausted)?)),
 		)
 	}
 }
@@ -1305,3 +1458,203 @@ fn determine_undisputed_chain(
 
 	Ok(last)
 }
+
+/// Unapplied slashes of a dispute to be reported by a background task.
+struct SlashReport {
+	session_index: SessionIndex,
+	candidate_hash: CandidateHash,
+	kind: slashing::SlashingOffenceKind,
+	/// The validators to report.
+	keys: Vec<(ValidatorIndex, ValidatorId)>,
+	/// The block to generate key ownership proofs at, if known already.
+	anchor: Option<Hash>,
+}
+
+/// The result of a background slash reporting task.
+#[derive(Debug)]
+struct SlashReportOutcome {
+	/// Blocks looked up for sessions, `None` if there is none with state available.
+	anchors: Vec<(SessionIndex, Option<Hash>)>,
+	/// Slashes which could not be reported.
+	failed: Vec<(SessionIndex, CandidateHash, ValidatorIndex)>,
+}
+
+/// Report the given slashes, generating key ownership proofs at a block of the session of the
+/// offence.
+///
+/// The outcome is sent back to the dispute coordinator via `outcome_sender`.
+async fn report_unapplied_slashes<Sender>(
+	mut outcome_sender: mpsc::Sender<SlashReportOutcome>,
+	mut sender: Sender,
+	relay_parent: Hash,
+	reports: Vec<SlashReport>,
+) where
+	Sender: overseer::DisputeCoordinatorSenderTrait,
+{
+	let mut anchors = BTreeMap::new();
+	let mut failed = Vec::new();
+
+	for SlashReport { session_index, candidate_hash, kind, keys, anchor } in reports {
+		let anchor = match anchor {
+			Some(anchor) => Some(anchor),
+			None => match anchors.get(&session_index) {
+				Some(anchor) => *anchor,
+				None => {
+					let anchor = find_last_block_of_session(&mut sender, session_index).await;
+					anchors.insert(session_index, anchor);
+					anchor
+				},
+			},
+		};
+		let anchor = match anchor {
+			Some(anchor) => anchor,
+			None => {
+				gum::debug!(
+					target: LOG_TARGET,
+					?session_index,
+					?candidate_hash,
+					"No block of the session to prove key ownership at",
+				);
+				for (validator_index, _) in keys {
+					failed.push((session_index, candidate_hash, validator_index));
+				}
+				continue
+			},
+		};
+
+		for (validator_index, validator_id) in keys {
+			let key_ownership_proof =
+				match key_ownership_proof(&mut sender, anchor, validator_id.clone()).await {
+					Ok(Some(proof)) => proof,
+					Ok(None) => {
+						gum::debug!(
+							target: LOG_TARGET,
+							?session_index,
+							?candidate_hash,
+							?validator_id,
+							"Key ownership proof is not available",
+						);
+						failed.push((session_index, candidate_hash, validator_index));
+						continue
+					},
+					Err(error) => {
+						gum::debug!(
+							target: LOG_TARGET,
+							?error,
+							?session_index,
+							?candidate_hash,
+							?validator_id,
+							"Failed to generate key ownership proof, the state may be pruned",
+						);
+						failed.push((session_index, candidate_hash, validator_index));
+						continue
+					},
+				};
+
+			let time_slot = slashing::DisputesTimeSlot::new(session_index, candidate_hash);
+			let dispute_proof =
+				slashing::DisputeProof { time_slot, kind, validator_index, validator_id };
+
+			let res = submit_report_dispute_lost(
+				&mut sender,
+				relay_parent,
+				dispute_proof,
+				key_ownership_proof,
+			)
+			.await;
+
+			match res {
+				Err(error) => {
+					gum::warn!(
+						target: LOG_TARGET,
+						?error,
+						?session_index,
+						?candidate_hash,
+						"Error reporting pending slash",
+					);
+					// Failed reports are retried on the next leaf.
+					failed.push((session_index, candidate_hash, validator_index));
+				},
+				Ok(Some(())) => {
+					gum::info!(
+						target: LOG_TARGET,
+						?session_index,
+						?candidate_hash,
+						"Successfully reported pending slash",
+					);
+				},
+				Ok(None) => {
+					gum::debug!(
+						target: LOG_TARGET,
+						?session_index,
+						?candidate_hash,
+						"Duplicate pending slash report",
+					);
+				},
+			}
+		}
+	}
+
+	let outcome = SlashReportOutcome { anchors: anchors.into_iter().collect(), failed };
+	if let Err(error) = outcome_sender.send(outcome).await {
+		gum::debug!(
+			target: LOG_TARGET,
+			?error,
+			"Sending slash report outcome failed, dispute coordinator gone?",
+		);
+	}
+}
+
+/// Find the last finalized block of `session`, at which a key ownership proof for the session can
+/// be generated.
+///
+/// The session index only grows with the block number, so this is a binary search over the
+/// finalized chain. Returns `None` if the session has no finalized block or a lookup fails, e.g.
+/// because the state of the blocks is pruned.
+async fn find_last_block_of_session<Sender>(
+	sender: &mut Sender,
+	session: SessionIndex,
+) -> Option<Hash>
+where
+	Sender: overseer::DisputeCoordinatorSenderTrait,
+{
+	let (tx, rx) = oneshot::channel();
+	sender.send_message(ChainApiMessage::FinalizedBlockNumber(tx)).await;
+	let finalized = rx.await.ok()?.ok()?;
+
+	// Search the last block with a session of at most `session`. The genesis block is in
+	// session 0, so there is one.
+	let (mut low, mut high) = (0, finalized);
+	let mut found = None;
+	while low < high {
+		let mid = low + (high - low + 1) / 2;
+		let (hash, mid_session) = finalized_block_session(sender, mid).await?;
+		if mid_session <= session {
+			low = mid;
+			found = Some((hash, mid_session));
+		} else {
+			high = mid - 1;
+		}
+	}
+
+	let (hash, found_session) = match found {
+		Some(found) => found,
+		None => finalized_block_session(sender, low).await?,
+	};
+	(found_session == session).then(|| hash)
+}
+
+/// The hash of the finalized block with the given number and the session of its state.
+async fn finalized_block_session<Sender>(
+	sender: &mut Sender,
+	number: BlockNumber,
+) -> Option<(Hash, SessionIndex)>
+where
+	Sender: overseer::DisputeCoordinatorSenderTrait,
+{
+	let (tx, rx) = oneshot::channel();
+	sender.send_message(ChainApiMessage::FinalizedBlockHash(number, tx)).await;
+	let hash = rx.await.ok()?.ok()??;
+	let session = request_session_index_for_child(hash, sender).await.await.ok()?.ok()?;
+	Some((hash, session))
+}
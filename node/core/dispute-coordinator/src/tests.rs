@@ -61,10 +61,10 @@ use polkadot_node_subsystem_test_helpers::{
 	make_buffered_subsystem_context, TestSubsystemContextHandle,
 };
 use polkadot_primitives::{
-	ApprovalVote, BlockNumber, CandidateCommitments, CandidateEvent, CandidateHash,
-	CandidateReceipt, CoreIndex, DisputeStatement, GroupIndex, Hash, HeadData, Header, IndexedVec,
-	MultiDisputeStatementSet, ScrapedOnChainVotes, SessionIndex, SessionInfo, SigningContext,
-	ValidDisputeStatementKind, ValidatorId, ValidatorIndex, ValidatorSignature,
+	vstaging::slashing, ApprovalVote, BlockNumber, CandidateCommitments, CandidateEvent,
+	CandidateHash, CandidateReceipt, CoreIndex, DisputeStatement, GroupIndex, Hash, HeadData,
+	Header, IndexedVec, MultiDisputeStatementSet, ScrapedOnChainVotes, SessionIndex, SessionInfo,
+	SigningContext, ValidDisputeStatementKind, ValidatorId, ValidatorIndex, ValidatorSignature,
 };
 
 use crate::{
@@ -173,6 +173,8 @@ struct TestState {
 	last_block: Hash,
 	// last session the subsystem knows about.
 	known_session: Option<SessionIndex>,
+	// the answer to `UnappliedSlashes` requests.
+	unapplied_slashes: Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>,
 }
 
 impl Default for TestState {
@@ -242,6 +244,7 @@ impl Default for TestState {
 			block_num_to_header,
 			last_block,
 			known_session: None,
+			unapplied_slashes: Vec::new(),
 		}
 	}
 }
@@ -305,14 +308,21 @@ impl TestState {
 		struct FinishedSteps {
 			got_session_information: bool,
 			got_scraping_information: bool,
+			got_unapplied_slashes: bool,
 		}
 
 		impl FinishedSteps {
 			fn new() -> Self {
-				Self { got_session_information: false, got_scraping_information: false }
+				Self {
+					got_session_information: false,
+					got_scraping_information: false,
+					got_unapplied_slashes: false,
+				}
 			}
 			fn is_done(&self) -> bool {
-				self.got_session_information && self.got_scraping_information
+				self.got_session_information &&
+					self.got_scraping_information &&
+					self.got_unapplied_slashes
 			}
 		}
 
@@ -405,6 +415,17 @@ impl TestState {
 				AllMessages::DisputeDistribution(DisputeDistributionMessage::SendDispute(msg)) => {
 					sent_disputes.push(msg);
 				},
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_new_leaf,
+					RuntimeApiRequest::UnappliedSlashes(tx),
+				)) => {
+					assert!(
+						!finished_steps.got_unapplied_slashes,
+						"Unapplied slashes were already retrieved!"
+					);
+					finished_steps.got_unapplied_slashes = true;
+					tx.send(Ok(self.unapplied_slashes.clone())).unwrap();
+				},
 				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
 					_new_leaf,
 					RuntimeApiRequest::CandidateEvents(tx),
@@ -3430,3 +3451,137 @@ fn informs_chain_selection_when_dispute_concluded_against() {
 		})
 	});
 }
+
+#[test]
+fn unapplied_slashes_are_reported_once_at_a_block_of_their_session() {
+	test_harness(|mut test_state, mut virtual_overseer| {
+		Box::pin(async move {
+			let session = 1;
+
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			let candidate_hash = make_invalid_candidate_receipt().hash();
+			let validator_id = test_state.validator_public[ValidatorIndex(3)].clone();
+			test_state.unapplied_slashes = vec![(
+				session,
+				candidate_hash,
+				slashing::PendingSlashes {
+					keys: [(ValidatorIndex(3), validator_id.clone())].into_iter().collect(),
+					kind: slashing::SlashingOffenceKind::AgainstValid,
+				},
+			)];
+			test_state
+				.activate_leaf_at_session(&mut virtual_overseer, session + 1, 3, Vec::new())
+				.await;
+			let leaf = test_state.last_block;
+
+			// The key ownership proof is generated at a block of the session of the offence.
+			let key_ownership_proof = slashing::OpaqueKeyOwnershipProof::new(vec![1, 2, 3]);
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					relay_parent,
+					RuntimeApiRequest::KeyOwnershipProof(id, tx),
+				)) => {
+					assert_ne!(relay_parent, leaf);
+					assert!(test_state.headers.contains_key(&relay_parent));
+					assert_eq!(id, validator_id);
+					tx.send(Ok(Some(key_ownership_proof.clone()))).unwrap();
+				}
+			);
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					relay_parent,
+					RuntimeApiRequest::SubmitReportDisputeLost(dispute_proof, proof, tx),
+				)) => {
+					assert_eq!(relay_parent, leaf);
+					assert_eq!(
+						dispute_proof,
+						slashing::DisputeProof {
+							time_slot: slashing::DisputesTimeSlot::new(session, candidate_hash),
+							kind: slashing::SlashingOffenceKind::AgainstValid,
+							validator_index: ValidatorIndex(3),
+							validator_id,
+						},
+					);
+					assert_eq!(proof, key_ownership_proof);
+					tx.send(Ok(Some(()))).unwrap();
+				}
+			);
+
+			// The slash is still pending on the next leaf, but it is not reported again.
+			test_state
+				.activate_leaf_at_session(&mut virtual_overseer, session + 1, 4, Vec::new())
+				.await;
+
+			// Wrap up
+			virtual_overseer.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+			assert!(virtual_overseer.try_recv().await.is_none());
+
+			test_state
+		})
+	});
+}
+
+#[test]
+fn sessions_without_a_block_to_report_slashes_at_are_not_looked_up_again() {
+	test_harness(|mut test_state, mut virtual_overseer| {
+		Box::pin(async move {
+			let session = 1;
+
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			// We have not seen any leaf of session 0.
+			let candidate_hash = make_invalid_candidate_receipt().hash();
+			let validator_id = test_state.validator_public[ValidatorIndex(3)].clone();
+			test_state.unapplied_slashes = vec![(
+				0,
+				candidate_hash,
+				slashing::PendingSlashes {
+					keys: [(ValidatorIndex(3), validator_id)].into_iter().collect(),
+					kind: slashing::SlashingOffenceKind::AgainstValid,
+				},
+			)];
+			test_state
+				.activate_leaf_at_session(&mut virtual_overseer, session + 1, 3, Vec::new())
+				.await;
+
+			// The finalized chain has no block of session 0 with state.
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(tx)) => {
+					tx.send(Ok(0)).unwrap();
+				}
+			);
+			let genesis = Hash::repeat_byte(0x01);
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::ChainApi(ChainApiMessage::FinalizedBlockHash(0, tx)) => {
+					tx.send(Ok(Some(genesis))).unwrap();
+				}
+			);
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					relay_parent,
+					RuntimeApiRequest::SessionIndexForChild(tx),
+				)) => {
+					assert_eq!(relay_parent, genesis);
+					tx.send(Ok(session)).unwrap();
+				}
+			);
+
+			// The slash is still pending on the next leaf, but the session is not looked up again.
+			test_state
+				.activate_leaf_at_session(&mut virtual_overseer, session + 1, 4, Vec::new())
+				.await;
+
+			// Wrap up
+			virtual_overseer.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+			assert!(virtual_overseer.try_recv().await.is_none());
+
+			test_state
+		})
+	});
+}
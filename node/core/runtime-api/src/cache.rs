@@ -20,12 +20,12 @@ use lru::LruCache;
 use sp_consensus_babe::Epoch;
 
 use polkadot_primitives::{
-	vstaging::ExecutorParams, AuthorityDiscoveryId, BlockNumber, CandidateCommitments,
-	CandidateEvent, CandidateHash, CommittedCandidateReceipt, CoreState, DisputeState,
-	GroupRotationInfo, Hash, Id as ParaId, InboundDownwardMessage, InboundHrmpMessage,
-	OccupiedCoreAssumption, PersistedValidationData, PvfCheckStatement, ScrapedOnChainVotes,
-	SessionIndex, SessionInfo, ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex,
-	ValidatorSignature,
+	vstaging::{slashing, ExecutorParams, HrmpTopology, UpgradePipeline, ValidatorPerformance},
+	AuthorityDiscoveryId, BlockNumber, CandidateCommitments, CandidateEvent, CandidateHash,
+	CommittedCandidateReceipt, CoreState, DisputeState, GroupRotationInfo, Hash, Id as ParaId,
+	InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption, PersistedValidationData,
+	PvfCheckStatement, ScrapedOnChainVotes, SessionIndex, SessionInfo, ValidationCode,
+	ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
 };
 
 /// For consistency we have the same capacity for all caches. We use 128 as we'll only need that
@@ -64,6 +64,12 @@ pub(crate) struct RequestResultCache {
 		LruCache<(Hash, ParaId, OccupiedCoreAssumption), Option<ValidationCodeHash>>,
 	version: LruCache<Hash, u32>,
 	disputes: LruCache<Hash, Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>>,
+	hrmp_topology: LruCache<(Hash, ParaId), HrmpTopology>,
+	unapplied_slashes: LruCache<Hash, Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>>,
+	key_ownership_proof: LruCache<(Hash, ValidatorId), Option<slashing::OpaqueKeyOwnershipProof>>,
+	candidates_pending_availability: LruCache<(Hash, ParaId), Vec<CommittedCandidateReceipt>>,
+	validator_performance: LruCache<(Hash, SessionIndex), Option<Vec<ValidatorPerformance>>>,
+	para_upgrade_pipeline: LruCache<(Hash, ParaId), Option<UpgradePipeline<BlockNumber>>>,
 }
 
 impl Default for RequestResultCache {
@@ -91,6 +97,12 @@ impl Default for RequestResultCache {
 			validation_code_hash: LruCache::new(DEFAULT_CACHE_CAP),
 			version: LruCache::new(DEFAULT_CACHE_CAP),
 			disputes: LruCache::new(DEFAULT_CACHE_CAP),
+			hrmp_topology: LruCache::new(DEFAULT_CACHE_CAP),
+			unapplied_slashes: LruCache::new(DEFAULT_CACHE_CAP),
+			key_ownership_proof: LruCache::new(DEFAULT_CACHE_CAP),
+			candidates_pending_availability: LruCache::new(DEFAULT_CACHE_CAP),
+			validator_performance: LruCache::new(DEFAULT_CACHE_CAP),
+			para_upgrade_pipeline: LruCache::new(DEFAULT_CACHE_CAP),
 		}
 	}
 }
@@ -386,6 +398,89 @@ impl RequestResultCache {
 	) {
 		self.disputes.put(relay_parent, value);
 	}
+
+	pub(crate) fn hrmp_topology(&mut self, key: (Hash, ParaId)) -> Option<&HrmpTopology> {
+		self.hrmp_topology.get(&key)
+	}
+
+	pub(crate) fn cache_hrmp_topology(&mut self, key: (Hash, ParaId), value: HrmpTopology) {
+		self.hrmp_topology.put(key, value);
+	}
+
+	pub(crate) fn unapplied_slashes(
+		&mut self,
+		relay_parent: &Hash,
+	) -> Option<&Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>> {
+		self.unapplied_slashes.get(relay_parent)
+	}
+
+	pub(crate) fn cache_unapplied_slashes(
+		&mut self,
+		relay_parent: Hash,
+		value: Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>,
+	) {
+		self.unapplied_slashes.put(relay_parent, value);
+	}
+
+	pub(crate) fn key_ownership_proof(
+		&mut self,
+		key: (Hash, ValidatorId),
+	) -> Option<&Option<slashing::OpaqueKeyOwnershipProof>> {
+		self.key_ownership_proof.get(&key)
+	}
+
+	pub(crate) fn cache_key_ownership_proof(
+		&mut self,
+		key: (Hash, ValidatorId),
+		value: Option<slashing::OpaqueKeyOwnershipProof>,
+	) {
+		self.key_ownership_proof.put(key, value);
+	}
+
+	pub(crate) fn candidates_pending_availability(
+		&mut self,
+		key: (Hash, ParaId),
+	) -> Option<&Vec<CommittedCandidateReceipt>> {
+		self.candidates_pending_availability.get(&key)
+	}
+
+	pub(crate) fn cache_candidates_pending_availability(
+		&mut self,
+		key: (Hash, ParaId),
+		value: Vec<CommittedCandidateReceipt>,
+	) {
+		self.candidates_pending_availability.put(key, value);
+	}
+
+	pub(crate) fn validator_performance(
+		&mut self,
+		key: (Hash, SessionIndex),
+	) -> Option<&Option<Vec<ValidatorPerformance>>> {
+		self.validator_performance.get(&key)
+	}
+
+	pub(crate) fn cache_validator_performance(
+		&mut self,
+		key: (Hash, SessionIndex),
+		value: Option<Vec<ValidatorPerformance>>,
+	) {
+		self.validator_performance.put(key, value);
+	}
+
+	pub(crate) fn para_upgrade_pipeline(
+		&mut self,
+		key: (Hash, ParaId),
+	) -> Option<&Option<UpgradePipeline<BlockNumber>>> {
+		self.para_upgrade_pipeline.get(&key)
+	}
+
+	pub(crate) fn cache_para_upgrade_pipeline(
+		&mut self,
+		key: (Hash, ParaId),
+		value: Option<UpgradePipeline<BlockNumber>>,
+	) {
+		self.para_upgrade_pipeline.put(key, value);
+	}
 }
 
 pub(crate) enum RequestResult {
@@ -423,4 +518,16 @@ pub(crate) enum RequestResult {
 	ValidationCodeHash(Hash, ParaId, OccupiedCoreAssumption, Option<ValidationCodeHash>),
 	Version(Hash, u32),
 	Disputes(Hash, Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>),
+	HrmpTopology(Hash, ParaId, HrmpTopology),
+	UnappliedSlashes(Hash, Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>),
+	KeyOwnershipProof(Hash, ValidatorId, Option<slashing::OpaqueKeyOwnershipProof>),
+	SubmitReportDisputeLost(
+		Hash,
+		slashing::DisputeProof,
+		slashing::OpaqueKeyOwnershipProof,
+		Option<()>,
+	),
+	CandidatesPendingAvailability(Hash, ParaId, Vec<CommittedCandidateReceipt>),
+	ValidatorPerformance(Hash, SessionIndex, Option<Vec<ValidatorPerformance>>),
+	ParaUpgradePipeline(Hash, ParaId, Option<UpgradePipeline<BlockNumber>>),
 }
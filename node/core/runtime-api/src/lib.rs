@@ -157,6 +157,23 @@ where
 				self.requests_cache.cache_version(relay_parent, version),
 			Disputes(relay_parent, disputes) =>
 				self.requests_cache.cache_disputes(relay_parent, disputes),
+			HrmpTopology(relay_parent, para_id, topology) =>
+				self.requests_cache.cache_hrmp_topology((relay_parent, para_id), topology),
+			UnappliedSlashes(relay_parent, unapplied_slashes) =>
+				self.requests_cache.cache_unapplied_slashes(relay_parent, unapplied_slashes),
+			KeyOwnershipProof(relay_parent, validator_id, key_ownership_proof) => self
+				.requests_cache
+				.cache_key_ownership_proof((relay_parent, validator_id), key_ownership_proof),
+			SubmitReportDisputeLost(_, _, _, _) => {},
+			CandidatesPendingAvailability(relay_parent, para_id, candidates) => self
+				.requests_cache
+				.cache_candidates_pending_availability((relay_parent, para_id), candidates),
+			ValidatorPerformance(relay_parent, session_index, performance) => self
+				.requests_cache
+				.cache_validator_performance((relay_parent, session_index), performance),
+			ParaUpgradePipeline(relay_parent, para_id, pipeline) => self
+				.requests_cache
+				.cache_para_upgrade_pipeline((relay_parent, para_id), pipeline),
 		}
 	}
 
@@ -271,6 +288,27 @@ where
 					.map(|sender| Request::ValidationCodeHash(para, assumption, sender)),
 			Request::Disputes(sender) =>
 				query!(disputes(), sender).map(|sender| Request::Disputes(sender)),
+			Request::HrmpTopology(para, sender) =>
+				query!(hrmp_topology(para), sender)
+					.map(|sender| Request::HrmpTopology(para, sender)),
+			Request::UnappliedSlashes(sender) =>
+				query!(unapplied_slashes(), sender).map(|sender| Request::UnappliedSlashes(sender)),
+			Request::KeyOwnershipProof(validator_id, sender) =>
+				query!(key_ownership_proof(validator_id), sender)
+					.map(|sender| Request::KeyOwnershipProof(validator_id, sender)),
+			request @ Request::SubmitReportDisputeLost(_, _, _) => {
+				// This request is side-effecting and thus cannot be cached.
+				Some(request)
+			},
+			Request::CandidatesPendingAvailability(para, sender) =>
+				query!(candidates_pending_availability(para), sender)
+					.map(|sender| Request::CandidatesPendingAvailability(para, sender)),
+			Request::ValidatorPerformance(session_index, sender) =>
+				query!(validator_performance(session_index), sender)
+					.map(|sender| Request::ValidatorPerformance(session_index, sender)),
+			Request::ParaUpgradePipeline(para_id, sender) =>
+				query!(para_upgrade_pipeline(para_id), sender)
+					.map(|sender| Request::ParaUpgradePipeline(para_id, sender)),
 		}
 	}
 
@@ -490,5 +528,47 @@ where
 			query!(ValidationCodeHash, validation_code_hash(para, assumption), ver = 2, sender),
 		Request::Disputes(sender) =>
 			query!(Disputes, disputes(), ver = Request::DISPUTES_RUNTIME_REQUIREMENT, sender),
+		Request::HrmpTopology(para, sender) => query!(
+			HrmpTopology,
+			hrmp_topology(para),
+			ver = Request::HRMP_TOPOLOGY_RUNTIME_REQUIREMENT,
+			sender
+		),
+		Request::UnappliedSlashes(sender) => query!(
+			UnappliedSlashes,
+			unapplied_slashes(),
+			ver = Request::UNAPPLIED_SLASHES_RUNTIME_REQUIREMENT,
+			sender
+		),
+		Request::KeyOwnershipProof(validator_id, sender) => query!(
+			KeyOwnershipProof,
+			key_ownership_proof(validator_id),
+			ver = Request::KEY_OWNERSHIP_PROOF_RUNTIME_REQUIREMENT,
+			sender
+		),
+		Request::SubmitReportDisputeLost(dispute_proof, key_ownership_proof, sender) => query!(
+			SubmitReportDisputeLost,
+			submit_report_dispute_lost(dispute_proof, key_ownership_proof),
+			ver = Request::SUBMIT_REPORT_DISPUTE_LOST_RUNTIME_REQUIREMENT,
+			sender
+		),
+		Request::CandidatesPendingAvailability(para, sender) => query!(
+			CandidatesPendingAvailability,
+			candidates_pending_availability(para),
+			ver = Request::CANDIDATES_PENDING_AVAILABILITY_RUNTIME_REQUIREMENT,
+			sender
+		),
+		Request::ValidatorPerformance(session_index, sender) => query!(
+			ValidatorPerformance,
+			validator_performance(session_index),
+			ver = Request::VALIDATOR_PERFORMANCE_RUNTIME_REQUIREMENT,
+			sender
+		),
+		Request::ParaUpgradePipeline(para_id, sender) => query!(
+			ParaUpgradePipeline,
+			para_upgrade_pipeline(para_id),
+			ver = Request::PARA_UPGRADE_PIPELINE_RUNTIME_REQUIREMENT,
+			sender
+		),
 	}
 }
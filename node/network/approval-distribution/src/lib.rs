@@ -22,10 +22,11 @@
 
 use futures::{channel::oneshot, FutureExt as _};
 use polkadot_node_network_protocol::{
-	self as net_protocol,
+	self as net_protocol, filter_by_peer_version,
 	grid_topology::{RandomRouting, RequiredRouting, SessionGridTopologies, SessionGridTopology},
-	peer_set::MAX_NOTIFICATION_SIZE,
-	v1 as protocol_v1, PeerId, UnifiedReputationChange as Rep, Versioned, View,
+	peer_set::{ProtocolVersion, ValidationVersion, MAX_NOTIFICATION_SIZE},
+	v1 as protocol_v1, vstaging as protocol_vstaging, PeerId, UnifiedReputationChange as Rep,
+	Versioned, View,
 };
 use polkadot_node_primitives::approval::{
 	AssignmentCert, BlockApprovalMeta, IndirectAssignmentCert, IndirectSignedApprovalVote,
@@ -151,6 +152,15 @@ enum Resend {
 	No,
 }
 
+/// Data stored on a per-peer basis.
+#[derive(Debug)]
+struct PeerData {
+	/// The peer's view.
+	view: View,
+	/// The peer's protocol version.
+	version: ProtocolVersion,
+}
+
 /// The [`State`] struct is responsible for tracking the overall state of the subsystem.
 ///
 /// It tracks metadata about our view of the unfinalized chain,
@@ -170,7 +180,7 @@ struct State {
 	pending_known: HashMap<Hash, Vec<(PeerId, PendingMessage)>>,
 
 	/// Peer data is partially stored here, and partially inline within the [`BlockEntry`]s
-	peer_views: HashMap<PeerId, View>,
+	peer_data: HashMap<PeerId, PeerData>,
 
 	/// Keeps a topology for various different sessions.
 	topologies: SessionGridTopologies,
@@ -331,14 +341,16 @@ impl State {
 		rng: &mut (impl CryptoRng + Rng),
 	) {
 		match event {
-			NetworkBridgeEvent::PeerConnected(peer_id, role, _, _) => {
+			NetworkBridgeEvent::PeerConnected(peer_id, role, version, _) => {
 				// insert a blank view if none already present
-				gum::trace!(target: LOG_TARGET, ?peer_id, ?role, "Peer connected");
-				self.peer_views.entry(peer_id).or_default();
+				gum::trace!(target: LOG_TARGET, ?peer_id, ?role, ?version, "Peer connected");
+				self.peer_data
+					.entry(peer_id)
+					.or_insert_with(|| PeerData { view: Default::default(), version });
 			},
 			NetworkBridgeEvent::PeerDisconnected(peer_id) => {
 				gum::trace!(target: LOG_TARGET, ?peer_id, "Peer disconnected");
-				self.peer_views.remove(&peer_id);
+				self.peer_data.remove(&peer_id);
 				self.blocks.iter_mut().for_each(|(_hash, entry)| {
 					entry.known_by.remove(&peer_id);
 				})
@@ -375,7 +387,8 @@ impl State {
 					live
 				});
 			},
-			NetworkBridgeEvent::PeerMessage(peer_id, Versioned::V1(msg)) => {
+			NetworkBridgeEvent::PeerMessage(peer_id, Versioned::V1(msg)) |
+			NetworkBridgeEvent::PeerMessage(peer_id, Versioned::VStaging(msg)) => {
 				self.process_incoming_peer_message(ctx, metrics, peer_id, msg, rng).await;
 			},
 		}
@@ -425,16 +438,18 @@ impl State {
 
 		{
 			let sender = ctx.sender();
-			for (peer_id, view) in self.peer_views.iter() {
-				let intersection = view.iter().filter(|h| new_hashes.contains(h));
-				let view_intersection = View::new(intersection.cloned(), view.finalized_number);
+			for (peer_id, data) in self.peer_data.iter() {
+				let intersection = data.view.iter().filter(|h| new_hashes.contains(h));
+				let view_intersection =
+					View::new(intersection.cloned(), data.view.finalized_number);
 				Self::unify_with_peer(
 					sender,
 					metrics,
 					&mut self.blocks,
 					&self.topologies,
-					self.peer_views.len(),
+					self.peer_data.len(),
 					*peer_id,
+					data.version,
 					view_intersection,
 					rng,
 				)
@@ -519,6 +534,7 @@ impl State {
 			ctx,
 			&mut self.blocks,
 			&self.topologies,
+			&self.peer_data,
 			|block_entry| block_entry.session == session,
 			|required_routing, local, validator_index| {
 				if *required_routing == RequiredRouting::PendingTopology {
@@ -634,9 +650,14 @@ impl State {
 	{
 		gum::trace!(target: LOG_TARGET, ?view, "Peer view change");
 		let finalized_number = view.finalized_number;
-		let old_view =
-			self.peer_views.get_mut(&peer_id).map(|d| std::mem::replace(d, view.clone()));
-		let old_finalized_number = old_view.map(|v| v.finalized_number).unwrap_or(0);
+		let (old_view, protocol_version) = match self.peer_data.get_mut(&peer_id) {
+			Some(data) => (std::mem::replace(&mut data.view, view.clone()), data.version),
+			None => {
+				gum::trace!(target: LOG_TARGET, ?peer_id, "View change from unconnected peer");
+				return
+			},
+		};
+		let old_finalized_number = old_view.finalized_number;
 
 		// we want to prune every block known_by peer up to (including) view.finalized_number
 		let blocks = &mut self.blocks;
@@ -661,8 +682,9 @@ impl State {
 			metrics,
 			&mut self.blocks,
 			&self.topologies,
-			self.peer_views.len(),
+			self.peer_data.len(),
 			peer_id,
+			protocol_version,
 			view,
 			rng,
 		)
@@ -904,7 +926,7 @@ impl State {
 		// then messages will be sent when we get it.
 
 		let assignments = vec![(assignment, claimed_candidate_index)];
-		let n_peers_total = self.peer_views.len();
+		let n_peers_total = self.peer_data.len();
 		let source_peer = source.peer_id();
 
 		let mut peer_filter = move |peer| {
@@ -931,10 +953,16 @@ impl State {
 			route_random
 		};
 
-		let peers = entry.known_by.keys().filter(|p| peer_filter(p)).cloned().collect::<Vec<_>>();
+		let peer_data = &self.peer_data;
+		let peers = entry
+			.known_by
+			.keys()
+			.filter(|p| peer_filter(p))
+			.filter_map(|p| peer_data.get(p).map(|d| (*p, d.version)))
+			.collect::<Vec<_>>();
 
 		// Add the metadata of the assignment to the knowledge of each peer.
-		for peer in peers.iter() {
+		for (peer, _) in peers.iter() {
 			// we already filtered peers above, so this should always be Some
 			if let Some(peer_knowledge) = entry.known_by.get_mut(peer) {
 				peer_knowledge.sent.insert(message_subject.clone(), message_kind);
@@ -951,13 +979,24 @@ impl State {
 				"Sending an assignment to peers",
 			);
 
-			ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(
-				peers,
-				Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Assignments(assignments),
-				)),
-			))
-			.await;
+			let v1_peers = filter_by_peer_version(&peers, ValidationVersion::V1.into());
+			let vstaging_peers = filter_by_peer_version(&peers, ValidationVersion::VStaging.into());
+
+			if !v1_peers.is_empty() {
+				ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(
+					v1_peers,
+					versioned_assignments_packet(ValidationVersion::V1.into(), assignments.clone()),
+				))
+				.await;
+			}
+
+			if !vstaging_peers.is_empty() {
+				ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(
+					vstaging_peers,
+					versioned_assignments_packet(ValidationVersion::VStaging.into(), assignments),
+				))
+				.await;
+			}
 		}
 	}
 
@@ -1186,16 +1225,16 @@ impl State {
 			in_topology || knowledge.sent.contains(message_subject, MessageKind::Assignment)
 		};
 
+		let peer_data = &self.peer_data;
 		let peers = entry
 			.known_by
 			.iter()
 			.filter(|(p, k)| peer_filter(p, k))
-			.map(|(p, _)| p)
-			.cloned()
+			.filter_map(|(p, _)| peer_data.get(p).map(|d| (*p, d.version)))
 			.collect::<Vec<_>>();
 
 		// Add the metadata of the assignment to the knowledge of each peer.
-		for peer in peers.iter() {
+		for (peer, _) in peers.iter() {
 			// we already filtered peers above, so this should always be Some
 			if let Some(entry) = entry.known_by.get_mut(peer) {
 				entry.sent.insert(message_subject.clone(), message_kind);
@@ -1213,13 +1252,24 @@ impl State {
 				"Sending an approval to peers",
 			);
 
-			ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(
-				peers,
-				Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Approvals(approvals),
-				)),
-			))
-			.await;
+			let v1_peers = filter_by_peer_version(&peers, ValidationVersion::V1.into());
+			let vstaging_peers = filter_by_peer_version(&peers, ValidationVersion::VStaging.into());
+
+			if !v1_peers.is_empty() {
+				ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(
+					v1_peers,
+					versioned_approvals_packet(ValidationVersion::V1.into(), approvals.clone()),
+				))
+				.await;
+			}
+
+			if !vstaging_peers.is_empty() {
+				ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(
+					vstaging_peers,
+					versioned_approvals_packet(ValidationVersion::VStaging.into(), approvals),
+				))
+				.await;
+			}
 		}
 	}
 
@@ -1273,6 +1323,7 @@ impl State {
 		topologies: &SessionGridTopologies,
 		total_peers: usize,
 		peer_id: PeerId,
+		protocol_version: ProtocolVersion,
 		view: View,
 		rng: &mut (impl CryptoRng + Rng),
 	) {
@@ -1382,7 +1433,8 @@ impl State {
 				"Sending assignments to unified peer",
 			);
 
-			send_assignments_batched(sender, assignments_to_send, peer_id).await;
+			send_assignments_batched(sender, assignments_to_send, (peer_id, protocol_version))
+				.await;
 		}
 
 		if !approvals_to_send.is_empty() {
@@ -1393,7 +1445,7 @@ impl State {
 				"Sending approvals to unified peer",
 			);
 
-			send_approvals_batched(sender, approvals_to_send, peer_id).await;
+			send_approvals_batched(sender, approvals_to_send, (peer_id, protocol_version)).await;
 		}
 	}
 
@@ -1421,6 +1473,7 @@ impl State {
 			ctx,
 			&mut self.blocks,
 			&self.topologies,
+			&self.peer_data,
 			|block_entry| {
 				let block_age = max_age - block_entry.number;
 
@@ -1448,6 +1501,7 @@ impl State {
 			ctx,
 			&mut self.blocks,
 			&self.topologies,
+			&self.peer_data,
 			|block_entry| {
 				// Ramp up aggression only for the very oldest block(s).
 				// Approval voting can get stuck on a single block preventing
@@ -1505,6 +1559,7 @@ async fn adjust_required_routing_and_propagate<Context, BlockFilter, RoutingModi
 	ctx: &mut Context,
 	blocks: &mut HashMap<Hash, BlockEntry>,
 	topologies: &SessionGridTopologies,
+	peer_data: &HashMap<PeerId, PeerData>,
 	block_filter: BlockFilter,
 	routing_modifier: RoutingModifier,
 ) where
@@ -1592,11 +1647,15 @@ async fn adjust_required_routing_and_propagate<Context, BlockFilter, RoutingModi
 	// Send messages in accumulated packets, assignments preceding approvals.
 
 	for (peer, assignments_packet) in peer_assignments {
-		send_assignments_batched(ctx.sender(), assignments_packet, peer).await;
+		if let Some(data) = peer_data.get(&peer) {
+			send_assignments_batched(ctx.sender(), assignments_packet, (peer, data.version)).await;
+		}
 	}
 
 	for (peer, approvals_packet) in peer_approvals {
-		send_approvals_batched(ctx.sender(), approvals_packet, peer).await;
+		if let Some(data) = peer_data.get(&peer) {
+			send_approvals_batched(ctx.sender(), approvals_packet, (peer, data.version)).await;
+		}
 	}
 }
 
@@ -1758,6 +1817,38 @@ pub const MAX_APPROVAL_BATCH_SIZE: usize = ensure_size_not_zero(
 	MAX_NOTIFICATION_SIZE as usize / std::mem::size_of::<IndirectSignedApprovalVote>() / 3,
 );
 
+/// Build an assignments packet for a peer speaking the given protocol version.
+fn versioned_assignments_packet(
+	version: ProtocolVersion,
+	assignments: Vec<(IndirectAssignmentCert, CandidateIndex)>,
+) -> net_protocol::VersionedValidationProtocol {
+	if version == ValidationVersion::VStaging.into() {
+		Versioned::VStaging(protocol_vstaging::ValidationProtocol::ApprovalDistribution(
+			protocol_vstaging::ApprovalDistributionMessage::Assignments(assignments),
+		))
+	} else {
+		Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+			protocol_v1::ApprovalDistributionMessage::Assignments(assignments),
+		))
+	}
+}
+
+/// Build an approvals packet for a peer speaking the given protocol version.
+fn versioned_approvals_packet(
+	version: ProtocolVersion,
+	approvals: Vec<IndirectSignedApprovalVote>,
+) -> net_protocol::VersionedValidationProtocol {
+	if version == ValidationVersion::VStaging.into() {
+		Versioned::VStaging(protocol_vstaging::ValidationProtocol::ApprovalDistribution(
+			protocol_vstaging::ApprovalDistributionMessage::Approvals(approvals),
+		))
+	} else {
+		Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+			protocol_v1::ApprovalDistributionMessage::Approvals(approvals),
+		))
+	}
+}
+
 /// Send assignments while honoring the `max_notification_size` of the protocol.
 ///
 /// Splitting the messages into multiple notifications allows more granular processing at the
@@ -1766,8 +1857,9 @@ pub const MAX_APPROVAL_BATCH_SIZE: usize = ensure_size_not_zero(
 pub(crate) async fn send_assignments_batched(
 	sender: &mut impl overseer::ApprovalDistributionSenderTrait,
 	assignments: Vec<(IndirectAssignmentCert, CandidateIndex)>,
-	peer: PeerId,
+	peer: (PeerId, ProtocolVersion),
 ) {
+	let (peer_id, version) = peer;
 	let mut batches = assignments.into_iter().peekable();
 
 	while batches.peek().is_some() {
@@ -1775,10 +1867,8 @@ pub(crate) async fn send_assignments_batched(
 
 		sender
 			.send_message(NetworkBridgeTxMessage::SendValidationMessage(
-				vec![peer],
-				Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Assignments(batch),
-				)),
+				vec![peer_id],
+				versioned_assignments_packet(version, batch),
 			))
 			.await;
 	}
@@ -1788,8 +1878,9 @@ pub(crate) async fn send_assignments_batched(
 pub(crate) async fn send_approvals_batched(
 	sender: &mut impl overseer::ApprovalDistributionSenderTrait,
 	approvals: Vec<IndirectSignedApprovalVote>,
-	peer: PeerId,
+	peer: (PeerId, ProtocolVersion),
 ) {
+	let (peer_id, version) = peer;
 	let mut batches = approvals.into_iter().peekable();
 
 	while batches.peek().is_some() {
@@ -1797,10 +1888,8 @@ pub(crate) async fn send_approvals_batched(
 
 		sender
 			.send_message(NetworkBridgeTxMessage::SendValidationMessage(
-				vec![peer],
-				Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Approvals(batch),
-				)),
+				vec![peer_id],
+				versioned_approvals_packet(version, batch),
 			))
 			.await;
 	}
@@ -848,7 +848,7 @@ fn update_peer_view() {
 		virtual_overseer
 	});
 
-	assert_eq!(state.peer_views.get(peer).map(|v| v.finalized_number), Some(0));
+	assert_eq!(state.peer_data.get(peer).map(|d| d.view.finalized_number), Some(0));
 	assert_eq!(
 		state
 			.blocks
@@ -900,7 +900,7 @@ fn update_peer_view() {
 		virtual_overseer
 	});
 
-	assert_eq!(state.peer_views.get(peer).map(|v| v.finalized_number), Some(2));
+	assert_eq!(state.peer_data.get(peer).map(|d| d.view.finalized_number), Some(2));
 	assert_eq!(
 		state
 			.blocks
@@ -930,7 +930,7 @@ fn update_peer_view() {
 		virtual_overseer
 	});
 
-	assert_eq!(state.peer_views.get(peer).map(|v| v.finalized_number), Some(finalized_number));
+	assert_eq!(state.peer_data.get(peer).map(|d| d.view.finalized_number), Some(finalized_number));
 	assert!(state.blocks.get(&hash_c).unwrap().known_by.get(peer).is_none());
 }
 
@@ -2305,7 +2305,7 @@ fn batch_test_round(message_count: usize) {
 			})
 			.collect();
 
-		let peer = PeerId::random();
+		let peer = (PeerId::random(), ValidationVersion::V1.into());
 		send_assignments_batched(&mut sender, assignments.clone(), peer).await;
 		send_approvals_batched(&mut sender, approvals.clone(), peer).await;
 
@@ -2420,3 +2420,33 @@ fn const_ensure_size_not_zero() {
 	crate::ensure_size_not_zero(super::MAX_ASSIGNMENT_BATCH_SIZE);
 	crate::ensure_size_not_zero(super::MAX_APPROVAL_BATCH_SIZE);
 }
+
+#[test]
+fn packets_follow_peer_protocol_version() {
+	let assignments = vec![(fake_assignment_cert(Hash::zero(), ValidatorIndex(0)), 0)];
+	let approvals = vec![IndirectSignedApprovalVote {
+		block_hash: Hash::zero(),
+		candidate_index: 0,
+		validator: ValidatorIndex(0),
+		signature: dummy_signature(),
+	}];
+
+	assert_matches!(
+		versioned_assignments_packet(ValidationVersion::V1.into(), assignments.clone()),
+		Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+			protocol_v1::ApprovalDistributionMessage::Assignments(sent)
+		)) => assert_eq!(sent.len(), 1)
+	);
+	assert_matches!(
+		versioned_assignments_packet(ValidationVersion::VStaging.into(), assignments),
+		Versioned::VStaging(protocol_vstaging::ValidationProtocol::ApprovalDistribution(
+			protocol_vstaging::ApprovalDistributionMessage::Assignments(sent)
+		)) => assert_eq!(sent.len(), 1)
+	);
+	assert_matches!(
+		versioned_approvals_packet(ValidationVersion::VStaging.into(), approvals.clone()),
+		Versioned::VStaging(protocol_vstaging::ValidationProtocol::ApprovalDistribution(
+			protocol_vstaging::ApprovalDistributionMessage::Approvals(sent)
+		)) => assert_eq!(sent, approvals)
+	);
+}
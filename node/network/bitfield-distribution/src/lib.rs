@@ -19,17 +19,25 @@
 //! In case this node is a validator, gossips its own signed availability bitfield
 //! for a particular relay parent.
 //! Independently of that, gossips on received messages from peers to other interested peers.
+//!
+//! Peers speaking `vstaging` are sent bitfields as deltas against a bitfield of the same validator
+//! at another relay parent, if we know they have it. The signature always covers the full
+//! bitfield, which the recipient reconstructs before checking it.
 
 #![deny(unused_crate_dependencies)]
 
 use futures::{channel::oneshot, FutureExt};
+use parity_scale_codec::Encode;
 
 use polkadot_node_network_protocol::{
 	self as net_protocol,
+	filter_by_peer_version,
 	grid_topology::{
 		GridNeighbors, RandomRouting, RequiredRouting, SessionBoundGridTopologyStorage,
 	},
-	v1 as protocol_v1, OurView, PeerId, UnifiedReputationChange as Rep, Versioned, View,
+	peer_set::{ProtocolVersion, ValidationVersion},
+	v1 as protocol_v1, vstaging as protocol_vstaging, OurView, PeerId,
+	UnifiedReputationChange as Rep, Versioned, View,
 };
 use polkadot_node_subsystem::{
 	jaeger, messages::*, overseer, ActiveLeavesUpdate, FromOrchestra, OverseerSignal, PerLeafSpan,
@@ -37,7 +45,10 @@ use polkadot_node_subsystem::{
 };
 use polkadot_node_subsystem_util::{self as util};
 
-use polkadot_primitives::{Hash, SignedAvailabilityBitfield, SigningContext, ValidatorId};
+use polkadot_primitives::{
+	AvailabilityBitfield, Hash, SignedAvailabilityBitfield, SigningContext,
+	UncheckedSignedAvailabilityBitfield, ValidatorId,
+};
 use rand::{CryptoRng, Rng, SeedableRng};
 use std::collections::{HashMap, HashSet};
 
@@ -49,6 +60,11 @@ mod metrics;
 mod tests;
 
 const COST_SIGNATURE_INVALID: Rep = Rep::CostMajor("Bitfield signature invalid");
+// The sender might have had a different base bitfield than us, if the validator equivocated.
+const COST_DELTA_SIGNATURE_INVALID: Rep =
+	Rep::CostMinor("Bitfield signature invalid after applying delta");
+const COST_DELTA_UNKNOWN_BASE: Rep = Rep::CostMinor("Bitfield delta against unknown base");
+const COST_DELTA_INVALID: Rep = Rep::CostMajor("Bitfield delta out of bounds");
 const COST_VALIDATOR_INDEX_INVALID: Rep = Rep::CostMajor("Bitfield validator index invalid");
 const COST_MISSING_PEER_SESSION_KEY: Rep = Rep::CostMinor("Missing peer session key");
 const COST_NOT_IN_VIEW: Rep = Rep::CostMinor("Not interested in that parent hash");
@@ -69,25 +85,54 @@ struct BitfieldGossipMessage {
 }
 
 impl BitfieldGossipMessage {
-	fn into_validation_protocol(self) -> net_protocol::VersionedValidationProtocol {
-		self.into_network_message().into()
+	/// Size of the message on the wire, when sent as full bitfield.
+	fn encoded_size(&self) -> usize {
+		// One byte for the message variant.
+		1 + self.relay_parent.encoded_size() +
+			self.signed_availability.as_unchecked().encoded_size()
+	}
+
+	fn into_validation_protocol(
+		self,
+		recipient_version: ProtocolVersion,
+	) -> net_protocol::VersionedValidationProtocol {
+		self.into_network_message(recipient_version).into()
 	}
 
-	fn into_network_message(self) -> net_protocol::BitfieldDistributionMessage {
-		Versioned::V1(protocol_v1::BitfieldDistributionMessage::Bitfield(
-			self.relay_parent,
-			self.signed_availability.into(),
-		))
+	fn into_network_message(
+		self,
+		recipient_version: ProtocolVersion,
+	) -> net_protocol::BitfieldDistributionMessage {
+		if recipient_version == ValidationVersion::VStaging.into() {
+			Versioned::VStaging(protocol_vstaging::BitfieldDistributionMessage::Bitfield(
+				self.relay_parent,
+				self.signed_availability.into(),
+			))
+		} else {
+			Versioned::V1(protocol_v1::BitfieldDistributionMessage::Bitfield(
+				self.relay_parent,
+				self.signed_availability.into(),
+			))
+		}
 	}
 }
 
+/// Data stored on a per-peer basis.
+#[derive(Debug)]
+pub struct PeerData {
+	/// The peer's view.
+	view: View,
+	/// The peer's protocol version.
+	version: ProtocolVersion,
+}
+
 /// Data used to track information of peers and relay parents the
 /// overseer ordered us to work on.
 #[derive(Default, Debug)]
 struct ProtocolState {
 	/// Track all active peers and their views
 	/// to determine what is relevant to them.
-	peer_views: HashMap<PeerId, View>,
+	peer_data: HashMap<PeerId, PeerData>,
 
 	/// The current and previous gossip topologies
 	topologies: SessionBoundGridTopologyStorage,
@@ -121,6 +166,11 @@ struct PerRelayParentData {
 	/// to prevent flooding.
 	message_received_from_peer: HashMap<PeerId, HashSet<ValidatorId>>,
 
+	/// Peers known to have exactly the message we store in `one_per_validator`.
+	///
+	/// Only those messages can serve as a base for sending deltas to a peer.
+	message_known_by_peer: HashMap<PeerId, HashSet<ValidatorId>>,
+
 	/// The span for this leaf/relay parent.
 	span: PerLeafSpan,
 }
@@ -139,6 +189,7 @@ impl PerRelayParentData {
 			one_per_validator: Default::default(),
 			message_sent_to_peer: Default::default(),
 			message_received_from_peer: Default::default(),
+			message_known_by_peer: Default::default(),
 		}
 	}
 
@@ -327,6 +378,14 @@ async fn handle_bitfield_distribution<Context>(
 	};
 
 	let msg = BitfieldGossipMessage { relay_parent, signed_availability };
+	// Keep our own bitfield around, so we can send deltas against it.
+	job_data.one_per_validator.insert(validator.clone(), msg.clone());
+
+	let delta_bases = delta_bases(&state.per_relay_parent, &validator, &msg);
+	let job_data = match state.per_relay_parent.get_mut(&relay_parent) {
+		Some(job_data) => job_data,
+		None => return,
+	};
 	let topology = state.topologies.get_topology_or_fallback(session_idx).local_grid_neighbors();
 	let required_routing = topology.required_routing_by_index(validator_index, true);
 
@@ -334,10 +393,12 @@ async fn handle_bitfield_distribution<Context>(
 		ctx,
 		job_data,
 		topology,
-		&mut state.peer_views,
+		&mut state.peer_data,
 		validator,
 		msg,
+		&delta_bases,
 		required_routing,
+		metrics,
 		rng,
 	)
 	.await;
@@ -353,10 +414,12 @@ async fn relay_message<Context>(
 	ctx: &mut Context,
 	job_data: &mut PerRelayParentData,
 	topology_neighbors: &GridNeighbors,
-	peer_views: &mut HashMap<PeerId, View>,
+	peers: &mut HashMap<PeerId, PeerData>,
 	validator: ValidatorId,
 	message: BitfieldGossipMessage,
+	delta_bases: &[DeltaBase],
 	required_routing: RequiredRouting,
+	metrics: &Metrics,
 	rng: &mut (impl CryptoRng + Rng),
 ) {
 	let relay_parent = message.relay_parent;
@@ -371,16 +434,16 @@ async fn relay_message<Context>(
 	.await;
 
 	drop(_span);
-	let total_peers = peer_views.len();
+	let total_peers = peers.len();
 	let mut random_routing: RandomRouting = Default::default();
 
 	let _span = span.child("interested-peers");
 	// pass on the bitfield distribution to all interested peers
-	let interested_peers = peer_views
+	let interested_peers = peers
 		.iter()
-		.filter_map(|(peer, view)| {
+		.filter_map(|(peer, data)| {
 			// check interest in the peer in this message's relay parent
-			if view.contains(&message.relay_parent) {
+			if data.view.contains(&message.relay_parent) {
 				let message_needed =
 					job_data.message_from_validator_needed_by_peer(&peer, &validator);
 				if message_needed {
@@ -395,7 +458,7 @@ async fn relay_message<Context>(
 					};
 
 					if need_routing {
-						Some(*peer)
+						Some((*peer, data.version))
 					} else {
 						None
 					}
@@ -406,15 +469,20 @@ async fn relay_message<Context>(
 				None
 			}
 		})
-		.collect::<Vec<PeerId>>();
+		.collect::<Vec<(PeerId, ProtocolVersion)>>();
 
-	interested_peers.iter().for_each(|peer| {
+	interested_peers.iter().for_each(|(peer, _)| {
 		// track the message as sent for this peer
 		job_data
 			.message_sent_to_peer
 			.entry(*peer)
 			.or_default()
 			.insert(validator.clone());
+		job_data
+			.message_known_by_peer
+			.entry(*peer)
+			.or_default()
+			.insert(validator.clone());
 	});
 
 	drop(_span);
@@ -427,12 +495,185 @@ async fn relay_message<Context>(
 		);
 	} else {
 		let _span = span.child("gossip");
-		ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(
-			interested_peers,
-			message.into_validation_protocol(),
-		))
-		.await;
+
+		let v1_interested_peers =
+			filter_by_peer_version(&interested_peers, ValidationVersion::V1.into());
+		let vstaging_interested_peers =
+			filter_by_peer_version(&interested_peers, ValidationVersion::VStaging.into());
+
+		// Peers which have a base bitfield get a delta against the most compact one.
+		let mut vstaging_full_peers = Vec::new();
+		let mut vstaging_delta_peers: Vec<Vec<PeerId>> = vec![Vec::new(); delta_bases.len()];
+		for peer in vstaging_interested_peers {
+			let base = peers.get(&peer).and_then(|data| {
+				delta_bases.iter().position(|base| {
+					base.known_by.contains(&peer) && data.view.contains(&base.relay_parent)
+				})
+			});
+			match base {
+				Some(i) => vstaging_delta_peers[i].push(peer),
+				None => vstaging_full_peers.push(peer),
+			}
+		}
+
+		let full_size = message.encoded_size();
+
+		if !v1_interested_peers.is_empty() {
+			metrics.on_full_bitfield_sent(full_size, v1_interested_peers.len());
+			ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(
+				v1_interested_peers,
+				message.clone().into_validation_protocol(ValidationVersion::V1.into()),
+			))
+			.await;
+		}
+
+		if !vstaging_full_peers.is_empty() {
+			metrics.on_full_bitfield_sent(full_size, vstaging_full_peers.len());
+			ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(
+				vstaging_full_peers,
+				message.into_validation_protocol(ValidationVersion::VStaging.into()),
+			))
+			.await;
+		}
+
+		for (base, delta_peers) in delta_bases.iter().zip(vstaging_delta_peers) {
+			if delta_peers.is_empty() {
+				continue
+			}
+			let delta_message =
+				protocol_vstaging::BitfieldDistributionMessage::BitfieldDelta(base.delta.clone());
+			metrics.on_delta_bitfield_sent(
+				delta_message.encoded_size(),
+				full_size,
+				delta_peers.len(),
+			);
+			ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(
+				delta_peers,
+				Versioned::VStaging(delta_message).into(),
+			))
+			.await;
+		}
+	}
+}
+
+/// A bitfield encoded as delta, along with the peers able to decode it.
+#[derive(Debug)]
+struct DeltaBase {
+	delta: protocol_vstaging::BitfieldDelta,
+	/// The relay parent of the base bitfield.
+	relay_parent: Hash,
+	/// Peers known to have the base bitfield.
+	known_by: HashSet<PeerId>,
+}
+
+/// Encode the bitfield in `message` as delta against each bitfield of the same validator we have
+/// at other relay parents of the same session, most compact first.
+///
+/// Only deltas which are smaller than the full bitfield and which at least one peer can decode
+/// are returned.
+fn delta_bases(
+	per_relay_parent: &HashMap<Hash, PerRelayParentData>,
+	validator: &ValidatorId,
+	message: &BitfieldGossipMessage,
+) -> Vec<DeltaBase> {
+	let session_index = match per_relay_parent.get(&message.relay_parent) {
+		Some(job_data) => job_data.signing_context.session_index,
+		None => return Vec::new(),
+	};
+	let bits = &message.signed_availability.payload().0;
+	if bits.len() > u16::MAX as usize + 1 {
+		return Vec::new()
 	}
+	let full_size = message.signed_availability.payload().encoded_size();
+
+	let mut bases: Vec<_> = per_relay_parent
+		.iter()
+		.filter(|(relay_parent, job_data)| {
+			**relay_parent != message.relay_parent &&
+				job_data.signing_context.session_index == session_index
+		})
+		.filter_map(|(base_relay_parent, job_data)| {
+			let prefix = base_prefix(base_relay_parent);
+			if per_relay_parent.keys().filter(|h| base_prefix(h) == prefix).count() > 1 {
+				return None
+			}
+			let base_bits = &job_data.one_per_validator.get(validator)?.signed_availability;
+			let base_bits = &base_bits.payload().0;
+			if base_bits.len() != bits.len() {
+				return None
+			}
+			let known_by: HashSet<_> = job_data
+				.message_known_by_peer
+				.iter()
+				.filter(|(_, validators)| validators.contains(validator))
+				.map(|(peer, _)| *peer)
+				.collect();
+			if known_by.is_empty() {
+				return None
+			}
+			let changed: Vec<u16> = bits
+				.iter()
+				.by_vals()
+				.zip(base_bits.iter().by_vals())
+				.enumerate()
+				.filter(|(_, (bit, base_bit))| bit != base_bit)
+				.map(|(i, _)| i as u16)
+				.collect();
+			if prefix.encoded_size() + changed.encoded_size() >= full_size {
+				return None
+			}
+			let delta = protocol_vstaging::BitfieldDelta {
+				relay_parent: message.relay_parent,
+				base_prefix: prefix,
+				validator_index: message.signed_availability.validator_index(),
+				changed,
+				signature: message.signed_availability.signature().clone(),
+			};
+			Some(DeltaBase { delta, relay_parent: *base_relay_parent, known_by })
+		})
+		.collect();
+
+	bases.sort_by_key(|base| base.delta.changed.len());
+	bases
+}
+
+fn base_prefix(relay_parent: &Hash) -> [u8; 4] {
+	let mut prefix = [0; 4];
+	prefix.copy_from_slice(&relay_parent.as_bytes()[..4]);
+	prefix
+}
+
+/// Reconstruct the full bitfield from a delta and the base bitfield we have.
+///
+/// The signature is not checked here.
+fn apply_delta(
+	per_relay_parent: &HashMap<Hash, PerRelayParentData>,
+	delta: protocol_vstaging::BitfieldDelta,
+) -> Result<(Hash, UncheckedSignedAvailabilityBitfield), Rep> {
+	let mut candidates =
+		per_relay_parent.iter().filter(|(h, _)| base_prefix(h) == delta.base_prefix);
+	let base_data = match (candidates.next(), candidates.next()) {
+		(Some((_, base_data)), None) => base_data,
+		_ => return Err(COST_DELTA_UNKNOWN_BASE),
+	};
+	let validator = base_data
+		.validator_set
+		.get(delta.validator_index.0 as usize)
+		.ok_or(COST_DELTA_UNKNOWN_BASE)?;
+	let base = base_data.one_per_validator.get(validator).ok_or(COST_DELTA_UNKNOWN_BASE)?;
+
+	let mut bits = base.signed_availability.payload().0.clone();
+	for position in delta.changed {
+		let mut bit = bits.get_mut(position as usize).ok_or(COST_DELTA_INVALID)?;
+		*bit = !*bit;
+	}
+
+	let bitfield = UncheckedSignedAvailabilityBitfield::new(
+		AvailabilityBitfield(bits),
+		delta.validator_index,
+		delta.signature,
+	);
+	Ok((delta.relay_parent, bitfield))
 }
 
 /// Handle an incoming message from a peer.
@@ -442,10 +683,35 @@ async fn process_incoming_peer_message<Context>(
 	state: &mut ProtocolState,
 	metrics: &Metrics,
 	origin: PeerId,
-	message: protocol_v1::BitfieldDistributionMessage,
+	message: net_protocol::BitfieldDistributionMessage,
 	rng: &mut (impl CryptoRng + Rng),
 ) {
-	let protocol_v1::BitfieldDistributionMessage::Bitfield(relay_parent, bitfield) = message;
+	let (relay_parent, bitfield, from_delta) = match message {
+		Versioned::V1(protocol_v1::BitfieldDistributionMessage::Bitfield(relay_parent, bitfield)) |
+		Versioned::VStaging(protocol_vstaging::BitfieldDistributionMessage::Bitfield(
+			relay_parent,
+			bitfield,
+		)) => (relay_parent, bitfield, false),
+		Versioned::VStaging(protocol_vstaging::BitfieldDistributionMessage::BitfieldDelta(
+			delta,
+		)) => {
+			let relay_parent = delta.relay_parent;
+			match apply_delta(&state.per_relay_parent, delta) {
+				Ok((relay_parent, bitfield)) => (relay_parent, bitfield, true),
+				Err(rep) => {
+					gum::trace!(
+						target: LOG_TARGET,
+						peer = %origin,
+						?relay_parent,
+						?rep,
+						"could not apply bitfield delta"
+					);
+					modify_reputation(ctx.sender(), relay_parent, origin, rep).await;
+					return
+				},
+			}
+		},
+	};
 	gum::trace!(
 		target: LOG_TARGET,
 		peer = %origin,
@@ -517,13 +783,16 @@ async fn process_incoming_peer_message<Context>(
 			"already received a message for validator",
 		);
 		if old_message.signed_availability.as_unchecked() == &bitfield {
+			job_data.message_known_by_peer.entry(origin).or_default().insert(validator);
 			modify_reputation(ctx.sender(), relay_parent, origin, BENEFIT_VALID_MESSAGE).await;
 		}
 		return
 	}
 	let signed_availability = match bitfield.try_into_checked(&signing_context, &validator) {
 		Err(_) => {
-			modify_reputation(ctx.sender(), relay_parent, origin, COST_SIGNATURE_INVALID).await;
+			let rep =
+				if from_delta { COST_DELTA_SIGNATURE_INVALID } else { COST_SIGNATURE_INVALID };
+			modify_reputation(ctx.sender(), relay_parent, origin, rep).await;
 			return
 		},
 		Ok(bitfield) => bitfield,
@@ -539,15 +808,24 @@ async fn process_incoming_peer_message<Context>(
 
 	metrics.on_bitfield_received();
 	one_per_validator.insert(validator.clone(), message.clone());
+	job_data.message_known_by_peer.entry(origin).or_default().insert(validator.clone());
+
+	let delta_bases = delta_bases(&state.per_relay_parent, &validator, &message);
+	let job_data = match state.per_relay_parent.get_mut(&relay_parent) {
+		Some(job_data) => job_data,
+		None => return,
+	};
 
 	relay_message(
 		ctx,
 		job_data,
 		topology,
-		&mut state.peer_views,
+		&mut state.peer_data,
 		validator,
 		message,
+		&delta_bases,
 		required_routing,
+		metrics,
 		rng,
 	)
 	.await;
@@ -568,15 +846,18 @@ async fn handle_network_msg<Context>(
 	let _timer = metrics.time_handle_network_msg();
 
 	match bridge_message {
-		NetworkBridgeEvent::PeerConnected(peer, role, _, _) => {
+		NetworkBridgeEvent::PeerConnected(peer, role, version, _) => {
 			gum::trace!(target: LOG_TARGET, ?peer, ?role, "Peer connected");
 			// insert if none already present
-			state.peer_views.entry(peer).or_default();
+			state
+				.peer_data
+				.entry(peer)
+				.or_insert_with(|| PeerData { view: View::default(), version });
 		},
 		NetworkBridgeEvent::PeerDisconnected(peer) => {
 			gum::trace!(target: LOG_TARGET, ?peer, "Peer disconnected");
 			// get rid of superfluous data
-			state.peer_views.remove(&peer);
+			state.peer_data.remove(&peer);
 		},
 		NetworkBridgeEvent::NewGossipTopology(gossip_topology) => {
 			let session_index = gossip_topology.session;
@@ -604,7 +885,10 @@ async fn handle_network_msg<Context>(
 				// in case we already knew that peer in the past
 				// it might have had an existing view, we use to initialize
 				// and minimize the delta on `PeerViewChange` to be sent
-				if let Some(old_view) = state.peer_views.remove(&new_peer) {
+				if let Some(old_view) = state.peer_data.get_mut(&new_peer).map(|d| {
+					// we must send the full view again, so the old one is reset here
+					std::mem::take(&mut d.view)
+				}) {
 					handle_peer_view_change(ctx, state, new_peer, old_view, rng).await;
 				}
 			}
@@ -617,7 +901,7 @@ async fn handle_network_msg<Context>(
 			gum::trace!(target: LOG_TARGET, ?new_view, "Our view change");
 			handle_our_view_change(state, new_view);
 		},
-		NetworkBridgeEvent::PeerMessage(remote, Versioned::V1(message)) =>
+		NetworkBridgeEvent::PeerMessage(remote, message) =>
 			process_incoming_peer_message(ctx, state, metrics, remote, message, rng).await,
 	}
 }
@@ -654,13 +938,13 @@ async fn handle_peer_view_change<Context>(
 	view: View,
 	rng: &mut (impl CryptoRng + Rng),
 ) {
-	let added = state
-		.peer_views
-		.entry(origin)
-		.or_default()
-		.replace_difference(view)
-		.cloned()
-		.collect::<Vec<_>>();
+	let added = match state.peer_data.get_mut(&origin) {
+		Some(data) => data.view.replace_difference(view).cloned().collect::<Vec<_>>(),
+		None => {
+			gum::trace!(target: LOG_TARGET, ?origin, "View change from unconnected peer");
+			return
+		},
+	};
 
 	let topology = state.topologies.get_current_topology().local_grid_neighbors();
 	let is_gossip_peer = topology.route_to_peer(RequiredRouting::GridXY, &origin);
@@ -711,6 +995,11 @@ async fn send_tracked_gossip_message<Context>(
 	validator: ValidatorId,
 	message: BitfieldGossipMessage,
 ) {
+	let version = match state.peer_data.get(&dest) {
+		Some(data) => data.version,
+		None => return,
+	};
+
 	let job_data = if let Some(job_data) = state.per_relay_parent.get_mut(&message.relay_parent) {
 		job_data
 	} else {
@@ -727,10 +1016,11 @@ async fn send_tracked_gossip_message<Context>(
 	);
 
 	job_data.message_sent_to_peer.entry(dest).or_default().insert(validator.clone());
+	job_data.message_known_by_peer.entry(dest).or_default().insert(validator.clone());
 
 	ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(
 		vec![dest],
-		message.into_validation_protocol(),
+		message.into_validation_protocol(version),
 	))
 	.await;
 }
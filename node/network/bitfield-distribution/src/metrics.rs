@@ -20,6 +20,8 @@ use polkadot_node_subsystem_util::metrics::{prometheus, Metrics as MetricsTrait}
 struct MetricsInner {
 	sent_own_availability_bitfields: prometheus::Counter<prometheus::U64>,
 	received_availability_bitfields: prometheus::Counter<prometheus::U64>,
+	sent_bitfield_bytes: prometheus::CounterVec<prometheus::U64>,
+	saved_bitfield_bytes: prometheus::Counter<prometheus::U64>,
 	active_leaves_update: prometheus::Histogram,
 	handle_bitfield_distribution: prometheus::Histogram,
 	handle_network_msg: prometheus::Histogram,
@@ -42,6 +44,29 @@ impl Metrics {
 		}
 	}
 
+	/// A bitfield was sent in full to `recipients` peers.
+	pub(crate) fn on_full_bitfield_sent(&self, size: usize, recipients: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics
+				.sent_bitfield_bytes
+				.with_label_values(&["full"])
+				.inc_by((size * recipients) as u64);
+		}
+	}
+
+	/// A bitfield was sent as delta to `recipients` peers instead of the full bitfield.
+	pub(crate) fn on_delta_bitfield_sent(&self, size: usize, full_size: usize, recipients: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics
+				.sent_bitfield_bytes
+				.with_label_values(&["delta"])
+				.inc_by((size * recipients) as u64);
+			metrics
+				.saved_bitfield_bytes
+				.inc_by((full_size.saturating_sub(size) * recipients) as u64);
+		}
+	}
+
 	/// Provide a timer for `active_leaves_update` which observes on drop.
 	pub(crate) fn time_active_leaves_update(
 		&self,
@@ -81,6 +106,23 @@ impl MetricsTrait for Metrics {
 				)?,
 				registry,
 			)?,
+			sent_bitfield_bytes: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_bitfield_distribution_sent_bytes_total",
+						"Bytes of availability bitfield messages sent to other peers.",
+					),
+					&["encoding"],
+				)?,
+				registry,
+			)?,
+			saved_bitfield_bytes: prometheus::register(
+				prometheus::Counter::new(
+					"polkadot_parachain_bitfield_distribution_saved_bytes_total",
+					"Bytes saved by sending bitfield deltas instead of full bitfields.",
+				)?,
+				registry,
+			)?,
 			active_leaves_update: prometheus::register(
 				prometheus::Histogram::with_opts(prometheus::HistogramOpts::new(
 					"polkadot_parachain_bitfield_distribution_active_leaves_update",
@@ -54,6 +54,10 @@ fn dummy_rng() -> ChaCha12Rng {
 	rand_chacha::ChaCha12Rng::seed_from_u64(12345)
 }
 
+fn peer_data_v1(view: View) -> PeerData {
+	PeerData { view, version: ValidationVersion::V1.into() }
+}
+
 /// A very limited state, only interested in the relay parent of the
 /// given message, which must be signed by `validator` and a set of peers
 /// which are also only interested in that relay parent.
@@ -80,10 +84,20 @@ fn prewarmed_state(
 					},
 					message_received_from_peer: hashmap!{},
 					message_sent_to_peer: hashmap!{},
+					message_known_by_peer: hashmap!{},
 					span: PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
 				},
 		},
-		peer_views: peers.iter().cloned().map(|peer| (peer, view!(relay_parent))).collect(),
+		peer_data: peers
+			.iter()
+			.cloned()
+			.map(|peer| {
+				(
+					peer,
+					PeerData { view: view!(relay_parent), version: ValidationVersion::V1.into() },
+				)
+			})
+			.collect(),
 		topologies,
 		view: our_view!(relay_parent),
 	}
@@ -112,6 +126,7 @@ fn state_with_view(
 					one_per_validator: hashmap! {},
 					message_received_from_peer: hashmap! {},
 					message_sent_to_peer: hashmap! {},
+					message_known_by_peer: hashmap! {},
 					span: PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
 				},
 			)
@@ -215,7 +230,10 @@ fn receive_invalid_signature() {
 			&mut ctx,
 			&mut state,
 			&Default::default(),
-			NetworkBridgeEvent::PeerMessage(peer_b.clone(), invalid_msg.into_network_message()),
+			NetworkBridgeEvent::PeerMessage(
+				peer_b.clone(),
+				invalid_msg.into_network_message(ValidationVersion::V1.into()),
+			),
 			&mut rng,
 		));
 
@@ -226,7 +244,10 @@ fn receive_invalid_signature() {
 			&mut ctx,
 			&mut state,
 			&Default::default(),
-			NetworkBridgeEvent::PeerMessage(peer_b.clone(), invalid_msg_2.into_network_message()),
+			NetworkBridgeEvent::PeerMessage(
+				peer_b.clone(),
+				invalid_msg_2.into_network_message(ValidationVersion::V1.into()),
+			),
 			&mut rng,
 		));
 		// reputation change due to invalid signature
@@ -260,7 +281,7 @@ fn receive_invalid_validator_index() {
 	let (mut state, signing_context, keystore, validator) =
 		state_with_view(our_view![hash_a, hash_b], hash_a.clone());
 
-	state.peer_views.insert(peer_b.clone(), view![hash_a]);
+	state.peer_data.insert(peer_b.clone(), peer_data_v1(view![hash_a]));
 
 	let payload = AvailabilityBitfield(bitvec![u8, bitvec::order::Lsb0; 1u8; 32]);
 	let signed = executor::block_on(Signed::<AvailabilityBitfield>::sign(
@@ -286,7 +307,10 @@ fn receive_invalid_validator_index() {
 			&mut ctx,
 			&mut state,
 			&Default::default(),
-			NetworkBridgeEvent::PeerMessage(peer_b.clone(), msg.into_network_message()),
+			NetworkBridgeEvent::PeerMessage(
+				peer_b.clone(),
+				msg.into_network_message(ValidationVersion::V1.into()),
+			),
 			&mut rng,
 		));
 
@@ -349,7 +373,10 @@ fn receive_duplicate_messages() {
 			&mut ctx,
 			&mut state,
 			&Default::default(),
-			NetworkBridgeEvent::PeerMessage(peer_b.clone(), msg.clone().into_network_message(),),
+			NetworkBridgeEvent::PeerMessage(
+				peer_b.clone(),
+				msg.clone().into_network_message(ValidationVersion::V1.into()),
+			),
 			&mut rng,
 		));
 
@@ -382,7 +409,10 @@ fn receive_duplicate_messages() {
 			&mut ctx,
 			&mut state,
 			&Default::default(),
-			NetworkBridgeEvent::PeerMessage(peer_a.clone(), msg.clone().into_network_message(),),
+			NetworkBridgeEvent::PeerMessage(
+				peer_a.clone(),
+				msg.clone().into_network_message(ValidationVersion::V1.into()),
+			),
 			&mut rng,
 		));
 
@@ -401,7 +431,10 @@ fn receive_duplicate_messages() {
 			&mut ctx,
 			&mut state,
 			&Default::default(),
-			NetworkBridgeEvent::PeerMessage(peer_b.clone(), msg.clone().into_network_message(),),
+			NetworkBridgeEvent::PeerMessage(
+				peer_b.clone(),
+				msg.clone().into_network_message(ValidationVersion::V1.into()),
+			),
 			&mut rng,
 		));
 
@@ -447,8 +480,8 @@ fn do_not_relay_message_twice() {
 	.flatten()
 	.expect("should be signed");
 
-	state.peer_views.insert(peer_b.clone(), view![hash]);
-	state.peer_views.insert(peer_a.clone(), view![hash]);
+	state.peer_data.insert(peer_b.clone(), peer_data_v1(view![hash]));
+	state.peer_data.insert(peer_a.clone(), peer_data_v1(view![hash]));
 
 	let msg = BitfieldGossipMessage {
 		relay_parent: hash.clone(),
@@ -467,10 +500,12 @@ fn do_not_relay_message_twice() {
 			&mut ctx,
 			state.per_relay_parent.get_mut(&hash).unwrap(),
 			&gossip_peers,
-			&mut state.peer_views,
+			&mut state.peer_data,
 			validator.clone(),
 			msg.clone(),
+			&[],
 			RequiredRouting::GridXY,
+			&Default::default(),
 			&mut rng,
 		)
 		.await;
@@ -494,7 +529,10 @@ fn do_not_relay_message_twice() {
 				assert_eq!(2, peers.len());
 				assert!(peers.contains(&peer_a));
 				assert!(peers.contains(&peer_b));
-				assert_eq!(send_msg, msg.clone().into_validation_protocol());
+				assert_eq!(
+					send_msg,
+					msg.clone().into_validation_protocol(ValidationVersion::V1.into())
+				);
 			}
 		);
 
@@ -503,10 +541,12 @@ fn do_not_relay_message_twice() {
 			&mut ctx,
 			state.per_relay_parent.get_mut(&hash).unwrap(),
 			&gossip_peers,
-			&mut state.peer_views,
+			&mut state.peer_data,
 			validator.clone(),
 			msg.clone(),
+			&[],
 			RequiredRouting::GridXY,
+			&Default::default(),
 			&mut rng,
 		)
 		.await;
@@ -527,6 +567,326 @@ fn do_not_relay_message_twice() {
 	});
 }
 
+#[test]
+fn relay_message_uses_each_peers_protocol_version() {
+	let hash = Hash::random();
+
+	let peer_a = PeerId::random();
+	let peer_b = PeerId::random();
+	assert_ne!(peer_a, peer_b);
+
+	// validator 0 key pair
+	let (mut state, signing_context, keystore, validator) =
+		state_with_view(our_view![hash], hash.clone());
+
+	// create a signed message by validator 0
+	let payload = AvailabilityBitfield(bitvec![u8, bitvec::order::Lsb0; 1u8; 32]);
+	let signed_bitfield = executor::block_on(Signed::<AvailabilityBitfield>::sign(
+		&keystore,
+		payload,
+		&signing_context,
+		ValidatorIndex(0),
+		&validator,
+	))
+	.ok()
+	.flatten()
+	.expect("should be signed");
+
+	state.peer_data.insert(peer_a.clone(), peer_data_v1(view![hash]));
+	state.peer_data.insert(
+		peer_b.clone(),
+		PeerData { view: view![hash], version: ValidationVersion::VStaging.into() },
+	);
+
+	let msg = BitfieldGossipMessage {
+		relay_parent: hash.clone(),
+		signed_availability: signed_bitfield.clone(),
+	};
+
+	let pool = sp_core::testing::TaskExecutor::new();
+	let (mut ctx, mut handle) = make_subsystem_context::<BitfieldDistributionMessage, _>(pool);
+	let mut rng = dummy_rng();
+
+	executor::block_on(async move {
+		let mut gossip_peers = GridNeighbors::empty();
+		gossip_peers.peers_x = HashSet::from_iter(vec![peer_a.clone(), peer_b.clone()].into_iter());
+
+		relay_message(
+			&mut ctx,
+			state.per_relay_parent.get_mut(&hash).unwrap(),
+			&gossip_peers,
+			&mut state.peer_data,
+			validator.clone(),
+			msg.clone(),
+			&[],
+			RequiredRouting::GridXY,
+			&Default::default(),
+			&mut rng,
+		)
+		.await;
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::Provisioner(ProvisionerMessage::ProvisionableData(
+				_,
+				ProvisionableData::Bitfield(h, signed)
+			)) => {
+				assert_eq!(h, hash);
+				assert_eq!(signed, signed_bitfield)
+			}
+		);
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::NetworkBridgeTx(
+				NetworkBridgeTxMessage::SendValidationMessage(peers, send_msg),
+			) => {
+				assert_eq!(peers, vec![peer_a]);
+				assert_eq!(
+					send_msg,
+					msg.clone().into_validation_protocol(ValidationVersion::V1.into())
+				);
+			}
+		);
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::NetworkBridgeTx(
+				NetworkBridgeTxMessage::SendValidationMessage(peers, send_msg),
+			) => {
+				assert_eq!(peers, vec![peer_b]);
+				assert_eq!(
+					send_msg,
+					msg.clone().into_validation_protocol(ValidationVersion::VStaging.into()),
+				);
+			}
+		);
+	});
+}
+
+#[test]
+fn relay_message_sends_delta_to_peers_knowing_the_base() {
+	let hash_a: Hash = [1; 32].into();
+	let hash_b: Hash = [2; 32].into();
+
+	let peer_a = PeerId::random();
+	let peer_b = PeerId::random();
+	assert_ne!(peer_a, peer_b);
+
+	let (mut state, signing_context, keystore, validator) =
+		state_with_view(our_view![hash_a, hash_b], hash_a.clone());
+
+	let base_payload = AvailabilityBitfield(bitvec![u8, bitvec::order::Lsb0; 1u8; 256]);
+	let mut payload = base_payload.clone();
+	payload.0.set(3, false);
+
+	let sign = |payload| {
+		executor::block_on(Signed::<AvailabilityBitfield>::sign(
+			&keystore,
+			payload,
+			&signing_context,
+			ValidatorIndex(0),
+			&validator,
+		))
+		.ok()
+		.flatten()
+		.expect("should be signed")
+	};
+	let base_msg =
+		BitfieldGossipMessage { relay_parent: hash_a, signed_availability: sign(base_payload) };
+	let msg = BitfieldGossipMessage { relay_parent: hash_b, signed_availability: sign(payload) };
+
+	// Only `peer_b` is known to have the base bitfield.
+	let base_data = state.per_relay_parent.get_mut(&hash_a).unwrap();
+	base_data.one_per_validator.insert(validator.clone(), base_msg);
+	base_data.message_known_by_peer.insert(peer_b, HashSet::from_iter([validator.clone()]));
+
+	state.peer_data.insert(
+		peer_a,
+		PeerData { view: view![hash_a, hash_b], version: ValidationVersion::VStaging.into() },
+	);
+	state.peer_data.insert(
+		peer_b,
+		PeerData { view: view![hash_a, hash_b], version: ValidationVersion::VStaging.into() },
+	);
+
+	let delta_bases = delta_bases(&state.per_relay_parent, &validator, &msg);
+	assert_eq!(delta_bases.len(), 1);
+
+	let pool = sp_core::testing::TaskExecutor::new();
+	let (mut ctx, mut handle) = make_subsystem_context::<BitfieldDistributionMessage, _>(pool);
+	let mut rng = dummy_rng();
+
+	executor::block_on(async move {
+		let mut gossip_peers = GridNeighbors::empty();
+		gossip_peers.peers_x = HashSet::from_iter([peer_a, peer_b]);
+
+		relay_message(
+			&mut ctx,
+			state.per_relay_parent.get_mut(&hash_b).unwrap(),
+			&gossip_peers,
+			&mut state.peer_data,
+			validator.clone(),
+			msg.clone(),
+			&delta_bases,
+			RequiredRouting::GridXY,
+			&Default::default(),
+			&mut rng,
+		)
+		.await;
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::Provisioner(ProvisionerMessage::ProvisionableData(_, _))
+		);
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::NetworkBridgeTx(
+				NetworkBridgeTxMessage::SendValidationMessage(peers, send_msg),
+			) => {
+				assert_eq!(peers, vec![peer_a]);
+				assert_eq!(
+					send_msg,
+					msg.clone().into_validation_protocol(ValidationVersion::VStaging.into()),
+				);
+			}
+		);
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::NetworkBridgeTx(
+				NetworkBridgeTxMessage::SendValidationMessage(peers, send_msg),
+			) => {
+				assert_eq!(peers, vec![peer_b]);
+				assert_matches!(
+					send_msg,
+					Versioned::VStaging(protocol_vstaging::ValidationProtocol::BitfieldDistribution(
+						protocol_vstaging::BitfieldDistributionMessage::BitfieldDelta(delta),
+					)) => {
+						assert_eq!(delta.relay_parent, hash_b);
+						assert_eq!(delta.base_prefix, [1; 4]);
+						assert_eq!(delta.changed, vec![3]);
+						assert_eq!(&delta.signature, msg.signed_availability.signature());
+					}
+				);
+			}
+		);
+	});
+}
+
+#[test]
+fn receive_delta_reconstructs_bitfield() {
+	let hash_a: Hash = [1; 32].into();
+	let hash_b: Hash = [2; 32].into();
+
+	let peer_b = PeerId::random();
+
+	let (mut state, signing_context, keystore, validator) =
+		state_with_view(our_view![hash_a, hash_b], hash_a.clone());
+
+	let base_payload = AvailabilityBitfield(bitvec![u8, bitvec::order::Lsb0; 0u8; 256]);
+	let mut payload = base_payload.clone();
+	payload.0.set(7, true);
+	payload.0.set(200, true);
+
+	let sign = |payload| {
+		executor::block_on(Signed::<AvailabilityBitfield>::sign(
+			&keystore,
+			payload,
+			&signing_context,
+			ValidatorIndex(0),
+			&validator,
+		))
+		.ok()
+		.flatten()
+		.expect("should be signed")
+	};
+	let base_signed = sign(base_payload);
+	let signed = sign(payload);
+
+	state.per_relay_parent.get_mut(&hash_a).unwrap().one_per_validator.insert(
+		validator.clone(),
+		BitfieldGossipMessage { relay_parent: hash_a, signed_availability: base_signed },
+	);
+	state.peer_data.insert(
+		peer_b,
+		PeerData { view: view![hash_a, hash_b], version: ValidationVersion::VStaging.into() },
+	);
+
+	let delta = |base_prefix| protocol_vstaging::BitfieldDelta {
+		relay_parent: hash_b,
+		base_prefix,
+		validator_index: ValidatorIndex(0),
+		changed: vec![7, 200],
+		signature: signed.signature().clone(),
+	};
+
+	let pool = sp_core::testing::TaskExecutor::new();
+	let (mut ctx, mut handle) = make_subsystem_context::<BitfieldDistributionMessage, _>(pool);
+	let mut rng = dummy_rng();
+
+	executor::block_on(async move {
+		// A delta against a base we don't know is rejected.
+		launch!(handle_network_msg(
+			&mut ctx,
+			&mut state,
+			&Default::default(),
+			NetworkBridgeEvent::PeerMessage(
+				peer_b,
+				Versioned::VStaging(protocol_vstaging::BitfieldDistributionMessage::BitfieldDelta(
+					delta([9; 4]),
+				)),
+			),
+			&mut rng,
+		));
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::NetworkBridgeTx(
+				NetworkBridgeTxMessage::ReportPeer(peer, rep)
+			) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep, COST_DELTA_UNKNOWN_BASE)
+			}
+		);
+
+		launch!(handle_network_msg(
+			&mut ctx,
+			&mut state,
+			&Default::default(),
+			NetworkBridgeEvent::PeerMessage(
+				peer_b,
+				Versioned::VStaging(protocol_vstaging::BitfieldDistributionMessage::BitfieldDelta(
+					delta([1; 4]),
+				)),
+			),
+			&mut rng,
+		));
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::Provisioner(ProvisionerMessage::ProvisionableData(
+				_,
+				ProvisionableData::Bitfield(h, received)
+			)) => {
+				assert_eq!(h, hash_b);
+				assert_eq!(received, signed);
+			}
+		);
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::NetworkBridgeTx(
+				NetworkBridgeTxMessage::ReportPeer(peer, rep)
+			) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep, BENEFIT_VALID_MESSAGE_FIRST)
+			}
+		);
+	});
+}
+
 #[test]
 fn changing_view() {
 	let _ = env_logger::builder()
@@ -590,14 +950,17 @@ fn changing_view() {
 			&mut rng,
 		));
 
-		assert!(state.peer_views.contains_key(&peer_b));
+		assert!(state.peer_data.contains_key(&peer_b));
 
 		// recv a first message from the network
 		launch!(handle_network_msg(
 			&mut ctx,
 			&mut state,
 			&Default::default(),
-			NetworkBridgeEvent::PeerMessage(peer_b.clone(), msg.clone().into_network_message(),),
+			NetworkBridgeEvent::PeerMessage(
+				peer_b.clone(),
+				msg.clone().into_network_message(ValidationVersion::V1.into()),
+			),
 			&mut rng,
 		));
 
@@ -632,8 +995,11 @@ fn changing_view() {
 			&mut rng,
 		));
 
-		assert!(state.peer_views.contains_key(&peer_b));
-		assert_eq!(state.peer_views.get(&peer_b).expect("Must contain value for peer B"), &view![]);
+		assert!(state.peer_data.contains_key(&peer_b));
+		assert_eq!(
+			state.peer_data.get(&peer_b).expect("Must contain value for peer B").view,
+			view![]
+		);
 
 		// on rx of the same message, since we are not interested,
 		// should give penalty
@@ -641,7 +1007,10 @@ fn changing_view() {
 			&mut ctx,
 			&mut state,
 			&Default::default(),
-			NetworkBridgeEvent::PeerMessage(peer_b.clone(), msg.clone().into_network_message(),),
+			NetworkBridgeEvent::PeerMessage(
+				peer_b.clone(),
+				msg.clone().into_network_message(ValidationVersion::V1.into()),
+			),
 			&mut rng,
 		));
 
@@ -673,7 +1042,10 @@ fn changing_view() {
 			&mut ctx,
 			&mut state,
 			&Default::default(),
-			NetworkBridgeEvent::PeerMessage(peer_a.clone(), msg.clone().into_network_message(),),
+			NetworkBridgeEvent::PeerMessage(
+				peer_a.clone(),
+				msg.clone().into_network_message(ValidationVersion::V1.into()),
+			),
 			&mut rng,
 		));
 
@@ -719,8 +1091,8 @@ fn do_not_send_message_back_to_origin() {
 	.flatten()
 	.expect("should be signed");
 
-	state.peer_views.insert(peer_b.clone(), view![hash]);
-	state.peer_views.insert(peer_a.clone(), view![hash]);
+	state.peer_data.insert(peer_b.clone(), peer_data_v1(view![hash]));
+	state.peer_data.insert(peer_a.clone(), peer_data_v1(view![hash]));
 
 	let msg = BitfieldGossipMessage {
 		relay_parent: hash.clone(),
@@ -737,7 +1109,10 @@ fn do_not_send_message_back_to_origin() {
 			&mut ctx,
 			&mut state,
 			&Default::default(),
-			NetworkBridgeEvent::PeerMessage(peer_b.clone(), msg.clone().into_network_message(),),
+			NetworkBridgeEvent::PeerMessage(
+				peer_b.clone(),
+				msg.clone().into_network_message(ValidationVersion::V1.into()),
+			),
 			&mut rng,
 		));
 
@@ -759,7 +1134,10 @@ fn do_not_send_message_back_to_origin() {
 			) => {
 				assert_eq!(1, peers.len());
 				assert!(peers.contains(&peer_a));
-				assert_eq!(send_msg, msg.clone().into_validation_protocol());
+				assert_eq!(
+					send_msg,
+					msg.clone().into_validation_protocol(ValidationVersion::V1.into())
+				);
 			}
 		);
 
@@ -835,7 +1213,7 @@ fn topology_test() {
 	.expect("should be signed");
 
 	peers_x.iter().chain(peers_y.iter()).for_each(|peer| {
-		state.peer_views.insert(peer.clone(), view![hash]);
+		state.peer_data.insert(peer.clone(), peer_data_v1(view![hash]));
 	});
 
 	let msg = BitfieldGossipMessage {
@@ -853,7 +1231,10 @@ fn topology_test() {
 			&mut ctx,
 			&mut state,
 			&Default::default(),
-			NetworkBridgeEvent::PeerMessage(peers_x[0].clone(), msg.clone().into_network_message(),),
+			NetworkBridgeEvent::PeerMessage(
+				peers_x[0].clone(),
+				msg.clone().into_network_message(ValidationVersion::V1.into()),
+			),
 			&mut rng,
 		));
 
@@ -880,7 +1261,10 @@ fn topology_test() {
 				assert!(topology.peers_x.iter().filter(|peer| peers.contains(&peer)).count() == 4);
 				// Must never include originator
 				assert!(!peers.contains(&peers_x[0]));
-				assert_eq!(send_msg, msg.clone().into_validation_protocol());
+				assert_eq!(
+					send_msg,
+					msg.clone().into_validation_protocol(ValidationVersion::V1.into())
+				);
 			}
 		);
 
@@ -45,7 +45,11 @@ mod validator_discovery;
 ///
 /// Defines the `Network` trait with an implementation for an `Arc<NetworkService>`.
 mod network;
-use self::network::Network;
+pub use self::network::Network;
+
+/// A simulated implementation of the `Network` trait, connecting several bridges in-process.
+#[cfg(any(test, feature = "simulated-network"))]
+pub mod simulated;
 
 mod metrics;
 pub use self::metrics::Metrics;
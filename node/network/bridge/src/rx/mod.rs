@@ -32,7 +32,8 @@ use polkadot_node_network_protocol::{
 		CollationVersion, PeerSet, PeerSetProtocolNames, PerPeerSet, ProtocolVersion,
 		ValidationVersion,
 	},
-	v1 as protocol_v1, ObservedRole, OurView, PeerId, UnifiedReputationChange as Rep, View,
+	v1 as protocol_v1, vstaging as protocol_vstaging, ObservedRole, OurView, PeerId,
+	UnifiedReputationChange as Rep, View,
 };
 
 use polkadot_node_subsystem::{
@@ -420,7 +421,17 @@ where
 						if expected_versions[PeerSet::Validation] ==
 							Some(ValidationVersion::V1.into())
 						{
-							handle_v1_peer_messages::<protocol_v1::ValidationProtocol, _>(
+							handle_peer_messages::<protocol_v1::ValidationProtocol, _>(
+								remote,
+								PeerSet::Validation,
+								&mut shared.0.lock().validation_peers,
+								v_messages,
+								&metrics,
+							)
+						} else if expected_versions[PeerSet::Validation] ==
+							Some(ValidationVersion::VStaging.into())
+						{
+							handle_peer_messages::<protocol_vstaging::ValidationProtocol, _>(
 								remote,
 								PeerSet::Validation,
 								&mut shared.0.lock().validation_peers,
@@ -434,7 +445,7 @@ where
 								"Major logic bug. Peer somehow has unsupported validation protocol version."
 							);
 
-							never!("Only version 1 is supported; peer set connection checked above; qed");
+							never!("Only versions 1 and 2 are supported; peer set connection checked above; qed");
 
 							// If a peer somehow triggers this, we'll disconnect them
 							// eventually.
@@ -453,7 +464,7 @@ where
 						if expected_versions[PeerSet::Collation] ==
 							Some(CollationVersion::V1.into())
 						{
-							handle_v1_peer_messages::<protocol_v1::CollationProtocol, _>(
+							handle_peer_messages::<protocol_v1::CollationProtocol, _>(
 								remote,
 								PeerSet::Collation,
 								&mut shared.0.lock().collation_peers,
@@ -696,7 +707,7 @@ fn update_our_view<Net, Context>(
 {
 	let new_view = construct_view(live_heads.iter().map(|v| v.hash), finalized_number);
 
-	let (validation_peers, collation_peers) = {
+	let (v1_validation_peers, vstaging_validation_peers, collation_peers) = {
 		let mut shared = shared.0.lock();
 
 		// We only want to send a view update when the heads changed.
@@ -715,15 +726,33 @@ fn update_our_view<Net, Context>(
 			},
 		}
 
+		let validation_peers = shared
+			.validation_peers
+			.iter()
+			.map(|(peer_id, data)| (*peer_id, data.version))
+			.collect::<Vec<_>>();
+
 		(
-			shared.validation_peers.keys().cloned().collect::<Vec<_>>(),
+			net_protocol::filter_by_peer_version(&validation_peers, ValidationVersion::V1.into()),
+			net_protocol::filter_by_peer_version(
+				&validation_peers,
+				ValidationVersion::VStaging.into(),
+			),
 			shared.collation_peers.keys().cloned().collect::<Vec<_>>(),
 		)
 	};
 
 	send_validation_message_v1(
 		net,
-		validation_peers,
+		v1_validation_peers,
+		peerset_protocol_names,
+		WireMessage::ViewUpdate(new_view.clone()),
+		metrics,
+	);
+
+	send_validation_message_vstaging(
+		net,
+		vstaging_validation_peers,
 		peerset_protocol_names,
 		WireMessage::ViewUpdate(new_view.clone()),
 		metrics,
@@ -753,9 +782,9 @@ fn update_our_view<Net, Context>(
 	);
 }
 
-// Handle messages on a specific v1 peer-set. The peer is expected to be connected on that
-// peer-set.
-fn handle_v1_peer_messages<RawMessage: Decode, OutMessage: From<RawMessage>>(
+// Handle messages on a specific peer-set. The peer is expected to be connected on that
+// peer-set, using the protocol version `RawMessage` belongs to.
+fn handle_peer_messages<RawMessage: Decode, OutMessage: From<RawMessage>>(
 	peer: PeerId,
 	peer_set: PeerSet,
 	peers: &mut HashMap<PeerId, PeerData>,
@@ -824,6 +853,24 @@ fn send_validation_message_v1(
 	);
 }
 
+fn send_validation_message_vstaging(
+	net: &mut impl Network,
+	peers: Vec<PeerId>,
+	peerset_protocol_names: &PeerSetProtocolNames,
+	message: WireMessage<protocol_vstaging::ValidationProtocol>,
+	metrics: &Metrics,
+) {
+	send_message(
+		net,
+		peers,
+		PeerSet::Validation,
+		ValidationVersion::VStaging.into(),
+		peerset_protocol_names,
+		message,
+		metrics,
+	);
+}
+
 fn send_collation_message_v1(
 	net: &mut impl Network,
 	peers: Vec<PeerId>,
@@ -889,6 +936,9 @@ fn dispatch_collation_event_to_all_unbounded(
 	if let Ok(msg) = event.focus() {
 		sender.send_unbounded_message(CollatorProtocolMessage::NetworkBridgeUpdate(msg))
 	}
+	if let Ok(msg) = event.focus() {
+		sender.send_unbounded_message(GossipSupportMessage::CollationNetworkBridgeUpdate(msg))
+	}
 }
 
 async fn dispatch_validation_events_to_all<I>(
@@ -915,9 +965,9 @@ async fn dispatch_collation_events_to_all<I>(
 	I: IntoIterator<Item = NetworkBridgeEvent<net_protocol::VersionedCollationProtocol>>,
 	I::IntoIter: Send,
 {
-	let messages_for = |event: NetworkBridgeEvent<net_protocol::VersionedCollationProtocol>| {
-		event.focus().ok().map(|m| CollatorProtocolMessage::NetworkBridgeUpdate(m))
-	};
-
-	ctx.send_messages(events.into_iter().flat_map(messages_for)).await
+	for event in events {
+		ctx.send_messages(event.focus().map(CollatorProtocolMessage::NetworkBridgeUpdate)).await;
+		ctx.send_messages(event.focus().map(GossipSupportMessage::CollationNetworkBridgeUpdate))
+			.await;
+	}
 }
@@ -373,12 +373,22 @@ async fn assert_sends_collation_event_to_all(
 	event: NetworkBridgeEvent<net_protocol::VersionedCollationProtocol>,
 	virtual_overseer: &mut TestSubsystemContextHandle<NetworkBridgeRxMessage>,
 ) {
+	// Ordering must be consistent across:
+	// `fn dispatch_collation_event_to_all_unbounded`
+	// `dispatch_collation_events_to_all`
 	assert_matches!(
 		virtual_overseer.recv().await,
 		AllMessages::CollatorProtocol(
 			CollatorProtocolMessage::NetworkBridgeUpdate(e)
 		) if e == event.focus().expect("could not focus message")
-	)
+	);
+
+	assert_matches!(
+		virtual_overseer.recv().await,
+		AllMessages::GossipSupport(
+			GossipSupportMessage::CollationNetworkBridgeUpdate(e)
+		) if e == event.focus().expect("could not focus message")
+	);
 }
 
 #[test]
@@ -0,0 +1,1332 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A simulated network backend, allowing several network bridges to run in a single process.
+//!
+//! A [`SimulatedNetwork`] connects any number of [`SimulatedNode`]s. Each node implements
+//! [`Network`] and can therefore back a `NetworkBridgeRx`/`NetworkBridgeTx` pair of a full
+//! overseer. Notifications and requests between nodes are subject to per-link
+//! [`LinkConditions`] (latency, jitter, loss and bandwidth) and to network partitions.
+//!
+//! Nothing is delivered unless the [`Router`] returned alongside the network is spawned.
+//! All randomness is drawn from a seeded RNG.
+
+use std::{
+	cmp::{Ordering, Reverse},
+	collections::{BinaryHeap, HashMap, HashSet},
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use futures::{
+	channel::{mpsc, oneshot},
+	future::{BoxFuture, Fuse},
+	prelude::*,
+	select,
+	stream::{BoxStream, FuturesUnordered},
+};
+use futures_timer::Delay;
+use parking_lot::Mutex;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use sc_network::{
+	config::{IncomingRequest, OutgoingResponse, RequestResponseConfig},
+	multiaddr::Multiaddr,
+	Event as NetworkEvent, IfDisconnected, OutboundFailure, RequestFailure,
+};
+use sc_network_common::{config::parse_addr, protocol::ProtocolName};
+
+use polkadot_node_network_protocol::{
+	peer_set::{PeerSet, PeerSetProtocolNames, ProtocolVersion},
+	request_response::{OutgoingRequest, Recipient, ReqProtocolNames, Requests},
+	ObservedRole, PeerId, UnifiedReputationChange as Rep,
+};
+
+use crate::{
+	network::{get_peer_id_by_authority_id, Network},
+	validator_discovery::AuthorityDiscovery,
+};
+
+// simulated network log target
+const LOG_TARGET: &'static str = "parachain::network-bridge-sim";
+
+/// Conditions applied to traffic travelling along one direction of a link.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkConditions {
+	/// Base one-way delay.
+	pub latency: Duration,
+	/// Upper bound of an additional delay drawn uniformly for every message.
+	///
+	/// A non-zero jitter reorders messages sent in quick succession.
+	pub jitter: Duration,
+	/// Probability in `[0, 1]` of dropping a notification.
+	///
+	/// Requests and responses travel over reliable streams and are never dropped.
+	pub loss: f64,
+	/// Capacity of the link in bytes per second. `None` means unlimited.
+	pub bandwidth: Option<u64>,
+}
+
+impl Default for LinkConditions {
+	fn default() -> Self {
+		LinkConditions {
+			latency: Duration::ZERO,
+			jitter: Duration::ZERO,
+			loss: 0.0,
+			bandwidth: None,
+		}
+	}
+}
+
+/// A reputation change reported by one simulated node about another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReputationReport {
+	/// The node which reported the peer.
+	pub reporter: PeerId,
+	/// The reported peer.
+	pub peer: PeerId,
+	/// The reported change.
+	pub change: Rep,
+}
+
+/// A network of simulated nodes.
+#[derive(Clone)]
+pub struct SimulatedNetwork {
+	inner: Arc<Mutex<Inner>>,
+}
+
+impl SimulatedNetwork {
+	/// Create a new, empty network together with the [`Router`] delivering its traffic.
+	pub fn new(protocol_names: PeerSetProtocolNames, seed: u64) -> (Self, Router) {
+		let (queue_tx, queue_rx) = mpsc::unbounded();
+		let inner = Arc::new(Mutex::new(Inner {
+			protocol_names,
+			nodes: HashMap::new(),
+			connections: HashMap::new(),
+			default_conditions: LinkConditions::default(),
+			conditions: HashMap::new(),
+			busy_until: HashMap::new(),
+			partition: HashMap::new(),
+			reports: Vec::new(),
+			rng: StdRng::seed_from_u64(seed),
+			next_seq: 0,
+			queue: queue_tx,
+		}));
+
+		(SimulatedNetwork { inner: inner.clone() }, Router { inner, queue: queue_rx })
+	}
+
+	/// Add a node to the network.
+	///
+	/// Incoming requests for the given request-response protocols are forwarded to their
+	/// `inbound_queue`, just like substrate networking does.
+	pub fn add_node(
+		&self,
+		peer_id: PeerId,
+		role: ObservedRole,
+		request_protocols: Vec<RequestResponseConfig>,
+	) -> SimulatedNode {
+		let (events_tx, events_rx) = mpsc::unbounded();
+		let request_handlers = request_protocols
+			.into_iter()
+			.filter_map(|config| {
+				config.inbound_queue.map(|queue| (config.name, (queue, config.request_timeout)))
+			})
+			.collect();
+
+		self.inner.lock().nodes.insert(
+			peer_id,
+			NodeState {
+				role,
+				validation_version: PeerSet::Validation.get_main_version(),
+				events: events_tx,
+				request_handlers,
+				reserved: HashMap::new(),
+			},
+		);
+
+		SimulatedNode {
+			peer_id,
+			inner: self.inner.clone(),
+			events: Arc::new(Mutex::new(Some(events_rx))),
+		}
+	}
+
+	/// Set the highest validation protocol version a node supports.
+	///
+	/// Only affects connections opened afterwards.
+	pub fn set_validation_version(&self, peer: PeerId, version: ProtocolVersion) {
+		if let Some(node) = self.inner.lock().nodes.get_mut(&peer) {
+			node.validation_version = version;
+		}
+	}
+
+	/// Open notification streams between two nodes on the given peer set.
+	pub fn connect(&self, a: PeerId, b: PeerId, peer_set: PeerSet) {
+		self.inner.lock().connect(a, b, peer_set);
+	}
+
+	/// Open notification streams between all pairs of nodes on the given peer set.
+	pub fn connect_all(&self, peer_set: PeerSet) {
+		let mut inner = self.inner.lock();
+		let peers: Vec<_> = inner.nodes.keys().cloned().collect();
+		for (i, a) in peers.iter().enumerate() {
+			for b in &peers[i + 1..] {
+				inner.connect(*a, *b, peer_set);
+			}
+		}
+	}
+
+	/// Close the notification streams between two nodes on the given peer set.
+	pub fn disconnect(&self, a: PeerId, b: PeerId, peer_set: PeerSet) {
+		self.inner.lock().disconnect(a, b, peer_set);
+	}
+
+	/// Set the conditions of all links without specific conditions.
+	pub fn set_default_conditions(&self, conditions: LinkConditions) {
+		self.inner.lock().default_conditions = conditions;
+	}
+
+	/// Set the conditions of the link from `from` to `to`. The reverse direction is unaffected.
+	pub fn set_link_conditions(&self, from: PeerId, to: PeerId, conditions: LinkConditions) {
+		self.inner.lock().conditions.insert((from, to), conditions);
+	}
+
+	/// Split the network into the given groups.
+	///
+	/// Nodes can only reach nodes of their own group. All nodes not mentioned form one further
+	/// group. Notification streams stay open, but traffic between groups is silently dropped and
+	/// requests fail, including those already in flight.
+	pub fn partition(&self, groups: Vec<Vec<PeerId>>) {
+		let mut inner = self.inner.lock();
+		inner.partition = groups
+			.into_iter()
+			.enumerate()
+			.flat_map(|(index, group)| group.into_iter().map(move |peer| (peer, index)))
+			.collect();
+	}
+
+	/// Remove any partition.
+	pub fn heal(&self) {
+		self.inner.lock().partition.clear();
+	}
+
+	/// Take all reputation changes reported via [`Network::report_peer`] so far.
+	pub fn take_reports(&self) -> Vec<ReputationReport> {
+		std::mem::take(&mut self.inner.lock().reports)
+	}
+}
+
+/// A single node of a [`SimulatedNetwork`].
+///
+/// Only supports a single call to `event_stream`.
+#[derive(Clone)]
+pub struct SimulatedNode {
+	peer_id: PeerId,
+	inner: Arc<Mutex<Inner>>,
+	events: Arc<Mutex<Option<mpsc::UnboundedReceiver<NetworkEvent>>>>,
+}
+
+impl SimulatedNode {
+	/// The `PeerId` of this node.
+	pub fn peer_id(&self) -> PeerId {
+		self.peer_id
+	}
+}
+
+#[async_trait]
+impl Network for SimulatedNode {
+	fn event_stream(&mut self) -> BoxStream<'static, NetworkEvent> {
+		self.events
+			.lock()
+			.take()
+			.expect("`event_stream` is called at most once per simulated node")
+			.boxed()
+	}
+
+	async fn set_reserved_peers(
+		&mut self,
+		protocol: ProtocolName,
+		multiaddresses: HashSet<Multiaddr>,
+	) -> Result<(), String> {
+		let mut inner = self.inner.lock();
+		let peer_set = inner
+			.peer_set_of(&protocol)
+			.ok_or_else(|| format!("Unknown protocol: {}", protocol))?;
+
+		let reserved: HashSet<PeerId> = multiaddresses
+			.into_iter()
+			.filter_map(|addr| parse_addr(addr).ok().map(|(peer, _)| peer))
+			.filter(|peer| peer != &self.peer_id)
+			.collect();
+
+		let previous = match inner.nodes.get_mut(&self.peer_id) {
+			Some(node) => node.reserved.insert(peer_set, reserved.clone()).unwrap_or_default(),
+			None => return Err("Simulated node was removed".into()),
+		};
+
+		for peer in previous.difference(&reserved) {
+			inner.disconnect(self.peer_id, *peer, peer_set);
+		}
+		for peer in reserved {
+			inner.connect(self.peer_id, peer, peer_set);
+		}
+
+		Ok(())
+	}
+
+	async fn remove_from_peers_set(&mut self, protocol: ProtocolName, peers: Vec<PeerId>) {
+		let mut inner = self.inner.lock();
+		let peer_set = match inner.peer_set_of(&protocol) {
+			Some(peer_set) => peer_set,
+			None => return,
+		};
+
+		if let Some(reserved) = inner
+			.nodes
+			.get_mut(&self.peer_id)
+			.and_then(|node| node.reserved.get_mut(&peer_set))
+		{
+			for peer in &peers {
+				reserved.remove(peer);
+			}
+		}
+		for peer in peers {
+			inner.disconnect(self.peer_id, peer, peer_set);
+		}
+	}
+
+	// Simulated nodes are always dialable, so `if_disconnected` makes no difference.
+	async fn start_request<AD: AuthorityDiscovery>(
+		&self,
+		authority_discovery: &mut AD,
+		req: Requests,
+		req_protocol_names: &ReqProtocolNames,
+		_: IfDisconnected,
+	) {
+		let (protocol, OutgoingRequest { peer, payload, pending_response }) = req.encode_request();
+
+		let peer_id = match peer {
+			Recipient::Peer(peer_id) => Some(peer_id),
+			Recipient::Authority(authority) =>
+				get_peer_id_by_authority_id(authority_discovery, authority).await,
+		};
+
+		let peer_id = match peer_id {
+			Some(peer_id) => peer_id,
+			None => {
+				gum::debug!(target: LOG_TARGET, "Discovering authority failed");
+				let _ = pending_response
+					.send(Err(RequestFailure::Network(OutboundFailure::DialFailure)));
+				return
+			},
+		};
+
+		self.inner.lock().start_request(
+			self.peer_id,
+			peer_id,
+			req_protocol_names.get_name(protocol),
+			payload,
+			pending_response,
+		);
+	}
+
+	fn report_peer(&self, who: PeerId, cost_benefit: Rep) {
+		self.inner.lock().reports.push(ReputationReport {
+			reporter: self.peer_id,
+			peer: who,
+			change: cost_benefit,
+		});
+	}
+
+	fn disconnect_peer(&self, who: PeerId, protocol: ProtocolName) {
+		let mut inner = self.inner.lock();
+		if let Some(peer_set) = inner.peer_set_of(&protocol) {
+			inner.disconnect(self.peer_id, who, peer_set);
+		}
+	}
+
+	fn write_notification(&self, who: PeerId, protocol: ProtocolName, message: Vec<u8>) {
+		self.inner.lock().write_notification(self.peer_id, who, protocol, message);
+	}
+}
+
+/// Delivers the traffic of a [`SimulatedNetwork`] once it is due.
+pub struct Router {
+	inner: Arc<Mutex<Inner>>,
+	queue: mpsc::UnboundedReceiver<Scheduled>,
+}
+
+impl Router {
+	/// Run the router. Never returns while the network is alive.
+	pub async fn run(self) {
+		let Router { inner, mut queue } = self;
+		let mut pending = BinaryHeap::new();
+		let mut answered = FuturesUnordered::new();
+
+		loop {
+			let now = Instant::now();
+			while pending
+				.peek()
+				.map_or(false, |Reverse(next): &Reverse<Scheduled>| next.deliver_at <= now)
+			{
+				let Reverse(scheduled) = pending.pop().expect("peeked above; qed");
+				deliver(&inner, scheduled.delivery, &mut answered);
+			}
+
+			let mut timer = match pending.peek() {
+				Some(Reverse(next)) => Delay::new(next.deliver_at - now).fuse(),
+				None => Fuse::terminated(),
+			};
+
+			select! {
+				scheduled = queue.next() => if let Some(scheduled) = scheduled {
+					pending.push(Reverse(scheduled));
+				},
+				_ = timer => {},
+				answered = answered.select_next_some() => {
+					let AnsweredRequest {
+						requester,
+						responder,
+						pending_response,
+						result,
+						sent_feedback,
+					} = answered;
+					let size = result.as_ref().map_or(0, |response| response.len());
+					inner.lock().schedule(
+						responder,
+						requester,
+						size,
+						Delivery::Response {
+							requester,
+							responder,
+							pending_response,
+							result,
+							sent_feedback,
+						},
+					);
+				},
+				complete => break,
+			}
+		}
+	}
+}
+
+struct NodeState {
+	role: ObservedRole,
+	validation_version: ProtocolVersion,
+	events: mpsc::UnboundedSender<NetworkEvent>,
+	request_handlers: HashMap<ProtocolName, (mpsc::Sender<IncomingRequest>, Duration)>,
+	reserved: HashMap<PeerSet, HashSet<PeerId>>,
+}
+
+struct Inner {
+	protocol_names: PeerSetProtocolNames,
+	nodes: HashMap<PeerId, NodeState>,
+	/// Open notification streams, keyed by the ordered pair of peers.
+	connections: HashMap<(PeerSet, PeerId, PeerId), ProtocolVersion>,
+	default_conditions: LinkConditions,
+	conditions: HashMap<(PeerId, PeerId), LinkConditions>,
+	/// Point in time at which a bandwidth limited link finishes transmitting queued traffic.
+	busy_until: HashMap<(PeerId, PeerId), Instant>,
+	partition: HashMap<PeerId, usize>,
+	reports: Vec<ReputationReport>,
+	rng: StdRng,
+	next_seq: u64,
+	queue: mpsc::UnboundedSender<Scheduled>,
+}
+
+fn connection_key(peer_set: PeerSet, a: PeerId, b: PeerId) -> (PeerSet, PeerId, PeerId) {
+	if a < b {
+		(peer_set, a, b)
+	} else {
+		(peer_set, b, a)
+	}
+}
+
+impl Inner {
+	fn peer_set_of(&self, protocol: &ProtocolName) -> Option<PeerSet> {
+		self.protocol_names.try_get_protocol(protocol).map(|(peer_set, _)| peer_set)
+	}
+
+	fn reachable(&self, a: &PeerId, b: &PeerId) -> bool {
+		self.partition.get(a) == self.partition.get(b)
+	}
+
+	fn is_connected(&self, a: PeerId, b: PeerId, peer_set: PeerSet) -> bool {
+		self.connections.contains_key(&connection_key(peer_set, a, b))
+	}
+
+	fn emit(&self, peer: &PeerId, event: NetworkEvent) {
+		if let Some(node) = self.nodes.get(peer) {
+			let _ = node.events.unbounded_send(event);
+		}
+	}
+
+	fn connect(&mut self, a: PeerId, b: PeerId, peer_set: PeerSet) {
+		if a == b || self.is_connected(a, b, peer_set) {
+			return
+		}
+
+		let (node_a, node_b) = match (self.nodes.get(&a), self.nodes.get(&b)) {
+			(Some(node_a), Some(node_b)) => (node_a, node_b),
+			_ => return,
+		};
+
+		let version = match peer_set {
+			PeerSet::Validation =>
+				if u32::from(node_a.validation_version) <= u32::from(node_b.validation_version) {
+					node_a.validation_version
+				} else {
+					node_b.validation_version
+				},
+			PeerSet::Collation => peer_set.get_main_version(),
+		};
+		let (role_a, role_b) = (node_a.role, node_b.role);
+
+		let protocol = self.protocol_names.get_main_name(peer_set);
+		let negotiated_fallback = if version == peer_set.get_main_version() {
+			None
+		} else {
+			Some(self.protocol_names.get_name(peer_set, version))
+		};
+
+		gum::trace!(
+			target: LOG_TARGET,
+			?a,
+			?b,
+			?peer_set,
+			%version,
+			"Opening notification streams",
+		);
+
+		self.connections.insert(connection_key(peer_set, a, b), version);
+		for (local, remote, role) in [(a, b, role_b), (b, a, role_a)] {
+			self.emit(
+				&local,
+				NetworkEvent::NotificationStreamOpened {
+					remote,
+					protocol: protocol.clone(),
+					negotiated_fallback: negotiated_fallback.clone(),
+					role: role.into(),
+				},
+			);
+		}
+	}
+
+	fn disconnect(&mut self, a: PeerId, b: PeerId, peer_set: PeerSet) {
+		if self.connections.remove(&connection_key(peer_set, a, b)).is_none() {
+			return
+		}
+
+		gum::trace!(target: LOG_TARGET, ?a, ?b, ?peer_set, "Closing notification streams");
+
+		let protocol = self.protocol_names.get_main_name(peer_set);
+		for (local, remote) in [(a, b), (b, a)] {
+			self.emit(
+				&local,
+				NetworkEvent::NotificationStreamClosed { remote, protocol: protocol.clone() },
+			);
+		}
+	}
+
+	fn conditions(&self, from: PeerId, to: PeerId) -> &LinkConditions {
+		self.conditions.get(&(from, to)).unwrap_or(&self.default_conditions)
+	}
+
+	/// Queue a delivery over the link from `from` to `to`, accounting for bandwidth and delay.
+	fn schedule(&mut self, from: PeerId, to: PeerId, size: usize, delivery: Delivery) {
+		let conditions = self.conditions(from, to).clone();
+		let now = Instant::now();
+
+		let mut deliver_at = now;
+		if let Some(bandwidth) = conditions.bandwidth {
+			let busy_until = self.busy_until.entry((from, to)).or_insert(now);
+			let transmission = Duration::from_secs_f64(size as f64 / bandwidth.max(1) as f64);
+			*busy_until = std::cmp::max(*busy_until, now) + transmission;
+			deliver_at = *busy_until;
+		}
+
+		deliver_at += conditions.latency;
+		if !conditions.jitter.is_zero() {
+			deliver_at += conditions.jitter.mul_f64(self.rng.gen::<f64>());
+		}
+
+		let seq = self.next_seq;
+		self.next_seq += 1;
+		let _ = self.queue.unbounded_send(Scheduled { deliver_at, seq, delivery });
+	}
+
+	fn write_notification(
+		&mut self,
+		from: PeerId,
+		to: PeerId,
+		protocol: ProtocolName,
+		message: Vec<u8>,
+	) {
+		let peer_set = match self.peer_set_of(&protocol) {
+			Some(peer_set) => peer_set,
+			None => return,
+		};
+
+		if !self.is_connected(from, to, peer_set) {
+			gum::trace!(
+				target: LOG_TARGET,
+				?from,
+				?to,
+				?peer_set,
+				"Notification to unconnected peer",
+			);
+			return
+		}
+
+		let loss = self.conditions(from, to).loss;
+		if !self.reachable(&from, &to) || (loss > 0.0 && self.rng.gen_bool(loss.min(1.0))) {
+			gum::trace!(target: LOG_TARGET, ?from, ?to, ?peer_set, "Dropping notification");
+			return
+		}
+
+		let size = message.len();
+		self.schedule(from, to, size, Delivery::Notification { from, to, peer_set, message });
+	}
+
+	fn start_request(
+		&mut self,
+		from: PeerId,
+		to: PeerId,
+		protocol: ProtocolName,
+		payload: Vec<u8>,
+		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+	) {
+		if !self.nodes.contains_key(&to) || !self.reachable(&from, &to) {
+			let _ =
+				pending_response.send(Err(RequestFailure::Network(OutboundFailure::DialFailure)));
+			return
+		}
+
+		let size = payload.len();
+		self.schedule(
+			from,
+			to,
+			size,
+			Delivery::Request { from, to, protocol, payload, pending_response },
+		);
+	}
+}
+
+/// Traffic queued for delivery at a certain point in time.
+struct Scheduled {
+	deliver_at: Instant,
+	/// Tie breaker, keeping traffic due at the same time in the order it was sent.
+	seq: u64,
+	delivery: Delivery,
+}
+
+impl PartialEq for Scheduled {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl Eq for Scheduled {}
+
+impl PartialOrd for Scheduled {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Scheduled {
+	fn cmp(&self, other: &Self) -> Ordering {
+		(self.deliver_at, self.seq).cmp(&(other.deliver_at, other.seq))
+	}
+}
+
+enum Delivery {
+	Notification {
+		from: PeerId,
+		to: PeerId,
+		peer_set: PeerSet,
+		message: Vec<u8>,
+	},
+	Request {
+		from: PeerId,
+		to: PeerId,
+		protocol: ProtocolName,
+		payload: Vec<u8>,
+		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+	},
+	Response {
+		requester: PeerId,
+		responder: PeerId,
+		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+		result: Result<Vec<u8>, RequestFailure>,
+		sent_feedback: Option<oneshot::Sender<()>>,
+	},
+}
+
+/// A request answered (or timed out) at the responder, waiting to travel back.
+struct AnsweredRequest {
+	requester: PeerId,
+	responder: PeerId,
+	pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+	result: Result<Vec<u8>, RequestFailure>,
+	sent_feedback: Option<oneshot::Sender<()>>,
+}
+
+fn deliver(
+	inner: &Mutex<Inner>,
+	delivery: Delivery,
+	answered: &mut FuturesUnordered<BoxFuture<'static, AnsweredRequest>>,
+) {
+	match delivery {
+		Delivery::Notification { from, to, peer_set, message } => {
+			let inner = inner.lock();
+			if !inner.is_connected(from, to, peer_set) || !inner.reachable(&from, &to) {
+				return
+			}
+
+			// Notifications are always received on the main protocol name, not the negotiated
+			// fallback.
+			let protocol = inner.protocol_names.get_main_name(peer_set);
+			inner.emit(
+				&to,
+				NetworkEvent::NotificationsReceived {
+					remote: from,
+					messages: vec![(protocol, message.into())],
+				},
+			);
+		},
+		Delivery::Request { from, to, protocol, payload, pending_response } => {
+			let inner = inner.lock();
+			if !inner.reachable(&from, &to) {
+				let _ = pending_response
+					.send(Err(RequestFailure::Network(OutboundFailure::ConnectionClosed)));
+				return
+			}
+
+			let (mut handler, request_timeout) =
+				match inner.nodes.get(&to).and_then(|node| node.request_handlers.get(&protocol)) {
+					Some((handler, request_timeout)) => (handler.clone(), *request_timeout),
+					None => {
+						let _ = pending_response.send(Err(RequestFailure::UnknownProtocol));
+						return
+					},
+				};
+
+			let (tx, rx) = oneshot::channel();
+			if handler
+				.try_send(IncomingRequest { peer: from, payload, pending_response: tx })
+				.is_err()
+			{
+				let _ = pending_response.send(Err(RequestFailure::Refused));
+				return
+			}
+
+			answered.push(
+				async move {
+					let response = select! {
+						response = rx.fuse() => response.ok(),
+						_ = Delay::new(request_timeout).fuse() => {
+							let result = Err(RequestFailure::Network(OutboundFailure::Timeout));
+							return AnsweredRequest {
+								requester: from,
+								responder: to,
+								pending_response,
+								result,
+								sent_feedback: None,
+							}
+						},
+					};
+
+					let (result, sent_feedback) = match response {
+						Some(OutgoingResponse { result: Ok(response), sent_feedback, .. }) =>
+							(Ok(response), sent_feedback),
+						_ => (Err(RequestFailure::Refused), None),
+					};
+
+					AnsweredRequest {
+						requester: from,
+						responder: to,
+						pending_response,
+						result,
+						sent_feedback,
+					}
+				}
+				.boxed(),
+			);
+		},
+		Delivery::Response { requester, responder, pending_response, result, sent_feedback } => {
+			let result = if inner.lock().reachable(&requester, &responder) {
+				result
+			} else {
+				Err(RequestFailure::Network(OutboundFailure::ConnectionClosed))
+			};
+
+			let succeeded = result.is_ok();
+			if pending_response.send(result).is_ok() && succeeded {
+				if let Some(sent_feedback) = sent_feedback {
+					let _ = sent_feedback.send(());
+				}
+			}
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use futures::executor;
+
+	use polkadot_availability_bitfield_distribution::BitfieldDistribution;
+	use polkadot_node_network_protocol::{
+		request_response::{v1, IncomingRequest},
+		v1 as protocol_v1, Versioned,
+	};
+	use polkadot_node_subsystem::{
+		jaeger,
+		messages::{
+			AllMessages, ApprovalDistributionMessage, BitfieldDistributionMessage,
+			NetworkBridgeEvent, NetworkBridgeRxMessage, NetworkBridgeTxMessage, ProvisionableData,
+			ProvisionerMessage, RuntimeApiMessage, RuntimeApiRequest,
+		},
+		overseer::Subsystem,
+		ActivatedLeaf, ActiveLeavesUpdate, FromOrchestra, LeafStatus, OverseerSignal,
+	};
+	use polkadot_node_subsystem_test_helpers::{
+		make_buffered_subsystem_context, make_subsystem_context, TestSubsystemContextHandle,
+	};
+	use polkadot_primitives::{
+		AuthorityDiscoveryId, AvailabilityBitfield, CandidateHash, Hash, SessionIndex, Signed,
+		SignedAvailabilityBitfield, SigningContext, ValidatorId, ValidatorIndex,
+		PARACHAIN_KEY_TYPE_ID,
+	};
+	use sp_consensus::SyncOracle;
+	use sp_keyring::Sr25519Keyring;
+	use sp_keystore::{testing::KeyStore, SyncCryptoStore, SyncCryptoStorePtr};
+
+	use crate::{Metrics, NetworkBridgeRx, NetworkBridgeTx};
+
+	#[derive(Clone, Debug)]
+	struct NoAuthorityDiscovery;
+
+	#[async_trait]
+	impl AuthorityDiscovery for NoAuthorityDiscovery {
+		async fn get_addresses_by_authority_id(
+			&mut self,
+			_authority: AuthorityDiscoveryId,
+		) -> Option<HashSet<Multiaddr>> {
+			None
+		}
+
+		async fn get_authority_ids_by_peer_id(
+			&mut self,
+			_peer_id: PeerId,
+		) -> Option<HashSet<AuthorityDiscoveryId>> {
+			None
+		}
+	}
+
+	struct DoneSyncing;
+
+	impl SyncOracle for DoneSyncing {
+		fn is_major_syncing(&self) -> bool {
+			false
+		}
+
+		fn is_offline(&self) -> bool {
+			false
+		}
+	}
+
+	fn protocol_names() -> PeerSetProtocolNames {
+		PeerSetProtocolNames::new(Hash::repeat_byte(42), None)
+	}
+
+	/// The handles to the network bridge subsystems of one overseer.
+	struct VirtualOverseer {
+		rx: TestSubsystemContextHandle<NetworkBridgeRxMessage>,
+		tx: TestSubsystemContextHandle<NetworkBridgeTxMessage>,
+	}
+
+	impl VirtualOverseer {
+		async fn conclude(mut self) {
+			self.rx.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+			self.tx.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+		}
+	}
+
+	/// Add a node to the network, running both halves of a network bridge on top of it.
+	fn start_bridge(
+		network: &SimulatedNetwork,
+		peer: PeerId,
+	) -> (VirtualOverseer, BoxFuture<'static, ()>) {
+		let node = network.add_node(peer, ObservedRole::Authority, Vec::new());
+		let pool = sp_core::testing::TaskExecutor::new();
+		let (rx_context, rx) = make_subsystem_context(pool.clone());
+		let (tx_context, tx) = make_subsystem_context(pool);
+
+		let bridge_rx = NetworkBridgeRx::new(
+			node.clone(),
+			NoAuthorityDiscovery,
+			Box::new(DoneSyncing),
+			Metrics(None),
+			protocol_names(),
+		);
+		let bridge_tx = NetworkBridgeTx::new(
+			node,
+			NoAuthorityDiscovery,
+			Metrics(None),
+			ReqProtocolNames::new(Hash::repeat_byte(42), None),
+			protocol_names(),
+		);
+
+		let bridges =
+			future::join(bridge_rx.start(rx_context).future, bridge_tx.start(tx_context).future)
+				.map(|(rx_result, tx_result)| {
+					rx_result.expect("bridge-in subsystem execution failed");
+					tx_result.expect("bridge-out subsystem execution failed");
+				})
+				.boxed();
+
+		(VirtualOverseer { rx, tx }, bridges)
+	}
+
+	const SESSION: SessionIndex = 1;
+
+	const VALIDATORS: [Sr25519Keyring; 3] =
+		[Sr25519Keyring::Alice, Sr25519Keyring::Bob, Sr25519Keyring::Charlie];
+
+	fn validators() -> Vec<ValidatorId> {
+		VALIDATORS.iter().map(|k| k.public().into()).collect()
+	}
+
+	/// Something the overseer of a bitfield distribution node observed.
+	#[derive(Debug)]
+	enum NodeEvent {
+		/// Bitfield distribution learned that a peer is interested in our leaf.
+		PeerView(PeerId),
+		/// Bitfield distribution handed a bitfield to the provisioner.
+		Provisioned(SignedAvailabilityBitfield),
+	}
+
+	/// Add a node running a network bridge and bitfield distribution on top of it.
+	///
+	/// The subsystems are wired together by a minimal overseer, which activates `leaf` and
+	/// answers runtime API requests. Bitfields sent to the returned sender are distributed at
+	/// `leaf`, dropping it concludes the node.
+	fn start_bitfield_node(
+		network: &SimulatedNetwork,
+		peer: PeerId,
+		leaf: Hash,
+	) -> (
+		mpsc::UnboundedSender<SignedAvailabilityBitfield>,
+		mpsc::UnboundedReceiver<NodeEvent>,
+		BoxFuture<'static, ()>,
+	) {
+		let (VirtualOverseer { rx, tx }, bridges) = start_bridge(network, peer);
+		let pool = sp_core::testing::TaskExecutor::new();
+		// Buffered, as the overseer may forward messages while the subsystem awaits a runtime
+		// API response from it.
+		let (context, bitfields) = make_buffered_subsystem_context(pool, 16);
+		let distribution = BitfieldDistribution::new(Default::default()).start(context).future;
+
+		let (distribute_tx, mut distribute) = mpsc::unbounded();
+		let (events_tx, events) = mpsc::unbounded();
+		let overseer = async move {
+			let TestSubsystemContextHandle { tx: mut to_bridge_rx, rx: mut from_bridge_rx } = rx;
+			let TestSubsystemContextHandle { tx: mut to_bridge_tx, .. } = tx;
+			let TestSubsystemContextHandle { tx: mut to_bitfields, rx: mut from_bitfields } =
+				bitfields;
+
+			let activated = ActiveLeavesUpdate::start_work(ActivatedLeaf {
+				hash: leaf,
+				number: 1,
+				status: LeafStatus::Fresh,
+				span: Arc::new(jaeger::Span::Disabled),
+			});
+			to_bitfields
+				.send(FromOrchestra::Signal(OverseerSignal::ActiveLeaves(activated.clone())))
+				.await
+				.unwrap();
+			to_bridge_rx
+				.send(FromOrchestra::Signal(OverseerSignal::ActiveLeaves(activated)))
+				.await
+				.unwrap();
+
+			loop {
+				select! {
+					bitfield = distribute.next() => match bitfield {
+						Some(bitfield) => {
+							let msg =
+								BitfieldDistributionMessage::DistributeBitfield(leaf, bitfield);
+							to_bitfields.send(FromOrchestra::Communication { msg }).await.unwrap();
+						},
+						None => break,
+					},
+					msg = from_bridge_rx.select_next_some() => {
+						let msg = match msg {
+							AllMessages::BitfieldDistribution(msg) => msg,
+							_ => continue,
+						};
+						if let BitfieldDistributionMessage::NetworkBridgeUpdate(
+							NetworkBridgeEvent::PeerViewChange(peer, ref view),
+						) = msg
+						{
+							if view.contains(&leaf) {
+								let _ = events_tx.unbounded_send(NodeEvent::PeerView(peer));
+							}
+						}
+						to_bitfields.send(FromOrchestra::Communication { msg }).await.unwrap();
+					},
+					msg = from_bitfields.select_next_some() => match msg {
+						AllMessages::NetworkBridgeTx(msg) =>
+							to_bridge_tx.send(FromOrchestra::Communication { msg }).await.unwrap(),
+						AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+							_,
+							RuntimeApiRequest::Validators(tx),
+						)) => {
+							let _ = tx.send(Ok(validators()));
+						},
+						AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+							_,
+							RuntimeApiRequest::SessionIndexForChild(tx),
+						)) => {
+							let _ = tx.send(Ok(SESSION));
+						},
+						AllMessages::Provisioner(ProvisionerMessage::ProvisionableData(
+							_,
+							ProvisionableData::Bitfield(_, bitfield),
+						)) => {
+							let _ = events_tx.unbounded_send(NodeEvent::Provisioned(bitfield));
+						},
+						msg => panic!("Unexpected message: {:?}", msg),
+					},
+				}
+			}
+
+			let conclude = || FromOrchestra::Signal(OverseerSignal::Conclude);
+			to_bitfields.send(conclude()).await.unwrap();
+			to_bridge_rx.send(conclude()).await.unwrap();
+			to_bridge_tx.send(conclude()).await.unwrap();
+		};
+
+		let node = future::join3(bridges, distribution, overseer)
+			.map(|(_, distribution_result, _)| {
+				distribution_result.expect("bitfield distribution subsystem execution failed");
+			})
+			.boxed();
+
+		(distribute_tx, events, node)
+	}
+
+	fn run_with_router(router: Router, test: impl Future<Output = ()>) {
+		executor::block_on(async move {
+			futures::pin_mut!(test);
+			future::select(router.run().boxed(), test).await;
+		});
+	}
+
+	async fn next_notification(events: &mut BoxStream<'static, NetworkEvent>) -> Vec<u8> {
+		loop {
+			match events.next().await.expect("network is alive") {
+				NetworkEvent::NotificationsReceived { mut messages, .. } =>
+					return messages.remove(0).1.to_vec(),
+				_ => continue,
+			}
+		}
+	}
+
+	#[test]
+	fn notifications_are_delayed_and_dropped_across_partitions() {
+		let protocol_names = protocol_names();
+		let validation = protocol_names.get_main_name(PeerSet::Validation);
+		let (network, router) = SimulatedNetwork::new(protocol_names, 0);
+		network.set_default_conditions(LinkConditions {
+			latency: Duration::from_millis(20),
+			..Default::default()
+		});
+
+		let (a, b) = (PeerId::random(), PeerId::random());
+		let node_a = network.add_node(a, ObservedRole::Authority, Vec::new());
+		let mut node_b = network.add_node(b, ObservedRole::Authority, Vec::new());
+		let mut events_b = node_b.event_stream();
+
+		// Not connected yet: dropped.
+		node_a.write_notification(b, validation.clone(), vec![0]);
+		network.connect(a, b, PeerSet::Validation);
+
+		run_with_router(router, async move {
+			match events_b.next().await {
+				Some(NetworkEvent::NotificationStreamOpened { remote, .. }) =>
+					assert_eq!(remote, a),
+				other => panic!("Unexpected event: {:?}", other),
+			}
+
+			let sent_at = Instant::now();
+			node_a.write_notification(b, validation.clone(), vec![1]);
+			assert_eq!(next_notification(&mut events_b).await, vec![1]);
+			assert!(sent_at.elapsed() >= Duration::from_millis(20));
+
+			network.partition(vec![vec![a], vec![b]]);
+			node_a.write_notification(b, validation.clone(), vec![2]);
+			network.heal();
+			node_a.write_notification(b, validation.clone(), vec![3]);
+			assert_eq!(next_notification(&mut events_b).await, vec![3]);
+		});
+	}
+
+	#[test]
+	fn bandwidth_limits_serialize_transmission() {
+		let protocol_names = protocol_names();
+		let validation = protocol_names.get_main_name(PeerSet::Validation);
+		let (network, router) = SimulatedNetwork::new(protocol_names, 0);
+
+		let (a, b) = (PeerId::random(), PeerId::random());
+		let node_a = network.add_node(a, ObservedRole::Authority, Vec::new());
+		let mut node_b = network.add_node(b, ObservedRole::Authority, Vec::new());
+		let mut events_b = node_b.event_stream();
+		network.connect_all(PeerSet::Validation);
+		network.set_link_conditions(
+			a,
+			b,
+			LinkConditions { bandwidth: Some(10_000), ..Default::default() },
+		);
+
+		run_with_router(router, async move {
+			let sent_at = Instant::now();
+			node_a.write_notification(b, validation.clone(), vec![1; 500]);
+			node_a.write_notification(b, validation.clone(), vec![2; 500]);
+
+			assert_eq!(next_notification(&mut events_b).await, vec![1; 500]);
+			assert_eq!(next_notification(&mut events_b).await, vec![2; 500]);
+			assert!(sent_at.elapsed() >= Duration::from_millis(100));
+		});
+	}
+
+	#[test]
+	fn requests_reach_handlers_and_fail_across_partitions() {
+		let req_protocol_names = ReqProtocolNames::new(Hash::repeat_byte(42), None);
+		let (network, router) = SimulatedNetwork::new(protocol_names(), 0);
+
+		let (mut receiver, config) =
+			IncomingRequest::<v1::PoVFetchingRequest>::get_config_receiver(&req_protocol_names);
+		let (a, b) = (PeerId::random(), PeerId::random());
+		let node_a = network.add_node(a, ObservedRole::Authority, Vec::new());
+		let _node_b = network.add_node(b, ObservedRole::Authority, vec![config]);
+
+		let request = || {
+			OutgoingRequest::new(
+				Recipient::Peer(b),
+				v1::PoVFetchingRequest { candidate_hash: CandidateHash(Hash::repeat_byte(1)) },
+			)
+		};
+
+		run_with_router(router, async move {
+			let (req, response) = request();
+			node_a
+				.start_request(
+					&mut NoAuthorityDiscovery,
+					Requests::PoVFetchingV1(req),
+					&req_protocol_names,
+					IfDisconnected::ImmediateError,
+				)
+				.await;
+
+			let incoming = receiver.recv(|| vec![]).await.unwrap();
+			assert_eq!(incoming.peer, a);
+			incoming.send_response(v1::PoVFetchingResponse::NoSuchPoV).unwrap();
+			assert!(matches!(response.await, Ok(v1::PoVFetchingResponse::NoSuchPoV)));
+
+			network.partition(vec![vec![a]]);
+			let (req, response) = request();
+			node_a
+				.start_request(
+					&mut NoAuthorityDiscovery,
+					Requests::PoVFetchingV1(req),
+					&req_protocol_names,
+					IfDisconnected::ImmediateError,
+				)
+				.await;
+			assert!(response.await.is_err());
+		});
+	}
+
+	#[test]
+	fn bridges_of_two_overseers_exchange_messages() {
+		let (network, router) = SimulatedNetwork::new(protocol_names(), 0);
+		network.set_default_conditions(LinkConditions {
+			latency: Duration::from_millis(10),
+			..Default::default()
+		});
+
+		let (a, b) = (PeerId::random(), PeerId::random());
+		let (mut overseer_a, bridges_a) = start_bridge(&network, a);
+		let (mut overseer_b, bridges_b) = start_bridge(&network, b);
+		network.connect(a, b, PeerSet::Validation);
+
+		let message = protocol_v1::ApprovalDistributionMessage::Approvals(Vec::new());
+		let test = async move {
+			overseer_a
+				.tx
+				.send(FromOrchestra::Communication {
+					msg: NetworkBridgeTxMessage::SendValidationMessage(
+						vec![b],
+						Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+							message.clone(),
+						)),
+					),
+				})
+				.await;
+
+			// The overseer of `b` learns about `a`, and then receives its message.
+			let mut connected = false;
+			loop {
+				match overseer_b.rx.recv().await {
+					AllMessages::ApprovalDistribution(
+						ApprovalDistributionMessage::NetworkBridgeUpdate(
+							NetworkBridgeEvent::PeerConnected(peer, ..),
+						),
+					) => {
+						assert_eq!(peer, a);
+						connected = true;
+					},
+					AllMessages::ApprovalDistribution(
+						ApprovalDistributionMessage::NetworkBridgeUpdate(
+							NetworkBridgeEvent::PeerMessage(peer, Versioned::V1(received)),
+						),
+					) => {
+						assert!(connected);
+						assert_eq!(peer, a);
+						assert_eq!(received, message);
+						break
+					},
+					_ => continue,
+				}
+			}
+
+			// And the overseer of `a` learns about `b`.
+			loop {
+				match overseer_a.rx.recv().await {
+					AllMessages::ApprovalDistribution(
+						ApprovalDistributionMessage::NetworkBridgeUpdate(
+							NetworkBridgeEvent::PeerConnected(peer, ..),
+						),
+					) => {
+						assert_eq!(peer, b);
+						break
+					},
+					_ => continue,
+				}
+			}
+
+			overseer_a.conclude().await;
+			overseer_b.conclude().await;
+		};
+
+		executor::block_on(async move {
+			let test = future::join3(test, bridges_a, bridges_b);
+			futures::pin_mut!(test);
+			future::select(router.run().boxed(), test).await;
+		});
+	}
+
+	#[test]
+	fn bitfields_are_gossiped_around_a_lossy_link() {
+		let (network, router) = SimulatedNetwork::new(protocol_names(), 0);
+		network.set_default_conditions(LinkConditions {
+			latency: Duration::from_millis(5),
+			..Default::default()
+		});
+
+		let leaf = Hash::repeat_byte(1);
+		let peers: Vec<_> = VALIDATORS.iter().map(|_| PeerId::random()).collect();
+		let mut distribute = Vec::new();
+		let mut events = Vec::new();
+		let mut nodes = Vec::new();
+		for peer in &peers {
+			let (distribute_tx, node_events, node) = start_bitfield_node(&network, *peer, leaf);
+			distribute.push(distribute_tx);
+			events.push(node_events);
+			nodes.push(node);
+		}
+		network.connect_all(PeerSet::Validation);
+
+		let keystore: SyncCryptoStorePtr = Arc::new(KeyStore::new());
+		let validator = SyncCryptoStore::sr25519_generate_new(
+			&*keystore,
+			PARACHAIN_KEY_TYPE_ID,
+			Some(&VALIDATORS[0].to_seed()),
+		)
+		.expect("key created");
+		let bitfield = executor::block_on(Signed::<AvailabilityBitfield>::sign(
+			&keystore,
+			AvailabilityBitfield(std::iter::repeat(true).take(VALIDATORS.len()).collect()),
+			&SigningContext { session_index: SESSION, parent_hash: leaf },
+			ValidatorIndex(0),
+			&validator.into(),
+		))
+		.ok()
+		.flatten()
+		.expect("should be signed");
+
+		let test = async move {
+			// Every node learns about the views of all others.
+			for node_events in &mut events {
+				let mut seen = HashSet::new();
+				while seen.len() < peers.len() - 1 {
+					match node_events.next().await.expect("node is alive") {
+						NodeEvent::PeerView(peer) => {
+							seen.insert(peer);
+						},
+						NodeEvent::Provisioned(_) => panic!("Nothing was distributed yet"),
+					}
+				}
+			}
+
+			// Everything the first node sends directly to the last one is lost from now on.
+			network.set_link_conditions(
+				peers[0],
+				peers[2],
+				LinkConditions { loss: 1.0, ..Default::default() },
+			);
+			distribute[0].unbounded_send(bitfield.clone()).unwrap();
+
+			// The bitfield still reaches every node, the last one through the second.
+			for node_events in &mut events {
+				loop {
+					match node_events.next().await.expect("node is alive") {
+						NodeEvent::Provisioned(received) => {
+							assert_eq!(received, bitfield);
+							break
+						},
+						NodeEvent::PeerView(_) => continue,
+					}
+				}
+			}
+
+			drop(distribute);
+		};
+
+		executor::block_on(async move {
+			let test = future::join(test, future::join_all(nodes));
+			futures::pin_mut!(test);
+			future::select(router.run().boxed(), test).await;
+		});
+	}
+}
@@ -20,7 +20,7 @@ use super::*;
 use polkadot_node_network_protocol::{
 	peer_set::{CollationVersion, PeerSet, PeerSetProtocolNames, ValidationVersion},
 	request_response::ReqProtocolNames,
-	v1 as protocol_v1, PeerId, Versioned,
+	v1 as protocol_v1, vstaging as protocol_vstaging, PeerId, Versioned,
 };
 
 use polkadot_node_subsystem::{
@@ -183,6 +183,13 @@ where
 					WireMessage::ProtocolMessage(msg),
 					&metrics,
 				),
+				Versioned::VStaging(msg) => send_validation_message_vstaging(
+					&mut network_service,
+					peers,
+					peerset_protocol_names,
+					WireMessage::ProtocolMessage(msg),
+					&metrics,
+				),
 			}
 		},
 		NetworkBridgeTxMessage::SendValidationMessages(msgs) => {
@@ -201,6 +208,13 @@ where
 						WireMessage::ProtocolMessage(msg),
 						&metrics,
 					),
+					Versioned::VStaging(msg) => send_validation_message_vstaging(
+						&mut network_service,
+						peers,
+						peerset_protocol_names,
+						WireMessage::ProtocolMessage(msg),
+						&metrics,
+					),
 				}
 			}
 		},
@@ -211,8 +225,9 @@ where
 				num_messages = 1usize,
 			);
 
+			// The collation protocol has no staging version, both variants carry v1 messages.
 			match msg {
-				Versioned::V1(msg) => send_collation_message_v1(
+				Versioned::V1(msg) | Versioned::VStaging(msg) => send_collation_message_v1(
 					&mut network_service,
 					peers,
 					peerset_protocol_names,
@@ -229,8 +244,9 @@ where
 			);
 
 			for (peers, msg) in msgs {
+				// The collation protocol has no staging version, both variants carry v1 messages.
 				match msg {
-					Versioned::V1(msg) => send_collation_message_v1(
+					Versioned::V1(msg) | Versioned::VStaging(msg) => send_collation_message_v1(
 						&mut network_service,
 						peers,
 						peerset_protocol_names,
@@ -350,6 +366,24 @@ fn send_validation_message_v1(
 	);
 }
 
+fn send_validation_message_vstaging(
+	net: &mut impl Network,
+	peers: Vec<PeerId>,
+	protocol_names: &PeerSetProtocolNames,
+	message: WireMessage<protocol_vstaging::ValidationProtocol>,
+	metrics: &Metrics,
+) {
+	send_message(
+		net,
+		peers,
+		PeerSet::Validation,
+		ValidationVersion::VStaging.into(),
+		protocol_names,
+		message,
+		metrics,
+	);
+}
+
 fn send_collation_message_v1(
 	net: &mut impl Network,
 	peers: Vec<PeerId>,
@@ -886,7 +886,8 @@ async fn handle_network_msg<Context>(
 			gum::trace!(target: LOG_TARGET, ?view, "Own view change");
 			handle_our_view_change(state, view).await?;
 		},
-		PeerMessage(remote, Versioned::V1(msg)) => {
+		PeerMessage(remote, Versioned::V1(msg)) |
+		PeerMessage(remote, Versioned::VStaging(msg)) => {
 			handle_incoming_peer_message(ctx, runtime, state, remote, msg).await?;
 		},
 		NewGossipTopology { .. } => {
@@ -1072,7 +1072,8 @@ async fn handle_network_msg<Context>(
 		OurViewChange(view) => {
 			handle_our_view_change(ctx, state, keystore, view).await?;
 		},
-		PeerMessage(remote, Versioned::V1(msg)) => {
+		PeerMessage(remote, Versioned::V1(msg)) |
+		PeerMessage(remote, Versioned::VStaging(msg)) => {
 			process_incoming_peer_message(ctx, state, remote, msg).await;
 		},
 	}
@@ -24,12 +24,12 @@
 //! The sender is responsible for getting our vote out, see [`sender`]. The receiver handles
 //! incoming [`DisputeRequest`]s and offers spam protection, see [`receiver`].
 
-use std::{num::NonZeroUsize, time::Duration};
+use std::{num::NonZeroUsize, sync::Arc, time::Duration};
 
 use futures::{channel::mpsc, FutureExt, StreamExt, TryFutureExt};
 
 use polkadot_node_network_protocol::authority_discovery::AuthorityDiscovery;
-use polkadot_node_subsystem_util::nesting_sender::NestingSender;
+use polkadot_node_subsystem_util::{database::Database, nesting_sender::NestingSender};
 use sp_keystore::SyncCryptoStorePtr;
 
 use polkadot_node_network_protocol::request_response::{incoming::IncomingRequestReceiver, v1};
@@ -55,6 +55,10 @@ use polkadot_node_subsystem_util::{runtime, runtime::RuntimeInfo};
 /// The actual work of sending and keeping track of transmission attempts to each validator for a
 /// particular dispute are done by [`SendTask`].  The purpose of the `DisputeSender` is to keep
 /// track of all ongoing disputes and start and clean up `SendTask`s accordingly.
+///
+/// First sends, as well as resends due to session changes or failed attempts, are batched per
+/// authority into `DisputeBatchRequest`s. Confirmed deliveries are persisted, so we don't flood the network
+/// again after a restart.
 mod sender;
 use self::sender::{DisputeSender, DisputeSenderMessage};
 
@@ -83,6 +87,10 @@ use self::sender::{DisputeSender, DisputeSenderMessage};
 /// For successfully imported votes, we will confirm the receipt of the message back to the sender.
 /// This way a received confirmation guarantees, that the vote has been stored to disk by the
 /// receiver.
+///
+/// `DisputeBatchRequest`s are handled the same way, with each contained dispute being checked and
+/// imported on its own. Disputes whose votes are part of an ongoing import batch already are
+/// skipped. The response lists all disputes which got imported successfully.
 mod receiver;
 use self::receiver::DisputesReceiver;
 
@@ -113,6 +121,13 @@ pub const RECEIVE_RATE_LIMIT: Duration = Duration::from_millis(100);
 /// We add 50ms extra, just to have some save margin to the `RECEIVE_RATE_LIMIT`.
 pub const SEND_RATE_LIMIT: Duration = RECEIVE_RATE_LIMIT.saturating_add(Duration::from_millis(50));
 
+/// Configuration for the dispute distribution subsystem.
+#[derive(Debug, Clone)]
+pub struct Config {
+	/// The column in the database used for persisting confirmed deliveries.
+	pub col_data: u32,
+}
+
 /// The dispute distribution subsystem.
 pub struct DisputeDistributionSubsystem<AD> {
 	/// Easy and efficient runtime access for this subsystem.
@@ -127,6 +142,9 @@ pub struct DisputeDistributionSubsystem<AD> {
 	/// Receiver for incoming requests.
 	req_receiver: Option<IncomingRequestReceiver<v1::DisputeRequest>>,
 
+	/// Receiver for incoming batch requests.
+	batch_req_receiver: Option<IncomingRequestReceiver<v1::DisputeBatchRequest>>,
+
 	/// Authority discovery service.
 	authority_discovery: AD,
 
@@ -160,7 +178,10 @@ where
 	pub fn new(
 		keystore: SyncCryptoStorePtr,
 		req_receiver: IncomingRequestReceiver<v1::DisputeRequest>,
+		batch_req_receiver: IncomingRequestReceiver<v1::DisputeBatchRequest>,
 		authority_discovery: AD,
+		db: Arc<dyn Database>,
+		config: Config,
 		metrics: Metrics,
 	) -> Self {
 		let runtime = RuntimeInfo::new_with_config(runtime::Config {
@@ -169,12 +190,13 @@ where
 				.expect("Dispute window can not be 0; qed"),
 		});
 		let (tx, sender_rx) = NestingSender::new_root(1);
-		let disputes_sender = DisputeSender::new(tx, metrics.clone());
+		let disputes_sender = DisputeSender::new(tx, db, config, metrics.clone());
 		Self {
 			runtime,
 			disputes_sender,
 			sender_rx,
 			req_receiver: Some(req_receiver),
+			batch_req_receiver: Some(batch_req_receiver),
 			authority_discovery,
 			metrics,
 		}
@@ -187,6 +209,9 @@ where
 			self.req_receiver
 				.take()
 				.expect("Must be provided on `new` and we take ownership here. qed."),
+			self.batch_req_receiver
+				.take()
+				.expect("Must be provided on `new` and we take ownership here. qed."),
 			self.authority_discovery.clone(),
 			self.metrics.clone(),
 		);
@@ -195,12 +220,13 @@ where
 
 		// Process messages for sending side.
 		//
-		// Note: We want the sender to be rate limited and we are currently taking advantage of the
-		// fact that the root task of this subsystem is only concerned with sending: Functions of
-		// `DisputeSender` might back pressure if the rate limit is hit, which will slow down this
-		// loop. If this fact ever changes, we will likely need another task.
+		// Note: We want the sender to be rate limited. Requests are queued by the `DisputeSender`
+		// and only sent once the rate limit allows, so all requests queued in the meantime get
+		// sent together in batches.
 		loop {
-			let message = MuxedMessage::receive(&mut ctx, &mut self.sender_rx).await;
+			let message =
+				MuxedMessage::receive(&mut ctx, &mut self.sender_rx, &mut self.disputes_sender)
+					.await;
 			match message {
 				MuxedMessage::Subsystem(result) => {
 					let result = match result? {
@@ -228,6 +254,11 @@ where
 						.map_err(Error::Sender);
 					log_error(result, "on_message")?;
 				},
+				MuxedMessage::SendReady => {
+					let result =
+						self.disputes_sender.send_queued(&mut ctx).await.map_err(Error::Sender);
+					log_error(result, "on send_queued")?;
+				},
 			}
 		}
 	}
@@ -239,7 +270,10 @@ where
 		signal: OverseerSignal,
 	) -> Result<SignalResult> {
 		match signal {
-			OverseerSignal::Conclude => return Ok(SignalResult::Conclude),
+			OverseerSignal::Conclude => {
+				self.disputes_sender.flush();
+				return Ok(SignalResult::Conclude)
+			},
 			OverseerSignal::ActiveLeaves(update) => {
 				self.disputes_sender.update_leaves(ctx, &mut self.runtime, update).await?;
 			},
@@ -269,6 +303,8 @@ enum MuxedMessage {
 	Subsystem(FatalResult<FromOrchestra<DisputeDistributionMessage>>),
 	/// Messages from spawned sender background tasks.
 	Sender(Option<DisputeSenderMessage>),
+	/// Queued requests can be sent.
+	SendReady,
 }
 
 #[overseer::contextbounds(DisputeDistribution, prefix = self::overseer)]
@@ -276,14 +312,17 @@ impl MuxedMessage {
 	async fn receive<Context>(
 		ctx: &mut Context,
 		from_sender: &mut mpsc::Receiver<DisputeSenderMessage>,
+		disputes_sender: &mut DisputeSender<DisputeSenderMessage>,
 	) -> Self {
 		// We are only fusing here to make `select` happy, in reality we will quit if the stream
 		// ends.
 		let from_overseer = ctx.recv().fuse();
-		futures::pin_mut!(from_overseer, from_sender);
+		let send_ready = disputes_sender.wait_for_send().fuse();
+		futures::pin_mut!(from_overseer, from_sender, send_ready);
 		// We select biased to make sure we finish up loose ends, before starting new work.
 		futures::select_biased!(
 			msg = from_sender.next() => MuxedMessage::Sender(msg),
+			() = send_ready => MuxedMessage::SendReady,
 			msg = from_overseer => MuxedMessage::Subsystem(msg.map_err(FatalError::SubsystemReceive)),
 		)
 	}
@@ -17,14 +17,10 @@
 use std::{collections::HashMap, time::Instant};
 
 use gum::CandidateHash;
-use polkadot_node_network_protocol::{
-	request_response::{incoming::OutgoingResponseSender, v1::DisputeRequest},
-	PeerId,
-};
 use polkadot_node_primitives::SignedDisputeStatement;
 use polkadot_primitives::{CandidateReceipt, ValidatorIndex};
 
-use crate::receiver::{BATCH_COLLECTING_INTERVAL, MIN_KEEP_BATCH_ALIVE_VOTES};
+use crate::receiver::{Requester, BATCH_COLLECTING_INTERVAL, MIN_KEEP_BATCH_ALIVE_VOTES};
 
 use super::MAX_BATCH_LIFETIME;
 
@@ -65,7 +61,7 @@ pub struct Batch {
 	best_before: Instant,
 
 	/// Requesters waiting for a response.
-	requesters: Vec<(PeerId, OutgoingResponseSender<DisputeRequest>)>,
+	requesters: Vec<Requester>,
 }
 
 /// Result of checking a batch every `BATCH_COLLECTING_INTERVAL`.
@@ -81,7 +77,7 @@ pub struct PreparedImport {
 	pub candidate_receipt: CandidateReceipt,
 	pub statements: Vec<(SignedDisputeStatement, ValidatorIndex)>,
 	/// Information about original requesters.
-	pub requesters: Vec<(PeerId, OutgoingResponseSender<DisputeRequest>)>,
+	pub requesters: Vec<Requester>,
 }
 
 impl From<Batch> for PreparedImport {
@@ -134,22 +130,30 @@ impl Batch {
 		&self.candidate_receipt
 	}
 
+	/// Whether this batch already contains a valid vote of `valid_index` and an invalid vote of
+	/// `invalid_index`.
+	///
+	/// Adding such votes would be completely redundant, so there is no point in checking their
+	/// signatures.
+	pub fn has_votes(&self, valid_index: ValidatorIndex, invalid_index: ValidatorIndex) -> bool {
+		self.valid_votes.contains_key(&valid_index) &&
+			self.invalid_votes.contains_key(&invalid_index)
+	}
+
 	/// Add votes from a validator into the batch.
 	///
 	/// The statements are supposed to be the valid and invalid statements received in a
 	/// `DisputeRequest`.
 	///
-	/// The given `pending_response` is the corresponding response sender for responding to `peer`.
-	/// If at least one of the votes is new as far as this batch is concerned we record the
-	/// pending_response, for later use. In case both votes are known already, we return the
-	/// response sender as an `Err` value.
+	/// The given `requester` is used for responding to the sending peer. If at least one of the
+	/// votes is new as far as this batch is concerned we record the requester, for later use. In
+	/// case both votes are known already, we return the requester as an `Err` value.
 	pub fn add_votes(
 		&mut self,
 		valid_vote: (SignedDisputeStatement, ValidatorIndex),
 		invalid_vote: (SignedDisputeStatement, ValidatorIndex),
-		peer: PeerId,
-		pending_response: OutgoingResponseSender<DisputeRequest>,
-	) -> Result<(), OutgoingResponseSender<DisputeRequest>> {
+		requester: Requester,
+	) -> Result<(), Requester> {
 		debug_assert!(valid_vote.0.candidate_hash() == invalid_vote.0.candidate_hash());
 		debug_assert!(valid_vote.0.candidate_hash() == &self.candidate_hash);
 
@@ -165,9 +169,9 @@ impl Batch {
 		}
 
 		if duplicate {
-			Err(pending_response)
+			Err(requester)
 		} else {
-			self.requesters.push((peer, pending_response));
+			self.requesters.push(requester);
 			Ok(())
 		}
 	}
@@ -22,7 +22,7 @@ use std::{
 use futures::future::pending;
 
 use polkadot_node_network_protocol::request_response::DISPUTE_REQUEST_TIMEOUT;
-use polkadot_primitives::{CandidateHash, CandidateReceipt};
+use polkadot_primitives::{CandidateHash, CandidateReceipt, ValidatorIndex};
 
 use crate::{
 	receiver::batches::{batch::TickResult, waiting_queue::PendingWake},
@@ -115,6 +115,18 @@ impl Batches {
 		Ok(result)
 	}
 
+	/// Whether there is a batch for the given candidate, already containing the given votes.
+	pub fn has_votes(
+		&self,
+		candidate_hash: &CandidateHash,
+		valid_index: ValidatorIndex,
+		invalid_index: ValidatorIndex,
+	) -> bool {
+		self.batches
+			.get(candidate_hash)
+			.map_or(false, |batch| batch.has_votes(valid_index, invalid_index))
+	}
+
 	/// Wait for the next `tick` to check for ready batches.
 	///
 	/// This function blocks (returns `Poll::Pending`) until at least one batch can be
@@ -46,6 +46,9 @@ pub enum Error {
 	#[error("Dispute request with invalid signatures, from peer {0}.")]
 	InvalidSignature(PeerId),
 
+	#[error("Batch request from peer {0} contained an invalid number of disputes: {1}")]
+	InvalidBatchSize(PeerId, usize),
+
 	#[error("Received votes from peer {0} have been completely redundant.")]
 	RedundantMessage(PeerId),
 
@@ -15,10 +15,11 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::{
+	collections::{HashMap, HashSet},
 	num::NonZeroUsize,
 	pin::Pin,
 	task::{Context, Poll},
-	time::Duration,
+	time::{Duration, Instant},
 };
 
 use futures::{
@@ -34,12 +35,12 @@ use polkadot_node_network_protocol::{
 	authority_discovery::AuthorityDiscovery,
 	request_response::{
 		incoming::{self, OutgoingResponse, OutgoingResponseSender},
-		v1::{DisputeRequest, DisputeResponse},
-		IncomingRequest, IncomingRequestReceiver,
+		v1::{DisputeBatchRequest, DisputeBatchResponse, DisputeRequest, DisputeResponse},
+		IncomingRequest, IncomingRequestReceiver, DISPUTE_REQUEST_TIMEOUT, MAX_DISPUTES_PER_BATCH,
 	},
 	PeerId, UnifiedReputationChange as Rep,
 };
-use polkadot_node_primitives::DISPUTE_WINDOW;
+use polkadot_node_primitives::{disputes::UncheckedDisputeMessage, DISPUTE_WINDOW};
 use polkadot_node_subsystem::{
 	messages::{DisputeCoordinatorMessage, ImportStatementsResult},
 	overseer,
@@ -62,7 +63,7 @@ mod batches;
 use self::{
 	batches::{Batches, FoundBatch, PreparedImport},
 	error::{log_error, JfyiError, JfyiResult, Result},
-	peer_queues::PeerQueues,
+	peer_queues::{PeerQueues, QueuedRequest},
 };
 
 const COST_INVALID_REQUEST: Rep = Rep::CostMajor("Received message could not be decoded.");
@@ -103,6 +104,9 @@ pub struct DisputesReceiver<Sender, AD> {
 	/// Channel to retrieve incoming requests from.
 	receiver: IncomingRequestReceiver<DisputeRequest>,
 
+	/// Channel to retrieve incoming batch requests from.
+	batch_receiver: IncomingRequestReceiver<DisputeBatchRequest>,
+
 	/// Rate limiting queue for each peer (only authorities).
 	peer_queues: PeerQueues,
 
@@ -115,6 +119,12 @@ pub struct DisputesReceiver<Sender, AD> {
 	/// Imports currently being processed by the `dispute-coordinator`.
 	pending_imports: FuturesUnordered<PendingImport>,
 
+	/// Batch requests waiting for the imports of their disputes.
+	pending_batch_responses: HashMap<BatchRequestId, PendingBatchResponse>,
+
+	/// Id to use for the next batch request.
+	next_batch_request_id: BatchRequestId,
+
 	/// Log received requests.
 	metrics: Metrics,
 }
@@ -131,12 +141,12 @@ enum MuxedMessage {
 	ConfirmedImport(ImportResult),
 
 	/// A new request has arrived and should be handled.
-	NewRequest(IncomingRequest<DisputeRequest>),
+	NewRequest(QueuedRequest),
 
 	/// Rate limit timer hit - is is time to process one row of messages.
 	///
 	/// This is the result of calling `self.peer_queues.pop_reqs()`.
-	WakePeerQueuesPopReqs(Vec<IncomingRequest<DisputeRequest>>),
+	WakePeerQueuesPopReqs(Vec<QueuedRequest>),
 
 	/// It is time to check batches.
 	///
@@ -156,6 +166,7 @@ where
 	pub fn new(
 		sender: Sender,
 		receiver: IncomingRequestReceiver<DisputeRequest>,
+		batch_receiver: IncomingRequestReceiver<DisputeBatchRequest>,
 		authority_discovery: AD,
 		metrics: Metrics,
 	) -> Self {
@@ -168,10 +179,13 @@ where
 			runtime,
 			sender,
 			receiver,
+			batch_receiver,
 			peer_queues: PeerQueues::new(),
 			batches: Batches::new(),
 			authority_discovery,
 			pending_imports: FuturesUnordered::new(),
+			pending_batch_responses: HashMap::new(),
+			next_batch_request_id: 0,
 			metrics,
 		}
 	}
@@ -214,7 +228,11 @@ where
 				for req in reqs {
 					// No early return - we cannot cancel imports of one peer, because the import of
 					// another failed:
-					match log_error(self.start_import_or_batch(req).await) {
+					let result = match req {
+						QueuedRequest::Single(req) => self.start_import_or_batch(req).await,
+						QueuedRequest::Batch(req) => self.start_batch_import(req).await,
+					};
+					match log_error(result) {
 						Ok(()) => {},
 						Err(fatal) => return Err(fatal.into()),
 					}
@@ -227,7 +245,7 @@ where
 			MuxedMessage::ConfirmedImport(import_result) => {
 				self.update_imported_requests_metrics(&import_result);
 				// Confirm imports to requesters/punish them on invalid imports:
-				send_responses_to_requesters(import_result).await?;
+				self.send_responses_to_requesters(import_result)?;
 			},
 		}
 
@@ -264,7 +282,16 @@ where
 			if let Poll::Ready(r) = next_req.poll(ctx) {
 				return match r {
 					Err(e) => Poll::Ready(Err(incoming::Error::from(e).into())),
-					Ok(v) => Poll::Ready(Ok(MuxedMessage::NewRequest(v))),
+					Ok(v) => Poll::Ready(Ok(MuxedMessage::NewRequest(QueuedRequest::Single(v)))),
+				}
+			}
+
+			let next_batch_req = self.batch_receiver.recv(|| vec![COST_INVALID_REQUEST]);
+			pin_mut!(next_batch_req);
+			if let Poll::Ready(r) = next_batch_req.poll(ctx) {
+				return match r {
+					Err(e) => Poll::Ready(Err(incoming::Error::from(e).into())),
+					Ok(v) => Poll::Ready(Ok(MuxedMessage::NewRequest(QueuedRequest::Batch(v)))),
 				}
 			}
 			Poll::Pending
@@ -275,10 +302,11 @@ where
 	/// Process incoming requests.
 	///
 	/// - Check sender is authority
+	/// - Check batch requests are well sized.
 	/// - Dispatch message to corresponding queue in `peer_queues`.
 	/// - If queue is full, drop message and change reputation of sender.
-	async fn dispatch_to_queues(&mut self, req: IncomingRequest<DisputeRequest>) -> JfyiResult<()> {
-		let peer = req.peer;
+	async fn dispatch_to_queues(&mut self, req: QueuedRequest) -> JfyiResult<()> {
+		let peer = req.peer();
 		// Only accept messages from validators, in case there are multiple `AuthorityId`s, we
 		// just take the first one. On session boundaries this might allow validators to double
 		// their rate limit for a short period of time, which seems acceptable.
@@ -289,17 +317,22 @@ where
 			.and_then(|s| s.into_iter().next())
 		{
 			None => {
-				req.send_outgoing_response(OutgoingResponse {
-					result: Err(()),
-					reputation_changes: vec![COST_NOT_A_VALIDATOR],
-					sent_feedback: None,
-				})
-				.map_err(|_| JfyiError::SendResponses(vec![peer]))?;
+				req.reject(vec![COST_NOT_A_VALIDATOR])
+					.map_err(|_| JfyiError::SendResponses(vec![peer]))?;
 				return Err(JfyiError::NotAValidator(peer).into())
 			},
 			Some(auth_id) => auth_id,
 		};
 
+		if let QueuedRequest::Batch(batch_req) = &req {
+			let len = batch_req.payload.0.len();
+			if len == 0 || len > MAX_DISPUTES_PER_BATCH {
+				req.reject(vec![COST_INVALID_REQUEST])
+					.map_err(|_| JfyiError::SendResponses(vec![peer]))?;
+				return Err(JfyiError::InvalidBatchSize(peer, len))
+			}
+		}
+
 		// Queue request:
 		if let Err((authority_id, req)) = self.peer_queues.push_req(authority_id, req) {
 			gum::debug!(
@@ -308,12 +341,8 @@ where
 				?peer,
 				"Peer hit the rate limit - dropping message."
 			);
-			req.send_outgoing_response(OutgoingResponse {
-				result: Err(()),
-				reputation_changes: vec![COST_APPARENT_FLOOD],
-				sent_feedback: None,
-			})
-			.map_err(|_| JfyiError::SendResponses(vec![peer]))?;
+			req.reject(vec![COST_APPARENT_FLOOD])
+				.map_err(|_| JfyiError::SendResponses(vec![peer]))?;
 			return Err(JfyiError::AuthorityFlooding(authority_id))
 		}
 		Ok(())
@@ -370,16 +399,16 @@ where
 				let import = PreparedImport {
 					candidate_receipt: batch.candidate_receipt().clone(),
 					statements: vec![valid_vote, invalid_vote],
-					requesters: vec![(peer, pending_response)],
+					requesters: vec![Requester::Single(peer, pending_response)],
 				};
 				self.start_import(import).await;
 			},
 			FoundBatch::Found(batch) => {
 				gum::trace!(target: LOG_TARGET, ?candidate_hash, "Batch exists - batching request");
-				let batch_result =
-					batch.add_votes(valid_vote, invalid_vote, peer, pending_response);
+				let requester = Requester::Single(peer, pending_response);
+				let batch_result = batch.add_votes(valid_vote, invalid_vote, requester);
 
-				if let Err(pending_response) = batch_result {
+				if let Err(Requester::Single(_, pending_response)) = batch_result {
 					// We don't expect honest peers to send redundant votes within a single batch,
 					// as the timeout for retry is much higher. Still we don't want to punish the
 					// node as it might not be the node's fault. Some other (malicious) node could have been
@@ -411,6 +440,159 @@ where
 		Ok(())
 	}
 
+	/// Start importing votes of all disputes in the given batch request.
+	///
+	/// Works like `start_import_or_batch` for each contained dispute, signatures are checked for
+	/// each dispute on its own. Disputes occurring more than once and disputes whose votes we
+	/// already have in a batch are skipped. The whole request is rejected on the first invalid
+	/// signature.
+	///
+	/// Disputes whose votes we already have are confirmed right away, so the requester won't keep
+	/// retrying them.
+	///
+	/// The response is sent once all imports have finished, see `send_responses_to_requesters`.
+	async fn start_batch_import(
+		&mut self,
+		incoming: IncomingRequest<DisputeBatchRequest>,
+	) -> Result<()> {
+		let IncomingRequest { peer, payload, pending_response } = incoming;
+
+		let mut seen = HashSet::with_capacity(payload.0.len());
+		let mut checked = Vec::with_capacity(payload.0.len());
+		let mut confirmed = Vec::new();
+		for message in payload.0 {
+			let candidate_hash = message.candidate_receipt.hash();
+			// Duplicates get confirmed or not together with their first occurrence:
+			if !seen.insert(candidate_hash) {
+				continue
+			}
+			if self.is_redundant(&candidate_hash, &message) {
+				confirmed.push(candidate_hash);
+				gum::trace!(
+					target: LOG_TARGET,
+					?candidate_hash,
+					?peer,
+					"Skipping redundant dispute in batch request"
+				);
+				continue
+			}
+
+			let info = match self
+				.runtime
+				.get_session_info_by_index(
+					&mut self.sender,
+					message.candidate_receipt.descriptor.relay_parent,
+					message.session_index,
+				)
+				.await
+			{
+				Err(err) => {
+					// Not the peer's fault necessarily, so no reputation change:
+					pending_response
+						.send_outgoing_response(OutgoingResponse {
+							result: Err(()),
+							reputation_changes: Vec::new(),
+							sent_feedback: None,
+						})
+						.map_err(|_| JfyiError::SendResponses(vec![peer]))?;
+					return Err(From::from(err))
+				},
+				Ok(info) => info,
+			};
+
+			match message.try_into_signed_votes(&info.session_info) {
+				Err(()) => {
+					pending_response
+						.send_outgoing_response(OutgoingResponse {
+							result: Err(()),
+							reputation_changes: vec![COST_INVALID_SIGNATURE],
+							sent_feedback: None,
+						})
+						.map_err(|_| JfyiError::SetPeerReputation(peer))?;
+
+					return Err(From::from(JfyiError::InvalidSignature(peer)))
+				},
+				Ok(votes) => checked.push(votes),
+			}
+		}
+
+		let id = self.next_batch_request_id;
+		self.next_batch_request_id = self.next_batch_request_id.wrapping_add(1);
+
+		let mut outstanding = 0;
+		for (candidate_receipt, valid_vote, invalid_vote) in checked {
+			let candidate_hash = *valid_vote.0.candidate_hash();
+			match self.batches.find_batch(candidate_hash, candidate_receipt) {
+				Err(err) => {
+					gum::debug!(
+						target: LOG_TARGET,
+						?candidate_hash,
+						?peer,
+						?err,
+						"Dropping dispute from batch request"
+					);
+				},
+				Ok(FoundBatch::Created(batch)) => {
+					let import = PreparedImport {
+						candidate_receipt: batch.candidate_receipt().clone(),
+						statements: vec![valid_vote, invalid_vote],
+						requesters: vec![Requester::Batched(peer, id)],
+					};
+					self.start_import(import).await;
+					outstanding += 1;
+				},
+				Ok(FoundBatch::Found(batch)) => {
+					let requester = Requester::Batched(peer, id);
+					if batch.add_votes(valid_vote, invalid_vote, requester).is_ok() {
+						outstanding += 1;
+					} else {
+						// Votes are known already:
+						confirmed.push(candidate_hash);
+					}
+				},
+			}
+		}
+
+		let pending = PendingBatchResponse {
+			peer,
+			pending_response,
+			outstanding,
+			confirmed,
+			invalid_import: false,
+			started: Instant::now(),
+		};
+		if outstanding == 0 {
+			// Nothing new to import - respond right away:
+			return pending.respond().map_err(From::from)
+		}
+
+		self.prune_pending_batch_responses();
+		self.pending_batch_responses.insert(id, pending);
+		Ok(())
+	}
+
+	/// Whether the votes of the given dispute are all part of a batch already.
+	fn is_redundant(
+		&self,
+		candidate_hash: &CandidateHash,
+		message: &UncheckedDisputeMessage,
+	) -> bool {
+		self.batches.has_votes(
+			candidate_hash,
+			message.valid_vote.validator_index,
+			message.invalid_vote.validator_index,
+		)
+	}
+
+	/// Drop pending batch responses, whose requester gave up waiting already.
+	///
+	/// Usually entries get removed once all imports are done, but imports might get canceled.
+	fn prune_pending_batch_responses(&mut self) {
+		let now = Instant::now();
+		self.pending_batch_responses
+			.retain(|_, pending| now.duration_since(pending.started) < DISPUTE_REQUEST_TIMEOUT);
+	}
+
 	/// Trigger import into the dispute-coordinator of ready batches (`PreparedImport`s).
 	async fn import_ready_batches(&mut self, ready_imports: Vec<PreparedImport>) {
 		for import in ready_imports {
@@ -449,6 +631,62 @@ where
 		self.pending_imports.push(pending);
 	}
 
+	/// Confirm imports to requesters, or punish them on invalid imports.
+	///
+	/// Requesters of batch requests get their response once all imports of the batch are done.
+	fn send_responses_to_requesters(&mut self, import_result: ImportResult) -> JfyiResult<()> {
+		let ImportResult { candidate_hash, requesters, result } = import_result;
+
+		let mk_response = match result {
+			ImportStatementsResult::ValidImport => || OutgoingResponse {
+				result: Ok(DisputeResponse::Confirmed),
+				reputation_changes: Vec::new(),
+				sent_feedback: None,
+			},
+			ImportStatementsResult::InvalidImport => || OutgoingResponse {
+				result: Err(()),
+				reputation_changes: vec![COST_INVALID_IMPORT],
+				sent_feedback: None,
+			},
+		};
+
+		let mut sending_failed_for = Vec::new();
+		for requester in requesters {
+			match requester {
+				Requester::Single(peer, pending_response) =>
+					if let Err(()) = pending_response.send_outgoing_response(mk_response()) {
+						sending_failed_for.push(peer);
+					},
+				Requester::Batched(peer, id) => {
+					let pending = match self.pending_batch_responses.get_mut(&id) {
+						None => continue,
+						Some(pending) => pending,
+					};
+					pending.outstanding -= 1;
+					match result {
+						ImportStatementsResult::ValidImport =>
+							pending.confirmed.push(candidate_hash),
+						ImportStatementsResult::InvalidImport => pending.invalid_import = true,
+					}
+					if pending.outstanding == 0 {
+						let pending = self.pending_batch_responses.remove(&id).expect(
+							"Entry exists, we just got it from `pending_batch_responses`. qed.",
+						);
+						if pending.respond().is_err() {
+							sending_failed_for.push(peer);
+						}
+					}
+				},
+			}
+		}
+
+		if !sending_failed_for.is_empty() {
+			Err(JfyiError::SendResponses(sending_failed_for))
+		} else {
+			Ok(())
+		}
+	}
+
 	fn update_imported_requests_metrics(&self, result: &ImportResult) {
 		let label = match result.result {
 			ImportStatementsResult::ValidImport => SUCCEEDED,
@@ -458,33 +696,48 @@ where
 	}
 }
 
-async fn send_responses_to_requesters(import_result: ImportResult) -> JfyiResult<()> {
-	let ImportResult { requesters, result } = import_result;
-
-	let mk_response = match result {
-		ImportStatementsResult::ValidImport => || OutgoingResponse {
-			result: Ok(DisputeResponse::Confirmed),
-			reputation_changes: Vec::new(),
-			sent_feedback: None,
-		},
-		ImportStatementsResult::InvalidImport => || OutgoingResponse {
-			result: Err(()),
-			reputation_changes: vec![COST_INVALID_IMPORT],
-			sent_feedback: None,
-		},
-	};
-
-	let mut sending_failed_for = Vec::new();
-	for (peer, pending_response) in requesters {
-		if let Err(()) = pending_response.send_outgoing_response(mk_response()) {
-			sending_failed_for.push(peer);
-		}
-	}
+/// Identifies a batch request while its disputes are being imported.
+type BatchRequestId = u64;
 
-	if !sending_failed_for.is_empty() {
-		Err(JfyiError::SendResponses(sending_failed_for))
-	} else {
-		Ok(())
+/// A peer waiting for the import of votes it sent us.
+pub enum Requester {
+	/// Votes were sent in a `DisputeRequest`, which can be responded to right away.
+	Single(PeerId, OutgoingResponseSender<DisputeRequest>),
+	/// Votes were sent as part of a `DisputeBatchRequest`.
+	///
+	/// The response is sent once all imports of that request are done, see
+	/// `DisputesReceiver::pending_batch_responses`.
+	Batched(PeerId, BatchRequestId),
+}
+
+/// A batch request waiting for the imports of its disputes.
+struct PendingBatchResponse {
+	/// The requesting peer.
+	peer: PeerId,
+	/// Sender for the eventual response.
+	pending_response: OutgoingResponseSender<DisputeBatchRequest>,
+	/// Number of imports we are still waiting for.
+	outstanding: usize,
+	/// Candidates whose import succeeded.
+	confirmed: Vec<CandidateHash>,
+	/// Whether any import was deemed invalid by the `dispute-coordinator`.
+	invalid_import: bool,
+	/// When we received the request.
+	started: Instant,
+}
+
+impl PendingBatchResponse {
+	/// Confirm the successfully imported disputes to the requester.
+	fn respond(self) -> JfyiResult<()> {
+		let reputation_changes =
+			if self.invalid_import { vec![COST_INVALID_IMPORT] } else { Vec::new() };
+		self.pending_response
+			.send_outgoing_response(OutgoingResponse {
+				result: Ok(DisputeBatchResponse::Confirmed(self.confirmed)),
+				reputation_changes,
+				sent_feedback: None,
+			})
+			.map_err(|_| JfyiError::SendResponses(vec![self.peer]))
 	}
 }
 
@@ -496,14 +749,16 @@ async fn send_responses_to_requesters(import_result: ImportResult) -> JfyiResult
 /// - Keep track of requesting peers so we can confirm the import/punish them on invalid imports.
 struct PendingImport {
 	candidate_hash: CandidateHash,
-	requesters: Vec<(PeerId, OutgoingResponseSender<DisputeRequest>)>,
+	requesters: Vec<Requester>,
 	pending_response: oneshot::Receiver<ImportStatementsResult>,
 }
 
 /// A `PendingImport` becomes an `ImportResult` once done.
 struct ImportResult {
+	/// The candidate the votes were imported for.
+	candidate_hash: CandidateHash,
 	/// Requesters of that import.
-	requesters: Vec<(PeerId, OutgoingResponseSender<DisputeRequest>)>,
+	requesters: Vec<Requester>,
 	/// Actual result of the import.
 	result: ImportStatementsResult,
 }
@@ -513,7 +768,11 @@ impl PendingImport {
 		let result = (&mut self.pending_response)
 			.await
 			.map_err(|_| JfyiError::ImportCanceled(self.candidate_hash))?;
-		Ok(ImportResult { requesters: std::mem::take(&mut self.requesters), result })
+		Ok(ImportResult {
+			candidate_hash: self.candidate_hash,
+			requesters: std::mem::take(&mut self.requesters),
+			result,
+		})
 	}
 }
 
@@ -18,7 +18,14 @@ use std::collections::{hash_map::Entry, HashMap, VecDeque};
 
 use futures::future::pending;
 use futures_timer::Delay;
-use polkadot_node_network_protocol::request_response::{v1::DisputeRequest, IncomingRequest};
+use polkadot_node_network_protocol::{
+	request_response::{
+		incoming::OutgoingResponse,
+		v1::{DisputeBatchRequest, DisputeRequest},
+		IncomingRequest,
+	},
+	PeerId, UnifiedReputationChange as Rep,
+};
 use polkadot_primitives::AuthorityDiscoveryId;
 
 use crate::RECEIVE_RATE_LIMIT;
@@ -27,7 +34,9 @@ use crate::RECEIVE_RATE_LIMIT;
 ///
 /// The larger this value is, the larger bursts are allowed to be without us dropping messages. On
 /// the flip side this gets allocated per validator, so for a size of 10 this will result
-/// in `10_000 * size_of(IncomingRequest)` in the worst case.
+/// in `10_000 * size_of(QueuedRequest)` in the worst case.
+///
+/// A `DisputeBatchRequest` is charged one slot per contained dispute, see `QueuedRequest::cost`.
 ///
 /// `PEER_QUEUE_CAPACITY` must not be 0 for obvious reasons.
 #[cfg(not(test))]
@@ -35,24 +44,93 @@ pub const PEER_QUEUE_CAPACITY: usize = 10;
 #[cfg(test)]
 pub const PEER_QUEUE_CAPACITY: usize = 2;
 
+/// An incoming request as queued in `PeerQueues`.
+pub enum QueuedRequest {
+	/// A request concerning a single dispute.
+	Single(IncomingRequest<DisputeRequest>),
+	/// A request concerning multiple disputes.
+	Batch(IncomingRequest<DisputeBatchRequest>),
+}
+
+impl QueuedRequest {
+	/// The peer which sent the request.
+	pub fn peer(&self) -> PeerId {
+		match self {
+			Self::Single(req) => req.peer,
+			Self::Batch(req) => req.peer,
+		}
+	}
+
+	/// How many slots of the peer's queue and rate limit this request is charged.
+	///
+	/// One per dispute, so batching does not allow a peer to get more disputes processed than it
+	/// could get with single requests.
+	pub fn cost(&self) -> usize {
+		match self {
+			Self::Single(_) => 1,
+			Self::Batch(req) => req.payload.0.len().max(1),
+		}
+	}
+
+	/// Respond with an error, applying the given reputation changes.
+	pub fn reject(self, reputation_changes: Vec<Rep>) -> Result<(), ()> {
+		match self {
+			Self::Single(req) => req.send_outgoing_response(OutgoingResponse {
+				result: Err(()),
+				reputation_changes,
+				sent_feedback: None,
+			}),
+			Self::Batch(req) => req.send_outgoing_response(OutgoingResponse {
+				result: Err(()),
+				reputation_changes,
+				sent_feedback: None,
+			}),
+		}
+	}
+}
+
 /// Queues for messages from authority peers for rate limiting.
 ///
 /// Invariants ensured:
 ///
-/// 1. No queue will ever have more than `PEER_QUEUE_CAPACITY` elements.
-/// 2. There are no empty queues. Whenever a queue gets empty, it is removed. This way checking
-///    whether there are any messages queued is cheap.
+/// 1. No request is accepted into a queue which is already charged `PEER_QUEUE_CAPACITY` slots
+///    or more.
+/// 2. There are no empty queues. Whenever a queue gets empty and its cooldown is over, it is
+///    removed. This way checking whether there are any messages queued is cheap.
 /// 3. As long as not empty, `pop_reqs` will, if called in sequence, not return `Ready` more often
 ///    than once for every `RECEIVE_RATE_LIMIT`, but it will always return Ready eventually.
 /// 4. If empty `pop_reqs` will never return `Ready`, but will always be `Pending`.
+/// 5. A request costing `n` slots blocks its peer's queue for `n` rounds of `pop_reqs`.
 pub struct PeerQueues {
 	/// Actual queues.
-	queues: HashMap<AuthorityDiscoveryId, VecDeque<IncomingRequest<DisputeRequest>>>,
+	queues: HashMap<AuthorityDiscoveryId, PeerQueue>,
 
 	/// Delay timer for establishing the rate limit.
 	rate_limit_timer: Option<Delay>,
 }
 
+/// The queue of a single peer.
+#[derive(Default)]
+struct PeerQueue {
+	/// Requests waiting to be processed.
+	reqs: VecDeque<QueuedRequest>,
+	/// Rounds of `pop_reqs` to skip, before the next request is processed.
+	///
+	/// Set after processing a request costing more than a single slot.
+	cooldown: usize,
+}
+
+impl PeerQueue {
+	/// Slots this queue is currently charged.
+	fn charged(&self) -> usize {
+		self.cooldown + self.reqs.iter().map(QueuedRequest::cost).sum::<usize>()
+	}
+
+	fn is_empty(&self) -> bool {
+		self.cooldown == 0 && self.reqs.is_empty()
+	}
+}
+
 impl PeerQueues {
 	/// New empty `PeerQueues`.
 	pub fn new() -> Self {
@@ -65,18 +143,18 @@ impl PeerQueues {
 	pub fn push_req(
 		&mut self,
 		peer: AuthorityDiscoveryId,
-		req: IncomingRequest<DisputeRequest>,
-	) -> Result<(), (AuthorityDiscoveryId, IncomingRequest<DisputeRequest>)> {
+		req: QueuedRequest,
+	) -> Result<(), (AuthorityDiscoveryId, QueuedRequest)> {
 		let queue = match self.queues.entry(peer) {
-			Entry::Vacant(vacant) => vacant.insert(VecDeque::new()),
+			Entry::Vacant(vacant) => vacant.insert(PeerQueue::default()),
 			Entry::Occupied(occupied) => {
-				if occupied.get().len() >= PEER_QUEUE_CAPACITY {
+				if occupied.get().charged() >= PEER_QUEUE_CAPACITY {
 					return Err((occupied.key().clone(), req))
 				}
 				occupied.into_mut()
 			},
 		};
-		queue.push_back(req);
+		queue.reqs.push_back(req);
 
 		// We have at least one element to process - rate limit `timer` needs to exist now:
 		self.ensure_timer();
@@ -85,23 +163,29 @@ impl PeerQueues {
 
 	/// Pop all heads and return them for processing.
 	///
-	/// This gets one message from each peer that has sent at least one.
+	/// This gets one message from each peer that has sent at least one and is not cooling down
+	/// from a previous batch request.
 	///
 	/// This function is rate limited, if called in sequence it will not return more often than
 	/// every `RECEIVE_RATE_LIMIT`.
 	///
 	/// NOTE: If empty this function will not return `Ready` at all, but will always be `Pending`.
-	pub async fn pop_reqs(&mut self) -> Vec<IncomingRequest<DisputeRequest>> {
+	pub async fn pop_reqs(&mut self) -> Vec<QueuedRequest> {
 		self.wait_for_timer().await;
 
 		let mut heads = Vec::with_capacity(self.queues.len());
 		let old_queues = std::mem::replace(&mut self.queues, HashMap::new());
 		for (k, mut queue) in old_queues.into_iter() {
-			let front = queue.pop_front();
-			debug_assert!(front.is_some(), "Invariant that queues are never empty is broken.");
-
-			if let Some(front) = front {
-				heads.push(front);
+			if queue.cooldown > 0 {
+				queue.cooldown -= 1;
+			} else {
+				let front = queue.reqs.pop_front();
+				debug_assert!(front.is_some(), "Invariant that queues are never empty is broken.");
+
+				if let Some(front) = front {
+					queue.cooldown = front.cost() - 1;
+					heads.push(front);
+				}
 			}
 			if !queue.is_empty() {
 				self.queues.insert(k, queue);
@@ -0,0 +1,167 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Persistence of confirmed dispute deliveries.
+//!
+//! For each dispute we keep the set of authorities which confirmed reception of our votes, so we
+//! don't need to send to them again after a restart.
+//!
+//! Failing database accesses are not fatal: In the worst case we just send a dispute again to an
+//! authority which already has it.
+
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
+
+use parity_scale_codec::{Decode, Encode};
+
+use polkadot_node_subsystem_util::database::{DBTransaction, Database};
+use polkadot_primitives::{AuthorityDiscoveryId, CandidateHash};
+
+use crate::{Config, LOG_TARGET};
+
+const DELIVERY_PREFIX: &[u8; 16] = b"dispute_delivery";
+
+/// Access to persisted delivery status.
+pub struct DeliveryDb {
+	db: Arc<dyn Database>,
+	config: Config,
+	/// Confirmed deliveries not yet written to disk.
+	dirty: HashMap<CandidateHash, HashSet<AuthorityDiscoveryId>>,
+	/// Whether we already got rid of stale entries from previous runs.
+	pruned: bool,
+}
+
+impl DeliveryDb {
+	/// Create a new `DeliveryDb` using the given database and column.
+	pub fn new(db: Arc<dyn Database>, config: Config) -> Self {
+		Self { db, config, dirty: HashMap::new(), pruned: false }
+	}
+
+	/// Authorities which confirmed reception of the given dispute in the past.
+	pub fn load(&self, candidate_hash: &CandidateHash) -> HashSet<AuthorityDiscoveryId> {
+		match self.db.get(self.config.col_data, &delivery_key(candidate_hash)) {
+			Ok(None) => HashSet::new(),
+			Ok(Some(raw)) => match Vec::<AuthorityDiscoveryId>::decode(&mut &raw[..]) {
+				Ok(delivered) => delivered.into_iter().collect(),
+				Err(err) => {
+					gum::warn!(
+						target: LOG_TARGET,
+						?candidate_hash,
+						?err,
+						"Decoding persisted dispute deliveries failed."
+					);
+					HashSet::new()
+				},
+			},
+			Err(err) => {
+				gum::warn!(
+					target: LOG_TARGET,
+					?candidate_hash,
+					?err,
+					"Loading persisted dispute deliveries failed."
+				);
+				HashSet::new()
+			},
+		}
+	}
+
+	/// Record the set of authorities which confirmed the given dispute.
+	///
+	/// Will be written on the next call to `flush`.
+	pub fn note_delivered(
+		&mut self,
+		candidate_hash: CandidateHash,
+		delivered: HashSet<AuthorityDiscoveryId>,
+	) {
+		self.dirty.insert(candidate_hash, delivered);
+	}
+
+	/// Whether there are recorded deliveries not yet written to disk.
+	pub fn is_dirty(&self) -> bool {
+		!self.dirty.is_empty()
+	}
+
+	/// Write all recorded deliveries to disk and remove entries of the given obsolete disputes.
+	pub fn flush(&mut self, obsolete: impl IntoIterator<Item = CandidateHash>) {
+		let mut tx = DBTransaction::new();
+		for candidate_hash in obsolete {
+			self.dirty.remove(&candidate_hash);
+			tx.delete(self.config.col_data, &delivery_key(&candidate_hash));
+		}
+		for (candidate_hash, delivered) in self.dirty.drain() {
+			let delivered: Vec<_> = delivered.into_iter().collect();
+			tx.put_vec(self.config.col_data, &delivery_key(&candidate_hash), delivered.encode());
+		}
+		if tx.ops.is_empty() {
+			return
+		}
+		if let Err(err) = self.db.write(tx) {
+			gum::warn!(target: LOG_TARGET, ?err, "Writing dispute deliveries failed.");
+		}
+	}
+
+	/// Remove entries of all disputes which are not active anymore.
+	///
+	/// Entries of disputes which concluded while we were offline can only be found by iterating
+	/// the column, we therefore do this once after startup only. Afterwards, entries are removed
+	/// via `flush` as disputes become obsolete.
+	pub fn prune_once(&mut self, active: &HashSet<CandidateHash>) {
+		if self.pruned {
+			return
+		}
+		self.pruned = true;
+
+		let mut tx = DBTransaction::new();
+		for entry in self.db.iter_with_prefix(self.config.col_data, DELIVERY_PREFIX) {
+			let key = match entry {
+				Ok((key, _)) => key,
+				Err(err) => {
+					gum::warn!(target: LOG_TARGET, ?err, "Iterating dispute deliveries failed.");
+					return
+				},
+			};
+			let candidate_hash = match decode_delivery_key(&key) {
+				Some(candidate_hash) => candidate_hash,
+				None => continue,
+			};
+			if !active.contains(&candidate_hash) {
+				tx.delete(self.config.col_data, &key);
+			}
+		}
+		if tx.ops.is_empty() {
+			return
+		}
+		if let Err(err) = self.db.write(tx) {
+			gum::warn!(target: LOG_TARGET, ?err, "Pruning stale dispute deliveries failed.");
+		}
+	}
+}
+
+fn delivery_key(candidate_hash: &CandidateHash) -> [u8; 16 + 32] {
+	let mut key = [0; 16 + 32];
+	key[..16].copy_from_slice(DELIVERY_PREFIX);
+	candidate_hash.using_encoded(|s| key[16..].copy_from_slice(s));
+	key
+}
+
+fn decode_delivery_key(key: &[u8]) -> Option<CandidateHash> {
+	if key.len() != 16 + 32 || !key.starts_with(DELIVERY_PREFIX) {
+		return None
+	}
+	CandidateHash::decode(&mut &key[16..]).ok()
+}
@@ -17,6 +17,7 @@
 use std::{
 	collections::{HashMap, HashSet},
 	pin::Pin,
+	sync::Arc,
 	task::Poll,
 	time::Duration,
 };
@@ -30,8 +31,10 @@ use polkadot_node_primitives::{DisputeMessage, DisputeStatus};
 use polkadot_node_subsystem::{
 	messages::DisputeCoordinatorMessage, overseer, ActiveLeavesUpdate, SubsystemSender,
 };
-use polkadot_node_subsystem_util::{nesting_sender::NestingSender, runtime::RuntimeInfo};
-use polkadot_primitives::{CandidateHash, Hash, SessionIndex};
+use polkadot_node_subsystem_util::{
+	database::Database, nesting_sender::NestingSender, runtime::RuntimeInfo,
+};
+use polkadot_primitives::{AuthorityDiscoveryId, CandidateHash, Hash, SessionIndex};
 
 /// For each ongoing dispute we have a `SendTask` which takes care of it.
 ///
@@ -41,15 +44,19 @@ use polkadot_primitives::{CandidateHash, Hash, SessionIndex};
 /// As we assume disputes have a priority, we start sending for disputes in the order
 /// `start_sender` got called.
 mod send_task;
-use send_task::SendTask;
 pub use send_task::TaskFinish;
+use send_task::{send_batched_requests, DeliveryStatus, SendTask, TaskResult};
+
+/// Persistence of confirmed deliveries, so we don't resend after a restart.
+mod db;
+use db::DeliveryDb;
 
 /// Error and [`Result`] type for sender.
 mod error;
 pub use error::{Error, FatalError, JfyiError, Result};
 
 use self::error::JfyiErrorResult;
-use crate::{Metrics, LOG_TARGET, SEND_RATE_LIMIT};
+use crate::{Config, Metrics, LOG_TARGET, SEND_RATE_LIMIT};
 
 /// Messages as sent by background tasks.
 #[derive(Debug)]
@@ -66,7 +73,10 @@ pub enum DisputeSenderMessage {
 /// particular dispute. The `DisputeSender` keeps track of those tasks, informs them about new
 /// sessions/validator sets and cleans them up when they become obsolete.
 ///
-/// The unit of work for the  `DisputeSender` is a dispute, represented by `SendTask`s.
+/// The unit of work for the  `DisputeSender` is a dispute, represented by `SendTask`s. Requests
+/// are batched per authority though, in order to keep the number of requests manageable when
+/// there are many disputes going on: First sends, resends on session changes and retries of
+/// failed sends all get queued and are dispatched together, once the rate limit allows.
 pub struct DisputeSender<M> {
 	/// All heads we currently consider active.
 	active_heads: Vec<Hash>,
@@ -79,7 +89,10 @@ pub struct DisputeSender<M> {
 	/// All ongoing dispute sendings this subsystem is aware of.
 	///
 	/// Using an `IndexMap` so items can be iterated in the order of insertion.
-	disputes: IndexMap<CandidateHash, SendTask<M>>,
+	disputes: IndexMap<CandidateHash, SendTask>,
+
+	/// Requests waiting to be sent, batched per authority.
+	outbox: HashMap<AuthorityDiscoveryId, Vec<DisputeRequest>>,
 
 	/// Sender to be cloned for `SendTask`s.
 	tx: NestingSender<M, DisputeSenderMessage>,
@@ -87,9 +100,17 @@ pub struct DisputeSender<M> {
 	/// `Some` if we are waiting for a response `DisputeCoordinatorMessage::ActiveDisputes`.
 	waiting_for_active_disputes: Option<WaitForActiveDisputesState>,
 
-	/// Future for delaying too frequent creation of dispute sending tasks.
+	/// Future for delaying too frequent dispatching of queued requests.
 	rate_limit: RateLimit,
 
+	/// Authorities which don't support batched requests.
+	///
+	/// They will get single requests only.
+	legacy_recipients: HashSet<AuthorityDiscoveryId>,
+
+	/// Persisted confirmed deliveries.
+	delivery_db: DeliveryDb,
+
 	/// Metrics for reporting stats about sent requests.
 	metrics: Metrics,
 }
@@ -106,22 +127,36 @@ struct WaitForActiveDisputesState {
 #[overseer::contextbounds(DisputeDistribution, prefix = self::overseer)]
 impl<M: 'static + Send + Sync> DisputeSender<M> {
 	/// Create a new `DisputeSender` which can be used to start dispute sendings.
-	pub fn new(tx: NestingSender<M, DisputeSenderMessage>, metrics: Metrics) -> Self {
+	pub fn new(
+		tx: NestingSender<M, DisputeSenderMessage>,
+		db: Arc<dyn Database>,
+		config: Config,
+		metrics: Metrics,
+	) -> Self {
 		Self {
 			active_heads: Vec::new(),
 			active_sessions: HashMap::new(),
 			disputes: IndexMap::new(),
+			outbox: HashMap::new(),
 			tx,
 			waiting_for_active_disputes: None,
 			rate_limit: RateLimit::new(),
+			legacy_recipients: HashSet::new(),
+			delivery_db: DeliveryDb::new(db, config),
 			metrics,
 		}
 	}
 
+	/// Write any pending delivery confirmations to disk.
+	pub fn flush(&mut self) {
+		if self.delivery_db.is_dirty() {
+			self.delivery_db.flush(std::iter::empty());
+		}
+	}
+
 	/// Create a `SendTask` for a particular new dispute.
 	///
-	/// This function is rate-limited by `SEND_RATE_LIMIT`. It will block if called too frequently
-	/// in order to maintain the limit.
+	/// Requests are only queued, they will be sent in batches by `send_queued`.
 	pub async fn start_sender<Context>(
 		&mut self,
 		ctx: &mut Context,
@@ -136,18 +171,11 @@ impl<M: 'static + Send + Sync> DisputeSender<M> {
 				return Ok(())
 			},
 			Entry::Vacant(vacant) => {
-				self.rate_limit.limit("in start_sender", candidate_hash).await;
-
-				let send_task = SendTask::new(
-					ctx,
-					runtime,
-					&self.active_sessions,
-					NestingSender::new(self.tx.clone(), DisputeSenderMessage::TaskFinish),
-					req,
-					&self.metrics,
-				)
-				.await?;
-				vacant.insert(send_task);
+				let delivered = self.delivery_db.load(&candidate_hash);
+				let send_task = vacant.insert(SendTask::new(req, delivered));
+				let receivers =
+					send_task.prepare_refresh(ctx, runtime, &self.active_sessions).await?;
+				queue_requests(&mut self.outbox, send_task, receivers);
 			},
 		}
 		Ok(())
@@ -178,7 +206,12 @@ impl<M: 'static + Send + Sync> DisputeSender<M> {
 					},
 					Some(task) => task,
 				};
-				task.on_finished_send(&receiver, result);
+				if let TaskResult::BatchUnsupported = result {
+					self.legacy_recipients.insert(receiver.clone());
+				}
+				if task.on_finished_send(&receiver, result) {
+					self.delivery_db.note_delivered(candidate_hash, task.delivered());
+				}
 			},
 			DisputeSenderMessage::ActiveDisputesReady(result) => {
 				let state = self.waiting_for_active_disputes.take();
@@ -207,6 +240,9 @@ impl<M: 'static + Send + Sync> DisputeSender<M> {
 
 		let have_new_sessions = self.refresh_sessions(ctx, runtime).await?;
 
+		// Once per block is often enough for persisting confirmations:
+		self.flush();
+
 		// Not yet waiting for data, request an update:
 		match self.waiting_for_active_disputes.take() {
 			None => {
@@ -250,7 +286,7 @@ impl<M: 'static + Send + Sync> DisputeSender<M> {
 	/// - Get new authorities to send messages to.
 	/// - Get rid of obsolete tasks and disputes.
 	///
-	/// This function ensures the `SEND_RATE_LIMIT`, therefore it might block.
+	/// All resulting requests are queued, to be sent in batches by `send_queued`.
 	async fn handle_new_active_disputes<Context>(
 		&mut self,
 		ctx: &mut Context,
@@ -261,28 +297,73 @@ impl<M: 'static + Send + Sync> DisputeSender<M> {
 		let active_disputes: HashSet<_> = active_disputes.into_iter().map(|(_, c, _)| c).collect();
 
 		// Cleanup obsolete senders (retain keeps order of remaining elements):
+		let obsolete: Vec<_> = self
+			.disputes
+			.keys()
+			.filter(|candidate_hash| !active_disputes.contains(candidate_hash))
+			.copied()
+			.collect();
 		self.disputes
 			.retain(|candidate_hash, _| active_disputes.contains(candidate_hash));
+		self.delivery_db.flush(obsolete);
+		self.delivery_db.prune_once(&active_disputes);
 
-		// Iterates in order of insertion:
-		let mut should_rate_limit = true;
-		for (candidate_hash, dispute) in self.disputes.iter_mut() {
+		for dispute in self.disputes.values_mut() {
 			if have_new_sessions || dispute.has_failed_sends() {
-				if should_rate_limit {
-					self.rate_limit
-						.limit("while going through new sessions/failed sends", *candidate_hash)
-						.await;
-				}
-				let sends_happened = dispute
-					.refresh_sends(ctx, runtime, &self.active_sessions, &self.metrics)
-					.await?;
-				// Only rate limit if we actually sent something out _and_ it was not just because
-				// of errors on previous sends.
-				//
-				// Reasoning: It would not be acceptable to slow down the whole subsystem, just
-				// because of a few bad peers having problems. It is actually better to risk
-				// running into their rate limit in that case and accept a minor reputation change.
-				should_rate_limit = sends_happened && have_new_sessions;
+				let receivers =
+					dispute.prepare_refresh(ctx, runtime, &self.active_sessions).await?;
+				queue_requests(&mut self.outbox, dispute, receivers);
+			}
+		}
+		Ok(())
+	}
+
+	/// Wait until queued requests can be sent.
+	///
+	/// Resolves once there are queued requests and the rate limit allows sending them. Never
+	/// resolves, if nothing is queued.
+	pub async fn wait_for_send(&mut self) {
+		if self.outbox.is_empty() {
+			futures::future::pending::<()>().await;
+		}
+		self.rate_limit.ready().await;
+	}
+
+	/// Send all queued requests, batched per authority.
+	///
+	/// With batching, each authority receives at most one request per `MAX_DISPUTES_PER_BATCH`
+	/// disputes per call, so limiting the rate of calls to `SEND_RATE_LIMIT` is sufficient. This
+	/// function might block in order to maintain that limit.
+	pub async fn send_queued<Context>(&mut self, ctx: &mut Context) -> Result<()> {
+		if self.outbox.is_empty() {
+			return Ok(())
+		}
+		self.rate_limit.limit("while sending queued requests", None).await;
+
+		let mut outbox = std::mem::take(&mut self.outbox);
+		for (receiver, requests) in outbox.iter_mut() {
+			// Disputes or receivers might have become obsolete in the meantime:
+			requests.retain(|req| {
+				self.disputes
+					.get(&req.0.candidate_receipt.hash())
+					.map_or(false, |dispute| dispute.is_queued(receiver))
+			});
+			// Fill batches in order of priority:
+			requests.sort_by_key(|req| self.disputes.get_index_of(&req.0.candidate_receipt.hash()));
+		}
+		outbox.retain(|_, requests| !requests.is_empty());
+
+		let statuses = send_batched_requests(
+			ctx,
+			NestingSender::new(self.tx.clone(), DisputeSenderMessage::TaskFinish),
+			outbox,
+			&self.legacy_recipients,
+			&self.metrics,
+		)
+		.await?;
+		for (candidate_hash, receiver, status) in statuses {
+			if let Some(dispute) = self.disputes.get_mut(&candidate_hash) {
+				dispute.on_pending_send(receiver, status);
 			}
 		}
 		Ok(())
@@ -325,10 +406,15 @@ impl RateLimit {
 		Self { limit: Delay::new(SEND_RATE_LIMIT) }
 	}
 
+	/// Wait until ready, without preparing for the next call.
+	async fn ready(&mut self) {
+		(&mut self.limit).await
+	}
+
 	/// Wait until ready and prepare for next call.
 	///
 	/// String given as occasion and candidate hash are logged in case the rate limit hit.
-	async fn limit(&mut self, occasion: &'static str, candidate_hash: CandidateHash) {
+	async fn limit(&mut self, occasion: &'static str, candidate_hash: Option<CandidateHash>) {
 		// Wait for rate limit and add some logging:
 		let mut num_wakes: u32 = 0;
 		poll_fn(|cx| {
@@ -353,6 +439,18 @@ impl RateLimit {
 	}
 }
 
+/// Queue the request of the given dispute for all given receivers.
+fn queue_requests(
+	outbox: &mut HashMap<AuthorityDiscoveryId, Vec<DisputeRequest>>,
+	dispute: &mut SendTask,
+	receivers: Vec<AuthorityDiscoveryId>,
+) {
+	for receiver in receivers {
+		outbox.entry(receiver.clone()).or_default().push(dispute.request().clone());
+		dispute.on_pending_send(receiver, DeliveryStatus::Queued);
+	}
+}
+
 /// Retrieve the currently active sessions.
 ///
 /// List is all indices of all active sessions together with the head that was used for the query.
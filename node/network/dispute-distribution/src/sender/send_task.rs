@@ -14,15 +14,18 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::{HashMap, HashSet};
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
 
 use futures::{future::RemoteHandle, Future, FutureExt};
 
 use polkadot_node_network_protocol::{
 	request_response::{
 		outgoing::RequestError,
-		v1::{DisputeRequest, DisputeResponse},
-		OutgoingRequest, OutgoingResult, Recipient, Requests,
+		v1::{DisputeBatchRequest, DisputeBatchResponse, DisputeRequest, DisputeResponse},
+		OutgoingRequest, OutgoingResult, Recipient, Requests, MAX_DISPUTES_PER_BATCH,
 	},
 	IfDisconnected,
 };
@@ -31,6 +34,7 @@ use polkadot_node_subsystem_util::{metrics, nesting_sender::NestingSender, runti
 use polkadot_primitives::{
 	AuthorityDiscoveryId, CandidateHash, Hash, SessionIndex, ValidatorIndex,
 };
+use sc_network::{OutboundFailure, RequestFailure};
 
 use super::error::{FatalError, Result};
 
@@ -44,7 +48,7 @@ use crate::{
 /// Keeps track of all the validators that have to be reached for a dispute.
 ///
 /// The unit of work for a `SendTask` is an authority/validator.
-pub struct SendTask<M> {
+pub struct SendTask {
 	/// The request we are supposed to get out to all `parachain` validators of the dispute's session
 	/// and to all current authorities.
 	request: DisputeRequest,
@@ -56,15 +60,16 @@ pub struct SendTask<M> {
 
 	/// Whether we have any tasks failed since the last refresh.
 	has_failed_sends: bool,
-
-	/// Sender to be cloned for tasks.
-	tx: NestingSender<M, TaskFinish>,
 }
 
 /// Status of a particular vote/statement delivery to a particular validator.
-enum DeliveryStatus {
+pub enum DeliveryStatus {
+	/// Request is waiting to be sent with the next batch.
+	Queued,
 	/// Request is still in flight.
-	Pending(RemoteHandle<()>),
+	///
+	/// The handle is shared by all disputes sent in the same batch request.
+	Pending(Arc<RemoteHandle<()>>),
 	/// Succeeded - no need to send request to this peer anymore.
 	Succeeded,
 }
@@ -87,37 +92,52 @@ pub enum TaskResult {
 	/// Task was not able to get the request out to its peer.
 	///
 	/// It should be retried in that case.
-	Failed(RequestError),
+	Failed(Arc<RequestError>),
+	/// The dispute was part of a batch, but the peer did not confirm importing it.
+	///
+	/// It should be retried in that case.
+	Unconfirmed,
+	/// The peer does not support batch requests.
+	///
+	/// It should be retried with single requests.
+	BatchUnsupported,
 }
 
 impl TaskResult {
 	pub fn as_metrics_label(&self) -> &'static str {
 		match self {
 			Self::Succeeded => SUCCEEDED,
-			Self::Failed(_) => FAILED,
+			Self::Failed(_) | Self::Unconfirmed | Self::BatchUnsupported => FAILED,
 		}
 	}
 }
 
 #[overseer::contextbounds(DisputeDistribution, prefix = self::overseer)]
-impl<M: 'static + Send + Sync> SendTask<M> {
-	/// Initiates sending a dispute message to peers.
+impl SendTask {
+	/// Create a task for sending a dispute message to peers.
 	///
-	/// Creation of new `SendTask`s is subject to rate limiting. As each `SendTask` will trigger
-	/// sending a message to each validator, hence for employing a per-peer rate limit, we need to
-	/// limit the construction of new `SendTask`s.
-	pub async fn new<Context>(
-		ctx: &mut Context,
-		runtime: &mut RuntimeInfo,
-		active_sessions: &HashMap<SessionIndex, Hash>,
-		tx: NestingSender<M, TaskFinish>,
-		request: DisputeRequest,
-		metrics: &Metrics,
-	) -> Result<Self> {
-		let mut send_task =
-			Self { request, deliveries: HashMap::new(), has_failed_sends: false, tx };
-		send_task.refresh_sends(ctx, runtime, active_sessions, metrics).await?;
-		Ok(send_task)
+	/// Nothing is sent yet, the caller is expected to call `prepare_refresh` and queue requests
+	/// for the returned authorities.
+	///
+	/// Authorities in `delivered` confirmed reception of the dispute in a previous run already and
+	/// won't be sent to again.
+	pub fn new(request: DisputeRequest, delivered: HashSet<AuthorityDiscoveryId>) -> Self {
+		let deliveries = delivered.into_iter().map(|a| (a, DeliveryStatus::Succeeded)).collect();
+		Self { request, deliveries, has_failed_sends: false }
+	}
+
+	/// The request this task is sending.
+	pub fn request(&self) -> &DisputeRequest {
+		&self.request
+	}
+
+	/// All authorities which confirmed reception of our request.
+	pub fn delivered(&self) -> HashSet<AuthorityDiscoveryId> {
+		self.deliveries
+			.iter()
+			.filter(|(_, status)| matches!(status, DeliveryStatus::Succeeded))
+			.map(|(authority, _)| authority.clone())
+			.collect()
 	}
 
 	/// Make sure we are sending to all relevant authorities.
@@ -125,18 +145,15 @@ impl<M: 'static + Send + Sync> SendTask<M> {
 	/// This function is called at construction and should also be called whenever a session change
 	/// happens and on a regular basis to ensure we are retrying failed attempts.
 	///
-	/// This might resend to validators and is thus subject to any rate limiting we might want.
-	/// Calls to this function for different instances should be rate limited according to
-	/// `SEND_RATE_LIMIT`.
-	///
-	/// Returns: `True` if this call resulted in new requests.
-	pub async fn refresh_sends<Context>(
+	/// Returns all authorities we need to send our request to. The caller is expected to call
+	/// `on_pending_send` for each of them, once requests have been queued or dispatched. Sending
+	/// is subject to rate limiting according to `SEND_RATE_LIMIT`.
+	pub async fn prepare_refresh<Context>(
 		&mut self,
 		ctx: &mut Context,
 		runtime: &mut RuntimeInfo,
 		active_sessions: &HashMap<SessionIndex, Hash>,
-		metrics: &Metrics,
-	) -> Result<bool> {
+	) -> Result<Vec<AuthorityDiscoveryId>> {
 		let new_authorities = self.get_relevant_validators(ctx, runtime, active_sessions).await?;
 
 		// Note this will also contain all authorities for which sending failed previously:
@@ -162,20 +179,20 @@ impl<M: 'static + Send + Sync> SendTask<M> {
 			already_running_deliveries = ?self.deliveries.len(),
 			"Starting new send requests for authorities."
 		);
-		let new_statuses =
-			send_requests(ctx, self.tx.clone(), add_authorities, self.request.clone(), metrics)
-				.await?;
-
-		let was_empty = new_statuses.is_empty();
-		gum::trace!(
-			target: LOG_TARGET,
-			sent_requests = ?new_statuses.len(),
-			"Requests dispatched."
-		);
 
 		self.has_failed_sends = false;
-		self.deliveries.extend(new_statuses.into_iter());
-		Ok(!was_empty)
+		Ok(add_authorities)
+	}
+
+	/// A request to the given authority has been queued or dispatched by the caller of
+	/// `prepare_refresh`.
+	pub fn on_pending_send(&mut self, authority: AuthorityDiscoveryId, status: DeliveryStatus) {
+		self.deliveries.insert(authority, status);
+	}
+
+	/// Whether a request to the given authority is waiting to be sent.
+	pub fn is_queued(&self, authority: &AuthorityDiscoveryId) -> bool {
+		matches!(self.deliveries.get(authority), Some(DeliveryStatus::Queued))
 	}
 
 	/// Whether any sends have failed since the last refresh.
@@ -186,20 +203,27 @@ impl<M: 'static + Send + Sync> SendTask<M> {
 	/// Handle a finished response waiting task.
 	///
 	/// Called by `DisputeSender` upon reception of the corresponding message from our spawned `wait_response_task`.
-	pub fn on_finished_send(&mut self, authority: &AuthorityDiscoveryId, result: TaskResult) {
+	///
+	/// Returns: `True` if the authority newly confirmed reception of our request.
+	pub fn on_finished_send(
+		&mut self,
+		authority: &AuthorityDiscoveryId,
+		result: TaskResult,
+	) -> bool {
 		match result {
-			TaskResult::Failed(err) => {
+			TaskResult::Failed(_) | TaskResult::Unconfirmed | TaskResult::BatchUnsupported => {
 				gum::trace!(
 					target: LOG_TARGET,
 					?authority,
 					candidate_hash = %self.request.0.candidate_receipt.hash(),
-					%err,
+					?result,
 					"Error sending dispute statements to node."
 				);
 
 				self.has_failed_sends = true;
 				// Remove state, so we know what to try again:
 				self.deliveries.remove(authority);
+				false
 			},
 			TaskResult::Succeeded => {
 				let status = match self.deliveries.get_mut(&authority) {
@@ -213,12 +237,14 @@ impl<M: 'static + Send + Sync> SendTask<M> {
 							?result,
 							"Received `FromSendingTask::Finished` for non existing task."
 						);
-						return
+						return false
 					},
 					Some(status) => status,
 				};
 				// We are done here:
+				let newly_delivered = !matches!(status, DeliveryStatus::Succeeded);
 				*status = DeliveryStatus::Succeeded;
+				newly_delivered
 			},
 		}
 	}
@@ -268,41 +294,68 @@ impl<M: 'static + Send + Sync> SendTask<M> {
 	}
 }
 
-/// Start sending of the given message to all given authorities.
+/// Start sending the given disputes, batched per authority.
 ///
-/// And spawn tasks for handling the response.
+/// Disputes for the same authority are sent in chunks of at most `MAX_DISPUTES_PER_BATCH` as a
+/// single `DisputeBatchRequest`. Single disputes and disputes to authorities in `legacy` (known to
+/// not support batching) are sent as plain `DisputeRequest`s.
+///
+/// Returns the status of each delivery, to be passed on to the corresponding `SendTask`s.
 #[overseer::contextbounds(DisputeDistribution, prefix = self::overseer)]
-async fn send_requests<Context, M: 'static + Send + Sync>(
+pub async fn send_batched_requests<Context, M: 'static + Send + Sync>(
 	ctx: &mut Context,
 	tx: NestingSender<M, TaskFinish>,
-	receivers: Vec<AuthorityDiscoveryId>,
-	req: DisputeRequest,
+	outbox: HashMap<AuthorityDiscoveryId, Vec<DisputeRequest>>,
+	legacy: &HashSet<AuthorityDiscoveryId>,
 	metrics: &Metrics,
-) -> Result<HashMap<AuthorityDiscoveryId, DeliveryStatus>> {
-	let mut statuses = HashMap::with_capacity(receivers.len());
-	let mut reqs = Vec::with_capacity(receivers.len());
-
-	for receiver in receivers {
-		let (outgoing, pending_response) =
-			OutgoingRequest::new(Recipient::Authority(receiver.clone()), req.clone());
-
-		reqs.push(Requests::DisputeSendingV1(outgoing));
-
-		let fut = wait_response_task(
-			pending_response,
-			req.0.candidate_receipt.hash(),
-			receiver.clone(),
-			tx.clone(),
-			metrics.time_dispute_request(),
-		);
-
-		let (remote, remote_handle) = fut.remote_handle();
-		ctx.spawn("dispute-sender", remote.boxed()).map_err(FatalError::SpawnTask)?;
-		statuses.insert(receiver, DeliveryStatus::Pending(remote_handle));
+) -> Result<Vec<(CandidateHash, AuthorityDiscoveryId, DeliveryStatus)>> {
+	let mut statuses = Vec::new();
+	let mut reqs = Vec::with_capacity(outbox.len());
+
+	for (receiver, disputes) in outbox {
+		let chunk_size = if legacy.contains(&receiver) { 1 } else { MAX_DISPUTES_PER_BATCH };
+		for chunk in disputes.chunks(chunk_size) {
+			let candidates: Vec<_> =
+				chunk.iter().map(|req| req.0.candidate_receipt.hash()).collect();
+			let recipient = Recipient::Authority(receiver.clone());
+			let remote = if let [req] = chunk {
+				let (outgoing, pending_response) = OutgoingRequest::new(recipient, req.clone());
+				reqs.push(Requests::DisputeSendingV1(outgoing));
+				wait_response_task(
+					pending_response,
+					candidates[0],
+					receiver.clone(),
+					tx.clone(),
+					metrics.time_dispute_request(),
+				)
+				.boxed()
+			} else {
+				let batch = DisputeBatchRequest(chunk.iter().map(|req| req.0.clone()).collect());
+				let (outgoing, pending_response) = OutgoingRequest::new(recipient, batch);
+				reqs.push(Requests::DisputeSendingBatchV1(outgoing));
+				wait_batch_response_task(
+					pending_response,
+					candidates.clone(),
+					receiver.clone(),
+					tx.clone(),
+					metrics.time_dispute_request(),
+				)
+				.boxed()
+			};
+
+			let (remote, remote_handle) = remote.remote_handle();
+			ctx.spawn("dispute-sender", remote.boxed()).map_err(FatalError::SpawnTask)?;
+			let remote_handle = Arc::new(remote_handle);
+			statuses.extend(candidates.into_iter().map(|candidate_hash| {
+				(candidate_hash, receiver.clone(), DeliveryStatus::Pending(remote_handle.clone()))
+			}));
+		}
 	}
 
-	let msg = NetworkBridgeTxMessage::SendRequests(reqs, IfDisconnected::ImmediateError);
-	ctx.send_message(msg).await;
+	if !reqs.is_empty() {
+		let msg = NetworkBridgeTxMessage::SendRequests(reqs, IfDisconnected::ImmediateError);
+		ctx.send_message(msg).await;
+	}
 	Ok(statuses)
 }
 
@@ -316,7 +369,8 @@ async fn wait_response_task<M: 'static + Send + Sync>(
 ) {
 	let result = pending_response.await;
 	let msg = match result {
-		Err(err) => TaskFinish { candidate_hash, receiver, result: TaskResult::Failed(err) },
+		Err(err) =>
+			TaskFinish { candidate_hash, receiver, result: TaskResult::Failed(Arc::new(err)) },
 		Ok(DisputeResponse::Confirmed) =>
 			TaskFinish { candidate_hash, receiver, result: TaskResult::Succeeded },
 	};
@@ -328,3 +382,40 @@ async fn wait_response_task<M: 'static + Send + Sync>(
 		);
 	}
 }
+
+/// Future to be spawned in a task for awaiting the response to a batch request.
+///
+/// Reports a `TaskFinish` for each dispute in the batch.
+async fn wait_batch_response_task<M: 'static + Send + Sync>(
+	pending_response: impl Future<Output = OutgoingResult<DisputeBatchResponse>>,
+	candidates: Vec<CandidateHash>,
+	receiver: AuthorityDiscoveryId,
+	mut tx: NestingSender<M, TaskFinish>,
+	_timer: Option<metrics::prometheus::prometheus::HistogramTimer>,
+) {
+	let result = match pending_response.await {
+		Err(RequestError::NetworkError(RequestFailure::Network(
+			OutboundFailure::UnsupportedProtocols,
+		))) => Err(None),
+		Err(err) => Err(Some(Arc::new(err))),
+		Ok(DisputeBatchResponse::Confirmed(confirmed)) =>
+			Ok(confirmed.into_iter().collect::<HashSet<_>>()),
+	};
+	for candidate_hash in candidates {
+		let result = match &result {
+			Ok(confirmed) if confirmed.contains(&candidate_hash) => TaskResult::Succeeded,
+			Ok(_) => TaskResult::Unconfirmed,
+			Err(Some(err)) => TaskResult::Failed(err.clone()),
+			Err(None) => TaskResult::BatchUnsupported,
+		};
+		let msg = TaskFinish { candidate_hash, receiver: receiver.clone(), result };
+		if let Err(err) = tx.send_message(msg).await {
+			gum::debug!(
+				target: LOG_TARGET,
+				%err,
+				"Failed to notify subsystem about dispute sending result."
+			);
+			return
+		}
+	}
+}
@@ -36,13 +36,19 @@ use parity_scale_codec::{Decode, Encode};
 use sc_network::config::RequestResponseConfig;
 
 use polkadot_node_network_protocol::{
-	request_response::{v1::DisputeRequest, IncomingRequest, ReqProtocolNames},
+	request_response::{
+		v1::{DisputeBatchRequest, DisputeRequest},
+		IncomingRequest, ReqProtocolNames,
+	},
 	PeerId,
 };
 use sp_keyring::Sr25519Keyring;
 
 use polkadot_node_network_protocol::{
-	request_response::{v1::DisputeResponse, Recipient, Requests},
+	request_response::{
+		v1::{DisputeBatchResponse, DisputeResponse},
+		Recipient, Requests,
+	},
 	IfDisconnected,
 };
 use polkadot_node_primitives::DisputeStatus;
@@ -56,6 +62,7 @@ use polkadot_node_subsystem::{
 use polkadot_node_subsystem_test_helpers::{
 	mock::make_ferdie_keystore, subsystem_test_harness, TestSubsystemContextHandle,
 };
+use polkadot_node_subsystem_util::database::{kvdb_impl::DbAdapter, Database};
 use polkadot_primitives::{
 	AuthorityDiscoveryId, CandidateHash, CandidateReceipt, Hash, SessionIndex, SessionInfo,
 };
@@ -68,7 +75,7 @@ use self::mock::{
 use crate::{
 	receiver::BATCH_COLLECTING_INTERVAL,
 	tests::mock::{BOB_INDEX, CHARLIE_INDEX},
-	DisputeDistributionSubsystem, Metrics, LOG_TARGET, SEND_RATE_LIMIT,
+	Config, DisputeDistributionSubsystem, Metrics, LOG_TARGET, SEND_RATE_LIMIT,
 };
 
 /// Useful mock providers.
@@ -643,6 +650,435 @@ fn dispute_retries_and_works_across_session_boundaries() {
 	test_harness(test);
 }
 
+#[test]
+fn received_batch_request_triggers_import() {
+	let test = |mut handle: TestSubsystemContextHandle<DisputeDistributionMessage>,
+	            req_cfg: RequestResponseConfig,
+	            mut batch_req_cfg: RequestResponseConfig| async move {
+		// Keep the request channel open:
+		let _req_cfg = req_cfg;
+		let batch_req_tx = batch_req_cfg.inbound_queue.as_mut().unwrap();
+		let _ = handle_subsystem_startup(&mut handle, None).await;
+
+		let candidate_a = make_candidate_receipt(Hash::random());
+		let candidate_b = make_candidate_receipt(Hash::random());
+		let message_a = make_dispute_message(candidate_a.clone(), ALICE_INDEX, FERDIE_INDEX).await;
+		let message_b = make_dispute_message(candidate_b.clone(), ALICE_INDEX, FERDIE_INDEX).await;
+		let batch = DisputeBatchRequest(vec![
+			DisputeRequest::from(message_a).0,
+			DisputeRequest::from(message_b.clone()).0,
+			// Duplicates are ignored:
+			DisputeRequest::from(message_b).0,
+		]);
+
+		let peer = MOCK_AUTHORITY_DISCOVERY.get_peer_id_by_authority(Sr25519Keyring::Alice);
+		let (pending_response, rx_response) = oneshot::channel();
+		let req =
+			sc_network::config::IncomingRequest { peer, payload: batch.encode(), pending_response };
+		batch_req_tx.feed(req).await.unwrap();
+
+		// Session info is only needed once for the whole batch:
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::SessionInfo(_, tx),
+			)) => {
+				tx.send(Ok(Some(MOCK_SESSION_INFO.clone())))
+					.expect("Receiver should stay alive.");
+			}
+		);
+
+		let mut imported = HashSet::new();
+		for _ in 0..2 {
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::DisputeCoordinator(
+					DisputeCoordinatorMessage::ImportStatements {
+						candidate_receipt,
+						session,
+						statements,
+						pending_confirmation: Some(pending_confirmation),
+					}
+				) => {
+					assert_eq!(session, MOCK_SESSION_INDEX);
+					assert_eq!(statements.len(), 2);
+					imported.insert(candidate_receipt.hash());
+					pending_confirmation.send(ImportStatementsResult::ValidImport).unwrap();
+				}
+			);
+		}
+		let expected: HashSet<_> =
+			vec![candidate_a.hash(), candidate_b.hash()].into_iter().collect();
+		assert_eq!(imported, expected);
+
+		assert_matches!(
+			rx_response.await,
+			Ok(resp) => {
+				let result = resp.result.unwrap();
+				let DisputeBatchResponse::Confirmed(confirmed) =
+					<DisputeBatchResponse as Decode>::decode(&mut result.as_slice()).unwrap();
+				assert_eq!(confirmed.into_iter().collect::<HashSet<_>>(), expected);
+				assert!(resp.reputation_changes.is_empty());
+			}
+		);
+
+		conclude(&mut handle).await;
+	};
+	test_harness_with_batches(test);
+}
+
+#[test]
+fn batch_requests_are_charged_per_dispute() {
+	let test = |mut handle: TestSubsystemContextHandle<DisputeDistributionMessage>,
+	            mut req_cfg: RequestResponseConfig,
+	            mut batch_req_cfg: RequestResponseConfig| async move {
+		let req_tx = req_cfg.inbound_queue.as_mut().unwrap();
+		let batch_req_tx = batch_req_cfg.inbound_queue.as_mut().unwrap();
+		let _ = handle_subsystem_startup(&mut handle, None).await;
+
+		let candidate_a = make_candidate_receipt(Hash::random());
+		let candidate_b = make_candidate_receipt(Hash::random());
+		let message_a = make_dispute_message(candidate_a.clone(), ALICE_INDEX, FERDIE_INDEX).await;
+		let message_b = make_dispute_message(candidate_b.clone(), ALICE_INDEX, FERDIE_INDEX).await;
+		let batch = DisputeBatchRequest(vec![
+			DisputeRequest::from(message_a).0,
+			DisputeRequest::from(message_b).0,
+		]);
+
+		let peer = MOCK_AUTHORITY_DISCOVERY.get_peer_id_by_authority(Sr25519Keyring::Bob);
+		let rx_response = send_network_dispute_batch_request(batch_req_tx, peer, batch).await;
+
+		// The batch exhausted the queue capacity of `Bob` already:
+		let candidate_c = make_candidate_receipt(Hash::random());
+		let message_c = make_dispute_message(candidate_c, ALICE_INDEX, FERDIE_INDEX).await;
+		let rx_response_flood = send_network_dispute_request(req_tx, peer, message_c.into()).await;
+		assert_matches!(
+			rx_response_flood.await,
+			Ok(resp) => {
+				assert!(resp.result.is_err());
+				assert_eq!(resp.reputation_changes.len(), 1);
+			}
+		);
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::SessionInfo(_, tx),
+			)) => {
+				tx.send(Ok(Some(MOCK_SESSION_INFO.clone())))
+					.expect("Receiver should stay alive.");
+			}
+		);
+		for _ in 0..2 {
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::DisputeCoordinator(
+					DisputeCoordinatorMessage::ImportStatements {
+						pending_confirmation: Some(pending_confirmation),
+						..
+					}
+				) => {
+					pending_confirmation.send(ImportStatementsResult::ValidImport).unwrap();
+				}
+			);
+		}
+
+		let expected: HashSet<_> =
+			vec![candidate_a.hash(), candidate_b.hash()].into_iter().collect();
+		assert_matches!(
+			rx_response.await,
+			Ok(resp) => {
+				let DisputeBatchResponse::Confirmed(confirmed) =
+					<DisputeBatchResponse as Decode>::decode(&mut resp.result.unwrap().as_slice())
+						.unwrap();
+				assert_eq!(confirmed.into_iter().collect::<HashSet<_>>(), expected);
+			}
+		);
+
+		conclude(&mut handle).await;
+	};
+	test_harness_with_batches(test);
+}
+
+#[test]
+fn redundant_disputes_in_batch_requests_get_confirmed() {
+	let test = |mut handle: TestSubsystemContextHandle<DisputeDistributionMessage>,
+	            mut req_cfg: RequestResponseConfig,
+	            mut batch_req_cfg: RequestResponseConfig| async move {
+		let req_tx = req_cfg.inbound_queue.as_mut().unwrap();
+		let batch_req_tx = batch_req_cfg.inbound_queue.as_mut().unwrap();
+		let _ = handle_subsystem_startup(&mut handle, None).await;
+
+		let candidate = make_candidate_receipt(Hash::random());
+		let message = make_dispute_message(candidate.clone(), ALICE_INDEX, FERDIE_INDEX).await;
+
+		// Creates the batch for `candidate`:
+		nested_network_dispute_request(
+			&mut handle,
+			req_tx,
+			MOCK_AUTHORITY_DISCOVERY.get_peer_id_by_authority(Sr25519Keyring::Alice),
+			message.into(),
+			ImportStatementsResult::ValidImport,
+			true,
+			move |_handle, _req_tx, _message| ready(()),
+		)
+		.await;
+
+		let peer = MOCK_AUTHORITY_DISCOVERY.get_peer_id_by_authority(Sr25519Keyring::Bob);
+		let message = make_dispute_message(candidate.clone(), BOB_INDEX, FERDIE_INDEX).await;
+
+		// Votes get added to the batch:
+		let batch = DisputeBatchRequest(vec![DisputeRequest::from(message.clone()).0]);
+		let rx_response_batched =
+			send_network_dispute_batch_request(batch_req_tx, peer, batch).await;
+
+		// Votes are in the batch already, thus confirmed right away:
+		let batch = DisputeBatchRequest(vec![
+			DisputeRequest::from(message.clone()).0,
+			DisputeRequest::from(message).0,
+		]);
+		let rx_response_redundant =
+			send_network_dispute_batch_request(batch_req_tx, peer, batch).await;
+		assert_matches!(
+			rx_response_redundant.await,
+			Ok(resp) => {
+				let DisputeBatchResponse::Confirmed(confirmed) =
+					<DisputeBatchResponse as Decode>::decode(&mut resp.result.unwrap().as_slice())
+						.unwrap();
+				assert_eq!(confirmed, vec![candidate.hash()]);
+				assert!(resp.reputation_changes.is_empty());
+			}
+		);
+
+		gum::trace!("Need to wait 2 batch intervals:");
+		Delay::new(BATCH_COLLECTING_INTERVAL).await;
+		Delay::new(BATCH_COLLECTING_INTERVAL).await;
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::DisputeCoordinator(
+				DisputeCoordinatorMessage::ImportStatements {
+					statements,
+					pending_confirmation: Some(pending_confirmation),
+					..
+				}
+			) => {
+				assert_eq!(statements.len(), 2);
+				pending_confirmation.send(ImportStatementsResult::ValidImport).unwrap();
+			}
+		);
+		assert_matches!(
+			rx_response_batched.await,
+			Ok(resp) => {
+				let DisputeBatchResponse::Confirmed(confirmed) =
+					<DisputeBatchResponse as Decode>::decode(&mut resp.result.unwrap().as_slice())
+						.unwrap();
+				assert_eq!(confirmed, vec![candidate.hash()]);
+			}
+		);
+
+		conclude(&mut handle).await;
+	};
+	test_harness_with_batches(test);
+}
+
+#[test]
+fn dispute_retries_are_batched_per_recipient() {
+	let test = |mut handle: TestSubsystemContextHandle<DisputeDistributionMessage>, _| async move {
+		let old_head = handle_subsystem_startup(&mut handle, None).await;
+
+		let expected_receivers: HashSet<_> = MOCK_SESSION_INFO
+			.discovery_keys
+			.clone()
+			.into_iter()
+			.filter(|a| *a != *FERDIE_DISCOVERY_KEY)
+			.collect();
+
+		let mut candidates = Vec::new();
+		for needs_session_info in [true, false] {
+			let candidate = make_candidate_receipt(Hash::random());
+			let message = make_dispute_message(candidate.clone(), ALICE_INDEX, FERDIE_INDEX).await;
+			handle
+				.send(FromOrchestra::Communication {
+					msg: DisputeDistributionMessage::SendDispute(message),
+				})
+				.await;
+			if needs_session_info {
+				assert_matches!(
+					handle.recv().await,
+					AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_,
+						RuntimeApiRequest::SessionInfo(_, tx),
+					)) => {
+						tx.send(Ok(Some(MOCK_SESSION_INFO.clone())))
+							.expect("Receiver should stay alive.");
+					}
+				);
+			}
+			// Requests don't get confirmed:
+			check_sent_requests(&mut handle, expected_receivers.clone(), false).await;
+			candidates.push(candidate.hash());
+		}
+
+		// Give tasks a chance to finish:
+		Delay::new(Duration::from_millis(20)).await;
+
+		// Trigger retry:
+		activate_leaf(
+			&mut handle,
+			Hash::random(),
+			Some(old_head),
+			MOCK_SESSION_INDEX,
+			None,
+			candidates
+				.iter()
+				.map(|c| (MOCK_SESSION_INDEX, *c, DisputeStatus::Active))
+				.collect(),
+		)
+		.await;
+
+		// A single batch request per recipient:
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::NetworkBridgeTx(
+				NetworkBridgeTxMessage::SendRequests(reqs, IfDisconnected::ImmediateError)
+			) => {
+				let mut receivers = HashSet::new();
+				for req in reqs {
+					let req = assert_matches!(req, Requests::DisputeSendingBatchV1(req) => req);
+					assert!(receivers.insert(req.peer.clone()), "No duplicates are expected.");
+					let sent: Vec<_> =
+						req.payload.0.iter().map(|d| d.candidate_receipt.hash()).collect();
+					// Order of priority is preserved:
+					assert_eq!(sent, candidates);
+					req.pending_response
+						.send(Ok(DisputeBatchResponse::Confirmed(sent).encode()))
+						.expect("Subsystem should be listening for a response.");
+				}
+				let expected: HashSet<_> =
+					expected_receivers.into_iter().map(Recipient::Authority).collect();
+				assert_eq!(receivers, expected);
+			}
+		);
+
+		conclude(&mut handle).await;
+	};
+	test_harness(test);
+}
+
+#[test]
+fn new_disputes_are_batched_per_recipient() {
+	let test = |mut handle: TestSubsystemContextHandle<DisputeDistributionMessage>, _| async move {
+		let _ = handle_subsystem_startup(&mut handle, None).await;
+
+		let expected_receivers: HashSet<_> = MOCK_SESSION_INFO
+			.discovery_keys
+			.clone()
+			.into_iter()
+			.filter(|a| *a != *FERDIE_DISCOVERY_KEY)
+			.collect();
+
+		// First send is not rate limited and goes out right away:
+		send_dispute(&mut handle, make_candidate_receipt(Hash::random()), true).await;
+
+		// Disputes arriving while the rate limit is hit, get sent together:
+		let mut candidates = Vec::new();
+		for _ in 0..2 {
+			let candidate = make_candidate_receipt(Hash::random());
+			let message = make_dispute_message(candidate.clone(), ALICE_INDEX, FERDIE_INDEX).await;
+			handle
+				.send(FromOrchestra::Communication {
+					msg: DisputeDistributionMessage::SendDispute(message),
+				})
+				.await;
+			candidates.push(candidate.hash());
+		}
+
+		// A single batch request per recipient:
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::NetworkBridgeTx(
+				NetworkBridgeTxMessage::SendRequests(reqs, IfDisconnected::ImmediateError)
+			) => {
+				let mut receivers = HashSet::new();
+				for req in reqs {
+					let req = assert_matches!(req, Requests::DisputeSendingBatchV1(req) => req);
+					assert!(receivers.insert(req.peer.clone()), "No duplicates are expected.");
+					let sent: Vec<_> =
+						req.payload.0.iter().map(|d| d.candidate_receipt.hash()).collect();
+					// Order of priority is preserved:
+					assert_eq!(sent, candidates);
+					req.pending_response
+						.send(Ok(DisputeBatchResponse::Confirmed(sent).encode()))
+						.expect("Subsystem should be listening for a response.");
+				}
+				let expected: HashSet<_> =
+					expected_receivers.into_iter().map(Recipient::Authority).collect();
+				assert_eq!(receivers, expected);
+			}
+		);
+
+		conclude(&mut handle).await;
+	};
+	test_harness(test);
+}
+
+#[test]
+fn confirmed_deliveries_survive_restart() {
+	let db: Arc<dyn Database> = Arc::new(DbAdapter::new(kvdb_memorydb::create(1), &[0]));
+	let candidate = make_candidate_receipt(Hash::random());
+
+	let test = {
+		let candidate = candidate.clone();
+		|mut handle: TestSubsystemContextHandle<DisputeDistributionMessage>, _, _| async move {
+			let _ = handle_subsystem_startup(&mut handle, None).await;
+			// All recipients confirm:
+			send_dispute(&mut handle, candidate, true).await;
+			// Give tasks a chance to finish:
+			Delay::new(Duration::from_millis(20)).await;
+			conclude(&mut handle).await;
+		}
+	};
+	test_harness_inner(db.clone(), test);
+
+	let test = |mut handle: TestSubsystemContextHandle<DisputeDistributionMessage>, _, _| async move {
+		// Dispute is still active:
+		activate_leaf(
+			&mut handle,
+			Hash::random(),
+			None,
+			MOCK_SESSION_INDEX,
+			None,
+			vec![(MOCK_SESSION_INDEX, candidate.hash(), DisputeStatus::Active)],
+		)
+		.await;
+
+		let message = make_dispute_message(candidate, ALICE_INDEX, FERDIE_INDEX).await;
+		handle
+			.send(FromOrchestra::Communication {
+				msg: DisputeDistributionMessage::SendDispute(message),
+			})
+			.await;
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::SessionInfo(_, tx),
+			)) => {
+				tx.send(Ok(Some(MOCK_SESSION_INFO.clone())))
+					.expect("Receiver should stay alive.");
+			}
+		);
+
+		// Nothing to send - all recipients confirmed already:
+		Delay::new(Duration::from_millis(20)).await;
+		conclude(&mut handle).await;
+	};
+	test_harness_inner(db, test);
+}
+
 async fn send_network_dispute_request(
 	req_tx: &mut mpsc::Sender<sc_network::config::IncomingRequest>,
 	peer: PeerId,
@@ -655,6 +1091,18 @@ async fn send_network_dispute_request(
 	rx_response
 }
 
+async fn send_network_dispute_batch_request(
+	batch_req_tx: &mut mpsc::Sender<sc_network::config::IncomingRequest>,
+	peer: PeerId,
+	batch: DisputeBatchRequest,
+) -> oneshot::Receiver<sc_network::config::OutgoingResponse> {
+	let (pending_response, rx_response) = oneshot::channel();
+	let req =
+		sc_network::config::IncomingRequest { peer, payload: batch.encode(), pending_response };
+	batch_req_tx.feed(req).await.unwrap();
+	rx_response
+}
+
 /// Send request and handle its reactions.
 ///
 /// Passed in function will be called while votes are still being imported.
@@ -895,6 +1343,37 @@ where
 		RequestResponseConfig,
 	) -> Fut,
 	Fut: Future<Output = ()>,
+{
+	test_harness_with_batches(|handle, req_cfg, batch_req_cfg| async move {
+		// Keep the batch request channel open:
+		let _batch_req_cfg = batch_req_cfg;
+		test(handle, req_cfg).await
+	})
+}
+
+/// Like `test_harness`, but also provides the config for batch requests.
+fn test_harness_with_batches<TestFn, Fut>(test: TestFn)
+where
+	TestFn: FnOnce(
+		TestSubsystemContextHandle<DisputeDistributionMessage>,
+		RequestResponseConfig,
+		RequestResponseConfig,
+	) -> Fut,
+	Fut: Future<Output = ()>,
+{
+	let db = Arc::new(DbAdapter::new(kvdb_memorydb::create(1), &[0]));
+	test_harness_inner(db, test)
+}
+
+/// Launch subsystem on the given database.
+fn test_harness_inner<TestFn, Fut>(db: Arc<dyn Database>, test: TestFn)
+where
+	TestFn: FnOnce(
+		TestSubsystemContextHandle<DisputeDistributionMessage>,
+		RequestResponseConfig,
+		RequestResponseConfig,
+	) -> Fut,
+	Fut: Future<Output = ()>,
 {
 	sp_tracing::try_init_simple();
 	let keystore = make_ferdie_keystore();
@@ -902,10 +1381,15 @@ where
 	let genesis_hash = Hash::repeat_byte(0xff);
 	let req_protocol_names = ReqProtocolNames::new(&genesis_hash, None);
 	let (req_receiver, req_cfg) = IncomingRequest::get_config_receiver(&req_protocol_names);
+	let (batch_req_receiver, batch_req_cfg) =
+		IncomingRequest::get_config_receiver(&req_protocol_names);
 	let subsystem = DisputeDistributionSubsystem::new(
 		keystore,
 		req_receiver,
+		batch_req_receiver,
 		MOCK_AUTHORITY_DISCOVERY.clone(),
+		db,
+		Config { col_data: 0 },
 		Metrics::new_dummy(),
 	);
 
@@ -921,5 +1405,5 @@ where
 			},
 		}
 	};
-	subsystem_test_harness(|handle| test(handle, req_cfg), subsystem);
+	subsystem_test_harness(|handle| test(handle, req_cfg, batch_req_cfg), subsystem);
 }
@@ -23,6 +23,9 @@
 //! to be an order of sqrt of the validators. Our neighbors
 //! in this graph will be forwarded to the network bridge with
 //! the `NetworkBridgeRxMessage::NewGossipTopology` message.
+//!
+//! It also keeps track of our connectivity to the authorities of the current session, which can
+//! be queried via `GossipSupportMessage::GetConnectivityReport` for diagnostics.
 
 use std::{
 	collections::{HashMap, HashSet},
@@ -40,13 +43,16 @@ use sp_application_crypto::{AppKey, ByteArray};
 use sp_keystore::{CryptoStore, SyncCryptoStorePtr};
 
 use polkadot_node_network_protocol::{
-	authority_discovery::AuthorityDiscovery, peer_set::PeerSet, GossipSupportNetworkMessage,
-	PeerId, Versioned,
+	authority_discovery::AuthorityDiscovery,
+	grid_topology::{SessionGridTopology, TopologyPeerInfo},
+	peer_set::{PeerSet, PerPeerSet},
+	GossipSupportNetworkMessage, PeerId, Versioned,
 };
 use polkadot_node_subsystem::{
 	messages::{
-		GossipSupportMessage, NetworkBridgeEvent, NetworkBridgeRxMessage, NetworkBridgeTxMessage,
-		RuntimeApiMessage, RuntimeApiRequest,
+		AuthorityConnectivity, ConnectivityError, ConnectivityReport, GossipSupportMessage,
+		NetworkBridgeEvent, NetworkBridgeRxMessage, NetworkBridgeTxMessage, RuntimeApiMessage,
+		RuntimeApiRequest,
 	},
 	overseer, ActiveLeavesUpdate, FromOrchestra, OverseerSignal, SpawnedSubsystem, SubsystemError,
 };
@@ -99,12 +105,18 @@ pub struct GossipSupport<AD> {
 	/// waiting for actual connection.
 	resolved_authorities: HashMap<AuthorityDiscoveryId, HashSet<Multiaddr>>,
 
-	/// Actually connected authorities.
-	connected_authorities: HashMap<AuthorityDiscoveryId, PeerId>,
-	/// By `PeerId`.
+	/// Actually connected authorities, per peer set.
+	connected_authorities: PerPeerSet<ConnectedAuthorities>,
+
+	/// Authorities of the current session, excluding ourselves.
+	session_authorities: Vec<AuthorityDiscoveryId>,
+	/// Our neighbors in the gossip topology of the current session.
+	grid_neighbors: HashSet<AuthorityDiscoveryId>,
+	/// The last connectivity problem observed per authority.
 	///
-	/// Needed for efficient handling of disconnect events.
-	connected_authorities_by_peer_id: HashMap<PeerId, HashSet<AuthorityDiscoveryId>>,
+	/// Only kept for authorities we are trying to connect to.
+	last_errors: HashMap<AuthorityDiscoveryId, ConnectivityError>,
+
 	/// Authority discovery service.
 	authority_discovery: AD,
 
@@ -129,8 +141,10 @@ where
 			last_failure: None,
 			failure_start: None,
 			resolved_authorities: HashMap::new(),
-			connected_authorities: HashMap::new(),
-			connected_authorities_by_peer_id: HashMap::new(),
+			connected_authorities: PerPeerSet::default(),
+			session_authorities: Vec::new(),
+			grid_neighbors: HashSet::new(),
+			last_errors: HashMap::new(),
 			authority_discovery,
 			metrics,
 		}
@@ -164,7 +178,15 @@ where
 			match message {
 				FromOrchestra::Communication {
 					msg: GossipSupportMessage::NetworkBridgeUpdate(ev),
-				} => self.handle_connect_disconnect(ev),
+				} => self.handle_connect_disconnect(PeerSet::Validation, ev),
+				FromOrchestra::Communication {
+					msg: GossipSupportMessage::CollationNetworkBridgeUpdate(ev),
+				} => self.handle_connect_disconnect(PeerSet::Collation, ev),
+				FromOrchestra::Communication {
+					msg: GossipSupportMessage::GetConnectivityReport(tx),
+				} => {
+					let _ = tx.send(self.connectivity_report());
+				},
 				FromOrchestra::Signal(OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
 					activated,
 					..
@@ -259,10 +281,15 @@ where
 				}
 
 				if is_new_session {
+					let mut session_authorities = session_info.discovery_keys.clone();
+					remove_all_controlled(&self.keystore, &mut session_authorities).await;
+					self.session_authorities = session_authorities;
+					self.grid_neighbors.clear();
+
 					// Gossip topology is only relevant for authorities in the current session.
 					let our_index = self.get_key_index_and_update_metrics(&session_info).await?;
 
-					update_gossip_topology(
+					self.grid_neighbors = update_gossip_topology(
 						sender,
 						our_index,
 						session_info.discovery_keys,
@@ -328,6 +355,8 @@ where
 		let mut validator_addrs = Vec::with_capacity(authorities.len());
 		let mut failures = 0;
 		let mut resolved = HashMap::with_capacity(authorities.len());
+		let requested: HashSet<_> = authorities.iter().collect();
+		self.last_errors.retain(|a, _| requested.contains(a));
 		for authority in authorities {
 			if let Some(addrs) =
 				self.authority_discovery.get_addresses_by_authority_id(authority.clone()).await
@@ -341,6 +370,7 @@ where
 					"Couldn't resolve addresses of authority: {:?}",
 					authority
 				);
+				self.last_errors.insert(authority, ConnectivityError::AddressesNotResolved);
 			}
 		}
 		self.resolved_authorities = resolved;
@@ -383,28 +413,29 @@ where
 		};
 	}
 
-	fn handle_connect_disconnect(&mut self, ev: NetworkBridgeEvent<GossipSupportNetworkMessage>) {
+	fn handle_connect_disconnect(
+		&mut self,
+		peer_set: PeerSet,
+		ev: NetworkBridgeEvent<GossipSupportNetworkMessage>,
+	) {
 		match ev {
 			NetworkBridgeEvent::PeerConnected(peer_id, _, _, o_authority) => {
 				if let Some(authority_ids) = o_authority {
-					authority_ids.iter().for_each(|a| {
-						self.connected_authorities.insert(a.clone(), peer_id);
-					});
-					self.connected_authorities_by_peer_id.insert(peer_id, authority_ids);
+					self.connected_authorities[peer_set].on_connected(peer_id, authority_ids);
 				}
 			},
 			NetworkBridgeEvent::PeerDisconnected(peer_id) => {
-				if let Some(authority_ids) = self.connected_authorities_by_peer_id.remove(&peer_id)
-				{
-					authority_ids.into_iter().for_each(|a| {
-						self.connected_authorities.remove(&a);
-					});
+				for a in self.connected_authorities[peer_set].on_disconnected(&peer_id) {
+					if self.resolved_authorities.contains_key(&a) {
+						self.last_errors.insert(a, ConnectivityError::Disconnected(peer_set));
+					}
 				}
 			},
 			NetworkBridgeEvent::OurViewChange(_) => {},
 			NetworkBridgeEvent::PeerViewChange(_, _) => {},
 			NetworkBridgeEvent::NewGossipTopology { .. } => {},
-			NetworkBridgeEvent::PeerMessage(_, Versioned::V1(v)) => {
+			NetworkBridgeEvent::PeerMessage(_, Versioned::V1(v)) |
+			NetworkBridgeEvent::PeerMessage(_, Versioned::VStaging(v)) => {
 				match v {};
 			},
 		}
@@ -412,14 +443,15 @@ where
 
 	/// Check connectivity and report on it in logs.
 	fn check_connectivity(&mut self) {
-		let absolute_connected = self.connected_authorities.len();
+		let connected_authorities = &self.connected_authorities[PeerSet::Validation];
+		let absolute_connected = connected_authorities.len();
 		let absolute_resolved = self.resolved_authorities.len();
 		let connected_ratio =
 			(100 * absolute_connected).checked_div(absolute_resolved).unwrap_or(100);
 		let unconnected_authorities = self
 			.resolved_authorities
 			.iter()
-			.filter(|(a, _)| !self.connected_authorities.contains_key(a));
+			.filter(|(a, _)| !connected_authorities.contains(a));
 		// TODO: Make that warning once connectivity issues are fixed (no point in warning, if
 		// we already know it is broken.
 		// https://github.com/paritytech/polkadot/issues/3921
@@ -439,6 +471,62 @@ where
 			"Connectivity Report"
 		);
 	}
+
+	/// Connectivity to each authority of the current session.
+	fn connectivity_report(&self) -> ConnectivityReport {
+		let authorities = self
+			.session_authorities
+			.iter()
+			.map(|authority_id| AuthorityConnectivity {
+				authority_id: authority_id.clone(),
+				addresses: self.resolved_authorities.get(authority_id).cloned(),
+				validation_peer: self.connected_authorities[PeerSet::Validation].get(authority_id),
+				collation_peer: self.connected_authorities[PeerSet::Collation].get(authority_id),
+				last_error: self.last_errors.get(authority_id).copied(),
+				is_grid_neighbor: self.grid_neighbors.contains(authority_id),
+			})
+			.collect();
+
+		ConnectivityReport { session_index: self.last_session_index, authorities }
+	}
+}
+
+/// Authorities connected on a single peer set.
+#[derive(Default)]
+struct ConnectedAuthorities {
+	by_authority: HashMap<AuthorityDiscoveryId, PeerId>,
+	/// Needed for efficient handling of disconnect events.
+	by_peer_id: HashMap<PeerId, HashSet<AuthorityDiscoveryId>>,
+}
+
+impl ConnectedAuthorities {
+	fn on_connected(&mut self, peer_id: PeerId, authority_ids: HashSet<AuthorityDiscoveryId>) {
+		authority_ids.iter().for_each(|a| {
+			self.by_authority.insert(a.clone(), peer_id);
+		});
+		self.by_peer_id.insert(peer_id, authority_ids);
+	}
+
+	/// Returns the authorities which got disconnected.
+	fn on_disconnected(&mut self, peer_id: &PeerId) -> HashSet<AuthorityDiscoveryId> {
+		let authority_ids = self.by_peer_id.remove(peer_id).unwrap_or_default();
+		authority_ids.iter().for_each(|a| {
+			self.by_authority.remove(a);
+		});
+		authority_ids
+	}
+
+	fn get(&self, authority_id: &AuthorityDiscoveryId) -> Option<PeerId> {
+		self.by_authority.get(authority_id).copied()
+	}
+
+	fn contains(&self, authority_id: &AuthorityDiscoveryId) -> bool {
+		self.by_authority.contains_key(authority_id)
+	}
+
+	fn len(&self) -> usize {
+		self.by_authority.len()
+	}
 }
 
 // Get the authorities of the past, present, and future.
@@ -496,13 +584,15 @@ async fn remove_all_controlled(
 /// This limits the amount of gossip peers to 2 * `sqrt(len)` and ensures the diameter of 2.
 ///
 /// [web3]: https://research.web3.foundation/en/latest/polkadot/networking/3-avail-valid.html#topology
+///
+/// Returns our neighbors in the resulting topology.
 async fn update_gossip_topology(
 	sender: &mut impl overseer::GossipSupportSenderTrait,
 	our_index: usize,
 	authorities: Vec<AuthorityDiscoveryId>,
 	relay_parent: Hash,
 	session_index: SessionIndex,
-) -> Result<(), util::Error> {
+) -> Result<HashSet<AuthorityDiscoveryId>, util::Error> {
 	// retrieve BABE randomness
 	let random_seed = {
 		let (tx, rx) = oneshot::channel();
@@ -542,6 +632,31 @@ async fn update_gossip_topology(
 		(shuffled_indices, canonical_shuffling)
 	};
 
+	let grid_neighbors = {
+		let topology = SessionGridTopology::new(
+			shuffled_indices.clone(),
+			canonical_shuffling
+				.iter()
+				.map(|(discovery_id, validator_index)| TopologyPeerInfo {
+					peer_ids: Vec::new(),
+					validator_index: *validator_index,
+					discovery_id: discovery_id.clone(),
+				})
+				.collect(),
+		);
+		topology
+			.compute_grid_neighbors_for(ValidatorIndex(our_index as _))
+			.map(|neighbors| {
+				neighbors
+					.validator_indices_x
+					.iter()
+					.chain(neighbors.validator_indices_y.iter())
+					.filter_map(|i| authorities.get(i.0 as usize).cloned())
+					.collect()
+			})
+			.unwrap_or_default()
+	};
+
 	sender
 		.send_message(NetworkBridgeRxMessage::NewGossipTopology {
 			session: session_index,
@@ -551,7 +666,7 @@ async fn update_gossip_topology(
 		})
 		.await;
 
-	Ok(())
+	Ok(grid_neighbors)
 }
 
 #[overseer::subsystem(GossipSupport, error = SubsystemError, prefix = self::overseer)]
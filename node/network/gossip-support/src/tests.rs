@@ -29,7 +29,11 @@ use sp_consensus_babe::{AllowedSlots, BabeEpochConfiguration, Epoch as BabeEpoch
 use sp_core::crypto::Pair as PairT;
 use sp_keyring::Sr25519Keyring;
 
-use polkadot_node_network_protocol::grid_topology::{SessionGridTopology, TopologyPeerInfo};
+use polkadot_node_network_protocol::{
+	grid_topology::{SessionGridTopology, TopologyPeerInfo},
+	peer_set::{CollationVersion, ValidationVersion},
+	ObservedRole,
+};
 use polkadot_node_subsystem::{
 	jaeger,
 	messages::{AllMessages, RuntimeApiMessage, RuntimeApiRequest},
@@ -719,3 +723,129 @@ fn issues_a_connection_request_when_last_request_was_mostly_unresolved() {
 	assert_eq!(state.last_session_index, Some(1));
 	assert!(state.last_failure.is_none());
 }
+
+#[test]
+fn connectivity_report_covers_session_authorities() {
+	let hash = Hash::repeat_byte(0xAA);
+	let mut state = make_subsystem();
+	let alice: AuthorityDiscoveryId = Sr25519Keyring::Alice.public().into();
+	let bob: AuthorityDiscoveryId = Sr25519Keyring::Bob.public().into();
+	let charlie: AuthorityDiscoveryId = Sr25519Keyring::Charlie.public().into();
+	state.authority_discovery.addrs.remove(&bob);
+
+	test_harness(state, |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		overseer_signal_active_leaves(overseer, hash).await;
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::SessionIndexForChild(tx),
+			)) => {
+				tx.send(Ok(1)).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::SessionInfo(_, tx),
+			)) => {
+				tx.send(Ok(Some(make_session_info()))).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::Authorities(tx),
+			)) => {
+				tx.send(Ok(AUTHORITIES.clone())).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::ConnectToResolvedValidators {
+				..
+			})
+		);
+
+		test_neighbors(overseer, 1).await;
+
+		let alice_peer = PeerId::random();
+		let charlie_peer = PeerId::random();
+		overseer
+			.send(FromOrchestra::Communication {
+				msg: GossipSupportMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerConnected(
+					alice_peer,
+					ObservedRole::Authority,
+					ValidationVersion::V1.into(),
+					Some(HashSet::from([alice.clone()])),
+				)),
+			})
+			.await;
+		overseer
+			.send(FromOrchestra::Communication {
+				msg: GossipSupportMessage::CollationNetworkBridgeUpdate(
+					NetworkBridgeEvent::PeerConnected(
+						charlie_peer,
+						ObservedRole::Authority,
+						CollationVersion::V1.into(),
+						Some(HashSet::from([charlie.clone()])),
+					),
+				),
+			})
+			.await;
+		overseer
+			.send(FromOrchestra::Communication {
+				msg: GossipSupportMessage::CollationNetworkBridgeUpdate(
+					NetworkBridgeEvent::PeerDisconnected(charlie_peer),
+				),
+			})
+			.await;
+
+		let (tx, rx) = oneshot::channel();
+		overseer
+			.send(FromOrchestra::Communication {
+				msg: GossipSupportMessage::GetConnectivityReport(tx),
+			})
+			.await;
+		let report = rx.timeout(TIMEOUT).await.expect("report timeout").unwrap();
+
+		assert_eq!(report.session_index, Some(1));
+		let got: Vec<_> = report.authorities.iter().map(|a| a.authority_id.clone()).collect();
+		assert_eq!(got, AUTHORITIES_WITHOUT_US.clone());
+
+		let grid_neighbors: HashSet<_> = ROW_NEIGHBORS
+			.iter()
+			.chain(COLUMN_NEIGHBORS.iter())
+			.map(|i| AUTHORITIES[i.0 as usize].clone())
+			.collect();
+		for entry in &report.authorities {
+			assert_eq!(entry.is_grid_neighbor, grid_neighbors.contains(&entry.authority_id));
+			if entry.authority_id == alice {
+				assert_eq!(entry.validation_peer, Some(alice_peer));
+				assert_eq!(entry.last_error, None);
+			} else if entry.authority_id == bob {
+				assert_eq!(entry.addresses, None);
+				assert_eq!(entry.last_error, Some(ConnectivityError::AddressesNotResolved));
+			} else if entry.authority_id == charlie {
+				assert!(entry.addresses.is_some());
+				assert_eq!(entry.collation_peer, None);
+				assert_eq!(
+					entry.last_error,
+					Some(ConnectivityError::Disconnected(PeerSet::Collation))
+				);
+			} else {
+				assert!(entry.addresses.is_some());
+				assert_eq!(entry.validation_peer, None);
+				assert_eq!(entry.last_error, None);
+			}
+		}
+
+		virtual_overseer
+	});
+}
@@ -251,22 +251,26 @@ impl View {
 
 /// A protocol-versioned type.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum Versioned<V1> {
+pub enum Versioned<V1, VStaging> {
 	/// V1 type.
 	V1(V1),
+	/// VStaging type.
+	VStaging(VStaging),
 }
 
-impl<V1: Clone> Versioned<&'_ V1> {
+impl<V1: Clone, VStaging: Clone> Versioned<&'_ V1, &'_ VStaging> {
 	/// Convert to a fully-owned version of the message.
-	pub fn clone_inner(&self) -> Versioned<V1> {
+	pub fn clone_inner(&self) -> Versioned<V1, VStaging> {
 		match *self {
 			Versioned::V1(inner) => Versioned::V1(inner.clone()),
+			Versioned::VStaging(inner) => Versioned::VStaging(inner.clone()),
 		}
 	}
 }
 
 /// All supported versions of the validation protocol message.
-pub type VersionedValidationProtocol = Versioned<v1::ValidationProtocol>;
+pub type VersionedValidationProtocol =
+	Versioned<v1::ValidationProtocol, vstaging::ValidationProtocol>;
 
 impl From<v1::ValidationProtocol> for VersionedValidationProtocol {
 	fn from(v1: v1::ValidationProtocol) -> Self {
@@ -274,8 +278,15 @@ impl From<v1::ValidationProtocol> for VersionedValidationProtocol {
 	}
 }
 
+impl From<vstaging::ValidationProtocol> for VersionedValidationProtocol {
+	fn from(vstaging: vstaging::ValidationProtocol) -> Self {
+		VersionedValidationProtocol::VStaging(vstaging)
+	}
+}
+
 /// All supported versions of the collation protocol message.
-pub type VersionedCollationProtocol = Versioned<v1::CollationProtocol>;
+pub type VersionedCollationProtocol =
+	Versioned<v1::CollationProtocol, vstaging::CollationProtocol>;
 
 impl From<v1::CollationProtocol> for VersionedCollationProtocol {
 	fn from(v1: v1::CollationProtocol) -> Self {
@@ -289,6 +300,7 @@ macro_rules! impl_versioned_full_protocol_from {
 			fn from(versioned_from: $from) -> $out {
 				match versioned_from {
 					Versioned::V1(x) => Versioned::V1(x.into()),
+					Versioned::VStaging(x) => Versioned::VStaging(x.into()),
 				}
 			}
 		}
@@ -298,7 +310,12 @@ macro_rules! impl_versioned_full_protocol_from {
 /// Implement `TryFrom` for one versioned enum variant into the inner type.
 /// `$m_ty::$variant(inner) -> Ok(inner)`
 macro_rules! impl_versioned_try_from {
-	($from:ty, $out:ty, $v1_pat:pat => $v1_out:expr) => {
+	(
+		$from:ty,
+		$out:ty,
+		$v1_pat:pat => $v1_out:expr,
+		$vstaging_pat:pat => $vstaging_out:expr
+	) => {
 		impl TryFrom<$from> for $out {
 			type Error = crate::WrongVariant;
 
@@ -306,6 +323,7 @@ macro_rules! impl_versioned_try_from {
 				#[allow(unreachable_patterns)] // when there is only one variant
 				match x {
 					Versioned::V1($v1_pat) => Ok(Versioned::V1($v1_out)),
+					Versioned::VStaging($vstaging_pat) => Ok(Versioned::VStaging($vstaging_out)),
 					_ => Err(crate::WrongVariant),
 				}
 			}
@@ -318,6 +336,8 @@ macro_rules! impl_versioned_try_from {
 				#[allow(unreachable_patterns)] // when there is only one variant
 				match x {
 					Versioned::V1($v1_pat) => Ok(Versioned::V1($v1_out.clone())),
+					Versioned::VStaging($vstaging_pat) =>
+						Ok(Versioned::VStaging($vstaging_out.clone())),
 					_ => Err(crate::WrongVariant),
 				}
 			}
@@ -326,7 +346,8 @@ macro_rules! impl_versioned_try_from {
 }
 
 /// Version-annotated messages used by the bitfield distribution subsystem.
-pub type BitfieldDistributionMessage = Versioned<v1::BitfieldDistributionMessage>;
+pub type BitfieldDistributionMessage =
+	Versioned<v1::BitfieldDistributionMessage, vstaging::BitfieldDistributionMessage>;
 impl_versioned_full_protocol_from!(
 	BitfieldDistributionMessage,
 	VersionedValidationProtocol,
@@ -335,11 +356,13 @@ impl_versioned_full_protocol_from!(
 impl_versioned_try_from!(
 	VersionedValidationProtocol,
 	BitfieldDistributionMessage,
-	v1::ValidationProtocol::BitfieldDistribution(x) => x
+	v1::ValidationProtocol::BitfieldDistribution(x) => x,
+	vstaging::ValidationProtocol::BitfieldDistribution(x) => x
 );
 
 /// Version-annotated messages used by the statement distribution subsystem.
-pub type StatementDistributionMessage = Versioned<v1::StatementDistributionMessage>;
+pub type StatementDistributionMessage =
+	Versioned<v1::StatementDistributionMessage, vstaging::StatementDistributionMessage>;
 impl_versioned_full_protocol_from!(
 	StatementDistributionMessage,
 	VersionedValidationProtocol,
@@ -348,11 +371,13 @@ impl_versioned_full_protocol_from!(
 impl_versioned_try_from!(
 	VersionedValidationProtocol,
 	StatementDistributionMessage,
-	v1::ValidationProtocol::StatementDistribution(x) => x
+	v1::ValidationProtocol::StatementDistribution(x) => x,
+	vstaging::ValidationProtocol::StatementDistribution(x) => x
 );
 
 /// Version-annotated messages used by the approval distribution subsystem.
-pub type ApprovalDistributionMessage = Versioned<v1::ApprovalDistributionMessage>;
+pub type ApprovalDistributionMessage =
+	Versioned<v1::ApprovalDistributionMessage, vstaging::ApprovalDistributionMessage>;
 impl_versioned_full_protocol_from!(
 	ApprovalDistributionMessage,
 	VersionedValidationProtocol,
@@ -361,11 +386,13 @@ impl_versioned_full_protocol_from!(
 impl_versioned_try_from!(
 	VersionedValidationProtocol,
 	ApprovalDistributionMessage,
-	v1::ValidationProtocol::ApprovalDistribution(x) => x
+	v1::ValidationProtocol::ApprovalDistribution(x) => x,
+	vstaging::ValidationProtocol::ApprovalDistribution(x) => x
 );
 
 /// Version-annotated messages used by the gossip-support subsystem (this is void).
-pub type GossipSupportNetworkMessage = Versioned<v1::GossipSupportNetworkMessage>;
+pub type GossipSupportNetworkMessage =
+	Versioned<v1::GossipSupportNetworkMessage, vstaging::GossipSupportNetworkMessage>;
 // This is a void enum placeholder, so never gets sent over the wire.
 impl TryFrom<VersionedValidationProtocol> for GossipSupportNetworkMessage {
 	type Error = WrongVariant;
@@ -381,8 +408,23 @@ impl<'a> TryFrom<&'a VersionedValidationProtocol> for GossipSupportNetworkMessag
 	}
 }
 
+impl TryFrom<VersionedCollationProtocol> for GossipSupportNetworkMessage {
+	type Error = WrongVariant;
+	fn try_from(_: VersionedCollationProtocol) -> Result<Self, Self::Error> {
+		Err(WrongVariant)
+	}
+}
+
+impl<'a> TryFrom<&'a VersionedCollationProtocol> for GossipSupportNetworkMessage {
+	type Error = WrongVariant;
+	fn try_from(_: &'a VersionedCollationProtocol) -> Result<Self, Self::Error> {
+		Err(WrongVariant)
+	}
+}
+
 /// Version-annotated messages used by the bitfield distribution subsystem.
-pub type CollatorProtocolMessage = Versioned<v1::CollatorProtocolMessage>;
+pub type CollatorProtocolMessage =
+	Versioned<v1::CollatorProtocolMessage, vstaging::CollatorProtocolMessage>;
 impl_versioned_full_protocol_from!(
 	CollatorProtocolMessage,
 	VersionedCollationProtocol,
@@ -391,7 +433,8 @@ impl_versioned_full_protocol_from!(
 impl_versioned_try_from!(
 	VersionedCollationProtocol,
 	CollatorProtocolMessage,
-	v1::CollationProtocol::CollatorProtocol(x) => x
+	v1::CollationProtocol::CollatorProtocol(x) => x,
+	vstaging::CollationProtocol::CollatorProtocol(x) => x
 );
 
 /// v1 notification protocol types.
@@ -551,3 +594,238 @@ pub mod v1 {
 		payload
 	}
 }
+
+/// vstaging notification protocol types.
+///
+/// Statement distribution messages differ from v1: peers first exchange compact manifests of
+/// backed candidates and then only push the statements the other side is missing. Bitfield
+/// distribution can send bitfields as deltas to a bitfield the recipient already has.
+/// All other subsystems use the same messages as in v1.
+pub mod vstaging {
+	use bitvec::{order::Lsb0, slice::BitSlice, vec::BitVec};
+	use parity_scale_codec::{Decode, Encode};
+
+	use polkadot_primitives::{
+		CandidateHash, GroupIndex, Hash, Id as ParaId, UncheckedSignedAvailabilityBitfield,
+		ValidatorIndex, ValidatorSignature,
+	};
+
+	use polkadot_node_primitives::UncheckedSignedFullStatement;
+
+	pub use super::v1::{
+		declare_signature_payload, ApprovalDistributionMessage, CollationProtocol,
+		CollatorProtocolMessage, GossipSupportNetworkMessage, StatementMetadata,
+	};
+
+	/// An availability bitfield, encoded as the difference to a bitfield of the same validator
+	/// at another relay parent.
+	///
+	/// The signature is over the full bitfield, which the recipient reconstructs from the base
+	/// bitfield it already has.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub struct BitfieldDelta {
+		/// The relay parent the bitfield is about.
+		pub relay_parent: Hash,
+		/// The first bytes of the relay parent of the bitfield the delta is relative to.
+		///
+		/// Senders only use bases which are unambiguous among the relay parents in our view.
+		pub base_prefix: [u8; 4],
+		/// The validator which signed the bitfield.
+		pub validator_index: ValidatorIndex,
+		/// Positions of the bits which differ from the base bitfield.
+		pub changed: Vec<u16>,
+		/// Signature of the validator over the full bitfield.
+		pub signature: ValidatorSignature,
+	}
+
+	/// Network messages used by the bitfield distribution subsystem.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub enum BitfieldDistributionMessage {
+		/// A signed availability bitfield for a given relay-parent hash.
+		#[codec(index = 0)]
+		Bitfield(Hash, UncheckedSignedAvailabilityBitfield),
+		/// A signed availability bitfield, sent as the difference to a bitfield of the same
+		/// validator the recipient is known to have.
+		#[codec(index = 1)]
+		BitfieldDelta(BitfieldDelta),
+	}
+
+	/// Bitfields indicating the statements that are known or undesired
+	/// about a candidate, indexed by the position of the validator within its backing group.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub struct StatementFilter {
+		/// Seconded statements. '1' is known or undesired.
+		pub seconded_in_group: BitVec<u8, Lsb0>,
+		/// Valid statements. '1' is known or undesired.
+		pub validated_in_group: BitVec<u8, Lsb0>,
+	}
+
+	impl StatementFilter {
+		/// Create a new blank filter with the given group size.
+		pub fn blank(group_size: usize) -> Self {
+			StatementFilter {
+				seconded_in_group: BitVec::repeat(false, group_size),
+				validated_in_group: BitVec::repeat(false, group_size),
+			}
+		}
+
+		/// Create a new full filter with the given group size.
+		pub fn full(group_size: usize) -> Self {
+			StatementFilter {
+				seconded_in_group: BitVec::repeat(true, group_size),
+				validated_in_group: BitVec::repeat(true, group_size),
+			}
+		}
+
+		/// Whether the filter has a specific expected length, consistent across both
+		/// bitfields.
+		pub fn has_len(&self, len: usize) -> bool {
+			self.seconded_in_group.len() == len && self.validated_in_group.len() == len
+		}
+
+		/// Determine the number of backing validators in the statement filter.
+		pub fn backing_validators(&self) -> usize {
+			self.seconded_in_group
+				.iter()
+				.by_vals()
+				.zip(self.validated_in_group.iter().by_vals())
+				.filter(|&(s, v)| s || v) // no double-counting
+				.count()
+		}
+
+		/// Whether the statement filter has at least one seconded statement.
+		pub fn has_seconded(&self) -> bool {
+			self.seconded_in_group.iter().by_vals().any(|x| x)
+		}
+
+		/// Whether the filter contains the statement of the given kind for the given
+		/// position in the group.
+		pub fn contains(&self, index_in_group: usize, seconded: bool) -> bool {
+			let bits: &BitSlice<u8, Lsb0> =
+				if seconded { &self.seconded_in_group } else { &self.validated_in_group };
+			bits.get(index_in_group).map_or(false, |b| *b)
+		}
+
+		/// Note a statement of the given kind for the given position in the group.
+		///
+		/// Positions outside of the group are ignored.
+		pub fn note(&mut self, index_in_group: usize, seconded: bool) {
+			let bits =
+				if seconded { &mut self.seconded_in_group } else { &mut self.validated_in_group };
+			if let Some(mut b) = bits.get_mut(index_in_group) {
+				*b = true;
+			}
+		}
+
+		/// Mask out statements which are known by the given filter.
+		///
+		/// Both filters are expected to have the same length. Positions which are only covered
+		/// by `self` are left untouched.
+		pub fn mask_known(&mut self, known: &StatementFilter) {
+			for (mut x, y) in
+				self.seconded_in_group.iter_mut().zip(known.seconded_in_group.iter())
+			{
+				*x = *x && !*y;
+			}
+
+			for (mut x, y) in
+				self.validated_in_group.iter_mut().zip(known.validated_in_group.iter())
+			{
+				*x = *x && !*y;
+			}
+		}
+	}
+
+	/// A manifest of a known backed candidate, along with a description
+	/// of the statements potentially available on the sending side.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub struct BackedCandidateManifest {
+		/// The relay-parent of the candidate.
+		pub relay_parent: Hash,
+		/// The hash of the candidate.
+		pub candidate_hash: CandidateHash,
+		/// The group index backing the candidate at the relay-parent.
+		pub group_index: GroupIndex,
+		/// The para ID of the candidate.
+		pub para_id: ParaId,
+		/// A statement filter which indicates which validators in the
+		/// para's group at the relay-parent have validated this candidate
+		/// and issued statements about it, to the advertiser's knowledge.
+		///
+		/// This MUST have exactly the minimum amount of bytes
+		/// necessary to represent the number of validators in the assigned
+		/// backing group as-of the relay-parent.
+		pub statement_knowledge: StatementFilter,
+	}
+
+	/// An acknowledgement of a backed candidate being known.
+	///
+	/// This is sent in response to a [`BackedCandidateManifest`] and carries the statements the
+	/// recipient already knows about, so the advertiser only sends the missing ones.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub struct BackedCandidateAcknowledgement {
+		/// The relay-parent of the candidate.
+		pub relay_parent: Hash,
+		/// The hash of the candidate.
+		pub candidate_hash: CandidateHash,
+		/// A statement filter which indicates which validators in the
+		/// para's group at the relay-parent have validated this candidate
+		/// and issued statements about it, to the acknowledger's knowledge.
+		///
+		/// This MUST have exactly the minimum amount of bytes
+		/// necessary to represent the number of validators in the assigned
+		/// backing group as-of the relay-parent.
+		pub statement_knowledge: StatementFilter,
+	}
+
+	/// Network messages used by the statement distribution subsystem.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub enum StatementDistributionMessage {
+		/// A signed full statement under a given relay-parent.
+		#[codec(index = 0)]
+		Statement(Hash, UncheckedSignedFullStatement),
+
+		/// A notification of a backed candidate being known by the
+		/// sending node, for the purpose of being requested by the receiving node
+		/// if needed.
+		#[codec(index = 1)]
+		BackedCandidateManifest(BackedCandidateManifest),
+
+		/// A notification of a backed candidate being known by the sending node,
+		/// for the purpose of informing a receiving node which already has the candidate.
+		#[codec(index = 2)]
+		BackedCandidateKnown(BackedCandidateAcknowledgement),
+
+		/// V1 statement distribution messages which have no dedicated staging equivalent,
+		/// such as large statement announcements.
+		///
+		/// These are illegal to send to V1 peers.
+		#[codec(index = 255)]
+		V1Compatibility(super::v1::StatementDistributionMessage),
+	}
+
+	/// All network messages on the validation peer-set.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq, derive_more::From)]
+	pub enum ValidationProtocol {
+		/// Bitfield distribution messages
+		#[codec(index = 1)]
+		#[from]
+		BitfieldDistribution(BitfieldDistributionMessage),
+		/// Statement distribution messages
+		#[codec(index = 3)]
+		#[from]
+		StatementDistribution(StatementDistributionMessage),
+		/// Approval distribution messages
+		#[codec(index = 4)]
+		#[from]
+		ApprovalDistribution(ApprovalDistributionMessage),
+	}
+}
+
+/// Returns the subset of `peers` with the specified `version`.
+pub fn filter_by_peer_version(
+	peers: &[(PeerId, peer_set::ProtocolVersion)],
+	version: peer_set::ProtocolVersion,
+) -> Vec<PeerId> {
+	peers.iter().filter(|(_, v)| v == &version).map(|(p, _)| *p).collect::<Vec<_>>()
+}
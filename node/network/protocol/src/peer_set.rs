@@ -72,7 +72,7 @@ impl PeerSet {
 		// Networking layer relies on `get_main_name()` being the main name of the protocol
 		// for peersets and connection management.
 		let protocol = peerset_protocol_names.get_main_name(self);
-		let fallback_names = PeerSetProtocolNames::get_fallback_names(self);
+		let fallback_names = peerset_protocol_names.get_fallback_names(self);
 		let max_notification_size = self.get_max_notification_size(is_authority);
 
 		match self {
@@ -117,10 +117,17 @@ impl PeerSet {
 	/// Networking layer relies on `get_main_version()` being the version
 	/// of the main protocol name reported by [`PeerSetProtocolNames::get_main_name()`].
 	pub fn get_main_version(self) -> ProtocolVersion {
+		#[cfg(not(feature = "network-protocol-staging"))]
 		match self {
 			PeerSet::Validation => ValidationVersion::V1.into(),
 			PeerSet::Collation => CollationVersion::V1.into(),
 		}
+
+		#[cfg(feature = "network-protocol-staging")]
+		match self {
+			PeerSet::Validation => ValidationVersion::VStaging.into(),
+			PeerSet::Collation => CollationVersion::V1.into(),
+		}
 	}
 
 	/// Get the max notification size for this peer set.
@@ -144,6 +151,8 @@ impl PeerSet {
 			PeerSet::Validation =>
 				if version == ValidationVersion::V1.into() {
 					Some("validation/1")
+				} else if version == ValidationVersion::VStaging.into() {
+					Some("validation/2")
 				} else {
 					None
 				},
@@ -211,6 +220,9 @@ impl From<ProtocolVersion> for u32 {
 pub enum ValidationVersion {
 	/// The first version.
 	V1 = 1,
+	/// The staging version, in which statement distribution exchanges manifests of backed
+	/// candidates before sending statements.
+	VStaging = 2,
 }
 
 /// Supported collation protocol versions. Only versions defined here must be used in the codebase.
@@ -374,10 +386,23 @@ impl PeerSetProtocolNames {
 		.into()
 	}
 
-	/// Get the protocol fallback names. Currently only holds the legacy name
-	/// for `LEGACY_PROTOCOL_VERSION` = 1.
-	fn get_fallback_names(protocol: PeerSet) -> Vec<ProtocolName> {
-		std::iter::once(Self::get_legacy_name(protocol)).collect()
+	/// Get the protocol fallback names. Holds the names of all supported versions older than
+	/// the main one, most recent first, followed by the legacy name for
+	/// `LEGACY_PROTOCOL_VERSION` = 1.
+	fn get_fallback_names(&self, protocol: PeerSet) -> Vec<ProtocolName> {
+		let main_version = protocol.get_main_version();
+		let mut older_versions: Vec<ProtocolVersion> = match protocol {
+			PeerSet::Validation => ValidationVersion::iter().map(Into::into).collect(),
+			PeerSet::Collation => CollationVersion::iter().map(Into::into).collect(),
+		};
+		older_versions.retain(|version| u32::from(*version) < u32::from(main_version));
+		older_versions.sort_by_key(|version| std::cmp::Reverse(u32::from(*version)));
+
+		older_versions
+			.into_iter()
+			.map(|version| self.get_name(protocol, version))
+			.chain(std::iter::once(Self::get_legacy_name(protocol)))
+			.collect()
 	}
 }
 
@@ -536,4 +561,34 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn fallback_names_cover_older_versions_and_legacy() {
+		let genesis_hash = Hash::from([
+			122, 200, 116, 29, 232, 183, 20, 109, 138, 86, 23, 253, 70, 41, 20, 85, 127, 230, 60,
+			38, 90, 127, 28, 16, 231, 218, 227, 40, 88, 238, 187, 128,
+		]);
+		let protocol_names = PeerSetProtocolNames::new(genesis_hash, None);
+
+		for protocol in PeerSet::iter() {
+			let main_version = u32::from(protocol.get_main_version());
+			let fallback_names = protocol_names.get_fallback_names(protocol);
+
+			// The legacy name always comes last.
+			assert_eq!(
+				fallback_names.last(),
+				Some(&PeerSetProtocolNames::get_legacy_name(protocol)),
+			);
+
+			// Every other fallback resolves to an older version on the same peer-set.
+			for name in &fallback_names[..fallback_names.len() - 1] {
+				let (peer_set, version) = protocol_names.try_get_protocol(name).unwrap();
+				assert_eq!(peer_set, protocol);
+				assert!(u32::from(version) < main_version);
+			}
+
+			// And the main name is never a fallback.
+			assert!(!fallback_names.contains(&protocol_names.get_main_name(protocol)));
+		}
+	}
 }
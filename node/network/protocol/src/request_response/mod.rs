@@ -71,6 +71,8 @@ pub enum Protocol {
 	StatementFetchingV1,
 	/// Sending of dispute statements with application level confirmations.
 	DisputeSendingV1,
+	/// Sending of statements of several disputes in one request, with confirmations per dispute.
+	DisputeSendingBatchV1,
 }
 
 /// Minimum bandwidth we expect for validators - 500Mbit/s is the recommendation, so approximately
@@ -125,6 +127,12 @@ const STATEMENT_RESPONSE_SIZE: u64 = MAX_CODE_SIZE as u64 + 10_000;
 /// timeout as we want to get statements through to each node in any case.
 pub const DISPUTE_REQUEST_TIMEOUT: Duration = Duration::from_secs(12);
 
+/// Maximum number of disputes in a single `DisputeBatchRequest`.
+///
+/// Each dispute is well below 1_000 bytes on the wire, which is what we allow for a single
+/// `DisputeRequest`.
+pub const MAX_DISPUTES_PER_BATCH: usize = 10;
+
 impl Protocol {
 	/// Get a configuration for a given Request response protocol.
 	///
@@ -221,6 +229,15 @@ impl Protocol {
 				request_timeout: DISPUTE_REQUEST_TIMEOUT,
 				inbound_queue: tx,
 			},
+			Protocol::DisputeSendingBatchV1 => RequestResponseConfig {
+				name,
+				fallback_names,
+				max_request_size: 1_000 * MAX_DISPUTES_PER_BATCH as u64,
+				// Confirmed candidate hashes only.
+				max_response_size: 100 + 32 * MAX_DISPUTES_PER_BATCH as u64,
+				request_timeout: DISPUTE_REQUEST_TIMEOUT,
+				inbound_queue: tx,
+			},
 		}
 	}
 
@@ -265,23 +282,29 @@ impl Protocol {
 			// average, so something in the ballpark of 100 should be fine. Nodes will retry on
 			// failure, so having a good value here is mostly about performance tuning.
 			Protocol::DisputeSendingV1 => 100,
+			// Batches are only sent on retries and session changes, at most one per sender at a
+			// time.
+			Protocol::DisputeSendingBatchV1 => 100,
 		}
 	}
 
 	/// Fallback protocol names of this protocol, as understood by substrate networking.
 	fn get_fallback_names(self) -> Vec<ProtocolName> {
-		std::iter::once(self.get_legacy_name().into()).collect()
+		self.get_legacy_name().into_iter().map(Into::into).collect()
 	}
 
 	/// Legacy protocol name associated with each peer set.
-	const fn get_legacy_name(self) -> &'static str {
+	///
+	/// Protocols introduced after the switch to genesis hash based names have none.
+	const fn get_legacy_name(self) -> Option<&'static str> {
 		match self {
-			Protocol::ChunkFetchingV1 => "/polkadot/req_chunk/1",
-			Protocol::CollationFetchingV1 => "/polkadot/req_collation/1",
-			Protocol::PoVFetchingV1 => "/polkadot/req_pov/1",
-			Protocol::AvailableDataFetchingV1 => "/polkadot/req_available_data/1",
-			Protocol::StatementFetchingV1 => "/polkadot/req_statement/1",
-			Protocol::DisputeSendingV1 => "/polkadot/send_dispute/1",
+			Protocol::ChunkFetchingV1 => Some("/polkadot/req_chunk/1"),
+			Protocol::CollationFetchingV1 => Some("/polkadot/req_collation/1"),
+			Protocol::PoVFetchingV1 => Some("/polkadot/req_pov/1"),
+			Protocol::AvailableDataFetchingV1 => Some("/polkadot/req_available_data/1"),
+			Protocol::StatementFetchingV1 => Some("/polkadot/req_statement/1"),
+			Protocol::DisputeSendingV1 => Some("/polkadot/send_dispute/1"),
+			Protocol::DisputeSendingBatchV1 => None,
 		}
 	}
 }
@@ -337,6 +360,7 @@ impl ReqProtocolNames {
 			Protocol::AvailableDataFetchingV1 => "/req_available_data/1",
 			Protocol::StatementFetchingV1 => "/req_statement/1",
 			Protocol::DisputeSendingV1 => "/send_dispute/1",
+			Protocol::DisputeSendingBatchV1 => "/send_dispute_batch/1",
 		};
 
 		format!("{}{}", prefix, short_name).into()
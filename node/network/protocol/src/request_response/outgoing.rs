@@ -40,6 +40,8 @@ pub enum Requests {
 	StatementFetchingV1(OutgoingRequest<v1::StatementFetchingRequest>),
 	/// Requests for notifying about an ongoing dispute.
 	DisputeSendingV1(OutgoingRequest<v1::DisputeRequest>),
+	/// Requests for notifying about several ongoing disputes at once.
+	DisputeSendingBatchV1(OutgoingRequest<v1::DisputeBatchRequest>),
 }
 
 impl Requests {
@@ -52,6 +54,7 @@ impl Requests {
 			Self::AvailableDataFetchingV1(_) => Protocol::AvailableDataFetchingV1,
 			Self::StatementFetchingV1(_) => Protocol::StatementFetchingV1,
 			Self::DisputeSendingV1(_) => Protocol::DisputeSendingV1,
+			Self::DisputeSendingBatchV1(_) => Protocol::DisputeSendingBatchV1,
 		}
 	}
 
@@ -70,6 +73,7 @@ impl Requests {
 			Self::AvailableDataFetchingV1(r) => r.encode_request(),
 			Self::StatementFetchingV1(r) => r.encode_request(),
 			Self::DisputeSendingV1(r) => r.encode_request(),
+			Self::DisputeSendingBatchV1(r) => r.encode_request(),
 		}
 	}
 }
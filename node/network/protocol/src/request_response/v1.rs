@@ -215,3 +215,24 @@ impl IsRequest for DisputeRequest {
 	type Response = DisputeResponse;
 	const PROTOCOL: Protocol = Protocol::DisputeSendingV1;
 }
+
+/// A dispute request for several disputes at once.
+///
+/// Contains at most `MAX_DISPUTES_PER_BATCH` disputes, at most one per candidate.
+#[derive(Clone, Encode, Decode, Debug)]
+pub struct DisputeBatchRequest(pub Vec<UncheckedDisputeMessage>);
+
+/// Possible responses to a `DisputeBatchRequest`.
+#[derive(Encode, Decode, Debug, PartialEq, Eq)]
+pub enum DisputeBatchResponse {
+	/// Recipient successfully processed the disputes of the given candidates.
+	///
+	/// Disputes of the batch not listed here were not (yet) imported and should be retried.
+	#[codec(index = 0)]
+	Confirmed(Vec<CandidateHash>),
+}
+
+impl IsRequest for DisputeBatchRequest {
+	type Response = DisputeBatchResponse;
+	const PROTOCOL: Protocol = Protocol::DisputeSendingBatchV1;
+}
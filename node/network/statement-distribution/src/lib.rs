@@ -26,11 +26,12 @@ use error::{log_error, FatalResult, JfyiErrorResult};
 use parity_scale_codec::Encode;
 
 use polkadot_node_network_protocol::{
-	self as net_protocol,
+	self as net_protocol, filter_by_peer_version,
 	grid_topology::{GridNeighbors, RequiredRouting, SessionBoundGridTopologyStorage},
-	peer_set::{IsAuthority, PeerSet},
+	peer_set::{IsAuthority, PeerSet, ProtocolVersion, ValidationVersion},
 	request_response::{v1 as request_v1, IncomingRequestReceiver},
 	v1::{self as protocol_v1, StatementMetadata},
+	vstaging::{self as protocol_vstaging, StatementFilter},
 	IfDisconnected, PeerId, UnifiedReputationChange as Rep, Versioned, View,
 };
 use polkadot_node_primitives::{SignedFullStatement, Statement, UncheckedSignedFullStatement};
@@ -46,9 +47,9 @@ use polkadot_node_subsystem::{
 	SubsystemError,
 };
 use polkadot_primitives::{
-	AuthorityDiscoveryId, CandidateHash, CommittedCandidateReceipt, CompactStatement, Hash,
-	IndexedVec, SignedStatement, SigningContext, UncheckedSignedStatement, ValidatorId,
-	ValidatorIndex, ValidatorSignature,
+	AuthorityDiscoveryId, CandidateHash, CommittedCandidateReceipt, CompactStatement, GroupIndex,
+	Hash, Id as ParaId, IndexedVec, SignedStatement, SigningContext, UncheckedSignedStatement,
+	ValidatorId, ValidatorIndex, ValidatorSignature,
 };
 
 use futures::{
@@ -97,6 +98,11 @@ const COST_WRONG_HASH: Rep = Rep::CostMajor("Received candidate had wrong hash")
 const COST_DUPLICATE_STATEMENT: Rep =
 	Rep::CostMajorRepeated("Statement sent more than once by peer");
 const COST_APPARENT_FLOOD: Rep = Rep::Malicious("Peer appears to be flooding us with statements");
+const COST_UNEXPECTED_MANIFEST: Rep = Rep::CostMinor("Unexpected Manifest");
+const COST_MALFORMED_MANIFEST: Rep = Rep::CostMajor("Manifest Malformed");
+const COST_DUPLICATE_MANIFEST: Rep =
+	Rep::CostMinorRepeated("Manifest sent more than once by peer");
+const COST_UNEXPECTED_ACKNOWLEDGEMENT: Rep = Rep::CostMinor("Unexpected Manifest Acknowledgement");
 
 const BENEFIT_VALID_STATEMENT: Rep = Rep::BenefitMajor("Peer provided a valid statement");
 const BENEFIT_VALID_STATEMENT_FIRST: Rep =
@@ -258,6 +264,14 @@ struct PeerRelayParentKnowledge {
 	/// We have seen a message that that is unexpected from this peer, so note this fact
 	/// and stop subsequent logging and peer reputation flood.
 	unexpected_count: usize,
+
+	/// Candidates we announced to the peer via a manifest instead of sending it the `Seconded`
+	/// statements directly. Only used for peers on the staging protocol.
+	sent_manifests: HashSet<CandidateHash>,
+	/// Candidates the peer announced to us via a manifest.
+	received_manifests: HashSet<CandidateHash>,
+	/// Candidates for which the peer acknowledged a manifest we sent it.
+	acknowledged_manifests: HashSet<CandidateHash>,
 }
 
 impl PeerRelayParentKnowledge {
@@ -437,6 +451,72 @@ impl PeerRelayParentKnowledge {
 	fn is_known_candidate(&self, candidate: &CandidateHash) -> bool {
 		self.sent_candidates.contains(candidate) || self.received_candidates.contains(candidate)
 	}
+
+	/// Note the statements about a candidate which the peer claims to know, as advertised in a
+	/// manifest or an acknowledgement. The filter is indexed by the validators in `group`.
+	///
+	/// We will not send any of these statements to the peer. They are not noted as received,
+	/// because the peer sends us exactly those of them we report missing.
+	fn note_statement_knowledge(
+		&mut self,
+		candidate_hash: CandidateHash,
+		group: &[ValidatorIndex],
+		filter: &StatementFilter,
+	) {
+		if filter.has_seconded() {
+			self.received_candidates.insert(candidate_hash);
+		}
+
+		for (index_in_group, validator_index) in group.iter().enumerate() {
+			if filter.contains(index_in_group, true) {
+				self.sent_statements
+					.insert((CompactStatement::Seconded(candidate_hash), *validator_index));
+			}
+
+			if filter.contains(index_in_group, false) {
+				self.sent_statements
+					.insert((CompactStatement::Valid(candidate_hash), *validator_index));
+			}
+		}
+	}
+
+	/// Attempt to note a manifest we are receiving from the peer.
+	///
+	/// This returns an error if the peer already announced the candidate or has announced
+	/// more candidates than `max_manifests`.
+	fn receive_manifest(
+		&mut self,
+		candidate_hash: CandidateHash,
+		max_manifests: usize,
+	) -> std::result::Result<(), Rep> {
+		if self.received_manifests.contains(&candidate_hash) {
+			return Err(COST_DUPLICATE_MANIFEST)
+		}
+
+		if self.received_manifests.len() >= max_manifests {
+			return Err(COST_APPARENT_FLOOD)
+		}
+
+		self.received_manifests.insert(candidate_hash);
+		Ok(())
+	}
+
+	/// Attempt to note an acknowledgement of a manifest we sent to the peer.
+	///
+	/// This returns an error if we never sent the peer a manifest for the candidate or it was
+	/// acknowledged already.
+	fn receive_acknowledgement(
+		&mut self,
+		candidate_hash: CandidateHash,
+	) -> std::result::Result<(), Rep> {
+		if !self.sent_manifests.contains(&candidate_hash) ||
+			!self.acknowledged_manifests.insert(candidate_hash)
+		{
+			return Err(COST_UNEXPECTED_ACKNOWLEDGEMENT)
+		}
+
+		Ok(())
+	}
 }
 
 struct PeerData {
@@ -444,9 +524,24 @@ struct PeerData {
 	view_knowledge: HashMap<Hash, PeerRelayParentKnowledge>,
 	/// Peer might be known as authority with the given ids.
 	maybe_authority: Option<HashSet<AuthorityDiscoveryId>>,
+	/// The validation protocol version the peer speaks.
+	protocol_version: ProtocolVersion,
 }
 
 impl PeerData {
+	/// Whether the peer speaks the staging protocol, learning about new candidates via
+	/// manifests rather than full `Seconded` statements.
+	fn wants_manifests(&self) -> bool {
+		self.protocol_version == ValidationVersion::VStaging.into()
+	}
+
+	/// Whether the peer is aware of the candidate at the given relay-parent.
+	fn knows_candidate(&self, relay_parent: &Hash, candidate_hash: &CandidateHash) -> bool {
+		self.view_knowledge
+			.get(relay_parent)
+			.map_or(false, |k| k.is_known_candidate(candidate_hash))
+	}
+
 	/// Updates our view of the peer's knowledge with this statement's fingerprint based
 	/// on something that we would like to send to the peer.
 	///
@@ -666,6 +761,11 @@ struct ActiveHeadData {
 	waiting_large_statements: HashMap<CandidateHash, LargeStatementStatus>,
 	/// The parachain validators at the head's child session index.
 	validators: IndexedVec<ValidatorIndex, ValidatorId>,
+	/// The validator groups at the head's child session index.
+	groups: IndexedVec<GroupIndex, Vec<ValidatorIndex>>,
+	/// The backing group and para of all candidates we are aware of for this head, determined
+	/// by the first `Seconded` statement about them.
+	candidate_groups: HashMap<CandidateHash, (GroupIndex, ParaId)>,
 	/// The current session index of this fork.
 	session_index: sp_staking::SessionIndex,
 	/// How many `Seconded` statements we've seen per validator.
@@ -677,6 +777,7 @@ struct ActiveHeadData {
 impl ActiveHeadData {
 	fn new(
 		validators: IndexedVec<ValidatorIndex, ValidatorId>,
+		groups: IndexedVec<GroupIndex, Vec<ValidatorIndex>>,
 		session_index: sp_staking::SessionIndex,
 		span: PerLeafSpan,
 	) -> Self {
@@ -685,6 +786,8 @@ impl ActiveHeadData {
 			statements: Default::default(),
 			waiting_large_statements: Default::default(),
 			validators,
+			groups,
+			candidate_groups: Default::default(),
 			session_index,
 			seconded_counts: Default::default(),
 			span,
@@ -727,6 +830,13 @@ impl ActiveHeadData {
 				}
 
 				self.candidates.insert(h);
+				if let Statement::Seconded(committed) = statement.payload() {
+					let para_id = committed.descriptor().para_id;
+					if let Some(group_index) = self.group_of(validator_index) {
+						self.candidate_groups.entry(h).or_insert((group_index, para_id));
+					}
+				}
+
 				if let Some(old) = self.statements.insert(comparator.clone(), statement) {
 					gum::trace!(
 						target: LOG_TARGET,
@@ -866,6 +976,53 @@ impl ActiveHeadData {
 		self.statements()
 			.filter(move |s| s.compact().candidate_hash() == &candidate_hash)
 	}
+
+	/// Get the backing group a validator belongs to.
+	fn group_of(&self, validator_index: ValidatorIndex) -> Option<GroupIndex> {
+		self.groups
+			.iter()
+			.position(|group| group.contains(&validator_index))
+			.map(|i| GroupIndex(i as u32))
+	}
+
+	/// Get a filter of the statements we have about a candidate, indexed by the validators
+	/// in `group`.
+	fn statement_filter(
+		&self,
+		candidate_hash: CandidateHash,
+		group: &[ValidatorIndex],
+	) -> StatementFilter {
+		let mut filter = StatementFilter::blank(group.len());
+		for statement in self.statements_about(candidate_hash) {
+			let validator_index = statement.statement.validator_index();
+			if let Some(index_in_group) = group.iter().position(|v| v == &validator_index) {
+				let seconded = matches!(statement.compact(), CompactStatement::Seconded(_));
+				filter.note(index_in_group, seconded);
+			}
+		}
+
+		filter
+	}
+
+	/// Get a manifest announcing a candidate to peers on the staging protocol.
+	///
+	/// Returns `None` if we don't know the backing group of the candidate.
+	fn candidate_manifest(
+		&self,
+		relay_parent: Hash,
+		candidate_hash: CandidateHash,
+	) -> Option<protocol_vstaging::BackedCandidateManifest> {
+		let (group_index, para_id) = *self.candidate_groups.get(&candidate_hash)?;
+		let group = self.groups.get(group_index)?;
+
+		Some(protocol_vstaging::BackedCandidateManifest {
+			relay_parent,
+			candidate_hash,
+			group_index,
+			para_id,
+			statement_knowledge: self.statement_filter(candidate_hash, group),
+		})
+	}
 }
 
 /// Check a statement signature under this parent hash.
@@ -916,7 +1073,7 @@ async fn circulate_statement_and_dependents<Context>(
 
 	// First circulate the statement directly to all peers needing it.
 	// The borrow of `active_head` needs to encompass only this (Rust) statement.
-	let outputs: Option<(CandidateHash, Vec<PeerId>)> = {
+	let outputs: Option<(CandidateHash, CirculationOutcome)> = {
 		match active_head.note_statement(statement) {
 			NotedStatement::Fresh(stored) => Some((
 				*stored.compact().candidate_hash(),
@@ -939,8 +1096,8 @@ async fn circulate_statement_and_dependents<Context>(
 
 	let _span = _span.child("send-to-peers");
 	// Now send dependent statements to all peers needing them, if any.
-	if let Some((candidate_hash, peers_needing_dependents)) = outputs {
-		for peer in peers_needing_dependents {
+	if let Some((candidate_hash, outcome)) = outputs {
+		for peer in outcome.needs_dependents {
 			if let Some(peer_data) = peers.get_mut(&peer) {
 				let _span_loop = _span.child("to-peer").with_peer_id(&peer);
 				// defensive: the peer data should always be some because the iterator
@@ -957,6 +1114,17 @@ async fn circulate_statement_and_dependents<Context>(
 				.await;
 			}
 		}
+
+		send_manifests(
+			outcome.needs_manifest,
+			peers,
+			ctx,
+			relay_parent,
+			candidate_hash,
+			&*active_head,
+			metrics,
+		)
+		.await;
 	}
 }
 
@@ -972,12 +1140,10 @@ fn statement_message(
 	}
 
 	let msg = if is_large {
-		protocol_v1::StatementDistributionMessage::LargeStatement(StatementMetadata {
+		protocol_v1::StatementDistributionMessage::LargeStatement(large_statement_metadata(
 			relay_parent,
-			candidate_hash: statement.payload().candidate_hash(),
-			signed_by: statement.validator_index(),
-			signature: statement.signature().clone(),
-		})
+			&statement,
+		))
 	} else {
 		protocol_v1::StatementDistributionMessage::Statement(relay_parent, statement.into())
 	};
@@ -985,6 +1151,61 @@ fn statement_message(
 	protocol_v1::ValidationProtocol::StatementDistribution(msg).into()
 }
 
+/// Create a staging network message from a given statement.
+///
+/// Large statements are still announced via the v1 metadata message, as their payload is
+/// fetched via request/response regardless of the protocol version.
+fn vstaging_statement_message(
+	relay_parent: Hash,
+	statement: SignedFullStatement,
+	metrics: &Metrics,
+) -> net_protocol::VersionedValidationProtocol {
+	let (is_large, size) = is_statement_large(&statement);
+	if let Some(size) = size {
+		metrics.on_created_message(size);
+	}
+
+	let msg = if is_large {
+		protocol_vstaging::StatementDistributionMessage::V1Compatibility(
+			protocol_v1::StatementDistributionMessage::LargeStatement(large_statement_metadata(
+				relay_parent,
+				&statement,
+			)),
+		)
+	} else {
+		protocol_vstaging::StatementDistributionMessage::Statement(relay_parent, statement.into())
+	};
+
+	protocol_vstaging::ValidationProtocol::StatementDistribution(msg).into()
+}
+
+/// Create a network message from a given statement for a peer on the given protocol version.
+fn versioned_statement_message(
+	relay_parent: Hash,
+	statement: SignedFullStatement,
+	version: ProtocolVersion,
+	metrics: &Metrics,
+) -> net_protocol::VersionedValidationProtocol {
+	if version == ValidationVersion::VStaging.into() {
+		vstaging_statement_message(relay_parent, statement, metrics)
+	} else {
+		statement_message(relay_parent, statement, metrics)
+	}
+}
+
+/// Get the metadata announcing a large statement.
+fn large_statement_metadata(
+	relay_parent: Hash,
+	statement: &SignedFullStatement,
+) -> StatementMetadata {
+	StatementMetadata {
+		relay_parent,
+		candidate_hash: statement.payload().candidate_hash(),
+		signed_by: statement.validator_index(),
+		signature: statement.signature().clone(),
+	}
+}
+
 /// Check whether a statement should be treated as large statement.
 ///
 /// Also report size of statement - if it is a `Seconded` statement, otherwise `None`.
@@ -1007,8 +1228,17 @@ fn is_statement_large(statement: &SignedFullStatement) -> (bool, Option<usize>)
 	}
 }
 
+/// Peers which need follow-up messages after circulating a statement.
+struct CirculationOutcome {
+	/// Peers which just learned about the candidate and need to have dependent statements sent.
+	needs_dependents: Vec<PeerId>,
+	/// Peers on the staging protocol which are not aware of the candidate. These need to be
+	/// sent a manifest instead of the `Seconded` statement.
+	needs_manifest: Vec<PeerId>,
+}
+
 /// Circulates a statement to all peers who have not seen it yet, and returns
-/// an iterator over peers who need to have dependent statements sent.
+/// the peers which need dependent statements or a manifest sent.
 #[overseer::contextbounds(StatementDistribution, prefix=self::overseer)]
 async fn circulate_statement<'a, Context>(
 	required_routing: RequiredRouting,
@@ -1020,7 +1250,7 @@ async fn circulate_statement<'a, Context>(
 	mut priority_peers: Vec<PeerId>,
 	metrics: &Metrics,
 	rng: &mut impl rand::Rng,
-) -> Vec<PeerId> {
+) -> CirculationOutcome {
 	let fingerprint = stored.fingerprint();
 
 	let mut peers_to_send: Vec<PeerId> = peers
@@ -1067,20 +1297,30 @@ async fn circulate_statement<'a, Context>(
 		peers_to_send.len() == peers_to_send.clone().into_iter().collect::<HashSet<_>>().len(),
 		"We filter out duplicates above. qed.",
 	);
-	let peers_to_send: Vec<(PeerId, bool)> = peers_to_send
+
+	// Peers on the staging protocol learn about new candidates via manifests.
+	let candidate_hash = *fingerprint.0.candidate_hash();
+	let is_seconded = matches!(fingerprint.0, CompactStatement::Seconded(_));
+	let (needs_manifest, peers_to_send): (Vec<PeerId>, Vec<PeerId>) =
+		peers_to_send.into_iter().partition(|peer_id| {
+			let peer_data = peers.get(peer_id).expect("a subset is taken above, so it exists; qed");
+			is_seconded &&
+				peer_data.wants_manifests() &&
+				!peer_data.knows_candidate(&relay_parent, &candidate_hash)
+		});
+
+	let peers_to_send: Vec<(PeerId, ProtocolVersion, bool)> = peers_to_send
 		.into_iter()
 		.map(|peer_id| {
-			let new = peers
-				.get_mut(&peer_id)
-				.expect("a subset is taken above, so it exists; qed")
-				.send(&relay_parent, &fingerprint);
-			(peer_id, new)
+			let peer_data =
+				peers.get_mut(&peer_id).expect("a subset is taken above, so it exists; qed");
+			let new = peer_data.send(&relay_parent, &fingerprint);
+			(peer_id, peer_data.protocol_version, new)
 		})
 		.collect();
 
 	// Send all these peers the initial statement.
 	if !peers_to_send.is_empty() {
-		let payload = statement_message(relay_parent, stored.statement.clone(), metrics);
 		gum::trace!(
 			target: LOG_TARGET,
 			?peers_to_send,
@@ -1088,17 +1328,32 @@ async fn circulate_statement<'a, Context>(
 			statement = ?stored.statement,
 			"Sending statement",
 		);
-		ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(
-			peers_to_send.iter().map(|(p, _)| *p).collect(),
-			payload,
-		))
-		.await;
+
+		let versioned_peers =
+			peers_to_send.iter().map(|(p, version, _)| (*p, *version)).collect::<Vec<_>>();
+		for version in [ValidationVersion::V1, ValidationVersion::VStaging] {
+			let recipients = filter_by_peer_version(&versioned_peers, version.into());
+			if recipients.is_empty() {
+				continue
+			}
+
+			let payload = versioned_statement_message(
+				relay_parent,
+				stored.statement.clone(),
+				version.into(),
+				metrics,
+			);
+			ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(recipients, payload))
+				.await;
+		}
 	}
 
-	peers_to_send
+	let needs_dependents = peers_to_send
 		.into_iter()
-		.filter_map(|(peer, needs_dependent)| if needs_dependent { Some(peer) } else { None })
-		.collect()
+		.filter_map(|(peer, _, needs_dependent)| if needs_dependent { Some(peer) } else { None })
+		.collect();
+
+	CirculationOutcome { needs_dependents, needs_manifest }
 }
 
 /// Send all statements about a given candidate hash to a peer.
@@ -1118,7 +1373,12 @@ async fn send_statements_about<Context>(
 			continue
 		}
 		peer_data.send(&relay_parent, &fingerprint);
-		let payload = statement_message(relay_parent, statement.statement.clone(), metrics);
+		let payload = versioned_statement_message(
+			relay_parent,
+			statement.statement.clone(),
+			peer_data.protocol_version,
+			metrics,
+		);
 
 		gum::trace!(
 			target: LOG_TARGET,
@@ -1150,8 +1410,32 @@ async fn send_statements<Context>(
 		if !peer_data.can_send(&relay_parent, &fingerprint) {
 			continue
 		}
+
+		if let CompactStatement::Seconded(candidate_hash) = statement.compact() {
+			if peer_data.wants_manifests() &&
+				!peer_data.knows_candidate(&relay_parent, candidate_hash)
+			{
+				send_manifest(
+					peer,
+					peer_data,
+					ctx,
+					relay_parent,
+					*candidate_hash,
+					active_head,
+					metrics,
+				)
+				.await;
+				continue
+			}
+		}
+
 		peer_data.send(&relay_parent, &fingerprint);
-		let payload = statement_message(relay_parent, statement.statement.clone(), metrics);
+		let payload = versioned_statement_message(
+			relay_parent,
+			statement.statement.clone(),
+			peer_data.protocol_version,
+			metrics,
+		);
 
 		gum::trace!(
 			target: LOG_TARGET,
@@ -1167,6 +1451,268 @@ async fn send_statements<Context>(
 	}
 }
 
+/// Announce a candidate to a peer on the staging protocol via a manifest, unless it was
+/// announced to the peer already.
+///
+/// Falls back to sending all statements about the candidate if we don't know its backing group.
+#[overseer::contextbounds(StatementDistribution, prefix=self::overseer)]
+async fn send_manifest<Context>(
+	peer: PeerId,
+	peer_data: &mut PeerData,
+	ctx: &mut Context,
+	relay_parent: Hash,
+	candidate_hash: CandidateHash,
+	active_head: &ActiveHeadData,
+	metrics: &Metrics,
+) {
+	let knowledge = match peer_data.view_knowledge.get_mut(&relay_parent) {
+		Some(knowledge) => knowledge,
+		None => return,
+	};
+
+	if knowledge.sent_manifests.contains(&candidate_hash) {
+		return
+	}
+
+	let manifest = match active_head.candidate_manifest(relay_parent, candidate_hash) {
+		Some(manifest) => manifest,
+		None => {
+			send_statements_about(
+				peer,
+				peer_data,
+				ctx,
+				relay_parent,
+				candidate_hash,
+				active_head,
+				metrics,
+			)
+			.await;
+			return
+		},
+	};
+
+	knowledge.sent_manifests.insert(candidate_hash);
+
+	gum::trace!(target: LOG_TARGET, ?peer, ?relay_parent, ?candidate_hash, "Sending manifest");
+	ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(
+		vec![peer],
+		Versioned::VStaging(
+			protocol_vstaging::StatementDistributionMessage::BackedCandidateManifest(manifest)
+				.into(),
+		),
+	))
+	.await;
+
+	metrics.on_manifest_sent();
+}
+
+/// Announce a candidate via manifests to all given peers.
+#[overseer::contextbounds(StatementDistribution, prefix=self::overseer)]
+async fn send_manifests<Context>(
+	peers_to_announce: Vec<PeerId>,
+	peers: &mut HashMap<PeerId, PeerData>,
+	ctx: &mut Context,
+	relay_parent: Hash,
+	candidate_hash: CandidateHash,
+	active_head: &ActiveHeadData,
+	metrics: &Metrics,
+) {
+	for peer in peers_to_announce {
+		// defensive: the peer data should always be some because the iterator
+		// of peers is derived from the set of peers.
+		if let Some(peer_data) = peers.get_mut(&peer) {
+			send_manifest(peer, peer_data, ctx, relay_parent, candidate_hash, active_head, metrics)
+				.await;
+		}
+	}
+}
+
+/// Handle a manifest announcing a candidate from a peer on the staging protocol.
+///
+/// If the manifest is acceptable, we acknowledge it with the statements we know about the
+/// candidate and send the peer all statements it is missing. The peer then sends us the
+/// statements we are missing in turn.
+#[overseer::contextbounds(StatementDistribution, prefix=self::overseer)]
+async fn handle_incoming_manifest<Context>(
+	peer: PeerId,
+	peers: &mut HashMap<PeerId, PeerData>,
+	active_heads: &HashMap<Hash, ActiveHeadData>,
+	recent_outdated_heads: &RecentOutdatedHeads,
+	ctx: &mut Context,
+	manifest: protocol_vstaging::BackedCandidateManifest,
+	metrics: &Metrics,
+) {
+	let relay_parent = manifest.relay_parent;
+	let candidate_hash = manifest.candidate_hash;
+	let _ = metrics.time_network_bridge_update_v1("handle_incoming_manifest");
+
+	let peer_data = match peers.get_mut(&peer) {
+		Some(peer_data) => peer_data,
+		None => return,
+	};
+
+	let active_head = match active_heads.get(&relay_parent) {
+		Some(h) => h,
+		None => {
+			gum::debug!(
+				target: LOG_TARGET,
+				%relay_parent,
+				"our view out-of-sync with active heads; head not found",
+			);
+
+			if !recent_outdated_heads.is_recent_outdated(&relay_parent) {
+				report_peer(ctx.sender(), peer, COST_UNEXPECTED_MANIFEST).await;
+			}
+
+			return
+		},
+	};
+
+	let group = match active_head.groups.get(manifest.group_index) {
+		Some(group)
+			if manifest.statement_knowledge.has_len(group.len()) &&
+				manifest.statement_knowledge.has_seconded() =>
+			group,
+		_ => {
+			gum::debug!(target: LOG_TARGET, ?peer, ?manifest, "Malformed manifest");
+			report_peer(ctx.sender(), peer, COST_MALFORMED_MANIFEST).await;
+			return
+		},
+	};
+
+	// The peer must agree with the group and para we determined for the candidate.
+	if let Some(known) = active_head.candidate_groups.get(&candidate_hash) {
+		if known != &(manifest.group_index, manifest.para_id) {
+			gum::debug!(target: LOG_TARGET, ?peer, ?manifest, "Manifest for wrong group");
+			report_peer(ctx.sender(), peer, COST_MALFORMED_MANIFEST).await;
+			return
+		}
+	}
+
+	let knowledge = match peer_data.view_knowledge.get_mut(&relay_parent) {
+		Some(knowledge) => knowledge,
+		None => {
+			report_peer(ctx.sender(), peer, COST_UNEXPECTED_MANIFEST).await;
+			return
+		},
+	};
+
+	let max_manifests = active_head.validators.len() * VC_THRESHOLD;
+	if let Err(rep) = knowledge.receive_manifest(candidate_hash, max_manifests) {
+		gum::debug!(target: LOG_TARGET, ?peer, ?manifest, ?rep, "Unexpected manifest");
+		report_peer(ctx.sender(), peer, rep).await;
+		return
+	}
+
+	knowledge.note_statement_knowledge(candidate_hash, group, &manifest.statement_knowledge);
+	metrics.on_manifest_received();
+
+	let acknowledgement = protocol_vstaging::BackedCandidateAcknowledgement {
+		relay_parent,
+		candidate_hash,
+		statement_knowledge: active_head.statement_filter(candidate_hash, group),
+	};
+
+	gum::trace!(
+		target: LOG_TARGET,
+		?peer,
+		?relay_parent,
+		?candidate_hash,
+		"Acknowledging manifest"
+	);
+	ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(
+		vec![peer],
+		Versioned::VStaging(
+			protocol_vstaging::StatementDistributionMessage::BackedCandidateKnown(acknowledgement)
+				.into(),
+		),
+	))
+	.await;
+
+	send_statements_about(peer, peer_data, ctx, relay_parent, candidate_hash, active_head, metrics)
+		.await;
+}
+
+/// Handle an acknowledgement of a manifest we sent to a peer on the staging protocol, by
+/// sending the peer all statements it is missing.
+#[overseer::contextbounds(StatementDistribution, prefix=self::overseer)]
+async fn handle_incoming_acknowledgement<Context>(
+	peer: PeerId,
+	peers: &mut HashMap<PeerId, PeerData>,
+	active_heads: &HashMap<Hash, ActiveHeadData>,
+	recent_outdated_heads: &RecentOutdatedHeads,
+	ctx: &mut Context,
+	acknowledgement: protocol_vstaging::BackedCandidateAcknowledgement,
+	metrics: &Metrics,
+) {
+	let relay_parent = acknowledgement.relay_parent;
+	let candidate_hash = acknowledgement.candidate_hash;
+	let _ = metrics.time_network_bridge_update_v1("handle_incoming_acknowledgement");
+
+	let peer_data = match peers.get_mut(&peer) {
+		Some(peer_data) => peer_data,
+		None => return,
+	};
+
+	let active_head = match active_heads.get(&relay_parent) {
+		Some(h) => h,
+		None => {
+			if !recent_outdated_heads.is_recent_outdated(&relay_parent) {
+				report_peer(ctx.sender(), peer, COST_UNEXPECTED_ACKNOWLEDGEMENT).await;
+			}
+
+			return
+		},
+	};
+
+	let group = match active_head
+		.candidate_groups
+		.get(&candidate_hash)
+		.and_then(|(group_index, _)| active_head.groups.get(*group_index))
+	{
+		Some(group) => group,
+		None => {
+			report_peer(ctx.sender(), peer, COST_UNEXPECTED_ACKNOWLEDGEMENT).await;
+			return
+		},
+	};
+
+	if !acknowledgement.statement_knowledge.has_len(group.len()) {
+		gum::debug!(target: LOG_TARGET, ?peer, ?acknowledgement, "Malformed acknowledgement");
+		report_peer(ctx.sender(), peer, COST_MALFORMED_MANIFEST).await;
+		return
+	}
+
+	let knowledge = match peer_data.view_knowledge.get_mut(&relay_parent) {
+		Some(knowledge) => knowledge,
+		None => {
+			report_peer(ctx.sender(), peer, COST_UNEXPECTED_ACKNOWLEDGEMENT).await;
+			return
+		},
+	};
+
+	if let Err(rep) = knowledge.receive_acknowledgement(candidate_hash) {
+		gum::debug!(
+			target: LOG_TARGET,
+			?peer,
+			?acknowledgement,
+			?rep,
+			"Unexpected acknowledgement"
+		);
+		report_peer(ctx.sender(), peer, rep).await;
+		return
+	}
+
+	knowledge.note_statement_knowledge(
+		candidate_hash,
+		group,
+		&acknowledgement.statement_knowledge,
+	);
+
+	send_statements_about(peer, peer_data, ctx, relay_parent, candidate_hash, active_head, metrics)
+		.await;
+}
+
 async fn report_peer(
 	sender: &mut impl overseer::StatementDistributionSenderTrait,
 	peer: PeerId,
@@ -1366,8 +1912,9 @@ async fn handle_incoming_message_and_circulate<'a, Context, R>(
 		};
 		let required_routing =
 			topology.required_routing_by_index(statement.statement.validator_index(), false);
+		let candidate_hash = *statement.compact().candidate_hash();
 
-		let _ = circulate_statement(
+		let outcome = circulate_statement(
 			required_routing,
 			topology,
 			peers,
@@ -1379,6 +1926,19 @@ async fn handle_incoming_message_and_circulate<'a, Context, R>(
 			rng,
 		)
 		.await;
+
+		if let Some(active_head) = active_heads.get(&relay_parent) {
+			send_manifests(
+				outcome.needs_manifest,
+				peers,
+				ctx,
+				relay_parent,
+				candidate_hash,
+				active_head,
+				metrics,
+			)
+			.await;
+		}
 	}
 }
 
@@ -1645,14 +2205,15 @@ async fn handle_network_update<Context, R>(
 	R: rand::Rng,
 {
 	match update {
-		NetworkBridgeEvent::PeerConnected(peer, role, _, maybe_authority) => {
-			gum::trace!(target: LOG_TARGET, ?peer, ?role, "Peer connected");
+		NetworkBridgeEvent::PeerConnected(peer, role, protocol_version, maybe_authority) => {
+			gum::trace!(target: LOG_TARGET, ?peer, ?role, ?protocol_version, "Peer connected");
 			peers.insert(
 				peer,
 				PeerData {
 					view: Default::default(),
 					view_knowledge: Default::default(),
 					maybe_authority: maybe_authority.clone(),
+					protocol_version,
 				},
 			);
 			if let Some(authority_ids) = maybe_authority {
@@ -1716,6 +2277,62 @@ async fn handle_network_update<Context, R>(
 			)
 			.await;
 		},
+		NetworkBridgeEvent::PeerMessage(peer, Versioned::VStaging(message)) => match message {
+			protocol_vstaging::StatementDistributionMessage::Statement(relay_parent, statement) =>
+				handle_incoming_message_and_circulate(
+					peer,
+					topology_storage,
+					peers,
+					active_heads,
+					recent_outdated_heads,
+					ctx,
+					protocol_v1::StatementDistributionMessage::Statement(relay_parent, statement),
+					req_sender,
+					metrics,
+					runtime,
+					rng,
+				)
+				.await,
+			protocol_vstaging::StatementDistributionMessage::V1Compatibility(message) =>
+				handle_incoming_message_and_circulate(
+					peer,
+					topology_storage,
+					peers,
+					active_heads,
+					recent_outdated_heads,
+					ctx,
+					message,
+					req_sender,
+					metrics,
+					runtime,
+					rng,
+				)
+				.await,
+			protocol_vstaging::StatementDistributionMessage::BackedCandidateManifest(manifest) =>
+				handle_incoming_manifest(
+					peer,
+					peers,
+					&*active_heads,
+					recent_outdated_heads,
+					ctx,
+					manifest,
+					metrics,
+				)
+				.await,
+			protocol_vstaging::StatementDistributionMessage::BackedCandidateKnown(
+				acknowledgement,
+			) =>
+				handle_incoming_acknowledgement(
+					peer,
+					peers,
+					&*active_heads,
+					recent_outdated_heads,
+					ctx,
+					acknowledgement,
+					metrics,
+				)
+				.await,
+		},
 		NetworkBridgeEvent::PeerViewChange(peer, view) => {
 			let _ = metrics.time_network_bridge_update_v1("peer_view_change");
 			gum::trace!(target: LOG_TARGET, ?peer, ?view, "Peer view change");
@@ -2032,6 +2649,7 @@ impl<R: rand::Rng> StatementDistributionSubsystem<R> {
 
 					active_heads.entry(relay_parent).or_insert(ActiveHeadData::new(
 						session_info.validators.clone(),
+						session_info.validator_groups.clone(),
 						session_index,
 						span,
 					));
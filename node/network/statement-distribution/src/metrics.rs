@@ -32,6 +32,7 @@ struct MetricsInner {
 	network_bridge_update_v1: prometheus::HistogramVec,
 	statements_unexpected: prometheus::CounterVec<prometheus::U64>,
 	created_message_size: prometheus::Gauge<prometheus::U64>,
+	manifests: prometheus::CounterVec<prometheus::U64>,
 }
 
 /// Statement Distribution metrics.
@@ -111,6 +112,20 @@ impl Metrics {
 		}
 	}
 
+	/// Update the manifests counter for a manifest sent to a peer
+	pub fn on_manifest_sent(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.manifests.with_label_values(&["sent"]).inc();
+		}
+	}
+
+	/// Update the manifests counter for a manifest received from a peer
+	pub fn on_manifest_received(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.manifests.with_label_values(&["received"]).inc();
+		}
+	}
+
 	/// Report size of a created message.
 	pub fn on_created_message(&self, size: usize) {
 		if let Some(metrics) = &self.0 {
@@ -196,6 +211,16 @@ impl metrics::Metrics for Metrics {
 				))?,
 				registry,
 			)?,
+			manifests: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_statement_distribution_manifests_total",
+						"Number of candidate manifests exchanged with staging protocol peers.",
+					),
+					&["direction"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
@@ -86,6 +86,7 @@ fn active_head_accepts_only_2_seconded_per_validator() {
 
 	let mut head_data = ActiveHeadData::new(
 		IndexedVec::<ValidatorIndex, ValidatorId>::from(validators),
+		Default::default(),
 		session_index,
 		PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
 	);
@@ -432,6 +433,7 @@ fn peer_view_update_sends_messages() {
 	let new_head_data = {
 		let mut data = ActiveHeadData::new(
 			IndexedVec::<ValidatorIndex, ValidatorId>::from(validators),
+			Default::default(),
 			session_index,
 			PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
 		);
@@ -502,6 +504,7 @@ fn peer_view_update_sends_messages() {
 			k
 		},
 		maybe_authority: None,
+		protocol_version: ValidationVersion::V1.into(),
 	};
 
 	let pool = sp_core::testing::TaskExecutor::new();
@@ -595,6 +598,7 @@ fn circulated_statement_goes_to_all_peers_with_view() {
 		view: view.clone(),
 		view_knowledge: view.iter().map(|v| (v.clone(), Default::default())).collect(),
 		maybe_authority: None,
+		protocol_version: ValidationVersion::V1.into(),
 	};
 
 	let mut peer_data: HashMap<_, _> = vec![
@@ -645,7 +649,7 @@ fn circulated_statement_goes_to_all_peers_with_view() {
 		let mut topology = GridNeighbors::empty();
 		topology.peers_x =
 			HashSet::from_iter(vec![peer_a.clone(), peer_b.clone(), peer_c.clone()].into_iter());
-		let needs_dependents = circulate_statement(
+		let outcome = circulate_statement(
 			RequiredRouting::GridXY,
 			&topology,
 			&mut peer_data,
@@ -659,9 +663,10 @@ fn circulated_statement_goes_to_all_peers_with_view() {
 		.await;
 
 		{
-			assert_eq!(needs_dependents.len(), 2);
-			assert!(needs_dependents.contains(&peer_b));
-			assert!(needs_dependents.contains(&peer_c));
+			assert_eq!(outcome.needs_dependents.len(), 2);
+			assert!(outcome.needs_dependents.contains(&peer_b));
+			assert!(outcome.needs_dependents.contains(&peer_c));
+			assert!(outcome.needs_manifest.is_empty());
 		}
 
 		let fingerprint = (statement.compact().clone(), ValidatorIndex(0));
@@ -704,6 +709,360 @@ fn circulated_statement_goes_to_all_peers_with_view() {
 	});
 }
 
+#[test]
+fn seconded_statement_is_announced_to_vstaging_peers_via_manifest() {
+	let hash_a = Hash::repeat_byte(1);
+
+	let candidate = {
+		let mut c = dummy_committed_candidate_receipt(dummy_hash());
+		c.descriptor.relay_parent = hash_a;
+		c.descriptor.para_id = ParaId::from(1_u32);
+		c
+	};
+	let candidate_hash = candidate.hash();
+
+	let peer_a = PeerId::random();
+	let peer_b = PeerId::random();
+
+	let session_index = 1;
+	let validators = vec![
+		Sr25519Keyring::Alice.public().into(),
+		Sr25519Keyring::Bob.public().into(),
+		Sr25519Keyring::Charlie.public().into(),
+	];
+
+	let mut head_data = ActiveHeadData::new(
+		IndexedVec::<ValidatorIndex, ValidatorId>::from(validators),
+		IndexedVec::<GroupIndex, Vec<ValidatorIndex>>::from(vec![
+			vec![ValidatorIndex(0), ValidatorIndex(1)],
+			vec![ValidatorIndex(2)],
+		]),
+		session_index,
+		PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
+	);
+
+	let peer_data_with_version = |version: ValidationVersion| PeerData {
+		view: view![hash_a],
+		view_knowledge: vec![(hash_a, Default::default())].into_iter().collect(),
+		maybe_authority: None,
+		protocol_version: version.into(),
+	};
+
+	let mut peer_data: HashMap<_, _> = vec![
+		(peer_a, peer_data_with_version(ValidationVersion::V1)),
+		(peer_b, peer_data_with_version(ValidationVersion::VStaging)),
+	]
+	.into_iter()
+	.collect();
+
+	let pool = sp_core::testing::TaskExecutor::new();
+	let (mut ctx, mut handle) = polkadot_node_subsystem_test_helpers::make_subsystem_context::<
+		StatementDistributionMessage,
+		_,
+	>(pool);
+
+	executor::block_on(async move {
+		let signing_context = SigningContext { parent_hash: hash_a, session_index };
+
+		let keystore: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+		let alice_public = CryptoStore::sr25519_generate_new(
+			&*keystore,
+			ValidatorId::ID,
+			Some(&Sr25519Keyring::Alice.to_seed()),
+		)
+		.await
+		.unwrap();
+
+		let statement = SignedFullStatement::sign(
+			&keystore,
+			Statement::Seconded(candidate),
+			&signing_context,
+			ValidatorIndex(0),
+			&alice_public.into(),
+		)
+		.await
+		.ok()
+		.flatten()
+		.expect("should be signed");
+
+		let stored = assert_matches!(
+			head_data.note_statement(statement.clone()),
+			NotedStatement::Fresh(stored) => stored
+		);
+
+		let mut topology = GridNeighbors::empty();
+		topology.peers_x = HashSet::from_iter(vec![peer_a, peer_b].into_iter());
+		let outcome = circulate_statement(
+			RequiredRouting::GridXY,
+			&topology,
+			&mut peer_data,
+			&mut ctx,
+			hash_a,
+			stored,
+			Vec::new(),
+			&Metrics::default(),
+			&mut AlwaysZeroRng,
+		)
+		.await;
+
+		assert_eq!(outcome.needs_dependents, vec![peer_a]);
+		assert_eq!(outcome.needs_manifest, vec![peer_b]);
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+				to,
+				payload,
+			)) => {
+				assert_eq!(to, vec![peer_a]);
+				assert_eq!(
+					payload,
+					statement_message(hash_a, statement.clone(), &Metrics::default()),
+				);
+			}
+		);
+
+		send_manifests(
+			outcome.needs_manifest,
+			&mut peer_data,
+			&mut ctx,
+			hash_a,
+			candidate_hash,
+			&head_data,
+			&Metrics::default(),
+		)
+		.await;
+
+		let mut statement_knowledge = StatementFilter::blank(2);
+		statement_knowledge.note(0, true);
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+				to,
+				Versioned::VStaging(protocol_vstaging::ValidationProtocol::StatementDistribution(
+					protocol_vstaging::StatementDistributionMessage::BackedCandidateManifest(
+						manifest,
+					),
+				)),
+			)) => {
+				assert_eq!(to, vec![peer_b]);
+				assert_eq!(
+					manifest,
+					protocol_vstaging::BackedCandidateManifest {
+						relay_parent: hash_a,
+						candidate_hash,
+						group_index: GroupIndex(0),
+						para_id: ParaId::from(1_u32),
+						statement_knowledge,
+					},
+				);
+			}
+		);
+
+		let knowledge = peer_data.get(&peer_b).unwrap().view_knowledge.get(&hash_a).unwrap();
+		assert!(knowledge.sent_manifests.contains(&candidate_hash));
+		assert!(!knowledge.is_known_candidate(&candidate_hash));
+	});
+}
+
+#[test]
+fn manifest_is_acknowledged_and_missing_statements_are_sent() {
+	let hash_a = Hash::repeat_byte(1);
+
+	let candidate = {
+		let mut c = dummy_committed_candidate_receipt(dummy_hash());
+		c.descriptor.relay_parent = hash_a;
+		c.descriptor.para_id = ParaId::from(1_u32);
+		c
+	};
+	let candidate_hash = candidate.hash();
+
+	let peer = PeerId::random();
+
+	let session_index = 1;
+	let validators = vec![
+		Sr25519Keyring::Alice.public().into(),
+		Sr25519Keyring::Bob.public().into(),
+		Sr25519Keyring::Charlie.public().into(),
+	];
+
+	let mut head_data = ActiveHeadData::new(
+		IndexedVec::<ValidatorIndex, ValidatorId>::from(validators),
+		IndexedVec::<GroupIndex, Vec<ValidatorIndex>>::from(vec![
+			vec![ValidatorIndex(0), ValidatorIndex(1)],
+			vec![ValidatorIndex(2)],
+		]),
+		session_index,
+		PerLeafSpan::new(Arc::new(jaeger::Span::Disabled), "test"),
+	);
+
+	let mut peer_data: HashMap<_, _> = vec![(
+		peer,
+		PeerData {
+			view: view![hash_a],
+			view_knowledge: vec![(hash_a, Default::default())].into_iter().collect(),
+			maybe_authority: None,
+			protocol_version: ValidationVersion::VStaging.into(),
+		},
+	)]
+	.into_iter()
+	.collect();
+
+	let pool = sp_core::testing::TaskExecutor::new();
+	let (mut ctx, mut handle) = polkadot_node_subsystem_test_helpers::make_subsystem_context::<
+		StatementDistributionMessage,
+		_,
+	>(pool);
+
+	executor::block_on(async move {
+		let signing_context = SigningContext { parent_hash: hash_a, session_index };
+
+		let keystore: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+		let alice_public = CryptoStore::sr25519_generate_new(
+			&*keystore,
+			ValidatorId::ID,
+			Some(&Sr25519Keyring::Alice.to_seed()),
+		)
+		.await
+		.unwrap();
+		let bob_public = CryptoStore::sr25519_generate_new(
+			&*keystore,
+			ValidatorId::ID,
+			Some(&Sr25519Keyring::Bob.to_seed()),
+		)
+		.await
+		.unwrap();
+
+		let seconded = SignedFullStatement::sign(
+			&keystore,
+			Statement::Seconded(candidate),
+			&signing_context,
+			ValidatorIndex(0),
+			&alice_public.into(),
+		)
+		.await
+		.ok()
+		.flatten()
+		.expect("should be signed");
+
+		let valid = SignedFullStatement::sign(
+			&keystore,
+			Statement::Valid(candidate_hash),
+			&signing_context,
+			ValidatorIndex(1),
+			&bob_public.into(),
+		)
+		.await
+		.ok()
+		.flatten()
+		.expect("should be signed");
+
+		assert_matches!(head_data.note_statement(seconded), NotedStatement::Fresh(_));
+		assert_matches!(head_data.note_statement(valid.clone()), NotedStatement::Fresh(_));
+
+		let mut active_heads = HashMap::new();
+		active_heads.insert(hash_a, head_data);
+		let recent_outdated_heads = RecentOutdatedHeads::default();
+
+		// The peer only knows the `Seconded` statement.
+		let mut peer_knowledge = StatementFilter::blank(2);
+		peer_knowledge.note(0, true);
+		let manifest = protocol_vstaging::BackedCandidateManifest {
+			relay_parent: hash_a,
+			candidate_hash,
+			group_index: GroupIndex(0),
+			para_id: ParaId::from(1_u32),
+			statement_knowledge: peer_knowledge,
+		};
+
+		handle_incoming_manifest(
+			peer,
+			&mut peer_data,
+			&active_heads,
+			&recent_outdated_heads,
+			&mut ctx,
+			manifest.clone(),
+			&Metrics::default(),
+		)
+		.await;
+
+		let mut our_knowledge = StatementFilter::blank(2);
+		our_knowledge.note(0, true);
+		our_knowledge.note(1, false);
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+				to,
+				Versioned::VStaging(protocol_vstaging::ValidationProtocol::StatementDistribution(
+					protocol_vstaging::StatementDistributionMessage::BackedCandidateKnown(
+						acknowledgement,
+					),
+				)),
+			)) => {
+				assert_eq!(to, vec![peer]);
+				assert_eq!(acknowledgement.candidate_hash, candidate_hash);
+				assert_eq!(acknowledgement.statement_knowledge, our_knowledge);
+			}
+		);
+
+		// Only the statement the peer is missing is sent.
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+				to,
+				payload,
+			)) => {
+				assert_eq!(to, vec![peer]);
+				assert_eq!(payload, vstaging_statement_message(hash_a, valid, &Metrics::default()));
+			}
+		);
+
+		// Announcing the same candidate twice is punished.
+		handle_incoming_manifest(
+			peer,
+			&mut peer_data,
+			&active_heads,
+			&recent_outdated_heads,
+			&mut ctx,
+			manifest,
+			&Metrics::default(),
+		)
+		.await;
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::NetworkBridgeTx(
+				NetworkBridgeTxMessage::ReportPeer(p, r)
+			) if p == peer && r == COST_DUPLICATE_MANIFEST => {}
+		);
+
+		// We never announced the candidate, so an acknowledgement is unexpected.
+		handle_incoming_acknowledgement(
+			peer,
+			&mut peer_data,
+			&active_heads,
+			&recent_outdated_heads,
+			&mut ctx,
+			protocol_vstaging::BackedCandidateAcknowledgement {
+				relay_parent: hash_a,
+				candidate_hash,
+				statement_knowledge: StatementFilter::blank(2),
+			},
+			&Metrics::default(),
+		)
+		.await;
+
+		assert_matches!(
+			handle.recv().await,
+			AllMessages::NetworkBridgeTx(
+				NetworkBridgeTxMessage::ReportPeer(p, r)
+			) if p == peer && r == COST_UNEXPECTED_ACKNOWLEDGEMENT => {}
+		);
+	});
+}
+
 #[test]
 fn receiving_from_one_sends_to_another_and_to_candidate_backing() {
 	let hash_a = Hash::repeat_byte(1);
@@ -36,6 +36,7 @@ mod tests;
 use {
 	grandpa::{self, FinalityProofProvider as GrandpaFinalityProofProvider},
 	gum::info,
+	polkadot_dispute_distribution::Config as DisputeDistributionConfig,
 	polkadot_node_core_approval_voting::{
 		self as approval_voting_subsystem, Config as ApprovalVotingConfig,
 	},
@@ -447,6 +448,7 @@ fn new_partial<RuntimeApi, ExecutorDispatch, ChainSelection>(
 		ExecutorDispatch,
 	>,
 	select_chain: ChainSelection,
+	overseer_handle: Option<Handle>,
 ) -> Result<
 	service::PartialComponents<
 		FullClient<RuntimeApi, ExecutorDispatch>,
@@ -596,6 +598,7 @@ where
 					beefy_best_block_stream: beefy_rpc_links.from_voter_best_beefy_stream.clone(),
 					subscription_executor,
 				},
+				overseer_handle: overseer_handle.clone(),
 			};
 
 			polkadot_rpc::create_full(deps, backend.clone()).map_err(Into::into)
@@ -809,6 +812,11 @@ where
 
 	let pvf_checker_enabled = role.is_authority() && !is_collator.is_collator();
 
+	// The overseer only runs with a local keystore and authority discovery, see `maybe_params`.
+	let rpc_overseer_handle = (local_keystore.is_some() &&
+		(auth_or_collator || overseer_enable_anyways))
+		.then(|| overseer_handle.clone());
+
 	let select_chain = if requires_overseer_for_chain_sel {
 		let metrics =
 			polkadot_node_subsystem_util::metrics::Metrics::register(prometheus_registry.as_ref())?;
@@ -835,6 +843,7 @@ where
 		&mut config,
 		basics,
 		select_chain,
+		rpc_overseer_handle,
 	)?;
 
 	let shared_voter_state = rpc_setup;
@@ -899,6 +908,9 @@ where
 	config.network.request_response_protocols.push(cfg);
 	let (dispute_req_receiver, cfg) = IncomingRequest::get_config_receiver(&req_protocol_names);
 	config.network.request_response_protocols.push(cfg);
+	let (dispute_batch_req_receiver, cfg) =
+		IncomingRequest::get_config_receiver(&req_protocol_names);
+	config.network.request_response_protocols.push(cfg);
 
 	let grandpa_hard_forks = if config.chain_spec.is_kusama() {
 		grandpa_support::kusama_hard_forks()
@@ -974,6 +986,10 @@ where
 		col_session_data: parachains_db::REAL_COLUMNS.col_session_window_data,
 	};
 
+	let dispute_distribution_config = DisputeDistributionConfig {
+		col_data: parachains_db::REAL_COLUMNS.col_dispute_distribution_data,
+	};
+
 	let rpc_handlers = service::spawn_tasks(service::SpawnTasksParams {
 		config,
 		backend: backend.clone(),
@@ -1082,6 +1098,7 @@ where
 					available_data_req_receiver,
 					statement_req_receiver,
 					dispute_req_receiver,
+					dispute_batch_req_receiver,
 					registry: prometheus_registry.as_ref(),
 					spawner,
 					is_collator,
@@ -1090,6 +1107,7 @@ where
 					candidate_validation_config,
 					chain_selection_config,
 					dispute_coordinator_config,
+					dispute_distribution_config,
 					pvf_checker_enabled,
 					overseer_message_channel_capacity_override,
 					req_protocol_names,
@@ -1337,6 +1355,7 @@ macro_rules! chain_ops {
 				&mut config,
 				basics,
 				chain_selection,
+				None,
 			)?;
 		Ok((Arc::new(Client::$variant(client)), backend, import_queue, task_manager))
 	}};
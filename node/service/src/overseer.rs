@@ -19,6 +19,7 @@ use sp_core::traits::SpawnNamed;
 
 use lru::LruCache;
 use polkadot_availability_distribution::IncomingRequestReceivers;
+use polkadot_dispute_distribution::Config as DisputeDistributionConfig;
 use polkadot_node_core_approval_voting::Config as ApprovalVotingConfig;
 use polkadot_node_core_av_store::Config as AvailabilityConfig;
 use polkadot_node_core_candidate_validation::Config as CandidateValidationConfig;
@@ -101,6 +102,7 @@ where
 		IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
 	pub statement_req_receiver: IncomingRequestReceiver<request_v1::StatementFetchingRequest>,
 	pub dispute_req_receiver: IncomingRequestReceiver<request_v1::DisputeRequest>,
+	pub dispute_batch_req_receiver: IncomingRequestReceiver<request_v1::DisputeBatchRequest>,
 	/// Prometheus registry, commonly used for production systems, less so for test.
 	pub registry: Option<&'a Registry>,
 	/// Task spawner to be used throughout the overseer and the APIs it provides.
@@ -117,6 +119,8 @@ where
 	pub chain_selection_config: ChainSelectionConfig,
 	/// Configuration for the dispute coordinator subsystem.
 	pub dispute_coordinator_config: DisputeCoordinatorConfig,
+	/// Configuration for the dispute distribution subsystem.
+	pub dispute_distribution_config: DisputeDistributionConfig,
 	/// Enable PVF pre-checking
 	pub pvf_checker_enabled: bool,
 	/// Overseer channel capacity override.
@@ -143,6 +147,7 @@ pub fn prepared_overseer_builder<Spawner, RuntimeClient>(
 		available_data_req_receiver,
 		statement_req_receiver,
 		dispute_req_receiver,
+		dispute_batch_req_receiver,
 		registry,
 		spawner,
 		is_collator,
@@ -151,6 +156,7 @@ pub fn prepared_overseer_builder<Spawner, RuntimeClient>(
 		candidate_validation_config,
 		chain_selection_config,
 		dispute_coordinator_config,
+		dispute_distribution_config,
 		pvf_checker_enabled,
 		overseer_message_channel_capacity_override,
 		req_protocol_names,
@@ -305,7 +311,10 @@ where
 		.dispute_distribution(DisputeDistributionSubsystem::new(
 			keystore.clone(),
 			dispute_req_receiver,
+			dispute_batch_req_receiver,
 			authority_discovery_service.clone(),
+			parachains_db.clone(),
+			dispute_distribution_config,
 			Metrics::register(registry)?,
 		))
 		.chain_selection(ChainSelectionSubsystem::new(chain_selection_config, parachains_db))
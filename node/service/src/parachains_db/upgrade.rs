@@ -28,7 +28,7 @@ type Version = u32;
 const VERSION_FILE_NAME: &'static str = "parachain_db_version";
 
 /// Current db version.
-const CURRENT_VERSION: Version = 2;
+const CURRENT_VERSION: Version = 3;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -53,22 +53,28 @@ impl From<Error> for io::Error {
 pub(crate) fn try_upgrade_db(db_path: &Path, db_kind: DatabaseKind) -> Result<(), Error> {
 	let is_empty = db_path.read_dir().map_or(true, |mut d| d.next().is_none());
 	if !is_empty {
-		match get_db_version(db_path)? {
-			// 0 -> 1 migration
-			Some(0) => migrate_from_version_0_to_1(db_path, db_kind)?,
-			// 1 -> 2 migration
-			Some(1) => migrate_from_version_1_to_2(db_path, db_kind)?,
-			// Already at current version, do nothing.
-			Some(CURRENT_VERSION) => (),
+		let version = match get_db_version(db_path)? {
 			// This is an arbitrary future version, we don't handle it.
-			Some(v) => return Err(Error::FutureVersion { current: CURRENT_VERSION, got: v }),
+			Some(v) if v > CURRENT_VERSION =>
+				return Err(Error::FutureVersion { current: CURRENT_VERSION, got: v }),
+			Some(v) => v,
 			// No version file. For `RocksDB` we dont need to do anything.
-			None if db_kind == DatabaseKind::RocksDB => (),
+			None if db_kind == DatabaseKind::RocksDB => CURRENT_VERSION,
 			// No version file. `ParityDB` did not previously have a version defined.
-			// We handle this as a `0 -> 1` migration.
-			None if db_kind == DatabaseKind::ParityDB =>
-				migrate_from_version_0_to_1(db_path, db_kind)?,
+			// We handle this as version 0.
+			None if db_kind == DatabaseKind::ParityDB => 0,
 			None => unreachable!(),
+		};
+
+		// Migrations are chained, so the database ends up at the current version:
+		if version < 1 {
+			migrate_from_version_0_to_1(db_path, db_kind)?;
+		}
+		if version < 2 {
+			migrate_from_version_1_to_2(db_path, db_kind)?;
+		}
+		if version < 3 {
+			migrate_from_version_2_to_3(db_path, db_kind)?;
 		}
 	}
 
@@ -127,6 +133,19 @@ fn migrate_from_version_1_to_2(path: &Path, db_kind: DatabaseKind) -> Result<(),
 	})
 }
 
+fn migrate_from_version_2_to_3(path: &Path, db_kind: DatabaseKind) -> Result<(), Error> {
+	gum::info!(target: LOG_TARGET, "Migrating parachains db from version 2 to version 3 ...");
+
+	match db_kind {
+		DatabaseKind::ParityDB => paritydb_migrate_from_version_2_to_3(path),
+		DatabaseKind::RocksDB => rocksdb_migrate_from_version_2_to_3(path),
+	}
+	.and_then(|result| {
+		gum::info!(target: LOG_TARGET, "Migration complete! ");
+		Ok(result)
+	})
+}
+
 /// Migration from version 0 to version 1:
 /// * the number of columns has changed from 3 to 5;
 fn rocksdb_migrate_from_version_0_to_1(path: &Path) -> Result<(), Error> {
@@ -160,6 +179,22 @@ fn rocksdb_migrate_from_version_1_to_2(path: &Path) -> Result<(), Error> {
 	Ok(())
 }
 
+/// Migration from version 2 to version 3:
+/// * the number of columns has changed from 6 to 7;
+fn rocksdb_migrate_from_version_2_to_3(path: &Path) -> Result<(), Error> {
+	use kvdb_rocksdb::{Database, DatabaseConfig};
+
+	let db_path = path
+		.to_str()
+		.ok_or_else(|| super::other_io_error("Invalid database path".into()))?;
+	let db_cfg = DatabaseConfig::with_columns(super::columns::v2::NUM_COLUMNS);
+	let mut db = Database::open(&db_cfg, db_path)?;
+
+	db.add_column()?;
+
+	Ok(())
+}
+
 // This currently clears columns which had their configs altered between versions.
 // The columns to be changed are constrained by the `allowed_columns` vector.
 fn paritydb_fix_columns(
@@ -239,6 +274,17 @@ pub(crate) fn paritydb_version_2_config(path: &Path) -> parity_db::Options {
 	options
 }
 
+/// Database configuration for version 3.
+pub(crate) fn paritydb_version_3_config(path: &Path) -> parity_db::Options {
+	let mut options =
+		parity_db::Options::with_columns(&path, super::columns::v3::NUM_COLUMNS as u8);
+	for i in columns::v3::ORDERED_COL {
+		options.columns[*i as usize].btree_index = true;
+	}
+
+	options
+}
+
 /// Database configuration for version 0. This is useful just for testing.
 #[cfg(test)]
 pub(crate) fn paritydb_version_0_config(path: &Path) -> parity_db::Options {
@@ -278,9 +324,22 @@ fn paritydb_migrate_from_version_1_to_2(path: &Path) -> Result<(), Error> {
 	Ok(())
 }
 
+/// Migration from version 2 to version 3:
+/// - add a new (ordered) column for dispute distribution
+fn paritydb_migrate_from_version_2_to_3(path: &Path) -> Result<(), Error> {
+	let mut options = paritydb_version_2_config(path);
+
+	// Adds the dispute distribution column.
+	let column = parity_db::ColumnOptions { btree_index: true, ..Default::default() };
+	parity_db::Db::add_column(&mut options, column)
+		.map_err(|e| other_io_error(format!("Error adding column {:?}", e)))?;
+
+	Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-	use super::{columns::v2::*, *};
+	use super::{columns::v3::*, *};
 
 	#[test]
 	fn test_paritydb_migrate_0_to_1() {
@@ -300,7 +359,9 @@ mod tests {
 
 		try_upgrade_db(&path, DatabaseKind::ParityDB).unwrap();
 
-		let db = Db::open(&paritydb_version_1_config(&path)).unwrap();
+		// Subsequent migrations are applied as well:
+		let db = Db::open(&paritydb_version_3_config(&path)).unwrap();
+		assert_eq!(db.num_columns(), columns::v3::NUM_COLUMNS as u8);
 		assert_eq!(db.get(COL_DISPUTE_COORDINATOR_DATA as u8, b"1234").unwrap(), None);
 		assert_eq!(
 			db.get(COL_AVAILABILITY_META as u8, b"5678").unwrap(),
@@ -334,9 +395,9 @@ mod tests {
 
 		try_upgrade_db(&path, DatabaseKind::ParityDB).unwrap();
 
-		let db = Db::open(&paritydb_version_2_config(&path)).unwrap();
+		let db = Db::open(&paritydb_version_3_config(&path)).unwrap();
 
-		assert_eq!(db.num_columns(), columns::v2::NUM_COLUMNS as u8);
+		assert_eq!(db.num_columns(), columns::v3::NUM_COLUMNS as u8);
 
 		assert_eq!(
 			db.get(COL_DISPUTE_COORDINATOR_DATA as u8, b"1234").unwrap(),
@@ -388,12 +449,12 @@ mod tests {
 
 		try_upgrade_db(&db_dir.path(), DatabaseKind::RocksDB).unwrap();
 
-		let db_cfg = DatabaseConfig::with_columns(super::columns::v2::NUM_COLUMNS);
+		let db_cfg = DatabaseConfig::with_columns(super::columns::v3::NUM_COLUMNS);
 		let db = Database::open(&db_cfg, db_path).unwrap();
 
-		assert_eq!(db.num_columns(), super::columns::v2::NUM_COLUMNS);
+		assert_eq!(db.num_columns(), super::columns::v3::NUM_COLUMNS);
 
-		let db = DbAdapter::new(db, columns::v2::ORDERED_COL);
+		let db = DbAdapter::new(db, columns::v3::ORDERED_COL);
 
 		assert_eq!(
 			db.get(COL_DISPUTE_COORDINATOR_DATA, b"1234").unwrap(),
@@ -416,4 +477,109 @@ mod tests {
 			Some("0xdeadb00b".as_bytes().to_vec())
 		);
 	}
+
+	#[test]
+	fn test_paritydb_migrate_2_to_3() {
+		use parity_db::Db;
+
+		let db_dir = tempfile::tempdir().unwrap();
+		let path = db_dir.path();
+
+		// We need to properly set db version for upgrade to work.
+		fs::write(version_file_path(path), "2").expect("Failed to write DB version");
+
+		{
+			let db = Db::open_or_create(&paritydb_version_2_config(&path)).unwrap();
+
+			// Write some dummy data
+			db.commit(vec![(
+				COL_SESSION_WINDOW_DATA as u8,
+				b"1234".to_vec(),
+				Some(b"somevalue".to_vec()),
+			)])
+			.unwrap();
+
+			assert_eq!(db.num_columns(), columns::v2::NUM_COLUMNS as u8);
+		}
+
+		try_upgrade_db(&path, DatabaseKind::ParityDB).unwrap();
+
+		let db = Db::open(&paritydb_version_3_config(&path)).unwrap();
+
+		assert_eq!(db.num_columns(), columns::v3::NUM_COLUMNS as u8);
+
+		assert_eq!(
+			db.get(COL_SESSION_WINDOW_DATA as u8, b"1234").unwrap(),
+			Some("somevalue".as_bytes().to_vec())
+		);
+
+		// Test we can write the new column.
+		db.commit(vec![(
+			COL_DISPUTE_DISTRIBUTION_DATA as u8,
+			b"1337".to_vec(),
+			Some(b"0xdeadb00b".to_vec()),
+		)])
+		.unwrap();
+
+		// Read back data from new column.
+		assert_eq!(
+			db.get(COL_DISPUTE_DISTRIBUTION_DATA as u8, b"1337").unwrap(),
+			Some("0xdeadb00b".as_bytes().to_vec())
+		);
+	}
+
+	#[test]
+	fn test_rocksdb_migrate_2_to_3() {
+		use kvdb::{DBKey, DBOp};
+		use kvdb_rocksdb::{Database, DatabaseConfig};
+		use polkadot_node_subsystem_util::database::{
+			kvdb_impl::DbAdapter, DBTransaction, KeyValueDB,
+		};
+
+		let db_dir = tempfile::tempdir().unwrap();
+		let db_path = db_dir.path().to_str().unwrap();
+		let db_cfg = DatabaseConfig::with_columns(super::columns::v2::NUM_COLUMNS);
+		let db = Database::open(&db_cfg, db_path).unwrap();
+		assert_eq!(db.num_columns(), super::columns::v2::NUM_COLUMNS as u32);
+
+		// We need to properly set db version for upgrade to work.
+		fs::write(version_file_path(db_dir.path()), "2").expect("Failed to write DB version");
+		{
+			let db = DbAdapter::new(db, columns::v2::ORDERED_COL);
+			db.write(DBTransaction {
+				ops: vec![DBOp::Insert {
+					col: COL_SESSION_WINDOW_DATA,
+					key: DBKey::from_slice(b"1234"),
+					value: b"0xdeadb00b".to_vec(),
+				}],
+			})
+			.unwrap();
+		}
+
+		try_upgrade_db(&db_dir.path(), DatabaseKind::RocksDB).unwrap();
+
+		let db_cfg = DatabaseConfig::with_columns(super::columns::v3::NUM_COLUMNS);
+		let db = Database::open(&db_cfg, db_path).unwrap();
+
+		assert_eq!(db.num_columns(), super::columns::v3::NUM_COLUMNS);
+
+		let db = DbAdapter::new(db, columns::v3::ORDERED_COL);
+
+		assert_eq!(
+			db.get(COL_SESSION_WINDOW_DATA, b"1234").unwrap(),
+			Some("0xdeadb00b".as_bytes().to_vec())
+		);
+
+		// Test we can write and iterate the new column.
+		db.write(DBTransaction {
+			ops: vec![DBOp::Insert {
+				col: COL_DISPUTE_DISTRIBUTION_DATA,
+				key: DBKey::from_slice(b"1337"),
+				value: b"0xdeadb00b".to_vec(),
+			}],
+		})
+		.unwrap();
+
+		assert_eq!(db.iter_with_prefix(COL_DISPUTE_DISTRIBUTION_DATA, b"13").count(), 1);
+	}
 }
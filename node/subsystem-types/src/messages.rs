@@ -39,11 +39,12 @@ use polkadot_node_primitives::{
 	SignedDisputeStatement, SignedFullStatement, ValidationResult,
 };
 use polkadot_primitives::{
-	vstaging::ExecutorParams, AuthorityDiscoveryId, BackedCandidate, BlockNumber, CandidateEvent,
-	CandidateHash, CandidateIndex, CandidateReceipt, CollatorId, CommittedCandidateReceipt,
-	CoreState, DisputeState, GroupIndex, GroupRotationInfo, Hash, Header as BlockHeader,
-	Id as ParaId, InboundDownwardMessage, InboundHrmpMessage, MultiDisputeStatementSet,
-	OccupiedCoreAssumption, PersistedValidationData, PvfCheckStatement, SessionIndex, SessionInfo,
+	vstaging::{slashing, ExecutorParams, HrmpTopology, UpgradePipeline, ValidatorPerformance},
+	AuthorityDiscoveryId, BackedCandidate, BlockNumber, CandidateEvent, CandidateHash,
+	CandidateIndex, CandidateReceipt, CollatorId, CommittedCandidateReceipt, CoreState,
+	DisputeState, GroupIndex, GroupRotationInfo, Hash, Header as BlockHeader, Id as ParaId,
+	InboundDownwardMessage, InboundHrmpMessage, MultiDisputeStatementSet, OccupiedCoreAssumption,
+	PersistedValidationData, PvfCheckStatement, SessionIndex, SessionInfo,
 	SignedAvailabilityBitfield, SignedAvailabilityBitfields, ValidationCode, ValidationCodeHash,
 	ValidatorId, ValidatorIndex, ValidatorSignature,
 };
@@ -603,6 +604,33 @@ pub enum RuntimeApiRequest {
 	),
 	/// Returns all on-chain disputes at given block number. Available in `v3`.
 	Disputes(RuntimeApiSender<Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>>),
+	/// Get the open and pending HRMP channels of a para along with their utilization.
+	/// Available in `v5`.
+	HrmpTopology(ParaId, RuntimeApiSender<HrmpTopology>),
+	/// Returns a list of validators that lost a past session dispute and need to be slashed.
+	/// Available in `v5`.
+	UnappliedSlashes(
+		RuntimeApiSender<Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>>,
+	),
+	/// Returns a merkle proof of a validator session key.
+	/// Available in `v5`.
+	KeyOwnershipProof(ValidatorId, RuntimeApiSender<Option<slashing::OpaqueKeyOwnershipProof>>),
+	/// Submits an unsigned extrinsic to slash validator who lost a past session dispute.
+	/// Available in `v5`.
+	SubmitReportDisputeLost(
+		slashing::DisputeProof,
+		slashing::OpaqueKeyOwnershipProof,
+		RuntimeApiSender<Option<()>>,
+	),
+	/// Get all candidates pending availability for a para, in chain order.
+	/// Available in `v5`.
+	CandidatesPendingAvailability(ParaId, RuntimeApiSender<Vec<CommittedCandidateReceipt>>),
+	/// Get the parachain performance counters of all validators of a session.
+	/// Available in `v5`.
+	ValidatorPerformance(SessionIndex, RuntimeApiSender<Option<Vec<ValidatorPerformance>>>),
+	/// Get the state of the code upgrade pipeline of a para.
+	/// Available in `v5`.
+	ParaUpgradePipeline(ParaId, RuntimeApiSender<Option<UpgradePipeline<BlockNumber>>>),
 }
 
 impl RuntimeApiRequest {
@@ -613,6 +641,27 @@ impl RuntimeApiRequest {
 
 	/// `ExecutorParams`
 	pub const EXECUTOR_PARAMS_RUNTIME_REQUIREMENT: u32 = 4;
+
+	/// `HrmpTopology`
+	pub const HRMP_TOPOLOGY_RUNTIME_REQUIREMENT: u32 = 5;
+
+	/// `UnappliedSlashes`
+	pub const UNAPPLIED_SLASHES_RUNTIME_REQUIREMENT: u32 = 5;
+
+	/// `KeyOwnershipProof`
+	pub const KEY_OWNERSHIP_PROOF_RUNTIME_REQUIREMENT: u32 = 5;
+
+	/// `SubmitReportDisputeLost`
+	pub const SUBMIT_REPORT_DISPUTE_LOST_RUNTIME_REQUIREMENT: u32 = 5;
+
+	/// `CandidatesPendingAvailability`
+	pub const CANDIDATES_PENDING_AVAILABILITY_RUNTIME_REQUIREMENT: u32 = 5;
+
+	/// `ValidatorPerformance`
+	pub const VALIDATOR_PERFORMANCE_RUNTIME_REQUIREMENT: u32 = 5;
+
+	/// `ParaUpgradePipeline`
+	pub const PARA_UPGRADE_PIPELINE_RUNTIME_REQUIREMENT: u32 = 5;
 }
 
 /// A message to the Runtime API subsystem.
@@ -832,4 +881,45 @@ pub enum GossipSupportMessage {
 	/// Dummy constructor, so we can receive networking events.
 	#[from]
 	NetworkBridgeUpdate(NetworkBridgeEvent<net_protocol::GossipSupportNetworkMessage>),
+	/// Connection events on the collation peer set.
+	///
+	/// Only used for keeping track of which authorities we are connected to.
+	CollationNetworkBridgeUpdate(NetworkBridgeEvent<net_protocol::GossipSupportNetworkMessage>),
+	/// Get a report on our connectivity to the authorities of the current session.
+	GetConnectivityReport(oneshot::Sender<ConnectivityReport>),
+}
+
+/// Connectivity to the authorities of a session, as seen by gossip support.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectivityReport {
+	/// The session the report is about, `None` if we have not seen any session yet.
+	pub session_index: Option<SessionIndex>,
+	/// One entry per authority of the session, excluding ourselves.
+	pub authorities: Vec<AuthorityConnectivity>,
+}
+
+/// Connectivity to a single authority.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorityConnectivity {
+	/// The authority this entry is about.
+	pub authority_id: AuthorityDiscoveryId,
+	/// Addresses found via authority discovery, `None` if the lookup failed.
+	pub addresses: Option<HashSet<Multiaddr>>,
+	/// Peer we are connected to on the validation peer set, if any.
+	pub validation_peer: Option<PeerId>,
+	/// Peer we are connected to on the collation peer set, if any.
+	pub collation_peer: Option<PeerId>,
+	/// The last connectivity problem we observed for this authority.
+	pub last_error: Option<ConnectivityError>,
+	/// Whether the authority is one of our neighbors in the gossip grid topology.
+	pub is_grid_neighbor: bool,
+}
+
+/// Connectivity problems observed for an authority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectivityError {
+	/// Authority discovery did not yield any addresses.
+	AddressesNotResolved,
+	/// The peer disconnected from the given peer set.
+	Disconnected(PeerSet),
 }
@@ -16,12 +16,13 @@
 
 use async_trait::async_trait;
 use polkadot_primitives::{
-	runtime_api::ParachainHost, vstaging::ExecutorParams, Block, BlockNumber, CandidateCommitments,
-	CandidateEvent, CandidateHash, CommittedCandidateReceipt, CoreState, DisputeState,
-	GroupRotationInfo, Hash, Id, InboundDownwardMessage, InboundHrmpMessage,
-	OccupiedCoreAssumption, PersistedValidationData, PvfCheckStatement, ScrapedOnChainVotes,
-	SessionIndex, SessionInfo, ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex,
-	ValidatorSignature,
+	runtime_api::ParachainHost,
+	vstaging::{slashing, ExecutorParams, HrmpTopology, UpgradePipeline, ValidatorPerformance},
+	Block, BlockNumber, CandidateCommitments, CandidateEvent, CandidateHash,
+	CommittedCandidateReceipt, CoreState, DisputeState, GroupRotationInfo, Hash, Id,
+	InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption, PersistedValidationData,
+	PvfCheckStatement, ScrapedOnChainVotes, SessionIndex, SessionInfo, ValidationCode,
+	ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
 };
 use sp_api::{ApiError, ApiExt, ProvideRuntimeApi};
 use sp_authority_discovery::AuthorityDiscoveryApi;
@@ -189,6 +190,61 @@ pub trait RuntimeApiSubsystemClient {
 		session_index: SessionIndex,
 	) -> Result<Option<ExecutorParams>, ApiError>;
 
+	/***** Staging *****/
+
+	/// Get the open and pending HRMP channels of a para along with their utilization.
+	/// This is a staging method! Do not use on production runtimes!
+	async fn hrmp_topology(&self, at: Hash, para_id: Id) -> Result<HrmpTopology, ApiError>;
+
+	/// Returns a list of validators that lost a past session dispute and need to be slashed.
+	/// This is a staging method! Do not use on production runtimes!
+	async fn unapplied_slashes(
+		&self,
+		at: Hash,
+	) -> Result<Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>, ApiError>;
+
+	/// Returns a merkle proof of a validator session key in a past session.
+	/// This is a staging method! Do not use on production runtimes!
+	async fn key_ownership_proof(
+		&self,
+		at: Hash,
+		validator_id: ValidatorId,
+	) -> Result<Option<slashing::OpaqueKeyOwnershipProof>, ApiError>;
+
+	/// Submits an unsigned extrinsic to slash validators who lost a dispute about
+	/// a candidate of a past session.
+	/// This is a staging method! Do not use on production runtimes!
+	async fn submit_report_dispute_lost(
+		&self,
+		at: Hash,
+		dispute_proof: slashing::DisputeProof,
+		key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+	) -> Result<Option<()>, ApiError>;
+
+	/// Get all candidates pending availability for a para, in chain order.
+	/// This is a staging method! Do not use on production runtimes!
+	async fn candidates_pending_availability(
+		&self,
+		at: Hash,
+		para_id: Id,
+	) -> Result<Vec<CommittedCandidateReceipt<Hash>>, ApiError>;
+
+	/// Get the parachain performance counters of all validators of a session.
+	/// This is a staging method! Do not use on production runtimes!
+	async fn validator_performance(
+		&self,
+		at: Hash,
+		session_index: SessionIndex,
+	) -> Result<Option<Vec<ValidatorPerformance>>, ApiError>;
+
+	/// Get the state of the code upgrade pipeline of a para.
+	/// This is a staging method! Do not use on production runtimes!
+	async fn para_upgrade_pipeline(
+		&self,
+		at: Hash,
+		para_id: Id,
+	) -> Result<Option<UpgradePipeline<BlockNumber>>, ApiError>;
+
 	// === BABE API ===
 
 	/// Returns information regarding the current epoch.
@@ -374,4 +430,56 @@ where
 	) -> Result<Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>, ApiError> {
 		self.runtime_api().disputes(at)
 	}
+
+	async fn hrmp_topology(&self, at: Hash, para_id: Id) -> Result<HrmpTopology, ApiError> {
+		self.runtime_api().hrmp_topology(at, para_id)
+	}
+
+	async fn unapplied_slashes(
+		&self,
+		at: Hash,
+	) -> Result<Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>, ApiError> {
+		self.runtime_api().unapplied_slashes(at)
+	}
+
+	async fn key_ownership_proof(
+		&self,
+		at: Hash,
+		validator_id: ValidatorId,
+	) -> Result<Option<slashing::OpaqueKeyOwnershipProof>, ApiError> {
+		self.runtime_api().key_ownership_proof(at, validator_id)
+	}
+
+	async fn submit_report_dispute_lost(
+		&self,
+		at: Hash,
+		dispute_proof: slashing::DisputeProof,
+		key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+	) -> Result<Option<()>, ApiError> {
+		self.runtime_api().submit_report_dispute_lost(at, dispute_proof, key_ownership_proof)
+	}
+
+	async fn candidates_pending_availability(
+		&self,
+		at: Hash,
+		para_id: Id,
+	) -> Result<Vec<CommittedCandidateReceipt<Hash>>, ApiError> {
+		self.runtime_api().candidates_pending_availability(at, para_id)
+	}
+
+	async fn validator_performance(
+		&self,
+		at: Hash,
+		session_index: SessionIndex,
+	) -> Result<Option<Vec<ValidatorPerformance>>, ApiError> {
+		self.runtime_api().validator_performance(at, session_index)
+	}
+
+	async fn para_upgrade_pipeline(
+		&self,
+		at: Hash,
+		para_id: Id,
+	) -> Result<Option<UpgradePipeline<BlockNumber>>, ApiError> {
+		self.runtime_api().para_upgrade_pipeline(at, para_id)
+	}
 }
@@ -222,5 +222,62 @@ sp_api::decl_runtime_apis! {
 		/// Returns execution parameters for the session.
 		#[api_version(4)]
 		fn session_executor_params(session_index: SessionIndex) -> Option<vstaging::ExecutorParams>;
+
+		/// Returns the spot price and the contents of the on-demand parathread order queue.
+		#[api_version(5)]
+		fn on_demand_queue() -> vstaging::OnDemandQueue;
+
+		/// Returns the footprint of the upward message queue of the para.
+		#[api_version(5)]
+		fn ump_queue_footprint(para_id: ppp::Id) -> vstaging::UmpQueueFootprint;
+
+		/// Returns the open and pending HRMP channels of the para along with their utilization.
+		#[api_version(5)]
+		fn hrmp_topology(para_id: ppp::Id) -> vstaging::HrmpTopology;
+
+		/// Applies the diff to the latest scheduled host configuration and returns every
+		/// consistency error of the result. An empty list means the diff can be scheduled.
+		#[api_version(5)]
+		fn check_host_configuration_diff(
+			diff: vstaging::HostConfigurationDiff<N>,
+		) -> Vec<vstaging::InconsistentError<N>>;
+
+		/// Returns a list of validators that lost a past session dispute and need to be slashed.
+		#[api_version(5)]
+		fn unapplied_slashes(
+		) -> Vec<(SessionIndex, CandidateHash, vstaging::slashing::PendingSlashes)>;
+
+		/// Returns a merkle proof of a validator session key.
+		#[api_version(5)]
+		fn key_ownership_proof(
+			validator_id: ValidatorId,
+		) -> Option<vstaging::slashing::OpaqueKeyOwnershipProof>;
+
+		/// Submit an unsigned extrinsic to slash validators who lost a dispute about
+		/// a candidate of a past session.
+		#[api_version(5)]
+		fn submit_report_dispute_lost(
+			dispute_proof: vstaging::slashing::DisputeProof,
+			key_ownership_proof: vstaging::slashing::OpaqueKeyOwnershipProof,
+		) -> Option<()>;
+
+		/// Get all candidates pending availability for the para, in chain order.
+		///
+		/// The first entry is the candidate building directly on the para head. Paras assigned a
+		/// single core have at most one candidate pending availability.
+		#[api_version(5)]
+		fn candidates_pending_availability(para_id: ppp::Id) -> Vec<CommittedCandidateReceipt<H>>;
+
+		/// Get the parachain performance counters of all validators of the given session, by
+		/// validator index. Returns `None` if the session is unknown or was pruned.
+		#[api_version(5)]
+		fn validator_performance(
+			session_index: SessionIndex,
+		) -> Option<Vec<vstaging::ValidatorPerformance>>;
+
+		/// Get the state of the code upgrade pipeline of a para. Returns `None` if the para is not
+		/// registered.
+		#[api_version(5)]
+		fn para_upgrade_pipeline(para_id: ppp::Id) -> Option<vstaging::UpgradePipeline<N>>;
 	}
 }
@@ -1034,6 +1034,8 @@ pub enum ValidityError {
 	NoPermission = 2,
 	/// An invalid statement was made for a claim.
 	InvalidStatement = 3,
+	/// The proof of a claim is invalid.
+	InvalidClaimProof = 4,
 }
 
 impl From<ValidityError> for u8 {
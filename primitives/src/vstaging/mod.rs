@@ -20,3 +20,237 @@
 
 pub mod executor_params;
 pub use executor_params::{ExecutorParam, ExecutorParams, ExecutorParamsHash};
+
+pub mod slashing;
+
+use crate::{
+	Balance, HrmpChannelId, Id, SessionIndex, UpgradeGoAhead, UpgradeRestriction,
+	ValidationCodeHash,
+};
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_std::vec::Vec;
+use sp_weights::Weight;
+
+/// The state of the queue of on-demand parathread orders.
+#[derive(Clone, Debug, Encode, Decode, PartialEq, Eq, TypeInfo)]
+pub struct OnDemandQueue {
+	/// The price of an order placed now.
+	pub spot_price: Balance,
+	/// The maximum number of claims the queue can hold.
+	pub capacity: u32,
+	/// The parathreads with a claim in the queue, in the order they will be scheduled.
+	pub queue: Vec<Id>,
+}
+
+/// The footprint of the upward message queue of a para.
+#[derive(Clone, Debug, Default, Encode, Decode, PartialEq, Eq, TypeInfo)]
+pub struct UmpQueueFootprint {
+	/// The number of messages waiting to be dispatched.
+	pub count: u32,
+	/// The total length (in bytes) of the messages waiting to be dispatched.
+	pub size: u32,
+	/// The number of pages holding the messages waiting to be dispatched.
+	pub pages: u32,
+	/// The number of pages suspended because of an overweight message.
+	pub suspended_pages: u32,
+}
+
+/// The state of the code upgrade pipeline of a para.
+#[derive(Clone, Debug, Default, Encode, Decode, PartialEq, TypeInfo)]
+pub struct UpgradePipeline<N> {
+	/// The hash of the validation code the para is upgrading to, if an upgrade is in progress.
+	pub future_code_hash: Option<ValidationCodeHash>,
+	/// Whether the future validation code is still undergoing PVF pre-checking.
+	pub pvf_check_in_progress: bool,
+	/// The relay-chain block number starting at which the upgrade is enacted by the next
+	/// included candidate. Only known once the future code has passed PVF pre-checking.
+	pub expected_at: Option<N>,
+	/// The relay-chain block at the start of which the go-ahead signal will be set, unless it
+	/// has been set already.
+	pub go_ahead_at: Option<N>,
+	/// The go-ahead signal currently observed by the para.
+	pub go_ahead_signal: Option<UpgradeGoAhead>,
+	/// The upgrade restriction currently observed by the para.
+	pub restriction_signal: Option<UpgradeRestriction>,
+	/// The relay-chain block at which the upgrade cooldown of the para expires, if it did not
+	/// expire yet.
+	pub cooldown_until: Option<N>,
+}
+
+/// The parachain performance counters of a validator in a session.
+#[derive(Clone, Copy, Debug, Default, Encode, Decode, PartialEq, Eq, TypeInfo)]
+pub struct ValidatorPerformance {
+	/// The number of included candidates the validator backed.
+	pub backed_candidates: u32,
+	/// The number of availability bitfields of the validator which were included on-chain.
+	pub bitfields_included: u32,
+	/// The number of disputes the validator raised by voting against a candidate.
+	pub disputes_raised: u32,
+	/// The number of concluded disputes the validator voted on the winning side of.
+	pub disputes_won: u32,
+	/// The number of concluded disputes the validator voted on the losing side of.
+	pub disputes_lost: u32,
+}
+
+/// An open HRMP channel and how much of its capacity is used.
+#[derive(Clone, Debug, Encode, Decode, PartialEq, Eq, TypeInfo)]
+pub struct HrmpChannelUtilization {
+	/// The para on the other end of the channel, i.e. the sender of an inbound channel or the
+	/// recipient of an outbound channel.
+	pub counterparty: Id,
+	/// The maximum number of messages that can be pending in the channel at once.
+	pub max_capacity: u32,
+	/// The maximum total size of the messages that can be pending in the channel at once.
+	pub max_total_size: u32,
+	/// The maximum message size that could be put into the channel.
+	pub max_message_size: u32,
+	/// The number of messages pending in the channel.
+	pub msg_count: u32,
+	/// The total size in bytes of all message payloads pending in the channel.
+	pub total_size: u32,
+	/// Whether the channel is going to be closed at the next session change.
+	pub closing: bool,
+}
+
+/// A pending request to open an HRMP channel.
+#[derive(Clone, Debug, Encode, Decode, PartialEq, Eq, TypeInfo)]
+pub struct HrmpOpenRequest {
+	/// The channel requested to be opened.
+	pub channel_id: HrmpChannelId,
+	/// Whether the recipient accepted the request. Accepted requests are enacted at the next
+	/// session change.
+	pub confirmed: bool,
+	/// The maximum number of messages that can be pending in the channel at once.
+	pub max_capacity: u32,
+	/// The maximum message size that could be put into the channel.
+	pub max_message_size: u32,
+}
+
+/// The HRMP channels of a para, both open and pending.
+#[derive(Clone, Debug, Default, Encode, Decode, PartialEq, Eq, TypeInfo)]
+pub struct HrmpTopology {
+	/// The open channels the para receives messages through, sorted by sender.
+	pub inbound: Vec<HrmpChannelUtilization>,
+	/// The open channels the para sends messages through, sorted by recipient.
+	pub outbound: Vec<HrmpChannelUtilization>,
+	/// The pending requests to open a channel in which the para participates.
+	pub open_requests: Vec<HrmpOpenRequest>,
+}
+
+/// A set of changes to the host configuration, applied atomically.
+///
+/// Each field corresponds to the field of the same name of the `HostConfiguration` of the
+/// configuration pallet. `None` leaves the field unchanged, `Some` sets it to the given value.
+#[derive(Clone, Debug, Default, Encode, Decode, PartialEq, Eq, TypeInfo)]
+#[allow(missing_docs)]
+pub struct HostConfigurationDiff<BlockNumber> {
+	pub max_code_size: Option<u32>,
+	pub max_head_data_size: Option<u32>,
+	pub max_upward_queue_count: Option<u32>,
+	pub max_upward_queue_size: Option<u32>,
+	pub max_upward_message_size: Option<u32>,
+	pub max_upward_message_num_per_candidate: Option<u32>,
+	pub hrmp_max_message_num_per_candidate: Option<u32>,
+	pub validation_upgrade_cooldown: Option<BlockNumber>,
+	pub validation_upgrade_delay: Option<BlockNumber>,
+	pub max_pov_size: Option<u32>,
+	pub max_downward_message_size: Option<u32>,
+	pub ump_service_total_weight: Option<Weight>,
+	pub hrmp_max_parachain_outbound_channels: Option<u32>,
+	pub hrmp_max_parathread_outbound_channels: Option<u32>,
+	pub hrmp_sender_deposit: Option<Balance>,
+	pub hrmp_recipient_deposit: Option<Balance>,
+	pub hrmp_channel_max_capacity: Option<u32>,
+	pub hrmp_channel_max_total_size: Option<u32>,
+	pub hrmp_max_parachain_inbound_channels: Option<u32>,
+	pub hrmp_max_parathread_inbound_channels: Option<u32>,
+	pub hrmp_channel_max_message_size: Option<u32>,
+	pub code_retention_period: Option<BlockNumber>,
+	pub parathread_cores: Option<u32>,
+	pub parathread_retries: Option<u32>,
+	pub group_rotation_frequency: Option<BlockNumber>,
+	pub chain_availability_period: Option<BlockNumber>,
+	pub thread_availability_period: Option<BlockNumber>,
+	pub scheduling_lookahead: Option<u32>,
+	pub max_validators_per_core: Option<Option<u32>>,
+	pub max_validators: Option<Option<u32>>,
+	pub dispute_period: Option<SessionIndex>,
+	pub dispute_post_conclusion_acceptance_period: Option<BlockNumber>,
+	pub dispute_conclusion_by_time_out_period: Option<BlockNumber>,
+	pub no_show_slots: Option<u32>,
+	pub n_delay_tranches: Option<u32>,
+	pub zeroth_delay_tranche_width: Option<u32>,
+	pub needed_approvals: Option<u32>,
+	pub relay_vrf_modulo_samples: Option<u32>,
+	pub ump_max_individual_weight: Option<Weight>,
+	pub pvf_checking_enabled: Option<bool>,
+	pub pvf_voting_ttl: Option<SessionIndex>,
+	pub minimum_validation_upgrade_delay: Option<BlockNumber>,
+}
+
+/// Enumerates the possible inconsistencies of the host configuration.
+#[derive(Clone, Debug, Encode, Decode, PartialEq, Eq, TypeInfo)]
+pub enum InconsistentError<BlockNumber> {
+	/// `group_rotation_frequency` is set to zero.
+	ZeroGroupRotationFrequency,
+	/// `chain_availability_period` is set to zero.
+	ZeroChainAvailabilityPeriod,
+	/// `thread_availability_period` is set to zero.
+	ZeroThreadAvailabilityPeriod,
+	/// `no_show_slots` is set to zero.
+	ZeroNoShowSlots,
+	/// `max_code_size` exceeds the hard limit of `MAX_CODE_SIZE`.
+	MaxCodeSizeExceedHardLimit {
+		/// The configured `max_code_size`.
+		max_code_size: u32,
+	},
+	/// `max_head_data_size` exceeds the hard limit of `MAX_HEAD_DATA_SIZE`.
+	MaxHeadDataSizeExceedHardLimit {
+		/// The configured `max_head_data_size`.
+		max_head_data_size: u32,
+	},
+	/// `max_pov_size` exceeds the hard limit of `MAX_POV_SIZE`.
+	MaxPovSizeExceedHardLimit {
+		/// The configured `max_pov_size`.
+		max_pov_size: u32,
+	},
+	/// `minimum_validation_upgrade_delay` is less than `chain_availability_period`.
+	MinimumValidationUpgradeDelayLessThanChainAvailabilityPeriod {
+		/// The configured `minimum_validation_upgrade_delay`.
+		minimum_validation_upgrade_delay: BlockNumber,
+		/// The configured `chain_availability_period`.
+		chain_availability_period: BlockNumber,
+	},
+	/// `minimum_validation_upgrade_delay` is less than `thread_availability_period`.
+	MinimumValidationUpgradeDelayLessThanThreadAvailabilityPeriod {
+		/// The configured `minimum_validation_upgrade_delay`.
+		minimum_validation_upgrade_delay: BlockNumber,
+		/// The configured `thread_availability_period`.
+		thread_availability_period: BlockNumber,
+	},
+	/// `validation_upgrade_delay` is less than or equal 1.
+	ValidationUpgradeDelayIsTooLow {
+		/// The configured `validation_upgrade_delay`.
+		validation_upgrade_delay: BlockNumber,
+	},
+	/// Maximum UMP message size (`MAX_UPWARD_MESSAGE_SIZE_BOUND`) exceeded.
+	MaxUpwardMessageSizeExceeded {
+		/// The configured `max_upward_message_size`.
+		max_message_size: u32,
+	},
+	/// Maximum HRMP message num (`MAX_HORIZONTAL_MESSAGE_NUM`) exceeded.
+	MaxHorizontalMessageNumExceeded {
+		/// The configured `hrmp_max_message_num_per_candidate`.
+		max_message_num: u32,
+	},
+	/// Maximum UMP message num (`MAX_UPWARD_MESSAGE_NUM`) exceeded.
+	MaxUpwardMessageNumExceeded {
+		/// The configured `max_upward_message_num_per_candidate`.
+		max_message_num: u32,
+	},
+	/// Maximum number of HRMP outbound channels exceeded.
+	MaxHrmpOutboundChannelsExceeded,
+	/// Maximum number of HRMP inbound channels exceeded.
+	MaxHrmpInboundChannelsExceeded,
+}
@@ -0,0 +1,159 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC for diagnosing connectivity to the authorities of the current session.
+
+use std::time::Duration;
+
+use futures::{channel::oneshot, future::Either, pin_mut};
+use futures_timer::Delay;
+use jsonrpsee::{
+	core::{async_trait, Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use serde::{Deserialize, Serialize};
+
+use polkadot_node_subsystem_types::messages::{self, GossipSupportMessage};
+use polkadot_overseer::Handle;
+use polkadot_primitives::{AuthorityDiscoveryId, SessionIndex};
+use sc_rpc::DenyUnsafe;
+
+/// How long to wait for the gossip support subsystem to answer.
+const REPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The node is not running the parachain subsystems.
+const NOT_AVAILABLE_ERROR: i32 = 9000;
+/// The subsystem did not answer in time.
+const TIMEOUT_ERROR: i32 = 9001;
+
+/// Connectivity to the authorities of a session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityReport {
+	/// The session the report is about, `None` if the node has not seen any session yet.
+	pub session_index: Option<SessionIndex>,
+	/// One entry per authority of the session, excluding the node itself.
+	pub authorities: Vec<AuthorityConnectivity>,
+}
+
+/// Connectivity to a single authority.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorityConnectivity {
+	/// The authority discovery key of the authority.
+	pub authority_id: AuthorityDiscoveryId,
+	/// Addresses found via authority discovery, `None` if the lookup failed.
+	pub addresses: Option<Vec<String>>,
+	/// Peer connected on the validation peer set, if any.
+	pub validation_peer: Option<String>,
+	/// Peer connected on the collation peer set, if any.
+	pub collation_peer: Option<String>,
+	/// The last connectivity problem observed for this authority.
+	pub last_error: Option<String>,
+	/// Whether the authority is one of our neighbors in the gossip grid topology.
+	pub is_grid_neighbor: bool,
+}
+
+impl ConnectivityReport {
+	/// Percentage of authorities we are connected to on the validation peer set.
+	///
+	/// 100 if there are no authorities to connect to.
+	pub fn connected_ratio(&self) -> usize {
+		let connected = self.authorities.iter().filter(|a| a.validation_peer.is_some()).count();
+		(100 * connected).checked_div(self.authorities.len()).unwrap_or(100)
+	}
+}
+
+impl From<messages::ConnectivityReport> for ConnectivityReport {
+	fn from(report: messages::ConnectivityReport) -> Self {
+		let authorities = report
+			.authorities
+			.into_iter()
+			.map(|a| AuthorityConnectivity {
+				authority_id: a.authority_id,
+				addresses: a.addresses.map(|addrs| addrs.iter().map(|a| a.to_string()).collect()),
+				validation_peer: a.validation_peer.map(|p| p.to_string()),
+				collation_peer: a.collation_peer.map(|p| p.to_string()),
+				last_error: a.last_error.map(|e| match e {
+					messages::ConnectivityError::AddressesNotResolved =>
+						"addresses not resolved".into(),
+					messages::ConnectivityError::Disconnected(peer_set) =>
+						format!("disconnected from {:?} peer set", peer_set),
+				}),
+				is_grid_neighbor: a.is_grid_neighbor,
+			})
+			.collect();
+
+		Self { session_index: report.session_index, authorities }
+	}
+}
+
+/// Connectivity diagnostics RPC methods.
+#[rpc(client, server)]
+pub trait ConnectivityApi {
+	/// Report on the connectivity to each authority of the current session.
+	#[method(name = "parachain_connectivityReport")]
+	async fn connectivity_report(&self) -> RpcResult<ConnectivityReport>;
+}
+
+/// Implementation of the [`ConnectivityApiServer`] based on the overseer.
+pub struct Connectivity {
+	overseer_handle: Option<Handle>,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl Connectivity {
+	/// Create a new instance.
+	///
+	/// `overseer_handle` is `None` if the node does not run the parachain subsystems.
+	pub fn new(overseer_handle: Option<Handle>, deny_unsafe: DenyUnsafe) -> Self {
+		Self { overseer_handle, deny_unsafe }
+	}
+}
+
+#[async_trait]
+impl ConnectivityApiServer for Connectivity {
+	async fn connectivity_report(&self) -> RpcResult<ConnectivityReport> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let mut overseer_handle = self.overseer_handle.clone().ok_or_else(|| {
+			error(NOT_AVAILABLE_ERROR, "Parachain subsystems are not running on this node")
+		})?;
+
+		let request = async move {
+			let (tx, rx) = oneshot::channel();
+			overseer_handle
+				.send_msg(GossipSupportMessage::GetConnectivityReport(tx), "ConnectivityRpc")
+				.await;
+			rx.await
+		};
+		let timeout = Delay::new(REPORT_TIMEOUT);
+		pin_mut!(request);
+
+		match futures::future::select(request, timeout).await {
+			Either::Left((Ok(report), _)) => Ok(report.into()),
+			Either::Left((Err(_), _)) =>
+				Err(error(NOT_AVAILABLE_ERROR, "Gossip support subsystem is not running")),
+			Either::Right(_) =>
+				Err(error(TIMEOUT_ERROR, "Gossip support subsystem did not answer in time")),
+		}
+	}
+}
+
+fn error(code: i32, message: &str) -> JsonRpseeError {
+	JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(code, message, None::<()>)))
+}
@@ -21,7 +21,10 @@
 use std::sync::Arc;
 
 use jsonrpsee::RpcModule;
-use polkadot_primitives::{AccountId, Balance, Block, BlockNumber, Hash, Nonce};
+use polkadot_overseer::Handle;
+use polkadot_primitives::{
+	runtime_api::ParachainHost, AccountId, Balance, Block, BlockNumber, Hash, Nonce,
+};
 use sc_client_api::AuxStore;
 use sc_consensus_babe::{BabeConfiguration, Epoch};
 use sc_finality_grandpa::FinalityProofProvider;
@@ -34,6 +37,9 @@ use sp_consensus_babe::BabeApi;
 use sp_keystore::SyncCryptoStorePtr;
 use txpool_api::TransactionPool;
 
+pub mod connectivity;
+pub mod slashing;
+
 /// A type representing all RPC extensions.
 pub type RpcExtension = RpcModule<()>;
 
@@ -92,6 +98,8 @@ pub struct FullDeps<C, P, SC, B> {
 	pub grandpa: GrandpaDeps<B>,
 	/// BEEFY specific dependencies.
 	pub beefy: BeefyDeps,
+	/// Handle to the overseer, `None` if the node does not run the parachain subsystems.
+	pub overseer_handle: Option<Handle>,
 }
 
 /// Instantiate all RPC extensions.
@@ -112,23 +120,35 @@ where
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
+	C::Api: ParachainHost<Block>,
 	P: TransactionPool + Sync + Send + 'static,
 	SC: SelectChain<Block> + 'static,
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
 	B::State: sc_client_api::StateBackend<sp_runtime::traits::HashFor<Block>>,
 {
 	use beefy_gadget_rpc::{Beefy, BeefyApiServer};
+	use connectivity::{Connectivity, ConnectivityApiServer};
 	use frame_rpc_system::{System, SystemApiServer};
 	use mmr_rpc::{Mmr, MmrApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use sc_consensus_babe_rpc::{Babe, BabeApiServer};
 	use sc_finality_grandpa_rpc::{Grandpa, GrandpaApiServer};
 	use sc_sync_state_rpc::{SyncState, SyncStateApiServer};
+	use slashing::{Slashing, SlashingApiServer};
 	use substrate_state_trie_migration_rpc::{StateMigration, StateMigrationApiServer};
 
 	let mut io = RpcModule::new(());
-	let FullDeps { client, pool, select_chain, chain_spec, deny_unsafe, babe, grandpa, beefy } =
-		deps;
+	let FullDeps {
+		client,
+		pool,
+		select_chain,
+		chain_spec,
+		deny_unsafe,
+		babe,
+		grandpa,
+		beefy,
+		overseer_handle,
+	} = deps;
 	let BabeDeps { keystore, babe_config, shared_epoch_changes } = babe;
 	let GrandpaDeps {
 		shared_voter_state,
@@ -142,6 +162,7 @@ where
 	io.merge(System::new(client.clone(), pool.clone(), deny_unsafe).into_rpc())?;
 	io.merge(TransactionPayment::new(client.clone()).into_rpc())?;
 	io.merge(Mmr::new(client.clone()).into_rpc())?;
+	io.merge(Slashing::new(client.clone()).into_rpc())?;
 	io.merge(
 		Babe::new(
 			client.clone(),
@@ -176,5 +197,7 @@ where
 		.into_rpc(),
 	)?;
 
+	io.merge(Connectivity::new(overseer_handle, deny_unsafe).into_rpc())?;
+
 	Ok(io)
 }
@@ -0,0 +1,92 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC for generating key ownership proofs for dispute slashing at historical blocks.
+//!
+//! Key ownership proofs of a validator need to be generated at a block of the session the
+//! validator lost a dispute in. Nodes usually prune the state of such blocks, so the dispute
+//! coordinator might not be able to report a slash itself. A node that still has the state, e.g.
+//! an archive node, can serve the proof via this RPC. The proof can then be submitted with a
+//! `report_dispute_lost_unsigned` extrinsic of the `ParasSlashing` pallet.
+
+use std::sync::Arc;
+
+use jsonrpsee::{
+	core::{Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+
+use polkadot_primitives::{runtime_api::ParachainHost, Block, Hash, ValidatorId};
+use sp_api::ProvideRuntimeApi;
+use sp_core::Bytes;
+
+/// The runtime does not support dispute slashing.
+const NOT_SUPPORTED_ERROR: i32 = 9010;
+/// Calling the runtime failed, e.g. because the state of the block is pruned.
+const RUNTIME_ERROR: i32 = 9011;
+
+/// The first version of the `ParachainHost` runtime API supporting dispute slashing.
+const SLASHING_RUNTIME_REQUIREMENT: u32 = 5;
+
+/// Dispute slashing RPC methods.
+#[rpc(client, server)]
+pub trait SlashingApi {
+	/// Generate the key ownership proof of `validator_id` at block `at`.
+	///
+	/// `at` needs to be a block of the session the proof is needed for. Returns the encoded
+	/// proof, `None` if the validator is not part of the session.
+	#[method(name = "parachain_keyOwnershipProof")]
+	fn key_ownership_proof(&self, at: Hash, validator_id: ValidatorId) -> RpcResult<Option<Bytes>>;
+}
+
+/// Implementation of the [`SlashingApiServer`] based on the runtime API.
+pub struct Slashing<C> {
+	client: Arc<C>,
+}
+
+impl<C> Slashing<C> {
+	/// Create a new instance.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client }
+	}
+}
+
+impl<C> SlashingApiServer for Slashing<C>
+where
+	C: ProvideRuntimeApi<Block> + Send + Sync + 'static,
+	C::Api: ParachainHost<Block>,
+{
+	fn key_ownership_proof(&self, at: Hash, validator_id: ValidatorId) -> RpcResult<Option<Bytes>> {
+		let api = self.client.runtime_api();
+
+		let version = api.api_version::<dyn ParachainHost<Block>>(at).map_err(|e| {
+			error(RUNTIME_ERROR, &format!("Fetching runtime version failed: {}", e))
+		})?;
+		if version.map_or(true, |v| v < SLASHING_RUNTIME_REQUIREMENT) {
+			return Err(error(NOT_SUPPORTED_ERROR, "The runtime does not support dispute slashing"))
+		}
+
+		let proof = api.key_ownership_proof(at, validator_id).map_err(|e| {
+			error(RUNTIME_ERROR, &format!("Generating key ownership proof failed: {}", e))
+		})?;
+		Ok(proof.map(|proof| proof.into_inner().into()))
+	}
+}
+
+fn error(code: i32, message: &str) -> JsonRpseeError {
+	JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(code, message, None::<()>)))
+}
@@ -20,6 +20,8 @@
 //! in a fair (though best-effort) manner.
 //! The dispatchables must be called from the configured origin
 //! (typically `Sudo` or a governance origin).
+//! Temporary slots can also be pinned to a future lease period, and the
+//! upcoming temporary slot schedule can be queried through `AssignedSlotsApi`.
 //! This pallet should not be used on a production relay chain,
 //! only on a test relay chain (e.g. Rococo).
 
@@ -31,14 +33,14 @@ use crate::{
 use frame_support::{pallet_prelude::*, traits::Currency};
 use frame_system::pallet_prelude::*;
 pub use pallet::*;
-use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+use parity_scale_codec::{Codec, Decode, Encode, MaxEncodedLen};
 use primitives::Id as ParaId;
 use runtime_parachains::{
 	configuration,
 	paras::{self},
 };
 use scale_info::TypeInfo;
-use sp_runtime::traits::{One, Saturating, Zero};
+use sp_runtime::traits::{CheckedSub, One, Saturating, Zero};
 use sp_std::prelude::*;
 
 /// Lease period an assigned slot should start from (current, or next one).
@@ -72,6 +74,21 @@ type BalanceOf<T> = <<<T as Config>::Leaser as Leaser<<T as frame_system::Config
 >>::Balance;
 type LeasePeriodOf<T> =
 	<<T as Config>::Leaser as Leaser<<T as frame_system::Config>::BlockNumber>>::LeasePeriod;
+type TemporarySlotOf<T> =
+	ParachainTemporarySlot<<T as frame_system::Config>::AccountId, LeasePeriodOf<T>>;
+
+sp_api::decl_runtime_apis! {
+	/// The API for planning around temporary parachain slots.
+	pub trait AssignedSlotsApi<LeasePeriod>
+	where
+		LeasePeriod: Codec,
+	{
+		/// The projected temporary slot schedule for the given number of lease periods,
+		/// starting with the current one. Each lease period is listed along with the paras
+		/// expected to hold a temporary slot lease during it.
+		fn temporary_slot_schedule(periods: u32) -> Vec<(LeasePeriod, Vec<ParaId>)>;
+	}
+}
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -150,6 +167,23 @@ pub mod pallet {
 	#[pallet::getter(fn active_temporary_slot_count)]
 	pub type ActiveTemporarySlotCount<T: Config> = StorageValue<_, u32, ValueQuery>;
 
+	/// Temporary slots pinned to the lease period their next lease must start in.
+	#[pallet::storage]
+	#[pallet::getter(fn pinned_temporary_slots)]
+	pub type PinnedTemporarySlots<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		LeasePeriodOf<T>,
+		BoundedVec<ParaId, T::MaxTemporarySlotPerLeasePeriod>,
+		ValueQuery,
+	>;
+
+	/// The lease period a temporary slot is pinned to, if any.
+	#[pallet::storage]
+	#[pallet::getter(fn temporary_slot_pin)]
+	pub type TemporarySlotPins<T: Config> =
+		StorageMap<_, Twox64Concat, ParaId, LeasePeriodOf<T>, OptionQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -157,6 +191,10 @@ pub mod pallet {
 		PermanentSlotAssigned(ParaId),
 		/// A para was assigned a temporary parachain slot
 		TemporarySlotAssigned(ParaId),
+		/// A temporary parachain slot was pinned to a lease period
+		TemporarySlotPinned(ParaId, LeasePeriodOf<T>),
+		/// A temporary parachain slot was unpinned
+		TemporarySlotUnpinned(ParaId),
 	}
 
 	#[pallet::error]
@@ -179,6 +217,12 @@ pub mod pallet {
 		MaxPermanentSlotsExceeded,
 		// Maximum number of temporary slots exceeded
 		MaxTemporarySlotsExceeded,
+		/// The lease period to pin a temporary slot to has already started.
+		LeasePeriodInPast,
+		/// All temporary slots are already taken for (part of) the pinned lease.
+		LeasePeriodFull,
+		/// The temporary slot is not pinned to any lease period.
+		SlotNotPinned,
 	}
 
 	#[pallet::hooks]
@@ -364,6 +408,7 @@ pub mod pallet {
 				<PermanentSlotCount<T>>::mutate(|count| *count = count.saturating_sub(One::one()));
 			} else if TemporarySlots::<T>::contains_key(id) {
 				TemporarySlots::<T>::remove(id);
+				Self::remove_temporary_slot_pin(id);
 				<TemporarySlotCount<T>>::mutate(|count| *count = count.saturating_sub(One::one()));
 				if is_parachain {
 					<ActiveTemporarySlotCount<T>>::mutate(|active_count| {
@@ -387,6 +432,65 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		// TODO: Benchmark this
+		/// Pin a temporary parachain slot to a future lease period. The slot will be given a
+		/// lease starting in that lease period, ahead of the regular round-robin allocation.
+		///
+		/// Fails if the slot would overlap with its ongoing lease, or if there would not be
+		/// enough temporary slot capacity for the whole lease. Pinning an already pinned slot
+		/// replaces its previous pin.
+		#[pallet::call_index(3)]
+		#[pallet::weight(((MAXIMUM_BLOCK_WEIGHT / 10) as Weight, DispatchClass::Operational))]
+		pub fn pin_temp_parachain_slot(
+			origin: OriginFor<T>,
+			id: ParaId,
+			lease_period: LeasePeriodOf<T>,
+		) -> DispatchResult {
+			T::AssignSlotOrigin::ensure_origin(origin)?;
+
+			let temp_slot = TemporarySlots::<T>::get(id).ok_or(Error::<T>::SlotNotAssigned)?;
+			ensure!(
+				lease_period > Self::current_lease_period_index(),
+				Error::<T>::LeasePeriodInPast
+			);
+			if let Some(last_lease) = temp_slot.last_lease {
+				ensure!(
+					last_lease.saturating_add(temp_slot.period_count) <= lease_period,
+					Error::<T>::OngoingLeaseExists
+				);
+			}
+
+			for offset in 0..T::TemporarySlotLeasePeriodLength::get() {
+				let period = lease_period.saturating_add(offset.into());
+				ensure!(
+					Self::temporary_slot_occupancy(period, id) <
+						T::MaxTemporarySlotPerLeasePeriod::get(),
+					Error::<T>::LeasePeriodFull
+				);
+			}
+
+			Self::remove_temporary_slot_pin(id);
+			PinnedTemporarySlots::<T>::try_mutate(lease_period, |pinned| pinned.try_push(id))
+				.map_err(|_| Error::<T>::LeasePeriodFull)?;
+			TemporarySlotPins::<T>::insert(id, lease_period);
+
+			Self::deposit_event(Event::<T>::TemporarySlotPinned(id, lease_period));
+			Ok(())
+		}
+
+		// TODO: Benchmark this
+		/// Unpin a temporary parachain slot, returning it to the regular round-robin allocation.
+		#[pallet::call_index(4)]
+		#[pallet::weight(((MAXIMUM_BLOCK_WEIGHT / 10) as Weight, DispatchClass::Operational))]
+		pub fn unpin_temp_parachain_slot(origin: OriginFor<T>, id: ParaId) -> DispatchResult {
+			T::AssignSlotOrigin::ensure_origin(origin)?;
+
+			Self::remove_temporary_slot_pin(id).ok_or(Error::<T>::SlotNotPinned)?;
+
+			Self::deposit_event(Event::<T>::TemporarySlotUnpinned(id));
+			Ok(())
+		}
 	}
 }
 
@@ -394,6 +498,7 @@ impl<T: Config> Pallet<T> {
 	/// Allocate temporary slot leases up to `MaxTemporarySlotPerLeasePeriod` per lease period.
 	/// Beyond the already active temporary slot leases, this function will activate more leases
 	/// in the following order of preference:
+	/// - Assigned slots pinned to the current lease period.
 	/// - Assigned slots that didn't have a turn yet, though their `period_begin` has passed.
 	/// - Assigned slots that already had one (or more) turn(s): they will be considered for the
 	/// current slot lease if they weren't active in the preceding one, and will be ranked by
@@ -404,9 +509,65 @@ impl<T: Config> Pallet<T> {
 	///
 	/// The function will call out to `Leaser::lease_out` to create the appropriate slot leases.
 	fn allocate_temporary_slot_leases(lease_period_index: LeasePeriodOf<T>) -> DispatchResult {
-		let mut active_temp_slots = 0u32;
+		let temp_slots = TemporarySlots::<T>::iter().collect::<Vec<_>>();
+		let (active_temp_slots, slots_to_be_upgraded) =
+			Self::select_temporary_slot_leases(&temp_slots, lease_period_index);
+
+		// Pins for this lease period are honoured (or obsolete) from here on.
+		for id in PinnedTemporarySlots::<T>::take(lease_period_index) {
+			TemporarySlotPins::<T>::remove(id);
+		}
+
+		let mut newly_created_lease = 0u32;
+		for id in slots_to_be_upgraded.iter() {
+			TemporarySlots::<T>::try_mutate::<_, _, Error<T>, _>(id, |s| {
+				let temp_slot = s.as_mut().ok_or(Error::<T>::SlotNotAssigned)?;
+
+				// Configure temp slot lease
+				Self::configure_slot_lease(
+					*id,
+					temp_slot.manager.clone(),
+					lease_period_index,
+					temp_slot.period_count,
+				)
+				.map_err(|_| Error::<T>::CannotUpgrade)?;
+
+				// Update temp slot lease info in storage
+				temp_slot.last_lease = Some(lease_period_index);
+				temp_slot.lease_count += 1;
+
+				newly_created_lease += 1;
+
+				Ok(())
+			})?;
+		}
+
+		ActiveTemporarySlotCount::<T>::set(active_temp_slots.len() as u32 + newly_created_lease);
+
+		Ok(())
+	}
+
+	/// Select which of the given temporary slots should be given a lease starting at
+	/// `lease_period_index`, following the order of preference documented on
+	/// `allocate_temporary_slot_leases`.
+	///
+	/// Returns the paras whose temporary slot lease is already active during that lease period,
+	/// and the paras which should be given a new lease. Capacity needed by slots pinned to one
+	/// of the following lease periods, which a new lease would overlap with, is held back.
+	fn select_temporary_slot_leases(
+		temp_slots: &[(ParaId, TemporarySlotOf<T>)],
+		lease_period_index: LeasePeriodOf<T>,
+	) -> (Vec<ParaId>, Vec<ParaId>) {
+		let pinned = PinnedTemporarySlots::<T>::get(lease_period_index);
+		let upcoming_pins = (1..T::TemporarySlotLeasePeriodLength::get())
+			.flat_map(|offset| {
+				PinnedTemporarySlots::<T>::get(lease_period_index.saturating_add(offset.into()))
+			})
+			.collect::<Vec<_>>();
+
+		let mut active_temp_slots = Vec::new();
 		let mut pending_temp_slots = Vec::new();
-		TemporarySlots::<T>::iter().for_each(|(para, slot)| {
+		temp_slots.iter().for_each(|(para, slot)| {
 				match slot.last_lease {
 					Some(last_lease)
 						if last_lease <= lease_period_index &&
@@ -414,8 +575,11 @@ impl<T: Config> Pallet<T> {
 								(last_lease.saturating_add(slot.period_count)) =>
 					{
 						// Active slot lease
-						active_temp_slots += 1;
+						active_temp_slots.push(*para);
 					}
+					_ if pinned.contains(para) || upcoming_pins.contains(para) => {
+						// Pinned slots only get a lease in the period they are pinned to
+					},
 					Some(last_lease)
 						// Slot w/ past lease, only consider it every other slot lease period (times period_count)
 						if last_lease.saturating_add(slot.period_count.saturating_mul(2u32.into())) <= lease_period_index => {
@@ -431,54 +595,85 @@ impl<T: Config> Pallet<T> {
 				}
 		});
 
-		let mut newly_created_lease = 0u32;
-		if active_temp_slots < T::MaxTemporarySlotPerLeasePeriod::get() &&
-			!pending_temp_slots.is_empty()
-		{
-			// Sort by lease_count, favoring slots that had no or less turns first
-			// (then by last_lease index, and then Para ID)
-			pending_temp_slots.sort_by(|a, b| {
-				a.1.lease_count
-					.cmp(&b.1.lease_count)
-					.then_with(|| a.1.last_lease.cmp(&b.1.last_lease))
-					.then_with(|| a.0.cmp(&b.0))
-			});
-
-			let slots_to_be_upgraded = pending_temp_slots.iter().take(
-				(T::MaxTemporarySlotPerLeasePeriod::get().saturating_sub(active_temp_slots))
-					as usize,
-			);
+		// Pinned slots go first, as long as they are still assigned
+		let mut slots_to_be_upgraded = pinned
+			.into_iter()
+			.filter(|id| {
+				temp_slots.iter().any(|(para, _)| para == id) && !active_temp_slots.contains(id)
+			})
+			.collect::<Vec<_>>();
+
+		// Sort by lease_count, favoring slots that had no or less turns first
+		// (then by last_lease index, and then Para ID)
+		pending_temp_slots.sort_by(|a, b| {
+			a.1.lease_count
+				.cmp(&b.1.lease_count)
+				.then_with(|| a.1.last_lease.cmp(&b.1.last_lease))
+				.then_with(|| a.0.cmp(&b.0))
+		});
 
-			for (id, temp_slot) in slots_to_be_upgraded {
-				TemporarySlots::<T>::try_mutate::<_, _, Error<T>, _>(id, |s| {
-					// Configure temp slot lease
-					Self::configure_slot_lease(
-						*id,
-						temp_slot.manager.clone(),
-						lease_period_index,
-						temp_slot.period_count,
-					)
-					.map_err(|_| Error::<T>::CannotUpgrade)?;
-
-					// Update temp slot lease info in storage
-					*s = Some(ParachainTemporarySlot {
-						manager: temp_slot.manager.clone(),
-						period_begin: temp_slot.period_begin,
-						period_count: temp_slot.period_count,
-						last_lease: Some(lease_period_index),
-						lease_count: temp_slot.lease_count + 1,
-					});
+		let taken = active_temp_slots.len() + slots_to_be_upgraded.len() + upcoming_pins.len();
+		let remaining = (T::MaxTemporarySlotPerLeasePeriod::get() as usize).saturating_sub(taken);
+		slots_to_be_upgraded.extend(pending_temp_slots.iter().take(remaining).map(|(id, _)| **id));
 
-					newly_created_lease += 1;
+		(active_temp_slots, slots_to_be_upgraded)
+	}
 
-					Ok(())
-				})?;
-			}
-		}
+	/// Project the temporary slot schedule for `periods` lease periods, starting with the
+	/// current one, assuming no slots are assigned, pinned or unassigned in the meantime.
+	///
+	/// Each lease period is returned along with the paras expected to hold a temporary slot
+	/// lease during it, in ascending order.
+	pub fn temporary_slot_schedule(periods: u32) -> Vec<(LeasePeriodOf<T>, Vec<ParaId>)> {
+		let current_lease_period = Self::current_lease_period_index();
+		let mut temp_slots = TemporarySlots::<T>::iter().collect::<Vec<_>>();
+
+		(0..periods)
+			.map(|offset| {
+				let lease_period = current_lease_period.saturating_add(offset.into());
+				let (mut paras, new_leases) =
+					Self::select_temporary_slot_leases(&temp_slots, lease_period);
+				// Leases for the current lease period have been allocated already.
+				if !offset.is_zero() {
+					for (para, slot) in temp_slots.iter_mut() {
+						if new_leases.contains(para) {
+							slot.last_lease = Some(lease_period);
+							slot.lease_count += 1;
+						}
+					}
+					paras.extend(new_leases);
+				}
+				paras.sort();
+				(lease_period, paras)
+			})
+			.collect()
+	}
 
-		ActiveTemporarySlotCount::<T>::set(active_temp_slots + newly_created_lease);
+	/// Number of temporary slots, other than `exclude`, which hold a lease or are pinned to a
+	/// lease overlapping with `lease_period_index`.
+	fn temporary_slot_occupancy(lease_period_index: LeasePeriodOf<T>, exclude: ParaId) -> u32 {
+		let leased = TemporarySlots::<T>::iter()
+			.filter(|(para, slot)| {
+				*para != exclude &&
+					slot.last_lease.map_or(false, |last_lease| {
+						last_lease <= lease_period_index &&
+							lease_period_index < last_lease.saturating_add(slot.period_count)
+					})
+			})
+			.count();
+		let pinned = (0..T::TemporarySlotLeasePeriodLength::get())
+			.filter_map(|offset| lease_period_index.checked_sub(&offset.into()))
+			.flat_map(PinnedTemporarySlots::<T>::get)
+			.filter(|para| *para != exclude)
+			.count();
+		(leased + pinned) as u32
+	}
 
-		Ok(())
+	/// Remove the pin of a temporary slot, if any, returning the lease period it was pinned to.
+	fn remove_temporary_slot_pin(id: ParaId) -> Option<LeasePeriodOf<T>> {
+		let lease_period = TemporarySlotPins::<T>::take(id)?;
+		PinnedTemporarySlots::<T>::mutate(lease_period, |pinned| pinned.retain(|para| *para != id));
+		Some(lease_period)
 	}
 
 	/// Clear out all slot leases for both permanent & temporary slots.
@@ -551,8 +746,8 @@ mod tests {
 	use pallet_balances;
 	use primitives::{BlockNumber, Header};
 	use runtime_parachains::{
-		configuration as parachains_configuration, paras as parachains_paras,
-		shared as parachains_shared,
+		configuration as parachains_configuration, origin as parachains_origin,
+		paras as parachains_paras, shared as parachains_shared,
 	};
 	use sp_core::H256;
 	use sp_runtime::{
@@ -575,6 +770,7 @@ mod tests {
 			Configuration: parachains_configuration::{Pallet, Call, Storage, Config<T>},
 			ParasShared: parachains_shared::{Pallet, Call, Storage},
 			Parachains: parachains_paras::{Pallet, Call, Storage, Config, Event},
+			ParachainsOrigin: parachains_origin::{Pallet, Origin},
 			Slots: slots::{Pallet, Call, Storage, Event<T>},
 			AssignedSlots: assigned_slots::{Pallet, Call, Storage, Event<T>},
 		}
@@ -644,6 +840,7 @@ mod tests {
 
 	impl parachains_paras::Config for Test {
 		type RuntimeEvent = RuntimeEvent;
+		type RuntimeOrigin = RuntimeOrigin;
 		type WeightInfo = parachains_paras::TestWeightInfo;
 		type UnsignedPriority = ParasUnsignedPriority;
 		type NextSessionRotation = crate::mock::TestNextSessionRotation;
@@ -651,6 +848,8 @@ mod tests {
 
 	impl parachains_shared::Config for Test {}
 
+	impl parachains_origin::Config for Test {}
+
 	parameter_types! {
 		pub const LeasePeriod: BlockNumber = 3;
 		pub static LeaseOffset: BlockNumber = 0;
@@ -1321,4 +1520,202 @@ mod tests {
 			assert_eq!(Slots::already_leased(ParaId::from(1_u32), 0, 1), false);
 		});
 	}
+
+	// Register paras `0..count` with a temporary slot each, alternating between slots starting
+	// in the current lease period and slots starting in the next one.
+	fn assign_temp_slots(count: u64) {
+		for n in 0..count {
+			assert_ok!(TestRegistrar::<Test>::register(
+				n,
+				ParaId::from(n as u32),
+				dummy_head_data(),
+				dummy_validation_code()
+			));
+
+			assert_ok!(AssignedSlots::assign_temp_parachain_slot(
+				RuntimeOrigin::root(),
+				ParaId::from(n as u32),
+				if (n % 2).is_zero() {
+					SlotLeasePeriodStart::Current
+				} else {
+					SlotLeasePeriodStart::Next
+				}
+			));
+		}
+	}
+
+	fn paras(ids: &[u32]) -> Vec<ParaId> {
+		ids.iter().map(|id| ParaId::from(*id)).collect()
+	}
+
+	#[test]
+	fn temporary_slot_schedule_projects_round_robin() {
+		new_test_ext().execute_with(|| {
+			// Block 1, Period 0
+			run_to_block(1);
+			assign_temp_slots(6);
+
+			assert_eq!(
+				AssignedSlots::temporary_slot_schedule(8),
+				vec![
+					(0, paras(&[0, 2])),
+					(1, paras(&[0, 2])),
+					(2, paras(&[1, 3])),
+					(3, paras(&[1, 3])),
+					(4, paras(&[4, 5])),
+					(5, paras(&[4, 5])),
+					(6, paras(&[0, 2])),
+					(7, paras(&[0, 2])),
+				]
+			);
+
+			// Block 6, Period 2
+			run_to_block(6);
+			assert_eq!(
+				AssignedSlots::temporary_slot_schedule(3),
+				vec![(2, paras(&[1, 3])), (3, paras(&[1, 3])), (4, paras(&[4, 5]))]
+			);
+			assert_eq!(TestRegistrar::<Test>::is_parachain(ParaId::from(1_u32)), true);
+			assert_eq!(TestRegistrar::<Test>::is_parachain(ParaId::from(3_u32)), true);
+		});
+	}
+
+	#[test]
+	fn pin_temp_slot_works() {
+		new_test_ext().execute_with(|| {
+			// Block 1, Period 0
+			run_to_block(1);
+			assign_temp_slots(6);
+
+			assert_ok!(AssignedSlots::pin_temp_parachain_slot(
+				RuntimeOrigin::root(),
+				ParaId::from(5_u32),
+				2,
+			));
+			assert_eq!(AssignedSlots::temporary_slot_pin(ParaId::from(5_u32)), Some(2));
+
+			// The pinned slot takes precedence over the round-robin order
+			assert_eq!(
+				AssignedSlots::temporary_slot_schedule(8),
+				vec![
+					(0, paras(&[0, 2])),
+					(1, paras(&[0, 2])),
+					(2, paras(&[1, 5])),
+					(3, paras(&[1, 5])),
+					(4, paras(&[3, 4])),
+					(5, paras(&[3, 4])),
+					(6, paras(&[0, 2])),
+					(7, paras(&[0, 2])),
+				]
+			);
+
+			// Block 6, Period 2
+			run_to_block(6);
+			assert_eq!(TestRegistrar::<Test>::is_parachain(ParaId::from(1_u32)), true);
+			assert_eq!(TestRegistrar::<Test>::is_parachain(ParaId::from(3_u32)), false);
+			assert_eq!(TestRegistrar::<Test>::is_parachain(ParaId::from(5_u32)), true);
+			assert_eq!(AssignedSlots::active_temporary_slot_count(), 2);
+
+			// The pin has been consumed
+			assert_eq!(AssignedSlots::temporary_slot_pin(ParaId::from(5_u32)), None);
+			assert!(AssignedSlots::pinned_temporary_slots(2).is_empty());
+		});
+	}
+
+	#[test]
+	fn pin_temp_slot_detects_conflicts() {
+		new_test_ext().execute_with(|| {
+			// Block 1, Period 0
+			run_to_block(1);
+			assign_temp_slots(6);
+
+			assert_noop!(
+				AssignedSlots::pin_temp_parachain_slot(
+					RuntimeOrigin::signed(1),
+					ParaId::from(4_u32),
+					2,
+				),
+				BadOrigin
+			);
+			assert_noop!(
+				AssignedSlots::pin_temp_parachain_slot(
+					RuntimeOrigin::root(),
+					ParaId::from(6_u32),
+					2,
+				),
+				Error::<Test>::SlotNotAssigned
+			);
+			assert_noop!(
+				AssignedSlots::pin_temp_parachain_slot(
+					RuntimeOrigin::root(),
+					ParaId::from(4_u32),
+					0,
+				),
+				Error::<Test>::LeasePeriodInPast
+			);
+			// Para 0 holds a lease for periods 0-1
+			assert_noop!(
+				AssignedSlots::pin_temp_parachain_slot(
+					RuntimeOrigin::root(),
+					ParaId::from(0_u32),
+					1,
+				),
+				Error::<Test>::OngoingLeaseExists
+			);
+
+			assert_ok!(AssignedSlots::pin_temp_parachain_slot(
+				RuntimeOrigin::root(),
+				ParaId::from(4_u32),
+				2,
+			));
+			assert_ok!(AssignedSlots::pin_temp_parachain_slot(
+				RuntimeOrigin::root(),
+				ParaId::from(5_u32),
+				2,
+			));
+			// Periods 2-3 are fully taken by the pinned slots
+			assert_noop!(
+				AssignedSlots::pin_temp_parachain_slot(
+					RuntimeOrigin::root(),
+					ParaId::from(1_u32),
+					3,
+				),
+				Error::<Test>::LeasePeriodFull
+			);
+
+			// Pinning again moves the pin
+			assert_ok!(AssignedSlots::pin_temp_parachain_slot(
+				RuntimeOrigin::root(),
+				ParaId::from(4_u32),
+				4,
+			));
+			assert_eq!(AssignedSlots::pinned_temporary_slots(2).into_inner(), paras(&[5]));
+			assert_eq!(AssignedSlots::pinned_temporary_slots(4).into_inner(), paras(&[4]));
+			assert_ok!(AssignedSlots::pin_temp_parachain_slot(
+				RuntimeOrigin::root(),
+				ParaId::from(1_u32),
+				3,
+			));
+
+			assert_ok!(AssignedSlots::unpin_temp_parachain_slot(
+				RuntimeOrigin::root(),
+				ParaId::from(4_u32),
+			));
+			assert_noop!(
+				AssignedSlots::unpin_temp_parachain_slot(
+					RuntimeOrigin::root(),
+					ParaId::from(4_u32),
+				),
+				Error::<Test>::SlotNotPinned
+			);
+
+			// Unassigning a slot removes its pin
+			assert_ok!(AssignedSlots::unassign_parachain_slot(
+				RuntimeOrigin::root(),
+				ParaId::from(5_u32),
+			));
+			assert_eq!(AssignedSlots::temporary_slot_pin(ParaId::from(5_u32)), None);
+			assert!(AssignedSlots::pinned_temporary_slots(2).is_empty());
+		});
+	}
 }
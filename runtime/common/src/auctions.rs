@@ -17,6 +17,11 @@
 //! Auctioning system to determine the set of Parachains in operation. This includes logic for the
 //! auctioning mechanism and for reserving balance as part of the "payment". Unreserving the balance
 //! happens elsewhere.
+//!
+//! For each closed auction, the randomness that chose the winning sample of the ending period and
+//! the winning bids at that sample are kept as an `AuctionAudit` for the last `MaxAuctionAudits`
+//! auctions. They are exposed through the `AuctionsApi` runtime API, and `AuctionAudit::replay`
+//! re-derives the winners from them, so that anyone can verify an auction's outcome.
 
 use crate::{
 	slot_range::SlotRange,
@@ -29,9 +34,13 @@ use frame_support::{
 	weights::Weight,
 };
 pub use pallet::*;
-use parity_scale_codec::Decode;
+use parity_scale_codec::{Codec, Decode, Encode, MaxEncodedLen};
 use primitives::Id as ParaId;
-use sp_runtime::traits::{CheckedSub, One, Saturating, Zero};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, CheckedSub, One, Saturating, Zero},
+	RuntimeDebug,
+};
 use sp_std::{mem::swap, prelude::*};
 
 type CurrencyOf<T> =
@@ -76,6 +85,66 @@ type WinningData<T> = [Option<(<T as frame_system::Config>::AccountId, ParaId, B
 // index assigned to them, their winning bid and the range that they won.
 type WinnersData<T> =
 	Vec<(<T as frame_system::Config>::AccountId, ParaId, BalanceOf<T>, SlotRange)>;
+type AuctionAuditOf<T> = AuctionAudit<
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T>,
+	<T as frame_system::Config>::BlockNumber,
+	<T as frame_system::Config>::Hash,
+>;
+
+/// How the winners of a closed auction were chosen.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct AuctionAudit<AccountId, Balance, BlockNumber, Hash> {
+	/// The first lease period of the lease periods that were auctioned.
+	pub lease_period_index: BlockNumber,
+	/// The length of the ending period of the auction.
+	pub ending_period: BlockNumber,
+	/// The length of each sample of the ending period.
+	pub sample_length: BlockNumber,
+	/// The randomness output from which the winning sample was derived.
+	pub randomness: Hash,
+	/// The block number since which `randomness` was known.
+	pub known_since: BlockNumber,
+	/// The index of the winning sample in the ending period.
+	pub sample: BlockNumber,
+	/// The top bid for each slot range at the winning sample.
+	pub winning: [Option<(AccountId, ParaId, Balance)>; SlotRange::SLOT_RANGE_COUNT],
+}
+
+impl<AccountId, Balance, BlockNumber, Hash> AuctionAudit<AccountId, Balance, BlockNumber, Hash>
+where
+	AccountId: Clone,
+	Balance: AtLeast32BitUnsigned + Copy,
+	BlockNumber: AtLeast32BitUnsigned + Decode + Copy,
+	Hash: AsRef<[u8]>,
+{
+	/// Re-derive the winners of the audited auction, as `calculate_winners` did when it closed.
+	///
+	/// Returns `None` if the recorded `sample` is not the one chosen by the recorded `randomness`.
+	/// The ranges of the winners are relative to `lease_period_index`.
+	pub fn replay(&self) -> Option<Vec<(AccountId, ParaId, Balance, SlotRange)>> {
+		let sample =
+			winning_sample(self.randomness.as_ref(), self.ending_period, self.sample_length)?;
+		(sample == self.sample).then(|| calculate_winners(self.winning.clone()))
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API for inspecting the outcome of past auctions.
+	pub trait AuctionsApi<AccountId, Balance, BlockNumber, Hash>
+	where
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+		Hash: Codec,
+	{
+		/// How the winners of the given auction were chosen, if it is among the recently closed
+		/// auctions which are still audited.
+		fn auction_audit(
+			auction_index: AuctionIndex,
+		) -> Option<AuctionAudit<AccountId, Balance, BlockNumber, Hash>>;
+	}
+}
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -116,6 +185,10 @@ pub mod pallet {
 		/// Something that provides randomness in the runtime.
 		type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
 
+		/// The number of most recently closed auctions for which an `AuctionAudit` is kept.
+		#[pallet::constant]
+		type MaxAuctionAudits: Get<u32>;
+
 		/// The origin which may initiate auctions.
 		type InitiateOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 
@@ -201,6 +274,13 @@ pub mod pallet {
 	#[pallet::getter(fn winning)]
 	pub type Winning<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, WinningData<T>>;
 
+	/// How the winners of each of the last `MaxAuctionAudits` closed auctions were chosen, by
+	/// auction index.
+	#[pallet::storage]
+	#[pallet::getter(fn auction_audit)]
+	pub type AuctionAudits<T: Config> =
+		StorageMap<_, Twox64Concat, AuctionIndex, AuctionAuditOf<T>>;
+
 	#[pallet::extra_constants]
 	impl<T: Config> Pallet<T> {
 		#[pallet::constant_name(SlotRangeCount)]
@@ -372,6 +452,19 @@ impl<T: Config> Auctioneer<T::BlockNumber> for Pallet<T> {
 	fn has_won_an_auction(para: ParaId, bidder: &T::AccountId) -> bool {
 		!T::Leaser::deposit_held(para, bidder).is_zero()
 	}
+
+	#[cfg(any(feature = "runtime-benchmarks", test))]
+	fn win_auction(
+		bidder: &T::AccountId,
+		para: ParaId,
+		first_slot: LeasePeriodOf<T>,
+		last_slot: LeasePeriodOf<T>,
+		amount: BalanceOf<T>,
+	) -> DispatchResult {
+		let period_count = last_slot.saturating_sub(first_slot).saturating_add(One::one());
+		T::Leaser::lease_out(para, bidder, amount, first_slot, period_count)
+			.map_err(|_| "failed to lease out the auctioned periods".into())
+	}
 }
 
 impl<T: Config> Pallet<T> {
@@ -543,12 +636,9 @@ impl<T: Config> Pallet<T> {
 
 				if late_end <= known_since {
 					// Our random seed was known only after the auction ended. Good to use.
-					let raw_offset_block_number = <T::BlockNumber>::decode(
-						&mut raw_offset.as_ref(),
-					)
-					.expect("secure hashes should always be bigger than the block number; qed");
-					let offset = (raw_offset_block_number % ending_period) /
-						T::SampleLength::get().max(One::one());
+					let sample_length = T::SampleLength::get();
+					let offset = winning_sample(raw_offset.as_ref(), ending_period, sample_length)
+						.expect("secure hashes should always be bigger than the block number; qed");
 
 					let auction_counter = AuctionCounter::<T>::get();
 					Self::deposit_event(Event::<T>::WinningOffset {
@@ -557,6 +647,22 @@ impl<T: Config> Pallet<T> {
 					});
 					let res = Winning::<T>::get(offset)
 						.unwrap_or([Self::EMPTY; SlotRange::SLOT_RANGE_COUNT]);
+
+					AuctionAudits::<T>::insert(
+						auction_counter,
+						AuctionAudit {
+							lease_period_index,
+							ending_period,
+							sample_length,
+							randomness: raw_offset,
+							known_since,
+							sample: offset,
+							winning: res.clone(),
+						},
+					);
+					if let Some(stale) = auction_counter.checked_sub(T::MaxAuctionAudits::get()) {
+						AuctionAudits::<T>::remove(stale);
+					}
 					// This `remove_all` statement should remove at most `EndingPeriod` / `SampleLength` items,
 					// which should be bounded and sensibly configured in the runtime.
 					#[allow(deprecated)]
@@ -622,52 +728,74 @@ impl<T: Config> Pallet<T> {
 	}
 
 	/// Calculate the final winners from the winning slots.
-	///
-	/// This is a simple dynamic programming algorithm designed by Al, the original code is at:
-	/// `https://github.com/w3f/consensus/blob/master/NPoS/auctiondynamicthing.py`
-	fn calculate_winners(mut winning: WinningData<T>) -> WinnersData<T> {
-		let winning_ranges = {
-			let mut best_winners_ending_at: [(Vec<SlotRange>, BalanceOf<T>);
-				SlotRange::LEASE_PERIODS_PER_SLOT] = Default::default();
-			let best_bid = |range: SlotRange| {
-				winning[range as u8 as usize]
-					.as_ref()
-					.map(|(_, _, amount)| *amount * (range.len() as u32).into())
-			};
-			for i in 0..SlotRange::LEASE_PERIODS_PER_SLOT {
-				let r = SlotRange::new_bounded(0, 0, i as u32).expect("`i < LPPS`; qed");
-				if let Some(bid) = best_bid(r) {
-					best_winners_ending_at[i] = (vec![r], bid);
-				}
-				for j in 0..i {
-					let r = SlotRange::new_bounded(0, j as u32 + 1, i as u32)
-						.expect("`i < LPPS`; `j < i`; `j + 1 < LPPS`; qed");
-					if let Some(mut bid) = best_bid(r) {
-						bid += best_winners_ending_at[j].1;
-						if bid > best_winners_ending_at[i].1 {
-							let mut new_winners = best_winners_ending_at[j].0.clone();
-							new_winners.push(r);
-							best_winners_ending_at[i] = (new_winners, bid);
-						}
-					} else {
-						if best_winners_ending_at[j].1 > best_winners_ending_at[i].1 {
-							best_winners_ending_at[i] = best_winners_ending_at[j].clone();
-						}
+	fn calculate_winners(winning: WinningData<T>) -> WinnersData<T> {
+		calculate_winners(winning)
+	}
+}
+
+/// Calculate the final winners from the winning slots.
+///
+/// This is a simple dynamic programming algorithm designed by Al, the original code is at:
+/// `https://github.com/w3f/consensus/blob/master/NPoS/auctiondynamicthing.py`
+pub fn calculate_winners<AccountId, Balance>(
+	mut winning: [Option<(AccountId, ParaId, Balance)>; SlotRange::SLOT_RANGE_COUNT],
+) -> Vec<(AccountId, ParaId, Balance, SlotRange)>
+where
+	Balance: AtLeast32BitUnsigned + Copy,
+{
+	let winning_ranges = {
+		let mut best_winners_ending_at: [(Vec<SlotRange>, Balance);
+			SlotRange::LEASE_PERIODS_PER_SLOT] = Default::default();
+		let best_bid = |range: SlotRange| {
+			winning[range as u8 as usize]
+				.as_ref()
+				.map(|(_, _, amount)| *amount * (range.len() as u32).into())
+		};
+		for i in 0..SlotRange::LEASE_PERIODS_PER_SLOT {
+			let r = SlotRange::new_bounded(0, 0, i as u32).expect("`i < LPPS`; qed");
+			if let Some(bid) = best_bid(r) {
+				best_winners_ending_at[i] = (vec![r], bid);
+			}
+			for j in 0..i {
+				let r = SlotRange::new_bounded(0, j as u32 + 1, i as u32)
+					.expect("`i < LPPS`; `j < i`; `j + 1 < LPPS`; qed");
+				if let Some(mut bid) = best_bid(r) {
+					bid += best_winners_ending_at[j].1;
+					if bid > best_winners_ending_at[i].1 {
+						let mut new_winners = best_winners_ending_at[j].0.clone();
+						new_winners.push(r);
+						best_winners_ending_at[i] = (new_winners, bid);
+					}
+				} else {
+					if best_winners_ending_at[j].1 > best_winners_ending_at[i].1 {
+						best_winners_ending_at[i] = best_winners_ending_at[j].clone();
 					}
 				}
 			}
-			best_winners_ending_at[SlotRange::LEASE_PERIODS_PER_SLOT - 1].0.clone()
-		};
+		}
+		best_winners_ending_at[SlotRange::LEASE_PERIODS_PER_SLOT - 1].0.clone()
+	};
 
-		winning_ranges
-			.into_iter()
-			.filter_map(|range| {
-				winning[range as u8 as usize]
-					.take()
-					.map(|(bidder, para, amount)| (bidder, para, amount, range))
-			})
-			.collect::<Vec<_>>()
-	}
+	winning_ranges
+		.into_iter()
+		.filter_map(|range| {
+			winning[range as u8 as usize]
+				.take()
+				.map(|(bidder, para, amount)| (bidder, para, amount, range))
+		})
+		.collect::<Vec<_>>()
+}
+
+/// The index of the sample of an auction's ending period which is chosen by `randomness`.
+///
+/// Returns `None` if `randomness` is too short to derive a block number from.
+pub fn winning_sample<BlockNumber: AtLeast32BitUnsigned + Decode>(
+	mut randomness: &[u8],
+	ending_period: BlockNumber,
+	sample_length: BlockNumber,
+) -> Option<BlockNumber> {
+	let raw_offset = BlockNumber::decode(&mut randomness).ok()?;
+	Some((raw_offset % ending_period) / sample_length.max(One::one()))
 }
 
 /// tests for this module
@@ -867,6 +995,7 @@ mod tests {
 	parameter_types! {
 		pub static EndingPeriod: BlockNumber = 3;
 		pub static SampleLength: BlockNumber = 1;
+		pub const MaxAuctionAudits: u32 = 2;
 	}
 
 	impl Config for Test {
@@ -876,6 +1005,7 @@ mod tests {
 		type EndingPeriod = EndingPeriod;
 		type SampleLength = SampleLength;
 		type Randomness = TestPastRandomness;
+		type MaxAuctionAudits = MaxAuctionAudits;
 		type InitiateOrigin = RootOrSix;
 		type WeightInfo = crate::auctions::TestWeightInfo;
 	}
@@ -1619,6 +1749,76 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn auction_audit_allows_replaying_winners() {
+		new_test_ext().execute_with(|| {
+			EndingPeriod::set(30);
+			SampleLength::set(10);
+
+			run_to_block(1);
+			assert_ok!(Auctions::new_auction(RuntimeOrigin::signed(6), 9, 11));
+			let para_1 = ParaId::from(1_u32);
+			let para_2 = ParaId::from(2_u32);
+			assert_ok!(Auctions::bid(RuntimeOrigin::signed(1), para_1, 1, 11, 14, 10));
+
+			// A later bid does not make it into the first sample.
+			run_to_block(20);
+			assert_ok!(Auctions::bid(RuntimeOrigin::signed(2), para_2, 1, 13, 14, 20));
+
+			// Randomness of zero picks the first sample.
+			set_last_random(H256::zero(), 40);
+			run_to_block(40);
+			assert_eq!(
+				leases(),
+				vec![
+					((1.into(), 11), LeaseData { leaser: 1, amount: 10 }),
+					((1.into(), 12), LeaseData { leaser: 1, amount: 10 }),
+					((1.into(), 13), LeaseData { leaser: 1, amount: 10 }),
+					((1.into(), 14), LeaseData { leaser: 1, amount: 10 }),
+				]
+			);
+
+			let mut winning = [None; SlotRange::SLOT_RANGE_COUNT];
+			winning[SlotRange::ZeroThree as u8 as usize] = Some((1, para_1, 10));
+			let audit = Auctions::auction_audit(1).unwrap();
+			assert_eq!(
+				audit,
+				AuctionAudit {
+					lease_period_index: 11,
+					ending_period: 30,
+					sample_length: 10,
+					randomness: H256::zero(),
+					known_since: 40,
+					sample: 0,
+					winning,
+				}
+			);
+			assert_eq!(audit.replay(), Some(vec![(1, para_1, 10, SlotRange::ZeroThree)]));
+
+			// A sample which does not follow from the randomness is caught.
+			let tampered = AuctionAudit { sample: 2, ..audit };
+			assert_eq!(tampered.replay(), None);
+		});
+	}
+
+	#[test]
+	fn auction_audits_are_bounded() {
+		new_test_ext().execute_with(|| {
+			for auction_index in 1..=3 {
+				let now = System::block_number() + 1;
+				run_to_block(now);
+				assert_ok!(Auctions::new_auction(RuntimeOrigin::signed(6), 5, 1));
+				set_last_random(H256::zero(), now + 5 + EndingPeriod::get());
+				run_to_block(now + 5 + EndingPeriod::get());
+				assert!(Auctions::auction_audit(auction_index).is_some());
+			}
+
+			// Only the last `MaxAuctionAudits` auctions are kept.
+			assert_eq!(Auctions::auction_audit(1), None);
+			assert!(Auctions::auction_audit(2).is_some());
+		});
+	}
+
 	#[test]
 	fn auction_status_works() {
 		new_test_ext().execute_with(|| {
@@ -1892,6 +2092,7 @@ mod benchmarking {
 			let auction_index = AuctionCounter::<T>::get();
 			assert_last_event::<T>(Event::<T>::AuctionClosed { auction_index }.into());
 			assert!(Winning::<T>::iter().count().is_zero());
+			assert!(AuctionAudits::<T>::contains_key(auction_index));
 		}
 
 		// Worst case: 10 bidders taking all wining spots, and winning data is full.
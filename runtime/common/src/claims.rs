@@ -15,11 +15,16 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Pallet to process claims from Ethereum addresses.
+//!
+//! Claims are keyed by the configured external `Address` type. Besides the Ethereum
+//! `claim`/`claim_attest` calls, `claim_with_proof` accepts any proof understood by the
+//! configured `ClaimVerifier`, such as EIP-712 typed data or Substrate signatures.
 
 use frame_support::{
 	ensure,
 	traits::{Currency, Get, IsSubType, VestingSchedule},
 	weights::Weight,
+	Parameter,
 };
 pub use pallet::*;
 use parity_scale_codec::{Decode, Encode};
@@ -31,11 +36,11 @@ use sp_io::{crypto::secp256k1_ecdsa_recover, hashing::keccak_256};
 #[cfg(feature = "std")]
 use sp_runtime::traits::Zero;
 use sp_runtime::{
-	traits::{CheckedSub, DispatchInfoOf, SignedExtension},
+	traits::{CheckedSub, DispatchInfoOf, MaybeSerializeDeserialize, SignedExtension, Verify},
 	transaction_validity::{
 		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
 	},
-	RuntimeDebug,
+	AccountId32, MultiSignature, RuntimeDebug,
 };
 use sp_std::{fmt::Debug, prelude::*};
 
@@ -43,6 +48,10 @@ type CurrencyOf<T> = <<T as Config>::VestingSchedule as VestingSchedule<
 	<T as frame_system::Config>::AccountId,
 >>::Currency;
 type BalanceOf<T> = <CurrencyOf<T> as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+type ClaimProofOf<T> =
+	<<T as Config>::Verifier as ClaimVerifier<<T as Config>::Address>>::Proof;
+
+pub mod migration;
 
 pub trait WeightInfo {
 	fn claim() -> Weight;
@@ -50,6 +59,7 @@ pub trait WeightInfo {
 	fn claim_attest() -> Weight;
 	fn attest() -> Weight;
 	fn move_claim() -> Weight;
+	fn claim_with_proof() -> Weight;
 }
 
 pub struct TestWeightInfo;
@@ -69,6 +79,9 @@ impl WeightInfo for TestWeightInfo {
 	fn move_claim() -> Weight {
 		Weight::zero()
 	}
+	fn claim_with_proof() -> Weight {
+		Weight::zero()
+	}
 }
 
 /// The kind of statement an account needs to make for a claim to be valid.
@@ -151,12 +164,233 @@ impl PartialEq for EcdsaSignature {
 	}
 }
 
+impl Eq for EcdsaSignature {}
+
 impl sp_std::fmt::Debug for EcdsaSignature {
 	fn fmt(&self, f: &mut sp_std::fmt::Formatter<'_>) -> sp_std::fmt::Result {
 		write!(f, "EcdsaSignature({:?})", &self.0[..])
 	}
 }
 
+/// An address on another chain which a claim can be made from.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum ExternalAddress {
+	/// An Ethereum address.
+	Ethereum(EthereumAddress),
+	/// A 32 byte Substrate account ID.
+	Substrate([u8; 32]),
+}
+
+impl From<EthereumAddress> for ExternalAddress {
+	fn from(address: EthereumAddress) -> Self {
+		ExternalAddress::Ethereum(address)
+	}
+}
+
+impl From<AccountId32> for ExternalAddress {
+	fn from(account: AccountId32) -> Self {
+		ExternalAddress::Substrate(account.into())
+	}
+}
+
+/// Something that can verify that the owner of an external address wants its claim to be paid
+/// out to a given account.
+pub trait ClaimVerifier<Address> {
+	/// The proof submitted along with a claim, usually a signature.
+	type Proof: Parameter;
+
+	/// Return the address which made `proof` for paying its claim out to the SCALE encoded
+	/// account `dest`, while attesting to `statement`, if the proof is valid.
+	///
+	/// `prefix` is the configured claims prefix, which is part of every signed message.
+	fn verify(
+		proof: &Self::Proof,
+		prefix: &[u8],
+		dest: &[u8],
+		statement: &[u8],
+	) -> Option<Address>;
+
+	/// Create a valid proof, made by an address derived from `seed`, returning it along with
+	/// that address.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn create_proof(
+		seed: u32,
+		prefix: &[u8],
+		dest: &[u8],
+		statement: &[u8],
+	) -> (Self::Proof, Address);
+}
+
+/// Verifies signatures made with the Ethereum RPC's `personal_sign` and `eth_sign`, over the
+/// message `(prefix)(hex encoded dest)(statement)`.
+pub struct EthereumPersonalSign;
+
+impl<Address: From<EthereumAddress>> ClaimVerifier<Address> for EthereumPersonalSign {
+	type Proof = EcdsaSignature;
+
+	fn verify(
+		proof: &Self::Proof,
+		prefix: &[u8],
+		dest: &[u8],
+		statement: &[u8],
+	) -> Option<Address> {
+		let msg = ethereum_signable_message(prefix, &to_ascii_hex(dest), statement);
+		ethereum_recover(proof, &keccak_256(&msg)).map(Into::into)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn create_proof(
+		seed: u32,
+		prefix: &[u8],
+		dest: &[u8],
+		statement: &[u8],
+	) -> (Self::Proof, Address) {
+		let secret = secp_utils::secret(seed);
+		let msg = ethereum_signable_message(prefix, &to_ascii_hex(dest), statement);
+		(secp_utils::sign_hash(&secret, &keccak_256(&msg)), secp_utils::eth(&secret).into())
+	}
+}
+
+/// Verifies EIP-712 typed data signatures over a `Claim(bytes dest,bytes statement)` struct,
+/// where `dest` is the SCALE encoded account. The domain is
+/// `EIP712Domain(string name,string version,uint256 chainId)`, named after the claims prefix at
+/// version `1`, with the chain ID given by `ChainId`. Chains sharing a claims prefix must use
+/// different chain IDs, so that a signature made for one chain cannot be replayed on another.
+pub struct Eip712TypedData<ChainId>(sp_std::marker::PhantomData<ChainId>);
+
+impl<Address, ChainId> ClaimVerifier<Address> for Eip712TypedData<ChainId>
+where
+	Address: From<EthereumAddress>,
+	ChainId: Get<u64>,
+{
+	type Proof = EcdsaSignature;
+
+	fn verify(
+		proof: &Self::Proof,
+		prefix: &[u8],
+		dest: &[u8],
+		statement: &[u8],
+	) -> Option<Address> {
+		let digest = eip712_claim_digest(prefix, ChainId::get(), dest, statement);
+		ethereum_recover(proof, &digest).map(Into::into)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn create_proof(
+		seed: u32,
+		prefix: &[u8],
+		dest: &[u8],
+		statement: &[u8],
+	) -> (Self::Proof, Address) {
+		let secret = secp_utils::secret(seed);
+		let msg = eip712_claim_digest(prefix, ChainId::get(), dest, statement);
+		(secp_utils::sign_hash(&secret, &msg), secp_utils::eth(&secret).into())
+	}
+}
+
+/// A Substrate signature along with the account which made it.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct SubstrateClaimProof {
+	/// The account which signed the claim.
+	pub signer: AccountId32,
+	/// The sr25519, ed25519 or ECDSA signature.
+	pub signature: MultiSignature,
+}
+
+/// Verifies Substrate `MultiSignature`s over the message
+/// `<Bytes>(prefix)(hex encoded dest)(statement)</Bytes>`, which is how wallets sign raw bytes.
+pub struct SubstrateSignature;
+
+impl<Address: From<AccountId32>> ClaimVerifier<Address> for SubstrateSignature {
+	type Proof = SubstrateClaimProof;
+
+	fn verify(
+		proof: &Self::Proof,
+		prefix: &[u8],
+		dest: &[u8],
+		statement: &[u8],
+	) -> Option<Address> {
+		let msg = substrate_signable_message(prefix, &to_ascii_hex(dest), statement);
+		proof
+			.signature
+			.verify(&msg[..], &proof.signer)
+			.then(|| proof.signer.clone().into())
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn create_proof(
+		_seed: u32,
+		prefix: &[u8],
+		dest: &[u8],
+		statement: &[u8],
+	) -> (Self::Proof, Address) {
+		use sp_core::crypto::key_types::DUMMY;
+
+		let msg = substrate_signable_message(prefix, &to_ascii_hex(dest), statement);
+		let public = sp_io::crypto::sr25519_generate(DUMMY, Some(b"//Claims".to_vec()));
+		let signature = sp_io::crypto::sr25519_sign(DUMMY, &public, &msg)
+			.expect("the key was generated in the keystore above; qed");
+		let signer = AccountId32::from(public);
+		(
+			SubstrateClaimProof { signer: signer.clone(), signature: signature.into() },
+			signer.into(),
+		)
+	}
+}
+
+/// A claim proof accepted by `MultiClaimVerifier`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum ClaimProof {
+	/// A signature checked by `EthereumPersonalSign`.
+	Ethereum(EcdsaSignature),
+	/// A signature checked by `Eip712TypedData`.
+	Eip712(EcdsaSignature),
+	/// A signature checked by `SubstrateSignature`.
+	Substrate(SubstrateClaimProof),
+}
+
+/// Verifies any of the proofs in `ClaimProof`, with `ChainId` being the EIP-712 chain ID.
+pub struct MultiClaimVerifier<ChainId>(sp_std::marker::PhantomData<ChainId>);
+
+impl<Address, ChainId> ClaimVerifier<Address> for MultiClaimVerifier<ChainId>
+where
+	Address: From<EthereumAddress> + From<AccountId32>,
+	ChainId: Get<u64>,
+{
+	type Proof = ClaimProof;
+
+	fn verify(
+		proof: &Self::Proof,
+		prefix: &[u8],
+		dest: &[u8],
+		statement: &[u8],
+	) -> Option<Address> {
+		match proof {
+			ClaimProof::Ethereum(s) =>
+				<EthereumPersonalSign as ClaimVerifier<_>>::verify(s, prefix, dest, statement),
+			ClaimProof::Eip712(s) =>
+				<Eip712TypedData<ChainId> as ClaimVerifier<_>>::verify(s, prefix, dest, statement),
+			ClaimProof::Substrate(p) =>
+				<SubstrateSignature as ClaimVerifier<_>>::verify(p, prefix, dest, statement),
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn create_proof(
+		seed: u32,
+		prefix: &[u8],
+		dest: &[u8],
+		statement: &[u8],
+	) -> (Self::Proof, Address) {
+		// EIP-712 signatures are the most expensive ones to verify.
+		let (proof, address) = <Eip712TypedData<ChainId> as ClaimVerifier<_>>::create_proof(
+			seed, prefix, dest, statement,
+		);
+		(ClaimProof::Eip712(proof), address)
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -165,6 +399,7 @@ pub mod pallet {
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(migration::STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -177,14 +412,19 @@ pub mod pallet {
 		#[pallet::constant]
 		type Prefix: Get<&'static [u8]>;
 		type MoveClaimOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// The external address type claims are keyed by.
+		type Address: Parameter + Copy + MaybeSerializeDeserialize + From<EthereumAddress>;
+		/// Verifies the proofs submitted with `claim_with_proof`.
+		type Verifier: ClaimVerifier<Self::Address>;
 		type WeightInfo: WeightInfo;
 	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// Someone claimed some DOTs.
-		Claimed { who: T::AccountId, ethereum_address: EthereumAddress, amount: BalanceOf<T> },
+		/// Someone claimed some DOTs. `ethereum_address` is the address the claim was made from,
+		/// which is not an Ethereum address if the chain accepts claims from other addresses.
+		Claimed { who: T::AccountId, ethereum_address: T::Address, amount: BalanceOf<T> },
 	}
 
 	#[pallet::error]
@@ -202,11 +442,13 @@ pub mod pallet {
 		InvalidStatement,
 		/// The account already has a vested balance.
 		VestedBalanceExists,
+		/// The claim proof could not be verified.
+		InvalidClaimProof,
 	}
 
 	#[pallet::storage]
 	#[pallet::getter(fn claims)]
-	pub(super) type Claims<T: Config> = StorageMap<_, Identity, EthereumAddress, BalanceOf<T>>;
+	pub(super) type Claims<T: Config> = StorageMap<_, Identity, T::Address, BalanceOf<T>>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn total)]
@@ -219,21 +461,20 @@ pub mod pallet {
 	#[pallet::storage]
 	#[pallet::getter(fn vesting)]
 	pub(super) type Vesting<T: Config> =
-		StorageMap<_, Identity, EthereumAddress, (BalanceOf<T>, BalanceOf<T>, T::BlockNumber)>;
+		StorageMap<_, Identity, T::Address, (BalanceOf<T>, BalanceOf<T>, T::BlockNumber)>;
 
 	/// The statement kind that must be signed, if any.
 	#[pallet::storage]
-	pub(super) type Signing<T> = StorageMap<_, Identity, EthereumAddress, StatementKind>;
+	pub(super) type Signing<T: Config> = StorageMap<_, Identity, T::Address, StatementKind>;
 
-	/// Pre-claimed Ethereum accounts, by the Account ID that they are claimed to.
+	/// Pre-claimed external addresses, by the Account ID that they are claimed to.
 	#[pallet::storage]
-	pub(super) type Preclaims<T: Config> = StorageMap<_, Identity, T::AccountId, EthereumAddress>;
+	pub(super) type Preclaims<T: Config> = StorageMap<_, Identity, T::AccountId, T::Address>;
 
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
-		pub claims:
-			Vec<(EthereumAddress, BalanceOf<T>, Option<T::AccountId>, Option<StatementKind>)>,
-		pub vesting: Vec<(EthereumAddress, (BalanceOf<T>, BalanceOf<T>, T::BlockNumber))>,
+		pub claims: Vec<(T::Address, BalanceOf<T>, Option<T::AccountId>, Option<StatementKind>)>,
+		pub vesting: Vec<(T::Address, (BalanceOf<T>, BalanceOf<T>, T::BlockNumber))>,
 	}
 
 	#[cfg(feature = "std")]
@@ -315,8 +556,9 @@ pub mod pallet {
 			ensure_none(origin)?;
 
 			let data = dest.using_encoded(to_ascii_hex);
-			let signer = Self::eth_recover(&ethereum_signature, &data, &[][..])
-				.ok_or(Error::<T>::InvalidEthereumSignature)?;
+			let signer: T::Address = Self::eth_recover(&ethereum_signature, &data, &[][..])
+				.ok_or(Error::<T>::InvalidEthereumSignature)?
+				.into();
 			ensure!(Signing::<T>::get(&signer).is_none(), Error::<T>::InvalidStatement);
 
 			Self::process_claim(signer, dest)?;
@@ -328,7 +570,7 @@ pub mod pallet {
 		/// The dispatch origin for this call must be _Root_.
 		///
 		/// Parameters:
-		/// - `who`: The Ethereum address allowed to collect this claim.
+		/// - `who`: The external address allowed to collect this claim.
 		/// - `value`: The number of DOTs that will be claimed.
 		/// - `vesting_schedule`: An optional vesting schedule for these DOTs.
 		///
@@ -342,7 +584,7 @@ pub mod pallet {
 		#[pallet::weight(T::WeightInfo::mint_claim())]
 		pub fn mint_claim(
 			origin: OriginFor<T>,
-			who: EthereumAddress,
+			who: T::Address,
 			value: BalanceOf<T>,
 			vesting_schedule: Option<(BalanceOf<T>, BalanceOf<T>, T::BlockNumber)>,
 			statement: Option<StatementKind>,
@@ -397,8 +639,9 @@ pub mod pallet {
 			ensure_none(origin)?;
 
 			let data = dest.using_encoded(to_ascii_hex);
-			let signer = Self::eth_recover(&ethereum_signature, &data, &statement)
-				.ok_or(Error::<T>::InvalidEthereumSignature)?;
+			let signer: T::Address = Self::eth_recover(&ethereum_signature, &data, &statement)
+				.ok_or(Error::<T>::InvalidEthereumSignature)?
+				.into();
 			if let Some(s) = Signing::<T>::get(signer) {
 				ensure!(s.to_text() == &statement[..], Error::<T>::InvalidStatement);
 			}
@@ -444,8 +687,8 @@ pub mod pallet {
 		#[pallet::weight(T::WeightInfo::move_claim())]
 		pub fn move_claim(
 			origin: OriginFor<T>,
-			old: EthereumAddress,
-			new: EthereumAddress,
+			old: T::Address,
+			new: T::Address,
 			maybe_preclaim: Option<T::AccountId>,
 		) -> DispatchResultWithPostInfo {
 			T::MoveClaimOrigin::try_origin(origin).map(|_| ()).or_else(ensure_root)?;
@@ -462,6 +705,47 @@ pub mod pallet {
 			});
 			Ok(Pays::No.into())
 		}
+
+		/// Make a claim to collect your DOTs with a proof checked by the configured `Verifier`.
+		///
+		/// The dispatch origin for this call must be _None_.
+		///
+		/// Unsigned Validation:
+		/// A call to `claim_with_proof` is deemed valid if the `Verifier` accepts `proof` for
+		/// paying out to `dest` while attesting to `statement`, and the address which made the
+		/// proof has a claim. `statement` must be the statement expected for that address, or
+		/// empty if no statement is expected.
+		///
+		/// Parameters:
+		/// - `dest`: The destination account to payout the claim.
+		/// - `proof`: The proof, usually a signature, made by the claiming address.
+		/// - `statement`: The identity of the statement which is being attested to in the proof.
+		///
+		/// <weight>
+		/// The weight of this call is invariant over the input parameters.
+		/// Weight includes logic to validate unsigned `claim_with_proof` call.
+		///
+		/// Total Complexity: O(1)
+		/// </weight>
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::claim_with_proof())]
+		pub fn claim_with_proof(
+			origin: OriginFor<T>,
+			dest: T::AccountId,
+			proof: ClaimProofOf<T>,
+			statement: Vec<u8>,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			let signer = Self::verify_claim_proof(&proof, &dest, &statement)
+				.ok_or(Error::<T>::InvalidClaimProof)?;
+			match Signing::<T>::get(signer) {
+				None => ensure!(statement.is_empty(), Error::<T>::InvalidStatement),
+				Some(s) => ensure!(s.to_text() == &statement[..], Error::<T>::InvalidStatement),
+			}
+			Self::process_claim(signer, dest)?;
+			Ok(())
+		}
 	}
 
 	#[pallet::validate_unsigned]
@@ -471,13 +755,17 @@ pub mod pallet {
 		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
 			const PRIORITY: u64 = 100;
 
-			let (maybe_signer, maybe_statement) = match call {
+			let (maybe_signer, maybe_statement, invalid_proof) = match call {
 				// <weight>
 				// The weight of this logic is included in the `claim` dispatchable.
 				// </weight>
 				Call::claim { dest: account, ethereum_signature } => {
 					let data = account.using_encoded(to_ascii_hex);
-					(Self::eth_recover(&ethereum_signature, &data, &[][..]), None)
+					(
+						Self::eth_recover(&ethereum_signature, &data, &[][..]).map(Into::into),
+						None,
+						ValidityError::InvalidEthereumSignature,
+					)
 				},
 				// <weight>
 				// The weight of this logic is included in the `claim_attest` dispatchable.
@@ -485,16 +773,24 @@ pub mod pallet {
 				Call::claim_attest { dest: account, ethereum_signature, statement } => {
 					let data = account.using_encoded(to_ascii_hex);
 					(
-						Self::eth_recover(&ethereum_signature, &data, &statement),
+						Self::eth_recover(&ethereum_signature, &data, &statement).map(Into::into),
 						Some(statement.as_slice()),
+						ValidityError::InvalidEthereumSignature,
 					)
 				},
+				// <weight>
+				// The weight of this logic is included in the `claim_with_proof` dispatchable.
+				// </weight>
+				Call::claim_with_proof { dest: account, proof, statement } => (
+					Self::verify_claim_proof(proof, account, statement),
+					Some(statement.as_slice()).filter(|s| !s.is_empty()),
+					ValidityError::InvalidClaimProof,
+				),
 				_ => return Err(InvalidTransaction::Call.into()),
 			};
 
-			let signer = maybe_signer.ok_or(InvalidTransaction::Custom(
-				ValidityError::InvalidEthereumSignature.into(),
-			))?;
+			let signer: T::Address =
+				maybe_signer.ok_or(InvalidTransaction::Custom(invalid_proof.into()))?;
 
 			let e = InvalidTransaction::Custom(ValidityError::SignerHasNoClaim.into());
 			ensure!(<Claims<T>>::contains_key(&signer), e);
@@ -527,35 +823,74 @@ fn to_ascii_hex(data: &[u8]) -> Vec<u8> {
 	r
 }
 
+// Constructs the message that Ethereum RPC's `personal_sign` and `eth_sign` would sign.
+fn ethereum_signable_message(prefix: &[u8], what: &[u8], extra: &[u8]) -> Vec<u8> {
+	let mut l = prefix.len() + what.len() + extra.len();
+	let mut rev = Vec::new();
+	while l > 0 {
+		rev.push(b'0' + (l % 10) as u8);
+		l /= 10;
+	}
+	let mut v = b"\x19Ethereum Signed Message:\n".to_vec();
+	v.extend(rev.into_iter().rev());
+	v.extend_from_slice(prefix);
+	v.extend_from_slice(what);
+	v.extend_from_slice(extra);
+	v
+}
+
+// Constructs the EIP-712 digest of a `Claim(bytes dest,bytes statement)` struct, in the domain
+// named after `prefix` on the chain `chain_id`.
+fn eip712_claim_digest(prefix: &[u8], chain_id: u64, dest: &[u8], statement: &[u8]) -> [u8; 32] {
+	let domain_type = keccak_256(b"EIP712Domain(string name,string version,uint256 chainId)");
+	// `uint256` values are encoded as 32 big endian bytes.
+	let mut encoded_chain_id = [0u8; 32];
+	encoded_chain_id[24..].copy_from_slice(&chain_id.to_be_bytes());
+	let domain_separator = keccak_256(
+		&[&domain_type[..], &keccak_256(prefix)[..], &keccak_256(b"1")[..], &encoded_chain_id[..]]
+			.concat(),
+	);
+	let claim_type = keccak_256(b"Claim(bytes dest,bytes statement)");
+	let claim_hash =
+		keccak_256(&[&claim_type[..], &keccak_256(dest)[..], &keccak_256(statement)[..]].concat());
+	keccak_256(&[&b"\x19\x01"[..], &domain_separator[..], &claim_hash[..]].concat())
+}
+
+// Constructs the message that Substrate wallets sign when asked to sign raw bytes.
+fn substrate_signable_message(prefix: &[u8], what: &[u8], extra: &[u8]) -> Vec<u8> {
+	[&b"<Bytes>"[..], prefix, what, extra, &b"</Bytes>"[..]].concat()
+}
+
+// Attempts to recover the Ethereum address which signed the given message hash.
+fn ethereum_recover(s: &EcdsaSignature, msg: &[u8; 32]) -> Option<EthereumAddress> {
+	let mut res = EthereumAddress::default();
+	res.0.copy_from_slice(&keccak_256(&secp256k1_ecdsa_recover(&s.0, msg).ok()?[..])[12..]);
+	Some(res)
+}
+
 impl<T: Config> Pallet<T> {
 	// Constructs the message that Ethereum RPC's `personal_sign` and `eth_sign` would sign.
 	fn ethereum_signable_message(what: &[u8], extra: &[u8]) -> Vec<u8> {
-		let prefix = T::Prefix::get();
-		let mut l = prefix.len() + what.len() + extra.len();
-		let mut rev = Vec::new();
-		while l > 0 {
-			rev.push(b'0' + (l % 10) as u8);
-			l /= 10;
-		}
-		let mut v = b"\x19Ethereum Signed Message:\n".to_vec();
-		v.extend(rev.into_iter().rev());
-		v.extend_from_slice(prefix);
-		v.extend_from_slice(what);
-		v.extend_from_slice(extra);
-		v
+		ethereum_signable_message(T::Prefix::get(), what, extra)
 	}
 
 	// Attempts to recover the Ethereum address from a message signature signed by using
 	// the Ethereum RPC's `personal_sign` and `eth_sign`.
 	fn eth_recover(s: &EcdsaSignature, what: &[u8], extra: &[u8]) -> Option<EthereumAddress> {
-		let msg = keccak_256(&Self::ethereum_signable_message(what, extra));
-		let mut res = EthereumAddress::default();
-		res.0
-			.copy_from_slice(&keccak_256(&secp256k1_ecdsa_recover(&s.0, &msg).ok()?[..])[12..]);
-		Some(res)
+		ethereum_recover(s, &keccak_256(&Self::ethereum_signable_message(what, extra)))
 	}
 
-	fn process_claim(signer: EthereumAddress, dest: T::AccountId) -> sp_runtime::DispatchResult {
+	// Returns the address which made `proof` for paying out to `dest`, according to the
+	// configured `Verifier`.
+	fn verify_claim_proof(
+		proof: &ClaimProofOf<T>,
+		dest: &T::AccountId,
+		statement: &[u8],
+	) -> Option<T::Address> {
+		dest.using_encoded(|dest| T::Verifier::verify(proof, T::Prefix::get(), dest, statement))
+	}
+
+	fn process_claim(signer: T::Address, dest: T::AccountId) -> sp_runtime::DispatchResult {
 		let balance_due = <Claims<T>>::get(&signer).ok_or(Error::<T>::SignerHasNoClaim)?;
 
 		let new_total = Self::total().checked_sub(&balance_due).ok_or(Error::<T>::PotUnderflow)?;
@@ -685,6 +1020,16 @@ mod secp_utils {
 		res.0.copy_from_slice(&keccak_256(&public(secret).serialize()[1..65])[12..]);
 		res
 	}
+	pub fn secret(seed: u32) -> libsecp256k1::SecretKey {
+		libsecp256k1::SecretKey::parse(&keccak_256(&seed.encode())).unwrap()
+	}
+	pub fn sign_hash(secret: &libsecp256k1::SecretKey, msg: &[u8; 32]) -> EcdsaSignature {
+		let (sig, recovery_id) = libsecp256k1::sign(&libsecp256k1::Message::parse(msg), secret);
+		let mut r = [0u8; 65];
+		r[0..64].copy_from_slice(&sig.serialize()[..]);
+		r[64] = recovery_id.serialize();
+		EcdsaSignature(r)
+	}
 	pub fn sig<T: Config>(
 		secret: &libsecp256k1::SecretKey,
 		what: &[u8],
@@ -694,11 +1039,7 @@ mod secp_utils {
 			&to_ascii_hex(what)[..],
 			extra,
 		));
-		let (sig, recovery_id) = libsecp256k1::sign(&libsecp256k1::Message::parse(&msg), secret);
-		let mut r = [0u8; 65];
-		r[0..64].copy_from_slice(&sig.serialize()[..]);
-		r[64] = recovery_id.serialize();
-		EcdsaSignature(r)
+		sign_hash(secret, &msg)
 	}
 }
 
@@ -807,6 +1148,7 @@ mod tests {
 
 	parameter_types! {
 		pub Prefix: &'static [u8] = b"Pay RUSTs to the TEST account:";
+		pub const ClaimsChainId: u64 = 42;
 	}
 	ord_parameter_types! {
 		pub const Six: u64 = 6;
@@ -817,9 +1159,15 @@ mod tests {
 		type VestingSchedule = Vesting;
 		type Prefix = Prefix;
 		type MoveClaimOrigin = frame_system::EnsureSignedBy<Six, u64>;
+		type Address = ExternalAddress;
+		type Verifier = MultiClaimVerifier<ClaimsChainId>;
 		type WeightInfo = TestWeightInfo;
 	}
 
+	fn eth(secret: &libsecp256k1::SecretKey) -> ExternalAddress {
+		secp_utils::eth(secret).into()
+	}
+
 	fn alice() -> libsecp256k1::SecretKey {
 		libsecp256k1::SecretKey::parse(&keccak_256(b"Alice")).unwrap()
 	}
@@ -870,7 +1218,7 @@ mod tests {
 			assert_eq!(Claims::claims(&eth(&dave())), Some(200));
 			assert_eq!(Claims::claims(&eth(&eve())), Some(300));
 			assert_eq!(Claims::claims(&eth(&frank())), Some(400));
-			assert_eq!(Claims::claims(&EthereumAddress::default()), None);
+			assert_eq!(Claims::claims(&ExternalAddress::from(EthereumAddress::default())), None);
 			assert_eq!(Claims::vesting(&eth(&alice())), Some((50, 10, 1)));
 		});
 	}
@@ -1356,6 +1704,195 @@ mod tests {
 		});
 	}
 
+	fn eip712_sig(secret: &libsecp256k1::SecretKey, dest: &[u8], statement: &[u8]) -> ClaimProof {
+		let digest = eip712_claim_digest(Prefix::get(), ClaimsChainId::get(), dest, statement);
+		ClaimProof::Eip712(sign_hash(secret, &digest))
+	}
+
+	fn substrate_sig<P>(pair: &P, dest: &[u8], statement: &[u8]) -> ClaimProof
+	where
+		P: sp_core::Pair,
+		P::Public: Into<AccountId32>,
+		P::Signature: Into<MultiSignature>,
+	{
+		let msg = substrate_signable_message(Prefix::get(), &to_ascii_hex(dest), statement);
+		ClaimProof::Substrate(SubstrateClaimProof {
+			signer: pair.public().into(),
+			signature: pair.sign(&msg).into(),
+		})
+	}
+
+	#[test]
+	fn claiming_with_proof_works() {
+		use sp_core::{ed25519, sr25519, Pair};
+
+		new_test_ext().execute_with(|| {
+			// Ethereum `personal_sign`
+			let proof = ClaimProof::Ethereum(sig::<Test>(&alice(), &42u64.encode(), &[][..]));
+			assert_noop!(
+				Claims::claim_with_proof(
+					RuntimeOrigin::none(),
+					42,
+					proof.clone(),
+					StatementKind::Regular.to_text().to_vec()
+				),
+				Error::<Test>::InvalidStatement
+			);
+			assert_ok!(Claims::claim_with_proof(RuntimeOrigin::none(), 42, proof, vec![]));
+			assert_eq!(Balances::free_balance(&42), 100);
+
+			// EIP-712 typed data, attesting to the expected statement
+			let statement = StatementKind::Regular.to_text();
+			assert_noop!(
+				Claims::claim_with_proof(
+					RuntimeOrigin::none(),
+					43,
+					eip712_sig(&dave(), &43u64.encode(), &[][..]),
+					vec![]
+				),
+				Error::<Test>::InvalidStatement
+			);
+			assert_ok!(Claims::claim_with_proof(
+				RuntimeOrigin::none(),
+				43,
+				eip712_sig(&dave(), &43u64.encode(), statement),
+				statement.to_vec()
+			));
+			assert_eq!(Balances::free_balance(&43), 200);
+
+			// Signatures made for another chain are not accepted.
+			let digest = eip712_claim_digest(Prefix::get(), 1, &43u64.encode(), statement);
+			assert_ne!(
+				<Eip712TypedData<ClaimsChainId> as ClaimVerifier<ExternalAddress>>::verify(
+					&sign_hash(&dave(), &digest),
+					Prefix::get(),
+					&43u64.encode(),
+					statement,
+				),
+				Some(eth(&dave()))
+			);
+
+			// sr25519 and ed25519 signatures
+			let sr_pair = sr25519::Pair::from_seed(&[1; 32]);
+			let ed_pair = ed25519::Pair::from_seed(&[2; 32]);
+			let sr_address = ExternalAddress::from(AccountId32::from(sr_pair.public()));
+			let ed_address = ExternalAddress::from(AccountId32::from(ed_pair.public()));
+			assert_ok!(Claims::mint_claim(RuntimeOrigin::root(), sr_address, 500, None, None));
+			assert_ok!(Claims::mint_claim(RuntimeOrigin::root(), ed_address, 600, None, None));
+
+			assert_noop!(
+				Claims::claim_with_proof(
+					RuntimeOrigin::none(),
+					44,
+					substrate_sig(&sr_pair, &45u64.encode(), &[][..]),
+					vec![]
+				),
+				Error::<Test>::InvalidClaimProof
+			);
+			assert_ok!(Claims::claim_with_proof(
+				RuntimeOrigin::none(),
+				44,
+				substrate_sig(&sr_pair, &44u64.encode(), &[][..]),
+				vec![]
+			));
+			assert_ok!(Claims::claim_with_proof(
+				RuntimeOrigin::none(),
+				45,
+				substrate_sig(&ed_pair, &45u64.encode(), &[][..]),
+				vec![]
+			));
+			assert_eq!(Balances::free_balance(&44), 500);
+			assert_eq!(Balances::free_balance(&45), 600);
+			assert_eq!(Claims::claims(&sr_address), None);
+			assert_eq!(Claims::total(), total_claims() - 300);
+		});
+	}
+
+	#[test]
+	fn validate_unsigned_claim_with_proof_works() {
+		use sp_runtime::traits::ValidateUnsigned;
+		let source = sp_runtime::transaction_validity::TransactionSource::External;
+
+		new_test_ext().execute_with(|| {
+			let statement = StatementKind::Saft.to_text().to_vec();
+			assert_eq!(
+				<Pallet<Test>>::validate_unsigned(
+					source,
+					&ClaimsCall::claim_with_proof {
+						dest: 1,
+						proof: eip712_sig(&eve(), &1u64.encode(), &statement),
+						statement: statement.clone(),
+					}
+				),
+				Ok(ValidTransaction {
+					priority: 100,
+					requires: vec![],
+					provides: vec![("claims", eth(&eve())).encode()],
+					longevity: TransactionLongevity::max_value(),
+					propagate: true,
+				})
+			);
+			assert_eq!(
+				<Pallet<Test>>::validate_unsigned(
+					source,
+					&ClaimsCall::claim_with_proof {
+						dest: 1,
+						proof: eip712_sig(&eve(), &1u64.encode(), &[][..]),
+						statement: vec![],
+					}
+				),
+				InvalidTransaction::Custom(ValidityError::InvalidStatement.into()).into(),
+			);
+			assert_eq!(
+				<Pallet<Test>>::validate_unsigned(
+					source,
+					&ClaimsCall::claim_with_proof {
+						dest: 1,
+						proof: ClaimProof::Ethereum(EcdsaSignature([0; 65])),
+						statement: vec![],
+					}
+				),
+				InvalidTransaction::Custom(ValidityError::InvalidClaimProof.into()).into(),
+			);
+		});
+	}
+
+	#[test]
+	fn migration_to_external_address_works() {
+		use frame_support::{
+			storage::{unhashed, StoragePrefixedMap},
+			traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		};
+
+		new_test_ext().execute_with(|| {
+			// Put back `EthereumAddress` keyed claims, like they were before version 1.
+			let _ = claims::Claims::<Test>::clear(u32::MAX, None);
+			let _ = claims::Vesting::<Test>::clear(u32::MAX, None);
+			let _ = claims::Signing::<Test>::clear(u32::MAX, None);
+			let _ = claims::Preclaims::<Test>::clear(u32::MAX, None);
+			let old_key = |prefix: [u8; 32], key: &[u8]| [&prefix[..], key].concat();
+			let bob = secp_utils::eth(&bob());
+			let claims_key = old_key(claims::Claims::<Test>::final_prefix(), &bob.encode());
+			let vesting_key = old_key(claims::Vesting::<Test>::final_prefix(), &bob.encode());
+			let signing_key = old_key(claims::Signing::<Test>::final_prefix(), &bob.encode());
+			let preclaims_key = old_key(claims::Preclaims::<Test>::final_prefix(), &42u64.encode());
+			unhashed::put(&claims_key, &1000u64);
+			unhashed::put(&vesting_key, &(50u64, 10u64, 1u64));
+			unhashed::put(&signing_key, &StatementKind::Saft);
+			unhashed::put(&preclaims_key, &bob);
+			StorageVersion::new(0).put::<Pallet<Test>>();
+
+			migration::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+
+			assert_eq!(Pallet::<Test>::on_chain_storage_version(), migration::STORAGE_VERSION);
+			assert_eq!(Claims::claims(&eth(&bob())), Some(1000));
+			assert_eq!(Claims::vesting(&eth(&bob())), Some((50, 10, 1)));
+			assert_eq!(claims::Signing::<Test>::get(eth(&bob())), Some(StatementKind::Saft));
+			assert_eq!(claims::Preclaims::<Test>::get(42), Some(eth(&bob())));
+			assert_eq!(claims::Claims::<Test>::iter().count(), 1);
+		});
+	}
+
 	#[test]
 	fn real_eth_sig_works() {
 		new_test_ext().execute_with(|| {
@@ -1488,7 +2025,7 @@ mod benchmarking {
 
 	fn create_claim<T: Config>(input: u32) -> DispatchResult {
 		let secret_key = libsecp256k1::SecretKey::parse(&keccak_256(&input.encode())).unwrap();
-		let eth_address = eth(&secret_key);
+		let eth_address: T::Address = eth(&secret_key).into();
 		let vesting = Some((100_000u32.into(), 1_000u32.into(), 100u32.into()));
 		super::Pallet::<T>::mint_claim(
 			RawOrigin::Root.into(),
@@ -1502,7 +2039,7 @@ mod benchmarking {
 
 	fn create_claim_attest<T: Config>(input: u32) -> DispatchResult {
 		let secret_key = libsecp256k1::SecretKey::parse(&keccak_256(&input.encode())).unwrap();
-		let eth_address = eth(&secret_key);
+		let eth_address: T::Address = eth(&secret_key).into();
 		let vesting = Some((100_000u32.into(), 1_000u32.into(), 100u32.into()));
 		super::Pallet::<T>::mint_claim(
 			RawOrigin::Root.into(),
@@ -1525,7 +2062,7 @@ mod benchmarking {
 			}
 
 			let secret_key = libsecp256k1::SecretKey::parse(&keccak_256(&c.encode())).unwrap();
-			let eth_address = eth(&secret_key);
+			let eth_address: T::Address = eth(&secret_key).into();
 			let account: T::AccountId = account("user", c, SEED);
 			let vesting = Some((100_000u32.into(), 1_000u32.into(), 100u32.into()));
 			let signature = sig::<T>(&secret_key, &account.encode(), &[][..]);
@@ -1555,7 +2092,7 @@ mod benchmarking {
 				create_claim_attest::<T>(u32::MAX - c)?;
 			}
 
-			let eth_address = account("eth_address", 0, SEED);
+			let eth_address: T::Address = account::<EthereumAddress>("eth_address", 0, SEED).into();
 			let vesting = Some((100_000u32.into(), 1_000u32.into(), 100u32.into()));
 			let statement = StatementKind::Regular;
 		}: _(RawOrigin::Root, eth_address, VALUE.into(), vesting, Some(statement))
@@ -1575,7 +2112,7 @@ mod benchmarking {
 			// Crate signature
 			let attest_c = u32::MAX - c;
 			let secret_key = libsecp256k1::SecretKey::parse(&keccak_256(&attest_c.encode())).unwrap();
-			let eth_address = eth(&secret_key);
+			let eth_address: T::Address = eth(&secret_key).into();
 			let account: T::AccountId = account("user", c, SEED);
 			let vesting = Some((100_000u32.into(), 1_000u32.into(), 100u32.into()));
 			let statement = StatementKind::Regular;
@@ -1609,7 +2146,7 @@ mod benchmarking {
 
 			let attest_c = u32::MAX - c;
 			let secret_key = libsecp256k1::SecretKey::parse(&keccak_256(&attest_c.encode())).unwrap();
-			let eth_address = eth(&secret_key);
+			let eth_address: T::Address = eth(&secret_key).into();
 			let account: T::AccountId = account("user", c, SEED);
 			let vesting = Some((100_000u32.into(), 1_000u32.into(), 100u32.into()));
 			let statement = StatementKind::Regular;
@@ -1650,10 +2187,10 @@ mod benchmarking {
 
 			let attest_c = u32::MAX - c;
 			let secret_key = libsecp256k1::SecretKey::parse(&keccak_256(&attest_c.encode())).unwrap();
-			let eth_address = eth(&secret_key);
+			let eth_address: T::Address = eth(&secret_key).into();
 
 			let new_secret_key = libsecp256k1::SecretKey::parse(&keccak_256(&(u32::MAX/2).encode())).unwrap();
-			let new_eth_address = eth(&new_secret_key);
+			let new_eth_address: T::Address = eth(&new_secret_key).into();
 
 			let account: T::AccountId = account("user", c, SEED);
 			Preclaims::<T>::insert(&account, eth_address);
@@ -1666,6 +2203,42 @@ mod benchmarking {
 			assert!(Claims::<T>::contains_key(new_eth_address));
 		}
 
+		// Benchmark `claim_with_proof` including `validate_unsigned` logic.
+		claim_with_proof {
+			let c = MAX_CLAIMS;
+
+			for i in 0 .. c / 2 {
+				create_claim::<T>(c)?;
+				create_claim_attest::<T>(u32::MAX - c)?;
+			}
+
+			let account: T::AccountId = account("user", c, SEED);
+			let vesting = Some((100_000u32.into(), 1_000u32.into(), 100u32.into()));
+			let statement = StatementKind::Regular;
+			let (proof, address) = <T::Verifier as ClaimVerifier<T::Address>>::create_proof(
+				c,
+				T::Prefix::get(),
+				&account.encode(),
+				statement.to_text(),
+			);
+			super::Pallet::<T>::mint_claim(RawOrigin::Root.into(), address, VALUE.into(), vesting, Some(statement))?;
+			assert_eq!(Claims::<T>::get(address), Some(VALUE.into()));
+			let call_enc = Call::<T>::claim_with_proof {
+				dest: account.clone(),
+				proof,
+				statement: statement.to_text().to_vec()
+			}.encode();
+			let source = sp_runtime::transaction_validity::TransactionSource::External;
+		}: {
+			let call = <Call<T> as Decode>::decode(&mut &*call_enc)
+				.expect("call is encoded above, encoding must be correct");
+			super::Pallet::<T>::validate_unsigned(source, &call).map_err(|e| -> &'static str { e.into() })?;
+			call.dispatch_bypass_filter(RawOrigin::None.into())?;
+		}
+		verify {
+			assert_eq!(Claims::<T>::get(address), None);
+		}
+
 		// Benchmark the time it takes to do `repeat` number of keccak256 hashes
 		#[extra]
 		keccak256 {
@@ -0,0 +1,128 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::claims::{Config, Pallet};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+pub mod v1 {
+	use super::*;
+	use crate::claims::{BalanceOf, EthereumAddress, StatementKind};
+	use frame_support::storage_alias;
+	use sp_std::prelude::*;
+
+	#[storage_alias]
+	type Claims<T: Config> = StorageMap<Pallet<T>, Identity, EthereumAddress, BalanceOf<T>>;
+
+	#[storage_alias]
+	type Vesting<T: Config> = StorageMap<
+		Pallet<T>,
+		Identity,
+		EthereumAddress,
+		(BalanceOf<T>, BalanceOf<T>, <T as frame_system::Config>::BlockNumber),
+	>;
+
+	#[storage_alias]
+	type Signing<T: Config> = StorageMap<Pallet<T>, Identity, EthereumAddress, StatementKind>;
+
+	#[storage_alias]
+	type Preclaims<T: Config> = StorageMap<
+		Pallet<T>,
+		Identity,
+		<T as frame_system::Config>::AccountId,
+		EthereumAddress,
+	>;
+
+	/// Re-keys the claims storage from `EthereumAddress` to the configured `Address` type.
+	///
+	/// If the configured `Address` encodes Ethereum addresses just like `EthereumAddress` does,
+	/// nothing but the storage version is written.
+	pub struct MigrateToV1<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() != 0 {
+				log::warn!("skipping v1, should be removed");
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut weight = T::DbWeight::get().reads(1);
+			let probe = EthereumAddress::default();
+			if T::Address::from(probe).encode() != probe.encode() {
+				let claims = Claims::<T>::drain().collect::<Vec<_>>();
+				let vesting = Vesting::<T>::drain().collect::<Vec<_>>();
+				let signing = Signing::<T>::drain().collect::<Vec<_>>();
+				let preclaims = Preclaims::<T>::iter().collect::<Vec<_>>();
+				let migrated = claims.len() + vesting.len() + signing.len() + preclaims.len();
+
+				for (address, balance) in claims {
+					crate::claims::Claims::<T>::insert(T::Address::from(address), balance);
+				}
+				for (address, schedule) in vesting {
+					crate::claims::Vesting::<T>::insert(T::Address::from(address), schedule);
+				}
+				for (address, statement) in signing {
+					crate::claims::Signing::<T>::insert(T::Address::from(address), statement);
+				}
+				for (who, address) in preclaims {
+					crate::claims::Preclaims::<T>::insert(who, T::Address::from(address));
+				}
+
+				log::info!("Migrated {} claims entries to the new address type", migrated);
+				weight.saturating_accrue(
+					T::DbWeight::get().reads_writes(migrated as u64, 2 * migrated as u64),
+				);
+			}
+
+			STORAGE_VERSION.put::<Pallet<T>>();
+
+			weight.saturating_add(T::DbWeight::get().writes(1))
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			let counts = (
+				Claims::<T>::iter_keys().count() as u32,
+				Vesting::<T>::iter_keys().count() as u32,
+				Signing::<T>::iter_keys().count() as u32,
+				Preclaims::<T>::iter_keys().count() as u32,
+			);
+			Ok(counts.encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(counts: Vec<u8>) -> Result<(), &'static str> {
+			let counts = <(u32, u32, u32, u32)>::decode(&mut counts.as_slice())
+				.expect("the state parameter should be something that was generated by pre_upgrade");
+			ensure!(StorageVersion::get::<Pallet<T>>() == 1, "claims storage version not set");
+			ensure!(
+				counts ==
+					(
+						crate::claims::Claims::<T>::iter_keys().count() as u32,
+						crate::claims::Vesting::<T>::iter_keys().count() as u32,
+						crate::claims::Signing::<T>::iter_keys().count() as u32,
+						crate::claims::Preclaims::<T>::iter_keys().count() as u32,
+					),
+				"claims entries were lost in the migration"
+			);
+			Ok(())
+		}
+	}
+}
@@ -48,6 +48,14 @@
 //! Successful funds remain tracked (in the `Funds` storage item and the associated child trie) as long as
 //! the parachain remains active. Users can withdraw their funds once the slot is completed and funds are
 //! returned to the crowdloan account.
+//!
+//! Root may attach an optional `RewardSchedule` to a fund through `set_reward_schedule`. While the
+//! fund holds a lease it has won, each contributor may `claim_reward` once: either a pro-rata share
+//! of a relay chain token reward paid out of the fund's reward pot, or a `ContributionReceipt`.
+//! Receipts are exposed through the `nonfungibles` traits (the collection being the fund index), so
+//! that they can be moved to a parachain over XCM with a `NonFungiblesAdapter` and serve there as
+//! proof of participation. Receipts outlive the fund, so that they can still return from a
+//! parachain after the fund is dissolved.
 
 pub mod migration;
 
@@ -60,6 +68,7 @@ use frame_support::{
 	pallet_prelude::{DispatchResult, Weight},
 	storage::{child, ChildTriePrefixIterator},
 	traits::{
+		tokens::nonfungibles,
 		Currency,
 		ExistenceRequirement::{self, AllowDeath, KeepAlive},
 		Get, ReservableCurrency,
@@ -74,7 +83,7 @@ use sp_runtime::{
 	traits::{
 		AccountIdConversion, CheckedAdd, Hash, IdentifyAccount, One, Saturating, Verify, Zero,
 	},
-	MultiSignature, MultiSigner, RuntimeDebug,
+	MultiSignature, MultiSigner, Perbill, RuntimeDebug,
 };
 use sp_std::vec::Vec;
 
@@ -89,7 +98,8 @@ type BalanceOf<T> = <CurrencyOf<T> as Currency<<T as frame_system::Config>::Acco
 type NegativeImbalanceOf<T> =
 	<CurrencyOf<T> as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
 
-type FundIndex = u32;
+pub type FundIndex = u32;
+type ReceiptIndex = u32;
 
 pub trait WeightInfo {
 	fn create() -> Weight;
@@ -101,6 +111,8 @@ pub trait WeightInfo {
 	fn add_memo() -> Weight;
 	fn on_initialize(n: u32) -> Weight;
 	fn poke() -> Weight;
+	fn claim_reward() -> Weight;
+	fn set_reward_schedule() -> Weight;
 }
 
 pub struct TestWeightInfo;
@@ -132,6 +144,12 @@ impl WeightInfo for TestWeightInfo {
 	fn poke() -> Weight {
 		Weight::zero()
 	}
+	fn claim_reward() -> Weight {
+		Weight::zero()
+	}
+	fn set_reward_schedule() -> Weight {
+		Weight::zero()
+	}
 }
 
 #[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
@@ -176,6 +194,30 @@ pub struct FundInfo<AccountId, Balance, BlockNumber, LeasePeriod> {
 	pub fund_index: FundIndex,
 }
 
+/// The reward a fund offers to its contributors once it has won an auction.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum RewardSchedule<Balance> {
+	/// A `total` amount of the relay chain token, paid out of the fund's reward pot to each
+	/// contributor in proportion to their share of the amount raised.
+	Native { total: Balance },
+	/// No token reward; each contributor may instead claim a `ContributionReceipt` attesting to
+	/// their participation.
+	Attestation,
+}
+
+/// A receipt for a contribution to a winning fund. Receipts are non-fungible items whose collection
+/// is the index of the fund they were issued for.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ContributionReceipt<AccountId, Balance> {
+	/// The current holder of the receipt. This is `None` while the receipt is burnt, e.g. because
+	/// it has been moved to another chain over XCM; it may then only be minted back as is.
+	pub owner: Option<AccountId>,
+	/// The account that made the contribution.
+	pub contributor: AccountId,
+	/// The amount contributed at the time the receipt was issued.
+	pub amount: Balance,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -255,6 +297,34 @@ pub mod pallet {
 	#[pallet::getter(fn next_fund_index)]
 	pub(super) type NextFundIndex<T> = StorageValue<_, u32, ValueQuery>;
 
+	/// The reward schedule of a fund, if it offers one.
+	#[pallet::storage]
+	#[pallet::getter(fn reward_schedule)]
+	pub(super) type RewardSchedules<T: Config> =
+		StorageMap<_, Twox64Concat, FundIndex, RewardSchedule<BalanceOf<T>>>;
+
+	/// The contributors of a fund who have already claimed their reward.
+	#[pallet::storage]
+	pub(super) type RewardClaims<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, FundIndex, Twox64Concat, T::AccountId, ()>;
+
+	/// The contribution receipts issued for a fund, by receipt index.
+	#[pallet::storage]
+	#[pallet::getter(fn receipts)]
+	pub(super) type Receipts<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		FundIndex,
+		Twox64Concat,
+		ReceiptIndex,
+		ContributionReceipt<T::AccountId, BalanceOf<T>>,
+	>;
+
+	/// The number of contribution receipts issued for a fund so far.
+	#[pallet::storage]
+	pub(super) type ReceiptCount<T> =
+		StorageMap<_, Twox64Concat, FundIndex, ReceiptIndex, ValueQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -271,6 +341,9 @@ pub mod pallet {
 		AllRefunded { para_id: ParaId },
 		/// Fund is dissolved.
 		Dissolved { para_id: ParaId },
+		/// The rewards of a fund have been partially removed, i.e. there are some reward claims
+		/// or contribution receipts left that still need to be removed before it is dissolved.
+		PartiallyDissolved { para_id: ParaId },
 		/// The result of trying to submit a new bid to the Slots pallet.
 		HandleBidResult { para_id: ParaId, result: DispatchResult },
 		/// The configuration to a crowdloan has been edited.
@@ -279,6 +352,12 @@ pub mod pallet {
 		MemoUpdated { who: T::AccountId, para_id: ParaId, memo: Vec<u8> },
 		/// A parachain has been moved to `NewRaise`
 		AddedToNewRaise { para_id: ParaId },
+		/// A contributor claimed their share of a fund's token reward.
+		RewardClaimed { who: T::AccountId, para_id: ParaId, amount: BalanceOf<T> },
+		/// A contribution receipt was issued to a contributor.
+		ReceiptIssued { who: T::AccountId, para_id: ParaId, receipt: ReceiptIndex },
+		/// The reward schedule of a crowdloan has been set.
+		RewardScheduleSet { para_id: ParaId },
 	}
 
 	#[pallet::error]
@@ -329,6 +408,18 @@ pub mod pallet {
 		VrfDelayInProgress,
 		/// A lease period has not started yet, due to an offset in the starting block.
 		NoLeasePeriod,
+		/// The fund does not offer a reward.
+		NoRewardSchedule,
+		/// The fund has not won an auction, so its rewards cannot be claimed yet.
+		FundNotWon,
+		/// The reward of this contributor has already been claimed.
+		RewardAlreadyClaimed,
+		/// There is no contribution receipt with the given index.
+		UnknownReceipt,
+		/// The contribution receipt is not held by the given account.
+		NotReceiptOwner,
+		/// The contribution receipt is already held by some account.
+		ReceiptAlreadyHeld,
 	}
 
 	#[pallet::hooks]
@@ -557,6 +648,10 @@ pub mod pallet {
 		}
 
 		/// Remove a fund after the retirement period has ended and all funds have been returned.
+		///
+		/// The reward claims of the fund are removed first, up to `RemoveKeysLimit` at a time, so
+		/// this function may need to be called multiple times. Any balance left in the fund's
+		/// reward pot is returned to the depositor. Contribution receipts are kept.
 		#[pallet::call_index(4)]
 		#[pallet::weight(T::WeightInfo::dissolve())]
 		pub fn dissolve(origin: OriginFor<T>, #[pallet::compact] index: ParaId) -> DispatchResult {
@@ -576,8 +671,20 @@ pub mod pallet {
 			// can take care of that.
 			debug_assert!(Self::contribution_iterator(fund.fund_index).count().is_zero());
 
+			if !Self::rewards_kill(fund.fund_index) {
+				Self::deposit_event(Event::<T>::PartiallyDissolved { para_id: index });
+				return Ok(())
+			}
+
+			let reward_account = Self::reward_account_id(fund.fund_index);
+			let leftover = CurrencyOf::<T>::free_balance(&reward_account);
+			if !leftover.is_zero() {
+				CurrencyOf::<T>::transfer(&reward_account, &fund.depositor, leftover, AllowDeath)?;
+			}
+
 			CurrencyOf::<T>::unreserve(&fund.depositor, fund.deposit);
 			Funds::<T>::remove(index);
+			RewardSchedules::<T>::remove(fund.fund_index);
 			Self::deposit_event(Event::<T>::Dissolved { para_id: index });
 			Ok(())
 		}
@@ -667,6 +774,97 @@ pub mod pallet {
 			let value = CurrencyOf::<T>::free_balance(&who);
 			Self::do_contribute(who, index, value, signature, AllowDeath)
 		}
+
+		/// Claim the reward of a contribution to a fund which has won an auction.
+		///
+		/// Depending on the fund's `RewardSchedule`, this either pays out the contributor's share
+		/// of the token reward from the fund's reward pot, or issues them a `ContributionReceipt`.
+		/// Each contributor may claim once, and only while the lease won by the fund is held: once
+		/// the lease has ended and the deposit is returned to the fund, rewards can no longer be
+		/// claimed.
+		///
+		/// Origin must be Signed, and the user must have contributed to the crowdloan.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::claim_reward())]
+		pub fn claim_reward(origin: OriginFor<T>, index: ParaId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let fund = Self::funds(index).ok_or(Error::<T>::InvalidParaId)?;
+			let schedule =
+				Self::reward_schedule(fund.fund_index).ok_or(Error::<T>::NoRewardSchedule)?;
+			ensure!(
+				T::Auctioneer::has_won_an_auction(index, &Self::fund_account_id(fund.fund_index)),
+				Error::<T>::FundNotWon
+			);
+			ensure!(
+				!RewardClaims::<T>::contains_key(fund.fund_index, &who),
+				Error::<T>::RewardAlreadyClaimed
+			);
+
+			let (balance, _) = Self::contribution_get(fund.fund_index, &who);
+			ensure!(balance > Zero::zero(), Error::<T>::NoContributions);
+
+			match schedule {
+				RewardSchedule::Native { total } => {
+					// Contributions are frozen once the fund has won, so `raised` is final here.
+					let amount = Perbill::from_rational(balance, fund.raised) * total;
+					CurrencyOf::<T>::transfer(
+						&Self::reward_account_id(fund.fund_index),
+						&who,
+						amount,
+						AllowDeath,
+					)?;
+					Self::deposit_event(Event::<T>::RewardClaimed {
+						who: who.clone(),
+						para_id: index,
+						amount,
+					});
+				},
+				RewardSchedule::Attestation => {
+					let receipt = ReceiptCount::<T>::mutate(fund.fund_index, |count| {
+						let receipt = *count;
+						*count = count.saturating_add(1);
+						receipt
+					});
+					Receipts::<T>::insert(
+						fund.fund_index,
+						receipt,
+						ContributionReceipt {
+							owner: Some(who.clone()),
+							contributor: who.clone(),
+							amount: balance,
+						},
+					);
+					Self::deposit_event(Event::<T>::ReceiptIssued {
+						who: who.clone(),
+						para_id: index,
+						receipt,
+					});
+				},
+			}
+
+			RewardClaims::<T>::insert(fund.fund_index, who, ());
+			Ok(())
+		}
+
+		/// Set the reward schedule of a crowdloan; `None` removes it.
+		///
+		/// Can only be called by Root origin.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::set_reward_schedule())]
+		pub fn set_reward_schedule(
+			origin: OriginFor<T>,
+			#[pallet::compact] index: ParaId,
+			reward: Option<RewardSchedule<BalanceOf<T>>>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			let fund = Self::funds(index).ok_or(Error::<T>::InvalidParaId)?;
+			RewardSchedules::<T>::set(fund.fund_index, reward);
+
+			Self::deposit_event(Event::<T>::RewardScheduleSet { para_id: index });
+			Ok(())
+		}
 	}
 }
 
@@ -679,6 +877,12 @@ impl<T: Config> Pallet<T> {
 		T::PalletId::get().into_sub_account_truncating(index)
 	}
 
+	/// The account ID of the reward pot of a fund, out of which `RewardSchedule::Native` rewards
+	/// are paid. It is up to the party declaring the reward to fill it.
+	pub fn reward_account_id(index: FundIndex) -> T::AccountId {
+		T::PalletId::get().into_sub_account_truncating((b"reward", index))
+	}
+
 	pub fn id_from_index(index: FundIndex) -> child::ChildInfo {
 		let mut buf = Vec::new();
 		buf.extend_from_slice(b"crowdloan");
@@ -710,6 +914,17 @@ impl<T: Config> Pallet<T> {
 		child::kill_storage(&Self::id_from_index(index), Some(T::RemoveKeysLimit::get()))
 	}
 
+	/// Remove up to `RemoveKeysLimit` reward claims of a fund. Returns whether all of them are
+	/// gone.
+	///
+	/// Contribution receipts are not removed: a receipt which has been moved to another chain
+	/// must remain known so that it can be minted back when it returns.
+	fn rewards_kill(index: FundIndex) -> bool {
+		RewardClaims::<T>::clear_prefix(index, T::RemoveKeysLimit::get(), None)
+			.maybe_cursor
+			.is_none()
+	}
+
 	pub fn contribution_iterator(
 		index: FundIndex,
 	) -> ChildTriePrefixIterator<(T::AccountId, (BalanceOf<T>, Vec<u8>))> {
@@ -838,6 +1053,71 @@ impl<T: Config> crate::traits::OnSwap for Pallet<T> {
 	}
 }
 
+impl<T: Config> nonfungibles::Inspect<T::AccountId> for Pallet<T> {
+	type ItemId = ReceiptIndex;
+	type CollectionId = FundIndex;
+
+	fn owner(collection: &FundIndex, item: &ReceiptIndex) -> Option<T::AccountId> {
+		Receipts::<T>::get(collection, item).and_then(|receipt| receipt.owner)
+	}
+
+	fn attribute(collection: &FundIndex, item: &ReceiptIndex, key: &[u8]) -> Option<Vec<u8>> {
+		let receipt = Receipts::<T>::get(collection, item)?;
+		match key {
+			b"contributor" => Some(receipt.contributor.encode()),
+			b"amount" => Some(receipt.amount.encode()),
+			_ => None,
+		}
+	}
+}
+
+// Receipts are only ever created by `claim_reward`. Burning a receipt keeps its record around so
+// that it can be minted back unchanged, which is what happens when it returns over XCM.
+impl<T: Config> nonfungibles::Mutate<T::AccountId> for Pallet<T> {
+	fn mint_into(
+		collection: &FundIndex,
+		item: &ReceiptIndex,
+		who: &T::AccountId,
+	) -> DispatchResult {
+		Receipts::<T>::try_mutate(collection, item, |maybe_receipt| -> DispatchResult {
+			let receipt = maybe_receipt.as_mut().ok_or(Error::<T>::UnknownReceipt)?;
+			ensure!(receipt.owner.is_none(), Error::<T>::ReceiptAlreadyHeld);
+			receipt.owner = Some(who.clone());
+			Ok(())
+		})
+	}
+
+	fn burn(
+		collection: &FundIndex,
+		item: &ReceiptIndex,
+		maybe_check_owner: Option<&T::AccountId>,
+	) -> DispatchResult {
+		Receipts::<T>::try_mutate(collection, item, |maybe_receipt| -> DispatchResult {
+			let receipt = maybe_receipt.as_mut().ok_or(Error::<T>::UnknownReceipt)?;
+			let owner = receipt.owner.take().ok_or(Error::<T>::UnknownReceipt)?;
+			if let Some(check_owner) = maybe_check_owner {
+				ensure!(*check_owner == owner, Error::<T>::NotReceiptOwner);
+			}
+			Ok(())
+		})
+	}
+}
+
+impl<T: Config> nonfungibles::Transfer<T::AccountId> for Pallet<T> {
+	fn transfer(
+		collection: &FundIndex,
+		item: &ReceiptIndex,
+		destination: &T::AccountId,
+	) -> DispatchResult {
+		Receipts::<T>::try_mutate(collection, item, |maybe_receipt| -> DispatchResult {
+			let receipt = maybe_receipt.as_mut().ok_or(Error::<T>::UnknownReceipt)?;
+			ensure!(receipt.owner.is_some(), Error::<T>::UnknownReceipt);
+			receipt.owner = Some(destination.clone());
+			Ok(())
+		})
+	}
+}
+
 #[cfg(any(feature = "runtime-benchmarks", test))]
 mod crypto {
 	use sp_core::ed25519;
@@ -1077,6 +1357,18 @@ mod tests {
 		fn has_won_an_auction(para: ParaId, bidder: &u64) -> bool {
 			HAS_WON.with(|p| *p.borrow().get(&(para, *bidder)).unwrap_or(&false))
 		}
+
+		fn win_auction(
+			bidder: &u64,
+			para: ParaId,
+			_first_slot: u64,
+			_last_slot: u64,
+			amount: u64,
+		) -> DispatchResult {
+			Balances::reserve(bidder, amount)?;
+			HAS_WON.with(|p| p.borrow_mut().insert((para, *bidder), true));
+			Ok(())
+		}
 	}
 
 	parameter_types! {
@@ -1780,6 +2072,68 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn dissolve_removes_reward_claims() {
+		let mut ext = new_test_ext();
+		let para = ext.execute_with(|| {
+			let para = new_para();
+			let index = NextFundIndex::<Test>::get();
+			let account_id = Crowdloan::fund_account_id(index);
+
+			assert_ok!(Crowdloan::create(RuntimeOrigin::signed(1), para, 1000, 1, 1, 9, None));
+			assert_ok!(Crowdloan::contribute(RuntimeOrigin::signed(2), para, 100, None));
+			assert_ok!(Crowdloan::set_reward_schedule(
+				RuntimeOrigin::root(),
+				para,
+				Some(RewardSchedule::Attestation)
+			));
+			set_winner(para, account_id, true);
+			assert_ok!(Crowdloan::claim_reward(RuntimeOrigin::signed(2), para));
+			// The receipt is moved to another chain.
+			assert_ok!(Crowdloan::burn(&index, &0, Some(&2)));
+			// Some reward is left unclaimed.
+			Balances::make_free_balance_be(&Crowdloan::reward_account_id(index), 50);
+			// More claims than can be removed at once.
+			for who in 100..111 {
+				RewardClaims::<Test>::insert(index, who, ());
+			}
+
+			run_to_block(10);
+			set_winner(para, account_id, false);
+			assert_ok!(Crowdloan::refund(RuntimeOrigin::signed(2), para));
+			para
+		});
+		// Storage is only removed in batches once it is committed.
+		ext.commit_all().unwrap();
+		ext.execute_with(|| {
+			let index = Crowdloan::funds(para).unwrap().fund_index;
+
+			assert_ok!(Crowdloan::dissolve(RuntimeOrigin::signed(1), para));
+			assert_eq!(
+				last_event(),
+				super::Event::<Test>::PartiallyDissolved { para_id: para }.into()
+			);
+			assert!(Crowdloan::funds(para).is_some());
+
+			assert_ok!(Crowdloan::dissolve(RuntimeOrigin::signed(1), para));
+			assert_eq!(last_event(), super::Event::<Test>::Dissolved { para_id: para }.into());
+			assert!(Crowdloan::funds(para).is_none());
+			assert_eq!(Crowdloan::reward_schedule(index), None);
+			assert_eq!(RewardClaims::<Test>::iter_prefix(index).count(), 0);
+			// The deposit and the unclaimed reward are returned to the depositor.
+			assert_eq!(Balances::free_balance(Crowdloan::reward_account_id(index)), 0);
+			assert_eq!(Balances::free_balance(1), 1050);
+
+			// The receipt can still return.
+			assert_eq!(ReceiptCount::<Test>::get(index), 1);
+			assert_ok!(Crowdloan::mint_into(&index, &0, &2));
+			assert_eq!(
+				Crowdloan::receipts(index, 0),
+				Some(ContributionReceipt { owner: Some(2), contributor: 2, amount: 100 })
+			);
+		});
+	}
+
 	#[test]
 	fn withdraw_from_finished_works() {
 		new_test_ext().execute_with(|| {
@@ -1939,6 +2293,128 @@ mod tests {
 			);
 		});
 	}
+
+	#[test]
+	fn claim_native_reward_works() {
+		new_test_ext().execute_with(|| {
+			let para = new_para();
+			let index = NextFundIndex::<Test>::get();
+			let account_id = Crowdloan::fund_account_id(index);
+			let reward_account = Crowdloan::reward_account_id(index);
+
+			assert_ok!(Crowdloan::create(RuntimeOrigin::signed(1), para, 1000, 1, 1, 9, None));
+			assert_ok!(Crowdloan::contribute(RuntimeOrigin::signed(2), para, 100, None));
+			assert_ok!(Crowdloan::contribute(RuntimeOrigin::signed(3), para, 300, None));
+
+			// No reward is offered yet.
+			assert_noop!(
+				Crowdloan::claim_reward(RuntimeOrigin::signed(2), para),
+				Error::<Test>::NoRewardSchedule
+			);
+			let schedule = RewardSchedule::Native { total: 40 };
+			assert_ok!(Crowdloan::set_reward_schedule(
+				RuntimeOrigin::root(),
+				para,
+				Some(schedule.clone())
+			));
+			assert_eq!(
+				last_event(),
+				super::Event::<Test>::RewardScheduleSet { para_id: para }.into()
+			);
+			assert_eq!(Crowdloan::reward_schedule(index), Some(schedule));
+			assert_ok!(Balances::transfer(RuntimeOrigin::signed(1), reward_account, 100));
+
+			// Rewards can only be claimed once the fund has won.
+			assert_noop!(
+				Crowdloan::claim_reward(RuntimeOrigin::signed(2), para),
+				Error::<Test>::FundNotWon
+			);
+			set_winner(para, account_id, true);
+
+			assert_ok!(Crowdloan::claim_reward(RuntimeOrigin::signed(2), para));
+			assert_eq!(
+				last_event(),
+				super::Event::<Test>::RewardClaimed { who: 2, para_id: para, amount: 10 }.into()
+			);
+			assert_ok!(Crowdloan::claim_reward(RuntimeOrigin::signed(3), para));
+			assert_eq!(Balances::free_balance(2), 2000 - 100 + 10);
+			assert_eq!(Balances::free_balance(3), 3000 - 300 + 30);
+			assert_eq!(Balances::free_balance(reward_account), 60);
+
+			assert_noop!(
+				Crowdloan::claim_reward(RuntimeOrigin::signed(2), para),
+				Error::<Test>::RewardAlreadyClaimed
+			);
+			assert_noop!(
+				Crowdloan::claim_reward(RuntimeOrigin::signed(4), para),
+				Error::<Test>::NoContributions
+			);
+
+			// Removing the schedule stops further claims.
+			assert_ok!(Crowdloan::set_reward_schedule(RuntimeOrigin::root(), para, None));
+			assert_eq!(Crowdloan::reward_schedule(index), None);
+			assert_noop!(
+				Crowdloan::claim_reward(RuntimeOrigin::signed(3), para),
+				Error::<Test>::NoRewardSchedule
+			);
+		});
+	}
+
+	#[test]
+	fn contribution_receipts_work() {
+		use nonfungibles::{Inspect, Mutate, Transfer};
+
+		new_test_ext().execute_with(|| {
+			let para = new_para();
+			let index = NextFundIndex::<Test>::get();
+			let account_id = Crowdloan::fund_account_id(index);
+
+			assert_ok!(Crowdloan::create(RuntimeOrigin::signed(1), para, 1000, 1, 1, 9, None));
+			assert_ok!(Crowdloan::contribute(RuntimeOrigin::signed(2), para, 100, None));
+			assert_ok!(Crowdloan::contribute(RuntimeOrigin::signed(3), para, 50, None));
+			assert_ok!(Crowdloan::set_reward_schedule(
+				RuntimeOrigin::root(),
+				para,
+				Some(RewardSchedule::Attestation)
+			));
+			set_winner(para, account_id, true);
+
+			assert_ok!(Crowdloan::claim_reward(RuntimeOrigin::signed(2), para));
+			assert_ok!(Crowdloan::claim_reward(RuntimeOrigin::signed(3), para));
+			assert_eq!(
+				last_event(),
+				super::Event::<Test>::ReceiptIssued { who: 3, para_id: para, receipt: 1 }.into()
+			);
+			assert_eq!(
+				Crowdloan::receipts(index, 0),
+				Some(ContributionReceipt { owner: Some(2), contributor: 2, amount: 100 })
+			);
+			assert_eq!(Crowdloan::owner(&index, &1), Some(3));
+			assert_eq!(Crowdloan::attribute(&index, &1, b"contributor"), Some(3u64.encode()));
+			assert_eq!(Crowdloan::attribute(&index, &1, b"amount"), Some(50u64.encode()));
+			assert_eq!(Crowdloan::owner(&index, &2), None);
+
+			// Receipts can be moved around, e.g. to a parachain's sovereign account.
+			assert_ok!(<Crowdloan as Transfer<u64>>::transfer(&index, &0, &4));
+			assert_eq!(Crowdloan::owner(&index, &0), Some(4));
+
+			// Burning keeps the receipt, so that it can only be minted back as it was.
+			assert_noop!(Crowdloan::burn(&index, &0, Some(&2)), Error::<Test>::NotReceiptOwner);
+			assert_ok!(Crowdloan::burn(&index, &0, Some(&4)));
+			assert_eq!(Crowdloan::owner(&index, &0), None);
+			assert_noop!(
+				<Crowdloan as Transfer<u64>>::transfer(&index, &0, &2),
+				Error::<Test>::UnknownReceipt
+			);
+			assert_ok!(Crowdloan::mint_into(&index, &0, &2));
+			assert_eq!(
+				Crowdloan::receipts(index, 0),
+				Some(ContributionReceipt { owner: Some(2), contributor: 2, amount: 100 })
+			);
+			assert_noop!(Crowdloan::mint_into(&index, &0, &4), Error::<Test>::ReceiptAlreadyHeld);
+			assert_noop!(Crowdloan::mint_into(&index, &2, &4), Error::<Test>::UnknownReceipt);
+		});
+	}
 }
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -2090,15 +2566,25 @@ mod benchmarking {
 			assert_last_event::<T>(Event::<T>::AllRefunded { para_id: fund_index }.into());
 		}
 
+		// Worst case: Dissolve removes the reward claims of the fund, returns the leftover of the
+		// reward pot and removes the fund.
 		dissolve {
 			let (lpl, offset) = T::Auctioneer::lease_period_length();
 			let end = lpl + offset;
 			let fund_index = create_fund::<T>(1337, end);
+			let fund = Funds::<T>::get(fund_index).expect("fund was created...");
+			for i in 0 .. T::RemoveKeysLimit::get().saturating_sub(1) {
+				let claimer: T::AccountId = account("claimer", i, 0);
+				RewardClaims::<T>::insert(fund.fund_index, claimer, ());
+			}
+			let reward_account = Crowdloan::<T>::reward_account_id(fund.fund_index);
+			CurrencyOf::<T>::make_free_balance_be(&reward_account, T::MinContribution::get());
 			let caller: T::AccountId = whitelisted_caller();
 			frame_system::Pallet::<T>::set_block_number(T::BlockNumber::max_value());
 		}: _(RawOrigin::Signed(caller.clone()), fund_index)
 		verify {
 			assert_last_event::<T>(Event::<T>::Dissolved { para_id: fund_index }.into());
+			assert!(CurrencyOf::<T>::free_balance(&reward_account).is_zero());
 		}
 
 		edit {
@@ -2160,6 +2646,47 @@ mod benchmarking {
 			assert_last_event::<T>(Event::<T>::AddedToNewRaise { para_id: fund_index }.into())
 		}
 
+		// Native rewards move funds between two accounts, which is heavier than issuing a receipt.
+		claim_reward {
+			let (lpl, offset) = T::Auctioneer::lease_period_length();
+			let end = lpl + offset;
+			let fund_index = create_fund::<T>(1, end);
+			let caller: T::AccountId = whitelisted_caller();
+			contribute_fund::<T>(&caller, fund_index);
+
+			let fund = Funds::<T>::get(fund_index).expect("fund was created...");
+			let total = T::MinContribution::get();
+			Crowdloan::<T>::set_reward_schedule(
+				RawOrigin::Root.into(),
+				fund_index,
+				Some(RewardSchedule::Native { total }),
+			)?;
+			let reward_account = Crowdloan::<T>::reward_account_id(fund.fund_index);
+			CurrencyOf::<T>::make_free_balance_be(&reward_account, BalanceOf::<T>::max_value());
+			T::Auctioneer::win_auction(
+				&Crowdloan::<T>::fund_account_id(fund.fund_index),
+				fund_index,
+				fund.first_period,
+				fund.last_period,
+				fund.raised,
+			)?;
+		}: _(RawOrigin::Signed(caller.clone()), fund_index)
+		verify {
+			assert_last_event::<T>(
+				Event::<T>::RewardClaimed { who: caller, para_id: fund_index, amount: total }.into(),
+			);
+		}
+
+		set_reward_schedule {
+			let (lpl, offset) = T::Auctioneer::lease_period_length();
+			let end = lpl + offset;
+			let fund_index = create_fund::<T>(1, end);
+			let reward = Some(RewardSchedule::Native { total: BalanceOf::<T>::max_value() });
+		}: _(RawOrigin::Root, fund_index, reward)
+		verify {
+			assert_last_event::<T>(Event::<T>::RewardScheduleSet { para_id: fund_index }.into())
+		}
+
 		// Worst case scenario: N funds are all in the `NewRaise` list, we are
 		// in the beginning of the ending period, and each fund outbids the next
 		// over the same periods.
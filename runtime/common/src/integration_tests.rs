@@ -17,7 +17,9 @@
 //! Mocking utilities for testing with real pallets.
 
 use crate::{
-	auctions, crowdloan, paras_registrar,
+	auctions, crowdloan,
+	para_deposits::{self, DepositKind, ParaDeposit},
+	paras_registrar,
 	slot_range::SlotRange,
 	slots,
 	traits::{AuctionStatus, Auctioneer, Leaser, Registrar as RegistrarT},
@@ -31,15 +33,17 @@ use frame_support::{
 use frame_support_test::TestRandomness;
 use frame_system::EnsureRoot;
 use parity_scale_codec::Encode;
-use primitives::{BlockNumber, HeadData, Header, Id as ParaId, ValidationCode, LOWEST_PUBLIC_ID};
+use primitives::{
+	BlockNumber, HeadData, Header, HrmpChannelId, Id as ParaId, ValidationCode, LOWEST_PUBLIC_ID,
+};
 use runtime_parachains::{
-	configuration, origin, paras, shared, Origin as ParaOrigin, ParaLifecycle,
+	configuration, dmp, hrmp, origin, paras, shared, Origin as ParaOrigin, ParaLifecycle,
 };
 use sp_core::{crypto::KeyTypeId, H256};
 use sp_io::TestExternalities;
 use sp_keystore::{testing::KeyStore, KeystoreExt};
 use sp_runtime::{
-	traits::{BlakeTwo256, IdentityLookup, One},
+	traits::{AccountIdConversion, BlakeTwo256, IdentityLookup, One},
 	transaction_validity::TransactionPriority,
 	AccountId32,
 };
@@ -80,6 +84,8 @@ frame_support::construct_runtime!(
 		Paras: paras::{Pallet, Call, Storage, Event, Config},
 		ParasShared: shared::{Pallet, Call, Storage},
 		ParachainsOrigin: origin::{Pallet, Origin},
+		Dmp: dmp::{Pallet, Storage},
+		Hrmp: hrmp::{Pallet, Call, Storage, Event<T>},
 
 		// Para Onboarding Pallets
 		Registrar: paras_registrar::{Pallet, Call, Storage, Event<T>},
@@ -202,11 +208,22 @@ parameter_types! {
 
 impl paras::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
 	type WeightInfo = paras::TestWeightInfo;
 	type UnsignedPriority = ParasUnsignedPriority;
 	type NextSessionRotation = crate::mock::TestNextSessionRotation;
 }
 
+impl dmp::Config for Test {}
+
+impl hrmp::Config for Test {
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type SystemParas = hrmp::IsSystemPara;
+	type WeightInfo = hrmp::TestWeightInfo;
+}
+
 parameter_types! {
 	pub const ParaDeposit: Balance = 500;
 	pub const DataDepositPerByte: Balance = 1;
@@ -234,6 +251,7 @@ impl auctions::Config for Test {
 	type EndingPeriod = EndingPeriod;
 	type SampleLength = SampleLength;
 	type Randomness = TestRandomness<Self>;
+	type MaxAuctionAudits = frame_support::traits::ConstU32<4>;
 	type InitiateOrigin = EnsureRoot<AccountId>;
 	type WeightInfo = crate::auctions::TestWeightInfo;
 }
@@ -1617,3 +1635,120 @@ fn cant_bid_on_existing_lease_periods() {
 		));
 	});
 }
+
+#[test]
+fn para_deposits_are_reported() {
+	new_test_ext().execute_with(|| {
+		let para = ParaId::from(LOWEST_PUBLIC_ID);
+		Balances::make_free_balance_be(&account_id(1), 1_000_000_000);
+		Balances::make_free_balance_be(&account_id(2), 1_000_000_000);
+		Balances::make_free_balance_be(&account_id(10), 1_000_000_000);
+		run_to_block(10);
+
+		assert_ok!(Registrar::reserve(signed(1)));
+		assert_ok!(Registrar::register(
+			signed(1),
+			para,
+			test_genesis_head(10),
+			test_validation_code(10),
+		));
+		let registration = Balances::reserved_balance(&account_id(1));
+
+		// A crowdloan, one third of which backs a lease of the para.
+		assert_ok!(Crowdloan::create(signed(1), para, 1_000, 1, 3, 200, None));
+		assert_ok!(Crowdloan::contribute(signed(2), para, 600, None));
+		let fund_index = Crowdloan::funds(para).unwrap().fund_index;
+		let crowdloan_account = Crowdloan::fund_account_id(fund_index);
+		assert_ok!(Slots::force_lease(RuntimeOrigin::root(), para, account_id(10), 300, 1, 2));
+		assert_ok!(Slots::force_lease(
+			RuntimeOrigin::root(),
+			para,
+			crowdloan_account.clone(),
+			200,
+			3,
+			1,
+		));
+
+		// Open channels to and from other paras, a request to open one to a third para and an
+		// accepted request from a system para, which takes no deposit.
+		let channel = |sender_deposit, recipient_deposit| hrmp::HrmpChannel {
+			max_capacity: 1,
+			max_total_size: 1,
+			max_message_size: 1,
+			msg_count: 0,
+			total_size: 0,
+			mqc_head: None,
+			sender_deposit,
+			recipient_deposit,
+		};
+		let egress = HrmpChannelId { sender: para, recipient: para + 1 };
+		hrmp::HrmpChannels::<Test>::insert(&egress, channel(50, 40));
+		hrmp::HrmpEgressChannelsIndex::<Test>::insert(para, vec![para + 1]);
+		let ingress = HrmpChannelId { sender: para + 3, recipient: para };
+		hrmp::HrmpChannels::<Test>::insert(&ingress, channel(30, 10));
+		hrmp::HrmpIngressChannelsIndex::<Test>::insert(para, vec![para + 3]);
+		let request = |confirmed, sender_deposit| hrmp::HrmpOpenChannelRequest {
+			confirmed,
+			_age: 0,
+			sender_deposit,
+			max_message_size: 1,
+			max_capacity: 1,
+			max_total_size: 1,
+		};
+		let outgoing_request = HrmpChannelId { sender: para, recipient: para + 2 };
+		hrmp::HrmpOpenChannelRequests::<Test>::insert(&outgoing_request, request(false, 20));
+		let system_request = HrmpChannelId { sender: para + 4, recipient: para };
+		hrmp::HrmpOpenChannelRequests::<Test>::insert(&system_request, request(true, 0));
+		hrmp::HrmpSystemChannelRequests::<Test>::insert(&system_request, ());
+		hrmp::HrmpOpenChannelRequestsList::<Test>::put(vec![outgoing_request, system_request]);
+
+		let sovereign: AccountId = para.into_account_truncating();
+		let deposits = para_deposits::para_deposits::<Test>(para);
+		assert_eq!(
+			deposits.deposits,
+			vec![
+				ParaDeposit {
+					kind: DepositKind::Registration,
+					who: account_id(1),
+					amount: registration,
+				},
+				ParaDeposit {
+					kind: DepositKind::CrowdloanSubmission { fund_index },
+					who: account_id(1),
+					amount: SubmissionDeposit::get(),
+				},
+				ParaDeposit {
+					kind: DepositKind::CrowdloanContributions { fund_index },
+					who: crowdloan_account.clone(),
+					amount: 400,
+				},
+				ParaDeposit {
+					kind: DepositKind::Lease { first_period: 1, last_period: 2 },
+					who: account_id(10),
+					amount: 300,
+				},
+				ParaDeposit {
+					kind: DepositKind::Lease { first_period: 3, last_period: 3 },
+					who: crowdloan_account,
+					amount: 200,
+				},
+				ParaDeposit {
+					kind: DepositKind::HrmpOpenRequest { sender: para, recipient: para + 2 },
+					who: sovereign.clone(),
+					amount: 20,
+				},
+				ParaDeposit {
+					kind: DepositKind::HrmpChannel { sender: para, recipient: para + 1 },
+					who: sovereign.clone(),
+					amount: 50,
+				},
+				ParaDeposit {
+					kind: DepositKind::HrmpChannel { sender: para + 3, recipient: para },
+					who: sovereign,
+					amount: 10,
+				},
+			],
+		);
+		assert_eq!(deposits.total, registration + SubmissionDeposit::get() + 400 + 300 + 200 + 80);
+	});
+}
@@ -24,6 +24,7 @@ pub mod claims;
 pub mod crowdloan;
 pub mod elections;
 pub mod impls;
+pub mod para_deposits;
 pub mod paras_registrar;
 pub mod paras_sudo_wrapper;
 pub mod purchase;
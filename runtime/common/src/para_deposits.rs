@@ -0,0 +1,251 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Accounting of the funds locked on behalf of a para.
+//!
+//! The funds backing a para are spread over the deposit of its registration in `paras_registrar`,
+//! the deposits of its leases in `slots`, the deposit and the contributions of its crowdloan in
+//! `crowdloan`, and the deposits of its HRMP channels in `hrmp`. [`para_deposits`] gathers all of
+//! them, along with the accounts they are held by, and is exposed through the `ParaDepositsApi`
+//! runtime API.
+
+use crate::{
+	crowdloan::{self, FundIndex},
+	paras_registrar, slots,
+	traits::Leaser,
+};
+use parity_scale_codec::{Codec, Decode, Encode};
+use primitives::{Balance, HrmpChannelId, Id as ParaId};
+use runtime_parachains::{configuration, hrmp};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{AccountIdConversion, Saturating, Zero},
+	RuntimeDebug, SaturatedConversion,
+};
+use sp_std::prelude::*;
+
+/// What a balance is locked for on behalf of a para.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum DepositKind<LeasePeriod> {
+	/// The deposit for the registration of the para, reserved by its manager.
+	Registration,
+	/// The deposit for the lease periods from `first_period` to `last_period` of the para,
+	/// reserved by their leaser.
+	Lease { first_period: LeasePeriod, last_period: LeasePeriod },
+	/// The deposit for creating a crowdloan for the para, reserved by its creator.
+	CrowdloanSubmission { fund_index: FundIndex },
+	/// The contributions to a crowdloan for the para, held by the account of the fund.
+	///
+	/// Contributions which back a lease of the para are accounted for by the `Lease` instead.
+	CrowdloanContributions { fund_index: FundIndex },
+	/// The deposit for requesting or accepting a request to open an HRMP channel, reserved by
+	/// the sovereign account of the para.
+	HrmpOpenRequest { sender: ParaId, recipient: ParaId },
+	/// The deposit for an open HRMP channel, reserved by the sovereign account of the para.
+	HrmpChannel { sender: ParaId, recipient: ParaId },
+}
+
+/// A balance locked on behalf of a para.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ParaDeposit<AccountId, Balance, LeasePeriod> {
+	/// What the balance is locked for.
+	pub kind: DepositKind<LeasePeriod>,
+	/// The account which the balance is held by.
+	pub who: AccountId,
+	/// The locked balance.
+	pub amount: Balance,
+}
+
+/// All the balances locked on behalf of a para.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ParaDeposits<AccountId, Balance, LeasePeriod> {
+	/// The locked balances, one per deposit.
+	pub deposits: Vec<ParaDeposit<AccountId, Balance, LeasePeriod>>,
+	/// The sum of all the locked balances.
+	pub total: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API for querying the balances locked on behalf of a para.
+	pub trait ParaDepositsApi<AccountId, Balance, LeasePeriod>
+	where
+		AccountId: Codec,
+		Balance: Codec,
+		LeasePeriod: Codec,
+	{
+		/// All the balances locked on behalf of `para`, along with the accounts holding them.
+		fn para_deposits(para: ParaId) -> ParaDeposits<AccountId, Balance, LeasePeriod>;
+	}
+}
+
+/// All the balances locked on behalf of `para` across the registrar, slots, crowdloan and HRMP
+/// pallets.
+pub fn para_deposits<T>(para: ParaId) -> ParaDeposits<T::AccountId, Balance, T::BlockNumber>
+where
+	T: paras_registrar::Config + slots::Config + crowdloan::Config + hrmp::Config,
+{
+	let mut deposits = Vec::new();
+
+	if let Some(info) = paras_registrar::Paras::<T>::get(para) {
+		deposits.push(ParaDeposit {
+			kind: DepositKind::Registration,
+			who: info.manager,
+			amount: info.deposit.saturated_into(),
+		});
+	}
+
+	let lease_deposits = lease_deposits::<T>(para);
+	if let Some(fund) = crowdloan::Funds::<T>::get(para) {
+		let fund_account = crowdloan::Pallet::<T>::fund_account_id(fund.fund_index);
+		let leased = lease_deposits
+			.iter()
+			.find(|deposit| deposit.who == fund_account)
+			.map_or_else(Zero::zero, |deposit| deposit.amount);
+		deposits.push(ParaDeposit {
+			kind: DepositKind::CrowdloanSubmission { fund_index: fund.fund_index },
+			who: fund.depositor,
+			amount: fund.deposit.saturated_into(),
+		});
+		deposits.push(ParaDeposit {
+			kind: DepositKind::CrowdloanContributions { fund_index: fund.fund_index },
+			who: fund_account,
+			amount: fund.raised.saturated_into::<Balance>().saturating_sub(leased),
+		});
+	}
+	deposits.extend(lease_deposits);
+	deposits.extend(hrmp_deposits::<T>(para));
+
+	deposits.retain(|deposit| !deposit.amount.is_zero());
+	let total = deposits.iter().fold(Zero::zero(), |total: Balance, deposit| {
+		total.saturating_add(deposit.amount)
+	});
+
+	ParaDeposits { deposits, total }
+}
+
+/// The deposits held for the current and upcoming leases of `para`, one per leaser.
+///
+/// A leaser holds the largest of the amounts of its leases, as `Leaser::deposit_held` does.
+fn lease_deposits<T: slots::Config>(
+	para: ParaId,
+) -> Vec<ParaDeposit<T::AccountId, Balance, T::BlockNumber>> {
+	let now = frame_system::Pallet::<T>::block_number();
+	let current_lease_period =
+		<slots::Pallet<T> as Leaser<T::BlockNumber>>::lease_period_index(now)
+			.map_or_else(Zero::zero, |(lease_period, _)| lease_period);
+
+	let mut deposits: Vec<ParaDeposit<T::AccountId, Balance, T::BlockNumber>> = Vec::new();
+	for (offset, lease) in slots::Leases::<T>::get(para).into_iter().enumerate() {
+		let (leaser, amount) = match lease {
+			Some(lease) => lease,
+			None => continue,
+		};
+		let lease_period = current_lease_period.saturating_add((offset as u32).into());
+		let amount = amount.saturated_into::<Balance>();
+		match deposits.iter_mut().find(|deposit| deposit.who == leaser) {
+			Some(deposit) => {
+				if let DepositKind::Lease { last_period, .. } = &mut deposit.kind {
+					*last_period = lease_period;
+				}
+				deposit.amount = deposit.amount.max(amount);
+			},
+			None => deposits.push(ParaDeposit {
+				kind: DepositKind::Lease {
+					first_period: lease_period,
+					last_period: lease_period,
+				},
+				who: leaser,
+				amount,
+			}),
+		}
+	}
+	deposits
+}
+
+/// The deposits reserved by the sovereign account of `para` for its HRMP channels and open
+/// channel requests.
+///
+/// The deposits are read from the channels and requests they were taken for, so that changes to
+/// the configuration do not affect them. Channels with system parachains take no deposit and are
+/// left out.
+fn hrmp_deposits<T: hrmp::Config>(
+	para: ParaId,
+) -> Vec<ParaDeposit<T::AccountId, Balance, T::BlockNumber>> {
+	let sovereign: T::AccountId = para.into_account_truncating();
+	let recipient_deposit = configuration::Pallet::<T>::config().hrmp_recipient_deposit;
+	let mut deposits = Vec::new();
+
+	for channel_id in hrmp::HrmpOpenChannelRequestsList::<T>::get() {
+		let request = match hrmp::HrmpOpenChannelRequests::<T>::get(&channel_id) {
+			Some(request) => request,
+			None => continue,
+		};
+		let amount = if channel_id.sender == para {
+			request.sender_deposit
+		} else if channel_id.recipient == para && request.confirmed {
+			// The deposit of the recipient is not stored with the request. Confirmed requests are
+			// enacted at the next session change, so it is the one of the active configuration.
+			if hrmp::HrmpSystemChannelRequests::<T>::contains_key(&channel_id) {
+				0
+			} else {
+				recipient_deposit
+			}
+		} else {
+			continue
+		};
+		if amount.is_zero() {
+			continue
+		}
+		deposits.push(ParaDeposit {
+			kind: DepositKind::HrmpOpenRequest {
+				sender: channel_id.sender,
+				recipient: channel_id.recipient,
+			},
+			who: sovereign.clone(),
+			amount,
+		});
+	}
+
+	let egress = hrmp::HrmpEgressChannelsIndex::<T>::get(para)
+		.into_iter()
+		.map(|recipient| HrmpChannelId { sender: para, recipient });
+	let ingress = hrmp::HrmpIngressChannelsIndex::<T>::get(para)
+		.into_iter()
+		.map(|sender| HrmpChannelId { sender, recipient: para });
+	for channel_id in egress.chain(ingress) {
+		let channel = match hrmp::HrmpChannels::<T>::get(&channel_id) {
+			Some(channel) => channel,
+			None => continue,
+		};
+		let amount = if channel_id.sender == para {
+			channel.sender_deposit
+		} else {
+			channel.recipient_deposit
+		};
+		if amount.is_zero() {
+			continue
+		}
+		deposits.push(ParaDeposit {
+			kind: DepositKind::HrmpChannel {
+				sender: channel_id.sender,
+				recipient: channel_id.recipient,
+			},
+			who: sovereign.clone(),
+			amount,
+		});
+	}
+	deposits
+}
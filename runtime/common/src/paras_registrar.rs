@@ -47,7 +47,7 @@ pub struct ParaInfo<Account, Balance> {
 	/// The account that has placed a deposit for registering this para.
 	pub(crate) manager: Account,
 	/// The amount reserved by the `manager` account for the registration.
-	deposit: Balance,
+	pub(crate) deposit: Balance,
 	/// Whether the para registration should be locked from being controlled by the manager.
 	locked: bool,
 }
@@ -63,6 +63,8 @@ pub trait WeightInfo {
 	fn swap() -> Weight;
 	fn schedule_code_upgrade(b: u32) -> Weight;
 	fn set_current_head(b: u32) -> Weight;
+	fn transfer_manager() -> Weight;
+	fn accept_manager() -> Weight;
 }
 
 pub struct TestWeightInfo;
@@ -88,6 +90,12 @@ impl WeightInfo for TestWeightInfo {
 	fn set_current_head(_b: u32) -> Weight {
 		Weight::zero()
 	}
+	fn transfer_manager() -> Weight {
+		Weight::zero()
+	}
+	fn accept_manager() -> Weight {
+		Weight::zero()
+	}
 }
 
 #[frame_support::pallet]
@@ -136,9 +144,41 @@ pub mod pallet {
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		Registered { para_id: ParaId, manager: T::AccountId },
-		Deregistered { para_id: ParaId },
-		Reserved { para_id: ParaId, who: T::AccountId },
+		Registered {
+			para_id: ParaId,
+			manager: T::AccountId,
+		},
+		Deregistered {
+			para_id: ParaId,
+		},
+		Reserved {
+			para_id: ParaId,
+			who: T::AccountId,
+		},
+		/// A manager transfer was proposed and is waiting for `new_manager` to accept it.
+		ManagerTransferProposed {
+			para_id: ParaId,
+			manager: T::AccountId,
+			new_manager: T::AccountId,
+		},
+		/// A pending manager transfer was cancelled.
+		ManagerTransferCancelled {
+			para_id: ParaId,
+		},
+		/// The manager of a para changed and the registration deposit moved with it.
+		ManagerChanged {
+			para_id: ParaId,
+			old_manager: T::AccountId,
+			new_manager: T::AccountId,
+		},
+		/// The para was locked from being controlled by its manager.
+		Locked {
+			para_id: ParaId,
+		},
+		/// The para was unlocked and can be controlled by its manager again.
+		Unlocked {
+			para_id: ParaId,
+		},
 	}
 
 	#[pallet::error]
@@ -172,6 +212,10 @@ pub mod pallet {
 		/// Cannot perform a parachain slot / lifecycle swap. Check that the state of both paras are
 		/// correct for the swap to work.
 		CannotSwap,
+		/// There is no pending manager transfer for this para.
+		NoPendingTransfer,
+		/// The caller is not the account the manager transfer was proposed to.
+		NotPendingManager,
 	}
 
 	/// Pending swap operations.
@@ -186,6 +230,12 @@ pub mod pallet {
 	pub type Paras<T: Config> =
 		StorageMap<_, Twox64Concat, ParaId, ParaInfo<T::AccountId, BalanceOf<T>>>;
 
+	/// Pending manager transfers, keyed by para and holding the proposed new manager.
+	///
+	/// The transfer only takes effect once the new manager accepts it with `accept_manager`.
+	#[pallet::storage]
+	pub type PendingManager<T: Config> = StorageMap<_, Twox64Concat, ParaId, T::AccountId>;
+
 	/// The next free `ParaId`.
 	#[pallet::storage]
 	pub type NextFreeParaId<T> = StorageValue<_, ParaId, ValueQuery>;
@@ -405,6 +455,66 @@ pub mod pallet {
 			runtime_parachains::set_current_head::<T>(para, new_head);
 			Ok(())
 		}
+
+		/// Propose a new manager for a para.
+		///
+		/// Can be called by Root, the parachain, or the parachain manager if the parachain is
+		/// unlocked. The transfer only takes effect once `new_manager` calls `accept_manager`,
+		/// which also moves the registration deposit over to them.
+		///
+		/// Proposing the current manager clears any pending transfer.
+		#[pallet::call_index(9)]
+		#[pallet::weight(<T as Config>::WeightInfo::transfer_manager())]
+		pub fn transfer_manager(
+			origin: OriginFor<T>,
+			para: ParaId,
+			new_manager: T::AccountId,
+		) -> DispatchResult {
+			Self::ensure_root_para_or_owner(origin, para)?;
+			let info = Paras::<T>::get(para).ok_or(Error::<T>::NotRegistered)?;
+
+			if info.manager == new_manager {
+				if PendingManager::<T>::take(para).is_some() {
+					Self::deposit_event(Event::<T>::ManagerTransferCancelled { para_id: para });
+				}
+				return Ok(())
+			}
+
+			PendingManager::<T>::insert(para, &new_manager);
+			Self::deposit_event(Event::<T>::ManagerTransferProposed {
+				para_id: para,
+				manager: info.manager,
+				new_manager,
+			});
+			Ok(())
+		}
+
+		/// Accept a pending manager transfer for a para.
+		///
+		/// Must be called by the `Signed` account the transfer was proposed to. The deposit held
+		/// for the registration is reserved from the new manager and released to the old one.
+		#[pallet::call_index(10)]
+		#[pallet::weight(<T as Config>::WeightInfo::accept_manager())]
+		pub fn accept_manager(origin: OriginFor<T>, para: ParaId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let pending = PendingManager::<T>::get(para).ok_or(Error::<T>::NoPendingTransfer)?;
+			ensure!(pending == who, Error::<T>::NotPendingManager);
+
+			Paras::<T>::try_mutate(para, |maybe_info| -> DispatchResult {
+				let info = maybe_info.as_mut().ok_or(Error::<T>::NotRegistered)?;
+				<T as Config>::Currency::reserve(&who, info.deposit)?;
+				<T as Config>::Currency::unreserve(&info.manager, info.deposit);
+				let old_manager = sp_std::mem::replace(&mut info.manager, who.clone());
+				Self::deposit_event(Event::<T>::ManagerChanged {
+					para_id: para,
+					old_manager,
+					new_manager: who,
+				});
+				Ok(())
+			})?;
+			PendingManager::<T>::remove(para);
+			Ok(())
+		}
 	}
 }
 
@@ -434,11 +544,13 @@ impl<T: Config> Registrar for Pallet<T> {
 	// Apply a lock to the parachain.
 	fn apply_lock(id: ParaId) {
 		Paras::<T>::mutate(id, |x| x.as_mut().map(|mut info| info.locked = true));
+		Self::deposit_event(Event::<T>::Locked { para_id: id });
 	}
 
 	// Remove a lock from the parachain.
 	fn remove_lock(id: ParaId) {
 		Paras::<T>::mutate(id, |x| x.as_mut().map(|mut info| info.locked = false));
+		Self::deposit_event(Event::<T>::Unlocked { para_id: id });
 	}
 
 	// Register a Para ID under control of `manager`.
@@ -510,6 +622,13 @@ impl<T: Config> Registrar for Pallet<T> {
 }
 
 impl<T: Config> Pallet<T> {
+	/// Whether the para is locked from being controlled by its manager.
+	///
+	/// Returns `None` if the para is not registered.
+	pub fn is_locked(id: ParaId) -> Option<bool> {
+		Paras::<T>::get(id).map(|info| info.locked)
+	}
+
 	/// Ensure the origin is one of Root, the `para` owner, or the `para` itself.
 	/// If the origin is the `para` owner, the `para` must be unlocked.
 	fn ensure_root_para_or_owner(
@@ -613,6 +732,7 @@ impl<T: Config> Pallet<T> {
 		}
 
 		PendingSwap::<T>::remove(id);
+		PendingManager::<T>::remove(id);
 		Self::deposit_event(Event::<T>::Deregistered { para_id: id });
 		Ok(())
 	}
@@ -763,6 +883,7 @@ mod tests {
 
 	impl paras::Config for Test {
 		type RuntimeEvent = RuntimeEvent;
+		type RuntimeOrigin = RuntimeOrigin;
 		type WeightInfo = paras::TestWeightInfo;
 		type UnsignedPriority = ParasUnsignedPriority;
 		type NextSessionRotation = crate::mock::TestNextSessionRotation;
@@ -905,6 +1026,8 @@ mod tests {
 			assert!(!Parachains::is_parachain(para_id));
 			// Some other external process will elevate parathread to parachain
 			assert_ok!(Registrar::make_parachain(para_id));
+			// Which locks it.
+			System::assert_last_event(Event::<Test>::Locked { para_id }.into());
 			run_to_session(4);
 			// It is now a parachain.
 			assert!(!Parachains::is_parathread(para_id));
@@ -1151,6 +1274,7 @@ mod tests {
 			assert_noop!(Registrar::add_lock(RuntimeOrigin::signed(2), para_id), BadOrigin);
 			// Once they begin onboarding, we lock them in.
 			assert_ok!(Registrar::add_lock(RuntimeOrigin::signed(1), para_id));
+			System::assert_last_event(Event::<Test>::Locked { para_id }.into());
 			// Owner cannot pass origin check when checking lock
 			assert_noop!(
 				Registrar::ensure_root_para_or_owner(RuntimeOrigin::signed(1), para_id),
@@ -1160,11 +1284,83 @@ mod tests {
 			assert_noop!(Registrar::remove_lock(RuntimeOrigin::signed(1), para_id), BadOrigin);
 			// Para can.
 			assert_ok!(Registrar::remove_lock(para_origin(para_id), para_id));
+			System::assert_last_event(Event::<Test>::Unlocked { para_id }.into());
 			// Owner can pass origin check again
 			assert_ok!(Registrar::ensure_root_para_or_owner(RuntimeOrigin::signed(1), para_id));
 		});
 	}
 
+	#[test]
+	fn manager_transfer_works() {
+		new_test_ext().execute_with(|| {
+			run_to_block(1);
+
+			assert_ok!(Registrar::reserve(RuntimeOrigin::signed(1)));
+			let para_id = LOWEST_PUBLIC_ID;
+			assert_ok!(Registrar::register(
+				RuntimeOrigin::signed(1),
+				para_id,
+				vec![1; 3].into(),
+				vec![1, 2, 3].into(),
+			));
+			let deposit = Balances::reserved_balance(&1);
+			assert!(deposit > 0);
+
+			// Only the manager, root or the para can propose a transfer.
+			assert_noop!(
+				Registrar::transfer_manager(RuntimeOrigin::signed(2), para_id, 2),
+				BadOrigin
+			);
+			assert_noop!(
+				Registrar::accept_manager(RuntimeOrigin::signed(2), para_id),
+				Error::<Test>::NoPendingTransfer
+			);
+
+			assert_ok!(Registrar::transfer_manager(RuntimeOrigin::signed(1), para_id, 2));
+			assert_eq!(PendingManager::<Test>::get(para_id), Some(2));
+			// Nothing changes until the transfer is accepted.
+			assert_eq!(Registrar::manager_of(para_id), Some(1));
+			assert_noop!(
+				Registrar::accept_manager(RuntimeOrigin::signed(3), para_id),
+				Error::<Test>::NotPendingManager
+			);
+
+			assert_ok!(Registrar::accept_manager(RuntimeOrigin::signed(2), para_id));
+			assert_eq!(Registrar::manager_of(para_id), Some(2));
+			assert_eq!(PendingManager::<Test>::get(para_id), None);
+			// The deposit moved with the manager.
+			assert_eq!(Balances::reserved_balance(&1), 0);
+			assert_eq!(Balances::reserved_balance(&2), deposit);
+
+			// Proposing the current manager cancels a pending transfer.
+			assert_ok!(Registrar::transfer_manager(RuntimeOrigin::signed(2), para_id, 1));
+			assert_ok!(Registrar::transfer_manager(RuntimeOrigin::signed(2), para_id, 2));
+			assert_eq!(PendingManager::<Test>::get(para_id), None);
+
+			// A locked para can no longer be transferred by its manager, but the para can.
+			assert_ok!(Registrar::add_lock(RuntimeOrigin::signed(2), para_id));
+			assert_eq!(Registrar::is_locked(para_id), Some(true));
+			assert_noop!(
+				Registrar::transfer_manager(RuntimeOrigin::signed(2), para_id, 1),
+				BadOrigin
+			);
+			assert_ok!(Registrar::transfer_manager(para_origin(para_id), para_id, 3));
+
+			// The new manager must be able to cover the deposit.
+			assert_noop!(
+				Registrar::accept_manager(RuntimeOrigin::signed(3), para_id),
+				BalancesError::<Test, _>::InsufficientBalance
+			);
+			Balances::make_free_balance_be(&3, deposit);
+			assert_ok!(Registrar::accept_manager(RuntimeOrigin::signed(3), para_id));
+			assert_eq!(Registrar::manager_of(para_id), Some(3));
+			assert_eq!(Balances::reserved_balance(&2), 0);
+			assert_eq!(Balances::reserved_balance(&3), deposit);
+			// The lock stays with the para.
+			assert_eq!(Registrar::is_locked(para_id), Some(true));
+		});
+	}
+
 	#[test]
 	fn swap_handles_bad_states() {
 		new_test_ext().execute_with(|| {
@@ -1416,6 +1612,38 @@ mod benchmarking {
 			let para_id = ParaId::from(1000);
 		}: _(RawOrigin::Root, para_id, new_head)
 
+		transfer_manager {
+			let para = register_para::<T>(LOWEST_PUBLIC_ID.into());
+			let caller: T::AccountId = whitelisted_caller();
+			let new_manager: T::AccountId = account("manager", 0, 0);
+		}: _(RawOrigin::Signed(caller.clone()), para, new_manager.clone())
+		verify {
+			assert_last_event::<T>(Event::<T>::ManagerTransferProposed {
+				para_id: para,
+				manager: caller,
+				new_manager,
+			}.into());
+		}
+
+		accept_manager {
+			let para = register_para::<T>(LOWEST_PUBLIC_ID.into());
+			let caller: T::AccountId = whitelisted_caller();
+			let new_manager: T::AccountId = account("manager", 0, 0);
+			T::Currency::make_free_balance_be(&new_manager, BalanceOf::<T>::max_value());
+			Registrar::<T>::transfer_manager(
+				RawOrigin::Signed(caller.clone()).into(),
+				para,
+				new_manager.clone(),
+			)?;
+		}: _(RawOrigin::Signed(new_manager.clone()), para)
+		verify {
+			assert_last_event::<T>(Event::<T>::ManagerChanged {
+				para_id: para,
+				old_manager: caller,
+				new_manager,
+			}.into());
+		}
+
 		impl_benchmark_test_suite!(
 			Registrar,
 			crate::integration_tests::new_test_ext(),
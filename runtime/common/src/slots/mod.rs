@@ -20,30 +20,48 @@
 //!
 //! This doesn't handle the mechanics of determining which para ID actually ends up with a parachain lease. This
 //! must handled by a separately, through the trait interface that this pallet provides or the root dispatchables.
+//!
+//! Leasers may offer individual future lease periods of a para for sale or transfer. Buying one
+//! moves the lease period to another para and hands its deposit over from the seller to the buyer.
 
 pub mod migration;
 
 use crate::traits::{LeaseError, Leaser, Registrar};
 use frame_support::{
 	pallet_prelude::*,
-	traits::{Currency, ReservableCurrency},
+	traits::{Currency, ExistenceRequirement, ReservableCurrency},
 	weights::Weight,
 };
 use frame_system::pallet_prelude::*;
 pub use pallet::*;
 use primitives::Id as ParaId;
-use sp_runtime::traits::{CheckedConversion, CheckedSub, Saturating, Zero};
+use sp_runtime::traits::{CheckedConversion, CheckedSub, One, Saturating, Zero};
 use sp_std::prelude::*;
 
 type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 type LeasePeriodOf<T> = <T as frame_system::Config>::BlockNumber;
 
+/// An offer of a future lease period of a para by its leaser.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct LeaseListing<AccountId, Balance> {
+	/// The leaser of the lease period, who receives the price.
+	pub seller: AccountId,
+	/// The price of the lease period. The buyer also takes over its deposit.
+	pub price: Balance,
+	/// The only account allowed to buy the lease period, if any. A listing with a buyer and
+	/// no price is a transfer.
+	pub buyer: Option<AccountId>,
+}
+
 pub trait WeightInfo {
 	fn force_lease() -> Weight;
 	fn manage_lease_period_start(c: u32, t: u32) -> Weight;
 	fn clear_all_leases() -> Weight;
 	fn trigger_onboard() -> Weight;
+	fn list_lease_period() -> Weight;
+	fn unlist_lease_period() -> Weight;
+	fn buy_lease_period() -> Weight;
 }
 
 pub struct TestWeightInfo;
@@ -60,6 +78,15 @@ impl WeightInfo for TestWeightInfo {
 	fn trigger_onboard() -> Weight {
 		Weight::zero()
 	}
+	fn list_lease_period() -> Weight {
+		Weight::zero()
+	}
+	fn unlist_lease_period() -> Weight {
+		Weight::zero()
+	}
+	fn buy_lease_period() -> Weight {
+		Weight::zero()
+	}
 }
 
 #[frame_support::pallet]
@@ -118,6 +145,20 @@ pub mod pallet {
 	pub type Leases<T: Config> =
 		StorageMap<_, Twox64Concat, ParaId, Vec<Option<(T::AccountId, BalanceOf<T>)>>, ValueQuery>;
 
+	/// Future lease periods offered by their leasers, by para and lease period.
+	///
+	/// Listings follow their lease periods when paras are swapped, and are removed once the
+	/// lease period begins.
+	#[pallet::storage]
+	pub type LeaseListings<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		ParaId,
+		Twox64Concat,
+		LeasePeriodOf<T>,
+		LeaseListing<T::AccountId, BalanceOf<T>>,
+	>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -134,6 +175,26 @@ pub mod pallet {
 			extra_reserved: BalanceOf<T>,
 			total_amount: BalanceOf<T>,
 		},
+		/// A leaser offered a future lease period of a para.
+		LeasePeriodListed {
+			para_id: ParaId,
+			lease_period: LeasePeriodOf<T>,
+			seller: T::AccountId,
+			price: BalanceOf<T>,
+			buyer: Option<T::AccountId>,
+		},
+		/// An offer of a lease period was withdrawn.
+		LeasePeriodUnlisted { para_id: ParaId, lease_period: LeasePeriodOf<T> },
+		/// A lease period was bought and moved to another para. The buyer now holds the deposit
+		/// of the lease period.
+		LeasePeriodSold {
+			para_id: ParaId,
+			lease_period: LeasePeriodOf<T>,
+			new_para_id: ParaId,
+			seller: T::AccountId,
+			buyer: T::AccountId,
+			price: BalanceOf<T>,
+		},
 	}
 
 	#[pallet::error]
@@ -142,6 +203,20 @@ pub mod pallet {
 		ParaNotOnboarding,
 		/// There was an error with the lease.
 		LeaseError,
+		/// The lease period is not in the future.
+		NotFutureLeasePeriod,
+		/// The origin is not the leaser of the lease period.
+		NotLeaser,
+		/// The lease period is not offered.
+		NotListed,
+		/// The lease period is offered to another buyer.
+		NotBuyer,
+		/// The price of the lease period is above the given limit.
+		PriceAboveLimit,
+		/// The para to receive the lease period is not registered.
+		ParaNotRegistered,
+		/// The para to receive the lease period already has a lease for it.
+		AlreadyLeased,
 	}
 
 	#[pallet::hooks]
@@ -197,6 +272,7 @@ pub mod pallet {
 			}
 
 			Leases::<T>::remove(para);
+			let _ = LeaseListings::<T>::clear_prefix(para, u32::MAX, None);
 			Ok(())
 		}
 
@@ -221,6 +297,110 @@ pub mod pallet {
 			};
 			Ok(())
 		}
+
+		/// Offer a future lease period of a para for sale, or for transfer to a given buyer.
+		///
+		/// Replaces any earlier offer of the lease period. The origin must be the leaser of the
+		/// lease period.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::list_lease_period())]
+		pub fn list_lease_period(
+			origin: OriginFor<T>,
+			para: ParaId,
+			lease_period: LeasePeriodOf<T>,
+			price: BalanceOf<T>,
+			buyer: Option<T::AccountId>,
+		) -> DispatchResult {
+			let seller = ensure_signed(origin)?;
+			let offset = Self::future_lease_offset(lease_period)?;
+			match Leases::<T>::get(para).get(offset) {
+				Some(Some((leaser, _))) if leaser == &seller => {},
+				_ => return Err(Error::<T>::NotLeaser.into()),
+			}
+
+			let listing = LeaseListing { seller: seller.clone(), price, buyer: buyer.clone() };
+			LeaseListings::<T>::insert(para, lease_period, listing);
+			Self::deposit_event(Event::<T>::LeasePeriodListed {
+				para_id: para,
+				lease_period,
+				seller,
+				price,
+				buyer,
+			});
+			Ok(())
+		}
+
+		/// Withdraw the offer of a lease period.
+		///
+		/// The origin must be the seller of the lease period.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::unlist_lease_period())]
+		pub fn unlist_lease_period(
+			origin: OriginFor<T>,
+			para: ParaId,
+			lease_period: LeasePeriodOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let listing =
+				LeaseListings::<T>::get(para, lease_period).ok_or(Error::<T>::NotListed)?;
+			ensure!(listing.seller == who, Error::<T>::NotLeaser);
+
+			LeaseListings::<T>::remove(para, lease_period);
+			Self::deposit_event(Event::<T>::LeasePeriodUnlisted { para_id: para, lease_period });
+			Ok(())
+		}
+
+		/// Buy an offered lease period of `para` and move it to `new_para`.
+		///
+		/// The price is paid to the seller, and the deposit of the lease period is reserved from
+		/// the buyer and released to the seller. The buyer becomes the leaser of the lease
+		/// period. `new_para` is onboarded as usual once the lease period begins.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::buy_lease_period())]
+		pub fn buy_lease_period(
+			origin: OriginFor<T>,
+			para: ParaId,
+			lease_period: LeasePeriodOf<T>,
+			new_para: ParaId,
+			max_price: BalanceOf<T>,
+		) -> DispatchResult {
+			let buyer = ensure_signed(origin)?;
+			let listing =
+				LeaseListings::<T>::get(para, lease_period).ok_or(Error::<T>::NotListed)?;
+			ensure!(
+				listing.buyer.as_ref().map_or(true, |allowed| allowed == &buyer),
+				Error::<T>::NotBuyer,
+			);
+			ensure!(listing.price <= max_price, Error::<T>::PriceAboveLimit);
+			ensure!(T::Registrar::is_registered(new_para), Error::<T>::ParaNotRegistered);
+			ensure!(
+				!Self::already_leased(new_para, lease_period, lease_period),
+				Error::<T>::AlreadyLeased,
+			);
+
+			let offset = Self::future_lease_offset(lease_period)?;
+			let deposit = Self::take_lease_period(para, offset, &listing.seller)?;
+			LeaseListings::<T>::remove(para, lease_period);
+
+			T::Currency::transfer(
+				&buyer,
+				&listing.seller,
+				listing.price,
+				ExistenceRequirement::KeepAlive,
+			)?;
+			Self::lease_out(new_para, &buyer, deposit, lease_period, One::one())
+				.map_err(|_| Error::<T>::LeaseError)?;
+
+			Self::deposit_event(Event::<T>::LeasePeriodSold {
+				para_id: para,
+				lease_period,
+				new_para_id: new_para,
+				seller: listing.seller,
+				buyer,
+				price: listing.price,
+			});
+			Ok(())
+		}
 	}
 }
 
@@ -242,6 +422,9 @@ impl<T: Config> Pallet<T> {
 			}
 			// ^^ should never be empty since we would have deleted the entry otherwise.
 
+			// The lease period is no longer in the future, so it can not be sold anymore.
+			LeaseListings::<T>::remove(para, lease_period_index);
+
 			if lease_periods.len() == 1 {
 				// Just one entry, which corresponds to the now-ended lease period.
 				//
@@ -326,11 +509,65 @@ impl<T: Config> Pallet<T> {
 
 		tracker.into_iter().collect()
 	}
+
+	// Return the index into `Leases` of the given lease period, if it lies in the future.
+	fn future_lease_offset(lease_period: LeasePeriodOf<T>) -> Result<usize, Error<T>> {
+		let now = frame_system::Pallet::<T>::block_number();
+		let (current_lease_period, _) =
+			Self::lease_period_index(now).ok_or(Error::<T>::NotFutureLeasePeriod)?;
+		lease_period
+			.checked_sub(&current_lease_period)
+			.filter(|offset| !offset.is_zero())
+			.and_then(|offset| offset.checked_into::<usize>())
+			.ok_or(Error::<T>::NotFutureLeasePeriod)
+	}
+
+	// Remove the lease of `leaser` at `offset` from the leases of `para`, and unreserve the part
+	// of the deposit of `leaser` which is no longer needed. Returns the deposit of the removed
+	// lease.
+	fn take_lease_period(
+		para: ParaId,
+		offset: usize,
+		leaser: &T::AccountId,
+	) -> Result<BalanceOf<T>, Error<T>> {
+		let mut leases = Leases::<T>::get(para);
+		let deposit = match leases.get(offset) {
+			Some(Some((who, deposit))) if who == leaser => *deposit,
+			_ => return Err(Error::<T>::NotLeaser),
+		};
+		let held = Self::deposit_held(para, leaser);
+
+		leases[offset] = None;
+		// It is illegal for a `None` value to trail in the list.
+		while leases.last() == Some(&None) {
+			leases.pop();
+		}
+		if leases.is_empty() {
+			Leases::<T>::remove(para);
+		} else {
+			Leases::<T>::insert(para, leases);
+		}
+
+		let now_held = Self::deposit_held(para, leaser);
+		T::Currency::unreserve(leaser, held.saturating_sub(now_held));
+
+		Ok(deposit)
+	}
 }
 
 impl<T: Config> crate::traits::OnSwap for Pallet<T> {
 	fn on_swap(one: ParaId, other: ParaId) {
-		Leases::<T>::mutate(one, |x| Leases::<T>::mutate(other, |y| sp_std::mem::swap(x, y)))
+		Leases::<T>::mutate(one, |x| Leases::<T>::mutate(other, |y| sp_std::mem::swap(x, y)));
+
+		// Listings follow the lease periods they offer.
+		let one_listings = LeaseListings::<T>::drain_prefix(one).collect::<Vec<_>>();
+		let other_listings = LeaseListings::<T>::drain_prefix(other).collect::<Vec<_>>();
+		for (lease_period, listing) in one_listings {
+			LeaseListings::<T>::insert(other, lease_period, listing);
+		}
+		for (lease_period, listing) in other_listings {
+			LeaseListings::<T>::insert(one, lease_period, listing);
+		}
 	}
 }
 
@@ -948,6 +1185,182 @@ mod tests {
 		});
 	}
 
+	fn register_paras(paras: &[u32]) {
+		for para in paras {
+			assert_ok!(TestRegistrar::<Test>::register(
+				1,
+				ParaId::from(*para),
+				dummy_head_data(),
+				dummy_validation_code()
+			));
+		}
+	}
+
+	#[test]
+	fn lease_period_sale_works() {
+		new_test_ext().execute_with(|| {
+			run_to_block(1);
+			register_paras(&[1, 2]);
+
+			assert_ok!(Slots::lease_out(1.into(), &1, 6, 1, 3));
+			assert_eq!(Balances::reserved_balance(1), 6);
+
+			// Only future lease periods of the leaser can be listed.
+			assert_noop!(
+				Slots::list_lease_period(RuntimeOrigin::signed(1), 1.into(), 0, 5, None),
+				Error::<Test>::NotFutureLeasePeriod,
+			);
+			assert_noop!(
+				Slots::list_lease_period(RuntimeOrigin::signed(2), 1.into(), 2, 5, None),
+				Error::<Test>::NotLeaser,
+			);
+			assert_noop!(
+				Slots::list_lease_period(RuntimeOrigin::signed(1), 1.into(), 4, 5, None),
+				Error::<Test>::NotLeaser,
+			);
+			assert_ok!(Slots::list_lease_period(RuntimeOrigin::signed(1), 1.into(), 2, 5, None));
+			assert_eq!(
+				LeaseListings::<Test>::get(ParaId::from(1_u32), 2),
+				Some(LeaseListing { seller: 1, price: 5, buyer: None }),
+			);
+
+			assert_noop!(
+				Slots::buy_lease_period(RuntimeOrigin::signed(3), 1.into(), 2, 2.into(), 4),
+				Error::<Test>::PriceAboveLimit,
+			);
+			assert_noop!(
+				Slots::buy_lease_period(RuntimeOrigin::signed(3), 1.into(), 2, 9.into(), 5),
+				Error::<Test>::ParaNotRegistered,
+			);
+			assert_noop!(
+				Slots::buy_lease_period(RuntimeOrigin::signed(3), 1.into(), 2, 1.into(), 5),
+				Error::<Test>::AlreadyLeased,
+			);
+			assert_ok!(Slots::buy_lease_period(RuntimeOrigin::signed(3), 1.into(), 2, 2.into(), 5));
+			System::assert_last_event(
+				Event::<Test>::LeasePeriodSold {
+					para_id: 1.into(),
+					lease_period: 2,
+					new_para_id: 2.into(),
+					seller: 1,
+					buyer: 3,
+					price: 5,
+				}
+				.into(),
+			);
+
+			// The lease period moved to para 2, and its deposit to the buyer.
+			assert_eq!(
+				Leases::<Test>::get(ParaId::from(1_u32)),
+				vec![None, Some((1, 6)), None, Some((1, 6))],
+			);
+			assert_eq!(Leases::<Test>::get(ParaId::from(2_u32)), vec![None, None, Some((3, 6))]);
+			assert!(LeaseListings::<Test>::get(ParaId::from(1_u32), 2).is_none());
+			assert_eq!(Balances::reserved_balance(1), 6);
+			assert_eq!(Balances::free_balance(1), 10 - 6 + 5);
+			assert_eq!(Balances::reserved_balance(3), 6);
+			assert_eq!(Balances::free_balance(3), 30 - 6 - 5);
+
+			// Para 2 can not be onboarded before its lease period begins.
+			run_to_block(15);
+			assert_noop!(
+				Slots::trigger_onboard(RuntimeOrigin::signed(1), 2.into()),
+				Error::<Test>::ParaNotOnboarding,
+			);
+
+			// Para 1 is offboarded for the lease period it sold, and can not be onboarded again.
+			run_to_block(20);
+			assert_noop!(
+				Slots::trigger_onboard(RuntimeOrigin::signed(1), 1.into()),
+				Error::<Test>::ParaNotOnboarding,
+			);
+			assert!(Slots::trigger_onboard(RuntimeOrigin::signed(1), 2.into()).is_err());
+
+			run_to_block(40);
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(Balances::reserved_balance(3), 0);
+			assert_eq!(
+				TestRegistrar::<Test>::operations(),
+				vec![
+					(1.into(), 10, true),
+					(2.into(), 20, true),
+					(1.into(), 20, false),
+					(1.into(), 30, true),
+					(2.into(), 30, false),
+					(1.into(), 40, false),
+				]
+			);
+		});
+	}
+
+	#[test]
+	fn lease_period_transfer_and_unlisting_work() {
+		new_test_ext().execute_with(|| {
+			run_to_block(1);
+			register_paras(&[1, 2]);
+
+			assert_ok!(Slots::lease_out(1.into(), &1, 6, 1, 2));
+			assert_ok!(Slots::list_lease_period(RuntimeOrigin::signed(1), 1.into(), 2, 0, Some(2)));
+
+			// Only the given buyer may take the lease period.
+			assert_noop!(
+				Slots::buy_lease_period(RuntimeOrigin::signed(3), 1.into(), 2, 2.into(), 0),
+				Error::<Test>::NotBuyer,
+			);
+
+			// Only the seller may withdraw the listing.
+			assert_noop!(
+				Slots::unlist_lease_period(RuntimeOrigin::signed(2), 1.into(), 2),
+				Error::<Test>::NotLeaser,
+			);
+			assert_ok!(Slots::unlist_lease_period(RuntimeOrigin::signed(1), 1.into(), 2));
+			assert_noop!(
+				Slots::buy_lease_period(RuntimeOrigin::signed(2), 1.into(), 2, 2.into(), 0),
+				Error::<Test>::NotListed,
+			);
+
+			assert_ok!(Slots::list_lease_period(RuntimeOrigin::signed(1), 1.into(), 2, 0, Some(2)));
+			assert_ok!(Slots::buy_lease_period(RuntimeOrigin::signed(2), 1.into(), 2, 2.into(), 0));
+			assert_eq!(Leases::<Test>::get(ParaId::from(1_u32)), vec![None, Some((1, 6))]);
+			assert_eq!(Leases::<Test>::get(ParaId::from(2_u32)), vec![None, None, Some((2, 6))]);
+			assert_eq!(Balances::free_balance(1), 10 - 6);
+			assert_eq!(Balances::reserved_balance(2), 6);
+
+			// Listings are removed once their lease period begins.
+			assert_ok!(Slots::list_lease_period(RuntimeOrigin::signed(1), 1.into(), 1, 3, None));
+			run_to_block(10);
+			assert!(LeaseListings::<Test>::get(ParaId::from(1_u32), 1).is_none());
+		});
+	}
+
+	#[test]
+	fn lease_listings_follow_swapped_leases() {
+		new_test_ext().execute_with(|| {
+			run_to_block(1);
+			register_paras(&[1, 2, 3]);
+
+			assert_ok!(Slots::lease_out(1.into(), &1, 6, 1, 2));
+			assert_ok!(Slots::list_lease_period(RuntimeOrigin::signed(1), 1.into(), 2, 1, None));
+
+			<Slots as crate::traits::OnSwap>::on_swap(1.into(), 2.into());
+			assert!(LeaseListings::<Test>::get(ParaId::from(1_u32), 2).is_none());
+			assert!(LeaseListings::<Test>::get(ParaId::from(2_u32), 2).is_some());
+
+			assert_noop!(
+				Slots::buy_lease_period(RuntimeOrigin::signed(3), 1.into(), 2, 3.into(), 1),
+				Error::<Test>::NotListed,
+			);
+			assert_ok!(Slots::buy_lease_period(RuntimeOrigin::signed(3), 2.into(), 2, 3.into(), 1));
+			assert_eq!(Leases::<Test>::get(ParaId::from(2_u32)), vec![None, Some((1, 6))]);
+			assert_eq!(Leases::<Test>::get(ParaId::from(3_u32)), vec![None, None, Some((3, 6))]);
+
+			// Clearing the leases of a para also clears its listings.
+			assert_ok!(Slots::list_lease_period(RuntimeOrigin::signed(1), 2.into(), 1, 1, None));
+			assert_ok!(Slots::clear_all_leases(RuntimeOrigin::root(), 2.into()));
+			assert!(LeaseListings::<Test>::get(ParaId::from(2_u32), 1).is_none());
+		});
+	}
+
 	#[test]
 	fn lease_period_offset_works() {
 		new_test_ext().execute_with(|| {
@@ -1146,6 +1559,76 @@ mod benchmarking {
 			assert!(T::Registrar::is_parachain(para));
 		}
 
+		list_lease_period {
+			// If there is an offset, we need to be on that block to be able to do lease things.
+			frame_system::Pallet::<T>::set_block_number(T::LeaseOffset::get() + One::one());
+			let (para, leaser) = register_a_parathread::<T>(1);
+			let amount = T::Currency::minimum_balance();
+			let lease_period = One::one();
+			Slots::<T>::lease_out(para, &leaser, amount, lease_period, One::one())
+				.map_err(|_| BenchmarkError::Stop("lease failed"))?;
+		}: _(RawOrigin::Signed(leaser.clone()), para, lease_period, amount, Some(leaser.clone()))
+		verify {
+			assert_last_event::<T>(Event::<T>::LeasePeriodListed {
+				para_id: para,
+				lease_period,
+				seller: leaser.clone(),
+				price: amount,
+				buyer: Some(leaser),
+			}.into());
+		}
+
+		unlist_lease_period {
+			// If there is an offset, we need to be on that block to be able to do lease things.
+			frame_system::Pallet::<T>::set_block_number(T::LeaseOffset::get() + One::one());
+			let (para, leaser) = register_a_parathread::<T>(1);
+			let amount = T::Currency::minimum_balance();
+			let lease_period = One::one();
+			Slots::<T>::lease_out(para, &leaser, amount, lease_period, One::one())
+				.map_err(|_| BenchmarkError::Stop("lease failed"))?;
+			Slots::<T>::list_lease_period(
+				RawOrigin::Signed(leaser.clone()).into(),
+				para,
+				lease_period,
+				amount,
+				None,
+			)?;
+		}: _(RawOrigin::Signed(leaser), para, lease_period)
+		verify {
+			assert_last_event::<T>(Event::<T>::LeasePeriodUnlisted {
+				para_id: para,
+				lease_period,
+			}.into());
+		}
+
+		buy_lease_period {
+			// If there is an offset, we need to be on that block to be able to do lease things.
+			frame_system::Pallet::<T>::set_block_number(T::LeaseOffset::get() + One::one());
+			let (para, seller) = register_a_parathread::<T>(1);
+			let (new_para, buyer) = register_a_parathread::<T>(2);
+			let amount = T::Currency::minimum_balance();
+			let lease_period = One::one();
+			Slots::<T>::lease_out(para, &seller, amount, lease_period, One::one())
+				.map_err(|_| BenchmarkError::Stop("lease failed"))?;
+			Slots::<T>::list_lease_period(
+				RawOrigin::Signed(seller.clone()).into(),
+				para,
+				lease_period,
+				amount,
+				None,
+			)?;
+		}: _(RawOrigin::Signed(buyer.clone()), para, lease_period, new_para, amount)
+		verify {
+			assert_last_event::<T>(Event::<T>::LeasePeriodSold {
+				para_id: para,
+				lease_period,
+				new_para_id: new_para,
+				seller,
+				buyer,
+				price: amount,
+			}.into());
+		}
+
 		impl_benchmark_test_suite!(
 			Slots,
 			crate::integration_tests::new_test_ext(),
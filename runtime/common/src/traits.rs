@@ -248,6 +248,18 @@ pub trait Auctioneer<BlockNumber> {
 
 	/// Check if the para and user combination has won an auction in the past.
 	fn has_won_an_auction(para: ParaId, bidder: &Self::AccountId) -> bool;
+
+	/// Lease the range of lease periods from `first_slot` to `last_slot` (inclusive) to `bidder`
+	/// for `para`, as if it had won them in an auction with a bid of `amount`.
+	/// This is only used in benchmarking to automate certain calls.
+	#[cfg(any(feature = "runtime-benchmarks", test))]
+	fn win_auction(
+		bidder: &Self::AccountId,
+		para: ParaId,
+		first_slot: Self::LeasePeriod,
+		last_slot: Self::LeasePeriod,
+		amount: <Self::Currency as Currency<Self::AccountId>>::Balance,
+	) -> DispatchResult;
 }
 
 /// Runtime hook for when we swap a parachain and parathread.
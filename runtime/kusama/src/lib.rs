@@ -30,9 +30,9 @@ use primitives::{
 	LOWEST_PUBLIC_ID,
 };
 use runtime_common::{
-	auctions, claims, crowdloan, impl_runtime_weights, impls::DealWithFees, paras_registrar,
-	prod_or_fast, slots, BalanceToU256, BlockHashCount, BlockLength, CurrencyToVote,
-	SlowAdjustingFeeUpdate, U256ToBalance,
+	auctions, claims, crowdloan, impl_runtime_weights, impls::DealWithFees, para_deposits,
+	paras_registrar, prod_or_fast, slots, BalanceToU256, BlockHashCount, BlockLength,
+	CurrencyToVote, SlowAdjustingFeeUpdate, U256ToBalance,
 };
 use sp_std::{cmp::Ordering, collections::btree_map::BTreeMap, prelude::*};
 
@@ -819,6 +819,8 @@ impl claims::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type VestingSchedule = Vesting;
 	type Prefix = Prefix;
+	type Address = claims::EthereumAddress;
+	type Verifier = claims::EthereumPersonalSign;
 	type MoveClaimOrigin = EnsureRoot<AccountId>;
 	type WeightInfo = weights::runtime_common_claims::WeightInfo<Runtime>;
 }
@@ -1124,6 +1126,7 @@ impl parachains_inclusion::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type DisputesHandler = ParasDisputes;
 	type RewardValidators = parachains_reward_points::RewardValidatorsWithEraPoints<Runtime>;
+	type RecordPerformance = ();
 }
 
 parameter_types! {
@@ -1132,6 +1135,7 @@ parameter_types! {
 
 impl parachains_paras::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
 	type WeightInfo = weights::runtime_parachains_paras::WeightInfo<Runtime>;
 	type UnsignedPriority = ParasUnsignedPriority;
 	type NextSessionRotation = Babe;
@@ -1139,6 +1143,7 @@ impl parachains_paras::Config for Runtime {
 
 parameter_types! {
 	pub const FirstMessageFactorPercent: u64 = 100;
+	pub const UmpMaxPageSize: u32 = 64 * 1024;
 }
 
 impl parachains_ump::Config for Runtime {
@@ -1147,6 +1152,7 @@ impl parachains_ump::Config for Runtime {
 		crate::parachains_ump::XcmSink<xcm_executor::XcmExecutor<xcm_config::XcmConfig>, Runtime>;
 	type FirstMessageFactorPercent = FirstMessageFactorPercent;
 	type ExecuteOverweightOrigin = EnsureRoot<AccountId>;
+	type MaxPageSize = UmpMaxPageSize;
 	type WeightInfo = weights::runtime_parachains_ump::WeightInfo<Runtime>;
 }
 
@@ -1156,6 +1162,7 @@ impl parachains_hrmp::Config for Runtime {
 	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
+	type SystemParas = parachains_hrmp::IsSystemPara;
 	type WeightInfo = weights::runtime_parachains_hrmp::WeightInfo<Runtime>;
 }
 
@@ -1163,7 +1170,9 @@ impl parachains_paras_inherent::Config for Runtime {
 	type WeightInfo = weights::runtime_parachains_paras_inherent::WeightInfo<Runtime>;
 }
 
-impl parachains_scheduler::Config for Runtime {}
+impl parachains_scheduler::Config for Runtime {
+	type OnParathreadClaim = ();
+}
 
 impl parachains_initializer::Config for Runtime {
 	type Randomness = pallet_babe::RandomnessFromOneEpochAgo<Runtime>;
@@ -1175,6 +1184,7 @@ impl parachains_disputes::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type RewardValidators = parachains_reward_points::RewardValidatorsWithEraPoints<Runtime>;
 	type SlashingHandler = ();
+	type RecordPerformance = ();
 	type WeightInfo = weights::runtime_parachains_disputes::WeightInfo<Runtime>;
 }
 
@@ -1234,6 +1244,8 @@ parameter_types! {
 	pub const EndingPeriod: BlockNumber = 5 * DAYS;
 	// ~ 1000 samples per day -> ~ 20 blocks per sample -> 2 minute samples
 	pub const SampleLength: BlockNumber = 2 * MINUTES;
+	// Keep the winner selection of the last few auctions around for verification.
+	pub const MaxAuctionAudits: u32 = 16;
 }
 
 impl auctions::Config for Runtime {
@@ -1243,6 +1255,7 @@ impl auctions::Config for Runtime {
 	type EndingPeriod = EndingPeriod;
 	type SampleLength = SampleLength;
 	type Randomness = pallet_babe::RandomnessFromOneEpochAgo<Runtime>;
+	type MaxAuctionAudits = MaxAuctionAudits;
 	type InitiateOrigin = EitherOf<EnsureRoot<Self::AccountId>, AuctionAdmin>;
 	type WeightInfo = weights::runtime_common_auctions::WeightInfo<Runtime>;
 }
@@ -1485,7 +1498,13 @@ impl Get<&'static str> for StakingMigrationV11OldPallet {
 /// All migrations that will run on the next runtime upgrade.
 ///
 /// Should be cleared after every release.
-pub type Migrations = ();
+pub type Migrations = (
+	parachains_ump::migration::v2::MigrateToV2<Runtime>,
+	parachains_dmp::migration::v1::MigrateToV1<Runtime>,
+	parachains_configuration::migration::v5::MigrateToV5<Runtime>,
+	parachains_scheduler::migration::v1::MigrateToV1<Runtime>,
+	claims::migration::v1::MigrateToV1<Runtime>,
+);
 
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic =
@@ -1892,6 +1911,22 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl auctions::AuctionsApi<Block, AccountId, Balance, BlockNumber, Hash> for Runtime {
+		fn auction_audit(
+			auction_index: auctions::AuctionIndex,
+		) -> Option<auctions::AuctionAudit<AccountId, Balance, BlockNumber, Hash>> {
+			Auctions::auction_audit(auction_index)
+		}
+	}
+
+	impl para_deposits::ParaDepositsApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+		fn para_deposits(
+			para: ParaId,
+		) -> para_deposits::ParaDeposits<AccountId, Balance, BlockNumber> {
+			para_deposits::para_deposits::<Runtime>(para)
+		}
+	}
+
 	impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Nonce> for Runtime {
 		fn account_nonce(account: AccountId) -> Nonce {
 			System::account_nonce(account)
@@ -20,6 +20,9 @@
 //! WORST CASE MAP SIZE: `1000000`
 //! HOSTNAME: `bm5`, CPU: `Intel(R) Core(TM) i7-7700K CPU @ 4.20GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("kusama-dev"), DB CACHE: 1024
+//!
+//! NOTE: `claim_with_proof` is estimated from its storage accesses, pending the next run of
+//! the benchmark CLI on reference hardware.
 
 // Executed Command:
 // ./target/production/polkadot
@@ -155,4 +158,24 @@ impl<T: frame_system::Config> runtime_common::claims::WeightInfo for WeightInfo<
 			.saturating_add(T::DbWeight::get().reads(4))
 			.saturating_add(T::DbWeight::get().writes(7))
 	}
+	/// Storage: Claims Claims (r:1 w:1)
+	/// Proof Skipped: Claims Claims (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Claims Signing (r:1 w:1)
+	/// Proof Skipped: Claims Signing (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Claims Total (r:1 w:1)
+	/// Proof Skipped: Claims Total (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Claims Vesting (r:1 w:1)
+	/// Proof Skipped: Claims Vesting (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Vesting Vesting (r:1 w:1)
+	/// Proof: Vesting Vesting (max_values: None, max_size: Some(1057), added: 3532, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:0)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Balances Locks (r:1 w:1)
+	/// Proof: Balances Locks (max_values: None, max_size: Some(1299), added: 3774, mode: MaxEncodedLen)
+	fn claim_with_proof() -> Weight {
+		Weight::from_ref_time(158_238_000)
+			.saturating_add(Weight::from_proof_size(20437))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
 }
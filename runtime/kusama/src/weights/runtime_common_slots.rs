@@ -20,6 +20,9 @@
 //! WORST CASE MAP SIZE: `1000000`
 //! HOSTNAME: `bm5`, CPU: `Intel(R) Core(TM) i7-7700K CPU @ 4.20GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("kusama-dev"), DB CACHE: 1024
+//!
+//! NOTE: `list_lease_period`, `unlist_lease_period` and `buy_lease_period` are estimated from their
+//! storage accesses, pending the next run of the benchmark CLI on reference hardware.
 
 // Executed Command:
 // ./target/production/polkadot
@@ -127,4 +130,36 @@ impl<T: frame_system::Config> runtime_common::slots::WeightInfo for WeightInfo<T
 			.saturating_add(T::DbWeight::get().reads(5))
 			.saturating_add(T::DbWeight::get().writes(3))
 	}
+	/// Storage: Slots Leases (r:1 w:0)
+	/// Proof Skipped: Slots Leases (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Slots LeaseListings (r:0 w:1)
+	/// Proof Skipped: Slots LeaseListings (max_values: None, max_size: None, mode: Measured)
+	fn list_lease_period() -> Weight {
+		Weight::from_ref_time(15_316_000)
+			.saturating_add(Weight::from_proof_size(2727))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: Slots LeaseListings (r:1 w:1)
+	/// Proof Skipped: Slots LeaseListings (max_values: None, max_size: None, mode: Measured)
+	fn unlist_lease_period() -> Weight {
+		Weight::from_ref_time(13_978_000)
+			.saturating_add(Weight::from_proof_size(2653))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: Slots LeaseListings (r:1 w:1)
+	/// Proof Skipped: Slots LeaseListings (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Registrar Paras (r:1 w:0)
+	/// Proof Skipped: Registrar Paras (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Slots Leases (r:3 w:2)
+	/// Proof Skipped: Slots Leases (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn buy_lease_period() -> Weight {
+		Weight::from_ref_time(58_845_000)
+			.saturating_add(Weight::from_proof_size(20846))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
 }
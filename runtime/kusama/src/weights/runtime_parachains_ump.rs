@@ -20,6 +20,9 @@
 //! WORST CASE MAP SIZE: `1000000`
 //! HOSTNAME: `bm5`, CPU: `Intel(R) Core(TM) i7-7700K CPU @ 4.20GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("kusama-dev"), DB CACHE: 1024
+//!
+//! NOTE: `resume_overweight_page`, `receive_upward_messages` and `service_page` are estimated
+//! from their storage accesses, pending the next run of the benchmark CLI on reference hardware.
 
 // Executed Command:
 // ./target/production/polkadot
@@ -78,14 +81,41 @@ impl<T: frame_system::Config> runtime_parachains::ump::WeightInfo for WeightInfo
 	/// Proof Skipped: Ump Overweight (max_values: None, max_size: None, mode: Measured)
 	/// Storage: Ump CounterForOverweight (r:1 w:1)
 	/// Proof: Ump CounterForOverweight (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn service_overweight() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `257`
-		//  Estimated: `3231`
-		// Minimum execution time: 22_080 nanoseconds.
-		Weight::from_ref_time(22_520_000)
-			.saturating_add(Weight::from_proof_size(3231))
-			.saturating_add(T::DbWeight::get().reads(2))
-			.saturating_add(T::DbWeight::get().writes(2))
+	/// Storage: Ump Books (r:1 w:1)
+	/// Proof Skipped: Ump Books (max_values: None, max_size: None, mode: Measured)
+	fn resume_overweight_page() -> Weight {
+		Weight::from_ref_time(30_000_000)
+			.saturating_add(Weight::from_proof_size(72_000))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// Storage: Ump Books (r:1 w:1)
+	/// Proof Skipped: Ump Books (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Ump Pages (r:0 w:64)
+	/// Proof Skipped: Ump Pages (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Ump RelayDispatchQueueSize (r:1 w:1)
+	/// Proof Skipped: Ump RelayDispatchQueueSize (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Ump NeedsDispatch (r:1 w:1)
+	/// Proof Skipped: Ump NeedsDispatch (max_values: Some(1), max_size: None, mode: Measured)
+	/// The range of component `p` is `[1, 64]`.
+	fn receive_upward_messages(p: u32, ) -> Weight {
+		Weight::from_ref_time(12_000_000)
+			.saturating_add(Weight::from_proof_size(1_500))
+			.saturating_add(Weight::from_ref_time(4_000_000).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(p.into())))
+	}
+	/// Storage: Ump Books (r:1 w:1)
+	/// Proof Skipped: Ump Books (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Ump RelayDispatchQueueSize (r:1 w:1)
+	/// Proof Skipped: Ump RelayDispatchQueueSize (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Ump Pages (r:1 w:1)
+	/// Proof Skipped: Ump Pages (max_values: None, max_size: None, mode: Measured)
+	fn service_page() -> Weight {
+		Weight::from_ref_time(20_000_000)
+			.saturating_add(Weight::from_proof_size(70_000))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
 	}
 }
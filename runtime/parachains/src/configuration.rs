@@ -24,6 +24,7 @@ use frame_system::pallet_prelude::*;
 use parity_scale_codec::{Decode, Encode};
 use polkadot_parachain::primitives::{MAX_HORIZONTAL_MESSAGE_NUM, MAX_UPWARD_MESSAGE_NUM};
 use primitives::{Balance, SessionIndex, MAX_CODE_SIZE, MAX_HEAD_DATA_SIZE, MAX_POV_SIZE};
+pub use primitives::vstaging::{HostConfigurationDiff, InconsistentError};
 use sp_runtime::traits::Zero;
 use sp_std::prelude::*;
 
@@ -294,47 +295,6 @@ impl<BlockNumber: Default + From<u32>> Default for HostConfiguration<BlockNumber
 	}
 }
 
-/// Enumerates the possible inconsistencies of `HostConfiguration`.
-#[derive(Debug)]
-pub enum InconsistentError<BlockNumber> {
-	/// `group_rotation_frequency` is set to zero.
-	ZeroGroupRotationFrequency,
-	/// `chain_availability_period` is set to zero.
-	ZeroChainAvailabilityPeriod,
-	/// `thread_availability_period` is set to zero.
-	ZeroThreadAvailabilityPeriod,
-	/// `no_show_slots` is set to zero.
-	ZeroNoShowSlots,
-	/// `max_code_size` exceeds the hard limit of `MAX_CODE_SIZE`.
-	MaxCodeSizeExceedHardLimit { max_code_size: u32 },
-	/// `max_head_data_size` exceeds the hard limit of `MAX_HEAD_DATA_SIZE`.
-	MaxHeadDataSizeExceedHardLimit { max_head_data_size: u32 },
-	/// `max_pov_size` exceeds the hard limit of `MAX_POV_SIZE`.
-	MaxPovSizeExceedHardLimit { max_pov_size: u32 },
-	/// `minimum_validation_upgrade_delay` is less than `chain_availability_period`.
-	MinimumValidationUpgradeDelayLessThanChainAvailabilityPeriod {
-		minimum_validation_upgrade_delay: BlockNumber,
-		chain_availability_period: BlockNumber,
-	},
-	/// `minimum_validation_upgrade_delay` is less than `thread_availability_period`.
-	MinimumValidationUpgradeDelayLessThanThreadAvailabilityPeriod {
-		minimum_validation_upgrade_delay: BlockNumber,
-		thread_availability_period: BlockNumber,
-	},
-	/// `validation_upgrade_delay` is less than or equal 1.
-	ValidationUpgradeDelayIsTooLow { validation_upgrade_delay: BlockNumber },
-	/// Maximum UMP message size ([`MAX_UPWARD_MESSAGE_SIZE_BOUND`]) exceeded.
-	MaxUpwardMessageSizeExceeded { max_message_size: u32 },
-	/// Maximum HRMP message num ([`MAX_HORIZONTAL_MESSAGE_NUM`]) exceeded.
-	MaxHorizontalMessageNumExceeded { max_message_num: u32 },
-	/// Maximum UMP message num ([`MAX_UPWARD_MESSAGE_NUM`]) exceeded.
-	MaxUpwardMessageNumExceeded { max_message_num: u32 },
-	/// Maximum number of HRMP outbound channels exceeded.
-	MaxHrmpOutboundChannelsExceeded,
-	/// Maximum number of HRMP inbound channels exceeded.
-	MaxHrmpInboundChannelsExceeded,
-}
-
 impl<BlockNumber> HostConfiguration<BlockNumber>
 where
 	BlockNumber: Zero + PartialOrd + sp_std::fmt::Debug + Clone + From<u32>,
@@ -343,86 +303,97 @@ where
 	///
 	/// # Errors
 	///
-	/// This function returns an error if the configuration is inconsistent.
+	/// This function returns the first inconsistency found if the configuration is inconsistent.
 	pub fn check_consistency(&self) -> Result<(), InconsistentError<BlockNumber>> {
+		match self.consistency_errors().into_iter().next() {
+			Some(err) => Err(err),
+			None => Ok(()),
+		}
+	}
+
+	/// Returns every inconsistency of this instance, in the order they are checked by
+	/// [`Self::check_consistency`]. The list is empty if the configuration is consistent.
+	pub fn consistency_errors(&self) -> Vec<InconsistentError<BlockNumber>> {
 		use InconsistentError::*;
 
+		let mut errors = Vec::new();
+
 		if self.group_rotation_frequency.is_zero() {
-			return Err(ZeroGroupRotationFrequency)
+			errors.push(ZeroGroupRotationFrequency);
 		}
 
 		if self.chain_availability_period.is_zero() {
-			return Err(ZeroChainAvailabilityPeriod)
+			errors.push(ZeroChainAvailabilityPeriod);
 		}
 
 		if self.thread_availability_period.is_zero() {
-			return Err(ZeroThreadAvailabilityPeriod)
+			errors.push(ZeroThreadAvailabilityPeriod);
 		}
 
 		if self.no_show_slots.is_zero() {
-			return Err(ZeroNoShowSlots)
+			errors.push(ZeroNoShowSlots);
 		}
 
 		if self.max_code_size > MAX_CODE_SIZE {
-			return Err(MaxCodeSizeExceedHardLimit { max_code_size: self.max_code_size })
+			errors.push(MaxCodeSizeExceedHardLimit { max_code_size: self.max_code_size });
 		}
 
 		if self.max_head_data_size > MAX_HEAD_DATA_SIZE {
-			return Err(MaxHeadDataSizeExceedHardLimit {
+			errors.push(MaxHeadDataSizeExceedHardLimit {
 				max_head_data_size: self.max_head_data_size,
-			})
+			});
 		}
 
 		if self.max_pov_size > MAX_POV_SIZE {
-			return Err(MaxPovSizeExceedHardLimit { max_pov_size: self.max_pov_size })
+			errors.push(MaxPovSizeExceedHardLimit { max_pov_size: self.max_pov_size });
 		}
 
 		if self.minimum_validation_upgrade_delay <= self.chain_availability_period {
-			return Err(MinimumValidationUpgradeDelayLessThanChainAvailabilityPeriod {
+			errors.push(MinimumValidationUpgradeDelayLessThanChainAvailabilityPeriod {
 				minimum_validation_upgrade_delay: self.minimum_validation_upgrade_delay.clone(),
 				chain_availability_period: self.chain_availability_period.clone(),
-			})
+			});
 		} else if self.minimum_validation_upgrade_delay <= self.thread_availability_period {
-			return Err(MinimumValidationUpgradeDelayLessThanThreadAvailabilityPeriod {
+			errors.push(MinimumValidationUpgradeDelayLessThanThreadAvailabilityPeriod {
 				minimum_validation_upgrade_delay: self.minimum_validation_upgrade_delay.clone(),
 				thread_availability_period: self.thread_availability_period.clone(),
-			})
+			});
 		}
 
 		if self.validation_upgrade_delay <= 1.into() {
-			return Err(ValidationUpgradeDelayIsTooLow {
+			errors.push(ValidationUpgradeDelayIsTooLow {
 				validation_upgrade_delay: self.validation_upgrade_delay.clone(),
-			})
+			});
 		}
 
 		if self.max_upward_message_size > crate::ump::MAX_UPWARD_MESSAGE_SIZE_BOUND {
-			return Err(MaxUpwardMessageSizeExceeded {
+			errors.push(MaxUpwardMessageSizeExceeded {
 				max_message_size: self.max_upward_message_size,
-			})
+			});
 		}
 
 		if self.hrmp_max_message_num_per_candidate > MAX_HORIZONTAL_MESSAGE_NUM {
-			return Err(MaxHorizontalMessageNumExceeded {
+			errors.push(MaxHorizontalMessageNumExceeded {
 				max_message_num: self.hrmp_max_message_num_per_candidate,
-			})
+			});
 		}
 
 		if self.max_upward_message_num_per_candidate > MAX_UPWARD_MESSAGE_NUM {
-			return Err(MaxUpwardMessageNumExceeded {
+			errors.push(MaxUpwardMessageNumExceeded {
 				max_message_num: self.max_upward_message_num_per_candidate,
-			})
+			});
 		}
 
 		if self.hrmp_max_parachain_outbound_channels > crate::hrmp::HRMP_MAX_OUTBOUND_CHANNELS_BOUND
 		{
-			return Err(MaxHrmpOutboundChannelsExceeded)
+			errors.push(MaxHrmpOutboundChannelsExceeded);
 		}
 
 		if self.hrmp_max_parachain_inbound_channels > crate::hrmp::HRMP_MAX_INBOUND_CHANNELS_BOUND {
-			return Err(MaxHrmpInboundChannelsExceeded)
+			errors.push(MaxHrmpInboundChannelsExceeded);
 		}
 
-		Ok(())
+		errors
 	}
 
 	/// Checks that this instance is consistent with the requirements on each individual member.
@@ -437,6 +408,72 @@ where
 	}
 }
 
+/// Overwrites the fields of `$config` which are set in the `HostConfigurationDiff` `$diff`.
+///
+/// The diff is destructured, so a field missing from the list fails to compile.
+macro_rules! apply_diff {
+	($config:expr, $diff:expr, $($field:ident),* $(,)?) => {{
+		let HostConfigurationDiff { $($field),* } = $diff;
+		$(
+			if let Some(new) = $field {
+				$config.$field = new;
+			}
+		)*
+	}};
+}
+
+impl<BlockNumber> HostConfiguration<BlockNumber> {
+	/// Applies all changes of the given diff to this instance.
+	pub fn apply_diff(&mut self, diff: HostConfigurationDiff<BlockNumber>) {
+		apply_diff!(
+			self,
+			diff,
+			max_code_size,
+			max_head_data_size,
+			max_upward_queue_count,
+			max_upward_queue_size,
+			max_upward_message_size,
+			max_upward_message_num_per_candidate,
+			hrmp_max_message_num_per_candidate,
+			validation_upgrade_cooldown,
+			validation_upgrade_delay,
+			max_pov_size,
+			max_downward_message_size,
+			ump_service_total_weight,
+			hrmp_max_parachain_outbound_channels,
+			hrmp_max_parathread_outbound_channels,
+			hrmp_sender_deposit,
+			hrmp_recipient_deposit,
+			hrmp_channel_max_capacity,
+			hrmp_channel_max_total_size,
+			hrmp_max_parachain_inbound_channels,
+			hrmp_max_parathread_inbound_channels,
+			hrmp_channel_max_message_size,
+			code_retention_period,
+			parathread_cores,
+			parathread_retries,
+			group_rotation_frequency,
+			chain_availability_period,
+			thread_availability_period,
+			scheduling_lookahead,
+			max_validators_per_core,
+			max_validators,
+			dispute_period,
+			dispute_post_conclusion_acceptance_period,
+			dispute_conclusion_by_time_out_period,
+			no_show_slots,
+			n_delay_tranches,
+			zeroth_delay_tranche_width,
+			needed_approvals,
+			relay_vrf_modulo_samples,
+			ump_max_individual_weight,
+			pvf_checking_enabled,
+			pvf_voting_ttl,
+			minimum_validation_upgrade_delay,
+		);
+	}
+}
+
 pub trait WeightInfo {
 	fn set_config_with_block_number() -> Weight;
 	fn set_config_with_u32() -> Weight;
@@ -444,6 +481,7 @@ pub trait WeightInfo {
 	fn set_config_with_weight() -> Weight;
 	fn set_config_with_balance() -> Weight;
 	fn set_hrmp_open_request_ttl() -> Weight;
+	fn set_config_diff() -> Weight;
 }
 
 pub struct TestWeightInfo;
@@ -466,6 +504,9 @@ impl WeightInfo for TestWeightInfo {
 	fn set_hrmp_open_request_ttl() -> Weight {
 		Weight::MAX
 	}
+	fn set_config_diff() -> Weight {
+		Weight::MAX
+	}
 }
 
 #[frame_support::pallet]
@@ -512,6 +553,28 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(crate) type BypassConsistencyCheck<T: Config> = StorageValue<_, bool, ValueQuery>;
 
+	/// The version of the active configuration.
+	///
+	/// The version is bumped each time a pending configuration becomes active. The genesis
+	/// configuration has version 0.
+	#[pallet::storage]
+	#[pallet::getter(fn active_config_version)]
+	pub(crate) type ActiveConfigVersion<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The session in which each configuration version became active.
+	///
+	/// Only the versions which were active in one of the sessions kept by `session_info` are
+	/// recorded, starting at `EarliestStoredConfigVersion`. The version that was active when this
+	/// record was introduced is recorded with the session of the migration.
+	#[pallet::storage]
+	#[pallet::getter(fn config_activation_session)]
+	pub(crate) type ConfigActivationSessions<T: Config> =
+		StorageMap<_, Twox64Concat, u32, SessionIndex, OptionQuery>;
+
+	/// The earliest configuration version with an entry in `ConfigActivationSessions`.
+	#[pallet::storage]
+	pub(crate) type EarliestStoredConfigVersion<T: Config> = StorageValue<_, u32, ValueQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		pub config: HostConfiguration<T::BlockNumber>,
@@ -529,6 +592,7 @@ pub mod pallet {
 		fn build(&self) {
 			self.config.panic_if_not_consistent();
 			ActiveConfig::<T>::put(&self.config);
+			ConfigActivationSessions::<T>::insert(0, 0);
 		}
 	}
 
@@ -1158,6 +1222,26 @@ pub mod pallet {
 			<Self as Store>::BypassConsistencyCheck::put(new);
 			Ok(())
 		}
+
+		/// Set all fields of the given diff at once.
+		///
+		/// The consistency check is done once for the configuration with all changes applied, so
+		/// changes that are only consistent together can be made. Fails without changing anything
+		/// if the resulting configuration is inconsistent.
+		#[pallet::call_index(45)]
+		#[pallet::weight((
+			T::WeightInfo::set_config_diff(),
+			DispatchClass::Operational,
+		))]
+		pub fn set_config_diff(
+			origin: OriginFor<T>,
+			diff: HostConfigurationDiff<T::BlockNumber>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::schedule_config_update(|config| {
+				config.apply_diff(diff);
+			})
+		}
 	}
 
 	#[pallet::hooks]
@@ -1204,6 +1288,7 @@ impl<T: Config> Pallet<T> {
 
 		// No pending configuration changes, so we're done.
 		if pending_configs.is_empty() {
+			Self::prune_config_activation_sessions(*session_index);
 			return SessionChangeOutcome { prev_config, new_config: None }
 		}
 
@@ -1224,13 +1309,42 @@ impl<T: Config> Pallet<T> {
 		if let Some(ref new_config) = new_config {
 			// Apply the new configuration.
 			<Self as Store>::ActiveConfig::put(new_config);
+
+			let version = <Self as Store>::ActiveConfigVersion::mutate(|version| {
+				*version = version.saturating_add(1);
+				*version
+			});
+			<Self as Store>::ConfigActivationSessions::insert(version, *session_index);
 		}
 
 		<PendingConfigs<T>>::put(future);
+		Self::prune_config_activation_sessions(*session_index);
 
 		SessionChangeOutcome { prev_config, new_config }
 	}
 
+	/// Remove the activation sessions of the configuration versions which were not active in any
+	/// of the sessions kept by `session_info`, i.e. the last `dispute_period` sessions.
+	fn prune_config_activation_sessions(session_index: SessionIndex) {
+		let dispute_period = <Self as Store>::ActiveConfig::get().dispute_period;
+		let earliest_kept_session = session_index.saturating_sub(dispute_period);
+		let active_version = <Self as Store>::ActiveConfigVersion::get();
+
+		let mut earliest_version = <Self as Store>::EarliestStoredConfigVersion::get();
+		while earliest_version < active_version {
+			// a version is still needed if its successor became active after the earliest kept
+			// session.
+			match <Self as Store>::ConfigActivationSessions::get(earliest_version + 1) {
+				Some(next_session) if next_session <= earliest_kept_session => {
+					<Self as Store>::ConfigActivationSessions::remove(earliest_version);
+					earliest_version += 1;
+				},
+				_ => break,
+			}
+		}
+		<Self as Store>::EarliestStoredConfigVersion::put(earliest_version);
+	}
+
 	/// Return the session index that should be used for any future scheduled changes.
 	fn scheduled_session() -> SessionIndex {
 		shared::Pallet::<T>::scheduled_session()
@@ -1243,6 +1357,87 @@ impl<T: Config> Pallet<T> {
 		<Self as Store>::ActiveConfig::set(config);
 	}
 
+	/// Dry-run `set_config_diff` and return every consistency error which makes it fail.
+	///
+	/// The diff is checked the same way `set_config_diff` checks it, so an empty list means that
+	/// `set_config_diff` would accept the diff.
+	pub fn check_config_diff(
+		diff: HostConfigurationDiff<T::BlockNumber>,
+	) -> Vec<InconsistentError<T::BlockNumber>> {
+		match Self::updated_config(&<PendingConfigs<T>>::get(), |config| config.apply_diff(diff)) {
+			Ok(_) => Vec::new(),
+			Err(errors) => errors,
+		}
+	}
+
+	/// Apply the `updater` to the configuration a configuration change is based on, and check
+	/// the result.
+	///
+	/// Returns every consistency error of the new configuration if it is rejected: that is, if
+	/// it is inconsistent while the base configuration is consistent and the consistency check
+	/// is not bypassed. Otherwise returns the new configuration.
+	fn updated_config(
+		pending_configs: &[(SessionIndex, HostConfiguration<T::BlockNumber>)],
+		updater: impl FnOnce(&mut HostConfiguration<T::BlockNumber>),
+	) -> Result<HostConfiguration<T::BlockNumber>, Vec<InconsistentError<T::BlockNumber>>> {
+		// First, we need to decide what we should use as the base configuration.
+		let mut base_config = Self::base_config(pending_configs);
+		let base_config_consistent = base_config.check_consistency().is_ok();
+
+		// Now, we need to decide what the new configuration should be.
+		// We also move the `base_config` to `new_config` to empahsize that the base config was
+		// destroyed by the `updater`.
+		updater(&mut base_config);
+		let new_config = base_config;
+
+		if <Self as Store>::BypassConsistencyCheck::get() {
+			// This will emit a warning each configuration update if the consistency check is
+			// bypassed. This is an attempt to make sure the bypass is not accidentally left on.
+			log::warn!(
+				target: LOG_TARGET,
+				"Bypassing the consistency check for the configuration change!",
+			);
+			return Ok(new_config)
+		}
+
+		let errors = new_config.consistency_errors();
+		if errors.is_empty() {
+			Ok(new_config)
+		} else if base_config_consistent {
+			// Base configuration is consistent and the new configuration is inconsistent.
+			// This means that the value set by the `updater` is invalid and we can return
+			// it as an error.
+			log::warn!(
+				target: LOG_TARGET,
+				"Configuration change rejected due to invalid configuration: {:?}",
+				errors,
+			);
+			Err(errors)
+		} else {
+			// The configuration was already broken, so we can as well proceed with the update.
+			// You cannot break something that is already broken.
+			//
+			// That will allow to call several functions and ultimately return the configuration
+			// into consistent state.
+			log::warn!(
+				target: LOG_TARGET,
+				"The new configuration is broken but the old is broken as well. Proceeding",
+			);
+			Ok(new_config)
+		}
+	}
+
+	/// The configuration a configuration change is based on: the last pending configuration or,
+	/// if there is none, the active one.
+	fn base_config(
+		pending_configs: &[(SessionIndex, HostConfiguration<T::BlockNumber>)],
+	) -> HostConfiguration<T::BlockNumber> {
+		pending_configs
+			.last()
+			.map(|&(_, ref config)| config.clone())
+			.unwrap_or_else(Self::config)
+	}
+
 	/// This function should be used to update members of the configuration.
 	///
 	/// This function is used to update the configuration in a way that is safe. It will check the
@@ -1289,49 +1484,8 @@ impl<T: Config> Pallet<T> {
 		// There cannot be (cur, X) because those are applied in the session change handler for the
 		// current session.
 
-		// First, we need to decide what we should use as the base configuration.
-		let mut base_config = pending_configs
-			.last()
-			.map(|&(_, ref config)| config.clone())
-			.unwrap_or_else(Self::config);
-		let base_config_consistent = base_config.check_consistency().is_ok();
-
-		// Now, we need to decide what the new configuration should be.
-		// We also move the `base_config` to `new_config` to empahsize that the base config was
-		// destroyed by the `updater`.
-		updater(&mut base_config);
-		let new_config = base_config;
-
-		if <Self as Store>::BypassConsistencyCheck::get() {
-			// This will emit a warning each configuration update if the consistency check is
-			// bypassed. This is an attempt to make sure the bypass is not accidentally left on.
-			log::warn!(
-				target: LOG_TARGET,
-				"Bypassing the consistency check for the configuration change!",
-			);
-		} else if let Err(e) = new_config.check_consistency() {
-			if base_config_consistent {
-				// Base configuration is consistent and the new configuration is inconsistent.
-				// This means that the value set by the `updater` is invalid and we can return
-				// it as an error.
-				log::warn!(
-					target: LOG_TARGET,
-					"Configuration change rejected due to invalid configuration: {:?}",
-					e,
-				);
-				return Err(Error::<T>::InvalidNewValue.into())
-			} else {
-				// The configuration was already broken, so we can as well proceed with the update.
-				// You cannot break something that is already broken.
-				//
-				// That will allow to call several functions and ultimately return the configuration
-				// into consistent state.
-				log::warn!(
-					target: LOG_TARGET,
-					"The new configuration is broken but the old is broken as well. Proceeding",
-				);
-			}
-		}
+		let new_config = Self::updated_config(&pending_configs, updater)
+			.map_err(|_| Error::<T>::InvalidNewValue)?;
 
 		let scheduled_session = Self::scheduled_session();
 
@@ -36,6 +36,17 @@ benchmarks! {
 
 	set_config_with_balance {}: set_hrmp_sender_deposit(RawOrigin::Root, 100_000_000_000)
 
+	set_config_diff {
+		let diff = HostConfigurationDiff {
+			code_retention_period: Some(One::one()),
+			max_code_size: Some(100),
+			max_validators: Some(Some(10)),
+			ump_service_total_weight: Some(Weight::from_ref_time(3_000_000)),
+			hrmp_sender_deposit: Some(100_000_000_000),
+			..Default::default()
+		};
+	}: _(RawOrigin::Root, diff)
+
 	impl_benchmark_test_suite!(
 		Pallet,
 		crate::mock::new_test_ext(Default::default()),
@@ -26,7 +26,8 @@ use frame_system::pallet_prelude::BlockNumberFor;
 /// v1-v2: <https://github.com/paritytech/polkadot/pull/4420>
 /// v2-v3: <https://github.com/paritytech/polkadot/pull/6091>
 /// v3-v4: <https://github.com/paritytech/polkadot/pull/6345>
-pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
+/// v4-v5: record the activation session of the active configuration
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(5);
 
 pub mod v4 {
 	use super::*;
@@ -152,7 +153,7 @@ pub mod v4 {
 				let weight_consumed = migrate_to_v4::<T>();
 
 				log::info!(target: configuration::LOG_TARGET, "MigrateToV4 executed successfully");
-				STORAGE_VERSION.put::<Pallet<T>>();
+				StorageVersion::new(4).put::<Pallet<T>>();
 
 				weight_consumed
 			} else {
@@ -165,7 +166,7 @@ pub mod v4 {
 		fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
 			log::trace!(target: crate::configuration::LOG_TARGET, "Running post_upgrade()");
 			ensure!(
-				StorageVersion::get::<Pallet<T>>() == STORAGE_VERSION,
+				StorageVersion::get::<Pallet<T>>() == 4,
 				"Storage version should be 4 after the migration"
 			);
 
@@ -245,6 +246,83 @@ minimum_validation_upgrade_delay         : pre.minimum_validation_upgrade_delay,
 	T::DbWeight::get().reads_writes(1, 1)
 }
 
+pub mod v5 {
+	use super::*;
+	use crate::shared;
+	use frame_support::traits::OnRuntimeUpgrade;
+	#[cfg(feature = "try-runtime")]
+	use sp_std::prelude::*;
+
+	/// Backfills `ConfigActivationSessions` for the configurations which were scheduled before it
+	/// was introduced.
+	///
+	/// The session in which the active configuration became active is unknown, so it is recorded
+	/// with the current session, the earliest one it is known to be active in. Each of the
+	/// `PendingConfigs` gets the version following the active one once it is applied, which
+	/// records its activation session like for any later configuration.
+	pub struct MigrateToV5<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV5<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			log::trace!(target: crate::configuration::LOG_TARGET, "Running pre_upgrade()");
+
+			ensure!(StorageVersion::get::<Pallet<T>>() == 4, "The migration requires version 4");
+			Ok(Vec::new())
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() == 4 {
+				let weight_consumed = migrate_to_v5::<T>();
+
+				log::info!(target: configuration::LOG_TARGET, "MigrateToV5 executed successfully");
+				STORAGE_VERSION.put::<Pallet<T>>();
+
+				weight_consumed
+			} else {
+				log::warn!(target: configuration::LOG_TARGET, "MigrateToV5 should be removed.");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+			log::trace!(target: crate::configuration::LOG_TARGET, "Running post_upgrade()");
+			ensure!(
+				StorageVersion::get::<Pallet<T>>() == STORAGE_VERSION,
+				"Storage version should be 5 after the migration"
+			);
+			let version = <Pallet<T> as Store>::ActiveConfigVersion::get();
+			ensure!(
+				<Pallet<T> as Store>::ConfigActivationSessions::contains_key(version),
+				"The active configuration should have an activation session"
+			);
+
+			Ok(())
+		}
+	}
+
+	fn migrate_to_v5<T: Config>() -> Weight {
+		let version = <Pallet<T> as Store>::ActiveConfigVersion::get();
+		let session_index = <shared::Pallet<T>>::session_index();
+		let pending = <Pallet<T> as Store>::PendingConfigs::decode_len().unwrap_or(0);
+
+		if !<Pallet<T> as Store>::ConfigActivationSessions::contains_key(version) {
+			<Pallet<T> as Store>::ConfigActivationSessions::insert(version, session_index);
+		}
+		<Pallet<T> as Store>::EarliestStoredConfigVersion::put(version);
+
+		log::info!(
+			target: configuration::LOG_TARGET,
+			"Recorded configuration version {} as active since session {}, {} pending",
+			version,
+			session_index,
+			pending,
+		);
+
+		T::DbWeight::get().reads_writes(4, 2)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -365,4 +443,23 @@ mod tests {
 			}; // ; makes this a statement. `rustfmt::skip` cannot be put on an expression.
 		});
 	}
+
+	#[test]
+	fn test_migrate_to_v5() {
+		use frame_support::traits::OnRuntimeUpgrade;
+
+		new_test_ext(Default::default()).execute_with(|| {
+			// Emulate a chain which has applied a configuration before the record existed.
+			crate::shared::Pallet::<Test>::set_session_index(7);
+			<Pallet<Test> as Store>::ActiveConfigVersion::put(3);
+			let _ = <Pallet<Test> as Store>::ConfigActivationSessions::clear(u32::MAX, None);
+			StorageVersion::new(4).put::<Pallet<Test>>();
+
+			v5::MigrateToV5::<Test>::on_runtime_upgrade();
+
+			assert_eq!(StorageVersion::get::<Pallet<Test>>(), STORAGE_VERSION);
+			assert_eq!(<Pallet<Test> as Store>::ConfigActivationSessions::get(3), Some(7));
+			assert_eq!(<Pallet<Test> as Store>::EarliestStoredConfigVersion::get(), 3);
+		});
+	}
 }
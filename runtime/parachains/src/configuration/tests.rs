@@ -289,6 +289,146 @@ fn consistency_bypass_works() {
 	});
 }
 
+#[test]
+fn set_config_diff_applies_changes_at_once() {
+	new_test_ext(Default::default()).execute_with(|| {
+		<Configuration as Store>::ActiveConfig::put(HostConfiguration {
+			chain_availability_period: 10,
+			thread_availability_period: 8,
+			minimum_validation_upgrade_delay: 11,
+			..Default::default()
+		});
+
+		// Each change on its own would make the configuration inconsistent.
+		assert_err!(
+			Configuration::set_chain_availability_period(RuntimeOrigin::root(), 20),
+			Error::<Test>::InvalidNewValue
+		);
+		let diff = HostConfigurationDiff {
+			chain_availability_period: Some(20),
+			minimum_validation_upgrade_delay: Some(21),
+			max_validators: Some(Some(100)),
+			..Default::default()
+		};
+		assert_ok!(Configuration::set_config_diff(RuntimeOrigin::root(), diff));
+
+		let expected = HostConfiguration {
+			chain_availability_period: 20,
+			thread_availability_period: 8,
+			minimum_validation_upgrade_delay: 21,
+			max_validators: Some(100),
+			..Default::default()
+		};
+		assert_eq!(<Configuration as Store>::PendingConfigs::get(), vec![(2, expected.clone())]);
+
+		// An inconsistent diff is rejected as a whole.
+		let diff = HostConfigurationDiff {
+			max_code_size: Some(100),
+			no_show_slots: Some(0),
+			..Default::default()
+		};
+		assert_err!(
+			Configuration::set_config_diff(RuntimeOrigin::root(), diff),
+			Error::<Test>::InvalidNewValue
+		);
+		assert_eq!(<Configuration as Store>::PendingConfigs::get(), vec![(2, expected)]);
+	});
+}
+
+#[test]
+fn check_config_diff_reports_every_error() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_eq!(Configuration::check_config_diff(Default::default()), vec![]);
+
+		let diff = HostConfigurationDiff {
+			no_show_slots: Some(0),
+			max_code_size: Some(MAX_CODE_SIZE + 1),
+			validation_upgrade_delay: Some(1),
+			..Default::default()
+		};
+		assert_eq!(
+			Configuration::check_config_diff(diff),
+			vec![
+				InconsistentError::ZeroNoShowSlots,
+				InconsistentError::MaxCodeSizeExceedHardLimit { max_code_size: MAX_CODE_SIZE + 1 },
+				InconsistentError::ValidationUpgradeDelayIsTooLow { validation_upgrade_delay: 1 },
+			],
+		);
+
+		// The dry run is based on the pending configuration.
+		assert_ok!(Configuration::set_minimum_validation_upgrade_delay(RuntimeOrigin::root(), 10));
+		let diff =
+			HostConfigurationDiff { chain_availability_period: Some(10), ..Default::default() };
+		assert_eq!(
+			Configuration::check_config_diff(diff),
+			vec![InconsistentError::MinimumValidationUpgradeDelayLessThanChainAvailabilityPeriod {
+				minimum_validation_upgrade_delay: 10,
+				chain_availability_period: 10,
+			}],
+		);
+
+		// The dry run accepts what `set_config_diff` accepts.
+		assert_ok!(Configuration::set_bypass_consistency_check(RuntimeOrigin::root(), true));
+		let diff = HostConfigurationDiff { no_show_slots: Some(0), ..Default::default() };
+		assert_eq!(Configuration::check_config_diff(diff.clone()), vec![]);
+		assert_ok!(Configuration::set_config_diff(RuntimeOrigin::root(), diff));
+		assert_ok!(Configuration::set_bypass_consistency_check(RuntimeOrigin::root(), false));
+		let diff =
+			HostConfigurationDiff { group_rotation_frequency: Some(0), ..Default::default() };
+		assert_eq!(Configuration::check_config_diff(diff.clone()), vec![]);
+		assert_ok!(Configuration::set_config_diff(RuntimeOrigin::root(), diff));
+	});
+}
+
+#[test]
+fn config_activation_sessions_are_recorded() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_eq!(Configuration::active_config_version(), 0);
+		assert_eq!(Configuration::config_activation_session(0), Some(0));
+
+		assert_ok!(Configuration::set_validation_upgrade_delay(RuntimeOrigin::root(), 100));
+		on_new_session(1);
+		assert_eq!(Configuration::active_config_version(), 0);
+
+		on_new_session(2);
+		assert_eq!(Configuration::active_config_version(), 1);
+		assert_eq!(Configuration::config_activation_session(1), Some(2));
+
+		// Sessions without a pending configuration leave the version untouched.
+		on_new_session(3);
+		assert_ok!(Configuration::set_validation_upgrade_delay(RuntimeOrigin::root(), 200));
+		on_new_session(4);
+		on_new_session(5);
+		assert_eq!(Configuration::active_config_version(), 2);
+		assert_eq!(Configuration::config_activation_session(2), Some(5));
+		assert_eq!(Configuration::config_activation_session(1), Some(2));
+	});
+}
+
+#[test]
+fn config_activation_sessions_are_pruned() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_ok!(Configuration::set_dispute_period(RuntimeOrigin::root(), 2));
+		on_new_session(1);
+		on_new_session(2);
+		assert_eq!(Configuration::active_config_version(), 1);
+
+		// Version 0 was active in session 1, which is still kept.
+		on_new_session(3);
+		assert_eq!(Configuration::config_activation_session(0), Some(0));
+
+		// Version 0 was not active in any of the sessions 2 to 4.
+		on_new_session(4);
+		assert_eq!(Configuration::config_activation_session(0), None);
+		assert_eq!(Configuration::config_activation_session(1), Some(2));
+		assert_eq!(<Configuration as Store>::EarliestStoredConfigVersion::get(), 1);
+
+		// The active version is never pruned.
+		on_new_session(10);
+		assert_eq!(Configuration::config_activation_session(1), Some(2));
+	});
+}
+
 #[test]
 fn setting_pending_config_members() {
 	new_test_ext(Default::default()).execute_with(|| {
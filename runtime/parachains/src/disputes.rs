@@ -16,7 +16,10 @@
 
 //! Runtime component for handling disputes of parachain candidates.
 
-use crate::{configuration, initializer::SessionChangeNotification, session_info};
+use crate::{
+	configuration, initializer::SessionChangeNotification, performance::RecordPerformance,
+	session_info,
+};
 use bitvec::{bitvec, order::Lsb0 as BitOrderLsb0};
 use frame_support::{ensure, traits::Get, weights::Weight};
 use frame_system::pallet_prelude::*;
@@ -438,6 +441,8 @@ pub mod pallet {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		type RewardValidators: RewardValidators;
 		type SlashingHandler: SlashingHandler<Self::BlockNumber>;
+		/// Accounts the dispute participation of validators.
+		type RecordPerformance: RecordPerformance;
 
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
@@ -1236,6 +1241,44 @@ impl<T: Config> Pallet<T> {
 			summary.new_participants.iter_ones().map(|i| ValidatorIndex(i as _)),
 		);
 
+		// Account the participation for the performance of validators.
+		{
+			if fresh {
+				T::RecordPerformance::note_disputes_raised(
+					session,
+					summary.state.validators_against.iter_ones().map(|i| ValidatorIndex(i as _)),
+				);
+			}
+
+			// All validators on the winning side win the dispute once it concludes. Votes on the
+			// winning side imported afterwards win as they come in.
+			let post_flags = DisputeStateFlags::from_state(&summary.state);
+			for (flag, side) in [
+				(DisputeStateFlags::FOR_SUPERMAJORITY, &summary.state.validators_for),
+				(DisputeStateFlags::AGAINST_SUPERMAJORITY, &summary.state.validators_against),
+			] {
+				if !post_flags.contains(flag) {
+					continue
+				}
+
+				let newly_concluded = summary.new_flags.contains(flag);
+				let is_new_participant =
+					|i: usize| summary.new_participants.get(i).map_or(false, |b| *b);
+				T::RecordPerformance::note_disputes_won(
+					session,
+					side
+						.iter_ones()
+						.filter(|i| newly_concluded || is_new_participant(*i))
+						.map(|i| ValidatorIndex(i as _)),
+				);
+			}
+
+			T::RecordPerformance::note_disputes_lost(
+				session,
+				summary.slash_against.iter().chain(summary.slash_for.iter()).copied(),
+			);
+		}
+
 		// Slash participants on a losing side.
 		{
 			// a valid candidate, according to 2/3. Punish those on the 'against' side.
@@ -20,7 +20,8 @@
 //! wrong side of the dispute. The slashing amount depends on whether the
 //! candidate was valid (none at the moment) or invalid (big). In addition to
 //! that, we might want to kick out the validators from the active set.
-//! Currently, we limit slashing to the backing group for invalid disputes.
+//! Currently, we limit slashing to the backing group for invalid disputes,
+//! while everyone who disputed a valid candidate is reported.
 //!
 //! The `offences` pallet from Substrate provides us with a way to do both.
 //! Currently, the interface expects us to provide staking information including
@@ -49,7 +50,6 @@ use frame_support::{
 	weights::Weight,
 };
 
-use parity_scale_codec::{Decode, Encode};
 use primitives::{CandidateHash, SessionIndex, ValidatorId, ValidatorIndex};
 use scale_info::TypeInfo;
 use sp_runtime::{
@@ -64,7 +64,7 @@ use sp_session::{GetSessionNumber, GetValidatorCount};
 use sp_staking::offence::{DisableStrategy, Kind, Offence, OffenceError, ReportOffence};
 use sp_std::{
 	collections::{
-		btree_map::{BTreeMap, Entry},
+		btree_map::Entry,
 		btree_set::BTreeSet,
 	},
 	prelude::*,
@@ -81,6 +81,9 @@ const DEFENSIVE_PROOF: &'static str = "disputes module should bail on old sessio
 #[cfg(feature = "runtime-benchmarks")]
 pub mod benchmarking;
 
+#[cfg(test)]
+mod tests;
+
 /// The benchmarking configuration.
 pub trait BenchmarkingConfiguration {
 	const MAX_VALIDATORS: u32;
@@ -92,20 +95,9 @@ impl<const M: u32> BenchmarkingConfiguration for BenchConfig<M> {
 	const MAX_VALIDATORS: u32 = M;
 }
 
-/// Timeslots should uniquely identify offences and are used for the offence
-/// deduplication.
-#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Encode, Decode, TypeInfo, RuntimeDebug)]
-pub struct DisputesTimeSlot {
-	// The order of these matters for `derive(Ord)`.
-	session_index: SessionIndex,
-	candidate_hash: CandidateHash,
-}
-
-impl DisputesTimeSlot {
-	pub fn new(session_index: SessionIndex, candidate_hash: CandidateHash) -> Self {
-		Self { session_index, candidate_hash }
-	}
-}
+pub use primitives::vstaging::slashing::{
+	DisputeProof, DisputesTimeSlot, PendingSlashes, SlashingOffenceKind,
+};
 
 /// An offence that is filed when a series of validators lost a dispute.
 #[derive(RuntimeDebug, TypeInfo)]
@@ -232,17 +224,19 @@ where
 		losers: impl IntoIterator<Item = ValidatorIndex>,
 		backers: impl IntoIterator<Item = ValidatorIndex>,
 	) {
-		// sanity check for the current implementation
-		if kind == SlashingOffenceKind::AgainstValid {
-			debug_assert!(false, "should only slash ForInvalid disputes");
-			return
-		}
 		let losers: BTreeSet<_> = losers.into_iter().collect();
 		if losers.is_empty() {
 			return
 		}
-		let backers: BTreeSet<_> = backers.into_iter().collect();
-		let to_punish: Vec<ValidatorIndex> = losers.intersection(&backers).cloned().collect();
+		let to_punish: Vec<ValidatorIndex> = match kind {
+			// Only the backers of an invalid candidate are punished, not everyone who voted for
+			// it in the dispute.
+			SlashingOffenceKind::ForInvalid => {
+				let backers: BTreeSet<_> = backers.into_iter().collect();
+				losers.intersection(&backers).cloned().collect()
+			},
+			SlashingOffenceKind::AgainstValid => losers.into_iter().collect(),
+		};
 		if to_punish.is_empty() {
 			return
 		}
@@ -301,13 +295,13 @@ where
 	}
 
 	fn punish_against_valid(
-		_session_index: SessionIndex,
-		_candidate_hash: CandidateHash,
-		_losers: impl IntoIterator<Item = ValidatorIndex>,
-		_backers: impl IntoIterator<Item = ValidatorIndex>,
+		session_index: SessionIndex,
+		candidate_hash: CandidateHash,
+		losers: impl IntoIterator<Item = ValidatorIndex>,
+		backers: impl IntoIterator<Item = ValidatorIndex>,
 	) {
-		// do nothing for now
-		// NOTE: changing that requires modifying `do_punish` implementation
+		let kind = SlashingOffenceKind::AgainstValid;
+		Self::do_punish(session_index, candidate_hash, kind, losers, backers);
 	}
 
 	fn initializer_initialize(now: T::BlockNumber) -> Weight {
@@ -323,39 +317,6 @@ where
 	}
 }
 
-#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
-pub enum SlashingOffenceKind {
-	#[codec(index = 0)]
-	ForInvalid,
-	#[codec(index = 1)]
-	AgainstValid,
-}
-
-/// We store most of the information about a lost dispute on chain. This struct
-/// is required to identify and verify it.
-#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
-pub struct DisputeProof {
-	/// Time slot when the dispute occured.
-	pub time_slot: DisputesTimeSlot,
-	/// The dispute outcome.
-	pub kind: SlashingOffenceKind,
-	/// The index of the validator who lost a dispute.
-	pub validator_index: ValidatorIndex,
-	/// The parachain session key of the validator.
-	pub validator_id: ValidatorId,
-}
-
-/// Slashes that are waiting to be applied once we have validator key
-/// identification.
-#[derive(Encode, Decode, RuntimeDebug, TypeInfo)]
-pub struct PendingSlashes {
-	/// Indices and keys of the validators who lost a dispute and are pending
-	/// slashes.
-	pub keys: BTreeMap<ValidatorIndex, ValidatorId>,
-	/// The dispute outcome.
-	pub kind: SlashingOffenceKind,
-}
-
 /// A trait that defines methods to report an offence (after the slashing report
 /// has been validated) and for submitting a transaction to report a slash (from
 /// an offchain context).
@@ -605,6 +566,24 @@ impl<T: Config> Pallet<T> {
 	}
 }
 
+impl<T: Config> Pallet<T> {
+	/// All unapplied slashes, along with the session and the candidate of the dispute that
+	/// caused them.
+	pub fn unapplied_slashes() -> Vec<(SessionIndex, CandidateHash, PendingSlashes)> {
+		<UnappliedSlashes<T>>::iter().collect()
+	}
+
+	/// Submit an unsigned transaction reporting a validator that lost a dispute.
+	///
+	/// This should only be called from an offchain context, e.g. the runtime API.
+	pub fn submit_unsigned_slashing_report(
+		dispute_proof: DisputeProof,
+		key_owner_proof: T::KeyOwnerProof,
+	) -> Option<()> {
+		T::HandleReports::submit_unsigned_slashing_report(dispute_proof, key_owner_proof).ok()
+	}
+}
+
 /// Methods for the `ValidateUnsigned` implementation:
 ///
 /// It restricts calls to `report_dispute_lost_unsigned` to local calls (i.e.
@@ -0,0 +1,124 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::{
+	disputes::SlashingHandler,
+	mock::{new_test_ext, MockGenesisConfig, Test},
+	session_info,
+};
+use keyring::Sr25519Keyring;
+use primitives::{BlockNumber, SessionInfo};
+
+// `MockValidatorSet` is always in session 0, so offences in this session are never
+// reported directly and end up in `UnappliedSlashes`.
+const SESSION: SessionIndex = 1;
+
+type Handler = SlashValidatorsForDisputes<Pallet<Test>>;
+
+const KEYS: [Sr25519Keyring; 5] = [
+	Sr25519Keyring::Alice,
+	Sr25519Keyring::Bob,
+	Sr25519Keyring::Charlie,
+	Sr25519Keyring::Dave,
+	Sr25519Keyring::Eve,
+];
+
+fn validators() -> Vec<ValidatorId> {
+	KEYS.iter().map(|k| k.public().into()).collect()
+}
+
+fn store_session_info() {
+	let info = SessionInfo {
+		discovery_keys: KEYS.iter().map(|k| k.public().into()).collect(),
+		validators: validators().into(),
+		assignment_keys: Vec::new(),
+		validator_groups: Default::default(),
+		n_cores: 1,
+		zeroth_delay_tranche_width: 0,
+		relay_vrf_modulo_samples: 0,
+		n_delay_tranches: 0,
+		no_show_slots: 0,
+		needed_approvals: 0,
+		active_validator_indices: Vec::new(),
+		random_seed: [0u8; 32],
+		dispute_period: 6,
+	};
+	session_info::Sessions::<Test>::insert(SESSION, info);
+}
+
+fn pending_keys(candidate_hash: CandidateHash) -> Vec<ValidatorIndex> {
+	<UnappliedSlashes<Test>>::get(SESSION, candidate_hash)
+		.map(|pending| pending.keys.into_keys().collect())
+		.unwrap_or_default()
+}
+
+#[test]
+fn against_valid_punishes_every_loser() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		store_session_info();
+		let candidate_hash = CandidateHash(sp_core::H256::repeat_byte(1));
+		let losers = vec![ValidatorIndex(1), ValidatorIndex(3), ValidatorIndex(4)];
+		// The winners backed the candidate, none of the losers did.
+		let backers = vec![ValidatorIndex(0), ValidatorIndex(2)];
+
+		<Handler as SlashingHandler<BlockNumber>>::punish_against_valid(
+			SESSION,
+			candidate_hash,
+			losers.clone(),
+			backers,
+		);
+
+		let pending = <UnappliedSlashes<Test>>::get(SESSION, candidate_hash).unwrap();
+		assert_eq!(pending.kind, SlashingOffenceKind::AgainstValid);
+		assert_eq!(pending_keys(candidate_hash), losers);
+		let all = validators();
+		for (index, key) in pending.keys {
+			assert_eq!(all[index.0 as usize], key);
+		}
+	});
+}
+
+#[test]
+fn for_invalid_punishes_only_losing_backers() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		store_session_info();
+		let candidate_hash = CandidateHash(sp_core::H256::repeat_byte(2));
+		let losers = vec![ValidatorIndex(0), ValidatorIndex(1), ValidatorIndex(2)];
+		let backers = vec![ValidatorIndex(1), ValidatorIndex(2), ValidatorIndex(4)];
+
+		<Handler as SlashingHandler<BlockNumber>>::punish_for_invalid(
+			SESSION,
+			candidate_hash,
+			losers,
+			backers,
+		);
+
+		let pending = <UnappliedSlashes<Test>>::get(SESSION, candidate_hash).unwrap();
+		assert_eq!(pending.kind, SlashingOffenceKind::ForInvalid);
+		assert_eq!(pending_keys(candidate_hash), vec![ValidatorIndex(1), ValidatorIndex(2)]);
+
+		// Losers who did not back the candidate are not punished at all.
+		let candidate_hash = CandidateHash(sp_core::H256::repeat_byte(3));
+		<Handler as SlashingHandler<BlockNumber>>::punish_for_invalid(
+			SESSION,
+			candidate_hash,
+			vec![ValidatorIndex(0)],
+			vec![ValidatorIndex(1)],
+		);
+		assert!(<UnappliedSlashes<Test>>::get(SESSION, candidate_hash).is_none());
+	});
+}
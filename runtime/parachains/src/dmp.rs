@@ -16,21 +16,56 @@
 
 use crate::{
 	configuration::{self, HostConfiguration},
-	initializer,
+	initializer, FeeTracker,
 };
 use frame_support::pallet_prelude::*;
 use primitives::{DownwardMessage, Hash, Id as ParaId, InboundDownwardMessage};
-use sp_runtime::traits::{BlakeTwo256, Hash as HashT, SaturatedConversion};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{BlakeTwo256, Hash as HashT, Saturating},
+	FixedU128,
+};
 use sp_std::{fmt, prelude::*};
 use xcm::latest::SendError;
 
 pub use pallet::*;
 
+pub mod migration;
 #[cfg(test)]
 mod tests;
 
 pub const MAX_MESSAGE_QUEUE_SIZE: usize = 1024;
 
+/// The maximum number of messages held by a single page of a downward message queue.
+pub const QUEUE_PAGE_CAPACITY: u32 = 32;
+
+/// The length of a downward message queue from which on the delivery fee starts to grow.
+pub const DELIVERY_FEE_THRESHOLD: u32 = 32;
+
+/// The factor by which the delivery fee grows with every message queued beyond
+/// [`DELIVERY_FEE_THRESHOLD`], i.e. 1.05.
+pub const EXPONENTIAL_FEE_BASE: FixedU128 = FixedU128::from_inner(1_050_000_000_000_000_000);
+
+/// Index of a page within the downward message queue of a para.
+pub type PageIndex = u32;
+
+/// The pages making up the downward message queue of a para.
+#[derive(Encode, Decode, TypeInfo, Default, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct QueueState {
+	/// The index of the page holding the oldest message.
+	pub begin: PageIndex,
+	/// One past the index of the page holding the newest message.
+	pub end: PageIndex,
+	/// The number of messages in the queue.
+	pub len: u32,
+}
+
+impl QueueState {
+	fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+}
+
 /// An error sending a downward message.
 #[cfg_attr(test, derive(Debug))]
 pub enum QueueDownwardMessageError {
@@ -77,17 +112,31 @@ pub mod pallet {
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
 	#[pallet::without_storage_info]
+	#[pallet::storage_version(migration::STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
 	pub trait Config: frame_system::Config + configuration::Config {}
 
-	/// The downward messages addressed for a certain para.
+	/// The pages making up the downward message queue of a certain para.
+	///
+	/// Invariant:
+	/// - The pages in `begin..end` exist in `DownwardMessageQueuePages` and hold at least one
+	///   message.
+	/// - An entry exists if and only if the queue of the para is not empty.
 	#[pallet::storage]
-	pub(crate) type DownwardMessageQueues<T: Config> = StorageMap<
+	pub(crate) type DownwardMessageQueueState<T: Config> =
+		StorageMap<_, Twox64Concat, ParaId, QueueState, ValueQuery>;
+
+	/// The downward messages addressed for a certain para, split into pages of at most
+	/// [`QUEUE_PAGE_CAPACITY`] messages.
+	#[pallet::storage]
+	pub(crate) type DownwardMessageQueuePages<T: Config> = StorageDoubleMap<
 		_,
 		Twox64Concat,
 		ParaId,
+		Twox64Concat,
+		PageIndex,
 		Vec<InboundDownwardMessage<T::BlockNumber>>,
 		ValueQuery,
 	>;
@@ -135,7 +184,12 @@ impl<T: Config> Pallet<T> {
 
 	/// Remove all relevant storage items for an outgoing parachain.
 	fn clean_dmp_after_outgoing(outgoing_para: &ParaId) {
-		<Self as Store>::DownwardMessageQueues::remove(outgoing_para);
+		let state = <Self as Store>::DownwardMessageQueueState::take(outgoing_para);
+		let _ = <Self as Store>::DownwardMessageQueuePages::clear_prefix(
+			outgoing_para,
+			state.end.saturating_sub(state.begin),
+			None,
+		);
 		<Self as Store>::DownwardMessageQueueHeads::remove(outgoing_para);
 	}
 
@@ -152,9 +206,7 @@ impl<T: Config> Pallet<T> {
 			return Err(QueueDownwardMessageError::ExceedsMaxMessageSize)
 		}
 
-		if <Self as Store>::DownwardMessageQueues::decode_len(para).unwrap_or(0) >
-			MAX_MESSAGE_QUEUE_SIZE
-		{
+		if Self::dmq_length(*para) as usize > MAX_MESSAGE_QUEUE_SIZE {
 			return Err(QueueDownwardMessageError::ExceedsMaxMessageSize)
 		}
 
@@ -179,9 +231,8 @@ impl<T: Config> Pallet<T> {
 			return Err(QueueDownwardMessageError::ExceedsMaxMessageSize)
 		}
 
-		if <Self as Store>::DownwardMessageQueues::decode_len(para).unwrap_or(0) >
-			MAX_MESSAGE_QUEUE_SIZE
-		{
+		let mut state = <Self as Store>::DownwardMessageQueueState::get(para);
+		if state.len as usize > MAX_MESSAGE_QUEUE_SIZE {
 			return Err(QueueDownwardMessageError::ExceedsMaxMessageSize)
 		}
 
@@ -195,19 +246,33 @@ impl<T: Config> Pallet<T> {
 			*head = new_head;
 		});
 
-		<Self as Store>::DownwardMessageQueues::mutate(para, |v| {
-			v.push(inbound);
-		});
+		// append the message to the last page, unless it is full.
+		let last_page_len = if state.is_empty() {
+			None
+		} else {
+			<Self as Store>::DownwardMessageQueuePages::decode_len(para, state.end - 1)
+		};
+		if last_page_len.map_or(true, |len| len as u32 >= QUEUE_PAGE_CAPACITY) {
+			state.end += 1;
+		}
+		<Self as Store>::DownwardMessageQueuePages::append(para, state.end - 1, inbound);
+
+		state.len += 1;
+		<Self as Store>::DownwardMessageQueueState::insert(para, state);
 
 		Ok(())
 	}
 
 	/// Checks if the number of processed downward messages is valid.
+	///
+	/// `already_processed` is the number of messages processed by the candidates pending
+	/// availability the candidate builds upon, which are not pruned from the queue yet.
 	pub(crate) fn check_processed_downward_messages(
 		para: ParaId,
+		already_processed: u32,
 		processed_downward_messages: u32,
 	) -> Result<(), ProcessedDownwardMessagesAcceptanceErr> {
-		let dmq_length = Self::dmq_length(para);
+		let dmq_length = Self::dmq_length(para).saturating_sub(already_processed);
 
 		if dmq_length > 0 && processed_downward_messages == 0 {
 			return Err(ProcessedDownwardMessagesAcceptanceErr::AdvancementRule)
@@ -223,18 +288,40 @@ impl<T: Config> Pallet<T> {
 	}
 
 	/// Prunes the specified number of messages from the downward message queue of the given para.
+	///
+	/// Only the pages holding the pruned messages are touched.
 	pub(crate) fn prune_dmq(para: ParaId, processed_downward_messages: u32) -> Weight {
-		<Self as Store>::DownwardMessageQueues::mutate(para, |q| {
-			let processed_downward_messages = processed_downward_messages as usize;
-			if processed_downward_messages > q.len() {
-				// reaching this branch is unexpected due to the constraint established by
-				// `check_processed_downward_messages`. But better be safe than sorry.
-				q.clear();
+		let mut state = <Self as Store>::DownwardMessageQueueState::get(para);
+		// exceeding the queue length is unexpected due to the constraint established by
+		// `check_processed_downward_messages`. But better be safe than sorry.
+		let mut to_prune = processed_downward_messages.min(state.len);
+		let mut pages_touched = 0;
+
+		while to_prune > 0 && state.begin < state.end {
+			pages_touched += 1;
+			let page_len = <Self as Store>::DownwardMessageQueuePages::decode_len(para, state.begin)
+				.unwrap_or(0) as u32;
+			if page_len <= to_prune {
+				<Self as Store>::DownwardMessageQueuePages::remove(para, state.begin);
+				state.begin += 1;
+				state.len = state.len.saturating_sub(page_len);
+				to_prune -= page_len;
 			} else {
-				*q = q.split_off(processed_downward_messages);
+				<Self as Store>::DownwardMessageQueuePages::mutate(para, state.begin, |page| {
+					*page = page.split_off(to_prune as usize);
+				});
+				state.len -= to_prune;
+				to_prune = 0;
 			}
-		});
-		T::DbWeight::get().reads_writes(1, 1)
+		}
+
+		if state.is_empty() {
+			<Self as Store>::DownwardMessageQueueState::remove(para);
+		} else {
+			<Self as Store>::DownwardMessageQueueState::insert(para, state);
+		}
+
+		T::DbWeight::get().reads_writes(1 + pages_touched, 1 + pages_touched)
 	}
 
 	/// Returns the Head of Message Queue Chain for the given para or `None` if there is none
@@ -248,15 +335,31 @@ impl<T: Config> Pallet<T> {
 	///
 	/// Returns 0 if the para doesn't have an associated downward message queue.
 	pub(crate) fn dmq_length(para: ParaId) -> u32 {
-		<Self as Store>::DownwardMessageQueues::decode_len(&para)
-			.unwrap_or(0)
-			.saturated_into::<u32>()
+		<Self as Store>::DownwardMessageQueueState::get(&para).len
 	}
 
 	/// Returns the downward message queue contents for the given para.
 	///
 	/// The most recent messages are the latest in the vector.
 	pub(crate) fn dmq_contents(recipient: ParaId) -> Vec<InboundDownwardMessage<T::BlockNumber>> {
-		<Self as Store>::DownwardMessageQueues::get(&recipient)
+		let state = <Self as Store>::DownwardMessageQueueState::get(&recipient);
+		(state.begin..state.end)
+			.flat_map(|page| <Self as Store>::DownwardMessageQueuePages::get(&recipient, page))
+			.collect()
+	}
+
+	/// Returns the factor the delivery fee of a message to the given para is multiplied with.
+	///
+	/// The factor is 1 up to [`DELIVERY_FEE_THRESHOLD`] queued messages, and grows by
+	/// [`EXPONENTIAL_FEE_BASE`] with every message queued beyond that.
+	pub fn delivery_fee_factor(para: ParaId) -> FixedU128 {
+		let excess = Self::dmq_length(para).saturating_sub(DELIVERY_FEE_THRESHOLD);
+		EXPONENTIAL_FEE_BASE.saturating_pow(excess as usize)
+	}
+}
+
+impl<T: Config> FeeTracker for Pallet<T> {
+	fn get_fee_factor(para: ParaId) -> FixedU128 {
+		Self::delivery_fee_factor(para)
 	}
 }
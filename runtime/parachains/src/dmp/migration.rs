@@ -0,0 +1,78 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::dmp::{Config, Pallet};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+pub mod v1 {
+	use super::*;
+	use crate::dmp::{
+		DownwardMessageQueuePages, DownwardMessageQueueState, QueueState, QUEUE_PAGE_CAPACITY,
+	};
+	use frame_support::storage_alias;
+	use primitives::{Id as ParaId, InboundDownwardMessage};
+	use sp_std::prelude::*;
+
+	#[storage_alias]
+	type DownwardMessageQueues<T: Config> = StorageMap<
+		Pallet<T>,
+		Twox64Concat,
+		ParaId,
+		Vec<InboundDownwardMessage<<T as frame_system::Config>::BlockNumber>>,
+		ValueQuery,
+	>;
+
+	/// Splits the downward message queues into pages of at most `QUEUE_PAGE_CAPACITY` messages.
+	pub struct MigrateToV1<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() != 0 {
+				log::warn!("skipping v1, should be removed");
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut weight = T::DbWeight::get().reads(1);
+			let mut queues = 0;
+
+			for (para, queue) in DownwardMessageQueues::<T>::drain() {
+				let mut state = QueueState::default();
+				for page in queue.chunks(QUEUE_PAGE_CAPACITY as usize) {
+					DownwardMessageQueuePages::<T>::insert(para, state.end, page.to_vec());
+					state.end += 1;
+					state.len += page.len() as u32;
+				}
+				if !state.is_empty() {
+					DownwardMessageQueueState::<T>::insert(para, state);
+				}
+
+				queues += 1;
+				weight.saturating_accrue(T::DbWeight::get().reads_writes(1, 2 + state.end as u64));
+			}
+
+			log::info!("Migrated {} downward message queues to pages", queues);
+
+			STORAGE_VERSION.put::<Pallet<T>>();
+
+			weight.saturating_add(T::DbWeight::get().writes(1))
+		}
+	}
+}
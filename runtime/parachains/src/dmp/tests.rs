@@ -73,9 +73,11 @@ fn clean_dmp_works() {
 		let outgoing_paras = vec![a, b];
 		Dmp::initializer_on_new_session(&notification, &outgoing_paras);
 
-		assert!(<Dmp as Store>::DownwardMessageQueues::get(&a).is_empty());
-		assert!(<Dmp as Store>::DownwardMessageQueues::get(&b).is_empty());
-		assert!(!<Dmp as Store>::DownwardMessageQueues::get(&c).is_empty());
+		assert!(Dmp::dmq_contents(a).is_empty());
+		assert!(Dmp::dmq_contents(b).is_empty());
+		assert!(!Dmp::dmq_contents(c).is_empty());
+		assert!(!<Dmp as Store>::DownwardMessageQueueState::contains_key(&a));
+		assert_eq!(<Dmp as Store>::DownwardMessageQueuePages::iter_prefix(&a).count(), 0);
 	});
 }
 
@@ -122,20 +124,28 @@ fn check_processed_downward_messages() {
 
 	new_test_ext(default_genesis_config()).execute_with(|| {
 		// processed_downward_messages=0 is allowed when the DMQ is empty.
-		assert!(Dmp::check_processed_downward_messages(a, 0).is_ok());
+		assert!(Dmp::check_processed_downward_messages(a, 0, 0).is_ok());
 
 		queue_downward_message(a, vec![1, 2, 3]).unwrap();
 		queue_downward_message(a, vec![4, 5, 6]).unwrap();
 		queue_downward_message(a, vec![7, 8, 9]).unwrap();
 
 		// 0 doesn't pass if the DMQ has msgs.
-		assert!(!Dmp::check_processed_downward_messages(a, 0).is_ok());
+		assert!(!Dmp::check_processed_downward_messages(a, 0, 0).is_ok());
 		// a candidate can consume up to 3 messages
-		assert!(Dmp::check_processed_downward_messages(a, 1).is_ok());
-		assert!(Dmp::check_processed_downward_messages(a, 2).is_ok());
-		assert!(Dmp::check_processed_downward_messages(a, 3).is_ok());
+		assert!(Dmp::check_processed_downward_messages(a, 0, 1).is_ok());
+		assert!(Dmp::check_processed_downward_messages(a, 0, 2).is_ok());
+		assert!(Dmp::check_processed_downward_messages(a, 0, 3).is_ok());
 		// there is no 4 messages in the queue
-		assert!(!Dmp::check_processed_downward_messages(a, 4).is_ok());
+		assert!(!Dmp::check_processed_downward_messages(a, 0, 4).is_ok());
+
+		// a candidate building on ancestors which processed 2 messages may only consume the last
+		// one, and has to.
+		assert!(!Dmp::check_processed_downward_messages(a, 2, 0).is_ok());
+		assert!(Dmp::check_processed_downward_messages(a, 2, 1).is_ok());
+		assert!(!Dmp::check_processed_downward_messages(a, 2, 2).is_ok());
+		// once the ancestors processed everything, nothing is left.
+		assert!(Dmp::check_processed_downward_messages(a, 3, 0).is_ok());
 	});
 }
 
@@ -160,6 +170,76 @@ fn dmq_pruning() {
 	});
 }
 
+#[test]
+fn dmq_is_paged() {
+	let a = ParaId::from(1312);
+	let msgs: Vec<DownwardMessage> = (0..QUEUE_PAGE_CAPACITY + 2).map(|i| i.encode()).collect();
+
+	new_test_ext(default_genesis_config()).execute_with(|| {
+		for msg in &msgs {
+			queue_downward_message(a, msg.clone()).unwrap();
+		}
+
+		assert_eq!(
+			<Dmp as Store>::DownwardMessageQueueState::get(&a),
+			QueueState { begin: 0, end: 2, len: QUEUE_PAGE_CAPACITY + 2 },
+		);
+		assert_eq!(<Dmp as Store>::DownwardMessageQueuePages::get(&a, 1).len(), 2);
+		let contents = |queue: Vec<InboundDownwardMessage<BlockNumber>>| {
+			queue.into_iter().map(|m| m.msg).collect::<Vec<_>>()
+		};
+		assert_eq!(contents(Dmp::dmq_contents(a)), msgs);
+
+		// pruning within the first page keeps the rest of it.
+		Dmp::prune_dmq(a, 3);
+		assert_eq!(contents(Dmp::dmq_contents(a)), msgs[3..].to_vec());
+		assert_eq!(<Dmp as Store>::DownwardMessageQueueState::get(&a).begin, 0);
+
+		// pruning the rest of the first page drops it.
+		Dmp::prune_dmq(a, QUEUE_PAGE_CAPACITY - 2);
+		let rest = msgs[QUEUE_PAGE_CAPACITY as usize + 1..].to_vec();
+		assert_eq!(contents(Dmp::dmq_contents(a)), rest);
+		assert_eq!(
+			<Dmp as Store>::DownwardMessageQueueState::get(&a),
+			QueueState { begin: 1, end: 2, len: 1 },
+		);
+		assert!(!<Dmp as Store>::DownwardMessageQueuePages::contains_key(&a, 0));
+
+		// new messages are appended to the last page.
+		queue_downward_message(a, vec![1, 2, 3]).unwrap();
+		assert_eq!(<Dmp as Store>::DownwardMessageQueuePages::get(&a, 1).len(), 2);
+
+		// an empty queue leaves no trace in the storage.
+		Dmp::prune_dmq(a, 2);
+		assert_eq!(Dmp::dmq_length(a), 0);
+		assert!(!<Dmp as Store>::DownwardMessageQueueState::contains_key(&a));
+		assert_eq!(<Dmp as Store>::DownwardMessageQueuePages::iter_prefix(&a).count(), 0);
+	});
+}
+
+#[test]
+fn delivery_fee_factor_grows_exponentially_beyond_threshold() {
+	let a = ParaId::from(1312);
+
+	new_test_ext(default_genesis_config()).execute_with(|| {
+		for _ in 0..DELIVERY_FEE_THRESHOLD {
+			queue_downward_message(a, vec![1, 2, 3]).unwrap();
+		}
+		assert_eq!(Dmp::delivery_fee_factor(a), FixedU128::from(1));
+
+		queue_downward_message(a, vec![1, 2, 3]).unwrap();
+		assert_eq!(Dmp::delivery_fee_factor(a), EXPONENTIAL_FEE_BASE);
+
+		queue_downward_message(a, vec![1, 2, 3]).unwrap();
+		assert_eq!(Dmp::delivery_fee_factor(a), EXPONENTIAL_FEE_BASE * EXPONENTIAL_FEE_BASE);
+
+		// the factor goes back down once the queue is pruned.
+		Dmp::prune_dmq(a, 2);
+		assert_eq!(Dmp::delivery_fee_factor(a), FixedU128::from(1));
+		assert_eq!(<Dmp as FeeTracker>::get_fee_factor(ParaId::from(228)), FixedU128::from(1));
+	});
+}
+
 #[test]
 fn queue_downward_message_critical() {
 	let a = ParaId::from(1312);
@@ -18,13 +18,17 @@ use crate::{
 	configuration::{self, HostConfiguration},
 	dmp, ensure_parachain, initializer, paras,
 };
-use frame_support::{pallet_prelude::*, traits::ReservableCurrency};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{Contains, ReservableCurrency},
+};
 use frame_system::pallet_prelude::*;
 use parity_scale_codec::{Decode, Encode};
 use polkadot_parachain::primitives::HorizontalMessages;
 use primitives::{
+	vstaging::{HrmpChannelUtilization, HrmpOpenRequest, HrmpTopology},
 	Balance, Hash, HrmpChannelId, Id as ParaId, InboundHrmpMessage, OutboundHrmpMessage,
-	SessionIndex,
+	SessionIndex, LOWEST_PUBLIC_ID,
 };
 use scale_info::TypeInfo;
 use sp_runtime::traits::{AccountIdConversion, BlakeTwo256, Hash as HashT, UniqueSaturatedInto};
@@ -60,6 +64,8 @@ pub trait WeightInfo {
 	fn hrmp_cancel_open_request(c: u32) -> Weight;
 	fn clean_open_channel_requests(c: u32) -> Weight;
 	fn force_open_hrmp_channel() -> Weight;
+	fn establish_system_channels() -> Weight;
+	fn establish_channel_with_system() -> Weight;
 }
 
 /// A weight info that is only suitable for testing.
@@ -93,6 +99,31 @@ impl WeightInfo for TestWeightInfo {
 	fn force_open_hrmp_channel() -> Weight {
 		Weight::MAX
 	}
+	fn establish_system_channels() -> Weight {
+		Weight::MAX
+	}
+	fn establish_channel_with_system() -> Weight {
+		Weight::MAX
+	}
+}
+
+/// Treats every para with an id below [`LOWEST_PUBLIC_ID`] as a system para.
+pub struct IsSystemPara;
+
+impl Contains<ParaId> for IsSystemPara {
+	fn contains(id: &ParaId) -> bool {
+		*id < LOWEST_PUBLIC_ID
+	}
+}
+
+/// The state of a channel between a system para and another para.
+enum SystemChannelState {
+	/// The channel is open.
+	Open,
+	/// There is a deposit-free request for the channel.
+	Requested { confirmed: bool },
+	/// There is neither a channel nor a request.
+	Absent,
 }
 
 /// A description of a request to open an HRMP channel.
@@ -257,6 +288,14 @@ pub mod pallet {
 		/// implementation should be the same as `Balance` as used in the `Configuration`.
 		type Currency: ReservableCurrency<Self::AccountId>;
 
+		/// The paras which count as system paras.
+		///
+		/// Channels with system paras take no deposits and are opened once the system para has
+		/// accepted them, see
+		/// [`establish_system_channels`](Pallet::establish_system_channels) and
+		/// [`establish_channel_with_system`](Pallet::establish_channel_with_system).
+		type SystemParas: Contains<ParaId>;
+
 		/// Something that provides the weight of this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -277,6 +316,12 @@ pub mod pallet {
 		/// An HRMP channel was opened via Root origin.
 		/// `[sender, recipient, proposed_max_capacity, proposed_max_message_size]`
 		HrmpChannelForceOpened(ParaId, ParaId, u32, u32),
+		/// An HRMP channel with a system para was requested without deposits.
+		/// `[sender, recipient, max_capacity, max_message_size]`
+		HrmpSystemChannelRequested(ParaId, ParaId, u32, u32),
+		/// A request for an HRMP channel with a system para was accepted by the system para.
+		/// `[sender, recipient]`
+		HrmpSystemChannelAccepted(ParaId, ParaId),
 	}
 
 	#[pallet::error]
@@ -319,6 +364,8 @@ pub mod pallet {
 		OpenHrmpChannelAlreadyConfirmed,
 		/// The provided witness data is wrong.
 		WrongWitness,
+		/// The para is expected to be a system para, but it is not.
+		NotSystemPara,
 	}
 
 	/// The set of pending HRMP open channel requests.
@@ -352,6 +399,16 @@ pub mod pallet {
 	pub type HrmpAcceptedChannelRequestCount<T: Config> =
 		StorageMap<_, Twox64Concat, ParaId, u32, ValueQuery>;
 
+	/// The open channel requests between a system para and another para, which take no deposits.
+	///
+	/// Such requests are confirmed by the system para, no matter on which side of the channel it
+	/// is.
+	///
+	/// Invariant:
+	/// - Each key is a key of `HrmpOpenChannelRequests` as well.
+	#[pallet::storage]
+	pub type HrmpSystemChannelRequests<T: Config> = StorageMap<_, Twox64Concat, HrmpChannelId, ()>;
+
 	/// A set of pending HRMP close channel requests that are going to be closed during the session
 	/// change. Used for checking if a given channel is registered for closure.
 	///
@@ -619,6 +676,40 @@ pub mod pallet {
 			));
 			Ok(())
 		}
+
+		/// Request and accept channels in both directions between the origin, which must be a
+		/// system para, and the given `para`.
+		///
+		/// No deposits are taken and the channels use the maximum capacity and message size
+		/// allowed by the configuration. Pending requests of `para` made through
+		/// [`establish_channel_with_system`](Pallet::establish_channel_with_system) get accepted.
+		/// Directions in which a channel already exists are skipped.
+		///
+		/// The channels are opened on the next session change.
+		#[pallet::call_index(8)]
+		#[pallet::weight(<T as Config>::WeightInfo::establish_system_channels())]
+		pub fn establish_system_channels(origin: OriginFor<T>, para: ParaId) -> DispatchResult {
+			let origin = ensure_parachain(<T as Config>::RuntimeOrigin::from(origin))?;
+			ensure!(T::SystemParas::contains(&origin), Error::<T>::NotSystemPara);
+			Self::request_system_channels(origin, para, true)
+		}
+
+		/// Request channels in both directions between the origin and the given `system_para`.
+		///
+		/// This is the counterpart of
+		/// [`establish_system_channels`](Pallet::establish_system_channels) for regular paras:
+		/// no deposits are taken, but the channels are only opened on the session change after
+		/// `system_para` accepted them.
+		#[pallet::call_index(9)]
+		#[pallet::weight(<T as Config>::WeightInfo::establish_channel_with_system())]
+		pub fn establish_channel_with_system(
+			origin: OriginFor<T>,
+			system_para: ParaId,
+		) -> DispatchResult {
+			let origin = ensure_parachain(<T as Config>::RuntimeOrigin::from(origin))?;
+			ensure!(T::SystemParas::contains(&system_para), Error::<T>::NotSystemPara);
+			Self::request_system_channels(system_para, origin, false)
+		}
 	}
 }
 
@@ -669,7 +760,7 @@ impl<T: Config> Pallet<T> {
 			outgoing_paras.len() as u32
 		))
 		.saturating_add(<T as Config>::WeightInfo::force_process_hrmp_close(
-			outgoing_paras.len() as u32
+			outgoing_paras.len() as u32,
 		))
 	}
 
@@ -725,6 +816,8 @@ impl<T: Config> Pallet<T> {
 					continue
 				},
 			};
+			let system_request =
+				<Self as Store>::HrmpSystemChannelRequests::take(&req_id).is_some();
 
 			// Return the deposit of the sender, but only if it is not the para being offboarded.
 			if !outgoing.contains(&req_id.sender) {
@@ -740,7 +833,7 @@ impl<T: Config> Pallet<T> {
 			//
 			// We still want to refund the deposit only if the para is not being offboarded.
 			if req_data.confirmed {
-				if !outgoing.contains(&req_id.recipient) {
+				if !outgoing.contains(&req_id.recipient) && !system_request {
 					T::Currency::unreserve(
 						&req_id.recipient.into_account_truncating(),
 						config.hrmp_recipient_deposit.unique_saturated_into(),
@@ -799,6 +892,10 @@ impl<T: Config> Pallet<T> {
 			);
 
 			if request.confirmed {
+				let system_request =
+					<Self as Store>::HrmpSystemChannelRequests::take(&channel_id).is_some();
+				let recipient_deposit =
+					if system_request { 0 } else { config.hrmp_recipient_deposit };
 				if <paras::Pallet<T>>::is_valid_para(channel_id.sender) &&
 					<paras::Pallet<T>>::is_valid_para(channel_id.recipient)
 				{
@@ -806,7 +903,7 @@ impl<T: Config> Pallet<T> {
 						&channel_id,
 						HrmpChannel {
 							sender_deposit: request.sender_deposit,
-							recipient_deposit: config.hrmp_recipient_deposit,
+							recipient_deposit,
 							max_capacity: request.max_capacity,
 							max_total_size: request.max_total_size,
 							max_message_size: request.max_message_size,
@@ -883,20 +980,30 @@ impl<T: Config> Pallet<T> {
 	}
 
 	/// Check that the candidate of the given recipient controls the HRMP watermark properly.
+	///
+	/// `pending_watermark` is the watermark of the last candidate pending availability the
+	/// candidate builds upon, if any. It is not stored in `HrmpWatermarks` yet.
 	pub(crate) fn check_hrmp_watermark(
 		recipient: ParaId,
 		relay_chain_parent_number: T::BlockNumber,
 		new_hrmp_watermark: T::BlockNumber,
+		pending_watermark: Option<T::BlockNumber>,
 	) -> Result<(), HrmpWatermarkAcceptanceErr<T::BlockNumber>> {
 		// First, check where the watermark CANNOT legally land.
 		//
 		// (a) For ensuring that messages are eventually, a rule requires each parablock new
-		//     watermark should be greater than the last one.
+		//     watermark should be greater than the last one. A parablock building on a parablock
+		//     with the same relay-chain context may keep the watermark at the relay parent.
 		//
 		// (b) However, a parachain cannot read into "the future", therefore the watermark should
 		//     not be greater than the relay-chain context block which the parablock refers to.
-		if let Some(last_watermark) = <Self as Store>::HrmpWatermarks::get(&recipient) {
-			if new_hrmp_watermark <= last_watermark {
+		let last_watermark =
+			pending_watermark.or_else(|| <Self as Store>::HrmpWatermarks::get(&recipient));
+		if let Some(last_watermark) = last_watermark {
+			let kept_at_relay_parent = pending_watermark.is_some() &&
+				new_hrmp_watermark == last_watermark &&
+				new_hrmp_watermark == relay_chain_parent_number;
+			if new_hrmp_watermark <= last_watermark && !kept_at_relay_parent {
 				return Err(HrmpWatermarkAcceptanceErr::AdvancementRule {
 					new_watermark: new_hrmp_watermark,
 					last_watermark,
@@ -1232,6 +1339,10 @@ impl<T: Config> Pallet<T> {
 			.ok_or(Error::<T>::AcceptHrmpChannelDoesntExist)?;
 		ensure!(!channel_req.confirmed, Error::<T>::AcceptHrmpChannelAlreadyConfirmed);
 
+		// requests with a system para can only be accepted by the system para and take no deposit.
+		let system_request = <Self as Store>::HrmpSystemChannelRequests::contains_key(&channel_id);
+		ensure!(!system_request || T::SystemParas::contains(&origin), Error::<T>::NotSystemPara);
+
 		// check if by accepting this open channel request, this parachain would exceed the
 		// number of inbound channels.
 		let config = <configuration::Pallet<T>>::config();
@@ -1248,10 +1359,12 @@ impl<T: Config> Pallet<T> {
 			Error::<T>::AcceptHrmpChannelLimitExceeded,
 		);
 
-		T::Currency::reserve(
-			&origin.into_account_truncating(),
-			config.hrmp_recipient_deposit.unique_saturated_into(),
-		)?;
+		if !system_request {
+			T::Currency::reserve(
+				&origin.into_account_truncating(),
+				config.hrmp_recipient_deposit.unique_saturated_into(),
+			)?;
+		}
 
 		// persist the updated open channel request and then increment the number of accepted
 		// channels.
@@ -1280,6 +1393,153 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Request the channels `system_para -> para` and `para -> system_para` without deposits.
+	///
+	/// If `accepted` is set, the system para accepts the channels: pending requests of these
+	/// channels get confirmed and new requests are confirmed right away. The channels are opened
+	/// like any other on the session change, once confirmed.
+	///
+	/// Both directions are checked before any request is made. Directions in which a channel
+	/// exists or which need no action are skipped, but at least one direction must need one.
+	fn request_system_channels(
+		system_para: ParaId,
+		para: ParaId,
+		accepted: bool,
+	) -> DispatchResult {
+		let config = <configuration::Pallet<T>>::config();
+		let outbound = HrmpChannelId { sender: system_para, recipient: para };
+		let inbound = HrmpChannelId { sender: para, recipient: system_para };
+
+		let mut to_request = Vec::with_capacity(2);
+		let mut to_accept = Vec::with_capacity(2);
+		let mut all_open = true;
+		for channel_id in [outbound, inbound] {
+			match Self::check_system_channel(&config, &channel_id)? {
+				SystemChannelState::Open => continue,
+				SystemChannelState::Requested { confirmed } =>
+					if accepted && !confirmed {
+						Self::check_inbound_capacity(&config, channel_id.recipient)?;
+						to_accept.push(channel_id);
+					},
+				SystemChannelState::Absent => to_request.push(channel_id),
+			}
+			all_open = false;
+		}
+		ensure!(!all_open, Error::<T>::OpenHrmpChannelAlreadyExists);
+		ensure!(
+			!to_request.is_empty() || !to_accept.is_empty(),
+			Error::<T>::OpenHrmpChannelAlreadyRequested,
+		);
+
+		// mutating storage directly now -- shall not bail henceforth.
+
+		for channel_id in to_request {
+			<Self as Store>::HrmpOpenChannelRequestCount::mutate(&channel_id.sender, |n| *n += 1);
+			<Self as Store>::HrmpOpenChannelRequests::insert(
+				&channel_id,
+				HrmpOpenChannelRequest {
+					confirmed: false,
+					_age: 0,
+					sender_deposit: 0,
+					max_capacity: config.hrmp_channel_max_capacity,
+					max_message_size: config.hrmp_channel_max_message_size,
+					max_total_size: config.hrmp_channel_max_total_size,
+				},
+			);
+			<Self as Store>::HrmpOpenChannelRequestsList::append(channel_id.clone());
+			<Self as Store>::HrmpSystemChannelRequests::insert(&channel_id, ());
+
+			Self::deposit_event(Event::HrmpSystemChannelRequested(
+				channel_id.sender,
+				channel_id.recipient,
+				config.hrmp_channel_max_capacity,
+				config.hrmp_channel_max_message_size,
+			));
+			if accepted {
+				to_accept.push(channel_id);
+			}
+		}
+
+		for channel_id in to_accept {
+			<Self as Store>::HrmpOpenChannelRequests::mutate(&channel_id, |request| {
+				if let Some(request) = request {
+					request.confirmed = true;
+				}
+			});
+			<Self as Store>::HrmpAcceptedChannelRequestCount::mutate(&channel_id.recipient, |n| {
+				*n += 1
+			});
+
+			Self::deposit_event(Event::HrmpSystemChannelAccepted(
+				channel_id.sender,
+				channel_id.recipient,
+			));
+		}
+
+		Ok(())
+	}
+
+	/// Check the given channel between a system para and another para.
+	///
+	/// Fails if there is a regular open request for the channel already. A channel which is
+	/// `Absent` is still subject to the channel limits of both participants.
+	fn check_system_channel(
+		config: &HostConfiguration<T::BlockNumber>,
+		channel_id: &HrmpChannelId,
+	) -> Result<SystemChannelState, DispatchError> {
+		let (sender, recipient) = (channel_id.sender, channel_id.recipient);
+		ensure!(sender != recipient, Error::<T>::OpenHrmpChannelToSelf);
+		ensure!(
+			<paras::Pallet<T>>::is_valid_para(sender) &&
+				<paras::Pallet<T>>::is_valid_para(recipient),
+			Error::<T>::OpenHrmpChannelInvalidRecipient,
+		);
+
+		if <Self as Store>::HrmpChannels::contains_key(channel_id) {
+			return Ok(SystemChannelState::Open)
+		}
+		if let Some(request) = <Self as Store>::HrmpOpenChannelRequests::get(channel_id) {
+			ensure!(
+				<Self as Store>::HrmpSystemChannelRequests::contains_key(channel_id),
+				Error::<T>::OpenHrmpChannelAlreadyRequested,
+			);
+			return Ok(SystemChannelState::Requested { confirmed: request.confirmed })
+		}
+
+		let egress_cnt =
+			<Self as Store>::HrmpEgressChannelsIndex::decode_len(&sender).unwrap_or(0) as u32;
+		let open_req_cnt = <Self as Store>::HrmpOpenChannelRequestCount::get(&sender);
+		let egress_limit = if <paras::Pallet<T>>::is_parathread(sender) {
+			config.hrmp_max_parathread_outbound_channels
+		} else {
+			config.hrmp_max_parachain_outbound_channels
+		};
+		ensure!(egress_cnt + open_req_cnt < egress_limit, Error::<T>::OpenHrmpChannelLimitExceeded);
+		Self::check_inbound_capacity(config, recipient)?;
+
+		Ok(SystemChannelState::Absent)
+	}
+
+	/// Check that `recipient` can accept one more inbound channel.
+	fn check_inbound_capacity(
+		config: &HostConfiguration<T::BlockNumber>,
+		recipient: ParaId,
+	) -> DispatchResult {
+		let ingress_cnt =
+			<Self as Store>::HrmpIngressChannelsIndex::decode_len(&recipient).unwrap_or(0) as u32;
+		let accepted_cnt = <Self as Store>::HrmpAcceptedChannelRequestCount::get(&recipient);
+		let ingress_limit = if <paras::Pallet<T>>::is_parathread(recipient) {
+			config.hrmp_max_parathread_inbound_channels
+		} else {
+			config.hrmp_max_parachain_inbound_channels
+		};
+		ensure!(
+			ingress_cnt + accepted_cnt < ingress_limit,
+			Error::<T>::AcceptHrmpChannelLimitExceeded,
+		);
+		Ok(())
+	}
+
 	fn cancel_open_request(origin: ParaId, channel_id: HrmpChannelId) -> DispatchResult {
 		// check if the origin is allowed to close the channel.
 		ensure!(channel_id.is_participant(origin), Error::<T>::CancelHrmpOpenChannelUnauthorized);
@@ -1290,6 +1550,7 @@ impl<T: Config> Pallet<T> {
 
 		// Remove the request by the channel id and sync the accompanying list with the set.
 		<Self as Store>::HrmpOpenChannelRequests::remove(&channel_id);
+		<Self as Store>::HrmpSystemChannelRequests::remove(&channel_id);
 		<Self as Store>::HrmpOpenChannelRequestsList::mutate(|open_req_channels| {
 			if let Some(pos) = open_req_channels.iter().position(|x| x == &channel_id) {
 				open_req_channels.swap_remove(pos);
@@ -1395,6 +1656,49 @@ impl<T: Config> Pallet<T> {
 
 		inbound_hrmp_channels_contents
 	}
+
+	/// Returns the open channels of the given para along with their utilization, and the pending
+	/// requests to open a channel in which it participates.
+	pub fn topology(para: ParaId) -> HrmpTopology {
+		let utilization = |channel_id: HrmpChannelId, counterparty: ParaId| {
+			let channel = <Self as Store>::HrmpChannels::get(&channel_id)?;
+			Some(HrmpChannelUtilization {
+				counterparty,
+				max_capacity: channel.max_capacity,
+				max_total_size: channel.max_total_size,
+				max_message_size: channel.max_message_size,
+				msg_count: channel.msg_count,
+				total_size: channel.total_size,
+				closing: <Self as Store>::HrmpCloseChannelRequests::contains_key(&channel_id),
+			})
+		};
+
+		let inbound = <Self as Store>::HrmpIngressChannelsIndex::get(&para)
+			.into_iter()
+			.filter_map(|sender| utilization(HrmpChannelId { sender, recipient: para }, sender))
+			.collect();
+		let outbound = <Self as Store>::HrmpEgressChannelsIndex::get(&para)
+			.into_iter()
+			.filter_map(|recipient| {
+				utilization(HrmpChannelId { sender: para, recipient }, recipient)
+			})
+			.collect();
+		let open_requests = <Self as Store>::HrmpOpenChannelRequestsList::get()
+			.into_iter()
+			.filter(|channel_id| channel_id.is_participant(para))
+			.filter_map(|channel_id| {
+				let request = <Self as Store>::HrmpOpenChannelRequests::get(&channel_id)?;
+				Some(HrmpOpenRequest {
+					channel_id,
+					confirmed: request.confirmed,
+					max_capacity: request.max_capacity,
+					max_message_size: request.max_message_size,
+				})
+			})
+			.collect();
+
+		HrmpTopology { inbound, outbound, open_requests }
+	}
 }
 
 impl<T: Config> Pallet<T> {
@@ -1446,6 +1750,11 @@ impl<T: Config> Pallet<T> {
 				.collect::<BTreeSet<_>>(),
 		);
 
+		// every system channel request is an open channel request.
+		for (channel_id, ()) in <Self as Store>::HrmpSystemChannelRequests::iter() {
+			assert!(<Self as Store>::HrmpOpenChannelRequests::contains_key(&channel_id));
+		}
+
 		// verify that the set of keys in `HrmpOpenChannelRequestCount` corresponds to the set
 		// of _senders_ in `HrmpOpenChannelRequests`.
 		//
@@ -21,6 +21,7 @@ use crate::{
 	shared::Pallet as Shared,
 };
 use frame_support::{assert_ok, traits::Currency};
+use sp_runtime::traits::Zero;
 
 type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
@@ -322,6 +323,52 @@ frame_benchmarking::benchmarks! {
 			Event::<T>::HrmpChannelForceOpened(sender_id, recipient_id, capacity, message_size).into()
 		);
 	}
+
+	establish_system_channels {
+		let system_id: ParaId = 1u32.into();
+		let system_origin: crate::Origin = 1u32.into();
+		let para_id: ParaId = LOWEST_PUBLIC_ID;
+		assert!(T::SystemParas::contains(&system_id));
+
+		// no deposits are taken.
+		register_parachain_with_balance::<T>(system_id, Zero::zero());
+		register_parachain_with_balance::<T>(para_id, Zero::zero());
+
+		let capacity = Configuration::<T>::config().hrmp_channel_max_capacity;
+		let message_size = Configuration::<T>::config().hrmp_channel_max_message_size;
+	}: _(system_origin, para_id)
+	verify {
+		// worst case: both directions are requested and accepted.
+		let channel_id = HrmpChannelId { sender: system_id, recipient: para_id };
+		let request = HrmpOpenChannelRequests::<T>::get(&channel_id).unwrap();
+		assert!(request.confirmed);
+		assert_eq!(request.max_capacity, capacity);
+		assert_eq!(request.max_message_size, message_size);
+		assert_last_event::<T>(Event::<T>::HrmpSystemChannelAccepted(para_id, system_id).into());
+	}
+
+	establish_channel_with_system {
+		let system_id: ParaId = 1u32.into();
+		let para_id: ParaId = LOWEST_PUBLIC_ID;
+		let para_origin = crate::Origin::Parachain(para_id);
+		assert!(T::SystemParas::contains(&system_id));
+
+		// no deposits are taken.
+		register_parachain_with_balance::<T>(system_id, Zero::zero());
+		register_parachain_with_balance::<T>(para_id, Zero::zero());
+
+		let capacity = Configuration::<T>::config().hrmp_channel_max_capacity;
+		let message_size = Configuration::<T>::config().hrmp_channel_max_message_size;
+	}: _(para_origin, system_id)
+	verify {
+		// worst case: both directions are requested.
+		let channel_id = HrmpChannelId { sender: system_id, recipient: para_id };
+		assert!(HrmpSystemChannelRequests::<T>::contains_key(&channel_id));
+		assert_last_event::<T>(
+			Event::<T>::HrmpSystemChannelRequested(para_id, system_id, capacity, message_size)
+				.into()
+		);
+	}
 }
 
 frame_benchmarking::impl_benchmark_test_suite!(
@@ -290,7 +290,12 @@ fn send_recv_messages() {
 		// On Block 7:
 		// B receives the message sent by A. B sets the watermark to 6.
 		run_to_block(7, None);
-		assert!(Hrmp::check_hrmp_watermark(para_b, 7, 6).is_ok());
+		assert!(Hrmp::check_hrmp_watermark(para_b, 7, 6, None).is_ok());
+		// A candidate building on a pending candidate with watermark 6 has to advance it, unless
+		// both keep it at the relay parent.
+		assert!(Hrmp::check_hrmp_watermark(para_b, 7, 6, Some(6)).is_err());
+		assert!(Hrmp::check_hrmp_watermark(para_b, 7, 7, Some(6)).is_ok());
+		assert!(Hrmp::check_hrmp_watermark(para_b, 7, 7, Some(7)).is_ok());
 		let _ = Hrmp::prune_hrmp(para_b, 6);
 		Hrmp::assert_storage_consistency_exhaustive();
 	});
@@ -657,3 +662,236 @@ fn cancel_pending_open_channel_request() {
 		Hrmp::assert_storage_consistency_exhaustive();
 	});
 }
+
+#[test]
+fn establish_system_channels_works() {
+	let system_para = 1.into();
+	let system_para_origin: crate::Origin = 1.into();
+	let para = 2000.into();
+	let para_origin: crate::Origin = 2000.into();
+
+	let mut genesis = GenesisConfigBuilder::default();
+	genesis.hrmp_sender_deposit = 20;
+	genesis.hrmp_recipient_deposit = 15;
+	new_test_ext(genesis.build()).execute_with(|| {
+		register_parachain_with_balance(system_para, 100);
+		register_parachain_with_balance(para, 100);
+		run_to_block(5, Some(vec![4, 5]));
+
+		// only system paras can establish system channels.
+		assert_noop!(
+			Hrmp::establish_system_channels(para_origin.into(), system_para),
+			Error::<Test>::NotSystemPara,
+		);
+
+		assert_ok!(Hrmp::establish_system_channels(system_para_origin.clone().into(), para));
+		Hrmp::assert_storage_consistency_exhaustive();
+		for (sender, recipient) in [(system_para, para), (para, system_para)] {
+			assert!(System::events().iter().any(|record| record.event ==
+				MockEvent::Hrmp(Event::HrmpSystemChannelRequested(sender, recipient, 2, 8))));
+			assert!(System::events().iter().any(|record| record.event ==
+				MockEvent::Hrmp(Event::HrmpSystemChannelAccepted(sender, recipient))));
+		}
+
+		// the requests are accepted already, but the channels only open on the session change.
+		assert_noop!(
+			Hrmp::establish_system_channels(system_para_origin.clone().into(), para),
+			Error::<Test>::OpenHrmpChannelAlreadyRequested,
+		);
+		assert!(!channel_exists(system_para, para));
+		assert!(!channel_exists(para, system_para));
+		run_to_block(6, Some(vec![6]));
+		assert!(channel_exists(system_para, para));
+		assert!(channel_exists(para, system_para));
+		Hrmp::assert_storage_consistency_exhaustive();
+
+		// no deposits were taken.
+		for id in [system_para, para] {
+			assert_eq!(
+				<Test as Config>::Currency::free_balance(&id.into_account_truncating()),
+				100
+			);
+		}
+
+		assert_noop!(
+			Hrmp::establish_system_channels(system_para_origin.into(), para),
+			Error::<Test>::OpenHrmpChannelAlreadyExists,
+		);
+
+		// closing a system channel returns no deposits.
+		let channel_id = HrmpChannelId { sender: para, recipient: system_para };
+		assert_ok!(Hrmp::close_channel(para, channel_id));
+		run_to_block(8, Some(vec![8]));
+		assert!(!channel_exists(para, system_para));
+		assert!(channel_exists(system_para, para));
+		assert_eq!(<Test as Config>::Currency::free_balance(&para.into_account_truncating()), 100);
+		Hrmp::assert_storage_consistency_exhaustive();
+	});
+}
+
+#[test]
+fn establish_channel_with_system_works() {
+	let system_para = 1.into();
+	let system_para_origin: crate::Origin = 1.into();
+	let para = 2000.into();
+	let para_origin: crate::Origin = 2000.into();
+	let other_para = 2001.into();
+
+	let mut genesis = GenesisConfigBuilder::default();
+	genesis.hrmp_sender_deposit = 20;
+	genesis.hrmp_recipient_deposit = 15;
+	new_test_ext(genesis.build()).execute_with(|| {
+		register_parachain_with_balance(system_para, 100);
+		register_parachain_with_balance(para, 100);
+		register_parachain_with_balance(other_para, 100);
+		run_to_block(5, Some(vec![4, 5]));
+
+		assert_noop!(
+			Hrmp::establish_channel_with_system(para_origin.clone().into(), other_para),
+			Error::<Test>::NotSystemPara,
+		);
+
+		// a pending regular request has to be canceled first.
+		Hrmp::init_open_channel(system_para, para, 2, 8).unwrap();
+		assert_noop!(
+			Hrmp::establish_channel_with_system(para_origin.clone().into(), system_para),
+			Error::<Test>::OpenHrmpChannelAlreadyRequested,
+		);
+
+		// an existing channel is skipped.
+		Hrmp::accept_open_channel(para, system_para).unwrap();
+		run_to_block(6, Some(vec![6]));
+		assert!(channel_exists(system_para, para));
+		System::reset_events();
+
+		assert_ok!(Hrmp::establish_channel_with_system(para_origin.clone().into(), system_para));
+		Hrmp::assert_storage_consistency_exhaustive();
+		assert_eq!(
+			System::events()
+				.into_iter()
+				.filter(|record| matches!(record.event, MockEvent::Hrmp(_)))
+				.map(|record| record.event)
+				.collect::<Vec<_>>(),
+			vec![MockEvent::Hrmp(Event::HrmpSystemChannelRequested(para, system_para, 2, 8))],
+		);
+		assert_noop!(
+			Hrmp::establish_channel_with_system(para_origin.into(), system_para),
+			Error::<Test>::OpenHrmpChannelAlreadyRequested,
+		);
+
+		// the request is not opened without the consent of the system para.
+		run_to_block(7, Some(vec![7]));
+		assert!(!channel_exists(para, system_para));
+
+		// the system para accepts the request without a deposit.
+		assert_ok!(Hrmp::establish_system_channels(system_para_origin.into(), para));
+		run_to_block(8, Some(vec![8]));
+		assert!(channel_exists(para, system_para));
+		Hrmp::assert_storage_consistency_exhaustive();
+
+		// only the deposits of the regular channel are held.
+		assert_eq!(
+			<Test as Config>::Currency::free_balance(&system_para.into_account_truncating()),
+			80
+		);
+		assert_eq!(<Test as Config>::Currency::free_balance(&para.into_account_truncating()), 85);
+	});
+}
+
+#[test]
+fn system_channel_requests_need_system_para_acceptance() {
+	let system_para = 1.into();
+	let para = 2000.into();
+	let para_origin: crate::Origin = 2000.into();
+
+	let mut genesis = GenesisConfigBuilder::default();
+	genesis.hrmp_sender_deposit = 20;
+	genesis.hrmp_recipient_deposit = 15;
+	new_test_ext(genesis.build()).execute_with(|| {
+		register_parachain_with_balance(system_para, 100);
+		register_parachain_with_balance(para, 100);
+		run_to_block(5, Some(vec![4, 5]));
+
+		assert_ok!(Hrmp::establish_channel_with_system(para_origin.into(), system_para));
+
+		// the para cannot accept the request into itself on behalf of the system para.
+		assert_noop!(Hrmp::accept_open_channel(para, system_para), Error::<Test>::NotSystemPara);
+
+		// the system para can accept the request into itself like a regular one, but without a
+		// deposit.
+		assert_ok!(Hrmp::accept_open_channel(system_para, para));
+		run_to_block(6, Some(vec![6]));
+		assert!(channel_exists(para, system_para));
+		assert!(!channel_exists(system_para, para));
+		assert_eq!(
+			<Test as Config>::Currency::free_balance(&system_para.into_account_truncating()),
+			100
+		);
+
+		// the remaining request can be canceled by either side and refunds nothing.
+		let channel_id = HrmpChannelId { sender: system_para, recipient: para };
+		assert_ok!(Hrmp::cancel_open_request(para, channel_id));
+		assert_eq!(<Test as Config>::Currency::free_balance(&para.into_account_truncating()), 100);
+		Hrmp::assert_storage_consistency_exhaustive();
+	});
+}
+
+#[test]
+fn topology_reports_channels_and_requests() {
+	let para_a = 32.into();
+	let para_b = 64.into();
+	let para_c = 96.into();
+
+	let mut genesis = GenesisConfigBuilder::default();
+	genesis.hrmp_channel_max_message_size = 20;
+	genesis.hrmp_channel_max_total_size = 20;
+	new_test_ext(genesis.build()).execute_with(|| {
+		register_parachain(para_a);
+		register_parachain(para_b);
+		register_parachain(para_c);
+
+		run_to_block(5, Some(vec![4, 5]));
+		assert_eq!(Hrmp::topology(para_a), Default::default());
+
+		Hrmp::init_open_channel(para_a, para_b, 2, 20).unwrap();
+		Hrmp::accept_open_channel(para_b, para_a).unwrap();
+		Hrmp::init_open_channel(para_b, para_a, 2, 8).unwrap();
+		Hrmp::accept_open_channel(para_a, para_b).unwrap();
+		run_to_block(6, Some(vec![6]));
+
+		let msgs: HorizontalMessages =
+			vec![OutboundHrmpMessage { recipient: para_b, data: b"knock knock".to_vec() }]
+				.try_into()
+				.unwrap();
+		let _ = Hrmp::queue_outbound_hrmp(para_a, msgs);
+		Hrmp::close_channel(para_a, HrmpChannelId { sender: para_b, recipient: para_a }).unwrap();
+		Hrmp::init_open_channel(para_c, para_a, 1, 4).unwrap();
+
+		let channel = |counterparty, max_message_size, msg_count, total_size, closing| {
+			HrmpChannelUtilization {
+				counterparty,
+				max_capacity: 2,
+				max_total_size: 20,
+				max_message_size,
+				msg_count,
+				total_size,
+				closing,
+			}
+		};
+		assert_eq!(
+			Hrmp::topology(para_a),
+			HrmpTopology {
+				inbound: vec![channel(para_b, 8, 0, 0, true)],
+				outbound: vec![channel(para_b, 20, 1, 11, false)],
+				open_requests: vec![HrmpOpenRequest {
+					channel_id: HrmpChannelId { sender: para_c, recipient: para_a },
+					confirmed: false,
+					max_capacity: 1,
+					max_message_size: 4,
+				}],
+			},
+		);
+		assert_eq!(Hrmp::topology(para_c).open_requests.len(), 1);
+		assert!(Hrmp::topology(para_c).inbound.is_empty());
+	});
+}
@@ -21,7 +21,7 @@
 //! to included.
 
 use crate::{
-	configuration, disputes, dmp, hrmp, paras, paras_inherent::DisputedBitfield,
+	configuration, disputes, dmp, hrmp, paras, paras_inherent::DisputedBitfield, performance,
 	scheduler::CoreAssignment, shared, ump,
 };
 use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
@@ -35,7 +35,10 @@ use primitives::{
 };
 use scale_info::TypeInfo;
 use sp_runtime::{traits::One, DispatchError};
-use sp_std::{collections::btree_set::BTreeSet, prelude::*};
+use sp_std::{
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	prelude::*,
+};
 
 pub use pallet::*;
 
@@ -89,6 +92,47 @@ pub struct CandidatePendingAvailability<H, N> {
 	backing_group: GroupIndex,
 }
 
+/// A chain of candidates pending availability of a single para, in chain order, along with their
+/// commitments.
+///
+/// The commitments are `None` only if the storage is inconsistent.
+type PendingChain<H, N> = Vec<(CandidatePendingAvailability<H, N>, Option<CandidateCommitments>)>;
+
+/// What a new candidate of a para has to build upon.
+#[derive(Clone)]
+pub(crate) struct CandidateParent<N> {
+	/// The head data of the parent.
+	pub(crate) head: HeadData,
+	/// The outputs of the candidates the candidate builds upon which are not enacted yet, if
+	/// the candidate extends a chain of candidates pending availability.
+	pub(crate) pending: Option<PendingOutputs<N>>,
+}
+
+/// The outputs of a chain of candidates pending availability which are not enacted yet.
+#[derive(Clone)]
+pub(crate) struct PendingOutputs<N> {
+	/// The number of downward messages processed by the chain.
+	processed_downward_messages: u32,
+	/// The HRMP watermark of the last candidate of the chain.
+	hrmp_watermark: N,
+}
+
+impl<N: From<u32>> CandidateParent<N> {
+	/// The parent of a candidate building on a candidate with the given commitments, which builds
+	/// on `self`.
+	pub(crate) fn child(&self, commitments: &CandidateCommitments) -> Self {
+		let processed = self.pending.as_ref().map_or(0, |p| p.processed_downward_messages);
+		CandidateParent {
+			head: commitments.head_data.clone(),
+			pending: Some(PendingOutputs {
+				processed_downward_messages: processed
+					.saturating_add(commitments.processed_downward_messages),
+				hrmp_watermark: commitments.hrmp_watermark.into(),
+			}),
+		}
+	}
+}
+
 impl<H, N> CandidatePendingAvailability<H, N> {
 	/// Get the availability votes on the candidate.
 	pub(crate) fn availability_votes(&self) -> &BitVec<u8, BitOrderLsb0> {
@@ -201,6 +245,8 @@ pub mod pallet {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		type DisputesHandler: disputes::DisputesHandler<Self::BlockNumber>;
 		type RewardValidators: RewardValidators;
+		/// Accounts the backing and bitfield participation of validators.
+		type RecordPerformance: performance::RecordPerformance;
 	}
 
 	#[pallet::event]
@@ -277,6 +323,9 @@ pub mod pallet {
 		/// either intentionally or as part of a concluded
 		/// invalid dispute.
 		BitfieldReferencesFreedCore,
+		/// A candidate building on a candidate pending availability sent upward or horizontal
+		/// messages.
+		MessagesFromPendingDescendant,
 	}
 
 	/// The latest bitfield for each validator, referred to by their index in the validator set.
@@ -294,6 +343,19 @@ pub mod pallet {
 	pub(crate) type PendingAvailabilityCommitments<T: Config> =
 		StorageMap<_, Twox64Concat, ParaId, CandidateCommitments>;
 
+	/// Candidates pending availability which build on top of the candidate in
+	/// `PendingAvailability` of the same para, in chain order, along with their commitments.
+	///
+	/// Only parachains assigned more than one core can have descendants pending availability.
+	#[pallet::storage]
+	pub(crate) type PendingAvailabilityDescendants<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		ParaId,
+		Vec<(CandidatePendingAvailability<T::Hash, T::BlockNumber>, CandidateCommitments)>,
+		ValueQuery,
+	>;
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {}
 }
@@ -317,6 +379,7 @@ impl<T: Config> Pallet<T> {
 		// and require consumption.
 		for _ in <PendingAvailabilityCommitments<T>>::drain() {}
 		for _ in <PendingAvailability<T>>::drain() {}
+		for _ in <PendingAvailabilityDescendants<T>>::drain() {}
 		for _ in <AvailabilityBitfields<T>>::drain() {}
 	}
 
@@ -333,10 +396,25 @@ impl<T: Config> Pallet<T> {
 	where
 		F: Fn(CoreIndex) -> Option<ParaId>,
 	{
-		let mut assigned_paras_record = (0..expected_bits)
-			.map(|bit_index| core_lookup(CoreIndex::from(bit_index as u32)))
-			.map(|opt_para_id| {
-				opt_para_id.map(|para_id| (para_id, PendingAvailability::<T>::get(&para_id)))
+		// The chains of candidates pending availability of all paras occupying a core, in the
+		// order of their first core, along with the position of the candidate occupying each core
+		// within the chain of its para.
+		let mut pending_chains = Vec::new();
+		let mut chain_indices = BTreeMap::new();
+		let core_positions = (0..expected_bits)
+			.map(|bit_index| {
+				let core = CoreIndex::from(bit_index as u32);
+				let para_id = core_lookup(core)?;
+				let chain_index = *chain_indices.entry(para_id).or_insert_with(|| {
+					pending_chains.push((para_id, Self::pending_chain(&para_id)));
+					pending_chains.len() - 1
+				});
+
+				pending_chains[chain_index]
+					.1
+					.iter()
+					.position(|(pending_availability, _)| pending_availability.core == core)
+					.map(|position| (chain_index, position))
 			})
 			.collect::<Vec<_>>();
 
@@ -349,23 +427,22 @@ impl<T: Config> Pallet<T> {
 				(checked_bitfield, validator_idx)
 			}) {
 			for (bit_idx, _) in checked_bitfield.0.iter().enumerate().filter(|(_, is_av)| **is_av) {
-				let pending_availability = if let Some((_, pending_availability)) =
-					assigned_paras_record[bit_idx].as_mut()
-				{
-					pending_availability
+				let (chain_index, position) = if let Some(core_position) = core_positions[bit_idx] {
+					core_position
 				} else {
 					// For honest validators, this happens in case of unoccupied cores,
 					// which in turn happens in case of a disputed candidate.
 					// A malicious one might include arbitrary indices, but they are represented
-					// by `None` values and will be sorted out in the next if case.
+					// by `None` values and will be sorted out here.
 					continue
 				};
 
-				// defensive check - this is constructed by loading the availability bitfield record,
-				// which is always `Some` if the core is occupied - that's why we're here.
+				// defensive check - the position was looked up in the very same chain above.
 				let validator_index = validator_index.0 as usize;
-				if let Some(mut bit) =
-					pending_availability.as_mut().and_then(|candidate_pending_availability| {
+				if let Some(mut bit) = pending_chains[chain_index]
+					.1
+					.get_mut(position)
+					.and_then(|(candidate_pending_availability, _)| {
 						candidate_pending_availability.availability_votes.get_mut(validator_index)
 					}) {
 					*bit = true;
@@ -381,14 +458,34 @@ impl<T: Config> Pallet<T> {
 		let threshold = availability_threshold(validators.len());
 
 		let mut freed_cores = Vec::with_capacity(expected_bits);
-		for (para_id, pending_availability) in assigned_paras_record
-			.into_iter()
-			.flatten()
-			.filter_map(|(id, p)| p.map(|p| (id, p)))
-		{
-			if pending_availability.availability_votes.count_ones() >= threshold {
-				<PendingAvailability<T>>::remove(&para_id);
-				let commitments = match PendingAvailabilityCommitments::<T>::take(&para_id) {
+		for (para_id, chain) in pending_chains {
+			// Candidates are enacted in chain order, so a candidate stays pending availability
+			// until all of its ancestors became available as well.
+			let n_available = chain
+				.iter()
+				.take_while(|(pending_availability, _)| {
+					pending_availability.availability_votes.count_ones() >= threshold
+				})
+				.count();
+
+			if n_available == 0 {
+				// Only the availability votes changed.
+				let mut chain = chain.into_iter();
+				if let Some((pending_availability, _)) = chain.next() {
+					<PendingAvailability<T>>::insert(&para_id, &pending_availability);
+				}
+				let descendants = chain
+					.filter_map(|(pending, commitments)| commitments.map(|c| (pending, c)))
+					.collect::<Vec<_>>();
+				if !descendants.is_empty() {
+					<PendingAvailabilityDescendants<T>>::insert(&para_id, descendants);
+				}
+				continue
+			}
+
+			let mut chain = chain.into_iter();
+			for (pending_availability, commitments) in chain.by_ref().take(n_available) {
+				let commitments = match commitments {
 					Some(commitments) => commitments,
 					None => {
 						log::warn!(
@@ -416,11 +513,14 @@ impl<T: Config> Pallet<T> {
 				}
 
 				freed_cores.push((pending_availability.core, pending_availability.hash));
-			} else {
-				<PendingAvailability<T>>::insert(&para_id, &pending_availability);
 			}
+
+			Self::set_pending_chain(&para_id, chain.collect());
 		}
 
+		// The candidates of a chain may occupy the cores of their para in any order.
+		freed_cores.sort_unstable_by_key(|(core, _)| *core);
+
 		freed_cores
 	}
 
@@ -449,6 +549,10 @@ impl<T: Config> Pallet<T> {
 			full_check,
 		)?;
 
+		T::RecordPerformance::note_bitfields_included(
+			checked_bitfields.iter().map(|bitfield| bitfield.unchecked_validator_index()),
+		);
+
 		let freed_cores = Self::update_pending_availability_and_get_freed_cores::<_>(
 			expected_bits,
 			&validators[..],
@@ -465,6 +569,10 @@ impl<T: Config> Pallet<T> {
 	///
 	/// Both should be sorted ascending by core index, and the candidates should be a subset of
 	/// scheduled cores. If these conditions are not met, the execution of the function fails.
+	///
+	/// Multiple candidates of a para assigned more than one core must appear in chain order, each
+	/// of them building on the head of the previous one. They are assigned to the scheduled cores
+	/// of the para in ascending order.
 	pub(crate) fn process_candidates<GV>(
 		parent_storage_root: T::Hash,
 		candidates: Vec<BackedCandidate<T::Hash>>,
@@ -512,6 +620,9 @@ impl<T: Config> Pallet<T> {
 			let signing_context =
 				SigningContext { parent_hash, session_index: shared::Pallet::<T>::session_index() };
 
+			// The parent of the next candidate of each para which was backed so far.
+			let mut chain_tails = BTreeMap::<ParaId, CandidateParent<T::BlockNumber>>::new();
+
 			// We combine an outer loop over candidates with an inner loop over the scheduled,
 			// where each iteration of the outer loop picks up at the position
 			// in scheduled just after the past iteration left off.
@@ -525,11 +636,18 @@ impl<T: Config> Pallet<T> {
 			'next_backed_candidate: for (candidate_idx, backed_candidate) in
 				candidates.iter().enumerate()
 			{
+				let para_id = backed_candidate.descriptor().para_id;
+				let parent = match chain_tails.get(&para_id) {
+					Some(parent) => Some(parent.clone()),
+					None => Self::expected_parent(para_id),
+				};
+
 				match check_ctx.verify_backed_candidate(
 					parent_hash,
 					parent_storage_root,
 					candidate_idx,
 					backed_candidate,
+					parent.as_ref(),
 				)? {
 					Err(FailedToCreatePVD) => {
 						log::debug!(
@@ -546,7 +664,6 @@ impl<T: Config> Pallet<T> {
 					Ok(rpn) => rpn,
 				}
 
-				let para_id = backed_candidate.descriptor().para_id;
 				let mut backers = bitvec::bitvec![u8, BitOrderLsb0; 0; validators.len()];
 
 				for (i, assignment) in scheduled[skip..].iter().enumerate() {
@@ -560,9 +677,13 @@ impl<T: Config> Pallet<T> {
 							);
 						}
 
+						// Only a para assigned more than one core may extend its chain of
+						// candidates pending availability.
+						let has_pending = <PendingAvailability<T>>::contains_key(&para_id);
 						ensure!(
-							<PendingAvailability<T>>::get(&para_id).is_none() &&
-								<PendingAvailabilityCommitments<T>>::get(&para_id).is_none(),
+							has_pending ==
+								<PendingAvailabilityCommitments<T>>::contains_key(&para_id) &&
+								(!has_pending || <paras::Pallet<T>>::core_count(para_id) > 1),
 							Error::<T>::CandidateScheduledBeforeParaFree,
 						);
 
@@ -625,6 +746,10 @@ impl<T: Config> Pallet<T> {
 							backers,
 							assignment.group_idx,
 						));
+						if let Some(parent) = parent {
+							let child = parent.child(&backed_candidate.candidate.commitments);
+							chain_tails.insert(para_id, child);
+						}
 						continue 'next_backed_candidate
 					}
 				}
@@ -666,20 +791,27 @@ impl<T: Config> Pallet<T> {
 			let (descriptor, commitments) =
 				(candidate.candidate.descriptor, candidate.candidate.commitments);
 
-			<PendingAvailability<T>>::insert(
-				&para_id,
-				CandidatePendingAvailability {
-					core,
-					hash: candidate_hash,
-					descriptor,
-					availability_votes,
-					relay_parent_number,
-					backers: backers.to_bitvec(),
-					backed_in_number: check_ctx.now,
-					backing_group: group,
-				},
-			);
-			<PendingAvailabilityCommitments<T>>::insert(&para_id, commitments);
+			let pending_availability = CandidatePendingAvailability {
+				core,
+				hash: candidate_hash,
+				descriptor,
+				availability_votes,
+				relay_parent_number,
+				backers: backers.to_bitvec(),
+				backed_in_number: check_ctx.now,
+				backing_group: group,
+			};
+
+			if <PendingAvailability<T>>::contains_key(&para_id) {
+				// The candidate extends the chain of candidates pending availability.
+				<PendingAvailabilityDescendants<T>>::append(
+					&para_id,
+					(pending_availability, commitments),
+				);
+			} else {
+				<PendingAvailability<T>>::insert(&para_id, pending_availability);
+				<PendingAvailabilityCommitments<T>>::insert(&para_id, commitments);
+			}
 		}
 
 		Ok(ProcessedCandidates::<T::Hash> {
@@ -698,9 +830,11 @@ impl<T: Config> Pallet<T> {
 		let now = <frame_system::Pallet<T>>::block_number();
 		let relay_parent_number = now;
 		let check_ctx = CandidateCheckContext::<T>::new(now, relay_parent_number);
+		let pending = Self::expected_parent(para_id).and_then(|parent| parent.pending);
 
 		if let Err(err) = check_ctx.check_validation_outputs(
 			para_id,
+			pending.as_ref(),
 			&validation_outputs.head_data,
 			&validation_outputs.new_validation_code,
 			validation_outputs.processed_downward_messages,
@@ -732,13 +866,15 @@ impl<T: Config> Pallet<T> {
 		let commitments = receipt.commitments;
 		let config = <configuration::Pallet<T>>::config();
 
-		T::RewardValidators::reward_backing(
+		let backing_validators = || {
 			backers
 				.iter()
 				.enumerate()
 				.filter(|(_, backed)| **backed)
-				.map(|(i, _)| ValidatorIndex(i as _)),
-		);
+				.map(|(i, _)| ValidatorIndex(i as _))
+		};
+		T::RewardValidators::reward_backing(backing_validators());
+		T::RecordPerformance::note_backed(backing_validators());
 
 		T::RewardValidators::reward_bitfields(
 			availability_votes
@@ -801,55 +937,62 @@ impl<T: Config> Pallet<T> {
 	pub(crate) fn collect_pending(
 		pred: impl Fn(CoreIndex, T::BlockNumber) -> bool,
 	) -> Vec<CoreIndex> {
-		let mut cleaned_up_ids = Vec::new();
 		let mut cleaned_up_cores = Vec::new();
 
-		for (para_id, pending_record) in <PendingAvailability<T>>::iter() {
-			if pred(pending_record.core, pending_record.backed_in_number) {
-				cleaned_up_ids.push(para_id);
-				cleaned_up_cores.push(pending_record.core);
-			}
-		}
+		for para_id in <PendingAvailability<T>>::iter_keys().collect::<Vec<_>>() {
+			let mut chain = Self::pending_chain(&para_id);
 
-		for para_id in cleaned_up_ids {
-			let pending = <PendingAvailability<T>>::take(&para_id);
-			let commitments = <PendingAvailabilityCommitments<T>>::take(&para_id);
+			// None of the descendants of a timed out candidate can be included anymore.
+			let timed_out_from = match chain
+				.iter()
+				.position(|(pending, _)| pred(pending.core, pending.backed_in_number))
+			{
+				Some(position) => position,
+				None => continue,
+			};
 
-			if let (Some(pending), Some(commitments)) = (pending, commitments) {
-				// defensive: this should always be true.
-				let candidate = CandidateReceipt {
-					descriptor: pending.descriptor,
-					commitments_hash: commitments.hash(),
-				};
+			for (pending, commitments) in chain.drain(timed_out_from..) {
+				cleaned_up_cores.push(pending.core);
 
-				Self::deposit_event(Event::<T>::CandidateTimedOut(
-					candidate,
-					commitments.head_data,
-					pending.core,
-				));
+				if let Some(commitments) = commitments {
+					// defensive: this should always be true.
+					let candidate = CandidateReceipt {
+						descriptor: pending.descriptor,
+						commitments_hash: commitments.hash(),
+					};
+
+					Self::deposit_event(Event::<T>::CandidateTimedOut(
+						candidate,
+						commitments.head_data,
+						pending.core,
+					));
+				}
 			}
+
+			Self::set_pending_chain(&para_id, chain);
 		}
 
 		cleaned_up_cores
 	}
 
-	/// Cleans up all paras pending availability that are in the given list of disputed candidates.
+	/// Cleans up all paras pending availability that are in the given list of disputed candidates,
+	/// along with all of their descendants.
 	///
 	/// Returns a vector of cleaned-up core IDs.
 	pub(crate) fn collect_disputed(disputed: &BTreeSet<CandidateHash>) -> Vec<CoreIndex> {
-		let mut cleaned_up_ids = Vec::new();
 		let mut cleaned_up_cores = Vec::new();
 
-		for (para_id, pending_record) in <PendingAvailability<T>>::iter() {
-			if disputed.contains(&pending_record.hash) {
-				cleaned_up_ids.push(para_id);
-				cleaned_up_cores.push(pending_record.core);
-			}
-		}
+		for para_id in <PendingAvailability<T>>::iter_keys().collect::<Vec<_>>() {
+			let mut chain = Self::pending_chain(&para_id);
 
-		for para_id in cleaned_up_ids {
-			let _ = <PendingAvailability<T>>::take(&para_id);
-			let _ = <PendingAvailabilityCommitments<T>>::take(&para_id);
+			let disputed_from =
+				match chain.iter().position(|(pending, _)| disputed.contains(&pending.hash)) {
+					Some(position) => position,
+					None => continue,
+				};
+
+			cleaned_up_cores.extend(chain.drain(disputed_from..).map(|(pending, _)| pending.core));
+			Self::set_pending_chain(&para_id, chain);
 		}
 
 		cleaned_up_cores
@@ -859,13 +1002,13 @@ impl<T: Config> Pallet<T> {
 	/// by bitfields.
 	///
 	/// Is a no-op if there is no candidate pending availability for this para-id.
+	/// In case of a chain of candidates pending availability, only the first one is enacted.
 	/// This should generally not be used but it is useful during execution of Runtime APIs,
 	/// where the changes to the state are expected to be discarded directly after.
 	pub(crate) fn force_enact(para: ParaId) {
-		let pending = <PendingAvailability<T>>::take(&para);
-		let commitments = <PendingAvailabilityCommitments<T>>::take(&para);
+		let mut chain = Self::pending_chain(&para).into_iter();
 
-		if let (Some(pending), Some(commitments)) = (pending, commitments) {
+		if let Some((pending, Some(commitments))) = chain.next() {
 			let candidate =
 				CommittedCandidateReceipt { descriptor: pending.descriptor, commitments };
 
@@ -878,6 +1021,8 @@ impl<T: Config> Pallet<T> {
 				pending.backing_group,
 			);
 		}
+
+		Self::set_pending_chain(&para, chain.collect());
 	}
 
 	/// Returns the `CommittedCandidateReceipt` pending availability for the para provided, if any.
@@ -890,6 +1035,22 @@ impl<T: Config> Pallet<T> {
 			.map(|(d, c)| CommittedCandidateReceipt { descriptor: d, commitments: c })
 	}
 
+	/// Returns all `CommittedCandidateReceipt`s pending availability for the para provided, in
+	/// chain order.
+	pub(crate) fn candidates_pending_availability(
+		para: ParaId,
+	) -> Vec<CommittedCandidateReceipt<T::Hash>> {
+		Self::pending_chain(&para)
+			.into_iter()
+			.filter_map(|(pending, commitments)| {
+				commitments.map(|commitments| CommittedCandidateReceipt {
+					descriptor: pending.descriptor,
+					commitments,
+				})
+			})
+			.collect()
+	}
+
 	/// Returns the metadata around the candidate pending availability for the
 	/// para provided, if any.
 	pub(crate) fn pending_availability(
@@ -897,6 +1058,82 @@ impl<T: Config> Pallet<T> {
 	) -> Option<CandidatePendingAvailability<T::Hash, T::BlockNumber>> {
 		<PendingAvailability<T>>::get(&para)
 	}
+
+	/// Returns the metadata around the candidate of the para provided which is pending
+	/// availability on the given core, if any.
+	pub(crate) fn pending_availability_on_core(
+		para: ParaId,
+		core: CoreIndex,
+	) -> Option<CandidatePendingAvailability<T::Hash, T::BlockNumber>> {
+		<PendingAvailability<T>>::get(&para)
+			.into_iter()
+			.chain(<PendingAvailabilityDescendants<T>>::get(&para).into_iter().map(|(p, _)| p))
+			.find(|pending| pending.core == core)
+	}
+
+	/// What a new candidate of the given para has to build upon.
+	///
+	/// For a para assigned more than one core, this is the last candidate pending availability,
+	/// if any. Otherwise, it is the current head of the para.
+	pub(crate) fn expected_parent(para: ParaId) -> Option<CandidateParent<T::BlockNumber>> {
+		let head = <paras::Pallet<T>>::para_head(&para)?;
+		let mut parent = CandidateParent { head, pending: None };
+
+		if <paras::Pallet<T>>::core_count(para) > 1 {
+			for (_, commitments) in Self::pending_chain(&para) {
+				if let Some(commitments) = commitments {
+					parent = parent.child(&commitments);
+				}
+			}
+		}
+
+		Some(parent)
+	}
+
+	/// Returns the chain of candidates pending availability for the para provided.
+	fn pending_chain(para: &ParaId) -> PendingChain<T::Hash, T::BlockNumber> {
+		let first = match <PendingAvailability<T>>::get(para) {
+			Some(first) => first,
+			None => return Vec::new(),
+		};
+
+		let mut chain = vec![(first, <PendingAvailabilityCommitments<T>>::get(para))];
+		chain.extend(
+			<PendingAvailabilityDescendants<T>>::get(para)
+				.into_iter()
+				.map(|(pending, commitments)| (pending, Some(commitments))),
+		);
+		chain
+	}
+
+	/// Replaces the chain of candidates pending availability for the para provided.
+	fn set_pending_chain(para: &ParaId, chain: PendingChain<T::Hash, T::BlockNumber>) {
+		let mut chain = chain.into_iter();
+
+		match chain.next() {
+			Some((first, commitments)) => {
+				<PendingAvailability<T>>::insert(para, first);
+				match commitments {
+					Some(commitments) =>
+						<PendingAvailabilityCommitments<T>>::insert(para, commitments),
+					None => <PendingAvailabilityCommitments<T>>::remove(para),
+				}
+			},
+			None => {
+				<PendingAvailability<T>>::remove(para);
+				<PendingAvailabilityCommitments<T>>::remove(para);
+			},
+		}
+
+		let descendants = chain
+			.filter_map(|(pending, commitments)| commitments.map(|c| (pending, c)))
+			.collect::<Vec<_>>();
+		if descendants.is_empty() {
+			<PendingAvailabilityDescendants<T>>::remove(para);
+		} else {
+			<PendingAvailabilityDescendants<T>>::insert(para, descendants);
+		}
+	}
 }
 
 const fn availability_threshold(n_validators: usize) -> usize {
@@ -914,6 +1151,7 @@ enum AcceptanceCheckErr<BlockNumber> {
 	UpwardMessages(ump::AcceptanceCheckErr),
 	HrmpWatermark(hrmp::HrmpWatermarkAcceptanceErr<BlockNumber>),
 	OutboundHrmp(hrmp::OutboundHrmpAcceptanceErr),
+	MessagesFromPendingDescendant,
 }
 
 impl<BlockNumber> AcceptanceCheckErr<BlockNumber> {
@@ -929,6 +1167,7 @@ impl<BlockNumber> AcceptanceCheckErr<BlockNumber> {
 			UpwardMessages(_) => Error::<T>::InvalidUpwardMessages,
 			HrmpWatermark(_) => Error::<T>::HrmpWatermarkMishandling,
 			OutboundHrmp(_) => Error::<T>::InvalidOutboundHrmp,
+			MessagesFromPendingDescendant => Error::<T>::MessagesFromPendingDescendant,
 		}
 	}
 }
@@ -951,6 +1190,9 @@ impl<T: Config> CandidateCheckContext<T> {
 
 	/// Execute verification of the candidate.
 	///
+	/// `parent` is what the candidate is expected to build upon, usually
+	/// [`Pallet::expected_parent`].
+	///
 	/// Assures:
 	///  * correct expected relay parent reference
 	///  * collator signature check passes
@@ -962,6 +1204,7 @@ impl<T: Config> CandidateCheckContext<T> {
 		parent_storage_root: T::Hash,
 		candidate_idx: usize,
 		backed_candidate: &BackedCandidate<<T as frame_system::Config>::Hash>,
+		parent: Option<&CandidateParent<T::BlockNumber>>,
 	) -> Result<Result<(), FailedToCreatePVD>, Error<T>> {
 		let para_id = backed_candidate.descriptor().para_id;
 		let now = <frame_system::Pallet<T>>::block_number();
@@ -969,14 +1212,16 @@ impl<T: Config> CandidateCheckContext<T> {
 
 		{
 			// this should never fail because the para is registered
-			let persisted_validation_data = match crate::util::make_persisted_validation_data::<T>(
-				para_id,
-				relay_parent_number,
-				parent_storage_root,
-			) {
-				Some(l) => l,
+			let parent_head = match parent {
+				Some(parent) => parent.head.clone(),
 				None => return Ok(Err(FailedToCreatePVD)),
 			};
+			let persisted_validation_data =
+				crate::util::make_persisted_validation_data_with_parent::<T>(
+					parent_head,
+					relay_parent_number,
+					parent_storage_root,
+				);
 
 			let expected = persisted_validation_data.hash();
 
@@ -1012,6 +1257,7 @@ impl<T: Config> CandidateCheckContext<T> {
 
 		if let Err(err) = self.check_validation_outputs(
 			para_id,
+			parent.and_then(|parent| parent.pending.as_ref()),
 			&backed_candidate.candidate.commitments.head_data,
 			&backed_candidate.candidate.commitments.new_validation_code,
 			backed_candidate.candidate.commitments.processed_downward_messages,
@@ -1033,9 +1279,15 @@ impl<T: Config> CandidateCheckContext<T> {
 
 	/// Check the given outputs after candidate validation on whether it passes the acceptance
 	/// criteria.
+	///
+	/// `pending` are the outputs of the candidates pending availability the candidate builds
+	/// upon. Downward message processing and the HRMP watermark are checked as if those were
+	/// enacted already. The other outputs are checked against the current state only, hence
+	/// such a candidate may not send messages or upgrade its code.
 	fn check_validation_outputs(
 		&self,
 		para_id: ParaId,
+		pending: Option<&PendingOutputs<T::BlockNumber>>,
 		head_data: &HeadData,
 		new_validation_code: &Option<primitives::ValidationCode>,
 		processed_downward_messages: u32,
@@ -1060,10 +1312,27 @@ impl<T: Config> CandidateCheckContext<T> {
 			);
 		}
 
+		if pending.is_some() {
+			ensure!(new_validation_code.is_none(), AcceptanceCheckErr::PrematureCodeUpgrade);
+			ensure!(
+				upward_messages.is_empty() && horizontal_messages.is_empty(),
+				AcceptanceCheckErr::MessagesFromPendingDescendant,
+			);
+		}
+
 		// check if the candidate passes the messaging acceptance criteria
-		<dmp::Pallet<T>>::check_processed_downward_messages(para_id, processed_downward_messages)?;
+		<dmp::Pallet<T>>::check_processed_downward_messages(
+			para_id,
+			pending.map_or(0, |p| p.processed_downward_messages),
+			processed_downward_messages,
+		)?;
 		<ump::Pallet<T>>::check_upward_messages(&self.config, para_id, upward_messages)?;
-		<hrmp::Pallet<T>>::check_hrmp_watermark(para_id, self.relay_parent_number, hrmp_watermark)?;
+		<hrmp::Pallet<T>>::check_hrmp_watermark(
+			para_id,
+			self.relay_parent_number,
+			hrmp_watermark,
+			pending.map(|p| p.hrmp_watermark),
+		)?;
 		<hrmp::Pallet<T>>::check_outbound_hrmp(&self.config, para_id, horizontal_messages)?;
 
 		Ok(())
@@ -19,15 +19,15 @@ use crate::{
 	configuration::HostConfiguration,
 	initializer::SessionChangeNotification,
 	mock::{
-		new_test_ext, Configuration, MockGenesisConfig, ParaInclusion, Paras, ParasShared, System,
-		Test,
+		new_test_ext, Configuration, Dmp, MockGenesisConfig, ParaInclusion, Paras, ParasShared,
+		RuntimeOrigin, System, Test,
 	},
 	paras::{ParaGenesisArgs, ParaKind},
 	paras_inherent::DisputedBitfield,
 	scheduler::AssignmentKind,
 };
 use assert_matches::assert_matches;
-use frame_support::assert_noop;
+use frame_support::{assert_noop, assert_ok};
 use futures::executor::block_on;
 use keyring::Sr25519Keyring;
 use primitives::{
@@ -1841,6 +1841,381 @@ fn can_include_candidate_with_ok_code_upgrade() {
 	});
 }
 
+#[test]
+fn chain_of_candidates_is_included_in_order() {
+	let chain_a = ParaId::from(1_u32);
+
+	let paras = vec![(chain_a, ParaKind::Parachain)];
+	let validators = vec![
+		Sr25519Keyring::Alice,
+		Sr25519Keyring::Bob,
+		Sr25519Keyring::Charlie,
+		Sr25519Keyring::Dave,
+		Sr25519Keyring::Ferdie,
+	];
+	let keystore: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+	for validator in validators.iter() {
+		SyncCryptoStore::sr25519_generate_new(
+			&*keystore,
+			PARACHAIN_KEY_TYPE_ID,
+			Some(&validator.to_seed()),
+		)
+		.unwrap();
+	}
+	let validator_public = validator_pubkeys(&validators);
+
+	let mut genesis = genesis_config(paras);
+	genesis.configuration.config.max_head_data_size = 4;
+
+	new_test_ext(genesis).execute_with(|| {
+		// assign two cores to chain A, taking effect at the session change.
+		assert_ok!(Paras::force_set_core_count(RuntimeOrigin::root(), chain_a, 2));
+		run_to_block(5, |n| match n {
+			5 => Some(SessionChangeNotification {
+				validators: validator_public.clone(),
+				new_config: Configuration::config(),
+				session_index: 5,
+				..Default::default()
+			}),
+			_ => None,
+		});
+		shared::Pallet::<Test>::set_active_validators_ascending(validator_public.clone());
+		assert_eq!(Paras::core_count(chain_a), 2);
+
+		let signing_context =
+			SigningContext { parent_hash: System::parent_hash(), session_index: 5 };
+
+		let group_validators = |group_index: GroupIndex| {
+			match group_index {
+				group_index if group_index == GroupIndex::from(0) => Some(vec![0, 1, 2]),
+				group_index if group_index == GroupIndex::from(1) => Some(vec![3, 4]),
+				_ => panic!("Group index out of bounds for 2 cores"),
+			}
+			.map(|vs| vs.into_iter().map(ValidatorIndex).collect::<Vec<_>>())
+		};
+
+		let assignments = vec![
+			CoreAssignment {
+				core: CoreIndex::from(0),
+				para_id: chain_a,
+				kind: AssignmentKind::Parachain,
+				group_idx: GroupIndex::from(0),
+			},
+			CoreAssignment {
+				core: CoreIndex::from(1),
+				para_id: chain_a,
+				kind: AssignmentKind::Parachain,
+				group_idx: GroupIndex::from(1),
+			},
+		];
+
+		let relay_parent_number = System::block_number() - 1;
+		let head_a: HeadData = vec![1, 2].into();
+		let head_b: HeadData = vec![3, 4].into();
+
+		let mut candidate_a = TestCandidateBuilder {
+			para_id: chain_a,
+			head_data: head_a.clone(),
+			relay_parent: System::parent_hash(),
+			pov_hash: Hash::repeat_byte(1),
+			persisted_validation_data_hash: make_vdata_hash(chain_a).unwrap(),
+			hrmp_watermark: relay_parent_number,
+			..Default::default()
+		}
+		.build();
+		collator_sign_candidate(Sr25519Keyring::One, &mut candidate_a);
+
+		// candidate B builds on top of candidate A.
+		let mut candidate_b = TestCandidateBuilder {
+			para_id: chain_a,
+			head_data: head_b.clone(),
+			relay_parent: System::parent_hash(),
+			pov_hash: Hash::repeat_byte(2),
+			persisted_validation_data_hash:
+				crate::util::make_persisted_validation_data_with_parent::<Test>(
+					head_a.clone(),
+					relay_parent_number,
+					Default::default(),
+				)
+				.hash(),
+			hrmp_watermark: relay_parent_number,
+			..Default::default()
+		}
+		.build();
+		collator_sign_candidate(Sr25519Keyring::Two, &mut candidate_b);
+
+		let backed_a = block_on(back_candidate(
+			candidate_a.clone(),
+			&validators,
+			group_validators(GroupIndex::from(0)).unwrap().as_ref(),
+			&keystore,
+			&signing_context,
+			BackingKind::Threshold,
+		));
+		let backed_b = block_on(back_candidate(
+			candidate_b.clone(),
+			&validators,
+			group_validators(GroupIndex::from(1)).unwrap().as_ref(),
+			&keystore,
+			&signing_context,
+			BackingKind::Threshold,
+		));
+
+		let ProcessedCandidates { core_indices: occupied_cores, .. } =
+			ParaInclusion::process_candidates(
+				Default::default(),
+				vec![backed_a, backed_b],
+				assignments,
+				&group_validators,
+			)
+			.expect("candidates scheduled, in order, and backed");
+
+		assert_eq!(occupied_cores, vec![CoreIndex::from(0), CoreIndex::from(1)]);
+		assert_eq!(
+			ParaInclusion::candidates_pending_availability(chain_a),
+			vec![candidate_a.clone(), candidate_b.clone()],
+		);
+		assert_eq!(<PendingAvailability<Test>>::get(&chain_a).unwrap().hash, candidate_a.hash());
+		assert_eq!(<PendingAvailabilityDescendants<Test>>::get(&chain_a).len(), 1);
+
+		let core_lookup = |core| match core {
+			core if core == CoreIndex::from(0) => Some(chain_a),
+			core if core == CoreIndex::from(1) => Some(chain_a),
+			_ => panic!("Core out of bounds for 2 cores."),
+		};
+
+		let sign_all = |bits: &[usize]| -> UncheckedSignedAvailabilityBitfields {
+			let mut bare_bitfield = default_bitfield();
+			for bit in bits {
+				*bare_bitfield.0.get_mut(*bit).unwrap() = true;
+			}
+
+			validators
+				.iter()
+				.enumerate()
+				.map(|(i, key)| {
+					block_on(sign_bitfield(
+						&keystore,
+						key,
+						ValidatorIndex(i as _),
+						bare_bitfield.clone(),
+						&signing_context,
+					))
+					.into()
+				})
+				.collect()
+		};
+
+		// B is available, but has to wait for A.
+		assert_matches!(
+			ParaInclusion::process_bitfields(
+				expected_bits(),
+				sign_all(&[1]),
+				DisputedBitfield::zeros(expected_bits()),
+				&core_lookup,
+				FullCheck::Yes,
+			),
+			Ok(v) => {
+				assert!(v.is_empty());
+			}
+		);
+		assert_eq!(ParaInclusion::candidates_pending_availability(chain_a).len(), 2);
+		assert_eq!(Paras::para_head(&chain_a), Some(Vec::new().into()));
+
+		// once A is available both are enacted in chain order.
+		assert_matches!(
+			ParaInclusion::process_bitfields(
+				expected_bits(),
+				sign_all(&[0]),
+				DisputedBitfield::zeros(expected_bits()),
+				&core_lookup,
+				FullCheck::Yes,
+			),
+			Ok(v) => {
+				assert_eq!(
+					v,
+					vec![(CoreIndex(0), candidate_a.hash()), (CoreIndex(1), candidate_b.hash())],
+				);
+			}
+		);
+		assert!(ParaInclusion::candidates_pending_availability(chain_a).is_empty());
+		assert!(<PendingAvailabilityDescendants<Test>>::get(&chain_a).is_empty());
+		assert_eq!(Paras::para_head(&chain_a), Some(head_b));
+	});
+}
+
+#[test]
+fn descendants_are_checked_against_the_outputs_of_their_ancestors() {
+	let chain_a = ParaId::from(1_u32);
+
+	let paras = vec![(chain_a, ParaKind::Parachain)];
+	let validators = vec![
+		Sr25519Keyring::Alice,
+		Sr25519Keyring::Bob,
+		Sr25519Keyring::Charlie,
+		Sr25519Keyring::Dave,
+		Sr25519Keyring::Ferdie,
+	];
+	let keystore: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+	for validator in validators.iter() {
+		SyncCryptoStore::sr25519_generate_new(
+			&*keystore,
+			PARACHAIN_KEY_TYPE_ID,
+			Some(&validator.to_seed()),
+		)
+		.unwrap();
+	}
+	let validator_public = validator_pubkeys(&validators);
+
+	let mut genesis = genesis_config(paras);
+	genesis.configuration.config.max_head_data_size = 4;
+	genesis.configuration.config.max_upward_message_num_per_candidate = 1;
+
+	new_test_ext(genesis).execute_with(|| {
+		assert_ok!(Paras::force_set_core_count(RuntimeOrigin::root(), chain_a, 2));
+		run_to_block(5, |n| match n {
+			5 => Some(SessionChangeNotification {
+				validators: validator_public.clone(),
+				new_config: Configuration::config(),
+				session_index: 5,
+				..Default::default()
+			}),
+			_ => None,
+		});
+		shared::Pallet::<Test>::set_active_validators_ascending(validator_public.clone());
+
+		// a single downward message, which candidate A processes.
+		assert_ok!(Dmp::queue_downward_message(&Configuration::config(), chain_a, vec![1]));
+
+		let signing_context =
+			SigningContext { parent_hash: System::parent_hash(), session_index: 5 };
+
+		let group_validators = |group_index: GroupIndex| {
+			match group_index {
+				group_index if group_index == GroupIndex::from(0) => Some(vec![0, 1, 2]),
+				group_index if group_index == GroupIndex::from(1) => Some(vec![3, 4]),
+				_ => panic!("Group index out of bounds for 2 cores"),
+			}
+			.map(|vs| vs.into_iter().map(ValidatorIndex).collect::<Vec<_>>())
+		};
+
+		let assignments = || {
+			vec![
+				CoreAssignment {
+					core: CoreIndex::from(0),
+					para_id: chain_a,
+					kind: AssignmentKind::Parachain,
+					group_idx: GroupIndex::from(0),
+				},
+				CoreAssignment {
+					core: CoreIndex::from(1),
+					para_id: chain_a,
+					kind: AssignmentKind::Parachain,
+					group_idx: GroupIndex::from(1),
+				},
+			]
+		};
+
+		let relay_parent_number = System::block_number() - 1;
+		let head_a: HeadData = vec![1, 2].into();
+
+		let mut candidate_a = TestCandidateBuilder {
+			para_id: chain_a,
+			head_data: head_a.clone(),
+			relay_parent: System::parent_hash(),
+			pov_hash: Hash::repeat_byte(1),
+			persisted_validation_data_hash: make_vdata_hash(chain_a).unwrap(),
+			hrmp_watermark: relay_parent_number,
+			..Default::default()
+		}
+		.build();
+		candidate_a.commitments.processed_downward_messages = 1;
+		collator_sign_candidate(Sr25519Keyring::One, &mut candidate_a);
+		let backed_a = block_on(back_candidate(
+			candidate_a.clone(),
+			&validators,
+			group_validators(GroupIndex::from(0)).unwrap().as_ref(),
+			&keystore,
+			&signing_context,
+			BackingKind::Threshold,
+		));
+
+		// candidate B builds on top of candidate A.
+		let backed_b = |processed_downward_messages: u32,
+		                upward_messages: Vec<Vec<u8>>,
+		                hrmp_watermark: BlockNumber| {
+			let mut candidate_b = TestCandidateBuilder {
+				para_id: chain_a,
+				head_data: vec![3, 4].into(),
+				relay_parent: System::parent_hash(),
+				pov_hash: Hash::repeat_byte(2),
+				persisted_validation_data_hash:
+					crate::util::make_persisted_validation_data_with_parent::<Test>(
+						head_a.clone(),
+						relay_parent_number,
+						Default::default(),
+					)
+					.hash(),
+				hrmp_watermark,
+				..Default::default()
+			}
+			.build();
+			candidate_b.commitments.processed_downward_messages = processed_downward_messages;
+			candidate_b.commitments.upward_messages = upward_messages.try_into().unwrap();
+			collator_sign_candidate(Sr25519Keyring::Two, &mut candidate_b);
+			block_on(back_candidate(
+				candidate_b,
+				&validators,
+				group_validators(GroupIndex::from(1)).unwrap().as_ref(),
+				&keystore,
+				&signing_context,
+				BackingKind::Threshold,
+			))
+		};
+
+		// the only downward message is processed by A already.
+		assert_noop!(
+			ParaInclusion::process_candidates(
+				Default::default(),
+				vec![backed_a.clone(), backed_b(1, Vec::new(), relay_parent_number)],
+				assignments(),
+				&group_validators,
+			),
+			Error::<Test>::IncorrectDownwardMessageHandling,
+		);
+
+		// B may not send messages before A is enacted.
+		assert_noop!(
+			ParaInclusion::process_candidates(
+				Default::default(),
+				vec![backed_a.clone(), backed_b(0, vec![vec![42]], relay_parent_number)],
+				assignments(),
+				&group_validators,
+			),
+			Error::<Test>::MessagesFromPendingDescendant,
+		);
+
+		// B may not move the watermark behind the one of A.
+		assert_noop!(
+			ParaInclusion::process_candidates(
+				Default::default(),
+				vec![backed_a.clone(), backed_b(0, Vec::new(), relay_parent_number - 1)],
+				assignments(),
+				&group_validators,
+			),
+			Error::<Test>::HrmpWatermarkMishandling,
+		);
+
+		assert_ok!(ParaInclusion::process_candidates(
+			Default::default(),
+			vec![backed_a, backed_b(0, Vec::new(), relay_parent_number)],
+			assignments(),
+			&group_validators,
+		));
+		assert_eq!(ParaInclusion::candidates_pending_availability(chain_a).len(), 2);
+	});
+}
+
 #[test]
 fn session_change_wipes() {
 	let chain_a = ParaId::from(1_u32);
@@ -22,7 +22,8 @@
 use crate::{
 	configuration::{self, HostConfiguration},
 	disputes::{self, DisputesHandler as _, SlashingHandler as _},
-	dmp, hrmp, inclusion, paras, scheduler, session_info, shared, ump,
+	dmp, hrmp, inclusion, paras, performance::RecordPerformance as _, scheduler, session_info,
+	shared, ump,
 };
 use frame_support::{
 	traits::{OneSessionHandler, Randomness},
@@ -266,6 +267,8 @@ impl<T: Config> Pallet<T> {
 		scheduler::Pallet::<T>::initializer_on_new_session(&notification);
 		inclusion::Pallet::<T>::initializer_on_new_session(&notification);
 		session_info::Pallet::<T>::initializer_on_new_session(&notification);
+		// Prunes along with `session_info`, hence called after it.
+		<T as inclusion::Config>::RecordPerformance::initializer_on_new_session(session_index);
 		T::DisputesHandler::initializer_on_new_session(&notification);
 		T::SlashingHandler::initializer_on_new_session(session_index);
 		dmp::Pallet::<T>::initializer_on_new_session(&notification, &outgoing_paras);
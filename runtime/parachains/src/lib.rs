@@ -30,9 +30,12 @@ pub mod hrmp;
 pub mod inclusion;
 pub mod initializer;
 pub mod metrics;
+pub mod on_demand;
 pub mod origin;
+pub mod paged_queue;
 pub mod paras;
 pub mod paras_inherent;
+pub mod performance;
 pub mod reward_points;
 pub mod scheduler;
 pub mod session_info;
@@ -51,7 +54,13 @@ mod mock;
 pub use origin::{ensure_parachain, Origin};
 pub use paras::ParaLifecycle;
 use primitives::{HeadData, Id as ParaId, ValidationCode};
-use sp_runtime::DispatchResult;
+use sp_runtime::{DispatchResult, FixedU128};
+
+/// Tracks the factor by which the fee for delivering messages to a para is multiplied.
+pub trait FeeTracker {
+	/// The current fee factor for messages to `para`.
+	fn get_fee_factor(para: ParaId) -> FixedU128;
+}
 
 /// Schedule a para to be initialized at the start of the next session with the given genesis data.
 ///
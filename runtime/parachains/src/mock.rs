@@ -17,8 +17,8 @@
 //! Mocks for all the traits.
 
 use crate::{
-	configuration, disputes, dmp, hrmp, inclusion, initializer, origin, paras, paras_inherent,
-	scheduler, session_info, shared,
+	configuration, disputes, dmp, hrmp, inclusion, initializer, on_demand, origin, paras,
+	paras_inherent, performance, scheduler, session_info, shared,
 	ump::{self, MessageId, UmpSink},
 	ParaId,
 };
@@ -32,7 +32,7 @@ use frame_support_test::TestRandomness;
 use parity_scale_codec::Decode;
 use primitives::{
 	AuthorityDiscoveryId, Balance, BlockNumber, CandidateHash, Header, Moment, SessionIndex,
-	UpwardMessage, ValidatorIndex,
+	UpwardMessage, ValidatorId, ValidatorIndex,
 };
 use sp_core::H256;
 use sp_io::TestExternalities;
@@ -60,6 +60,7 @@ frame_support::construct_runtime!(
 		ParaInclusion: inclusion,
 		ParaInherent: paras_inherent,
 		Scheduler: scheduler,
+		OnDemand: on_demand,
 		Initializer: initializer,
 		Dmp: dmp,
 		Ump: ump,
@@ -67,6 +68,8 @@ frame_support::construct_runtime!(
 		ParachainsOrigin: origin,
 		SessionInfo: session_info,
 		Disputes: disputes,
+		ParasSlashing: disputes::slashing,
+		ParasPerformance: performance,
 		Babe: pallet_babe,
 	}
 );
@@ -216,6 +219,7 @@ impl frame_support::traits::EstimateNextSessionRotation<u32> for TestNextSession
 
 impl crate::paras::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
 	type WeightInfo = crate::paras::TestWeightInfo;
 	type UnsignedPriority = ParasUnsignedPriority;
 	type NextSessionRotation = TestNextSessionRotation;
@@ -225,6 +229,7 @@ impl crate::dmp::Config for Test {}
 
 parameter_types! {
 	pub const FirstMessageFactorPercent: u64 = 100;
+	pub const UmpMaxPageSize: u32 = 32;
 }
 
 impl crate::ump::Config for Test {
@@ -232,6 +237,7 @@ impl crate::ump::Config for Test {
 	type UmpSink = TestUmpSink;
 	type FirstMessageFactorPercent = FirstMessageFactorPercent;
 	type ExecuteOverweightOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxPageSize = UmpMaxPageSize;
 	type WeightInfo = crate::ump::TestWeightInfo;
 }
 
@@ -239,6 +245,7 @@ impl crate::hrmp::Config for Test {
 	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = pallet_balances::Pallet<Test>;
+	type SystemParas = crate::hrmp::IsSystemPara;
 	type WeightInfo = crate::hrmp::TestWeightInfo;
 }
 
@@ -246,9 +253,14 @@ impl crate::disputes::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type RewardValidators = Self;
 	type SlashingHandler = Self;
+	type RecordPerformance = ParasPerformance;
 	type WeightInfo = crate::disputes::TestWeightInfo;
 }
 
+impl crate::performance::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+}
+
 thread_local! {
 	pub static REWARD_VALIDATORS: RefCell<Vec<(SessionIndex, Vec<ValidatorIndex>)>> = RefCell::new(Vec::new());
 	pub static PUNISH_VALIDATORS_FOR: RefCell<Vec<(SessionIndex, Vec<ValidatorIndex>)>> = RefCell::new(Vec::new());
@@ -296,18 +308,63 @@ impl crate::disputes::SlashingHandler<BlockNumber> for Test {
 	fn initializer_on_new_session(_: SessionIndex) {}
 }
 
-impl crate::scheduler::Config for Test {}
+impl crate::scheduler::Config for Test {
+	type OnParathreadClaim = OnDemand;
+}
+
+parameter_types! {
+	pub const OnDemandBaseFee: Balance = 1_000;
+	pub const OnDemandMaxFeeMultiplier: u32 = 5;
+}
+
+impl crate::on_demand::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = pallet_balances::Pallet<Test>;
+	type FeeDestination = ();
+	type BaseFee = OnDemandBaseFee;
+	type MaxFeeMultiplier = OnDemandMaxFeeMultiplier;
+	type WeightInfo = crate::on_demand::TestWeightInfo;
+}
 
 impl crate::inclusion::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type DisputesHandler = Disputes;
 	type RewardValidators = TestRewardValidators;
+	type RecordPerformance = ParasPerformance;
 }
 
 impl crate::paras_inherent::Config for Test {
 	type WeightInfo = crate::paras_inherent::TestWeightInfo;
 }
 
+impl disputes::slashing::Config for Test {
+	type KeyOwnerProof = sp_session::MembershipProof;
+	type KeyOwnerIdentification = session_info::IdentificationTuple<Test>;
+	type KeyOwnerProofSystem = MockKeyOwnerProofSystem;
+	type HandleReports = ();
+	type WeightInfo = disputes::slashing::TestWeightInfo;
+	type BenchmarkingConfig = disputes::slashing::BenchConfig<1000>;
+}
+
+/// Proves the ownership of no key, so no slashing report is valid.
+pub struct MockKeyOwnerProofSystem;
+
+impl KeyOwnerProofSystem<(KeyTypeId, ValidatorId)> for MockKeyOwnerProofSystem {
+	type Proof = sp_session::MembershipProof;
+	type IdentificationTuple = session_info::IdentificationTuple<Test>;
+
+	fn prove(_key: (KeyTypeId, ValidatorId)) -> Option<Self::Proof> {
+		None
+	}
+
+	fn check_proof(
+		_key: (KeyTypeId, ValidatorId),
+		_proof: Self::Proof,
+	) -> Option<Self::IdentificationTuple> {
+		None
+	}
+}
+
 pub struct MockValidatorSet;
 
 impl ValidatorSet<AccountId> for MockValidatorSet {
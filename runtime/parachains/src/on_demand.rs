@@ -0,0 +1,230 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! On-demand orders for parathread blocks.
+//!
+//! Anyone can buy a single block for a parathread by placing an order, which is turned into a
+//! claim in the parathread queue of the scheduler. The price of an order rises with the
+//! utilization of that queue.
+//!
+//! The price is reserved from the payer when the order is placed. It is taken once a candidate of
+//! the parathread has been included, and refunded if the claim is dropped before that, e.g.
+//! because it ran out of `parathread_retries`.
+
+use crate::{
+	configuration,
+	scheduler::{self, OnParathreadClaim, ParathreadClaimError},
+};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{Currency, OnUnbalanced, ReservableCurrency},
+};
+use frame_system::pallet_prelude::*;
+use primitives::{vstaging::OnDemandQueue, Balance, CollatorId, Id as ParaId, ParathreadClaim};
+use scale_info::TypeInfo;
+use sp_runtime::{traits::UniqueSaturatedInto, Perbill};
+use sp_std::prelude::*;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub trait WeightInfo {
+	fn place_order() -> Weight;
+}
+
+/// A weight info that is only suitable for testing.
+pub struct TestWeightInfo;
+
+impl WeightInfo for TestWeightInfo {
+	fn place_order() -> Weight {
+		Weight::zero()
+	}
+}
+
+type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+/// An order backing the claim of a parathread.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Order<AccountId> {
+	/// The account the price is reserved from.
+	pub payer: AccountId,
+	/// The price of the order.
+	pub price: Balance,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + configuration::Config + scheduler::Config {
+		/// The outer event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The currency orders are paid with.
+		///
+		/// NOTE that prices are expressed in `Balance`, which should be the `Balance` of this
+		/// `Currency` implementation.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// Receives the price of orders which have been served.
+		type FeeDestination: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// The price of an order while the parathread queue is empty.
+		#[pallet::constant]
+		type BaseFee: Get<Balance>;
+
+		/// The multiple of `BaseFee` an order costs while the parathread queue is full. The price
+		/// rises linearly with the utilization of the queue in between.
+		#[pallet::constant]
+		type MaxFeeMultiplier: Get<u32>;
+
+		/// Something that provides the weight of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An order was placed and the parathread was added to the queue.
+		OrderPlaced { para_id: ParaId, payer: T::AccountId, price: Balance },
+		/// A candidate of the parathread was included and the price of the order was taken.
+		OrderFulfilled { para_id: ParaId, payer: T::AccountId, price: Balance },
+		/// The claim of the order was dropped and the price was refunded to the payer.
+		OrderRefunded { para_id: ParaId, payer: T::AccountId, price: Balance },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The spot price is above the limit given by the caller.
+		PriceAboveLimit,
+		/// The caller can not pay the spot price.
+		InsufficientBalance,
+		/// The para is not a live parathread.
+		NotParathread,
+		/// The parathread queue is full, or there are no parathread cores.
+		QueueFull,
+		/// There already is a claim for the parathread in the queue or on a core.
+		AlreadyClaimed,
+	}
+
+	/// The order behind the claim of a parathread, for claims placed through this pallet.
+	///
+	/// Bounded by the size of the parathread queue plus the number of parathread cores, as there
+	/// is at most one claim per parathread.
+	#[pallet::storage]
+	pub(crate) type Orders<T: Config> =
+		StorageMap<_, Twox64Concat, ParaId, Order<T::AccountId>, OptionQuery>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Order a single block of the parathread `para_id`, to be collated by `collator`.
+		///
+		/// The spot price is reserved from the origin. It is taken once a candidate of the
+		/// parathread has been included and refunded if the claim is dropped before that.
+		///
+		/// Fails if the spot price is above `max_price`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::place_order())]
+		pub fn place_order(
+			origin: OriginFor<T>,
+			para_id: ParaId,
+			collator: CollatorId,
+			max_price: Balance,
+		) -> DispatchResult {
+			let payer = ensure_signed(origin)?;
+
+			let price = Self::spot_price();
+			ensure!(price <= max_price, Error::<T>::PriceAboveLimit);
+			ensure!(
+				T::Currency::can_reserve(&payer, price.unique_saturated_into()),
+				Error::<T>::InsufficientBalance,
+			);
+
+			<scheduler::Pallet<T>>::add_parathread_claim(ParathreadClaim(para_id, collator))
+				.map_err(|e| match e {
+					ParathreadClaimError::NotParathread => Error::<T>::NotParathread,
+					ParathreadClaimError::QueueFull => Error::<T>::QueueFull,
+					ParathreadClaimError::AlreadyClaimed => Error::<T>::AlreadyClaimed,
+				})?;
+			T::Currency::reserve(&payer, price.unique_saturated_into())?;
+
+			Orders::<T>::insert(para_id, Order { payer: payer.clone(), price });
+			Self::deposit_event(Event::OrderPlaced { para_id, payer, price });
+
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The price of an order placed now.
+	pub fn spot_price() -> Balance {
+		let config = <configuration::Pallet<T>>::config();
+		let capacity = <scheduler::Pallet<T>>::parathread_queue_capacity(&config);
+		let queued = <scheduler::Pallet<T>>::queued_parathreads().len() as u32;
+		Self::price_at(queued, capacity)
+	}
+
+	/// The spot price and the contents of the parathread queue.
+	pub fn queue() -> OnDemandQueue {
+		let config = <configuration::Pallet<T>>::config();
+		let capacity = <scheduler::Pallet<T>>::parathread_queue_capacity(&config);
+		let queue = <scheduler::Pallet<T>>::queued_parathreads();
+		let spot_price = Self::price_at(queue.len() as u32, capacity);
+
+		OnDemandQueue { spot_price, capacity, queue }
+	}
+
+	fn price_at(queued: u32, capacity: u32) -> Balance {
+		let base_fee = T::BaseFee::get();
+		let utilization = Perbill::from_rational(queued.min(capacity), capacity.max(1));
+		let max_surcharge =
+			base_fee.saturating_mul(T::MaxFeeMultiplier::get().saturating_sub(1).into());
+
+		base_fee.saturating_add(utilization * max_surcharge)
+	}
+}
+
+impl<T: Config> OnParathreadClaim for Pallet<T> {
+	fn on_claim_concluded(claim: &ParathreadClaim) {
+		if let Some(Order { payer, price }) = Orders::<T>::take(claim.0) {
+			let (imbalance, _) =
+				T::Currency::slash_reserved(&payer, price.unique_saturated_into());
+			T::FeeDestination::on_unbalanced(imbalance);
+			Self::deposit_event(Event::OrderFulfilled { para_id: claim.0, payer, price });
+		}
+	}
+
+	fn on_claim_dropped(claim: &ParathreadClaim) {
+		if let Some(Order { payer, price }) = Orders::<T>::take(claim.0) {
+			T::Currency::unreserve(&payer, price.unique_saturated_into());
+			Self::deposit_event(Event::OrderRefunded { para_id: claim.0, payer, price });
+		}
+	}
+}
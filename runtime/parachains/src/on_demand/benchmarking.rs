@@ -0,0 +1,86 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::{
+	configuration::{HostConfiguration, Pallet as Configuration},
+	on_demand::Pallet as OnDemand,
+	paras::{Pallet as Paras, ParaGenesisArgs, ParaKind, ParachainsCache},
+	scheduler::Pallet as Scheduler,
+};
+use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
+	let events = frame_system::Pallet::<T>::events();
+	let system_event: <T as frame_system::Config>::RuntimeEvent = generic_event.into();
+	// compare to the last event record
+	let frame_system::EventRecord { event, .. } = &events[events.len() - 1];
+	assert_eq!(event, &system_event);
+}
+
+fn register_parathread<T: Config>(id: ParaId) {
+	let mut parachains = ParachainsCache::new();
+	Paras::<T>::initialize_para_now(
+		&mut parachains,
+		id,
+		&ParaGenesisArgs {
+			para_kind: ParaKind::Parathread,
+			genesis_head: vec![1].into(),
+			validation_code: vec![1].into(),
+		},
+	);
+}
+
+fn collator(seed: u32) -> CollatorId {
+	CollatorId::from(sp_core::sr25519::Public::from_raw([seed as u8; 32]))
+}
+
+benchmarks! {
+	// Worst case: all but one slot of the queue is taken.
+	place_order {
+		Configuration::<T>::force_set_active_config(HostConfiguration {
+			parathread_cores: 10,
+			scheduling_lookahead: 10,
+			..Configuration::<T>::config()
+		});
+		let capacity = Scheduler::<T>::parathread_queue_capacity(&Configuration::<T>::config());
+
+		for i in 1..capacity {
+			let para_id = ParaId::from(1_000 + i);
+			register_parathread::<T>(para_id);
+			Scheduler::<T>::add_parathread_claim(ParathreadClaim(para_id, collator(i)))
+				.expect("queue is not full; qed");
+		}
+
+		let para_id = ParaId::from(1_000 + capacity);
+		register_parathread::<T>(para_id);
+
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, Bounded::max_value());
+		let price = OnDemand::<T>::spot_price();
+	}: _(RawOrigin::Signed(caller.clone()), para_id, collator(capacity), price)
+	verify {
+		assert_last_event::<T>(Event::<T>::OrderPlaced { para_id, payer: caller, price }.into());
+	}
+
+	impl_benchmark_test_suite!(
+		OnDemand,
+		crate::mock::new_test_ext(Default::default()),
+		crate::mock::Test
+	);
+}
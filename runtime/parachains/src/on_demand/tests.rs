@@ -0,0 +1,245 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+
+use frame_support::{assert_noop, assert_ok, traits::Currency as _};
+use keyring::Sr25519Keyring;
+use primitives::{BlockNumber, CoreOccupied, ValidatorId};
+
+use crate::{
+	configuration::HostConfiguration,
+	initializer::SessionChangeNotification,
+	mock::{
+		assert_last_event, new_test_ext, Balances, MockGenesisConfig, OnDemand, Paras,
+		ParasShared, RuntimeOrigin, Scheduler, System, Test,
+	},
+	paras::{ParaGenesisArgs, ParaKind},
+	scheduler::FreedReason,
+};
+
+const PAYER: u64 = 1;
+
+fn default_config() -> HostConfiguration<BlockNumber> {
+	HostConfiguration {
+		parathread_cores: 2,
+		scheduling_lookahead: 2,
+		parathread_retries: 1,
+		group_rotation_frequency: 10,
+		chain_availability_period: 3,
+		thread_availability_period: 5,
+		minimum_validation_upgrade_delay: 6,
+		pvf_checking_enabled: false,
+		..Default::default()
+	}
+}
+
+fn new_test_ext_with_config(config: HostConfiguration<BlockNumber>) -> sp_io::TestExternalities {
+	new_test_ext(MockGenesisConfig {
+		configuration: crate::configuration::GenesisConfig { config, ..Default::default() },
+		..Default::default()
+	})
+}
+
+fn collator() -> CollatorId {
+	CollatorId::from(Sr25519Keyring::Alice.public())
+}
+
+/// Register the given parathreads and start a session with the given validators.
+fn activate_parathreads(threads: &[ParaId], validators: &[Sr25519Keyring]) {
+	for id in threads {
+		assert_ok!(Paras::schedule_para_initialize(
+			*id,
+			ParaGenesisArgs {
+				genesis_head: Vec::new().into(),
+				validation_code: vec![1, 2, 3].into(),
+				para_kind: ParaKind::Parathread,
+			}
+		));
+	}
+
+	let notification = SessionChangeNotification {
+		new_config: default_config(),
+		validators: validators.iter().map(|v| ValidatorId::from(v.public())).collect(),
+		session_index: ParasShared::scheduled_session(),
+		..Default::default()
+	};
+	Paras::initializer_on_new_session(&notification);
+	Scheduler::initializer_on_new_session(&notification);
+
+	next_block();
+}
+
+fn next_block() {
+	let b = System::block_number();
+	System::set_block_number(b + 1);
+	Scheduler::clear();
+	Scheduler::schedule(Vec::new(), b + 1);
+}
+
+#[test]
+fn spot_price_rises_with_queue_utilization() {
+	let threads = [ParaId::from(1), ParaId::from(2), ParaId::from(3)];
+
+	new_test_ext_with_config(default_config()).execute_with(|| {
+		// no validators, so claims stay in the queue.
+		activate_parathreads(&threads, &[]);
+		Balances::make_free_balance_be(&PAYER, 100_000);
+
+		// capacity of the queue: 2 cores * 2 lookahead.
+		assert_eq!(OnDemand::queue().capacity, 4);
+
+		for (queued, thread) in threads.iter().enumerate() {
+			let expected_price = 1_000 + 4_000 * queued as Balance / 4;
+			assert_eq!(OnDemand::spot_price(), expected_price);
+
+			assert_ok!(OnDemand::place_order(
+				RuntimeOrigin::signed(PAYER),
+				*thread,
+				collator(),
+				expected_price,
+			));
+			assert_last_event(
+				Event::OrderPlaced { para_id: *thread, payer: PAYER, price: expected_price }.into(),
+			);
+		}
+
+		assert_eq!(Balances::reserved_balance(PAYER), 1_000 + 2_000 + 3_000);
+		assert_eq!(
+			OnDemand::queue(),
+			OnDemandQueue { spot_price: 4_000, capacity: 4, queue: threads.to_vec() },
+		);
+	});
+}
+
+#[test]
+fn place_order_checks() {
+	let thread = ParaId::from(1);
+
+	new_test_ext_with_config(default_config()).execute_with(|| {
+		activate_parathreads(&[thread], &[]);
+		Balances::make_free_balance_be(&PAYER, 100_000);
+
+		assert_noop!(
+			OnDemand::place_order(RuntimeOrigin::signed(PAYER), thread, collator(), 999),
+			Error::<Test>::PriceAboveLimit,
+		);
+		assert_noop!(
+			OnDemand::place_order(RuntimeOrigin::signed(PAYER), ParaId::from(2), collator(), 1_000),
+			Error::<Test>::NotParathread,
+		);
+		assert_noop!(
+			OnDemand::place_order(RuntimeOrigin::signed(2), thread, collator(), 1_000),
+			Error::<Test>::InsufficientBalance,
+		);
+
+		assert_ok!(OnDemand::place_order(RuntimeOrigin::signed(PAYER), thread, collator(), 2_000));
+		assert_noop!(
+			OnDemand::place_order(RuntimeOrigin::signed(PAYER), thread, collator(), 2_000),
+			Error::<Test>::AlreadyClaimed,
+		);
+	});
+}
+
+#[test]
+fn price_is_taken_once_candidate_is_included() {
+	let thread = ParaId::from(1);
+
+	new_test_ext_with_config(default_config()).execute_with(|| {
+		activate_parathreads(&[thread], &[Sr25519Keyring::Alice, Sr25519Keyring::Bob]);
+		Balances::make_free_balance_be(&PAYER, 100_000);
+
+		assert_ok!(OnDemand::place_order(RuntimeOrigin::signed(PAYER), thread, collator(), 1_000));
+		next_block();
+
+		let core = Scheduler::scheduled()[0].core;
+		Scheduler::occupied(&[core]);
+		assert_matches::assert_matches!(
+			Scheduler::availability_cores()[core.0 as usize],
+			Some(CoreOccupied::Parathread(_))
+		);
+		// the order stays until the candidate is included.
+		assert!(Orders::<Test>::contains_key(thread));
+
+		Scheduler::free_cores(vec![(core, FreedReason::Concluded)]);
+
+		assert!(!Orders::<Test>::contains_key(thread));
+		assert_eq!(Balances::reserved_balance(PAYER), 0);
+		assert_eq!(Balances::free_balance(PAYER), 100_000 - 1_000);
+		assert_last_event(
+			Event::OrderFulfilled { para_id: thread, payer: PAYER, price: 1_000 }.into(),
+		);
+
+		// a new order can be placed for the parathread.
+		assert_ok!(OnDemand::place_order(RuntimeOrigin::signed(PAYER), thread, collator(), 1_000));
+	});
+}
+
+#[test]
+fn price_is_refunded_once_retries_are_exhausted() {
+	let thread = ParaId::from(1);
+
+	new_test_ext_with_config(default_config()).execute_with(|| {
+		activate_parathreads(&[thread], &[Sr25519Keyring::Alice, Sr25519Keyring::Bob]);
+		Balances::make_free_balance_be(&PAYER, 100_000);
+
+		assert_ok!(OnDemand::place_order(RuntimeOrigin::signed(PAYER), thread, collator(), 1_000));
+		assert_eq!(Balances::reserved_balance(PAYER), 1_000);
+
+		// the claim is scheduled, but never backed. With `parathread_retries: 1` it is scheduled
+		// twice before being dropped.
+		next_block();
+		assert_eq!(Scheduler::scheduled().len(), 1);
+		next_block();
+		assert_eq!(Scheduler::scheduled().len(), 1);
+		assert!(Orders::<Test>::contains_key(thread));
+		next_block();
+		assert!(Scheduler::scheduled().is_empty());
+
+		assert!(!Orders::<Test>::contains_key(thread));
+		assert_eq!(Balances::reserved_balance(PAYER), 0);
+		assert_eq!(Balances::free_balance(PAYER), 100_000);
+		assert_last_event(
+			Event::OrderRefunded { para_id: thread, payer: PAYER, price: 1_000 }.into(),
+		);
+
+		// the parathread can be ordered again.
+		assert_ok!(OnDemand::place_order(RuntimeOrigin::signed(PAYER), thread, collator(), 1_000));
+	});
+}
+
+#[test]
+fn price_is_refunded_when_parathread_cores_are_removed() {
+	let thread = ParaId::from(1);
+
+	new_test_ext_with_config(default_config()).execute_with(|| {
+		activate_parathreads(&[thread], &[]);
+		Balances::make_free_balance_be(&PAYER, 100_000);
+
+		assert_ok!(OnDemand::place_order(RuntimeOrigin::signed(PAYER), thread, collator(), 1_000));
+
+		Scheduler::initializer_on_new_session(&SessionChangeNotification {
+			new_config: HostConfiguration { parathread_cores: 0, ..default_config() },
+			..Default::default()
+		});
+
+		assert!(OnDemand::queue().queue.is_empty());
+		assert_eq!(Balances::reserved_balance(PAYER), 0);
+		assert_last_event(
+			Event::OrderRefunded { para_id: thread, payer: PAYER, price: 1_000 }.into(),
+		);
+	});
+}
@@ -0,0 +1,358 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A message queue per origin, split into bounded pages.
+//!
+//! The queue does not declare any storage itself. A pallet plugs in its storage items by
+//! implementing [`QueueStorage`] and then uses [`enqueue`] and [`QueueCache`] for appending and
+//! consuming messages. UMP keeps its upward message queues this way.
+
+use frame_support::pallet_prelude::*;
+use primitives::UpwardMessage;
+use scale_info::TypeInfo;
+use sp_std::{collections::btree_map::BTreeMap, mem, prelude::*};
+
+/// Index of a page within the queue of an origin.
+pub type PageIndex = u32;
+
+/// A message which can be kept in a paged queue.
+pub trait QueueMessage {
+	/// The size (in bytes) the message is accounted with.
+	fn size(&self) -> u32;
+}
+
+impl QueueMessage for UpwardMessage {
+	fn size(&self) -> u32 {
+		self.len() as u32
+	}
+}
+
+/// A bounded chunk of the queue of an origin.
+///
+/// Messages are appended to the last page of a queue as long as the page stays within the
+/// maximum page size. A page always accepts at least one message, so a message larger than the
+/// page size occupies a page of its own.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Page<M> {
+	/// The messages of the page, in FIFO order.
+	pub messages: Vec<M>,
+	/// The total size (in bytes) of the messages.
+	pub size: u32,
+}
+
+impl<M> Default for Page<M> {
+	fn default() -> Self {
+		Self { messages: Vec::new(), size: 0 }
+	}
+}
+
+impl<M: QueueMessage> Page<M> {
+	/// Creates a page holding the given messages.
+	pub fn from_messages(messages: Vec<M>) -> Self {
+		let size = messages.iter().fold(0u32, |size, msg| size.saturating_add(msg.size()));
+		Self { messages, size }
+	}
+
+	/// Returns whether a message of `msg_size` bytes can be appended without growing the page
+	/// past `max_size`.
+	pub(crate) fn has_room_for(&self, msg_size: u32, max_size: u32) -> bool {
+		self.messages.is_empty() || self.size.saturating_add(msg_size) <= max_size
+	}
+
+	pub(crate) fn push(&mut self, msg: M) {
+		self.size = self.size.saturating_add(msg.size());
+		self.messages.push(msg);
+	}
+}
+
+/// The pages making up the queue of an origin.
+#[derive(Encode, Decode, TypeInfo, Default, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct BookState {
+	/// The index of the first page holding messages waiting to be dispatched.
+	pub begin: PageIndex,
+	/// One past the index of the last page. The queue is empty if `begin == end`.
+	pub end: PageIndex,
+	/// The number of pages of this origin that are suspended.
+	pub suspended: u32,
+}
+
+impl BookState {
+	/// The number of pages holding messages waiting to be dispatched.
+	pub fn pages(&self) -> u32 {
+		self.end.saturating_sub(self.begin)
+	}
+
+	pub(crate) fn is_empty(&self) -> bool {
+		self.begin >= self.end
+	}
+}
+
+/// The storage backing paged queues.
+///
+/// Invariants the implementation can rely on, as maintained by this module:
+/// - The pages in `begin..end` of the book of an origin exist and hold at least one message.
+/// - A book is stored if and only if its origin has pages or suspended pages.
+/// - A footprint is stored if and only if its origin has pages.
+pub trait QueueStorage {
+	/// The origin of the messages, each origin has a queue of its own.
+	type Origin: Ord + Copy;
+	/// The messages of the queues.
+	type Message: QueueMessage + Clone;
+
+	/// The book of the queue of `origin`, empty if there is none.
+	fn book(origin: &Self::Origin) -> BookState;
+	/// Stores the book of the queue of `origin`, or removes it on `None`.
+	fn set_book(origin: &Self::Origin, book: Option<BookState>);
+	/// The page of `origin` at `index`.
+	fn page(origin: &Self::Origin, index: PageIndex) -> Option<Page<Self::Message>>;
+	/// Stores the page of `origin` at `index`, or removes it on `None`.
+	fn set_page(origin: &Self::Origin, index: PageIndex, page: Option<Page<Self::Message>>);
+	/// The count of messages waiting to be dispatched for `origin` and their total size.
+	fn footprint(origin: &Self::Origin) -> (u32, u32);
+	/// Stores the footprint of the queue of `origin`, or removes it on `None`.
+	fn set_footprint(origin: &Self::Origin, footprint: Option<(u32, u32)>);
+}
+
+/// The outcome of [`enqueue`].
+#[derive(Default, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct Enqueued {
+	/// The number of messages appended.
+	pub count: u32,
+	/// The total size of the messages appended.
+	pub size: u32,
+	/// The number of pages written, including the page which was the last one before.
+	pub pages_written: u32,
+}
+
+/// Appends `messages` to the queue of `origin`.
+///
+/// The messages are appended to the last page of the queue, opening new pages as the pages
+/// grow past `max_page_size`.
+pub fn enqueue<S: QueueStorage>(
+	origin: &S::Origin,
+	messages: impl IntoIterator<Item = S::Message>,
+	max_page_size: u32,
+) -> Enqueued {
+	let mut messages = messages.into_iter().peekable();
+	if messages.peek().is_none() {
+		return Enqueued::default()
+	}
+
+	let mut book = S::book(origin);
+	let (mut tail_index, mut tail) = if book.is_empty() {
+		book.end += 1;
+		(book.end - 1, Page::default())
+	} else {
+		let tail_index = book.end - 1;
+		(tail_index, S::page(origin, tail_index).unwrap_or_default())
+	};
+
+	let mut enqueued = Enqueued { pages_written: 1, ..Default::default() };
+	for msg in messages {
+		let msg_size = msg.size();
+		if !tail.has_room_for(msg_size, max_page_size) {
+			S::set_page(origin, tail_index, Some(mem::take(&mut tail)));
+			tail_index = book.end;
+			book.end += 1;
+			enqueued.pages_written += 1;
+		}
+		tail.push(msg);
+		enqueued.count += 1;
+		enqueued.size += msg_size;
+	}
+	S::set_page(origin, tail_index, Some(tail));
+	S::set_book(origin, Some(book));
+
+	let (count, size) = S::footprint(origin);
+	S::set_footprint(origin, Some((count + enqueued.count, size + enqueued.size)));
+
+	enqueued
+}
+
+/// Notes that a suspended page of `origin` was fully dispatched.
+pub fn release_suspended<S: QueueStorage>(origin: &S::Origin) {
+	let mut book = S::book(origin);
+	book.suspended = book.suspended.saturating_sub(1);
+	if book.is_empty() && book.suspended == 0 {
+		// the book is gone already if the origin was cleaned up in the meantime.
+		S::set_book(origin, None);
+	} else {
+		S::set_book(origin, Some(book));
+	}
+}
+
+/// To avoid constant fetching, deserializing and serialization the queues are cached.
+///
+/// Only the book and the page at the front of a queue are kept in this struct. Pages that were
+/// fully consumed are removed from the storage right away, while the page at the front is stored
+/// only when the cache is flushed.
+///
+/// This struct is not supposed to be dropped but rather to be consumed by [`flush`].
+pub struct QueueCache<S: QueueStorage> {
+	entries: BTreeMap<S::Origin, QueueCacheEntry<S::Message>>,
+	/// The number of pages loaded from the storage so far.
+	pages_loaded: u32,
+}
+
+struct QueueCacheEntry<M> {
+	book: BookState,
+	/// The page at `book.begin`, if it has been loaded.
+	head: Option<Page<M>>,
+	/// The number of messages consumed from the front of `head`.
+	consumed: usize,
+	/// The count of messages and their total size, as in `QueueStorage::footprint`.
+	count: u32,
+	size: u32,
+}
+
+impl<M: QueueMessage + Clone> QueueCacheEntry<M> {
+	/// Makes sure that `head` holds the first unconsumed message of the queue, unless the queue is
+	/// empty.
+	///
+	/// Pages which were fully consumed are removed from the storage. Returns the number of pages
+	/// loaded.
+	fn settle<S: QueueStorage<Message = M>>(&mut self, origin: &S::Origin) -> u32 {
+		let mut loaded = 0;
+		loop {
+			if let Some(page) = &self.head {
+				if self.consumed < page.messages.len() {
+					return loaded
+				}
+				S::set_page(origin, self.book.begin, None);
+				self.book.begin += 1;
+				self.head = None;
+				self.consumed = 0;
+			}
+			if self.book.is_empty() {
+				return loaded
+			}
+			self.head = S::page(origin, self.book.begin);
+			loaded += 1;
+			if self.head.is_none() {
+				// we maintain an invariant that all pages of the book exist. Skip the page as a
+				// safe bet.
+				debug_assert!(false);
+				self.book.begin += 1;
+			}
+		}
+	}
+}
+
+impl<S: QueueStorage> QueueCache<S> {
+	pub fn new() -> Self {
+		Self { entries: BTreeMap::new(), pages_loaded: 0 }
+	}
+
+	/// The number of pages loaded from the storage so far.
+	pub fn pages_loaded(&self) -> u32 {
+		self.pages_loaded
+	}
+
+	fn ensure_cached(&mut self, origin: S::Origin) -> &mut QueueCacheEntry<S::Message> {
+		let entry = self.entries.entry(origin).or_insert_with(|| {
+			let (count, size) = S::footprint(&origin);
+			QueueCacheEntry { book: S::book(&origin), head: None, consumed: 0, count, size }
+		});
+		self.pages_loaded += entry.settle::<S>(&origin);
+		entry
+	}
+
+	/// Returns the message at the front of `origin`'s queue, or `None` if the queue is empty.
+	///
+	/// Does not mutate the queue.
+	pub fn peek_front(&mut self, origin: S::Origin) -> Option<&S::Message> {
+		let entry = self.ensure_cached(origin);
+		entry.head.as_ref().and_then(|page| page.messages.get(entry.consumed))
+	}
+
+	/// Attempts to remove one message from the front of `origin`'s queue. If the queue is empty,
+	/// then does nothing.
+	///
+	/// Returns whether a message was removed.
+	pub fn consume_front(&mut self, origin: S::Origin) -> bool {
+		let entry = self.ensure_cached(origin);
+
+		match entry.head.as_ref().and_then(|page| page.messages.get(entry.consumed)) {
+			Some(msg) => {
+				entry.size = entry.size.saturating_sub(msg.size());
+				entry.count = entry.count.saturating_sub(1);
+				entry.consumed += 1;
+				true
+			},
+			None => false,
+		}
+	}
+
+	/// Removes the rest of the page at the front of `origin`'s queue, starting with the message at
+	/// the front, and notes it as suspended. If the queue is empty, then does nothing.
+	pub fn suspend_front(&mut self, origin: S::Origin) -> Option<Page<S::Message>> {
+		let entry = self.ensure_cached(origin);
+		let mut head = entry.head.take()?;
+
+		let page = Page::from_messages(head.messages.split_off(entry.consumed));
+		entry.count = entry.count.saturating_sub(page.messages.len() as u32);
+		entry.size = entry.size.saturating_sub(page.size);
+		entry.book.suspended += 1;
+
+		S::set_page(&origin, entry.book.begin, None);
+		entry.book.begin += 1;
+		entry.consumed = 0;
+
+		Some(page)
+	}
+
+	/// Returns if the queue for the given origin is empty.
+	///
+	/// That is, if this returns `true` then the next call to [`peek_front`] will return `None`.
+	///
+	/// Does not mutate the queue.
+	pub fn is_empty(&mut self, origin: S::Origin) -> bool {
+		self.ensure_cached(origin).book.is_empty()
+	}
+
+	/// Flushes the updated queues into the storage.
+	pub fn flush(self) {
+		// NOTE we use an explicit method here instead of Drop impl because it has unwanted
+		// semantics within runtime. It is dangerous to use because of double-panics and flushing
+		// on a panic is not necessary as well.
+		for (origin, mut entry) in self.entries {
+			// make sure a fully consumed head page is not written back.
+			entry.settle::<S>(&origin);
+
+			if let Some(head) = entry.head {
+				if entry.consumed > 0 {
+					S::set_page(
+						&origin,
+						entry.book.begin,
+						Some(Page::from_messages(head.messages[entry.consumed..].to_vec())),
+					);
+				}
+			}
+
+			if entry.book.is_empty() {
+				S::set_footprint(&origin, None);
+			} else {
+				S::set_footprint(&origin, Some((entry.count, entry.size)));
+			}
+
+			if entry.book.is_empty() && entry.book.suspended == 0 {
+				S::set_book(&origin, None);
+			} else {
+				S::set_book(&origin, Some(entry.book));
+			}
+		}
+	}
+}
@@ -143,6 +143,23 @@ benchmarks! {
 		let code_hash = [0; 32].into();
 	}: _(RawOrigin::Root, code_hash)
 
+	force_set_core_count {
+		let para_id = ParaId::from(1000);
+	}: _(RawOrigin::Root, para_id, 2)
+	verify {
+		assert_last_event::<T>(Event::CoreCountScheduled(para_id, 2).into());
+	}
+
+	cancel_code_upgrade {
+		let para_id = ParaId::from(1000);
+		let config = HostConfiguration::<T::BlockNumber>::default();
+		let now = frame_system::Pallet::<T>::block_number();
+		Pallet::<T>::schedule_code_upgrade(para_id, ValidationCode(vec![0]), now, &config);
+	}: _(RawOrigin::Root, para_id)
+	verify {
+		assert_last_event::<T>(Event::CodeUpgradeCancelled(para_id).into());
+	}
+
 	include_pvf_check_statement {
 		let (stmt, signature) = pvf_check::prepare_inclusion_bench::<T>();
 	}: {
@@ -107,20 +107,21 @@
 //! ```
 //!
 
-use crate::{configuration, initializer::SessionChangeNotification, shared};
+use crate::{configuration, ensure_parachain, initializer::SessionChangeNotification, shared};
 use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
 use frame_support::{pallet_prelude::*, traits::EstimateNextSessionRotation};
 use frame_system::pallet_prelude::*;
 use parity_scale_codec::{Decode, Encode};
 use primitives::{
-	ConsensusLog, HeadData, Id as ParaId, PvfCheckStatement, SessionIndex, UpgradeGoAhead,
-	UpgradeRestriction, ValidationCode, ValidationCodeHash, ValidatorSignature,
+	vstaging::UpgradePipeline, ConsensusLog, HeadData, Id as ParaId, PvfCheckStatement,
+	SessionIndex, UpgradeGoAhead, UpgradeRestriction, ValidationCode, ValidationCodeHash,
+	ValidatorSignature,
 };
 use scale_info::{Type, TypeInfo};
 use sp_core::RuntimeDebug;
 use sp_runtime::{
 	traits::{AppVerify, One, Saturating},
-	DispatchResult, SaturatedConversion,
+	DispatchError, DispatchResult, SaturatedConversion,
 };
 use sp_std::{cmp, mem, prelude::*};
 
@@ -475,6 +476,8 @@ pub trait WeightInfo {
 	fn force_queue_action() -> Weight;
 	fn add_trusted_validation_code(c: u32) -> Weight;
 	fn poke_unused_validation_code() -> Weight;
+	fn force_set_core_count() -> Weight;
+	fn cancel_code_upgrade() -> Weight;
 
 	fn include_pvf_check_statement_finalize_upgrade_accept() -> Weight;
 	fn include_pvf_check_statement_finalize_upgrade_reject() -> Weight;
@@ -506,6 +509,12 @@ impl WeightInfo for TestWeightInfo {
 	fn poke_unused_validation_code() -> Weight {
 		Weight::MAX
 	}
+	fn force_set_core_count() -> Weight {
+		Weight::MAX
+	}
+	fn cancel_code_upgrade() -> Weight {
+		Weight::MAX
+	}
 	fn include_pvf_check_statement_finalize_upgrade_accept() -> Weight {
 		Weight::MAX
 	}
@@ -546,6 +555,11 @@ pub mod pallet {
 	{
 		type RuntimeEvent: From<Event> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
+		/// The outer origin type, used to allow paras to cancel their own code upgrades.
+		type RuntimeOrigin: From<crate::Origin>
+			+ From<<Self as frame_system::Config>::RuntimeOrigin>
+			+ Into<Result<crate::Origin, <Self as Config>::RuntimeOrigin>>;
+
 		#[pallet::constant]
 		type UnsignedPriority: Get<TransactionPriority>;
 
@@ -577,6 +591,11 @@ pub mod pallet {
 		/// The given validation code was rejected by the PVF pre-checking vote.
 		/// `code_hash` `para_id`
 		PvfCheckRejected(ValidationCodeHash, ParaId),
+		/// The number of cores assigned to a parachain will change at the next session.
+		/// `para_id` `core_count`
+		CoreCountScheduled(ParaId, u32),
+		/// The pending code upgrade of a para has been cancelled. `para_id`
+		CodeUpgradeCancelled(ParaId),
 	}
 
 	#[pallet::error]
@@ -608,6 +627,12 @@ pub mod pallet {
 		PvfCheckDisabled,
 		/// Parachain cannot currently schedule a code upgrade.
 		CannotUpgradeCode,
+		/// A para must be assigned at least one core.
+		ZeroCoreCount,
+		/// The para has no code upgrade pending which passed PVF pre-checking.
+		NoUpgradeToCancel,
+		/// The para was already signalled to go ahead with the code upgrade.
+		UpgradeGoAheadSignalled,
 	}
 
 	/// All currently active PVF pre-checking votes.
@@ -748,6 +773,18 @@ pub mod pallet {
 	pub(super) type UpcomingParasGenesis<T: Config> =
 		StorageMap<_, Twox64Concat, ParaId, ParaGenesisArgs>;
 
+	/// The number of availability cores assigned to a parachain during the current session, if
+	/// it is more than one.
+	///
+	/// Candidates of a parachain with more than one core may form a chain, where each candidate
+	/// builds on the head of a candidate that is still pending availability.
+	#[pallet::storage]
+	pub(super) type CoreCount<T: Config> = StorageMap<_, Twox64Concat, ParaId, u32>;
+
+	/// Core counts which take effect at the start of the next session.
+	#[pallet::storage]
+	pub(super) type UpcomingCoreCount<T: Config> = StorageMap<_, Twox64Concat, ParaId, u32>;
+
 	/// The number of reference on the validation code in [`CodeByHash`] storage.
 	#[pallet::storage]
 	pub(super) type CodeByHashRefs<T: Config> =
@@ -1051,6 +1088,40 @@ pub mod pallet {
 				Ok(Some(<T as Config>::WeightInfo::include_pvf_check_statement()).into())
 			}
 		}
+
+		/// Set the number of availability cores the given parachain is assigned, starting with
+		/// the next session.
+		///
+		/// With more than one core, the parachain may have a chain of candidates pending
+		/// availability, one per core.
+		#[pallet::call_index(8)]
+		#[pallet::weight(<T as Config>::WeightInfo::force_set_core_count())]
+		pub fn force_set_core_count(
+			origin: OriginFor<T>,
+			para: ParaId,
+			core_count: u32,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(core_count > 0, Error::<T>::ZeroCoreCount);
+			UpcomingCoreCount::<T>::insert(&para, core_count);
+			Self::deposit_event(Event::CoreCountScheduled(para, core_count));
+			Ok(())
+		}
+
+		/// Cancel the pending code upgrade of a para, once its new validation code has passed PVF
+		/// pre-checking and until the para is signalled to go ahead with the upgrade.
+		///
+		/// Can be called by Root or the para itself. The para observes the `Abort` go-ahead
+		/// signal until its next candidate is included. The upgrade restriction stays in place
+		/// until the upgrade cooldown expires.
+		#[pallet::call_index(9)]
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_code_upgrade())]
+		pub fn cancel_code_upgrade(origin: OriginFor<T>, para: ParaId) -> DispatchResult {
+			Self::ensure_root_or_para(origin, para)?;
+			Self::do_cancel_code_upgrade(para)?;
+			Self::deposit_event(Event::CodeUpgradeCancelled(para));
+			Ok(())
+		}
 	}
 
 	#[pallet::validate_unsigned]
@@ -1173,10 +1244,34 @@ impl<T: Config> Pallet<T> {
 		notification: &SessionChangeNotification<T::BlockNumber>,
 	) -> Vec<ParaId> {
 		let outgoing_paras = Self::apply_actions_queue(notification.session_index);
+		Self::apply_upcoming_core_counts();
 		Self::groom_ongoing_pvf_votes(&notification.new_config, notification.validators.len());
 		outgoing_paras
 	}
 
+	/// The number of availability cores assigned to the given parachain in the current session.
+	pub(crate) fn core_count(para_id: ParaId) -> u32 {
+		CoreCount::<T>::get(&para_id).unwrap_or(1)
+	}
+
+	// Apply the core counts scheduled for this session.
+	//
+	// Must be called after the actions queue was applied, so that counts of paras which went
+	// away in the meantime are dropped.
+	fn apply_upcoming_core_counts() {
+		for (para, core_count) in UpcomingCoreCount::<T>::drain() {
+			if !ParaLifecycles::<T>::contains_key(&para) {
+				continue
+			}
+
+			if core_count > 1 {
+				CoreCount::<T>::insert(&para, core_count);
+			} else {
+				CoreCount::<T>::remove(&para);
+			}
+		}
+	}
+
 	/// The validation code of live para.
 	pub(crate) fn current_code(para_id: &ParaId) -> Option<ValidationCode> {
 		Self::current_code_hash(para_id).and_then(|code_hash| {
@@ -1235,6 +1330,7 @@ impl<T: Config> Pallet<T> {
 					<Self as Store>::FutureCodeUpgrades::remove(&para);
 					<Self as Store>::UpgradeGoAheadSignal::remove(&para);
 					<Self as Store>::UpgradeRestrictionSignal::remove(&para);
+					CoreCount::<T>::remove(&para);
 					ParaLifecycles::<T>::remove(&para);
 					let removed_future_code_hash = <Self as Store>::FutureCodeHash::take(&para);
 					if let Some(removed_future_code_hash) = removed_future_code_hash {
@@ -1589,6 +1685,46 @@ impl<T: Config> Pallet<T> {
 		weight
 	}
 
+	/// Ensure the origin is one of Root or the `para` itself.
+	fn ensure_root_or_para(origin: OriginFor<T>, para: ParaId) -> DispatchResult {
+		if let Ok(caller) = ensure_parachain(<T as Config>::RuntimeOrigin::from(origin.clone())) {
+			ensure!(caller == para, DispatchError::BadOrigin);
+		} else {
+			ensure_root(origin)?;
+		}
+		Ok(())
+	}
+
+	/// Undo everything `schedule_code_upgrade` and `proceed_with_upgrade` did for the pending
+	/// upgrade of the para, apart from the upgrade cooldown.
+	///
+	/// Upgrades still undergoing PVF pre-checking can not be cancelled.
+	fn do_cancel_code_upgrade(id: ParaId) -> DispatchResult {
+		ensure!(FutureCodeUpgrades::<T>::contains_key(&id), Error::<T>::NoUpgradeToCancel);
+		// Once the go-ahead was signalled the para may have enacted the new code in its state
+		// already, so the upgrade can only be cancelled while it is upcoming.
+		UpcomingUpgrades::<T>::try_mutate(|upcoming_upgrades| {
+			let idx = upcoming_upgrades
+				.iter()
+				.position(|&(para, _)| para == id)
+				.ok_or(Error::<T>::UpgradeGoAheadSignalled)?;
+			upcoming_upgrades.remove(idx);
+			Ok::<_, Error<T>>(())
+		})?;
+		FutureCodeUpgrades::<T>::remove(&id);
+
+		// The para still waits for the go-ahead, so it must learn that the upgrade will not
+		// happen. The signal is reset with the next head of the para.
+		UpgradeGoAheadSignal::<T>::insert(&id, UpgradeGoAhead::Abort);
+
+		if let Some(code_hash) = FutureCodeHash::<T>::take(&id) {
+			Self::decrease_code_ref(&code_hash);
+		} else {
+			log::error!(target: LOG_TARGET, "Missing future code hash for {:?}", &id);
+		}
+		Ok(())
+	}
+
 	/// Verify that `schedule_para_initialize` can be called successfully.
 	///
 	/// Returns false if para is already registered in the system.
@@ -2038,6 +2174,33 @@ impl<T: Config> Pallet<T> {
 		FutureCodeHash::<T>::get(&id).is_none() && UpgradeRestrictionSignal::<T>::get(&id).is_none()
 	}
 
+	/// The state of the code upgrade pipeline of the para, or `None` if the para is not
+	/// registered.
+	pub(crate) fn upgrade_pipeline(id: ParaId) -> Option<UpgradePipeline<T::BlockNumber>> {
+		ParaLifecycles::<T>::get(&id)?;
+
+		let now = frame_system::Pallet::<T>::block_number();
+		let future_code_hash = FutureCodeHash::<T>::get(&id);
+		let pvf_check_in_progress = future_code_hash
+			.map_or(false, |code_hash| PvfActiveVoteMap::<T>::contains_key(&code_hash));
+		let go_ahead_at = UpcomingUpgrades::<T>::get()
+			.into_iter()
+			.find_map(|(para, at)| (para == id).then_some(at));
+		let cooldown_until = UpgradeCooldowns::<T>::get()
+			.into_iter()
+			.find_map(|(para, at)| (para == id && at > now).then_some(at));
+
+		Some(UpgradePipeline {
+			future_code_hash,
+			pvf_check_in_progress,
+			expected_at: FutureCodeUpgrades::<T>::get(&id),
+			go_ahead_at,
+			go_ahead_signal: UpgradeGoAheadSignal::<T>::get(&id),
+			restriction_signal: UpgradeRestrictionSignal::<T>::get(&id),
+			cooldown_until,
+		})
+	}
+
 	/// Return the session index that should be used for any future scheduled changes.
 	fn scheduled_session() -> SessionIndex {
 		shared::Pallet::<T>::scheduled_session()
@@ -1698,6 +1698,245 @@ fn add_trusted_validation_code_enacts_existing_pvf_vote() {
 	});
 }
 
+#[test]
+fn force_set_core_count_applies_at_session_change() {
+	let paras = vec![(
+		0u32.into(),
+		ParaGenesisArgs {
+			para_kind: ParaKind::Parachain,
+			genesis_head: dummy_head_data(),
+			validation_code: dummy_validation_code(),
+		},
+	)];
+
+	let genesis_config = MockGenesisConfig {
+		paras: GenesisConfig { paras, ..Default::default() },
+		..Default::default()
+	};
+
+	new_test_ext(genesis_config).execute_with(|| {
+		let id_a = ParaId::from(0u32);
+		let unknown = ParaId::from(7u32);
+
+		assert_err!(
+			Paras::force_set_core_count(RuntimeOrigin::root(), id_a, 0),
+			Error::<Test>::ZeroCoreCount,
+		);
+		assert_ok!(Paras::force_set_core_count(RuntimeOrigin::root(), id_a, 2));
+		assert_ok!(Paras::force_set_core_count(RuntimeOrigin::root(), unknown, 3));
+
+		// Nothing changes within the current session.
+		assert_eq!(Paras::core_count(id_a), 1);
+
+		run_to_block(2, Some(vec![2]));
+
+		assert_eq!(Paras::core_count(id_a), 2);
+		assert_eq!(Paras::core_count(unknown), 1);
+		assert!(!CoreCount::<Test>::contains_key(&unknown));
+		assert_eq!(UpcomingCoreCount::<Test>::iter().count(), 0);
+
+		// Going back to a single core removes the entry.
+		assert_ok!(Paras::force_set_core_count(RuntimeOrigin::root(), id_a, 1));
+		run_to_block(3, Some(vec![3]));
+
+		assert_eq!(Paras::core_count(id_a), 1);
+		assert!(!CoreCount::<Test>::contains_key(&id_a));
+	});
+}
+
+#[test]
+fn cancel_code_upgrade_cleans_up_pending_upgrade() {
+	let validation_upgrade_delay = 5;
+	let validation_upgrade_cooldown = 10;
+
+	let original_code = ValidationCode(vec![1, 2, 3]);
+	let paras = vec![(
+		0u32.into(),
+		ParaGenesisArgs {
+			para_kind: ParaKind::Parachain,
+			genesis_head: dummy_head_data(),
+			validation_code: original_code.clone(),
+		},
+	)];
+
+	let genesis_config = MockGenesisConfig {
+		paras: GenesisConfig { paras, ..Default::default() },
+		configuration: crate::configuration::GenesisConfig {
+			config: HostConfiguration {
+				validation_upgrade_delay,
+				validation_upgrade_cooldown,
+				pvf_checking_enabled: false,
+				..Default::default()
+			},
+			..Default::default()
+		},
+		..Default::default()
+	};
+
+	new_test_ext(genesis_config).execute_with(|| {
+		let para_id = ParaId::from(0);
+		let para_origin = |id: ParaId| RuntimeOrigin::from(crate::Origin::Parachain(id));
+		let new_code = ValidationCode(vec![4, 5, 6]);
+
+		run_to_block(2, None);
+
+		let expected_at = 1 + validation_upgrade_delay;
+		Paras::schedule_code_upgrade(para_id, new_code.clone(), 1, &Configuration::config());
+		Paras::note_new_head(para_id, Default::default(), 1);
+		check_code_is_stored(&new_code);
+
+		assert_eq!(
+			Paras::upgrade_pipeline(para_id),
+			Some(UpgradePipeline {
+				future_code_hash: Some(new_code.hash()),
+				pvf_check_in_progress: false,
+				expected_at: Some(expected_at),
+				go_ahead_at: Some(expected_at),
+				go_ahead_signal: None,
+				restriction_signal: Some(UpgradeRestriction::Present),
+				cooldown_until: Some(1 + validation_upgrade_cooldown),
+			}),
+		);
+		assert_eq!(Paras::upgrade_pipeline(ParaId::from(1)), None);
+
+		// Only Root and the para itself may cancel.
+		assert_err!(
+			Paras::cancel_code_upgrade(RuntimeOrigin::signed(1), para_id),
+			DispatchError::BadOrigin,
+		);
+		assert_err!(
+			Paras::cancel_code_upgrade(para_origin(ParaId::from(1)), para_id),
+			DispatchError::BadOrigin,
+		);
+
+		// The para was not signalled to go ahead yet.
+		run_to_block(expected_at - 1, None);
+		assert!(<Paras as Store>::UpgradeGoAheadSignal::get(&para_id).is_none());
+
+		assert_ok!(Paras::cancel_code_upgrade(para_origin(para_id), para_id));
+		System::assert_last_event(Event::CodeUpgradeCancelled(para_id).into());
+
+		assert!(<Paras as Store>::FutureCodeUpgrades::get(&para_id).is_none());
+		assert!(<Paras as Store>::FutureCodeHash::get(&para_id).is_none());
+		assert!(<Paras as Store>::UpcomingUpgrades::get().is_empty());
+		check_code_is_not_stored(&new_code);
+		check_code_is_stored(&original_code);
+		assert_eq!(
+			Paras::upgrade_pipeline(para_id),
+			Some(UpgradePipeline {
+				go_ahead_signal: Some(UpgradeGoAhead::Abort),
+				restriction_signal: Some(UpgradeRestriction::Present),
+				cooldown_until: Some(1 + validation_upgrade_cooldown),
+				..Default::default()
+			}),
+		);
+
+		assert_err!(
+			Paras::cancel_code_upgrade(RuntimeOrigin::root(), para_id),
+			Error::<Test>::NoUpgradeToCancel,
+		);
+
+		// The next head of the para is not subject to the upgrade and resets the signal.
+		Paras::note_new_head(para_id, Default::default(), expected_at);
+		assert_eq!(Paras::current_code(&para_id), Some(original_code.clone()));
+		assert!(<Paras as Store>::UpgradeGoAheadSignal::get(&para_id).is_none());
+	});
+}
+
+#[test]
+fn cancel_code_upgrade_is_rejected_after_go_ahead() {
+	let validation_upgrade_delay = 5;
+
+	let paras = vec![(
+		0u32.into(),
+		ParaGenesisArgs {
+			para_kind: ParaKind::Parachain,
+			genesis_head: dummy_head_data(),
+			validation_code: ValidationCode(vec![1, 2, 3]),
+		},
+	)];
+
+	let genesis_config = MockGenesisConfig {
+		paras: GenesisConfig { paras, ..Default::default() },
+		configuration: crate::configuration::GenesisConfig {
+			config: HostConfiguration {
+				validation_upgrade_delay,
+				pvf_checking_enabled: false,
+				..Default::default()
+			},
+			..Default::default()
+		},
+		..Default::default()
+	};
+
+	new_test_ext(genesis_config).execute_with(|| {
+		let para_id = ParaId::from(0);
+		let new_code = ValidationCode(vec![4, 5, 6]);
+
+		run_to_block(2, None);
+
+		let expected_at = 1 + validation_upgrade_delay;
+		Paras::schedule_code_upgrade(para_id, new_code.clone(), 1, &Configuration::config());
+		Paras::note_new_head(para_id, Default::default(), 1);
+
+		// The para may have enacted the new code in its state after observing the go-ahead.
+		run_to_block(expected_at + 1, None);
+		assert_eq!(
+			<Paras as Store>::UpgradeGoAheadSignal::get(&para_id),
+			Some(UpgradeGoAhead::GoAhead),
+		);
+
+		assert_err!(
+			Paras::cancel_code_upgrade(RuntimeOrigin::root(), para_id),
+			Error::<Test>::UpgradeGoAheadSignalled,
+		);
+
+		// The upgrade is applied with the next head of the para.
+		Paras::note_new_head(para_id, Default::default(), expected_at);
+		assert_eq!(Paras::current_code(&para_id), Some(new_code));
+	});
+}
+
+#[test]
+fn cancel_code_upgrade_requires_accepted_pvf() {
+	let a = ParaId::from(111);
+	let new_code: ValidationCode = vec![3, 2, 1].into();
+
+	let paras = vec![(
+		a,
+		ParaGenesisArgs {
+			para_kind: ParaKind::Parachain,
+			genesis_head: Default::default(),
+			validation_code: vec![1, 2, 3].into(),
+		},
+	)];
+
+	let genesis_config = MockGenesisConfig {
+		paras: GenesisConfig { paras, ..Default::default() },
+		configuration: crate::configuration::GenesisConfig {
+			config: HostConfiguration { pvf_checking_enabled: true, ..Default::default() },
+			..Default::default()
+		},
+		..Default::default()
+	};
+
+	new_test_ext(genesis_config).execute_with(|| {
+		run_to_block(2, Some(vec![1]));
+
+		Paras::schedule_code_upgrade(a, new_code.clone(), 1, &Configuration::config());
+		let pipeline = Paras::upgrade_pipeline(a).unwrap();
+		assert!(pipeline.pvf_check_in_progress);
+		assert_eq!(pipeline.future_code_hash, Some(new_code.hash()));
+		assert_eq!(pipeline.expected_at, None);
+
+		assert_err!(
+			Paras::cancel_code_upgrade(RuntimeOrigin::root(), a),
+			Error::<Test>::NoUpgradeToCancel,
+		);
+		check_code_is_stored(&new_code);
+	});
+}
+
 #[test]
 fn verify_upgrade_go_ahead_signal_is_externally_accessible() {
 	use primitives::well_known_keys;
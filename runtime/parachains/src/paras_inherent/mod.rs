@@ -25,7 +25,7 @@ use crate::{
 	configuration,
 	disputes::{DisputesHandler, VerifyDisputeSignatures},
 	inclusion,
-	inclusion::{CandidateCheckContext, FullCheck},
+	inclusion::{CandidateCheckContext, CandidateParent, FullCheck},
 	initializer,
 	metrics::METRICS,
 	scheduler::{self, CoreAssignment, FreedReason},
@@ -711,21 +711,43 @@ impl<T: Config> Pallet<T> {
 			let parent_storage_root = *parent_header.state_root();
 
 			let check_ctx = CandidateCheckContext::<T>::new(now, relay_parent_number);
+			// The last accepted candidate of each para, which the next candidate of the same para
+			// has to build upon.
+			let mut chain_tails = BTreeMap::<ParaId, CandidateParent<T::BlockNumber>>::new();
 			let backed_candidates = sanitize_backed_candidates::<T, _>(
 				parent_hash,
 				backed_candidates,
 				move |candidate_idx: usize,
 				      backed_candidate: &BackedCandidate<<T as frame_system::Config>::Hash>|
 				      -> bool {
+					let para_id = backed_candidate.descriptor().para_id;
+					let parent = chain_tails
+						.get(&para_id)
+						.cloned()
+						.or_else(|| <inclusion::Pallet<T>>::expected_parent(para_id));
+
 					// never include a concluded-invalid candidate
-					concluded_invalid_disputes.contains(&backed_candidate.hash()) ||
-							// Instead of checking the candidates with code upgrades twice
-							// move the checking up here and skip it in the training wheels fallback.
-							// That way we avoid possible duplicate checks while assuring all
-							// backed candidates fine to pass on.
-							check_ctx
-								.verify_backed_candidate(parent_hash, parent_storage_root, candidate_idx, backed_candidate)
-								.is_err()
+					let candidate_hash = backed_candidate.hash();
+					let is_invalid = concluded_invalid_disputes.contains(&candidate_hash) ||
+						// Instead of checking the candidates with code upgrades twice
+						// move the checking up here and skip it in the training wheels fallback.
+						// That way we avoid possible duplicate checks while assuring all
+						// backed candidates fine to pass on.
+						check_ctx
+							.verify_backed_candidate(
+								parent_hash,
+								parent_storage_root,
+								candidate_idx,
+								backed_candidate,
+								parent.as_ref(),
+							)
+							.is_err();
+
+					if let (false, Some(parent)) = (is_invalid, parent) {
+						let child = parent.child(&backed_candidate.candidate.commitments);
+						chain_tails.insert(para_id, child);
+					}
+					is_invalid
 				},
 				&scheduled[..],
 			);
@@ -901,7 +923,25 @@ fn apply_weight_limit<T: Config + inclusion::Config>(
 				|c| backed_candidate_weight::<T>(c),
 				max_consumable_by_candidates,
 			);
-		candidates.indexed_retain(|idx, _backed_candidate| indices.binary_search(&idx).is_ok());
+		// The candidates of a para form a chain, so once a candidate was not picked, none of its
+		// descendants can be included either.
+		let mut broken_chains = BTreeSet::new();
+		let mut acc_candidate_weight = acc_candidate_weight;
+		candidates.indexed_retain(|idx, backed_candidate| {
+			let para_id = backed_candidate.descriptor().para_id;
+			let picked = indices.binary_search(&idx).is_ok();
+			if broken_chains.contains(&para_id) {
+				if picked {
+					acc_candidate_weight = acc_candidate_weight
+						.saturating_sub(backed_candidate_weight::<T>(backed_candidate));
+				}
+				return false
+			}
+			if !picked {
+				broken_chains.insert(para_id);
+			}
+			picked
+		});
 		// pick all bitfields, and
 		// fill the remaining space with candidates
 		let total_consumed = acc_candidate_weight.saturating_add(total_bitfields_weight);
@@ -1102,6 +1142,9 @@ pub(crate) fn assure_sanity_bitfields<T: crate::inclusion::Config>(
 /// `candidate_has_concluded_invalid_dispute` must return `true` if the candidate
 /// is disputed, false otherwise. The passed `usize` is the candidate index.
 ///
+/// Candidates of the same para are kept in the given order, which must be their chain order, and
+/// at most one candidate per scheduled core of the para is kept.
+///
 /// The returned `Vec` is sorted according to the occupied core index.
 fn sanitize_backed_candidates<
 	T: crate::inclusion::Config,
@@ -1118,10 +1161,13 @@ fn sanitize_backed_candidates<
 		!candidate_has_concluded_invalid_dispute_or_is_invalid(candidate_idx, backed_candidate)
 	});
 
-	let scheduled_paras_to_core_idx = scheduled
-		.into_iter()
-		.map(|core_assignment| (core_assignment.para_id, core_assignment.core))
-		.collect::<BTreeMap<ParaId, CoreIndex>>();
+	let scheduled_paras_to_core_idx = scheduled_paras_to_first_core_idx(scheduled);
+
+	// The number of free cores scheduled for each para.
+	let mut free_cores = BTreeMap::<ParaId, usize>::new();
+	for core_assignment in scheduled {
+		*free_cores.entry(core_assignment.para_id).or_default() += 1;
+	}
 
 	// Assure the backed candidate's `ParaId`'s core is free.
 	// This holds under the assumption that `Scheduler::schedule` is called _before_.
@@ -1129,8 +1175,17 @@ fn sanitize_backed_candidates<
 
 	backed_candidates.retain(|backed_candidate| {
 		let desc = backed_candidate.descriptor();
-		desc.relay_parent == relay_parent &&
-			scheduled_paras_to_core_idx.get(&desc.para_id).is_some()
+		if desc.relay_parent != relay_parent {
+			return false
+		}
+
+		match free_cores.get_mut(&desc.para_id) {
+			Some(n_free) if *n_free > 0 => {
+				*n_free -= 1;
+				true
+			},
+			_ => false,
+		}
 	});
 
 	// Sort the `Vec` last, once there is a guarantee that these
@@ -1138,6 +1193,8 @@ fn sanitize_backed_candidates<
 	// but more importantly are scheduled for a free core.
 	// This both avoids extra work for obviously invalid candidates,
 	// but also allows this to be done in place.
+	//
+	// The sorting is stable, so candidates of the same para stay in chain order.
 	backed_candidates.sort_by(|x, y| {
 		// Never panics, since we filtered all panic arguments out in the previous `fn retain`.
 		scheduled_paras_to_core_idx[&x.descriptor().para_id]
@@ -1172,10 +1229,7 @@ pub(crate) fn assure_sanity_backed_candidates<
 		}
 	}
 
-	let scheduled_paras_to_core_idx = scheduled
-		.into_iter()
-		.map(|core_assignment| (core_assignment.para_id, core_assignment.core))
-		.collect::<BTreeMap<ParaId, CoreIndex>>();
+	let scheduled_paras_to_core_idx = scheduled_paras_to_first_core_idx(scheduled);
 
 	if !IsSortedBy::is_sorted_by(backed_candidates, |x, y| {
 		// Never panics, since we would have early returned on those in the above loop.
@@ -1187,6 +1241,17 @@ pub(crate) fn assure_sanity_backed_candidates<
 	Ok(())
 }
 
+/// Map each scheduled para to the lowest of its scheduled core indices.
+fn scheduled_paras_to_first_core_idx(
+	scheduled: &[CoreAssignment],
+) -> BTreeMap<ParaId, CoreIndex> {
+	let mut paras_to_core_idx = BTreeMap::new();
+	for core_assignment in scheduled {
+		paras_to_core_idx.entry(core_assignment.para_id).or_insert(core_assignment.core);
+	}
+	paras_to_core_idx
+}
+
 /// Derive entropy from babe provided per block randomness.
 ///
 /// In the odd case none is available, uses the `parent_hash` and
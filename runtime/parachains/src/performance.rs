@@ -0,0 +1,234 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Per-validator accounting of parachain performance.
+//!
+//! Counts, for every session and validator, the included candidates the validator backed, the
+//! availability bitfields of the validator included on-chain, and the disputes the validator
+//! raised, won and lost. The counters are fed by `inclusion` and `disputes` through the
+//! [`RecordPerformance`] hooks and kept for as long as `session_info` keeps the session.
+//!
+//! Approval votes are not submitted on-chain, so approval no-shows can not be accounted for here.
+
+use crate::{session_info, shared};
+use frame_support::pallet_prelude::*;
+use primitives::{vstaging::ValidatorPerformance, SessionIndex, ValidatorIndex};
+use sp_std::prelude::*;
+
+pub use pallet::*;
+
+pub mod migration;
+
+#[cfg(test)]
+mod tests;
+
+/// The maximum number of counters pruned at a session change. The counters of a session are
+/// bounded by the number of validators, so this usually prunes at least one whole session.
+const PRUNE_LIMIT: u32 = 1_000;
+
+/// Hooks for accounting the parachain performance of validators.
+pub trait RecordPerformance {
+	/// Note that the validators backed a candidate which was included. The indices refer to the
+	/// active validators of the current session.
+	fn note_backed(validators: impl IntoIterator<Item = ValidatorIndex>);
+
+	/// Note that availability bitfields of the validators were included. The indices refer to the
+	/// active validators of the current session.
+	fn note_bitfields_included(validators: impl IntoIterator<Item = ValidatorIndex>);
+
+	/// Note that the validators raised a dispute about a candidate of the given session.
+	fn note_disputes_raised(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	);
+
+	/// Note that the validators voted on the winning side of a concluded dispute about a candidate
+	/// of the given session.
+	fn note_disputes_won(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	);
+
+	/// Note that the validators voted on the losing side of a concluded dispute about a candidate
+	/// of the given session.
+	fn note_disputes_lost(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	);
+
+	/// Called by the initializer to note that a new session has started.
+	fn initializer_on_new_session(session_index: SessionIndex);
+}
+
+impl RecordPerformance for () {
+	fn note_backed(_: impl IntoIterator<Item = ValidatorIndex>) {}
+
+	fn note_bitfields_included(_: impl IntoIterator<Item = ValidatorIndex>) {}
+
+	fn note_disputes_raised(_: SessionIndex, _: impl IntoIterator<Item = ValidatorIndex>) {}
+
+	fn note_disputes_won(_: SessionIndex, _: impl IntoIterator<Item = ValidatorIndex>) {}
+
+	fn note_disputes_lost(_: SessionIndex, _: impl IntoIterator<Item = ValidatorIndex>) {}
+
+	fn initializer_on_new_session(_: SessionIndex) {}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::without_storage_info]
+	#[pallet::storage_version(migration::STORAGE_VERSION)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + shared::Config + session_info::Config {
+		/// The outer event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A session ended. Its backing and bitfield counters are final, its dispute counters
+		/// may still change. `[session]`
+		SessionEnded(SessionIndex),
+		/// The counters of a session were pruned. `[session]`
+		SessionPruned(SessionIndex),
+	}
+
+	/// The performance counters of each validator, by session. Validators without any recorded
+	/// activity have no entry.
+	#[pallet::storage]
+	pub(super) type Performance<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		SessionIndex,
+		Twox64Concat,
+		ValidatorIndex,
+		ValidatorPerformance,
+		ValueQuery,
+	>;
+
+	/// The earliest session counters are stored for.
+	#[pallet::storage]
+	pub(super) type EarliestStoredSession<T: Config> = StorageValue<_, SessionIndex, ValueQuery>;
+}
+
+impl<T: Config> Pallet<T> {
+	/// Called by the initializer to note that a new session has started.
+	pub(crate) fn initializer_on_new_session(session_index: SessionIndex) {
+		// The genesis session does not end any session.
+		if let Some(ended) = session_index.checked_sub(1) {
+			Self::deposit_event(Event::SessionEnded(ended));
+		}
+
+		// Keep the counters of all sessions `session_info` keeps.
+		Self::prune_sessions_before(<session_info::Pallet<T>>::earliest_stored_session());
+	}
+
+	/// Prune the counters of the sessions before `earliest`, removing at most `PRUNE_LIMIT`
+	/// counters. The remaining ones are pruned at the next session changes.
+	fn prune_sessions_before(earliest: SessionIndex) {
+		let mut session = EarliestStoredSession::<T>::get();
+		let mut budget = PRUNE_LIMIT;
+
+		while session < earliest && budget > 0 {
+			let removed = Performance::<T>::clear_prefix(session, budget, None);
+			// Account for visiting a session without counters as well.
+			budget = budget.saturating_sub(removed.unique.max(1));
+			if removed.maybe_cursor.is_some() {
+				break
+			}
+			Self::deposit_event(Event::SessionPruned(session));
+			session += 1;
+		}
+
+		EarliestStoredSession::<T>::set(session);
+	}
+
+	/// The performance counters of all validators of the session, by validator index.
+	///
+	/// Returns `None` if the session is not known to `session_info`, or its counters were pruned.
+	pub(crate) fn session_performance(session: SessionIndex) -> Option<Vec<ValidatorPerformance>> {
+		if session < EarliestStoredSession::<T>::get() {
+			return None
+		}
+		let n_validators = <session_info::Pallet<T>>::session_info(session)?.validators.len();
+
+		let mut performance = vec![ValidatorPerformance::default(); n_validators];
+		for (validator, counters) in Performance::<T>::iter_prefix(session) {
+			if let Some(entry) = performance.get_mut(validator.0 as usize) {
+				*entry = counters;
+			}
+		}
+
+		Some(performance)
+	}
+
+	fn increment(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+		counter: impl Fn(&mut ValidatorPerformance) -> &mut u32,
+	) {
+		for validator in validators {
+			Performance::<T>::mutate(session, validator, |performance| {
+				let count = counter(performance);
+				*count = count.saturating_add(1);
+			});
+		}
+	}
+}
+
+impl<T: Config> RecordPerformance for Pallet<T> {
+	fn note_backed(validators: impl IntoIterator<Item = ValidatorIndex>) {
+		let session = shared::Pallet::<T>::session_index();
+		Self::increment(session, validators, |p| &mut p.backed_candidates);
+	}
+
+	fn note_bitfields_included(validators: impl IntoIterator<Item = ValidatorIndex>) {
+		let session = shared::Pallet::<T>::session_index();
+		Self::increment(session, validators, |p| &mut p.bitfields_included);
+	}
+
+	fn note_disputes_raised(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		Self::increment(session, validators, |p| &mut p.disputes_raised);
+	}
+
+	fn note_disputes_won(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		Self::increment(session, validators, |p| &mut p.disputes_won);
+	}
+
+	fn note_disputes_lost(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		Self::increment(session, validators, |p| &mut p.disputes_lost);
+	}
+
+	fn initializer_on_new_session(session_index: SessionIndex) {
+		Self::initializer_on_new_session(session_index)
+	}
+}
@@ -0,0 +1,72 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A module that is responsible for migration of storage.
+
+use crate::{
+	performance::{Config, EarliestStoredSession, Pallet},
+	shared,
+};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+pub mod v1 {
+	use super::*;
+
+	/// Initializes the pallet when it is added to a running chain.
+	///
+	/// No counters exist for the sessions before the current one, so they do not need to be
+	/// pruned and are not reported as stored.
+	pub struct MigrateToV1<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() != 0 {
+				log::warn!("skipping v1, should be removed");
+				return T::DbWeight::get().reads(1)
+			}
+
+			EarliestStoredSession::<T>::set(shared::Pallet::<T>::session_index());
+			STORAGE_VERSION.put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(2, 2)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{v1::MigrateToV1, *};
+	use crate::mock::{new_test_ext, MockGenesisConfig, Test};
+
+	#[test]
+	fn test_migrate_to_v1() {
+		new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+			shared::Pallet::<Test>::set_session_index(42);
+			StorageVersion::new(0).put::<Pallet<Test>>();
+
+			MigrateToV1::<Test>::on_runtime_upgrade();
+
+			assert_eq!(EarliestStoredSession::<Test>::get(), 42);
+			assert_eq!(StorageVersion::get::<Pallet<Test>>(), STORAGE_VERSION);
+			assert_eq!(Pallet::<Test>::session_performance(41), None);
+		});
+	}
+}
@@ -0,0 +1,176 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::{
+	configuration::HostConfiguration,
+	mock::{
+		new_test_ext, AccountId, AllPalletsWithSystem, Initializer, MockGenesisConfig,
+		ParasPerformance, RuntimeEvent, System, Test,
+	},
+};
+use frame_support::traits::{OnFinalize, OnInitialize};
+use primitives::{BlockNumber, ValidatorId};
+use sp_core::{crypto::CryptoType, Pair};
+
+fn genesis_config() -> MockGenesisConfig {
+	MockGenesisConfig {
+		configuration: crate::configuration::GenesisConfig {
+			config: HostConfiguration { dispute_period: 2, ..Default::default() },
+			..Default::default()
+		},
+		..Default::default()
+	}
+}
+
+// Run to the given block, starting a new session with the given validators at each block
+// returned by `new_session`.
+fn run_to_block(
+	to: BlockNumber,
+	new_session: impl Fn(BlockNumber) -> bool,
+	validators: &[(AccountId, ValidatorId)],
+) {
+	while System::block_number() < to {
+		let b = System::block_number();
+		if b != 0 {
+			// circumvent requirement to have bitfields and headers in block for testing purposes
+			crate::paras_inherent::Included::<Test>::set(Some(()));
+
+			AllPalletsWithSystem::on_finalize(b);
+			System::finalize();
+		}
+
+		System::reset_events();
+		System::initialize(&(b + 1), &Default::default(), &Default::default());
+		AllPalletsWithSystem::on_initialize(b + 1);
+
+		if new_session(b + 1) {
+			let validators = validators.iter().map(|(a, v)| (a, v.clone()));
+			Initializer::test_trigger_on_new_session(
+				true,
+				b + 1,
+				validators.clone(),
+				Some(validators),
+			);
+		}
+	}
+}
+
+#[test]
+fn counters_are_recorded_per_session_and_validator() {
+	let validators = (0..3)
+		.map(|i| (i as AccountId, <ValidatorId as CryptoType>::Pair::generate().0.public()))
+		.collect::<Vec<_>>();
+
+	new_test_ext(genesis_config()).execute_with(|| {
+		run_to_block(2, |b| b == 1, &validators);
+		assert_eq!(shared::Pallet::<Test>::session_index(), 1);
+
+		ParasPerformance::note_backed([ValidatorIndex(0), ValidatorIndex(2)]);
+		ParasPerformance::note_backed([ValidatorIndex(0)]);
+		ParasPerformance::note_bitfields_included([ValidatorIndex(0)]);
+		ParasPerformance::note_disputes_raised(1, [ValidatorIndex(1)]);
+		ParasPerformance::note_disputes_won(1, [ValidatorIndex(0), ValidatorIndex(2)]);
+		ParasPerformance::note_disputes_lost(1, [ValidatorIndex(1)]);
+
+		assert_eq!(
+			ParasPerformance::session_performance(1),
+			Some(vec![
+				ValidatorPerformance {
+					backed_candidates: 2,
+					bitfields_included: 1,
+					disputes_won: 1,
+					..Default::default()
+				},
+				ValidatorPerformance { disputes_raised: 1, disputes_lost: 1, ..Default::default() },
+				ValidatorPerformance {
+					backed_candidates: 1,
+					disputes_won: 1,
+					..Default::default()
+				},
+			]),
+		);
+
+		// Unknown sessions have no counters.
+		assert_eq!(ParasPerformance::session_performance(2), None);
+	});
+}
+
+#[test]
+fn counters_are_pruned_with_session_info() {
+	let validators = (0..2)
+		.map(|i| (i as AccountId, <ValidatorId as CryptoType>::Pair::generate().0.public()))
+		.collect::<Vec<_>>();
+
+	new_test_ext(genesis_config()).execute_with(|| {
+		run_to_block(2, |b| b == 1, &validators);
+		ParasPerformance::note_backed([ValidatorIndex(1)]);
+		assert!(ParasPerformance::session_performance(1).is_some());
+
+		// Session 4 is the first one with session 1 outside of the dispute period. It starts at
+		// the end of block 4.
+		run_to_block(4, |b| b >= 3, &validators);
+		assert_eq!(Performance::<Test>::iter_prefix(1).count(), 1);
+
+		crate::paras_inherent::Included::<Test>::set(Some(()));
+		AllPalletsWithSystem::on_finalize(4);
+		assert_eq!(shared::Pallet::<Test>::session_index(), 4);
+		assert_eq!(EarliestStoredSession::<Test>::get(), 2);
+		assert_eq!(Performance::<Test>::iter_prefix(1).count(), 0);
+		assert_eq!(ParasPerformance::session_performance(1), None);
+
+		let events = System::events().into_iter().map(|r| r.event).collect::<Vec<_>>();
+		assert!(events.contains(&Event::<Test>::SessionEnded(3).into()));
+		assert!(events.contains(&Event::<Test>::SessionPruned(1).into()));
+		// Session 0 was pruned before and is not reported again.
+		assert!(!events.contains(&Event::<Test>::SessionPruned(0).into()));
+	});
+}
+
+#[test]
+fn pruning_is_bounded() {
+	let mut ext = new_test_ext(genesis_config());
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		for session in 0..3 {
+			ParasPerformance::note_disputes_raised(session, (0..600).map(ValidatorIndex));
+		}
+	});
+	// The limit only applies to counters in the backend.
+	ext.commit_all().unwrap();
+
+	// All of session 0 and part of session 1.
+	ext.execute_with(|| {
+		Pallet::<Test>::prune_sessions_before(3);
+		assert_eq!(EarliestStoredSession::<Test>::get(), 1);
+		assert_eq!(Performance::<Test>::iter_prefix(0).count(), 0);
+		assert_eq!(Performance::<Test>::iter_prefix(1).count(), 200);
+	});
+	ext.commit_all().unwrap();
+
+	// The rest of session 1 and all of session 2.
+	ext.execute_with(|| {
+		Pallet::<Test>::prune_sessions_before(3);
+		assert_eq!(EarliestStoredSession::<Test>::get(), 3);
+		assert_eq!(Performance::<Test>::iter().count(), 0);
+
+		let pruned = System::events()
+			.into_iter()
+			.filter(|r| matches!(r.event, RuntimeEvent::ParasPerformance(_)))
+			.count();
+		assert_eq!(pruned, 3);
+	});
+}
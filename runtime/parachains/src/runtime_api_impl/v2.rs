@@ -50,7 +50,7 @@ pub fn validator_groups<T: initializer::Config>(
 /// Implementation for the `availability_cores` function of the runtime API.
 pub fn availability_cores<T: initializer::Config>() -> Vec<CoreState<T::Hash, T::BlockNumber>> {
 	let cores = <scheduler::Pallet<T>>::availability_cores();
-	let parachains = <paras::Pallet<T>>::parachains();
+	let parachain_cores = <scheduler::Pallet<T>>::parachain_cores();
 	let config = <configuration::Pallet<T>>::config();
 
 	let now = <frame_system::Pallet<T>>::block_number() + One::one();
@@ -102,10 +102,13 @@ pub fn availability_cores<T: initializer::Config>() -> Vec<CoreState<T::Hash, T:
 		.map(|(i, core)| match core {
 			Some(occupied) => CoreState::Occupied(match occupied {
 				CoreOccupied::Parachain => {
-					let para_id = parachains[i];
+					let para_id = parachain_cores[i];
 					let pending_availability =
-						<inclusion::Pallet<T>>::pending_availability(para_id)
-							.expect("Occupied core always has pending availability; qed");
+						<inclusion::Pallet<T>>::pending_availability_on_core(
+							para_id,
+							CoreIndex(i as u32),
+						)
+						.expect("Occupied core always has pending availability; qed");
 
 					let backed_in_number = *pending_availability.backed_in_number();
 					OccupiedCore {
@@ -16,8 +16,16 @@
 
 //! Put implementations of functions from staging APIs here.
 
-use crate::{disputes, session_info};
-use primitives::{vstaging::ExecutorParams, CandidateHash, DisputeState, SessionIndex};
+use crate::{
+	configuration, disputes, hrmp, inclusion, on_demand, paras, performance, session_info, ump,
+};
+use primitives::{
+	vstaging::{
+		slashing, ExecutorParams, HostConfigurationDiff, HrmpTopology, InconsistentError,
+		OnDemandQueue, UmpQueueFootprint, UpgradePipeline, ValidatorPerformance,
+	},
+	CandidateHash, CommittedCandidateReceipt, DisputeState, Id as ParaId, SessionIndex,
+};
 use sp_std::prelude::*;
 
 /// Implementation for `get_session_disputes` function from the runtime API
@@ -40,3 +48,65 @@ pub fn session_executor_params<T: session_info::Config>(
 		None => Some(ExecutorParams::default()),
 	}
 }
+
+/// Get the spot price and the contents of the on-demand parathread order queue.
+pub fn on_demand_queue<T: on_demand::Config>() -> OnDemandQueue {
+	<on_demand::Pallet<T>>::queue()
+}
+
+/// Get the footprint of the upward message queue of a para.
+pub fn ump_queue_footprint<T: ump::Config>(para_id: ParaId) -> UmpQueueFootprint {
+	<ump::Pallet<T>>::queue_footprint(para_id)
+}
+
+/// Get the open and pending HRMP channels of a para.
+pub fn hrmp_topology<T: hrmp::Config>(para_id: ParaId) -> HrmpTopology {
+	<hrmp::Pallet<T>>::topology(para_id)
+}
+
+/// Dry-run a host configuration diff and return every consistency error of the result.
+pub fn check_host_configuration_diff<T: configuration::Config>(
+	diff: HostConfigurationDiff<T::BlockNumber>,
+) -> Vec<InconsistentError<T::BlockNumber>> {
+	<configuration::Pallet<T>>::check_config_diff(diff)
+}
+
+/// Implementation of `unapplied_slashes` runtime API
+pub fn unapplied_slashes<T: disputes::slashing::Config>(
+) -> Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)> {
+	<disputes::slashing::Pallet<T>>::unapplied_slashes()
+}
+
+/// Implementation of `submit_report_dispute_lost` runtime API
+pub fn submit_unsigned_slashing_report<T: disputes::slashing::Config>(
+	dispute_proof: slashing::DisputeProof,
+	key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+) -> Option<()> {
+	let key_ownership_proof = key_ownership_proof.decode()?;
+
+	<disputes::slashing::Pallet<T>>::submit_unsigned_slashing_report(
+		dispute_proof,
+		key_ownership_proof,
+	)
+}
+
+/// Get all candidates pending availability for a para, in chain order.
+pub fn candidates_pending_availability<T: inclusion::Config>(
+	para_id: ParaId,
+) -> Vec<CommittedCandidateReceipt<T::Hash>> {
+	<inclusion::Pallet<T>>::candidates_pending_availability(para_id)
+}
+
+/// Get the parachain performance counters of all validators of a session.
+pub fn validator_performance<T: performance::Config>(
+	session_index: SessionIndex,
+) -> Option<Vec<ValidatorPerformance>> {
+	<performance::Pallet<T>>::session_performance(session_index)
+}
+
+/// Get the state of the code upgrade pipeline of a para.
+pub fn para_upgrade_pipeline<T: paras::Config>(
+	para_id: ParaId,
+) -> Option<UpgradePipeline<T::BlockNumber>> {
+	<paras::Pallet<T>>::upgrade_pipeline(para_id)
+}
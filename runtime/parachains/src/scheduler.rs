@@ -48,6 +48,8 @@ use crate::{configuration, initializer::SessionChangeNotification, paras};
 
 pub use pallet::*;
 
+pub mod migration;
+
 #[cfg(test)]
 mod tests;
 
@@ -92,6 +94,34 @@ impl ParathreadClaimQueue {
 	}
 }
 
+/// Reasons for a parathread claim to be rejected by [`Pallet::add_parathread_claim`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParathreadClaimError {
+	/// The para is not a live parathread.
+	NotParathread,
+	/// The parathread queue is full, or there are no parathread cores.
+	QueueFull,
+	/// There already is a claim for the parathread in the queue or on a core.
+	AlreadyClaimed,
+}
+
+/// Handler for the outcome of parathread claims.
+///
+/// There is at most one claim per parathread queued or being handled at any time.
+pub trait OnParathreadClaim {
+	/// A candidate of the parathread was included, the claim has been served.
+	fn on_claim_concluded(claim: &ParathreadClaim);
+
+	/// The claim was dropped without being served, because it ran out of retries or the para is
+	/// no longer a parathread.
+	fn on_claim_dropped(claim: &ParathreadClaim);
+}
+
+impl OnParathreadClaim for () {
+	fn on_claim_concluded(_: &ParathreadClaim) {}
+	fn on_claim_dropped(_: &ParathreadClaim) {}
+}
+
 impl Default for ParathreadClaimQueue {
 	fn default() -> Self {
 		Self { queue: vec![], next_core_offset: 0 }
@@ -160,10 +190,14 @@ pub mod pallet {
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
 	#[pallet::without_storage_info]
+	#[pallet::storage_version(migration::STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
-	pub trait Config: frame_system::Config + configuration::Config + paras::Config {}
+	pub trait Config: frame_system::Config + configuration::Config + paras::Config {
+		/// Notified when a parathread claim is served or dropped.
+		type OnParathreadClaim: OnParathreadClaim;
+	}
 
 	/// All the validator groups. One for each core. Indices are into `ActiveValidators` - not the
 	/// broader set of Polkadot validators, but instead just the subset used for parachains during
@@ -184,8 +218,8 @@ pub mod pallet {
 
 	/// One entry for each availability core. Entries are `None` if the core is not currently occupied. Can be
 	/// temporarily `Some` if scheduled but not occupied.
-	/// The i'th parachain belongs to the i'th core, with the remaining cores all being
-	/// parathread-multiplexers.
+	/// The i'th entry of `ParachainCores` belongs to the i'th core, with the remaining cores all
+	/// being parathread-multiplexers.
 	///
 	/// Bounded by the maximum of either of these two values:
 	///   * The number of parachains and parathread multiplexers
@@ -194,6 +228,15 @@ pub mod pallet {
 	#[pallet::getter(fn availability_cores)]
 	pub(crate) type AvailabilityCores<T> = StorageValue<_, Vec<Option<CoreOccupied>>, ValueQuery>;
 
+	/// The parachain assigned to each of the parachain cores for this session, which are the
+	/// first `ParachainCores::len()` availability cores.
+	///
+	/// Every parachain is assigned `paras::core_count` consecutive cores, in the order of
+	/// `paras::Parachains`.
+	#[pallet::storage]
+	#[pallet::getter(fn parachain_cores)]
+	pub(crate) type ParachainCores<T> = StorageValue<_, Vec<ParaId>, ValueQuery>;
+
 	/// An index used to ensure that only one claim on a parathread exists in the queue or is
 	/// currently being handled by an occupied core.
 	///
@@ -232,6 +275,18 @@ impl<T: Config> Pallet<T> {
 	/// Called by the initializer to finalize the scheduler pallet.
 	pub(crate) fn initializer_finalize() {}
 
+	/// Assign `paras::core_count` consecutive cores to every parachain, in the order of
+	/// `paras::Parachains`.
+	pub(crate) fn assign_parachain_cores() -> Vec<ParaId> {
+		<paras::Pallet<T>>::parachains()
+			.into_iter()
+			.flat_map(|para_id| {
+				let core_count = <paras::Pallet<T>>::core_count(para_id) as usize;
+				sp_std::iter::repeat(para_id).take(core_count)
+			})
+			.collect()
+	}
+
 	/// Called by the initializer to note that a new session has started.
 	pub(crate) fn initializer_on_new_session(
 		notification: &SessionChangeNotification<T::BlockNumber>,
@@ -240,9 +295,12 @@ impl<T: Config> Pallet<T> {
 		let config = new_config;
 
 		let mut thread_queue = ParathreadQueue::<T>::get();
-		let n_parachains = <paras::Pallet<T>>::parachains().len() as u32;
+		let parachain_cores = Self::assign_parachain_cores();
+		let n_parachain_cores = parachain_cores.len() as u32;
+		ParachainCores::<T>::set(parachain_cores);
+
 		let n_cores = core::cmp::max(
-			n_parachains + config.parathread_cores,
+			n_parachain_cores + config.parathread_cores,
 			match config.max_validators_per_core {
 				Some(x) if x != 0 => validators.len() as u32 / x,
 				_ => 0,
@@ -304,6 +362,9 @@ impl<T: Config> Pallet<T> {
 		ParathreadClaimIndex::<T>::mutate(|claim_index| {
 			// wipe all parathread metadata if no parathread cores are configured.
 			if config.parathread_cores == 0 {
+				for queued in thread_queue.queue.drain(..) {
+					T::OnParathreadClaim::on_claim_dropped(&queued.claim.claim);
+				}
 				thread_queue = ParathreadClaimQueue { queue: Vec::new(), next_core_offset: 0 };
 				claim_index.clear();
 				return
@@ -321,6 +382,8 @@ impl<T: Config> Pallet<T> {
 					if let Ok(i) = claim_index.binary_search(&claim_para) {
 						claim_index.remove(i);
 					}
+
+					T::OnParathreadClaim::on_claim_dropped(&queued.claim.claim);
 				}
 
 				will_keep
@@ -346,18 +409,19 @@ impl<T: Config> Pallet<T> {
 	/// assigned to a core, this call will fail. This call will also fail if the queue is full.
 	///
 	/// Fails if the claim does not correspond to any live parathread.
-	#[allow(unused)]
-	pub fn add_parathread_claim(claim: ParathreadClaim) {
+	///
+	/// Once added, the outcome of the claim is reported to [`Config::OnParathreadClaim`].
+	pub fn add_parathread_claim(claim: ParathreadClaim) -> Result<(), ParathreadClaimError> {
 		if !<paras::Pallet<T>>::is_parathread(claim.0) {
-			return
+			return Err(ParathreadClaimError::NotParathread)
 		}
 
 		let config = <configuration::Pallet<T>>::config();
-		let queue_max_size = config.parathread_cores * config.scheduling_lookahead;
+		let queue_max_size = Self::parathread_queue_capacity(&config);
 
 		ParathreadQueue::<T>::mutate(|queue| {
 			if queue.queue.len() >= queue_max_size as usize {
-				return
+				return Err(ParathreadClaimError::QueueFull)
 			}
 
 			let para_id = claim.0;
@@ -372,14 +436,27 @@ impl<T: Config> Pallet<T> {
 				});
 
 			if competes_with_another {
-				return
+				return Err(ParathreadClaimError::AlreadyClaimed)
 			}
 
 			let entry = ParathreadEntry { claim, retries: 0 };
 			queue.enqueue_entry(entry, config.parathread_cores);
+			Ok(())
 		})
 	}
 
+	/// The maximum number of claims in the parathread queue.
+	pub(crate) fn parathread_queue_capacity(
+		config: &configuration::HostConfiguration<T::BlockNumber>,
+	) -> u32 {
+		config.parathread_cores.saturating_mul(config.scheduling_lookahead)
+	}
+
+	/// The parathreads with a claim in the queue, in queue order.
+	pub(crate) fn queued_parathreads() -> Vec<ParaId> {
+		ParathreadQueue::<T>::get().queue.into_iter().map(|queued| queued.claim.claim.0).collect()
+	}
+
 	/// Free unassigned cores. Provide a list of cores that should be considered newly-freed along with the reason
 	/// for them being freed. The list is assumed to be sorted in ascending order by core index.
 	pub(crate) fn free_cores(just_freed_cores: impl IntoIterator<Item = (CoreIndex, FreedReason)>) {
@@ -400,7 +477,8 @@ impl<T: Config> Pallet<T> {
 										if let Ok(i) = index.binary_search(&entry.claim.0) {
 											index.remove(i);
 										}
-									})
+									});
+									T::OnParathreadClaim::on_claim_concluded(&entry.claim);
 								},
 								FreedReason::TimedOut => {
 									// If a parathread candidate times out, it's not the collator's fault,
@@ -427,7 +505,7 @@ impl<T: Config> Pallet<T> {
 		Self::free_cores(just_freed_cores);
 
 		let cores = AvailabilityCores::<T>::get();
-		let parachains = <paras::Pallet<T>>::parachains();
+		let parachain_cores = ParachainCores::<T>::get();
 		let mut scheduled = Scheduled::<T>::get();
 		let mut parathread_queue = ParathreadQueue::<T>::get();
 
@@ -478,11 +556,11 @@ impl<T: Config> Pallet<T> {
 
 				let core = CoreIndex(core_index as u32);
 
-				let core_assignment = if core_index < parachains.len() {
+				let core_assignment = if core_index < parachain_cores.len() {
 					// parachain core.
 					Some(CoreAssignment {
 						kind: AssignmentKind::Parachain,
-						para_id: parachains[core_index],
+						para_id: parachain_cores[core_index],
 						core,
 						group_idx: Self::group_assigned_to_core(core, now).expect(
 							"core is not out of bounds and we are guaranteed \
@@ -491,7 +569,7 @@ impl<T: Config> Pallet<T> {
 					})
 				} else {
 					// parathread core offset, rel. to beginning.
-					let core_offset = (core_index - parachains.len()) as u32;
+					let core_offset = (core_index - parachain_cores.len()) as u32;
 
 					parathread_queue.take_next_on_core(core_offset).map(|entry| CoreAssignment {
 						kind: AssignmentKind::Parathread(entry.claim.1, entry.retries),
@@ -574,10 +652,8 @@ impl<T: Config> Pallet<T> {
 		let cores = AvailabilityCores::<T>::get();
 		match cores.get(core_index.0 as usize).and_then(|c| c.as_ref()) {
 			None => None,
-			Some(CoreOccupied::Parachain) => {
-				let parachains = <paras::Pallet<T>>::parachains();
-				Some(parachains[core_index.0 as usize])
-			},
+			Some(CoreOccupied::Parachain) =>
+				ParachainCores::<T>::get().get(core_index.0 as usize).copied(),
 			Some(CoreOccupied::Parathread(ref entry)) => Some(entry.claim.0),
 		}
 	}
@@ -682,16 +758,17 @@ impl<T: Config> Pallet<T> {
 	/// Return the next thing that will be scheduled on this core assuming it is currently
 	/// occupied and the candidate occupying it became available.
 	///
-	/// For parachains, this is always the ID of the parachain and no specified collator.
+	/// For parachains, this is always the ID of the parachain assigned to the core and no
+	/// specified collator.
 	/// For parathreads, this is based on the next item in the `ParathreadQueue` assigned to that
 	/// core, and is None if there isn't one.
 	pub(crate) fn next_up_on_available(core: CoreIndex) -> Option<ScheduledCore> {
-		let parachains = <paras::Pallet<T>>::parachains();
-		if (core.0 as usize) < parachains.len() {
-			Some(ScheduledCore { para_id: parachains[core.0 as usize], collator: None })
+		let parachain_cores = ParachainCores::<T>::get();
+		if (core.0 as usize) < parachain_cores.len() {
+			Some(ScheduledCore { para_id: parachain_cores[core.0 as usize], collator: None })
 		} else {
 			let queue = ParathreadQueue::<T>::get();
-			let core_offset = (core.0 as usize - parachains.len()) as u32;
+			let core_offset = (core.0 as usize - parachain_cores.len()) as u32;
 			queue.get_next_on_core(core_offset).map(|entry| ScheduledCore {
 				para_id: entry.claim.0,
 				collator: Some(entry.claim.1.clone()),
@@ -707,14 +784,14 @@ impl<T: Config> Pallet<T> {
 	/// core, or if there isn't one, the claim that is currently occupying the core, as long
 	/// as the claim's retries would not exceed the limit. Otherwise None.
 	pub(crate) fn next_up_on_time_out(core: CoreIndex) -> Option<ScheduledCore> {
-		let parachains = <paras::Pallet<T>>::parachains();
-		if (core.0 as usize) < parachains.len() {
-			Some(ScheduledCore { para_id: parachains[core.0 as usize], collator: None })
+		let parachain_cores = ParachainCores::<T>::get();
+		if (core.0 as usize) < parachain_cores.len() {
+			Some(ScheduledCore { para_id: parachain_cores[core.0 as usize], collator: None })
 		} else {
 			let queue = ParathreadQueue::<T>::get();
 
 			// This is the next scheduled para on this core.
-			let core_offset = (core.0 as usize - parachains.len()) as u32;
+			let core_offset = (core.0 as usize - parachain_cores.len()) as u32;
 			queue
 				.get_next_on_core(core_offset)
 				.map(|entry| ScheduledCore {
@@ -739,22 +816,28 @@ impl<T: Config> Pallet<T> {
 	}
 
 	// Free all scheduled cores and return parathread claims to queue, with retries incremented.
+	// Claims which are out of retries or of paras which are no longer parathreads are dropped.
 	pub(crate) fn clear() {
 		let config = <configuration::Pallet<T>>::config();
 		ParathreadQueue::<T>::mutate(|queue| {
 			for core_assignment in Scheduled::<T>::take() {
 				if let AssignmentKind::Parathread(collator, retries) = core_assignment.kind {
-					if !<paras::Pallet<T>>::is_parathread(core_assignment.para_id) {
-						continue
-					}
-
 					let entry = ParathreadEntry {
 						claim: ParathreadClaim(core_assignment.para_id, collator),
 						retries: retries + 1,
 					};
 
-					if entry.retries <= config.parathread_retries {
+					if entry.retries <= config.parathread_retries &&
+						<paras::Pallet<T>>::is_parathread(core_assignment.para_id)
+					{
 						queue.enqueue_entry(entry, config.parathread_cores);
+					} else {
+						ParathreadClaimIndex::<T>::mutate(|index| {
+							if let Ok(i) = index.binary_search(&entry.claim.0) {
+								index.remove(i);
+							}
+						});
+						T::OnParathreadClaim::on_claim_dropped(&entry.claim);
 					}
 				}
 			}
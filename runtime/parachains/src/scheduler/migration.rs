@@ -0,0 +1,100 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A module that is responsible for migration of storage.
+
+use crate::scheduler::{Config, Pallet};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+pub mod v1 {
+	use super::*;
+	use crate::scheduler::ParachainCores;
+
+	/// Fills `ParachainCores`, which is otherwise only set at the next session change.
+	///
+	/// Before v1 every parachain occupied exactly one core and the i'th core belonged to the
+	/// i'th entry of `paras::Parachains`. No core counts can have been applied yet, so
+	/// assigning the cores from the current parachains keeps the same mapping.
+	pub struct MigrateToV1<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() != 0 {
+				log::warn!("skipping v1, should be removed");
+				return T::DbWeight::get().reads(1)
+			}
+
+			let parachain_cores = Pallet::<T>::assign_parachain_cores();
+			// One read of the core count per parachain.
+			let reads = 2 + parachain_cores.len() as u64;
+			log::info!("Assigned {} parachain cores", parachain_cores.len());
+			ParachainCores::<T>::set(parachain_cores);
+
+			STORAGE_VERSION.put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(reads, 2)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: sp_std::vec::Vec<u8>) -> Result<(), &'static str> {
+			ensure!(
+				StorageVersion::get::<Pallet<T>>() == STORAGE_VERSION,
+				"Storage version should be 1 after the migration"
+			);
+			ensure!(
+				ParachainCores::<T>::get().len() >= crate::paras::Pallet::<T>::parachains().len(),
+				"Every parachain should have at least one core"
+			);
+			Ok(())
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{v1::MigrateToV1, *};
+	use crate::{
+		mock::{new_test_ext, MockGenesisConfig, Test},
+		paras,
+		scheduler::ParachainCores,
+	};
+	use primitives::Id as ParaId;
+
+	#[test]
+	fn test_migrate_to_v1() {
+		new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+			let parachains = vec![ParaId::from(100), ParaId::from(200), ParaId::from(300)];
+			paras::Parachains::<Test>::put(parachains.clone());
+			StorageVersion::new(0).put::<Pallet<Test>>();
+			assert!(ParachainCores::<Test>::get().is_empty());
+
+			MigrateToV1::<Test>::on_runtime_upgrade();
+
+			assert_eq!(ParachainCores::<Test>::get(), parachains);
+			assert_eq!(StorageVersion::get::<Pallet<Test>>(), STORAGE_VERSION);
+
+			// Running it again does nothing.
+			ParachainCores::<Test>::kill();
+			MigrateToV1::<Test>::on_runtime_upgrade();
+			assert!(ParachainCores::<Test>::get().is_empty());
+		});
+	}
+}
@@ -24,7 +24,8 @@ use crate::{
 	configuration::HostConfiguration,
 	initializer::SessionChangeNotification,
 	mock::{
-		new_test_ext, Configuration, MockGenesisConfig, Paras, ParasShared, Scheduler, System, Test,
+		new_test_ext, Configuration, MockGenesisConfig, Paras, ParasShared, RuntimeOrigin,
+		Scheduler, System, Test,
 	},
 	paras::{ParaGenesisArgs, ParaKind},
 };
@@ -131,7 +132,10 @@ fn add_parathread_claim_works() {
 		assert!(Paras::is_parathread(thread_id));
 
 		{
-			Scheduler::add_parathread_claim(ParathreadClaim(thread_id, collator.clone()));
+			assert_ok!(Scheduler::add_parathread_claim(ParathreadClaim(
+				thread_id,
+				collator.clone()
+			)));
 			let queue = ParathreadQueue::<Test>::get();
 			assert_eq!(queue.next_core_offset, 1);
 			assert_eq!(queue.queue.len(), 1);
@@ -150,7 +154,10 @@ fn add_parathread_claim_works() {
 		// due to the index, completing claims are not allowed.
 		{
 			let collator2 = CollatorId::from(Sr25519Keyring::Bob.public());
-			Scheduler::add_parathread_claim(ParathreadClaim(thread_id, collator2.clone()));
+			assert_eq!(
+				Scheduler::add_parathread_claim(ParathreadClaim(thread_id, collator2.clone())),
+				Err(ParathreadClaimError::AlreadyClaimed),
+			);
 			let queue = ParathreadQueue::<Test>::get();
 			assert_eq!(queue.next_core_offset, 1);
 			assert_eq!(queue.queue.len(), 1);
@@ -169,7 +176,10 @@ fn add_parathread_claim_works() {
 		// claims on non-live parathreads have no effect.
 		{
 			let thread_id2 = ParaId::from(11);
-			Scheduler::add_parathread_claim(ParathreadClaim(thread_id2, collator.clone()));
+			assert_eq!(
+				Scheduler::add_parathread_claim(ParathreadClaim(thread_id2, collator.clone())),
+				Err(ParathreadClaimError::NotParathread),
+			);
 			let queue = ParathreadQueue::<Test>::get();
 			assert_eq!(queue.next_core_offset, 1);
 			assert_eq!(queue.queue.len(), 1);
@@ -211,7 +221,10 @@ fn cannot_add_claim_when_no_parathread_cores() {
 
 		assert!(Paras::is_parathread(thread_id));
 
-		Scheduler::add_parathread_claim(ParathreadClaim(thread_id, collator.clone()));
+		assert_eq!(
+			Scheduler::add_parathread_claim(ParathreadClaim(thread_id, collator.clone())),
+			Err(ParathreadClaimError::QueueFull),
+		);
 		assert_eq!(ParathreadQueue::<Test>::get(), Default::default());
 	});
 }
@@ -497,8 +510,8 @@ fn schedule_schedules() {
 		}
 
 		// add a couple of parathread claims.
-		Scheduler::add_parathread_claim(ParathreadClaim(thread_a, collator.clone()));
-		Scheduler::add_parathread_claim(ParathreadClaim(thread_c, collator.clone()));
+		assert_ok!(Scheduler::add_parathread_claim(ParathreadClaim(thread_a, collator.clone())));
+		assert_ok!(Scheduler::add_parathread_claim(ParathreadClaim(thread_c, collator.clone())));
 
 		run_to_block(2, |_| None);
 
@@ -601,8 +614,8 @@ fn schedule_schedules_including_just_freed() {
 		});
 
 		// add a couple of parathread claims now that the parathreads are live.
-		Scheduler::add_parathread_claim(ParathreadClaim(thread_a, collator.clone()));
-		Scheduler::add_parathread_claim(ParathreadClaim(thread_c, collator.clone()));
+		assert_ok!(Scheduler::add_parathread_claim(ParathreadClaim(thread_a, collator.clone())));
+		assert_ok!(Scheduler::add_parathread_claim(ParathreadClaim(thread_c, collator.clone())));
 
 		run_to_block(2, |_| None);
 
@@ -626,9 +639,9 @@ fn schedule_schedules_including_just_freed() {
 		// add a couple more parathread claims - the claim on `b` will go to the 3rd parathread core (4)
 		// and the claim on `d` will go back to the 1st parathread core (2). The claim on `e` then
 		// will go for core `3`.
-		Scheduler::add_parathread_claim(ParathreadClaim(thread_b, collator.clone()));
-		Scheduler::add_parathread_claim(ParathreadClaim(thread_d, collator.clone()));
-		Scheduler::add_parathread_claim(ParathreadClaim(thread_e, collator.clone()));
+		assert_ok!(Scheduler::add_parathread_claim(ParathreadClaim(thread_b, collator.clone())));
+		assert_ok!(Scheduler::add_parathread_claim(ParathreadClaim(thread_d, collator.clone())));
+		assert_ok!(Scheduler::add_parathread_claim(ParathreadClaim(thread_e, collator.clone())));
 
 		run_to_block(3, |_| None);
 
@@ -868,8 +881,8 @@ fn schedule_rotates_groups() {
 		let session_start_block = <Scheduler as Store>::SessionStartBlock::get();
 		assert_eq!(session_start_block, 1);
 
-		Scheduler::add_parathread_claim(ParathreadClaim(thread_a, collator.clone()));
-		Scheduler::add_parathread_claim(ParathreadClaim(thread_b, collator.clone()));
+		assert_ok!(Scheduler::add_parathread_claim(ParathreadClaim(thread_a, collator.clone())));
+		assert_ok!(Scheduler::add_parathread_claim(ParathreadClaim(thread_b, collator.clone())));
 
 		run_to_block(2, |_| None);
 
@@ -937,8 +950,8 @@ fn parathread_claims_are_pruned_after_retries() {
 			_ => None,
 		});
 
-		Scheduler::add_parathread_claim(ParathreadClaim(thread_a, collator.clone()));
-		Scheduler::add_parathread_claim(ParathreadClaim(thread_b, collator.clone()));
+		assert_ok!(Scheduler::add_parathread_claim(ParathreadClaim(thread_a, collator.clone())));
+		assert_ok!(Scheduler::add_parathread_claim(ParathreadClaim(thread_b, collator.clone())));
 
 		run_to_block(2, |_| None);
 		assert_eq!(Scheduler::scheduled().len(), 2);
@@ -1094,7 +1107,7 @@ fn next_up_on_available_uses_next_scheduled_or_none_for_thread() {
 		let thread_claim_a = ParathreadClaim(thread_a, collator.clone());
 		let thread_claim_b = ParathreadClaim(thread_b, collator.clone());
 
-		Scheduler::add_parathread_claim(thread_claim_a.clone());
+		assert_ok!(Scheduler::add_parathread_claim(thread_claim_a.clone()));
 
 		run_to_block(2, |_| None);
 
@@ -1112,7 +1125,7 @@ fn next_up_on_available_uses_next_scheduled_or_none_for_thread() {
 
 			assert!(Scheduler::next_up_on_available(CoreIndex(0)).is_none());
 
-			Scheduler::add_parathread_claim(thread_claim_b);
+			assert_ok!(Scheduler::add_parathread_claim(thread_claim_b));
 
 			let queue = ParathreadQueue::<Test>::get();
 			assert_eq!(
@@ -1166,7 +1179,7 @@ fn next_up_on_time_out_reuses_claim_if_nothing_queued() {
 		let thread_claim_a = ParathreadClaim(thread_a, collator.clone());
 		let thread_claim_b = ParathreadClaim(thread_b, collator.clone());
 
-		Scheduler::add_parathread_claim(thread_claim_a.clone());
+		assert_ok!(Scheduler::add_parathread_claim(thread_claim_a.clone()));
 
 		run_to_block(2, |_| None);
 
@@ -1189,7 +1202,7 @@ fn next_up_on_time_out_reuses_claim_if_nothing_queued() {
 				ScheduledCore { para_id: thread_a, collator: Some(collator.clone()) }
 			);
 
-			Scheduler::add_parathread_claim(thread_claim_b);
+			assert_ok!(Scheduler::add_parathread_claim(thread_claim_b));
 
 			let queue = ParathreadQueue::<Test>::get();
 			assert_eq!(
@@ -1260,6 +1273,61 @@ fn next_up_on_available_is_parachain_always() {
 	});
 }
 
+#[test]
+fn parachain_with_multiple_cores_is_scheduled_on_each() {
+	let mut config = default_config();
+	config.parathread_cores = 0;
+
+	let genesis_config = MockGenesisConfig {
+		configuration: crate::configuration::GenesisConfig {
+			config: config.clone(),
+			..Default::default()
+		},
+		..Default::default()
+	};
+
+	let chain_a = ParaId::from(1_u32);
+	let chain_b = ParaId::from(2_u32);
+
+	new_test_ext(genesis_config).execute_with(|| {
+		schedule_blank_para(chain_a, ParaKind::Parachain);
+		schedule_blank_para(chain_b, ParaKind::Parachain);
+		assert_ok!(Paras::force_set_core_count(RuntimeOrigin::root(), chain_a, 2));
+
+		// start a new session to activate, 3 validators for 3 cores.
+		run_to_block(1, |number| match number {
+			1 => Some(SessionChangeNotification {
+				new_config: config.clone(),
+				validators: vec![
+					ValidatorId::from(Sr25519Keyring::Alice.public()),
+					ValidatorId::from(Sr25519Keyring::Bob.public()),
+					ValidatorId::from(Sr25519Keyring::Charlie.public()),
+				],
+				..Default::default()
+			}),
+			_ => None,
+		});
+
+		run_to_block(2, |_| None);
+
+		assert_eq!(Scheduler::parachain_cores(), vec![chain_a, chain_a, chain_b]);
+		assert_eq!(Scheduler::availability_cores().len(), 3);
+
+		let scheduled = Scheduler::scheduled();
+		assert_eq!(scheduled.len(), 3);
+		assert_eq!(
+			scheduled.iter().map(|a| (a.core, a.para_id)).collect::<Vec<_>>(),
+			vec![(CoreIndex(0), chain_a), (CoreIndex(1), chain_a), (CoreIndex(2), chain_b)],
+		);
+
+		Scheduler::occupied(&[CoreIndex(1)]);
+		assert_eq!(
+			Scheduler::next_up_on_available(CoreIndex(1)).unwrap(),
+			ScheduledCore { para_id: chain_a, collator: None }
+		);
+	});
+}
+
 #[test]
 fn next_up_on_time_out_is_parachain_always() {
 	let mut config = default_config();
@@ -1425,8 +1493,8 @@ fn parathread_claims_are_pruned_after_deregistration() {
 			_ => None,
 		});
 
-		Scheduler::add_parathread_claim(ParathreadClaim(thread_a, collator.clone()));
-		Scheduler::add_parathread_claim(ParathreadClaim(thread_b, collator.clone()));
+		assert_ok!(Scheduler::add_parathread_claim(ParathreadClaim(thread_a, collator.clone())));
+		assert_ok!(Scheduler::add_parathread_claim(ParathreadClaim(thread_b, collator.clone())));
 
 		run_to_block(2, |_| None);
 		assert_eq!(Scheduler::scheduled().len(), 2);
@@ -17,23 +17,25 @@
 use crate::{
 	configuration::{self, HostConfiguration},
 	initializer,
+	paged_queue::{self, QueueCache, QueueStorage},
 };
 use frame_support::{pallet_prelude::*, traits::EnsureOrigin};
 use frame_system::pallet_prelude::*;
 use polkadot_parachain::primitives::UpwardMessages;
-use primitives::{Id as ParaId, UpwardMessage};
-use sp_std::{collections::btree_map::BTreeMap, fmt, marker::PhantomData, mem, prelude::*};
+use primitives::{vstaging::UmpQueueFootprint, Id as ParaId, UpwardMessage};
+use sp_std::{fmt, marker::PhantomData, prelude::*};
 use xcm::latest::Outcome;
 
 pub use pallet::*;
+pub use paged_queue::{BookState, PageIndex};
 
 /// Maximum value that `config.max_upward_message_size` can be set to
 ///
 /// This is used for benchmarking sanely bounding relevant storage items. It is expected from the `configurations`
 /// pallet to check these values before setting.
 pub const MAX_UPWARD_MESSAGE_SIZE_BOUND: u32 = 50 * 1024;
-/// Maximum amount of overweight messages that can exist in the queue at any given time.
-pub const MAX_OVERWEIGHT_MESSAGES: u32 = 1000;
+/// Maximum amount of suspended pages that can exist at any given time.
+pub const MAX_OVERWEIGHT_PAGES: u32 = 1000;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
@@ -85,9 +87,50 @@ impl UmpSink for () {
 /// if the message content is unique.
 pub type MessageId = [u8; 32];
 
-/// Index used to identify overweight messages.
+/// Index used to identify suspended pages.
 pub type OverweightIndex = u64;
 
+/// A bounded chunk of the upward message queue of a para.
+pub type Page = paged_queue::Page<UpwardMessage>;
+
+/// The storage of the upward message queues, as used by [`paged_queue`].
+pub struct UmpQueues<T>(PhantomData<T>);
+
+impl<T: Config> QueueStorage for UmpQueues<T> {
+	type Origin = ParaId;
+	type Message = UpwardMessage;
+
+	fn book(para: &ParaId) -> BookState {
+		Books::<T>::get(para)
+	}
+
+	fn set_book(para: &ParaId, book: Option<BookState>) {
+		match book {
+			Some(book) => Books::<T>::insert(para, book),
+			None => Books::<T>::remove(para),
+		}
+	}
+
+	fn page(para: &ParaId, index: PageIndex) -> Option<Page> {
+		Pages::<T>::get(para, index)
+	}
+
+	fn set_page(para: &ParaId, index: PageIndex, page: Option<Page>) {
+		Pages::<T>::set(para, index, page)
+	}
+
+	fn footprint(para: &ParaId) -> (u32, u32) {
+		RelayDispatchQueueSize::<T>::get(para)
+	}
+
+	fn set_footprint(para: &ParaId, footprint: Option<(u32, u32)>) {
+		match footprint {
+			Some(footprint) => RelayDispatchQueueSize::<T>::insert(para, footprint),
+			None => RelayDispatchQueueSize::<T>::remove(para),
+		}
+	}
+}
+
 /// A specific implementation of a `UmpSink` where messages are in the XCM format
 /// and will be forwarded to the XCM Executor.
 pub struct XcmSink<XcmExecutor, Config>(PhantomData<(XcmExecutor, Config)>);
@@ -189,15 +232,17 @@ impl fmt::Debug for AcceptanceCheckErr {
 
 /// Weight information of this pallet.
 pub trait WeightInfo {
-	fn service_overweight() -> Weight;
+	fn resume_overweight_page() -> Weight;
 	fn process_upward_message(s: u32) -> Weight;
 	fn clean_ump_after_outgoing() -> Weight;
+	fn receive_upward_messages(p: u32) -> Weight;
+	fn service_page() -> Weight;
 }
 
 /// fallback implementation
 pub struct TestWeightInfo;
 impl WeightInfo for TestWeightInfo {
-	fn service_overweight() -> Weight {
+	fn resume_overweight_page() -> Weight {
 		Weight::MAX
 	}
 
@@ -208,6 +253,15 @@ impl WeightInfo for TestWeightInfo {
 	fn clean_ump_after_outgoing() -> Weight {
 		Weight::MAX
 	}
+
+	// The following are accounted on top of other weights, which `Weight::MAX` would overflow.
+	fn receive_upward_messages(_p: u32) -> Weight {
+		Weight::zero()
+	}
+
+	fn service_page() -> Weight {
+		Weight::zero()
+	}
 }
 
 #[frame_support::pallet]
@@ -236,9 +290,16 @@ pub mod pallet {
 		/// Generally you'll want this to be a bit more - 150 or 200 would be good values.
 		type FirstMessageFactorPercent: Get<u64>;
 
-		/// Origin which is allowed to execute overweight messages.
+		/// Origin which is allowed to resume suspended pages.
 		type ExecuteOverweightOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 
+		/// The maximum total length (in bytes) of the message payloads of a single page.
+		///
+		/// A page always holds at least one message, so this should be at least
+		/// `config.max_upward_message_size` to keep pages bounded by this value.
+		#[pallet::constant]
+		type MaxPageSize: Get<u32>;
+
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -263,58 +324,72 @@ pub mod pallet {
 		UpwardMessagesReceived(ParaId, u32, u32),
 		/// The weight budget was exceeded for an individual upward message.
 		///
-		/// This message can be later dispatched manually using `service_overweight` dispatchable
-		/// using the assigned `overweight_index`.
+		/// The rest of the page holding the message, starting with the message itself, was
+		/// suspended. It can be later dispatched manually using the `resume_overweight_page`
+		/// dispatchable using the assigned `overweight_index`.
 		///
 		/// \[ para, id, overweight_index, required \]
-		OverweightEnqueued(ParaId, MessageId, OverweightIndex, Weight),
-		/// Upward message from the overweight queue was executed with the given actual weight
-		/// used.
+		PageSuspended(ParaId, MessageId, OverweightIndex, Weight),
+		/// Messages of a suspended page were executed with the given actual weight used.
+		///
+		/// The page is removed once no messages remain.
 		///
-		/// \[ overweight_index, used \]
-		OverweightServiced(OverweightIndex, Weight),
+		/// \[ overweight_index, remaining, used \]
+		PageResumed(OverweightIndex, u32, Weight),
 	}
 
 	#[pallet::error]
 	pub enum Error<T> {
-		/// The message index given is unknown.
-		UnknownMessageIndex,
-		/// The amount of weight given is possibly not enough for executing the message.
+		/// The page index given is unknown.
+		UnknownPageIndex,
+		/// The amount of weight given is possibly not enough for executing the first message of
+		/// the page.
 		WeightOverLimit,
 	}
 
-	/// The messages waiting to be handled by the relay-chain originating from a certain parachain.
+	/// The pages making up the upward message queue of a para.
+	///
+	/// Invariant:
+	/// - The pages in `begin..end` exist in `Pages` and hold at least one message.
+	/// - An entry exists if and only if the para has pages in `Pages` or suspended pages.
+	#[pallet::storage]
+	pub type Books<T: Config> = StorageMap<_, Twox64Concat, ParaId, BookState, ValueQuery>;
+
+	/// The messages waiting to be handled by the relay-chain originating from a certain parachain,
+	/// split into pages.
 	///
 	/// Note that some upward messages might have been already processed by the inclusion logic. E.g.
 	/// channel management messages.
 	///
-	/// The messages are processed in FIFO order.
+	/// The messages are processed in FIFO order, starting with the page at `Books::begin`.
 	#[pallet::storage]
-	pub type RelayDispatchQueues<T: Config> =
-		StorageMap<_, Twox64Concat, ParaId, Vec<UpwardMessage>, ValueQuery>;
+	pub type Pages<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, ParaId, Twox64Concat, PageIndex, Page, OptionQuery>;
 
-	/// Size of the dispatch queues. Caches sizes of the queues in `RelayDispatchQueue`.
+	/// Size of the dispatch queues. Caches sizes of the queues in `Pages`.
 	///
 	/// First item in the tuple is the count of messages and second
 	/// is the total length (in bytes) of the message payloads.
 	///
 	/// Note that this is an auxiliary mapping: it's possible to tell the byte size and the number of
-	/// messages only looking at `RelayDispatchQueues`. This mapping is separate to avoid the cost of
+	/// messages only looking at `Pages`. This mapping is separate to avoid the cost of
 	/// loading the whole message queue if only the total size and count are required.
 	///
+	/// Messages of suspended pages are not accounted for.
+	///
 	/// Invariant:
-	/// - The set of keys should exactly match the set of keys of `RelayDispatchQueues`.
+	/// - The set of keys should exactly match the set of paras with pages in `Pages`.
 	// NOTE that this field is used by parachains via merkle storage proofs, therefore changing
 	// the format will require migration of parachains.
 	#[pallet::storage]
 	pub type RelayDispatchQueueSize<T: Config> =
 		StorageMap<_, Twox64Concat, ParaId, (u32, u32), ValueQuery>;
 
-	/// The ordered list of `ParaId`s that have a `RelayDispatchQueue` entry.
+	/// The ordered list of `ParaId`s that have pages in `Pages`.
 	///
 	/// Invariant:
 	/// - The set of items from this vector should be exactly the set of the keys in
-	///   `RelayDispatchQueues` and `RelayDispatchQueueSize`.
+	///   `RelayDispatchQueueSize`.
 	#[pallet::storage]
 	pub type NeedsDispatch<T: Config> = StorageValue<_, Vec<ParaId>, ValueQuery>;
 
@@ -326,48 +401,80 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type NextDispatchRoundStartWith<T: Config> = StorageValue<_, ParaId>;
 
-	/// The messages that exceeded max individual message weight budget.
+	/// The pages that were suspended because a message exceeded the max individual message weight
+	/// budget.
 	///
-	/// These messages stay there until manually dispatched.
+	/// These pages stay there until all of their messages are manually dispatched.
 	#[pallet::storage]
 	pub type Overweight<T: Config> =
-		CountedStorageMap<_, Twox64Concat, OverweightIndex, (ParaId, Vec<u8>), OptionQuery>;
+		CountedStorageMap<_, Twox64Concat, OverweightIndex, (ParaId, Page), OptionQuery>;
 
-	/// The number of overweight messages ever recorded in `Overweight` (and thus the lowest free
-	/// index).
+	/// The number of pages ever suspended to `Overweight` (and thus the lowest free index).
 	#[pallet::storage]
 	pub type OverweightCount<T: Config> = StorageValue<_, OverweightIndex, ValueQuery>;
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		/// Service a single overweight upward message.
+		/// Resume a suspended page, executing its messages in order until `weight_limit` is
+		/// exhausted.
 		///
 		/// - `origin`: Must pass `ExecuteOverweightOrigin`.
-		/// - `index`: The index of the overweight message to service.
+		/// - `index`: The index of the suspended page to resume.
 		/// - `weight_limit`: The amount of weight that message execution may take.
 		///
+		/// The page is removed once all of its messages have been executed. Otherwise it stays
+		/// suspended with the remaining messages.
+		///
 		/// Errors:
-		/// - `UnknownMessageIndex`: Message of `index` is unknown.
-		/// - `WeightOverLimit`: Message execution may use greater than `weight_limit`.
+		/// - `UnknownPageIndex`: Page of `index` is unknown.
+		/// - `WeightOverLimit`: Execution of the first message may use greater than `weight_limit`.
 		///
 		/// Events:
-		/// - `OverweightServiced`: On success.
+		/// - `PageResumed`: On success.
 		#[pallet::call_index(0)]
-		#[pallet::weight(weight_limit.saturating_add(<T as Config>::WeightInfo::service_overweight()))]
-		pub fn service_overweight(
+		#[pallet::weight(
+			weight_limit.saturating_add(<T as Config>::WeightInfo::resume_overweight_page())
+		)]
+		pub fn resume_overweight_page(
 			origin: OriginFor<T>,
 			index: OverweightIndex,
 			weight_limit: Weight,
 		) -> DispatchResultWithPostInfo {
 			T::ExecuteOverweightOrigin::ensure_origin(origin)?;
 
-			let (sender, data) =
-				Overweight::<T>::get(index).ok_or(Error::<T>::UnknownMessageIndex)?;
-			let used = T::UmpSink::process_upward_message(sender, &data[..], weight_limit)
-				.map_err(|_| Error::<T>::WeightOverLimit)?;
-			Overweight::<T>::remove(index);
-			Self::deposit_event(Event::OverweightServiced(index, used));
-			Ok(Some(used.saturating_add(<T as Config>::WeightInfo::service_overweight())).into())
+			let (sender, mut page) =
+				Overweight::<T>::get(index).ok_or(Error::<T>::UnknownPageIndex)?;
+
+			let mut used = Weight::zero();
+			let mut executed = 0;
+			for msg in &page.messages {
+				match T::UmpSink::process_upward_message(
+					sender,
+					&msg[..],
+					weight_limit.saturating_sub(used),
+				) {
+					Ok(weight) => {
+						used.saturating_accrue(weight);
+						executed += 1;
+					},
+					Err(_) => break,
+				}
+			}
+			ensure!(executed > 0, Error::<T>::WeightOverLimit);
+
+			let remaining = Page::from_messages(page.messages.split_off(executed));
+			let remaining_count = remaining.messages.len() as u32;
+			if remaining.messages.is_empty() {
+				Overweight::<T>::remove(index);
+				paged_queue::release_suspended::<UmpQueues<T>>(&sender);
+			} else {
+				Overweight::<T>::insert(index, (sender, remaining));
+			}
+
+			Self::deposit_event(Event::PageResumed(index, remaining_count, used));
+			let actual_weight =
+				used.saturating_add(<T as Config>::WeightInfo::resume_overweight_page());
+			Ok(Some(actual_weight).into())
 		}
 	}
 }
@@ -401,9 +508,12 @@ impl<T: Config> Pallet<T> {
 	}
 
 	/// Remove all relevant storage items for an outgoing parachain.
+	///
+	/// Suspended pages of the para are kept, so that they can still be resumed.
 	pub(crate) fn clean_ump_after_outgoing(outgoing_para: &ParaId) -> Weight {
+		let book = <Self as Store>::Books::take(outgoing_para);
+		let _ = <Self as Store>::Pages::clear_prefix(outgoing_para, book.pages(), None);
 		<Self as Store>::RelayDispatchQueueSize::remove(outgoing_para);
-		<Self as Store>::RelayDispatchQueues::remove(outgoing_para);
 
 		// Remove the outgoing para from the `NeedsDispatch` list and from
 		// `NextDispatchRoundStartWith`.
@@ -471,24 +581,17 @@ impl<T: Config> Pallet<T> {
 	}
 
 	/// Enqueues `upward_messages` from a `para`'s accepted candidate block.
+	///
+	/// The messages are appended to the last page of the queue of `para`, opening new pages as
+	/// the pages fill up.
 	pub(crate) fn receive_upward_messages(para: ParaId, upward_messages: UpwardMessages) -> Weight {
 		let mut weight = Weight::zero();
 
 		if !upward_messages.is_empty() {
-			let (extra_count, extra_size) = upward_messages
-				.iter()
-				.fold((0, 0), |(cnt, size), d| (cnt + 1, size + d.len() as u32));
-
-			<Self as Store>::RelayDispatchQueues::mutate(&para, |v| {
-				v.extend(upward_messages.into_iter())
-			});
-
-			<Self as Store>::RelayDispatchQueueSize::mutate(
+			let enqueued = paged_queue::enqueue::<UmpQueues<T>>(
 				&para,
-				|(ref mut cnt, ref mut size)| {
-					*cnt += extra_count;
-					*size += extra_size;
-				},
+				upward_messages,
+				T::MaxPageSize::get(),
 			);
 
 			<Self as Store>::NeedsDispatch::mutate(|v| {
@@ -497,16 +600,17 @@ impl<T: Config> Pallet<T> {
 				}
 			});
 
-			// NOTE: The actual computation is not accounted for. It should be benchmarked.
-			weight += T::DbWeight::get().reads_writes(3, 3);
+			weight += <T as Config>::WeightInfo::receive_upward_messages(enqueued.pages_written);
 
-			Self::deposit_event(Event::UpwardMessagesReceived(para, extra_count, extra_size));
+			Self::deposit_event(Event::UpwardMessagesReceived(para, enqueued.count, enqueued.size));
 		}
 
 		weight
 	}
 
 	/// Devote some time into dispatching pending upward messages.
+	///
+	/// The queues of the paras are served round-robin, one message at a time.
 	pub(crate) fn process_pending_upward_messages() -> Weight {
 		const MAX_MESSAGES_PER_BLOCK: u8 = 10;
 		let mut messages_processed = 0;
@@ -514,7 +618,7 @@ impl<T: Config> Pallet<T> {
 
 		let config = <configuration::Pallet<T>>::config();
 		let mut cursor = NeedsDispatchCursor::new::<T>();
-		let mut queue_cache = QueueCache::new();
+		let mut queue_cache = QueueCache::<UmpQueues<T>>::new();
 
 		while let Some(dispatchee) = cursor.peek() {
 			if weight_used.any_gte(config.ump_service_total_weight) ||
@@ -542,27 +646,27 @@ impl<T: Config> Pallet<T> {
 
 			// attempt to process the next message from the queue of the dispatchee; if not beyond
 			// our remaining weight limit, then consume it.
-			let maybe_next = queue_cache.peek_front::<T>(dispatchee);
+			let maybe_next = queue_cache.peek_front(dispatchee);
 			if let Some(upward_message) = maybe_next {
 				messages_processed += 1;
 				match T::UmpSink::process_upward_message(dispatchee, upward_message, max_weight) {
 					Ok(used) => {
 						weight_used += used;
-						let _ = queue_cache.consume_front::<T>(dispatchee);
+						let _ = queue_cache.consume_front(dispatchee);
 					},
 					Err((id, required)) => {
-						let is_under_limit = Overweight::<T>::count() < MAX_OVERWEIGHT_MESSAGES;
+						let is_under_limit = Overweight::<T>::count() < MAX_OVERWEIGHT_PAGES;
 						weight_used.saturating_accrue(T::DbWeight::get().reads(1));
 						if required.any_gt(config.ump_max_individual_weight) && is_under_limit {
-							// overweight - add to overweight queue and continue with message
-							// execution consuming the message.
-							let upward_message = queue_cache.consume_front::<T>(dispatchee).expect(
-								"`consume_front` should return the same msg as `peek_front`;\
+							// overweight - suspend the rest of the page starting with this
+							// message and continue with the next page of the dispatchee.
+							let page = queue_cache.suspend_front(dispatchee).expect(
+								"`suspend_front` starts with the same msg as `peek_front`;\
 								if we get into this branch then `peek_front` returned `Some`;\
-								thus `upward_message` cannot be `None`; qed",
+								thus the page cannot be `None`; qed",
 							);
-							let index = Self::stash_overweight(dispatchee, upward_message);
-							Self::deposit_event(Event::OverweightEnqueued(
+							let index = Self::stash_overweight(dispatchee, page);
+							Self::deposit_event(Event::PageSuspended(
 								dispatchee, id, index, required,
 							));
 						} else {
@@ -575,7 +679,7 @@ impl<T: Config> Pallet<T> {
 				}
 			}
 
-			if queue_cache.is_empty::<T>(dispatchee) {
+			if queue_cache.is_empty(dispatchee) {
 				// the queue is empty now - this para doesn't need attention anymore.
 				cursor.remove();
 			} else {
@@ -584,116 +688,36 @@ impl<T: Config> Pallet<T> {
 		}
 
 		cursor.flush::<T>();
-		queue_cache.flush::<T>();
+		weight_used.saturating_accrue(
+			<T as Config>::WeightInfo::service_page()
+				.saturating_mul(queue_cache.pages_loaded().into()),
+		);
+		queue_cache.flush();
 
 		weight_used
 	}
 
-	/// Puts a given upward message into the list of overweight messages allowing it to be executed
-	/// later.
-	fn stash_overweight(sender: ParaId, upward_message: Vec<u8>) -> OverweightIndex {
+	/// Puts a given page into the list of suspended pages allowing it to be resumed later.
+	fn stash_overweight(sender: ParaId, page: Page) -> OverweightIndex {
 		let index = <Self as Store>::OverweightCount::mutate(|count| {
 			let index = *count;
 			*count += 1;
 			index
 		});
 
-		<Self as Store>::Overweight::insert(index, (sender, upward_message));
+		<Self as Store>::Overweight::insert(index, (sender, page));
 		index
 	}
-}
-
-/// To avoid constant fetching, deserializing and serialization the queues are cached.
-///
-/// After an item dequeued from a queue for the first time, the queue is stored in this struct
-/// rather than being serialized and persisted.
-///
-/// This implementation works best when:
-///
-/// 1. when the queues are shallow
-/// 2. the dispatcher makes more than one cycle
-///
-/// if the queues are deep and there are many we would load and keep the queues for a long time,
-/// thus increasing the peak memory consumption of the wasm runtime. Under such conditions persisting
-/// queues might play better since it's unlikely that they are going to be requested once more.
-///
-/// On the other hand, the situation when deep queues exist and it takes more than one dispatcher
-/// cycle to traverse the queues is already sub-optimal and better be avoided.
-///
-/// This struct is not supposed to be dropped but rather to be consumed by [`flush`].
-struct QueueCache(BTreeMap<ParaId, QueueCacheEntry>);
-
-struct QueueCacheEntry {
-	queue: Vec<UpwardMessage>,
-	total_size: u32,
-	consumed_count: usize,
-	consumed_size: usize,
-}
-
-impl QueueCache {
-	fn new() -> Self {
-		Self(BTreeMap::new())
-	}
-
-	fn ensure_cached<T: Config>(&mut self, para: ParaId) -> &mut QueueCacheEntry {
-		self.0.entry(para).or_insert_with(|| {
-			let queue = RelayDispatchQueues::<T>::get(&para);
-			let (_, total_size) = RelayDispatchQueueSize::<T>::get(&para);
-			QueueCacheEntry { queue, total_size, consumed_count: 0, consumed_size: 0 }
-		})
-	}
 
-	/// Returns the message at the front of `para`'s queue, or `None` if the queue is empty.
+	/// The footprint of the upward message queue of `para`.
 	///
-	/// Does not mutate the queue.
-	fn peek_front<T: Config>(&mut self, para: ParaId) -> Option<&UpwardMessage> {
-		let entry = self.ensure_cached::<T>(para);
-		entry.queue.get(entry.consumed_count)
-	}
-
-	/// Attempts to remove one message from the front of `para`'s queue. If the queue is empty, then
-	/// does nothing.
-	fn consume_front<T: Config>(&mut self, para: ParaId) -> Option<UpwardMessage> {
-		let cache_entry = self.ensure_cached::<T>(para);
-
-		match cache_entry.queue.get_mut(cache_entry.consumed_count) {
-			Some(msg) => {
-				cache_entry.consumed_count += 1;
-				cache_entry.consumed_size += msg.len();
+	/// Collators can compare it against `config.max_upward_queue_count` and
+	/// `config.max_upward_queue_size` to hold back messages while the queue is filling up.
+	pub fn queue_footprint(para: ParaId) -> UmpQueueFootprint {
+		let (count, size) = <Self as Store>::RelayDispatchQueueSize::get(&para);
+		let book = <Self as Store>::Books::get(&para);
 
-				Some(mem::take(msg))
-			},
-			None => None,
-		}
-	}
-
-	/// Returns if the queue for the given para is empty.
-	///
-	/// That is, if this returns `true` then the next call to [`peek_front`] will return `None`.
-	///
-	/// Does not mutate the queue.
-	fn is_empty<T: Config>(&mut self, para: ParaId) -> bool {
-		let cache_entry = self.ensure_cached::<T>(para);
-		cache_entry.consumed_count >= cache_entry.queue.len()
-	}
-
-	/// Flushes the updated queues into the storage.
-	fn flush<T: Config>(self) {
-		// NOTE we use an explicit method here instead of Drop impl because it has unwanted semantics
-		// within runtime. It is dangerous to use because of double-panics and flushing on a panic
-		// is not necessary as well.
-		for (para, entry) in self.0 {
-			if entry.consumed_count >= entry.queue.len() {
-				// remove the entries altogether.
-				RelayDispatchQueues::<T>::remove(&para);
-				RelayDispatchQueueSize::<T>::remove(&para);
-			} else if entry.consumed_count > 0 {
-				RelayDispatchQueues::<T>::insert(&para, &entry.queue[entry.consumed_count..]);
-				let count = (entry.queue.len() - entry.consumed_count) as u32;
-				let size = entry.total_size.saturating_sub(entry.consumed_size as u32);
-				RelayDispatchQueueSize::<T>::insert(&para, (count, size));
-			}
-		}
+		UmpQueueFootprint { count, size, pages: book.pages(), suspended_pages: book.suspended }
 	}
 }
 
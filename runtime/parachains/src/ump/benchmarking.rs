@@ -104,10 +104,38 @@ frame_benchmarking::benchmarks! {
 		Ump::<T>::clean_ump_after_outgoing(&ParaId::from(0));
 	}
 
-	service_overweight {
+	// Worst case: every message opens a page of its own.
+	receive_upward_messages {
+		let p in 1 .. 64;
+		let para = ParaId::from(1978);
+		let msg = vec![0u8; (T::MaxPageSize::get() / 2 + 1) as usize];
+		let msgs: UpwardMessages = vec![msg; p as usize].try_into().unwrap();
+	}: {
+		Ump::<T>::receive_upward_messages(para, msgs);
+	}
+	verify {
+		assert_eq!(Books::<T>::get(&para).pages(), p);
+	}
+
+	// Loading a page of the maximum size and removing it, once all of its messages are consumed.
+	service_page {
+		let para = ParaId::from(1978);
+		let msg = vec![0u8; T::MaxPageSize::get() as usize];
+		let msgs: UpwardMessages = vec![msg].try_into().unwrap();
+		Ump::<T>::receive_upward_messages(para, msgs);
+	}: {
+		let mut queue_cache = QueueCache::<UmpQueues<T>>::new();
+		assert!(queue_cache.consume_front(para));
+		queue_cache.flush();
+	}
+	verify {
+		assert!(!Books::<T>::contains_key(&para));
+	}
+
+	resume_overweight_page {
 		let host_conf = configuration::ActiveConfig::<T>::get();
 		let para = ParaId::from(1978);
-		// The message's weight does not really matter here, as we add service_overweight's
+		// The message's weight does not really matter here, as we add resume_overweight_page's
 		// max_weight parameter to the extrinsic's weight in the weight calculation.
 		// The size of the message influences decoding time, so we create a min-sized message here
 		// and take the decoding weight into account by adding it to the extrinsic execution weight
@@ -115,18 +143,22 @@ frame_benchmarking::benchmarks! {
 		let msg = create_message_overweight::<T>();
 
 		// This just makes sure that 0 is not a valid index and we can use it later on.
-		let _ = Ump::<T>::service_overweight(RawOrigin::Root.into(), 0, Weight::from_parts(1000, 1000));
+		let _ = Ump::<T>::resume_overweight_page(
+			RawOrigin::Root.into(),
+			0,
+			Weight::from_parts(1000, 1000),
+		);
 		// Start with the block number 1. This is needed because should an event be
 		// emitted during the genesis block they will be implicitly wiped.
 		frame_system::Pallet::<T>::set_block_number(1u32.into());
 		queue_upward_msg::<T>(&host_conf, para, msg.clone());
 		Ump::<T>::process_pending_upward_messages();
 		assert_last_event_type::<T>(
-			Event::OverweightEnqueued(para, upward_message_id(&msg), 0, Weight::zero()).into()
+			Event::PageSuspended(para, upward_message_id(&msg), 0, Weight::zero()).into()
 			);
 	}: _(RawOrigin::Root, 0, Weight::MAX)
 	verify {
-		assert_last_event_type::<T>(Event::OverweightServiced(0, Weight::zero()).into());
+		assert_last_event_type::<T>(Event::PageResumed(0, 0, Weight::zero()).into());
 	}
 }
 
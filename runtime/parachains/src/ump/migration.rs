@@ -21,7 +21,7 @@ use frame_support::{
 	weights::Weight,
 };
 
-pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
 
 pub mod v1 {
 	use super::*;
@@ -47,3 +47,77 @@ pub mod v1 {
 		}
 	}
 }
+
+pub mod v2 {
+	use super::*;
+	use crate::ump::{BookState, Books, Page, Pages};
+	use frame_support::storage_alias;
+	use primitives::{Id as ParaId, UpwardMessage};
+	use sp_std::{collections::btree_map::BTreeMap, mem, prelude::*};
+
+	#[storage_alias]
+	type RelayDispatchQueues<T: Config> =
+		StorageMap<Pallet<T>, Twox64Concat, ParaId, Vec<UpwardMessage>, ValueQuery>;
+
+	/// Splits the upward message queues into pages and turns every overweight message into a
+	/// suspended page holding just that message.
+	pub struct MigrateToV2<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() != 1 {
+				log::warn!("skipping v2, should be removed");
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut weight = T::DbWeight::get().reads(1);
+			let max_page_size = T::MaxPageSize::get();
+			let mut books = BTreeMap::<ParaId, BookState>::new();
+
+			for (para, queue) in RelayDispatchQueues::<T>::drain() {
+				let book = books.entry(para).or_default();
+				let mut page = Page::default();
+				for msg in queue {
+					if !page.has_room_for(msg.len() as u32, max_page_size) {
+						Pages::<T>::insert(&para, book.end, mem::take(&mut page));
+						book.end += 1;
+					}
+					page.push(msg);
+				}
+				if !page.messages.is_empty() {
+					Pages::<T>::insert(&para, book.end, page);
+					book.end += 1;
+				}
+
+				weight.saturating_accrue(
+					T::DbWeight::get().reads_writes(1, 1 + book.pages() as u64),
+				);
+			}
+
+			let mut overweight_messages = 0u64;
+			Overweight::<T>::translate(|_, (para, msg): (ParaId, Vec<u8>)| {
+				books.entry(para).or_default().suspended += 1;
+				overweight_messages += 1;
+				Some((para, Page::from_messages(vec![msg])))
+			});
+			weight.saturating_accrue(
+				T::DbWeight::get().reads_writes(overweight_messages, overweight_messages),
+			);
+
+			log::info!(
+				"Migrated the upward message queues of {} paras and {} overweight messages",
+				books.len(),
+				overweight_messages,
+			);
+			weight.saturating_accrue(T::DbWeight::get().writes(books.len() as u64));
+			for (para, book) in books {
+				if !book.is_empty() || book.suspended > 0 {
+					Books::<T>::insert(&para, book);
+				}
+			}
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+
+			weight.saturating_add(T::DbWeight::get().writes(1))
+		}
+	}
+}
@@ -20,7 +20,7 @@ use crate::mock::{
 	RuntimeOrigin, System, Test, Ump,
 };
 use frame_support::{assert_noop, assert_ok, weights::Weight};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 pub(super) struct GenesisConfigBuilder {
 	max_upward_message_size: u32,
@@ -78,26 +78,50 @@ fn queue_upward_msg(para: ParaId, msg: UpwardMessage) {
 }
 
 fn assert_storage_consistency_exhaustive() {
+	let books = <Ump as Store>::Books::iter().collect::<HashMap<ParaId, BookState>>();
+
 	// check that empty queues don't clutter the storage.
-	for (_para, queue) in <Ump as Store>::RelayDispatchQueues::iter() {
-		assert!(!queue.is_empty());
+	for (_para, book) in &books {
+		assert!(!book.is_empty() || book.suspended > 0);
+	}
+
+	// every page belongs to the range of pages of its book and holds messages.
+	for (para, index, page) in <Ump as Store>::Pages::iter() {
+		let book = books.get(&para).expect("pages are tracked by a book");
+		assert!(book.begin <= index && index < book.end);
+		assert!(!page.messages.is_empty());
+		assert_eq!(page, Page::from_messages(page.messages.clone()));
 	}
 
 	// actually count the counts and sizes in queues and compare them to the bookkept version.
-	for (para, queue) in <Ump as Store>::RelayDispatchQueues::iter() {
+	for (para, book) in books.iter().filter(|(_, book)| !book.is_empty()) {
 		let (expected_count, expected_size) = <Ump as Store>::RelayDispatchQueueSize::get(para);
-		let (actual_count, actual_size) = queue
-			.into_iter()
-			.fold((0, 0), |(acc_count, acc_size), x| (acc_count + 1, acc_size + x.len() as u32));
+		let (actual_count, actual_size) = (book.begin..book.end)
+			.map(|index| <Ump as Store>::Pages::get(para, index).expect("pages of a book exist"))
+			.fold((0, 0), |(acc_count, acc_size), page| {
+				(acc_count + page.messages.len() as u32, acc_size + page.size)
+			});
 
 		assert_eq!(expected_count, actual_count);
 		assert_eq!(expected_size, actual_size);
 	}
 
+	// the suspended pages are accounted for in the books.
+	let mut suspended = HashMap::<ParaId, u32>::new();
+	for (_index, (para, page)) in <Ump as Store>::Overweight::iter() {
+		assert!(!page.messages.is_empty());
+		*suspended.entry(para).or_default() += 1;
+	}
+	for (para, book) in &books {
+		assert_eq!(book.suspended, suspended.get(para).copied().unwrap_or_default());
+	}
+
 	// since we wipe the empty queues the sets of paras in queue contents, queue sizes and
 	// need dispatch set should all be equal.
-	let queue_contents_set = <Ump as Store>::RelayDispatchQueues::iter()
-		.map(|(k, _)| k)
+	let queue_contents_set = books
+		.iter()
+		.filter(|(_, book)| !book.is_empty())
+		.map(|(k, _)| *k)
 		.collect::<HashSet<ParaId>>();
 	let queue_sizes_set = <Ump as Store>::RelayDispatchQueueSize::iter()
 		.map(|(k, _)| k)
@@ -291,13 +315,13 @@ fn verify_relay_dispatch_queue_size_is_externally_accessible() {
 }
 
 #[test]
-fn service_overweight_unknown() {
+fn resume_overweight_page_unknown() {
 	// This test just makes sure that 0 is not a valid index and we can use it not worrying in
 	// the next test.
 	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
 		assert_noop!(
-			Ump::service_overweight(RuntimeOrigin::root(), 0, Weight::from_parts(1000, 1000)),
-			Error::<Test>::UnknownMessageIndex
+			Ump::resume_overweight_page(RuntimeOrigin::root(), 0, Weight::from_parts(1000, 1000)),
+			Error::<Test>::UnknownPageIndex
 		);
 	});
 }
@@ -334,7 +358,7 @@ fn overweight_queue_works() {
 		queue_upward_msg(para_a, a_msg_3.clone());
 		Ump::process_pending_upward_messages();
 		assert_last_event(
-			Event::OverweightEnqueued(
+			Event::PageSuspended(
 				para_a,
 				upward_message_id(&a_msg_3[..]),
 				0,
@@ -342,23 +366,122 @@ fn overweight_queue_works() {
 			)
 			.into(),
 		);
+		assert_storage_consistency_exhaustive();
 
-		// Now verify that if we wanted to service this overweight message with less than enough
-		// weight it will fail.
+		// Now verify that if we wanted to resume this page with less than enough weight for its
+		// first message it will fail.
 		assert_noop!(
-			Ump::service_overweight(RuntimeOrigin::root(), 0, Weight::from_parts(499, 499)),
+			Ump::resume_overweight_page(RuntimeOrigin::root(), 0, Weight::from_parts(499, 499)),
 			Error::<Test>::WeightOverLimit
 		);
 
-		// ... and if we try to service it with just enough weight it will succeed as well.
-		assert_ok!(Ump::service_overweight(RuntimeOrigin::root(), 0, Weight::from_parts(500, 500)));
-		assert_last_event(Event::OverweightServiced(0, Weight::from_parts(500, 500)).into());
+		// ... and if we try to resume it with just enough weight it will succeed as well.
+		assert_ok!(Ump::resume_overweight_page(
+			RuntimeOrigin::root(),
+			0,
+			Weight::from_parts(500, 500)
+		));
+		assert_last_event(Event::PageResumed(0, 0, Weight::from_parts(500, 500)).into());
+		assert_storage_consistency_exhaustive();
 
-		// ... and if we try to service a message with index that doesn't exist it will error
+		// ... and if we try to resume a page with index that doesn't exist it will error
 		// out.
 		assert_noop!(
-			Ump::service_overweight(RuntimeOrigin::root(), 1, Weight::from_parts(1000, 1000)),
-			Error::<Test>::UnknownMessageIndex
+			Ump::resume_overweight_page(RuntimeOrigin::root(), 1, Weight::from_parts(1000, 1000)),
+			Error::<Test>::UnknownPageIndex
+		);
+	});
+}
+
+#[test]
+fn messages_are_split_into_pages() {
+	let a = ParaId::from(2021);
+
+	// 12 bytes each, so two of them fit into a page of the mock.
+	let msgs: Vec<_> = ["a_msg_1", "a_msg_2", "a_msg_3"]
+		.into_iter()
+		.map(|name| (10u32, name).encode())
+		.collect();
+
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		for msg in &msgs {
+			queue_upward_msg(a, msg.clone());
+		}
+
+		assert_eq!(<Ump as Store>::Books::get(a), BookState { begin: 0, end: 2, suspended: 0 });
+		assert_eq!(
+			<Ump as Store>::Pages::get(a, 0),
+			Some(Page::from_messages(msgs[..2].to_vec()))
+		);
+		assert_eq!(
+			Ump::queue_footprint(a),
+			UmpQueueFootprint { count: 3, size: 36, pages: 2, suspended_pages: 0 },
+		);
+		assert_storage_consistency_exhaustive();
+
+		Ump::process_pending_upward_messages();
+		assert_eq!(take_processed(), msgs.iter().map(|msg| (a, msg.clone())).collect::<Vec<_>>());
+		assert_eq!(Ump::queue_footprint(a), UmpQueueFootprint::default());
+		assert_storage_consistency_exhaustive();
+	});
+}
+
+#[test]
+fn suspended_page_can_be_resumed_in_parts() {
+	let a = ParaId::from(2021);
+
+	let a_msg_1 = (500u32, "a_msg_1").encode();
+	let a_msg_2 = (100u32, "a_msg_2").encode();
+	let a_msg_3 = (100u32, "a_msg_3").encode();
+
+	new_test_ext(
+		GenesisConfigBuilder {
+			ump_service_total_weight: Weight::from_parts(400, 400),
+			ump_max_individual_weight: Weight::from_parts(300, 300),
+			..Default::default()
+		}
+		.build(),
+	)
+	.execute_with(|| {
+		System::set_block_number(1);
+
+		// the first message is overweight, so the whole page gets suspended.
+		queue_upward_msg(a, a_msg_1.clone());
+		queue_upward_msg(a, a_msg_2.clone());
+		Ump::process_pending_upward_messages();
+		assert_eq!(take_processed(), vec![]);
+		assert_eq!(
+			Ump::queue_footprint(a),
+			UmpQueueFootprint { count: 0, size: 0, pages: 0, suspended_pages: 1 },
 		);
+		assert_storage_consistency_exhaustive();
+
+		// messages arriving later are processed regardless of the suspended page.
+		queue_upward_msg(a, a_msg_3.clone());
+		Ump::process_pending_upward_messages();
+		assert_eq!(take_processed(), vec![(a, a_msg_3)]);
+		assert_storage_consistency_exhaustive();
+
+		// there is only enough weight for the first message of the page.
+		assert_ok!(Ump::resume_overweight_page(
+			RuntimeOrigin::root(),
+			0,
+			Weight::from_parts(550, 550)
+		));
+		assert_eq!(take_processed(), vec![(a, a_msg_1)]);
+		assert_last_event(Event::PageResumed(0, 1, Weight::from_parts(500, 500)).into());
+		assert_eq!(Ump::queue_footprint(a).suspended_pages, 1);
+		assert_storage_consistency_exhaustive();
+
+		assert_ok!(Ump::resume_overweight_page(
+			RuntimeOrigin::root(),
+			0,
+			Weight::from_parts(100, 100)
+		));
+		assert_eq!(take_processed(), vec![(a, a_msg_2)]);
+		assert_last_event(Event::PageResumed(0, 0, Weight::from_parts(100, 100)).into());
+		assert_eq!(Ump::queue_footprint(a), UmpQueueFootprint::default());
+		assert!(<Ump as Store>::Overweight::get(0).is_none());
+		assert_storage_consistency_exhaustive();
 	});
 }
@@ -22,8 +22,9 @@
 
 use pallet_transaction_payment::CurrencyAdapter;
 use runtime_common::{
-	auctions, claims, crowdloan, impl_runtime_weights, impls::DealWithFees, paras_registrar,
-	prod_or_fast, slots, BlockHashCount, BlockLength, CurrencyToVote, SlowAdjustingFeeUpdate,
+	auctions, claims, crowdloan, impl_runtime_weights, impls::DealWithFees, para_deposits,
+	paras_registrar, prod_or_fast, slots, BlockHashCount, BlockLength, CurrencyToVote,
+	SlowAdjustingFeeUpdate,
 };
 
 use runtime_parachains::{
@@ -1042,6 +1043,8 @@ impl claims::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type VestingSchedule = Vesting;
 	type Prefix = Prefix;
+	type Address = claims::EthereumAddress;
+	type Verifier = claims::EthereumPersonalSign;
 	/// At least 3/4 of the council must agree to a claim move before it can happen.
 	type MoveClaimOrigin =
 		pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 3, 4>;
@@ -1286,6 +1289,7 @@ impl parachains_inclusion::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type DisputesHandler = ParasDisputes;
 	type RewardValidators = parachains_reward_points::RewardValidatorsWithEraPoints<Runtime>;
+	type RecordPerformance = ();
 }
 
 parameter_types! {
@@ -1294,6 +1298,7 @@ parameter_types! {
 
 impl parachains_paras::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
 	type WeightInfo = weights::runtime_parachains_paras::WeightInfo<Runtime>;
 	type UnsignedPriority = ParasUnsignedPriority;
 	type NextSessionRotation = Babe;
@@ -1301,6 +1306,7 @@ impl parachains_paras::Config for Runtime {
 
 parameter_types! {
 	pub const FirstMessageFactorPercent: u64 = 100;
+	pub const UmpMaxPageSize: u32 = 64 * 1024;
 }
 
 impl parachains_ump::Config for Runtime {
@@ -1309,6 +1315,7 @@ impl parachains_ump::Config for Runtime {
 		crate::parachains_ump::XcmSink<xcm_executor::XcmExecutor<xcm_config::XcmConfig>, Runtime>;
 	type FirstMessageFactorPercent = FirstMessageFactorPercent;
 	type ExecuteOverweightOrigin = EnsureRoot<AccountId>;
+	type MaxPageSize = UmpMaxPageSize;
 	type WeightInfo = weights::runtime_parachains_ump::WeightInfo<Self>;
 }
 
@@ -1318,6 +1325,7 @@ impl parachains_hrmp::Config for Runtime {
 	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
+	type SystemParas = parachains_hrmp::IsSystemPara;
 	type WeightInfo = weights::runtime_parachains_hrmp::WeightInfo<Self>;
 }
 
@@ -1325,7 +1333,9 @@ impl parachains_paras_inherent::Config for Runtime {
 	type WeightInfo = weights::runtime_parachains_paras_inherent::WeightInfo<Runtime>;
 }
 
-impl parachains_scheduler::Config for Runtime {}
+impl parachains_scheduler::Config for Runtime {
+	type OnParathreadClaim = ();
+}
 
 impl parachains_initializer::Config for Runtime {
 	type Randomness = pallet_babe::RandomnessFromOneEpochAgo<Runtime>;
@@ -1337,6 +1347,7 @@ impl parachains_disputes::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type RewardValidators = ();
 	type SlashingHandler = ();
+	type RecordPerformance = ();
 	type WeightInfo = weights::runtime_parachains_disputes::WeightInfo<Runtime>;
 }
 
@@ -1408,6 +1419,8 @@ parameter_types! {
 	pub const EndingPeriod: BlockNumber = 5 * DAYS;
 	// ~ 1000 samples per day -> ~ 20 blocks per sample -> 2 minute samples
 	pub const SampleLength: BlockNumber = 2 * MINUTES;
+	// Keep the winner selection of the last few auctions around for verification.
+	pub const MaxAuctionAudits: u32 = 16;
 }
 
 type AuctionInitiate = EitherOfDiverse<
@@ -1422,6 +1435,7 @@ impl auctions::Config for Runtime {
 	type EndingPeriod = EndingPeriod;
 	type SampleLength = SampleLength;
 	type Randomness = pallet_babe::RandomnessFromOneEpochAgo<Runtime>;
+	type MaxAuctionAudits = MaxAuctionAudits;
 	type InitiateOrigin = AuctionInitiate;
 	type WeightInfo = weights::runtime_common_auctions::WeightInfo<Runtime>;
 }
@@ -1606,7 +1620,13 @@ impl Get<&'static str> for StakingMigrationV11OldPallet {
 /// All migrations that will run on the next runtime upgrade.
 ///
 /// Should be cleared after every release.
-pub type Migrations = ();
+pub type Migrations = (
+	parachains_ump::migration::v2::MigrateToV2<Runtime>,
+	parachains_dmp::migration::v1::MigrateToV1<Runtime>,
+	parachains_configuration::migration::v5::MigrateToV5<Runtime>,
+	parachains_scheduler::migration::v1::MigrateToV1<Runtime>,
+	claims::migration::v1::MigrateToV1<Runtime>,
+);
 
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic =
@@ -2028,6 +2048,22 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl auctions::AuctionsApi<Block, AccountId, Balance, BlockNumber, Hash> for Runtime {
+		fn auction_audit(
+			auction_index: auctions::AuctionIndex,
+		) -> Option<auctions::AuctionAudit<AccountId, Balance, BlockNumber, Hash>> {
+			Auctions::auction_audit(auction_index)
+		}
+	}
+
+	impl para_deposits::ParaDepositsApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+		fn para_deposits(
+			para: ParaId,
+		) -> para_deposits::ParaDeposits<AccountId, Balance, BlockNumber> {
+			para_deposits::para_deposits::<Runtime>(para)
+		}
+	}
+
 	impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Nonce> for Runtime {
 		fn account_nonce(account: AccountId) -> Nonce {
 			System::account_nonce(account)
@@ -20,6 +20,9 @@
 //! WORST CASE MAP SIZE: `1000000`
 //! HOSTNAME: `bm4`, CPU: `Intel(R) Core(TM) i7-7700K CPU @ 4.20GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("polkadot-dev"), DB CACHE: 1024
+//!
+//! NOTE: `on_initialize` is estimated from its storage accesses, pending the next run of the
+//! benchmark CLI on reference hardware.
 
 // Executed Command:
 // ./target/production/polkadot
@@ -93,6 +96,8 @@ impl<T: frame_system::Config> runtime_common::auctions::WeightInfo for WeightInf
 	/// Proof: Auctions AuctionCounter (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
 	/// Storage: Auctions Winning (r:3600 w:3600)
 	/// Proof: Auctions Winning (max_values: None, max_size: Some(1920), added: 4395, mode: MaxEncodedLen)
+	/// Storage: Auctions AuctionAudits (r:0 w:2)
+	/// Proof: Auctions AuctionAudits (max_values: None, max_size: Some(1972), added: 4447, mode: MaxEncodedLen)
 	/// Storage: Auctions ReservedAmounts (r:37 w:36)
 	/// Proof: Auctions ReservedAmounts (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
 	/// Storage: System Account (r:36 w:36)
@@ -110,12 +115,11 @@ impl<T: frame_system::Config> runtime_common::auctions::WeightInfo for WeightInf
 	fn on_initialize() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `7060445`
-		//  Estimated: `51339005`
-		// Minimum execution time: 15_974_151 nanoseconds.
+		//  Estimated: `51347899`
 		Weight::from_ref_time(16_296_082_000)
-			.saturating_add(Weight::from_proof_size(51339005))
+			.saturating_add(Weight::from_proof_size(51347899))
 			.saturating_add(T::DbWeight::get().reads(3688))
-			.saturating_add(T::DbWeight::get().writes(3683))
+			.saturating_add(T::DbWeight::get().writes(3685))
 	}
 	/// Storage: Auctions ReservedAmounts (r:37 w:36)
 	/// Proof: Auctions ReservedAmounts (max_values: None, max_size: Some(60), added: 2535, mode: MaxEncodedLen)
@@ -20,6 +20,9 @@
 //! WORST CASE MAP SIZE: `1000000`
 //! HOSTNAME: `bm4`, CPU: `Intel(R) Core(TM) i7-7700K CPU @ 4.20GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("polkadot-dev"), DB CACHE: 1024
+//!
+//! NOTE: `dissolve`, `claim_reward` and `set_reward_schedule` are estimated from their storage
+//! accesses, pending the next run of the benchmark CLI on reference hardware.
 
 // Executed Command:
 // ./target/production/polkadot
@@ -127,17 +130,17 @@ impl<T: frame_system::Config> runtime_common::crowdloan::WeightInfo for WeightIn
 	}
 	/// Storage: Crowdloan Funds (r:1 w:1)
 	/// Proof Skipped: Crowdloan Funds (max_values: None, max_size: None, mode: Measured)
-	/// Storage: System Account (r:1 w:1)
+	/// Storage: System Account (r:2 w:2)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Crowdloan RewardClaims (r:0 w:999)
+	/// Proof Skipped: Crowdloan RewardClaims (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Crowdloan RewardSchedules (r:0 w:1)
+	/// Proof Skipped: Crowdloan RewardSchedules (max_values: None, max_size: None, mode: Measured)
 	fn dissolve() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `439`
-		//  Estimated: `5517`
-		// Minimum execution time: 26_037 nanoseconds.
-		Weight::from_ref_time(27_102_000)
-			.saturating_add(Weight::from_proof_size(5517))
-			.saturating_add(T::DbWeight::get().reads(2))
-			.saturating_add(T::DbWeight::get().writes(2))
+		Weight::from_ref_time(37_102_000)
+			.saturating_add(Weight::from_proof_size(8120))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(1003))
 	}
 	/// Storage: Crowdloan Funds (r:1 w:1)
 	/// Proof Skipped: Crowdloan Funds (max_values: None, max_size: None, mode: Measured)
@@ -215,4 +218,30 @@ impl<T: frame_system::Config> runtime_common::crowdloan::WeightInfo for WeightIn
 			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(n.into())))
 			.saturating_add(Weight::from_proof_size(14663).saturating_mul(n.into()))
 	}
+	/// Storage: Crowdloan Funds (r:1 w:0)
+	/// Proof Skipped: Crowdloan Funds (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Crowdloan RewardSchedules (r:1 w:0)
+	/// Proof Skipped: Crowdloan RewardSchedules (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Slots Leases (r:1 w:0)
+	/// Proof Skipped: Slots Leases (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Crowdloan RewardClaims (r:1 w:1)
+	/// Proof Skipped: Crowdloan RewardClaims (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn claim_reward() -> Weight {
+		Weight::from_ref_time(68_213_000)
+			.saturating_add(Weight::from_proof_size(16471))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	/// Storage: Crowdloan Funds (r:1 w:0)
+	/// Proof Skipped: Crowdloan Funds (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Crowdloan RewardSchedules (r:0 w:1)
+	/// Proof Skipped: Crowdloan RewardSchedules (max_values: None, max_size: None, mode: Measured)
+	fn set_reward_schedule() -> Weight {
+		Weight::from_ref_time(16_435_000)
+			.saturating_add(Weight::from_proof_size(2742))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
 }
@@ -20,6 +20,9 @@
 //! WORST CASE MAP SIZE: `1000000`
 //! HOSTNAME: `bm4`, CPU: `Intel(R) Core(TM) i7-7700K CPU @ 4.20GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("polkadot-dev"), DB CACHE: 1024
+//!
+//! NOTE: `establish_system_channels` and `establish_channel_with_system` are estimated from
+//! their storage accesses, pending the next run of the benchmark CLI on reference hardware.
 
 // Executed Command:
 // ./target/production/polkadot
@@ -287,4 +290,56 @@ impl<T: frame_system::Config> runtime_parachains::hrmp::WeightInfo for WeightInf
 			.saturating_add(T::DbWeight::get().reads(14))
 			.saturating_add(T::DbWeight::get().writes(8))
 	}
+	/// Storage: Configuration ActiveConfig (r:1 w:0)
+	/// Proof Skipped: Configuration ActiveConfig (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Paras ParaLifecycles (r:2 w:0)
+	/// Proof Skipped: Paras ParaLifecycles (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Hrmp HrmpChannels (r:2 w:0)
+	/// Proof Skipped: Hrmp HrmpChannels (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Hrmp HrmpOpenChannelRequests (r:2 w:2)
+	/// Proof Skipped: Hrmp HrmpOpenChannelRequests (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Hrmp HrmpEgressChannelsIndex (r:2 w:0)
+	/// Proof Skipped: Hrmp HrmpEgressChannelsIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Hrmp HrmpOpenChannelRequestCount (r:2 w:2)
+	/// Proof Skipped: Hrmp HrmpOpenChannelRequestCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Hrmp HrmpIngressChannelsIndex (r:2 w:0)
+	/// Proof Skipped: Hrmp HrmpIngressChannelsIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Hrmp HrmpAcceptedChannelRequestCount (r:2 w:2)
+	/// Proof Skipped: Hrmp HrmpAcceptedChannelRequestCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Hrmp HrmpOpenChannelRequestsList (r:1 w:1)
+	/// Proof Skipped: Hrmp HrmpOpenChannelRequestsList (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Hrmp HrmpSystemChannelRequests (r:0 w:2)
+	/// Proof Skipped: Hrmp HrmpSystemChannelRequests (max_values: None, max_size: None, mode: Measured)
+	fn establish_system_channels() -> Weight {
+		Weight::from_ref_time(45_050_000)
+			.saturating_add(Weight::from_proof_size(33479))
+			.saturating_add(T::DbWeight::get().reads(16))
+			.saturating_add(T::DbWeight::get().writes(9))
+	}
+	/// Storage: Configuration ActiveConfig (r:1 w:0)
+	/// Proof Skipped: Configuration ActiveConfig (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Paras ParaLifecycles (r:2 w:0)
+	/// Proof Skipped: Paras ParaLifecycles (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Hrmp HrmpChannels (r:2 w:0)
+	/// Proof Skipped: Hrmp HrmpChannels (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Hrmp HrmpOpenChannelRequests (r:2 w:2)
+	/// Proof Skipped: Hrmp HrmpOpenChannelRequests (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Hrmp HrmpEgressChannelsIndex (r:2 w:0)
+	/// Proof Skipped: Hrmp HrmpEgressChannelsIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Hrmp HrmpOpenChannelRequestCount (r:2 w:2)
+	/// Proof Skipped: Hrmp HrmpOpenChannelRequestCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Hrmp HrmpIngressChannelsIndex (r:2 w:0)
+	/// Proof Skipped: Hrmp HrmpIngressChannelsIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Hrmp HrmpAcceptedChannelRequestCount (r:2 w:0)
+	/// Proof Skipped: Hrmp HrmpAcceptedChannelRequestCount (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Hrmp HrmpOpenChannelRequestsList (r:1 w:1)
+	/// Proof Skipped: Hrmp HrmpOpenChannelRequestsList (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Hrmp HrmpSystemChannelRequests (r:0 w:2)
+	/// Proof Skipped: Hrmp HrmpSystemChannelRequests (max_values: None, max_size: None, mode: Measured)
+	fn establish_channel_with_system() -> Weight {
+		Weight::from_ref_time(40_545_000)
+			.saturating_add(Weight::from_proof_size(33479))
+			.saturating_add(T::DbWeight::get().reads(16))
+			.saturating_add(T::DbWeight::get().writes(7))
+	}
 }
@@ -20,6 +20,9 @@
 //! WORST CASE MAP SIZE: `1000000`
 //! HOSTNAME: `bm4`, CPU: `Intel(R) Core(TM) i7-7700K CPU @ 4.20GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("polkadot-dev"), DB CACHE: 1024
+//!
+//! NOTE: `force_set_core_count` and `cancel_code_upgrade` are estimated from their storage
+//! accesses, pending the next run of the benchmark CLI on reference hardware.
 
 // Executed Command:
 // ./target/production/polkadot
@@ -183,6 +186,31 @@ impl<T: frame_system::Config> runtime_parachains::paras::WeightInfo for WeightIn
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	/// Storage: Paras UpcomingCoreCount (r:0 w:1)
+	/// Proof Skipped: Paras UpcomingCoreCount (max_values: None, max_size: None, mode: Measured)
+	fn force_set_core_count() -> Weight {
+		Weight::from_ref_time(5_104_000)
+			.saturating_add(Weight::from_proof_size(0))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: Paras FutureCodeUpgrades (r:1 w:1)
+	/// Proof Skipped: Paras FutureCodeUpgrades (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Paras UpcomingUpgrades (r:1 w:1)
+	/// Proof Skipped: Paras UpcomingUpgrades (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Paras FutureCodeHash (r:1 w:1)
+	/// Proof Skipped: Paras FutureCodeHash (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Paras CodeByHashRefs (r:1 w:1)
+	/// Proof Skipped: Paras CodeByHashRefs (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Paras UpgradeGoAheadSignal (r:0 w:1)
+	/// Proof Skipped: Paras UpgradeGoAheadSignal (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Paras CodeByHash (r:0 w:1)
+	/// Proof Skipped: Paras CodeByHash (max_values: None, max_size: None, mode: Measured)
+	fn cancel_code_upgrade() -> Weight {
+		Weight::from_ref_time(22_150_000)
+			.saturating_add(Weight::from_proof_size(6492))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
 	/// Storage: Configuration ActiveConfig (r:1 w:0)
 	/// Proof Skipped: Configuration ActiveConfig (max_values: Some(1), max_size: None, mode: Measured)
 	/// Storage: ParasShared ActiveValidatorKeys (r:1 w:0)
@@ -296,7 +296,9 @@ impl Contains<RuntimeCall> for SafeCallFilter {
 				paras_registrar::Call::swap { .. } |
 				paras_registrar::Call::remove_lock { .. } |
 				paras_registrar::Call::reserve { .. } |
-				paras_registrar::Call::add_lock { .. },
+				paras_registrar::Call::add_lock { .. } |
+				paras_registrar::Call::transfer_manager { .. } |
+				paras_registrar::Call::accept_manager { .. },
 			) |
 			RuntimeCall::XcmPallet(pallet_xcm::Call::limited_reserve_transfer_assets {
 				..
@@ -23,16 +23,20 @@
 use pallet_nis::WithMaximumOf;
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 use primitives::{
-	vstaging::ExecutorParams, AccountId, AccountIndex, Balance, BlockNumber, CandidateEvent,
-	CandidateHash, CommittedCandidateReceipt, CoreState, DisputeState, GroupRotationInfo, Hash,
-	Id as ParaId, InboundDownwardMessage, InboundHrmpMessage, Moment, Nonce,
-	OccupiedCoreAssumption, PersistedValidationData, ScrapedOnChainVotes, SessionInfo, Signature,
-	ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex,
+	vstaging::{
+		slashing, ExecutorParams, HostConfigurationDiff, HrmpTopology, InconsistentError,
+		OnDemandQueue, UmpQueueFootprint, UpgradePipeline, ValidatorPerformance,
+	},
+	AccountId, AccountIndex, Balance, BlockNumber, CandidateEvent, CandidateHash,
+	CommittedCandidateReceipt, CoreState, DisputeState, GroupRotationInfo, Hash, Id as ParaId,
+	InboundDownwardMessage, InboundHrmpMessage, Moment, Nonce, OccupiedCoreAssumption,
+	PersistedValidationData, ScrapedOnChainVotes, SessionInfo, Signature, ValidationCode,
+	ValidationCodeHash, ValidatorId, ValidatorIndex, PARACHAIN_KEY_TYPE_ID,
 };
 use runtime_common::{
 	assigned_slots, auctions, claims, crowdloan, impl_runtime_weights, impls::ToAuthor,
-	paras_registrar, paras_sudo_wrapper, prod_or_fast, slots, BlockHashCount, BlockLength,
-	SlowAdjustingFeeUpdate,
+	para_deposits, paras_registrar, paras_sudo_wrapper, prod_or_fast, slots, BlockHashCount,
+	BlockLength, SlowAdjustingFeeUpdate,
 };
 use sp_std::{cmp::Ordering, collections::btree_map::BTreeMap, prelude::*};
 
@@ -40,8 +44,9 @@ use runtime_parachains::{
 	configuration as parachains_configuration, disputes as parachains_disputes,
 	disputes::slashing as parachains_slashing,
 	dmp as parachains_dmp, hrmp as parachains_hrmp, inclusion as parachains_inclusion,
-	initializer as parachains_initializer, origin as parachains_origin, paras as parachains_paras,
-	paras_inherent as parachains_paras_inherent,
+	initializer as parachains_initializer, on_demand as parachains_on_demand,
+	origin as parachains_origin, paras as parachains_paras,
+	paras_inherent as parachains_paras_inherent, performance as parachains_performance,
 	runtime_api_impl::{
 		v2 as parachains_runtime_api_impl, vstaging as parachains_runtime_api_impl_staging,
 	},
@@ -753,12 +758,16 @@ where
 
 parameter_types! {
 	pub Prefix: &'static [u8] = b"Pay ROCs to the Rococo account:";
+	/// The chain ID of the EIP-712 domain claims are signed in, which is the SS58 prefix.
+	pub const ClaimsChainId: u64 = 42;
 }
 
 impl claims::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type VestingSchedule = Vesting;
 	type Prefix = Prefix;
+	type Address = claims::ExternalAddress;
+	type Verifier = claims::MultiClaimVerifier<ClaimsChainId>;
 	type MoveClaimOrigin =
 		pallet_collective::EnsureProportionMoreThan<AccountId, CouncilCollective, 1, 2>;
 	type WeightInfo = weights::runtime_common_claims::WeightInfo<Runtime>;
@@ -1049,6 +1058,7 @@ impl parachains_inclusion::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type DisputesHandler = ParasDisputes;
 	type RewardValidators = RewardValidators;
+	type RecordPerformance = ParasPerformance;
 }
 
 parameter_types! {
@@ -1057,6 +1067,7 @@ parameter_types! {
 
 impl parachains_paras::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
 	type WeightInfo = weights::runtime_parachains_paras::WeightInfo<Runtime>;
 	type UnsignedPriority = ParasUnsignedPriority;
 	type NextSessionRotation = Babe;
@@ -1064,6 +1075,7 @@ impl parachains_paras::Config for Runtime {
 
 parameter_types! {
 	pub const FirstMessageFactorPercent: u64 = 100;
+	pub const UmpMaxPageSize: u32 = 64 * 1024;
 }
 
 impl parachains_ump::Config for Runtime {
@@ -1072,6 +1084,7 @@ impl parachains_ump::Config for Runtime {
 		crate::parachains_ump::XcmSink<xcm_executor::XcmExecutor<xcm_config::XcmConfig>, Runtime>;
 	type FirstMessageFactorPercent = FirstMessageFactorPercent;
 	type ExecuteOverweightOrigin = EnsureRoot<AccountId>;
+	type MaxPageSize = UmpMaxPageSize;
 	type WeightInfo = weights::runtime_parachains_ump::WeightInfo<Runtime>;
 }
 
@@ -1081,6 +1094,7 @@ impl parachains_hrmp::Config for Runtime {
 	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
+	type SystemParas = parachains_hrmp::IsSystemPara;
 	type WeightInfo = weights::runtime_parachains_hrmp::WeightInfo<Runtime>;
 }
 
@@ -1088,7 +1102,23 @@ impl parachains_paras_inherent::Config for Runtime {
 	type WeightInfo = weights::runtime_parachains_paras_inherent::WeightInfo<Runtime>;
 }
 
-impl parachains_scheduler::Config for Runtime {}
+impl parachains_scheduler::Config for Runtime {
+	type OnParathreadClaim = OnDemand;
+}
+
+parameter_types! {
+	pub const OnDemandBaseFee: Balance = 100 * MILLICENTS;
+	pub const OnDemandMaxFeeMultiplier: u32 = 10;
+}
+
+impl parachains_on_demand::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type FeeDestination = Treasury;
+	type BaseFee = OnDemandBaseFee;
+	type MaxFeeMultiplier = OnDemandMaxFeeMultiplier;
+	type WeightInfo = weights::runtime_parachains_on_demand::WeightInfo<Runtime>;
+}
 
 impl parachains_initializer::Config for Runtime {
 	type Randomness = pallet_babe::RandomnessFromOneEpochAgo<Runtime>;
@@ -1100,9 +1130,14 @@ impl parachains_disputes::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type RewardValidators = ();
 	type SlashingHandler = parachains_slashing::SlashValidatorsForDisputes<ParasSlashing>;
+	type RecordPerformance = ParasPerformance;
 	type WeightInfo = weights::runtime_parachains_disputes::WeightInfo<Runtime>;
 }
 
+impl parachains_performance::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+}
+
 impl parachains_slashing::Config for Runtime {
 	type KeyOwnerProofSystem = Historical;
 	type KeyOwnerProof =
@@ -1175,6 +1210,8 @@ parameter_types! {
 	pub const EndingPeriod: BlockNumber = 5 * DAYS;
 	// ~ 1000 samples per day -> ~ 20 blocks per sample -> 2 minute samples
 	pub const SampleLength: BlockNumber = 2 * MINUTES;
+	// Keep the winner selection of the last few auctions around for verification.
+	pub const MaxAuctionAudits: u32 = 16;
 }
 
 type AuctionInitiate = EitherOfDiverse<
@@ -1189,6 +1226,7 @@ impl auctions::Config for Runtime {
 	type EndingPeriod = EndingPeriod;
 	type SampleLength = SampleLength;
 	type Randomness = pallet_babe::RandomnessFromOneEpochAgo<Runtime>;
+	type MaxAuctionAudits = MaxAuctionAudits;
 	type InitiateOrigin = AuctionInitiate;
 	type WeightInfo = weights::runtime_common_auctions::WeightInfo<Runtime>;
 }
@@ -1450,6 +1488,8 @@ construct_runtime! {
 		ParaSessionInfo: parachains_session_info::{Pallet, Storage} = 61,
 		ParasDisputes: parachains_disputes::{Pallet, Call, Storage, Event<T>} = 62,
 		ParasSlashing: parachains_slashing::{Pallet, Call, Storage, ValidateUnsigned} = 63,
+		OnDemand: parachains_on_demand::{Pallet, Call, Storage, Event<T>} = 64,
+		ParasPerformance: parachains_performance::{Pallet, Storage, Event<T>} = 65,
 
 		// Parachain Onboarding Pallets. Start indices at 70 to leave room.
 		Registrar: paras_registrar::{Pallet, Call, Storage, Event<T>, Config} = 70,
@@ -1509,7 +1549,14 @@ pub type UncheckedExtrinsic =
 /// All migrations that will run on the next runtime upgrade.
 ///
 /// Should be cleared after every release.
-pub type Migrations = ();
+pub type Migrations = (
+	parachains_ump::migration::v2::MigrateToV2<Runtime>,
+	parachains_dmp::migration::v1::MigrateToV1<Runtime>,
+	parachains_configuration::migration::v5::MigrateToV5<Runtime>,
+	parachains_scheduler::migration::v1::MigrateToV1<Runtime>,
+	parachains_performance::migration::v1::MigrateToV1<Runtime>,
+	claims::migration::v1::MigrateToV1<Runtime>,
+);
 
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
@@ -1565,6 +1612,7 @@ mod benches {
 		[runtime_common::paras_registrar, Registrar]
 		[runtime_parachains::configuration, Configuration]
 		[runtime_parachains::hrmp, Hrmp]
+		[runtime_parachains::on_demand, OnDemand]
 		[runtime_parachains::disputes, ParasDisputes]
 		[runtime_parachains::initializer, Initializer]
 		[runtime_parachains::paras_inherent, ParaInherent]
@@ -1664,7 +1712,7 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
-	#[api_version(4)]
+	#[api_version(5)]
 	impl primitives::runtime_api::ParachainHost<Block, Hash, BlockNumber> for Runtime {
 		fn validators() -> Vec<ValidatorId> {
 			parachains_runtime_api_impl::validators::<Runtime>()
@@ -1770,6 +1818,63 @@ sp_api::impl_runtime_apis! {
 		fn disputes() -> Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)> {
 			runtime_parachains::runtime_api_impl::vstaging::get_session_disputes::<Runtime>()
 		}
+
+		fn on_demand_queue() -> OnDemandQueue {
+			parachains_runtime_api_impl_staging::on_demand_queue::<Runtime>()
+		}
+
+		fn ump_queue_footprint(para_id: ParaId) -> UmpQueueFootprint {
+			parachains_runtime_api_impl_staging::ump_queue_footprint::<Runtime>(para_id)
+		}
+
+		fn hrmp_topology(para_id: ParaId) -> HrmpTopology {
+			parachains_runtime_api_impl_staging::hrmp_topology::<Runtime>(para_id)
+		}
+
+		fn check_host_configuration_diff(
+			diff: HostConfigurationDiff<BlockNumber>,
+		) -> Vec<InconsistentError<BlockNumber>> {
+			parachains_runtime_api_impl_staging::check_host_configuration_diff::<Runtime>(diff)
+		}
+
+		fn unapplied_slashes(
+		) -> Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)> {
+			parachains_runtime_api_impl_staging::unapplied_slashes::<Runtime>()
+		}
+
+		fn key_ownership_proof(
+			validator_id: ValidatorId,
+		) -> Option<slashing::OpaqueKeyOwnershipProof> {
+			Historical::prove((PARACHAIN_KEY_TYPE_ID, validator_id))
+				.map(|p| p.encode())
+				.map(slashing::OpaqueKeyOwnershipProof::new)
+		}
+
+		fn submit_report_dispute_lost(
+			dispute_proof: slashing::DisputeProof,
+			key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			parachains_runtime_api_impl_staging::submit_unsigned_slashing_report::<Runtime>(
+				dispute_proof,
+				key_ownership_proof,
+			)
+		}
+
+		fn candidates_pending_availability(
+			para_id: ParaId,
+		) -> Vec<CommittedCandidateReceipt<Hash>> {
+			parachains_runtime_api_impl_staging::candidates_pending_availability::<Runtime>(para_id)
+		}
+
+		fn validator_performance(
+			session_index: SessionIndex,
+		) -> Option<Vec<ValidatorPerformance>> {
+			parachains_runtime_api_impl_staging::validator_performance::<Runtime>(session_index)
+		}
+
+		fn para_upgrade_pipeline(para_id: ParaId) -> Option<UpgradePipeline<BlockNumber>> {
+			parachains_runtime_api_impl_staging::para_upgrade_pipeline::<Runtime>(para_id)
+		}
 	}
 
 	impl beefy_primitives::BeefyApi<Block> for Runtime {
@@ -1959,6 +2064,28 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl auctions::AuctionsApi<Block, AccountId, Balance, BlockNumber, Hash> for Runtime {
+		fn auction_audit(
+			auction_index: auctions::AuctionIndex,
+		) -> Option<auctions::AuctionAudit<AccountId, Balance, BlockNumber, Hash>> {
+			Auctions::auction_audit(auction_index)
+		}
+	}
+
+	impl assigned_slots::AssignedSlotsApi<Block, BlockNumber> for Runtime {
+		fn temporary_slot_schedule(periods: u32) -> Vec<(BlockNumber, Vec<ParaId>)> {
+			AssignedSlots::temporary_slot_schedule(periods)
+		}
+	}
+
+	impl para_deposits::ParaDepositsApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+		fn para_deposits(
+			para: ParaId,
+		) -> para_deposits::ParaDeposits<AccountId, Balance, BlockNumber> {
+			para_deposits::para_deposits::<Runtime>(para)
+		}
+	}
+
 	impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Nonce> for Runtime {
 		fn account_nonce(account: AccountId) -> Nonce {
 			System::account_nonce(account)
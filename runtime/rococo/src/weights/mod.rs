@@ -49,6 +49,7 @@ pub mod runtime_parachains_configuration;
 pub mod runtime_parachains_disputes;
 pub mod runtime_parachains_hrmp;
 pub mod runtime_parachains_initializer;
+pub mod runtime_parachains_on_demand;
 pub mod runtime_parachains_paras;
 pub mod runtime_parachains_paras_inherent;
 pub mod runtime_parachains_ump;
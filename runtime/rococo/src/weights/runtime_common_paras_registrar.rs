@@ -20,6 +20,9 @@
 //! WORST CASE MAP SIZE: `1000000`
 //! HOSTNAME: `bm6`, CPU: `Intel(R) Core(TM) i7-7700K CPU @ 4.20GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("rococo-dev"), DB CACHE: 1024
+//!
+//! NOTE: `transfer_manager` and `accept_manager` are estimated from their storage accesses, pending
+//! the next run of the benchmark CLI on reference hardware.
 
 // Executed Command:
 // ./target/production/polkadot
@@ -216,4 +219,26 @@ impl<T: frame_system::Config> runtime_common::paras_registrar::WeightInfo for We
 			.saturating_add(Weight::from_ref_time(922).saturating_mul(b.into()))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	/// Storage: Registrar Paras (r:1 w:0)
+	/// Proof Skipped: Registrar Paras (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Registrar PendingManager (r:0 w:1)
+	/// Proof Skipped: Registrar PendingManager (max_values: None, max_size: None, mode: Measured)
+	fn transfer_manager() -> Weight {
+		Weight::from_ref_time(22_131_000)
+			.saturating_add(Weight::from_proof_size(3036))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: Registrar PendingManager (r:1 w:1)
+	/// Proof Skipped: Registrar PendingManager (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Registrar Paras (r:1 w:1)
+	/// Proof Skipped: Registrar Paras (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn accept_manager() -> Weight {
+		Weight::from_ref_time(54_562_000)
+			.saturating_add(Weight::from_proof_size(12022))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
 }
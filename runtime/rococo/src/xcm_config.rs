@@ -17,26 +17,33 @@
 //! XCM configuration for Rococo.
 
 use super::{
-	parachains_origin, AccountId, AllPalletsWithSystem, Balances, CouncilCollective, ParaId,
-	Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, WeightToFee, XcmPallet,
+	parachains_origin, AccountId, AllPalletsWithSystem, Balances, CouncilCollective, Crowdloan, Dmp,
+	ParaId, Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, TransactionByteFee, WeightToFee,
+	XcmPallet,
 };
 use frame_support::{
 	match_types, parameter_types,
-	traits::{Contains, Everything, Nothing},
+	traits::{Contains, Everything, Nothing, PalletInfoAccess},
 	weights::Weight,
 };
+use rococo_runtime_constants::currency::CENTS;
 use runtime_common::{paras_registrar, xcm_sender, ToAuthor};
 use sp_core::ConstU32;
 use xcm::latest::prelude::*;
 use xcm_builder::{
 	AccountId32Aliases, AllowExplicitUnpaidExecutionFrom, AllowKnownQueryResponses,
-	AllowSubscriptionsFrom, AllowTopLevelPaidExecutionFrom, BackingToPlurality,
-	ChildParachainAsNative, ChildParachainConvertsVia, ChildSystemParachainAsSuperuser,
-	CurrencyAdapter as XcmCurrencyAdapter, FixedWeightBounds, IsChildSystemParachain, IsConcrete,
-	MintLocation, SignedAccountId32AsNative, SignedToAccountId32, SovereignSignedViaLocation,
-	TakeWeightCredit, UsingComponents, WeightInfoBounds, WithComputedOrigin,
+	AllowSubscriptionsFrom, AllowTopLevelPaidExecutionFrom, AsPrefixedGeneralIndex,
+	BackingToPlurality, ChildParachainAsNative, ChildParachainConvertsVia,
+	ChildSystemParachainAsSuperuser, ConvertedConcreteId, CurrencyAdapter as XcmCurrencyAdapter,
+	FixedWeightBounds, IsChildSystemParachain, IsConcrete, MintLocation, NoChecking,
+	NonFungiblesAdapter, SignedAccountId32AsNative, SignedToAccountId32,
+	SovereignSignedViaLocation, TakeWeightCredit, UsingComponents, WeightInfoBounds,
+	WithComputedOrigin,
+};
+use xcm_executor::{
+	traits::{JustTry, WithOriginFilter},
+	XcmExecutor,
 };
-use xcm_executor::{traits::WithOriginFilter, XcmExecutor};
 
 parameter_types! {
 	pub const TokenLocation: MultiLocation = Here.into_location();
@@ -66,6 +73,38 @@ pub type LocalAssetTransactor = XcmCurrencyAdapter<
 	LocalCheckAccount,
 >;
 
+parameter_types! {
+	pub CrowdloanReceiptsLocation: MultiLocation =
+		PalletInstance(<Crowdloan as PalletInfoAccess>::index() as u8).into();
+}
+
+/// Crowdloan contribution receipts, as non-fungible assets. The collection of a receipt is
+/// `PalletInstance(Crowdloan)/GeneralIndex(fund_index)` and its instance is the receipt index.
+///
+/// This lets contributors move their receipts to a parachain, e.g. with a reserve transfer, and
+/// prove their participation in the crowdloan there.
+pub type CrowdloanReceiptTransactor = NonFungiblesAdapter<
+	// Use this non-fungibles implementation:
+	Crowdloan,
+	// Use this matcher for the collection and instance of a receipt:
+	ConvertedConcreteId<
+		u32,
+		u32,
+		AsPrefixedGeneralIndex<CrowdloanReceiptsLocation, u32, JustTry>,
+		JustTry,
+	>,
+	// We can convert the MultiLocations with our converter above:
+	LocationConverter,
+	// Our chain's account ID type (we can't get away without mentioning it explicitly):
+	AccountId,
+	// Receipts are only issued by the crowdloan pallet, so there is nothing to track:
+	NoChecking,
+	CheckAccount,
+>;
+
+/// All of our asset transactors.
+pub type AssetTransactors = (LocalAssetTransactor, CrowdloanReceiptTransactor);
+
 /// The means that we convert an the XCM message origin location into a local dispatch origin.
 type LocalOriginConverter = (
 	// A `Signed` origin of the sovereign account that the original location controls.
@@ -81,12 +120,20 @@ type LocalOriginConverter = (
 parameter_types! {
 	/// The amount of weight an XCM operation takes. This is a safe overestimate.
 	pub const BaseXcmWeight: Weight = Weight::from_parts(1_000_000_000, 64 * 1024);
+	/// The asset ID for the asset that we use to pay for message delivery fees.
+	pub FeeAssetId: AssetId = Concrete(TokenLocation::get());
+	/// The base fee for the message delivery fees.
+	pub const BaseDeliveryFee: u128 = CENTS.saturating_mul(3);
 }
 /// The XCM router. When we want to send an XCM message, we use this type. It amalgamates all of our
 /// individual routers.
 pub type XcmRouter = (
 	// Only one router so far - use DMP to communicate with child parachains.
-	xcm_sender::ChildParachainRouter<Runtime, XcmPallet, ()>,
+	xcm_sender::ChildParachainRouter<
+		Runtime,
+		XcmPallet,
+		xcm_sender::ExponentialPrice<FeeAssetId, BaseDeliveryFee, TransactionByteFee, Dmp>,
+	>,
 );
 
 parameter_types! {
@@ -267,7 +314,9 @@ impl Contains<RuntimeCall> for SafeCallFilter {
 				paras_registrar::Call::swap { .. } |
 				paras_registrar::Call::remove_lock { .. } |
 				paras_registrar::Call::reserve { .. } |
-				paras_registrar::Call::add_lock { .. },
+				paras_registrar::Call::add_lock { .. } |
+				paras_registrar::Call::transfer_manager { .. } |
+				paras_registrar::Call::accept_manager { .. },
 			) |
 			RuntimeCall::XcmPallet(pallet_xcm::Call::limited_reserve_transfer_assets {
 				..
@@ -281,7 +330,7 @@ pub struct XcmConfig;
 impl xcm_executor::Config for XcmConfig {
 	type RuntimeCall = RuntimeCall;
 	type XcmSender = XcmRouter;
-	type AssetTransactor = LocalAssetTransactor;
+	type AssetTransactor = AssetTransactors;
 	type OriginConverter = LocalOriginConverter;
 	type IsReserve = ();
 	type IsTeleporter = TrustedTeleporters;
@@ -45,8 +45,9 @@ use primitives::{
 };
 use runtime_common::{
 	assigned_slots, auctions, crowdloan, elections::OnChainAccuracy, impl_runtime_weights,
-	impls::ToAuthor, paras_registrar, paras_sudo_wrapper, prod_or_fast, slots, BalanceToU256,
-	BlockHashCount, BlockLength, CurrencyToVote, SlowAdjustingFeeUpdate, U256ToBalance,
+	impls::ToAuthor, para_deposits, paras_registrar, paras_sudo_wrapper, prod_or_fast, slots,
+	BalanceToU256, BlockHashCount, BlockLength, CurrencyToVote, SlowAdjustingFeeUpdate,
+	U256ToBalance,
 };
 use runtime_parachains::{
 	configuration as parachains_configuration, disputes as parachains_disputes,
@@ -908,6 +909,7 @@ impl parachains_inclusion::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type DisputesHandler = ParasDisputes;
 	type RewardValidators = parachains_reward_points::RewardValidatorsWithEraPoints<Runtime>;
+	type RecordPerformance = ();
 }
 
 parameter_types! {
@@ -916,6 +918,7 @@ parameter_types! {
 
 impl parachains_paras::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
 	type WeightInfo = weights::runtime_parachains_paras::WeightInfo<Runtime>;
 	type UnsignedPriority = ParasUnsignedPriority;
 	type NextSessionRotation = Babe;
@@ -923,6 +926,7 @@ impl parachains_paras::Config for Runtime {
 
 parameter_types! {
 	pub const FirstMessageFactorPercent: u64 = 100;
+	pub const UmpMaxPageSize: u32 = 64 * 1024;
 }
 
 impl parachains_ump::Config for Runtime {
@@ -931,6 +935,7 @@ impl parachains_ump::Config for Runtime {
 		crate::parachains_ump::XcmSink<xcm_executor::XcmExecutor<xcm_config::XcmConfig>, Runtime>;
 	type FirstMessageFactorPercent = FirstMessageFactorPercent;
 	type ExecuteOverweightOrigin = EnsureRoot<AccountId>;
+	type MaxPageSize = UmpMaxPageSize;
 	type WeightInfo = weights::runtime_parachains_ump::WeightInfo<Runtime>;
 }
 
@@ -940,6 +945,7 @@ impl parachains_hrmp::Config for Runtime {
 	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
+	type SystemParas = parachains_hrmp::IsSystemPara;
 	type WeightInfo = weights::runtime_parachains_hrmp::WeightInfo<Self>;
 }
 
@@ -947,7 +953,9 @@ impl parachains_paras_inherent::Config for Runtime {
 	type WeightInfo = weights::runtime_parachains_paras_inherent::WeightInfo<Runtime>;
 }
 
-impl parachains_scheduler::Config for Runtime {}
+impl parachains_scheduler::Config for Runtime {
+	type OnParathreadClaim = ();
+}
 
 impl parachains_initializer::Config for Runtime {
 	type Randomness = pallet_babe::RandomnessFromOneEpochAgo<Runtime>;
@@ -980,6 +988,7 @@ impl parachains_disputes::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type RewardValidators = parachains_reward_points::RewardValidatorsWithEraPoints<Runtime>;
 	type SlashingHandler = parachains_slashing::SlashValidatorsForDisputes<ParasSlashing>;
+	type RecordPerformance = ();
 	type WeightInfo = weights::runtime_parachains_disputes::WeightInfo<Runtime>;
 }
 
@@ -1056,6 +1065,8 @@ parameter_types! {
 	pub const EndingPeriod: BlockNumber = 5 * DAYS;
 	// ~ 1000 samples per day -> ~ 20 blocks per sample -> 2 minute samples
 	pub const SampleLength: BlockNumber = 2 * MINUTES;
+	// Keep the winner selection of the last few auctions around for verification.
+	pub const MaxAuctionAudits: u32 = 16;
 }
 
 impl auctions::Config for Runtime {
@@ -1065,6 +1076,7 @@ impl auctions::Config for Runtime {
 	type EndingPeriod = EndingPeriod;
 	type SampleLength = SampleLength;
 	type Randomness = pallet_babe::RandomnessFromOneEpochAgo<Runtime>;
+	type MaxAuctionAudits = MaxAuctionAudits;
 	type InitiateOrigin = EnsureRoot<AccountId>;
 	type WeightInfo = weights::runtime_common_auctions::WeightInfo<Runtime>;
 }
@@ -1226,7 +1238,13 @@ impl Get<&'static str> for StakingMigrationV11OldPallet {
 /// All migrations that will run on the next runtime upgrade.
 ///
 /// Should be cleared after every release.
-pub type Migrations = (clean_state_migration::CleanMigrate,);
+pub type Migrations = (
+	clean_state_migration::CleanMigrate,
+	parachains_ump::migration::v2::MigrateToV2<Runtime>,
+	parachains_dmp::migration::v1::MigrateToV1<Runtime>,
+	parachains_configuration::migration::v5::MigrateToV5<Runtime>,
+	parachains_scheduler::migration::v1::MigrateToV1<Runtime>,
+);
 
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic =
@@ -1628,6 +1646,28 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl auctions::AuctionsApi<Block, AccountId, Balance, BlockNumber, Hash> for Runtime {
+		fn auction_audit(
+			auction_index: auctions::AuctionIndex,
+		) -> Option<auctions::AuctionAudit<AccountId, Balance, BlockNumber, Hash>> {
+			Auctions::auction_audit(auction_index)
+		}
+	}
+
+	impl assigned_slots::AssignedSlotsApi<Block, BlockNumber> for Runtime {
+		fn temporary_slot_schedule(periods: u32) -> Vec<(BlockNumber, Vec<ParaId>)> {
+			AssignedSlots::temporary_slot_schedule(periods)
+		}
+	}
+
+	impl para_deposits::ParaDepositsApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+		fn para_deposits(
+			para: ParaId,
+		) -> para_deposits::ParaDeposits<AccountId, Balance, BlockNumber> {
+			para_deposits::para_deposits::<Runtime>(para)
+		}
+	}
+
 	impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Nonce> for Runtime {
 		fn account_nonce(account: AccountId) -> Nonce {
 			System::account_nonce(account)
@@ -1858,8 +1898,10 @@ sp_api::impl_runtime_apis! {
 				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da95ecffd7b6c0f78751baa9d281e0bfa3a6d6f646c70792f74727372790000000000000000000000000000000000000000").to_vec().into(),
 				// Dmp DownwardMessageQueueHeads
 				hex_literal::hex!("63f78c98723ddc9073523ef3beefda0c4d7fefc408aac59dbfe80a72ac8e3ce5").to_vec().into(),
-				// Dmp DownwardMessageQueues
-				hex_literal::hex!("63f78c98723ddc9073523ef3beefda0ca95dac46c07a40d91506e7637ec4ba57").to_vec().into(),
+				// Dmp DownwardMessageQueueState
+				hex_literal::hex!("63f78c98723ddc9073523ef3beefda0c61f093ef11ca2e53cbacd774c008a141").to_vec().into(),
+				// Dmp DownwardMessageQueuePages
+				hex_literal::hex!("63f78c98723ddc9073523ef3beefda0c34d894ec6fadffc214525dde845b9027").to_vec().into(),
 				// Configuration ActiveConfig
 				hex_literal::hex!("06de3d8a54d27e44a9d5ce189618f22db4b49d95320d9021994c850f25b8e385").to_vec().into(),
 				// The transactional storage limit.
@@ -19,6 +19,9 @@
 //! DATE: 2023-01-23, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! HOSTNAME: `runner-b3zmxxc-project-163-concurrent-0`, CPU: `Intel(R) Xeon(R) CPU @ 2.60GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("westend-dev"), DB CACHE: 1024
+//!
+//! NOTE: `on_initialize` is estimated from its storage accesses, pending the next run of the
+//! benchmark CLI on reference hardware.
 
 // Executed Command:
 // ./target/production/polkadot
@@ -70,6 +73,7 @@ impl<T: frame_system::Config> runtime_common::auctions::WeightInfo for WeightInf
 	// Storage: Babe EpochStart (r:1 w:0)
 	// Storage: Auctions AuctionCounter (r:1 w:0)
 	// Storage: Auctions Winning (r:3600 w:3600)
+	// Storage: Auctions AuctionAudits (r:0 w:2)
 	// Storage: Auctions ReservedAmounts (r:37 w:36)
 	// Storage: System Account (r:36 w:36)
 	// Storage: Slots Leases (r:7 w:7)
@@ -78,10 +82,9 @@ impl<T: frame_system::Config> runtime_common::auctions::WeightInfo for WeightInf
 	// Storage: Paras ActionsQueue (r:1 w:1)
 	// Storage: Registrar Paras (r:1 w:1)
 	fn on_initialize() -> Weight {
-		// Minimum execution time: 18_715_887 nanoseconds.
 		Weight::from_ref_time(19_146_918_000)
 			.saturating_add(T::DbWeight::get().reads(3688))
-			.saturating_add(T::DbWeight::get().writes(3683))
+			.saturating_add(T::DbWeight::get().writes(3685))
 	}
 	// Storage: Auctions ReservedAmounts (r:37 w:36)
 	// Storage: System Account (r:36 w:36)
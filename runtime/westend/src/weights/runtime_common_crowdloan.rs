@@ -19,6 +19,9 @@
 //! DATE: 2023-01-23, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! HOSTNAME: `runner-b3zmxxc-project-163-concurrent-0`, CPU: `Intel(R) Xeon(R) CPU @ 2.60GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("westend-dev"), DB CACHE: 1024
+//!
+//! NOTE: `dissolve`, `claim_reward` and `set_reward_schedule` are estimated from their storage
+//! accesses, pending the next run of the benchmark CLI on reference hardware.
 
 // Executed Command:
 // ./target/production/polkadot
@@ -91,12 +94,13 @@ impl<T: frame_system::Config> runtime_common::crowdloan::WeightInfo for WeightIn
 			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(k.into())))
 	}
 	// Storage: Crowdloan Funds (r:1 w:1)
-	// Storage: System Account (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Crowdloan RewardClaims (r:0 w:499)
+	// Storage: Crowdloan RewardSchedules (r:0 w:1)
 	fn dissolve() -> Weight {
-		// Minimum execution time: 50_520 nanoseconds.
-		Weight::from_ref_time(55_019_000)
-			.saturating_add(T::DbWeight::get().reads(2))
-			.saturating_add(T::DbWeight::get().writes(2))
+		Weight::from_ref_time(75_019_000)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(503))
 	}
 	// Storage: Crowdloan Funds (r:1 w:1)
 	fn edit() -> Weight {
@@ -142,4 +146,21 @@ impl<T: frame_system::Config> runtime_common::crowdloan::WeightInfo for WeightIn
 			.saturating_add(T::DbWeight::get().writes(3))
 			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(n.into())))
 	}
+	// Storage: Crowdloan Funds (r:1 w:0)
+	// Storage: Crowdloan RewardSchedules (r:1 w:0)
+	// Storage: Slots Leases (r:1 w:0)
+	// Storage: Crowdloan RewardClaims (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn claim_reward() -> Weight {
+		Weight::from_ref_time(104_925_000)
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: Crowdloan Funds (r:1 w:0)
+	// Storage: Crowdloan RewardSchedules (r:0 w:1)
+	fn set_reward_schedule() -> Weight {
+		Weight::from_ref_time(42_137_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
 }
@@ -19,6 +19,9 @@
 //! DATE: 2023-01-23, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! HOSTNAME: `runner-b3zmxxc-project-163-concurrent-0`, CPU: `Intel(R) Xeon(R) CPU @ 2.60GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("westend-dev"), DB CACHE: 1024
+//!
+//! NOTE: `transfer_manager` and `accept_manager` are estimated from their storage accesses, pending
+//! the next run of the benchmark CLI on reference hardware.
 
 // Executed Command:
 // ./target/production/polkadot
@@ -136,4 +139,19 @@ impl<T: frame_system::Config> runtime_common::paras_registrar::WeightInfo for We
 			.saturating_add(Weight::from_ref_time(1_024).saturating_mul(b.into()))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	// Storage: Registrar Paras (r:1 w:0)
+	// Storage: Registrar PendingManager (r:0 w:1)
+	fn transfer_manager() -> Weight {
+		Weight::from_ref_time(26_912_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Registrar PendingManager (r:1 w:1)
+	// Storage: Registrar Paras (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn accept_manager() -> Weight {
+		Weight::from_ref_time(63_285_000)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
 }
@@ -19,6 +19,9 @@
 //! DATE: 2023-01-23, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! HOSTNAME: `runner-b3zmxxc-project-163-concurrent-0`, CPU: `Intel(R) Xeon(R) CPU @ 2.60GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("westend-dev"), DB CACHE: 1024
+//!
+//! NOTE: `list_lease_period`, `unlist_lease_period` and `buy_lease_period` are estimated from their
+//! storage accesses, pending the next run of the benchmark CLI on reference hardware.
 
 // Executed Command:
 // ./target/production/polkadot
@@ -93,4 +96,26 @@ impl<T: frame_system::Config> runtime_common::slots::WeightInfo for WeightInfo<T
 			.saturating_add(T::DbWeight::get().reads(5))
 			.saturating_add(T::DbWeight::get().writes(3))
 	}
+	// Storage: Slots Leases (r:1 w:0)
+	// Storage: Slots LeaseListings (r:0 w:1)
+	fn list_lease_period() -> Weight {
+		Weight::from_ref_time(25_007_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Slots LeaseListings (r:1 w:1)
+	fn unlist_lease_period() -> Weight {
+		Weight::from_ref_time(22_822_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Slots LeaseListings (r:1 w:1)
+	// Storage: Registrar Paras (r:1 w:0)
+	// Storage: Slots Leases (r:3 w:2)
+	// Storage: System Account (r:2 w:2)
+	fn buy_lease_period() -> Weight {
+		Weight::from_ref_time(96_075_000)
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
 }
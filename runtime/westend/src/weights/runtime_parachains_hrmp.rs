@@ -19,6 +19,9 @@
 //! DATE: 2023-01-23, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! HOSTNAME: `runner-b3zmxxc-project-163-concurrent-0`, CPU: `Intel(R) Xeon(R) CPU @ 2.60GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("westend-dev"), DB CACHE: 1024
+//!
+//! NOTE: `establish_system_channels` and `establish_channel_with_system` are estimated from
+//! their storage accesses, pending the next run of the benchmark CLI on reference hardware.
 
 // Executed Command:
 // ./target/production/polkadot
@@ -180,4 +183,34 @@ impl<T: frame_system::Config> runtime_parachains::hrmp::WeightInfo for WeightInf
 			.saturating_add(T::DbWeight::get().reads(13))
 			.saturating_add(T::DbWeight::get().writes(8))
 	}
+	// Storage: Configuration ActiveConfig (r:1 w:0)
+	// Storage: Paras ParaLifecycles (r:2 w:0)
+	// Storage: Hrmp HrmpChannels (r:2 w:0)
+	// Storage: Hrmp HrmpOpenChannelRequests (r:2 w:2)
+	// Storage: Hrmp HrmpEgressChannelsIndex (r:2 w:0)
+	// Storage: Hrmp HrmpOpenChannelRequestCount (r:2 w:2)
+	// Storage: Hrmp HrmpIngressChannelsIndex (r:2 w:0)
+	// Storage: Hrmp HrmpAcceptedChannelRequestCount (r:2 w:2)
+	// Storage: Hrmp HrmpOpenChannelRequestsList (r:1 w:1)
+	// Storage: Hrmp HrmpSystemChannelRequests (r:0 w:2)
+	fn establish_system_channels() -> Weight {
+		Weight::from_ref_time(53_204_000)
+			.saturating_add(T::DbWeight::get().reads(16))
+			.saturating_add(T::DbWeight::get().writes(9))
+	}
+	// Storage: Configuration ActiveConfig (r:1 w:0)
+	// Storage: Paras ParaLifecycles (r:2 w:0)
+	// Storage: Hrmp HrmpChannels (r:2 w:0)
+	// Storage: Hrmp HrmpOpenChannelRequests (r:2 w:2)
+	// Storage: Hrmp HrmpEgressChannelsIndex (r:2 w:0)
+	// Storage: Hrmp HrmpOpenChannelRequestCount (r:2 w:2)
+	// Storage: Hrmp HrmpIngressChannelsIndex (r:2 w:0)
+	// Storage: Hrmp HrmpAcceptedChannelRequestCount (r:2 w:0)
+	// Storage: Hrmp HrmpOpenChannelRequestsList (r:1 w:1)
+	// Storage: Hrmp HrmpSystemChannelRequests (r:0 w:2)
+	fn establish_channel_with_system() -> Weight {
+		Weight::from_ref_time(47_883_000)
+			.saturating_add(T::DbWeight::get().reads(16))
+			.saturating_add(T::DbWeight::get().writes(7))
+	}
 }
@@ -19,6 +19,9 @@
 //! DATE: 2023-01-23, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! HOSTNAME: `runner-b3zmxxc-project-163-concurrent-0`, CPU: `Intel(R) Xeon(R) CPU @ 2.60GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("westend-dev"), DB CACHE: 1024
+//!
+//! NOTE: `force_set_core_count` and `cancel_code_upgrade` are estimated from their storage
+//! accesses, pending the next run of the benchmark CLI on reference hardware.
 
 // Executed Command:
 // ./target/production/polkadot
@@ -126,6 +129,22 @@ impl<T: frame_system::Config> runtime_parachains::paras::WeightInfo for WeightIn
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	// Storage: Paras UpcomingCoreCount (r:0 w:1)
+	fn force_set_core_count() -> Weight {
+		Weight::from_ref_time(6_412_000)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Paras FutureCodeUpgrades (r:1 w:1)
+	// Storage: Paras UpcomingUpgrades (r:1 w:1)
+	// Storage: Paras FutureCodeHash (r:1 w:1)
+	// Storage: Paras CodeByHashRefs (r:1 w:1)
+	// Storage: Paras UpgradeGoAheadSignal (r:0 w:1)
+	// Storage: Paras CodeByHash (r:0 w:1)
+	fn cancel_code_upgrade() -> Weight {
+		Weight::from_ref_time(24_806_000)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
 	// Storage: ParasShared ActiveValidatorKeys (r:1 w:0)
 	// Storage: ParasShared CurrentSessionIndex (r:1 w:0)
 	// Storage: Paras PvfActiveVoteMap (r:1 w:1)
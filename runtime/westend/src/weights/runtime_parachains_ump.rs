@@ -19,6 +19,9 @@
 //! DATE: 2023-01-23, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! HOSTNAME: `runner-b3zmxxc-project-163-concurrent-0`, CPU: `Intel(R) Xeon(R) CPU @ 2.60GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("westend-dev"), DB CACHE: 1024
+//!
+//! NOTE: `resume_overweight_page`, `receive_upward_messages` and `service_page` are estimated
+//! from their storage accesses, pending the next run of the benchmark CLI on reference hardware.
 
 // Executed Command:
 // ./target/production/polkadot
@@ -63,10 +66,30 @@ impl<T: frame_system::Config> runtime_parachains::ump::WeightInfo for WeightInfo
 	}
 	// Storage: Ump Overweight (r:1 w:1)
 	// Storage: Ump CounterForOverweight (r:1 w:1)
-	fn service_overweight() -> Weight {
-		// Minimum execution time: 29_452 nanoseconds.
-		Weight::from_ref_time(31_140_000)
-			.saturating_add(T::DbWeight::get().reads(2))
-			.saturating_add(T::DbWeight::get().writes(2))
+	// Storage: Ump Books (r:1 w:1)
+	fn resume_overweight_page() -> Weight {
+		Weight::from_ref_time(40_000_000)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: Ump Books (r:1 w:1)
+	// Storage: Ump Pages (r:0 w:64)
+	// Storage: Ump RelayDispatchQueueSize (r:1 w:1)
+	// Storage: Ump NeedsDispatch (r:1 w:1)
+	/// The range of component `p` is `[1, 64]`.
+	fn receive_upward_messages(p: u32, ) -> Weight {
+		Weight::from_ref_time(12_000_000)
+			.saturating_add(Weight::from_ref_time(4_000_000).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(p.into())))
+	}
+	// Storage: Ump Books (r:1 w:1)
+	// Storage: Ump RelayDispatchQueueSize (r:1 w:1)
+	// Storage: Ump Pages (r:1 w:1)
+	fn service_page() -> Weight {
+		Weight::from_ref_time(20_000_000)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
 	}
 }
@@ -0,0 +1,84 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The compare command.
+
+use crate::{opts::CompareConfig, prelude::*, Error, SharedRpcClient};
+use sp_npos_elections::ElectionScore;
+use sp_runtime::Perbill;
+use std::time::Instant;
+
+macro_rules! compare_cmd_for { ($runtime:ident) => { paste::paste! {
+	/// Execute the compare command.
+	pub(crate) async fn [<compare_cmd_ $runtime>](
+		rpc: Option<SharedRpcClient>,
+		config: CompareConfig,
+	) -> Result<(), Error<$crate::[<$runtime _runtime_exports>]::Runtime>> {
+		use $crate::[<$runtime _runtime_exports>]::*;
+		let pallets = if config.force_snapshot {
+			vec!["Staking".to_string(), "BagsList".to_string()]
+		} else {
+			Default::default()
+		};
+		let mut ext = crate::load_or_create_election_ext::<Runtime, Block>(
+			rpc,
+			config.snapshot.as_deref(),
+			config.at,
+			pallets,
+		).await?;
+		if config.force_snapshot {
+			crate::dry_run::force_create_snapshot::<Runtime>(&mut ext)?;
+		};
+
+		let mut best: Option<(crate::opts::Solver, ElectionScore)> = None;
+		for solver in crate::all_solvers(config.iterations) {
+			let started = Instant::now();
+			let outcome = crate::mine_with::<Runtime>(&solver, &mut ext, true);
+			let elapsed = started.elapsed();
+
+			match outcome {
+				Ok(raw_solution) => {
+					let ElectionScore { minimal_stake, sum_stake, sum_stake_squared } = raw_solution.score;
+					println!(
+						"{:?}: score [{}, {}, {}] in {:?}",
+						solver,
+						Token::from(minimal_stake),
+						Token::from(sum_stake),
+						Token::from(sum_stake_squared),
+						elapsed,
+					);
+					let is_better = best.as_ref().map_or(true, |(_, score)| {
+						raw_solution.score.strict_threshold_better(*score, Perbill::zero())
+					});
+					if is_better {
+						best = Some((solver, raw_solution.score));
+					}
+				},
+				Err(err) => println!("{:?}: failed after {:?}: {:?}", solver, elapsed, err),
+			}
+		}
+
+		match best {
+			Some((solver, _)) => println!("best solver: {:?}", solver),
+			None => println!("no solver found a feasible solution"),
+		}
+		Ok(())
+	}
+}}}
+
+compare_cmd_for!(polkadot);
+compare_cmd_for!(kusama);
+compare_cmd_for!(westend);
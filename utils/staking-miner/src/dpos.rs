@@ -0,0 +1,122 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A DPoS-like approximation of the NPoS election.
+
+use frame_election_provider_support::{NposSolver, VoteWeight, WeightInfo};
+use frame_support::weights::Weight;
+use sp_npos_elections::{Assignment, ElectionResult, ExtendedBalance, IdentifierT, PerThing128};
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	marker::PhantomData,
+};
+
+/// Elects the targets with the most approval stake, where each voter splits its stake evenly
+/// among the targets it votes for, and assigns each voter evenly to the winners it voted for.
+///
+/// This is much faster than the Phragmén family of solvers, but its solutions usually score
+/// worse, as it does not balance the stake of the voters at all.
+pub(crate) struct Dpos<AccountId, Accuracy>(PhantomData<(AccountId, Accuracy)>);
+
+impl<AccountId: IdentifierT, Accuracy: PerThing128> NposSolver for Dpos<AccountId, Accuracy> {
+	type AccountId = AccountId;
+	type Accuracy = Accuracy;
+	type Error = sp_npos_elections::Error;
+
+	fn solve(
+		to_elect: usize,
+		targets: Vec<Self::AccountId>,
+		voters: Vec<(Self::AccountId, VoteWeight, impl IntoIterator<Item = Self::AccountId>)>,
+	) -> Result<ElectionResult<Self::AccountId, Self::Accuracy>, Self::Error> {
+		let mut backing =
+			targets.into_iter().map(|t| (t, 0)).collect::<BTreeMap<_, ExtendedBalance>>();
+		let voters = voters
+			.into_iter()
+			.map(|(who, stake, votes)| {
+				let votes =
+					votes.into_iter().filter(|t| backing.contains_key(t)).collect::<Vec<_>>();
+				(who, stake, votes)
+			})
+			.collect::<Vec<_>>();
+
+		for (_, stake, votes) in voters.iter().filter(|(_, _, votes)| !votes.is_empty()) {
+			let share = ExtendedBalance::from(*stake) / votes.len() as ExtendedBalance;
+			for target in votes {
+				if let Some(backed) = backing.get_mut(target) {
+					*backed = backed.saturating_add(share);
+				}
+			}
+		}
+
+		let mut winners = backing.into_iter().collect::<Vec<_>>();
+		winners.sort_by(|(_, x), (_, y)| y.cmp(x));
+		winners.truncate(to_elect);
+
+		let elected = winners.iter().map(|(w, _)| w.clone()).collect::<BTreeSet<_>>();
+		let assignments = voters
+			.into_iter()
+			.filter_map(|(who, _, votes)| {
+				let votes = votes.into_iter().filter(|t| elected.contains(t)).collect::<Vec<_>>();
+				// the ratios are normalized when the assignments are converted to stakes.
+				let ratio = Accuracy::from_rational(1, votes.len().max(1) as u64);
+				let distribution = votes.into_iter().map(|t| (t, ratio)).collect::<Vec<_>>();
+				(!distribution.is_empty()).then(|| Assignment { who, distribution })
+			})
+			.collect::<Vec<_>>();
+
+		Ok(ElectionResult { winners, assignments })
+	}
+
+	fn weight<T: WeightInfo>(voters: u32, targets: u32, vote_degree: u32) -> Weight {
+		T::phragmen(voters, targets, vote_degree)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::Perbill;
+
+	#[test]
+	fn dpos_elects_most_approved_targets() {
+		let voters = vec![
+			(10u32, 30, vec![1, 2, 3]),
+			(20, 20, vec![2]),
+			(30, 10, vec![3, 4]),
+			// votes for a non-target only.
+			(40, 100, vec![5]),
+		];
+
+		let ElectionResult { winners, assignments } =
+			Dpos::<u32, Perbill>::solve(2, vec![1, 2, 3, 4], voters).unwrap();
+
+		assert_eq!(winners, vec![(2, 30), (3, 15)]);
+		assert_eq!(
+			assignments,
+			vec![
+				Assignment {
+					who: 10,
+					distribution: vec![
+						(2, Perbill::from_percent(50)),
+						(3, Perbill::from_percent(50))
+					],
+				},
+				Assignment { who: 20, distribution: vec![(2, Perbill::one())] },
+				Assignment { who: 30, distribution: vec![(3, Perbill::one())] },
+			]
+		);
+	}
+}
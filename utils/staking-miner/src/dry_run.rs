@@ -21,9 +21,10 @@ use codec::Encode;
 use frame_support::traits::Currency;
 use sp_core::Bytes;
 use sp_npos_elections::ElectionScore;
+use std::io::Write;
 
 /// Forcefully create the snapshot. This can be used to compute the election at anytime.
-fn force_create_snapshot<T: EPM::Config>(ext: &mut Ext) -> Result<(), Error<T>> {
+pub(crate) fn force_create_snapshot<T: EPM::Config>(ext: &mut Ext) -> Result<(), Error<T>> {
 	ext.execute_with(|| {
 		if <EPM::Snapshot<T>>::exists() {
 			log::info!(target: LOG_TARGET, "snapshot already exists.");
@@ -161,6 +162,45 @@ macro_rules! dry_run_cmd_for { ($runtime:ident) => { paste::paste! {
 	}
 }}}
 
+macro_rules! offline_dry_run_cmd_for { ($runtime:ident) => { paste::paste! {
+	/// Execute the dry-run command on a state snapshot, without a node to build and dry-run the
+	/// transaction with. The solution is written to `solution.raw.bin` instead.
+	pub(crate) async fn [<offline_dry_run_cmd_ $runtime>](
+		config: DryRunConfig,
+	) -> Result<(), Error<$crate::[<$runtime _runtime_exports>]::Runtime>> {
+		use $crate::[<$runtime _runtime_exports>]::*;
+		let path = config.snapshot.as_deref().expect("offline dry-run requires a snapshot; qed.");
+		let mut ext = crate::load_election_ext::<Runtime, Block>(path).await?;
+		if config.force_snapshot {
+			force_create_snapshot::<Runtime>(&mut ext)?;
+		};
+
+		log::debug!(target: LOG_TARGET, "solving with {:?}", config.solver);
+		let raw_solution = crate::mine_with::<Runtime>(&config.solver, &mut ext, false)?;
+
+		let feasibility_result = ext.execute_with(|| {
+			EPM::Pallet::<Runtime>::feasibility_check(raw_solution.clone(), EPM::ElectionCompute::Signed)
+		});
+		log::info!(target: LOG_TARGET, "feasibility result is {:?}", feasibility_result.map(|_| ()));
+
+		let ElectionScore { minimal_stake, sum_stake, sum_stake_squared } = raw_solution.score;
+		log::info!(
+			target: LOG_TARGET,
+			"solution score {:?} / length {:?}",
+			[Token::from(minimal_stake), Token::from(sum_stake), Token::from(sum_stake_squared)],
+			raw_solution.encode().len(),
+		);
+
+		let mut solution_file = std::fs::File::create("solution.raw.bin")?;
+		solution_file.write_all(&raw_solution.encode())?;
+		Ok(())
+	}
+}}}
+
 dry_run_cmd_for!(polkadot);
 dry_run_cmd_for!(kusama);
 dry_run_cmd_for!(westend);
+
+offline_dry_run_cmd_for!(polkadot);
+offline_dry_run_cmd_for!(kusama);
+offline_dry_run_cmd_for!(westend);
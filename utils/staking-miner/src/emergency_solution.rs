@@ -23,12 +23,17 @@ use std::io::Write;
 macro_rules! emergency_solution_cmd_for { ($runtime:ident) => { paste::paste! {
 	/// Execute the emergency-solution command.
 	pub(crate) async fn [<emergency_solution_cmd_ $runtime>](
-		client: SharedRpcClient,
+		client: Option<SharedRpcClient>,
 		config: EmergencySolutionConfig,
 	) -> Result<(), Error<$crate::[<$runtime _runtime_exports>]::Runtime>> {
 		use $crate::[<$runtime _runtime_exports>]::*;
 
-		let mut ext = crate::create_election_ext::<Runtime, Block>(client, config.at, vec![]).await?;
+		let mut ext = crate::load_or_create_election_ext::<Runtime, Block>(
+			client,
+			config.snapshot.as_deref(),
+			config.at,
+			vec![],
+		).await?;
 		let raw_solution = crate::mine_with::<Runtime>(&config.solver, &mut ext, false)?;
 
 		ext.execute_with(|| {
@@ -32,8 +32,11 @@
 // see https://github.com/rust-lang/rust/issues/49112
 #![allow(unused_unsafe)]
 
+mod compare;
+mod dpos;
 mod dry_run;
 mod emergency_solution;
+mod metrics;
 mod monitor;
 mod opts;
 mod prelude;
@@ -50,14 +53,19 @@ use frame_election_provider_support::NposSolver;
 use frame_support::traits::Get;
 use futures_util::StreamExt;
 use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
-use remote_externalities::{Builder, Mode, OnlineConfig};
+use remote_externalities::{Builder, Mode, OfflineConfig, OnlineConfig, SnapshotConfig};
 use rpc::{RpcApiClient, SharedRpcClient};
 use runtime_versions::RuntimeVersions;
 use signal_hook::consts::signal::*;
 use signal_hook_tokio::Signals;
 use sp_npos_elections::BalancingConfig;
-use sp_runtime::{traits::Block as BlockT, DeserializeOwned};
-use std::{ops::Deref, sync::Arc, time::Duration};
+use sp_runtime::{traits::Block as BlockT, DeserializeOwned, Perbill};
+use std::{
+	ops::Deref,
+	path::Path,
+	sync::Arc,
+	time::{Duration, Instant},
+};
 use tracing_subscriber::{fmt, EnvFilter};
 
 pub(crate) enum AnyRuntime {
@@ -75,7 +83,9 @@ macro_rules! construct_runtime_prelude {
 			pub(crate) use [<$runtime _runtime>]::*;
 			pub(crate) use crate::monitor::[<monitor_cmd_ $runtime>] as monitor_cmd;
 			pub(crate) use crate::dry_run::[<dry_run_cmd_ $runtime>] as dry_run_cmd;
+			pub(crate) use crate::dry_run::[<offline_dry_run_cmd_ $runtime>] as offline_dry_run_cmd;
 			pub(crate) use crate::emergency_solution::[<emergency_solution_cmd_ $runtime>] as emergency_solution_cmd;
+			pub(crate) use crate::compare::[<compare_cmd_ $runtime>] as compare_cmd;
 			pub(crate) use private::{[<create_uxt_ $runtime>] as create_uxt};
 
 			mod private {
@@ -245,6 +255,7 @@ enum Error<T: EPM::Config> {
 	Codec(#[from] codec::Error),
 	Crypto(sp_core::crypto::SecretStringError),
 	RemoteExternalities(&'static str),
+	Prometheus(#[from] prometheus_endpoint::PrometheusError),
 	PalletMiner(EPM::unsigned::MinerError),
 	PalletElection(EPM::ElectionError<T>),
 	PalletFeasibility(EPM::FeasibilityError),
@@ -253,6 +264,9 @@ enum Error<T: EPM::Config> {
 	AlreadySubmitted,
 	VersionMismatch,
 	StrategyNotSatisfied,
+	QueueFull,
+	FeeCapExceeded,
+	DepositCapExceeded,
 	Other(String),
 }
 
@@ -293,13 +307,14 @@ frame_support::parameter_types! {
 	pub static Balancing: Option<BalancingConfig> = Some( BalancingConfig { iterations: BalanceIterations::get(), tolerance: 0 } );
 }
 
-/// Build the Ext at hash with all the data of `ElectionProviderMultiPhase` and any additional
-/// pallets.
-async fn create_election_ext<T, B>(
+/// The config to scrape all the data of `ElectionProviderMultiPhase` and any additional pallets
+/// at hash, optionally saving it to `state_snapshot`.
+fn election_online_config<T, B>(
 	client: SharedRpcClient,
 	at: Option<B::Hash>,
 	additional: Vec<String>,
-) -> Result<Ext, Error<T>>
+	state_snapshot: Option<SnapshotConfig>,
+) -> OnlineConfig<B>
 where
 	T: EPM::Config,
 	B: BlockT + DeserializeOwned,
@@ -312,23 +327,96 @@ where
 		.expect("Pallet always has name; qed.")
 		.to_string()];
 	pallets.extend(additional);
+	OnlineConfig {
+		transport: client.into_inner().into(),
+		at,
+		pallets,
+		state_snapshot,
+		hashed_prefixes: vec![<frame_system::BlockHash<T>>::prefix_hash()],
+		hashed_keys: vec![[twox_128(b"System"), twox_128(b"Number")].concat()],
+		..Default::default()
+	}
+}
+
+/// Build the Ext at hash with all the data of `ElectionProviderMultiPhase` and any additional
+/// pallets.
+async fn create_election_ext<T, B>(
+	client: SharedRpcClient,
+	at: Option<B::Hash>,
+	additional: Vec<String>,
+) -> Result<Ext, Error<T>>
+where
+	T: EPM::Config,
+	B: BlockT + DeserializeOwned,
+	B::Header: DeserializeOwned,
+{
+	Builder::<B>::new()
+		.mode(Mode::Online(election_online_config::<T, B>(client, at, additional, None)))
+		.build()
+		.await
+		.map_err(|why| Error::RemoteExternalities(why))
+		.map(|rx| rx.inner_ext)
+}
+
+/// Same as [`create_election_ext`], but also saves the scraped state to the file at `path`, to be
+/// loaded later by [`load_election_ext`].
+async fn save_election_ext<T, B>(
+	client: SharedRpcClient,
+	at: Option<B::Hash>,
+	additional: Vec<String>,
+	path: &Path,
+) -> Result<Ext, Error<T>>
+where
+	T: EPM::Config,
+	B: BlockT + DeserializeOwned,
+	B::Header: DeserializeOwned,
+{
+	let state_snapshot = Some(SnapshotConfig::new(path));
+	Builder::<B>::new()
+		.mode(Mode::Online(election_online_config::<T, B>(client, at, additional, state_snapshot)))
+		.build()
+		.await
+		.map_err(|why| Error::RemoteExternalities(why))
+		.map(|rx| rx.inner_ext)
+}
+
+/// Build the Ext from the state saved to the file at `path` by [`save_election_ext`].
+async fn load_election_ext<T, B>(path: &Path) -> Result<Ext, Error<T>>
+where
+	T: EPM::Config,
+	B: BlockT + DeserializeOwned,
+	B::Header: DeserializeOwned,
+{
 	Builder::<B>::new()
-		.mode(Mode::Online(OnlineConfig {
-			transport: client.into_inner().into(),
-			at,
-			pallets,
-			hashed_prefixes: vec![<frame_system::BlockHash<T>>::prefix_hash()],
-			hashed_keys: vec![[twox_128(b"System"), twox_128(b"Number")].concat()],
-			..Default::default()
-		}))
+		.mode(Mode::Offline(OfflineConfig { state_snapshot: SnapshotConfig::new(path) }))
 		.build()
 		.await
 		.map_err(|why| Error::RemoteExternalities(why))
 		.map(|rx| rx.inner_ext)
 }
 
-/// Compute the election. It expects to NOT be `Phase::Off`. In other words, the snapshot must
-/// exists on the given externalities.
+/// Build the Ext from the state file at `snapshot` if one is given, else with the data scraped at
+/// hash from `client`.
+async fn load_or_create_election_ext<T, B>(
+	client: Option<SharedRpcClient>,
+	snapshot: Option<&Path>,
+	at: Option<B::Hash>,
+	additional: Vec<String>,
+) -> Result<Ext, Error<T>>
+where
+	T: EPM::Config,
+	B: BlockT + DeserializeOwned,
+	B::Header: DeserializeOwned,
+{
+	match (snapshot, client) {
+		(Some(path), _) => load_election_ext::<T, B>(path).await,
+		(None, Some(client)) => create_election_ext::<T, B>(client, at, additional).await,
+		(None, None) => Err(Error::Other("neither a snapshot nor a client is provided".into())),
+	}
+}
+
+/// Compute the election with the solver `S`. It expects to NOT be `Phase::Off`. In other words,
+/// the snapshot must exists on the given externalities.
 fn mine_solution<T, S>(
 	ext: &mut Ext,
 	do_feasibility: bool,
@@ -337,11 +425,22 @@ where
 	T: EPM::Config,
 	S: NposSolver<
 		Error = <<T as EPM::Config>::Solver as NposSolver>::Error,
-		AccountId = <<T as EPM::Config>::Solver as NposSolver>::AccountId,
+		AccountId = <T as frame_system::Config>::AccountId,
 	>,
 {
 	ext.execute_with(|| {
-		let (solution, _) = <EPM::Pallet<T>>::mine_solution().map_err::<Error<T>, _>(Into::into)?;
+		let EPM::RoundSnapshot { voters, targets } = EPM::Snapshot::<T>::get()
+			.ok_or_else(|| Error::Other("snapshot does not exist".into()))?;
+		let desired_targets = EPM::DesiredTargets::<T>::get()
+			.ok_or_else(|| Error::Other("desired targets do not exist".into()))?;
+		let (solution, score, _) =
+			EPM::unsigned::Miner::<T::MinerConfig>::mine_solution_with_snapshot::<S>(
+				voters,
+				targets,
+				desired_targets,
+			)
+			.map_err::<Error<T>, _>(Into::into)?;
+		let solution = EPM::RawSolution { solution, score, round: EPM::Round::<T>::get() };
 		if do_feasibility {
 			let _ = <EPM::Pallet<T>>::feasibility_check(
 				solution.clone(),
@@ -383,45 +482,58 @@ where
 				PhragMMS<<T as frame_system::Config>::AccountId, sp_runtime::Perbill, Balancing>,
 			>(ext, do_feasibility)
 		},
+		Solver::Dpos => mine_solution::<
+			T,
+			dpos::Dpos<<T as frame_system::Config>::AccountId, sp_runtime::Perbill>,
+		>(ext, do_feasibility),
+		Solver::Best { iterations, time_budget } => {
+			let deadline = Instant::now() + Duration::from_secs(*time_budget);
+			let mut best: Option<EPM::RawSolution<EPM::SolutionOf<T::MinerConfig>>> = None;
+			let mut last_error = None;
+
+			for solver in all_solvers(*iterations) {
+				if best.is_some() && Instant::now() >= deadline {
+					log::debug!(target: LOG_TARGET, "time budget exhausted, skipping {:?}", solver);
+					break
+				}
+
+				let started = Instant::now();
+				let raw_solution = match mine_with::<T>(&solver, ext, do_feasibility) {
+					Ok(raw_solution) => raw_solution,
+					Err(err) => {
+						log::warn!(target: LOG_TARGET, "{:?} failed to mine: {:?}", solver, err);
+						last_error = Some(err);
+						continue
+					},
+				};
+				log::debug!(
+					target: LOG_TARGET,
+					"{:?} mined solution with {:?} in {:?}",
+					solver,
+					raw_solution.score,
+					started.elapsed(),
+				);
+
+				let is_better = best.as_ref().map_or(true, |best| {
+					raw_solution.score.strict_threshold_better(best.score, Perbill::zero())
+				});
+				if best.is_some() && Instant::now() > deadline {
+					log::debug!(target: LOG_TARGET, "{:?} exceeded the time budget", solver);
+				} else if is_better {
+					best = Some(raw_solution);
+				}
+			}
+
+			best.ok_or_else(|| {
+				last_error.unwrap_or_else(|| Error::Other("no solver was run".into()))
+			})
+		},
 	}
 }
 
-#[allow(unused)]
-fn mine_dpos<T: EPM::Config>(ext: &mut Ext) -> Result<(), Error<T>> {
-	ext.execute_with(|| {
-		use std::collections::BTreeMap;
-		use EPM::RoundSnapshot;
-		let RoundSnapshot { voters, .. } = EPM::Snapshot::<T>::get().unwrap();
-		let desired_targets = EPM::DesiredTargets::<T>::get().unwrap();
-		let mut candidates_and_backing = BTreeMap::<T::AccountId, u128>::new();
-		voters.into_iter().for_each(|(who, stake, targets)| {
-			if targets.is_empty() {
-				println!("target = {:?}", (who, stake, targets));
-				return
-			}
-			let share: u128 = (stake as u128) / (targets.len() as u128);
-			for target in targets {
-				*candidates_and_backing.entry(target.clone()).or_default() += share
-			}
-		});
-
-		let mut candidates_and_backing =
-			candidates_and_backing.into_iter().collect::<Vec<(_, _)>>();
-		candidates_and_backing.sort_by_key(|(_, total_stake)| *total_stake);
-		let winners = candidates_and_backing
-			.into_iter()
-			.rev()
-			.take(desired_targets as usize)
-			.collect::<Vec<_>>();
-		let score = {
-			let min_staker = *winners.last().map(|(_, stake)| stake).unwrap();
-			let sum_stake = winners.iter().fold(0u128, |acc, (_, stake)| acc + stake);
-			let sum_squared = winners.iter().fold(0u128, |acc, (_, stake)| acc + stake);
-			[min_staker, sum_stake, sum_squared]
-		};
-		println!("mined a dpos-like solution with score = {:?}", score);
-		Ok(())
-	})
+/// All the solvers, with the given balancing `iterations`, from the fastest to the slowest.
+fn all_solvers(iterations: usize) -> Vec<Solver> {
+	vec![Solver::Dpos, Solver::SeqPhragmen { iterations }, Solver::PhragMMS { iterations }]
 }
 
 pub(crate) async fn check_versions<T: frame_system::Config + EPM::Config>(
@@ -509,38 +621,41 @@ async fn handle_signals(mut signals: Signals) {
 	}
 }
 
-#[tokio::main]
-async fn main() {
-	fmt().with_env_filter(EnvFilter::from_default_env()).init();
-
-	let Opt { uri, command, connection_timeout, request_timeout } = Opt::parse();
-	log::debug!(target: LOG_TARGET, "attempting to connect to {:?}", uri);
-
-	let signals = Signals::new(&[SIGTERM, SIGINT, SIGQUIT]).expect("Failed initializing Signals");
-	let handle = signals.handle();
-	let signals_task = tokio::spawn(handle_signals(signals));
+/// Execute the save-snapshot command.
+async fn save_snapshot_cmd<T, B>(
+	rpc: SharedRpcClient,
+	config: SaveSnapshotConfig,
+) -> Result<(), Error<T>>
+where
+	T: EPM::Config,
+	B: BlockT<Hash = Hash> + DeserializeOwned,
+	B::Header: DeserializeOwned,
+{
+	let pallets = if config.with_staking {
+		vec!["Staking".to_string(), "BagsList".to_string()]
+	} else {
+		Default::default()
+	};
+	save_election_ext::<T, B>(rpc, config.at, pallets, &config.path).await?;
 
-	let rpc = loop {
-		match SharedRpcClient::new(
-			&uri,
-			Duration::from_secs(connection_timeout as u64),
-			Duration::from_secs(request_timeout as u64),
-		)
-		.await
-		{
-			Ok(client) => break client,
-			Err(why) => {
-				log::warn!(
-					target: LOG_TARGET,
-					"failed to connect to client due to {:?}, retrying soon..",
-					why
-				);
-				tokio::time::sleep(std::time::Duration::from_millis(2500)).await;
-			},
-		}
+	// safety: this program will always be single threaded, thus accessing global static is safe.
+	let chain = match unsafe { &RUNTIME } {
+		AnyRuntime::Polkadot => "polkadot",
+		AnyRuntime::Kusama => "kusama",
+		AnyRuntime::Westend => "westend",
 	};
+	log::info!(
+		target: LOG_TARGET,
+		"saved the state, use it with `--snapshot {} --chain {}`",
+		config.path.display(),
+		chain,
+	);
+	Ok(())
+}
 
-	let chain: String = rpc.system_chain().await.expect("system_chain infallible; qed.");
+/// Set the runtime, the ss58 format and the token of `chain`. Returns `false` if the chain is not
+/// supported.
+fn set_runtime(chain: &str) -> bool {
 	match chain.to_lowercase().as_str() {
 		"polkadot" | "development" => {
 			sp_core::crypto::set_default_ss58_version(
@@ -578,10 +693,79 @@ async fn main() {
 				RUNTIME = AnyRuntime::Westend;
 			}
 		},
-		_ => {
+		_ => return false,
+	}
+	true
+}
+
+#[tokio::main]
+async fn main() {
+	fmt().with_env_filter(EnvFilter::from_default_env()).init();
+
+	let Opt { uri, command, connection_timeout, request_timeout } = Opt::parse();
+
+	if let Some(chain) = command.offline_chain() {
+		if !set_runtime(chain) {
 			eprintln!("unexpected chain: {:?}", chain);
 			return
-		},
+		}
+		log::info!(target: LOG_TARGET, "running offline on a snapshot of {:?}", chain);
+
+		let outcome = any_runtime! {
+			match command {
+				Command::DryRun(dryrun_config) =>
+					offline_dry_run_cmd(dryrun_config).await
+					.map_err(|e| {
+						log::error!(target: LOG_TARGET, "DryRun error: {:?}", e);
+					}),
+				Command::EmergencySolution(emergency_solution_config) =>
+					emergency_solution_cmd(None, emergency_solution_config).await
+					.map_err(|e| {
+						log::error!(target: LOG_TARGET, "EmergencySolution error: {:?}", e);
+					}),
+				Command::Compare(compare_config) =>
+					compare_cmd(None, compare_config).await
+					.map_err(|e| {
+						log::error!(target: LOG_TARGET, "Compare error: {:?}", e);
+					}),
+				Command::Monitor(_) | Command::SaveSnapshot(_) | Command::Info(_) =>
+					unreachable!("only commands with a chain are run offline; qed."),
+			}
+		};
+		log::info!(target: LOG_TARGET, "round of execution finished. outcome = {:?}", outcome);
+		return
+	}
+
+	log::debug!(target: LOG_TARGET, "attempting to connect to {:?}", uri);
+
+	let signals = Signals::new(&[SIGTERM, SIGINT, SIGQUIT]).expect("Failed initializing Signals");
+	let handle = signals.handle();
+	let signals_task = tokio::spawn(handle_signals(signals));
+
+	let rpc = loop {
+		match SharedRpcClient::new(
+			&uri,
+			Duration::from_secs(connection_timeout as u64),
+			Duration::from_secs(request_timeout as u64),
+		)
+		.await
+		{
+			Ok(client) => break client,
+			Err(why) => {
+				log::warn!(
+					target: LOG_TARGET,
+					"failed to connect to client due to {:?}, retrying soon..",
+					why
+				);
+				tokio::time::sleep(std::time::Duration::from_millis(2500)).await;
+			},
+		}
+	};
+
+	let chain: String = rpc.system_chain().await.expect("system_chain infallible; qed.");
+	if !set_runtime(&chain) {
+		eprintln!("unexpected chain: {:?}", chain);
+		return
 	}
 	log::info!(target: LOG_TARGET, "connected to chain {:?}", chain);
 
@@ -603,8 +787,12 @@ async fn main() {
 					log::error!(target: LOG_TARGET, "Monitor error: {:?}", e);
 				})},
 			Command::DryRun(dryrun_config) => {
+				let seed_or_path = dryrun_config
+					.seed_or_path
+					.as_deref()
+					.expect("seed is required unless a snapshot is given; qed.");
 				let signer_account = any_runtime! {
-					signer::signer_uri_from_string::<Runtime>(&dryrun_config.seed_or_path , &rpc)
+					signer::signer_uri_from_string::<Runtime>(seed_or_path, &rpc)
 						.await
 						.expect("Provided account is invalid, terminating.")
 				};
@@ -613,10 +801,20 @@ async fn main() {
 					log::error!(target: LOG_TARGET, "DryRun error: {:?}", e);
 				})},
 			Command::EmergencySolution(emergency_solution_config) =>
-				emergency_solution_cmd(rpc, emergency_solution_config).await
+				emergency_solution_cmd(Some(rpc), emergency_solution_config).await
 				.map_err(|e| {
 					log::error!(target: LOG_TARGET, "EmergencySolution error: {:?}", e);
 				}),
+			Command::Compare(compare_config) =>
+				compare_cmd(Some(rpc), compare_config).await
+				.map_err(|e| {
+					log::error!(target: LOG_TARGET, "Compare error: {:?}", e);
+				}),
+			Command::SaveSnapshot(save_snapshot_config) =>
+				save_snapshot_cmd::<Runtime, Block>(rpc, save_snapshot_config).await
+				.map_err(|e| {
+					log::error!(target: LOG_TARGET, "SaveSnapshot error: {:?}", e);
+				}),
 			Command::Info(info_opts) => {
 				let remote_runtime_version = rpc.runtime_version(None).await.expect("runtime_version infallible; qed.");
 
@@ -0,0 +1,135 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The Prometheus metrics of the monitor command.
+
+use crate::prelude::*;
+use prometheus_endpoint::{
+	prometheus::HistogramTimer, register, Counter, CounterVec, GaugeVec, Histogram, HistogramOpts,
+	Opts, PrometheusError, Registry, F64, U64,
+};
+use sp_npos_elections::ElectionScore;
+use std::net::{Ipv4Addr, SocketAddr};
+
+#[derive(Clone)]
+struct MetricsInner {
+	mining_duration: Histogram,
+	score: GaugeVec<F64>,
+	submissions: CounterVec<U64>,
+	fees_paid: Counter<U64>,
+	outbid: Counter<U64>,
+}
+
+/// The metrics of the monitor command, which do nothing if they are not served.
+#[derive(Default, Clone)]
+pub(crate) struct Metrics(Option<MetricsInner>);
+
+impl Metrics {
+	/// Register the metrics, and serve them on `port` of the loopback interface, or of all
+	/// interfaces if `external`.
+	pub(crate) fn serve(port: u16, external: bool) -> Result<Self, PrometheusError> {
+		let registry = Registry::new_custom(Some("staking_miner".into()), None)?;
+		let metrics = Self::register(&registry)?;
+
+		let interface = if external { Ipv4Addr::UNSPECIFIED } else { Ipv4Addr::LOCALHOST };
+		let addr = SocketAddr::from((interface, port));
+		log::info!(target: LOG_TARGET, "serving prometheus metrics on {}", addr);
+		tokio::spawn(async move {
+			if let Err(e) = prometheus_endpoint::init_prometheus(addr, registry).await {
+				log::error!(target: LOG_TARGET, "prometheus endpoint failed: {:?}", e);
+			}
+		});
+
+		Ok(metrics)
+	}
+
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		let metrics = MetricsInner {
+			mining_duration: register(
+				Histogram::with_opts(HistogramOpts::new(
+					"mining_duration_seconds",
+					"Time spent mining a solution",
+				))?,
+				registry,
+			)?,
+			score: register(
+				GaugeVec::new(
+					Opts::new("score", "Score of the last mined solution"),
+					&["component"],
+				)?,
+				registry,
+			)?,
+			submissions: register(
+				CounterVec::new(
+					Opts::new("submissions_total", "Number of submitted solutions"),
+					&["outcome"],
+				)?,
+				registry,
+			)?,
+			fees_paid: register(
+				Counter::new(
+					"fees_paid_total",
+					"Transaction fees paid for submissions, in plancks",
+				)?,
+				registry,
+			)?,
+			outbid: register(
+				Counter::new(
+					"outbid_total",
+					"Number of our solutions pushed out of the queue by better ones",
+				)?,
+				registry,
+			)?,
+		};
+		Ok(Metrics(Some(metrics)))
+	}
+
+	/// Provide a timer for mining a solution, which updates on drop.
+	pub(crate) fn time_mining(&self) -> Option<HistogramTimer> {
+		self.0.as_ref().map(|metrics| metrics.mining_duration.start_timer())
+	}
+
+	pub(crate) fn on_solution_mined(&self, score: &ElectionScore) {
+		if let Some(metrics) = &self.0 {
+			let ElectionScore { minimal_stake, sum_stake, sum_stake_squared } = *score;
+			metrics.score.with_label_values(&["minimal_stake"]).set(minimal_stake as f64);
+			metrics.score.with_label_values(&["sum_stake"]).set(sum_stake as f64);
+			metrics
+				.score
+				.with_label_values(&["sum_stake_squared"])
+				.set(sum_stake_squared as f64);
+		}
+	}
+
+	pub(crate) fn on_submission(&self, succeeded: bool) {
+		if let Some(metrics) = &self.0 {
+			let outcome = if succeeded { "success" } else { "failure" };
+			metrics.submissions.with_label_values(&[outcome]).inc();
+		}
+	}
+
+	pub(crate) fn on_fee_paid(&self, fee: Balance) {
+		if let Some(metrics) = &self.0 {
+			metrics.fees_paid.inc_by(fee.try_into().unwrap_or(u64::MAX));
+		}
+	}
+
+	pub(crate) fn on_outbid(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.outbid.inc();
+		}
+	}
+}
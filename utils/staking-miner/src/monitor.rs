@@ -17,13 +17,16 @@
 //! The monitor command.
 
 use crate::{
-	prelude::*, rpc::*, signer::Signer, Error, MonitorConfig, SharedRpcClient, SubmissionStrategy,
+	metrics::Metrics, prelude::*, rpc::*, signer::Signer, Error, MonitorConfig, SharedRpcClient,
+	SubmissionStrategy,
 };
 use codec::Encode;
 use jsonrpsee::core::Error as RpcError;
 use sc_transaction_pool_api::TransactionStatus;
 use sp_core::storage::StorageKey;
+use sp_npos_elections::ElectionScore;
 use sp_runtime::Perbill;
+use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 use EPM::{signed::SubmissionIndicesOf, SignedSubmissionOf};
 
@@ -46,12 +49,100 @@ async fn ensure_signed_phase<T: EPM::Config, B: BlockT<Hash = Hash>>(
 	}
 }
 
-/// Ensure that our current `us` have not submitted anything previously.
-async fn ensure_no_previous_solution<T, B>(
+/// What the monitor knows of its own submissions, shared by the tasks spawned for each block.
+#[derive(Debug, Default)]
+struct SubmissionTracker {
+	/// The round that the rest of the fields are about.
+	round: u32,
+	/// The estimated fees of our submissions in `round`.
+	fees_risked: Balance,
+	/// The score of our submission in the queue of `round`, and the block it was last seen at.
+	queued: Option<(BlockNumber, ElectionScore)>,
+	/// Whether a submission of ours was pushed out of the queue of `round`.
+	outbid: bool,
+}
+
+type SharedTracker = Arc<Mutex<SubmissionTracker>>;
+
+impl SubmissionTracker {
+	/// Move on to `round` if it is a new one. Returns `false` if `round` is an older one.
+	fn on_round(&mut self, round: u32) -> bool {
+		if round > self.round {
+			*self = SubmissionTracker { round, ..Default::default() };
+		}
+		round == self.round
+	}
+
+	/// Update with the queue of `round` at block `at`, where `queued` is the score of our
+	/// submission in it, if any.
+	///
+	/// Returns the score of our submission if it got pushed out of the queue since it was last
+	/// seen in it.
+	fn on_queue(
+		&mut self,
+		round: u32,
+		at: BlockNumber,
+		queued: Option<ElectionScore>,
+	) -> Option<ElectionScore> {
+		if !self.on_round(round) {
+			return None
+		}
+		match (queued, self.queued) {
+			(Some(score), Some((seen_at, _))) if at < seen_at => {
+				log::trace!(target: LOG_TARGET, "ignoring stale queue with {:?}", score);
+				None
+			},
+			(Some(score), _) => {
+				self.queued = Some((at, score));
+				None
+			},
+			(None, Some((seen_at, score))) if at > seen_at => {
+				self.queued = None;
+				self.outbid = true;
+				Some(score)
+			},
+			(None, _) => None,
+		}
+	}
+
+	/// Account for the estimated `fee` of a submission with `deposit` in `round`, unless they
+	/// exceed the caps.
+	fn try_risk<T: EPM::Config>(
+		&mut self,
+		round: u32,
+		fee: Balance,
+		deposit: Balance,
+		max_fee: Option<Balance>,
+		max_deposit: Option<Balance>,
+	) -> Result<(), Error<T>> {
+		self.on_round(round);
+		if max_deposit.map_or(false, |max| deposit > max) {
+			return Err(Error::DepositCapExceeded)
+		}
+		let fees_risked = self.fees_risked.saturating_add(fee);
+		if max_fee.map_or(false, |max| fees_risked > max) {
+			return Err(Error::FeeCapExceeded)
+		}
+		self.fees_risked = fees_risked;
+		Ok(())
+	}
+}
+
+/// Get the current round of the election.
+async fn get_round<T: EPM::Config>(rpc: &SharedRpcClient, at: Hash) -> Result<u32, Error<T>> {
+	let key = StorageKey(EPM::Round::<T>::hashed_key().to_vec());
+	rpc.get_storage_and_decode::<u32>(&key, Some(at))
+		.await
+		.map_err::<Error<T>, _>(Into::into)
+		.map(|round| round.unwrap_or(1))
+}
+
+/// Get the score of the submission of `us` in the queue, if any.
+async fn get_queued_score<T, B>(
 	rpc: &SharedRpcClient,
 	at: Hash,
 	us: &AccountId,
-) -> Result<(), Error<T>>
+) -> Result<Option<ElectionScore>, Error<T>>
 where
 	T: EPM::Config + frame_system::Config<AccountId = AccountId, Hash = Hash>,
 	B: BlockT,
@@ -73,12 +164,12 @@ where
 			.map_err::<Error<T>, _>(Into::into)?
 		{
 			if &submission.who == us {
-				return Err(Error::AlreadySubmitted)
+				return Ok(Some(submission.raw_solution.score))
 			}
 		}
 	}
 
-	Ok(())
+	Ok(None)
 }
 
 /// `true` if `our_score` should pass the onchain `best_score` with the given strategy.
@@ -99,16 +190,34 @@ pub(crate) fn score_passes_strategy(
 	}
 }
 
+/// `true` if `our_score` makes it into a queue of `queue_len` submissions, of which the weakest
+/// has `weakest_score`.
+pub(crate) fn score_fits_queue(
+	our_score: sp_npos_elections::ElectionScore,
+	weakest_score: Option<sp_npos_elections::ElectionScore>,
+	queue_len: usize,
+	max_submissions: u32,
+) -> bool {
+	queue_len < max_submissions as usize ||
+		weakest_score.map_or(true, |weakest_score| {
+			our_score.strict_threshold_better(weakest_score, Perbill::zero())
+		})
+}
+
 /// Reads all current solutions and checks the scores according to the `SubmissionStrategy`.
+///
+/// If we were `outbid` in this round, the solution must also make it into the queue, as there is
+/// no point in paying for a submission which is rejected right away.
 async fn ensure_strategy_met<T: EPM::Config, B: BlockT>(
 	rpc: &SharedRpcClient,
 	at: Hash,
 	score: sp_npos_elections::ElectionScore,
 	strategy: SubmissionStrategy,
 	max_submissions: u32,
+	outbid: bool,
 ) -> Result<(), Error<T>> {
 	// don't care about current scores.
-	if matches!(strategy, SubmissionStrategy::Always) {
+	if matches!(strategy, SubmissionStrategy::Always) && !outbid {
 		return Ok(())
 	}
 
@@ -124,6 +233,11 @@ async fn ensure_strategy_met<T: EPM::Config, B: BlockT>(
 		log::debug!(target: LOG_TARGET, "The submissions queue is full");
 	}
 
+	let weakest_score = indices.first().map(|(score, _, _)| *score);
+	if outbid && !score_fits_queue(score, weakest_score, indices.len(), max_submissions) {
+		return Err(Error::QueueFull)
+	}
+
 	// default score is all zeros, any score is better than it.
 	let best_score = indices.last().map(|(score, _, _)| *score).unwrap_or_default();
 	log::debug!(target: LOG_TARGET, "best onchain score is {:?}", best_score);
@@ -170,6 +284,11 @@ macro_rules! monitor_cmd_for { ($runtime:tt) => { paste::paste! {
 
 		let mut subscription = heads_subscription().await?;
 		let (tx, mut rx) = mpsc::unbounded_channel::<StakingMinerError>();
+		let tracker = SharedTracker::default();
+		let metrics = match config.prometheus_port {
+			Some(port) => Metrics::serve(port, config.prometheus_external)?,
+			None => Metrics::default(),
+		};
 
 		loop {
 			let at = tokio::select! {
@@ -200,9 +319,15 @@ macro_rules! monitor_cmd_for { ($runtime:tt) => { paste::paste! {
 
 			// Spawn task and non-recoverable errors are sent back to the main task
 			// such as if the connection has been closed.
-			tokio::spawn(
-				send_and_watch_extrinsic(rpc.clone(), tx.clone(), at, signer.clone(), config.clone())
-			);
+			tokio::spawn(send_and_watch_extrinsic(
+				rpc.clone(),
+				tx.clone(),
+				at,
+				signer.clone(),
+				config.clone(),
+				tracker.clone(),
+				metrics.clone(),
+			));
 
 		}
 
@@ -213,6 +338,8 @@ macro_rules! monitor_cmd_for { ($runtime:tt) => { paste::paste! {
 			at: Header,
 			signer: Signer,
 			config: MonitorConfig,
+			tracker: SharedTracker,
+			metrics: Metrics,
 		) {
 
 			async fn flatten<T>(
@@ -245,13 +372,35 @@ macro_rules! monitor_cmd_for { ($runtime:tt) => { paste::paste! {
 
 			tokio::time::sleep(std::time::Duration::from_secs(config.delay as u64)).await;
 
-			let no_prev_sol_fut = tokio::spawn(async move {
-				ensure_no_previous_solution::<Runtime, Block>(&rpc2, hash, &account).await
+			let queue_fut = tokio::spawn(async move {
+				let round = get_round::<Runtime>(&rpc2, hash).await?;
+				let queued = get_queued_score::<Runtime, Block>(&rpc2, hash, &account).await?;
+				Ok::<_, StakingMinerError>((round, queued))
 			});
 
 			// Run the calls in parallel and return once all has completed or any failed.
-			if let Err(err) = tokio::try_join!(flatten(signed_phase_fut), flatten(no_prev_sol_fut)) {
-				log::debug!(target: LOG_TARGET, "Skipping block {}; {}", at.number, err);
+			let (round, queued) = match tokio::try_join!(flatten(signed_phase_fut), flatten(queue_fut)) {
+				Ok((_, round_and_queued)) => round_and_queued,
+				Err(err) => {
+					log::debug!(target: LOG_TARGET, "Skipping block {}; {}", at.number, err);
+					return;
+				}
+			};
+
+			let pushed_out = tracker
+				.lock()
+				.expect("tracker is never poisoned; qed.")
+				.on_queue(round, at.number, queued);
+			if let Some(score) = pushed_out {
+				log::warn!(
+					target: LOG_TARGET,
+					"our solution with {:?} was pushed out of the queue at block {}",
+					score, at.number,
+				);
+				metrics.on_outbid();
+			}
+			if queued.is_some() {
+				log::debug!(target: LOG_TARGET, "Skipping block {}; {}", at.number, StakingMinerError::AlreadySubmitted);
 				return;
 			}
 
@@ -264,6 +413,7 @@ macro_rules! monitor_cmd_for { ($runtime:tt) => { paste::paste! {
 			};
 
 			// mine a solution, and run feasibility check on it as well.
+			let mining_timer = metrics.time_mining();
 			let raw_solution = match crate::mine_with::<Runtime>(&config.solver, &mut ext, true) {
 				Ok(r) => r,
 				Err(err) => {
@@ -271,9 +421,17 @@ macro_rules! monitor_cmd_for { ($runtime:tt) => { paste::paste! {
 					return;
 				}
 			};
+			drop(mining_timer);
 
 			let score = raw_solution.score;
 			log::info!(target: LOG_TARGET, "mined solution with {:?}", score);
+			metrics.on_solution_mined(&score);
+
+			let deposit = ext.execute_with(|| {
+				let size = EPM::Pallet::<Runtime>::snapshot_metadata()
+					.expect("snapshot exists as a solution was mined from it; qed.");
+				EPM::Pallet::<Runtime>::deposit_for(&raw_solution, size)
+			});
 
 			let nonce = match crate::get_account_info::<Runtime>(&rpc, &signer.account, Some(hash)).await {
 				Ok(maybe_account) => {
@@ -311,13 +469,19 @@ macro_rules! monitor_cmd_for { ($runtime:tt) => { paste::paste! {
 				}
 			};
 
+			let outbid = {
+				let tracker = tracker.lock().expect("tracker is never poisoned; qed.");
+				tracker.round == round && tracker.outbid
+			};
+			let submission_strategy = config.submission_strategy;
 			let ensure_strategy_met_fut = tokio::spawn(async move {
 				ensure_strategy_met::<Runtime, Block>(
 					&rpc1,
 					latest_head,
 					score,
-					config.submission_strategy,
-					SignedMaxSubmissions::get()
+					submission_strategy,
+					SignedMaxSubmissions::get(),
+					outbid,
 				).await
 			});
 
@@ -334,6 +498,26 @@ macro_rules! monitor_cmd_for { ($runtime:tt) => { paste::paste! {
 				return;
 			}
 
+			let fee = match rpc.payment_query_info(&bytes, Some(&latest_head)).await {
+				Ok(info) => info.partial_fee,
+				Err(err) => {
+					log::debug!(target: LOG_TARGET, "Skipping to submit at block {}; {}", at.number, err);
+					return;
+				}
+			};
+			let risked = tracker
+				.lock()
+				.expect("tracker is never poisoned; qed.")
+				.try_risk::<Runtime>(round, fee, deposit, config.max_fee, config.max_deposit);
+			if let Err(err) = risked {
+				log::warn!(
+					target: LOG_TARGET,
+					"Skipping to submit at block {}; {} (fee {}, deposit {})",
+					at.number, err, Token::from(fee), Token::from(deposit),
+				);
+				return;
+			}
+
 			let mut tx_subscription = match rpc.watch_extrinsic(&bytes).await {
 				Ok(sub) => sub,
 				Err(RpcError::RestartNeeded(e)) => {
@@ -353,10 +537,15 @@ macro_rules! monitor_cmd_for { ($runtime:tt) => { paste::paste! {
 						"failing to submit a transaction {:?}. ignore block: {}",
 						why, at.number
 					);
+					metrics.on_submission(false);
 					return;
 				},
 			};
 
+			// whether the outcome of the submission was reported, as it can be included more than
+			// once if retracted.
+			let mut reported = false;
+
 			while let Some(rp) = tx_subscription.next().await {
 				let status_update = match rp {
 					Ok(r) => r,
@@ -397,6 +586,23 @@ macro_rules! monitor_cmd_for { ($runtime:tt) => { paste::paste! {
 						};
 
 						log::info!(target: LOG_TARGET, "events at inclusion {:?}", events);
+						if reported {
+							continue
+						}
+						reported = true;
+
+						let fee_paid = events.iter().filter_map(|record| match &record.event {
+							RuntimeEvent::TransactionPayment(
+								::pallet_transaction_payment::Event::TransactionFeePaid { who, actual_fee, .. }
+							) if who == &signer.account => Some(*actual_fee),
+							_ => None,
+						}).fold(0 as Balance, |total, fee| total.saturating_add(fee));
+						metrics.on_fee_paid(fee_paid);
+
+						match get_queued_score::<Runtime, Block>(&rpc, hash, &signer.account).await {
+							Ok(queued) => metrics.on_submission(queued.is_some()),
+							Err(e) => log::warn!(target: LOG_TARGET, "failed to read the queue at {:?}: {:?}", hash, e),
+						}
 					},
 					TransactionStatus::Retracted(hash) => {
 						log::info!(target: LOG_TARGET, "Retracted at {:?}", hash);
@@ -411,6 +617,9 @@ macro_rules! monitor_cmd_for { ($runtime:tt) => { paste::paste! {
 							"Stopping listen due to other status {:?}",
 							status_update
 						);
+						if !reported {
+							metrics.on_submission(false);
+						}
 						break
 					},
 				};
@@ -464,4 +673,69 @@ pub mod tests {
 		assert!(score_passes_strategy(s(103), s(100), SubmissionStrategy::ClaimNoWorseThan(two)));
 		assert!(score_passes_strategy(s(150), s(100), SubmissionStrategy::ClaimNoWorseThan(two)));
 	}
+
+	#[test]
+	fn score_fits_queue_works() {
+		let s = |x| sp_npos_elections::ElectionScore { minimal_stake: x, ..Default::default() };
+
+		// anything fits a queue with room left.
+		assert!(score_fits_queue(s(0), None, 0, 2));
+		assert!(score_fits_queue(s(5), Some(s(10)), 1, 2));
+
+		// a full queue only takes scores better than its weakest.
+		assert!(!score_fits_queue(s(5), Some(s(10)), 2, 2));
+		assert!(!score_fits_queue(s(10), Some(s(10)), 2, 2));
+		assert!(score_fits_queue(s(11), Some(s(10)), 2, 2));
+	}
+
+	#[test]
+	fn submission_tracker_reports_outbid() {
+		let s = |x| sp_npos_elections::ElectionScore { minimal_stake: x, ..Default::default() };
+		let mut tracker = SubmissionTracker::default();
+
+		// our submission shows up in the queue.
+		assert_eq!(tracker.on_queue(1, 10, None), None);
+		assert_eq!(tracker.on_queue(1, 11, Some(s(5))), None);
+		// a queue read before it was seen is ignored.
+		assert_eq!(tracker.on_queue(1, 10, None), None);
+		assert_eq!(tracker.on_queue(1, 11, None), None);
+		assert!(!tracker.outbid);
+
+		// then it is pushed out.
+		assert_eq!(tracker.on_queue(1, 12, None), Some(s(5)));
+		assert!(tracker.outbid);
+		assert_eq!(tracker.on_queue(1, 13, None), None);
+
+		// a new round starts afresh, and older rounds are ignored.
+		assert_eq!(tracker.on_queue(2, 20, Some(s(7))), None);
+		assert!(!tracker.outbid);
+		assert_eq!(tracker.on_queue(1, 14, None), None);
+		assert_eq!(tracker.queued, Some((20, s(7))));
+	}
+
+	#[test]
+	fn submission_tracker_caps_costs() {
+		type Runtime = polkadot_runtime::Runtime;
+		let mut tracker = SubmissionTracker::default();
+
+		// no caps.
+		assert!(tracker.try_risk::<Runtime>(1, 100, 1_000, None, None).is_ok());
+		assert_eq!(tracker.fees_risked, 100);
+
+		// the deposit is capped per submission.
+		assert!(matches!(
+			tracker.try_risk::<Runtime>(1, 100, 1_000, None, Some(999)),
+			Err(Error::DepositCapExceeded)
+		));
+		assert!(tracker.try_risk::<Runtime>(1, 100, 1_000, None, Some(1_000)).is_ok());
+
+		// the fees are capped per round.
+		assert!(matches!(
+			tracker.try_risk::<Runtime>(1, 100, 1_000, Some(250), None),
+			Err(Error::FeeCapExceeded)
+		));
+		assert_eq!(tracker.fees_risked, 200);
+		assert!(tracker.try_risk::<Runtime>(2, 100, 1_000, Some(250), None).is_ok());
+		assert_eq!(tracker.fees_risked, 100);
+	}
 }
@@ -17,7 +17,7 @@
 use crate::prelude::*;
 use clap::Parser;
 use sp_runtime::Perbill;
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
 
 #[derive(Debug, Clone, Parser)]
 #[cfg_attr(test, derive(PartialEq))]
@@ -51,6 +51,12 @@ pub(crate) enum Command {
 	/// Provide a solution that can be submitted to the chain as an emergency response.
 	EmergencySolution(EmergencySolutionConfig),
 
+	/// Compute a solution now with every solver, and report the score of each.
+	Compare(CompareConfig),
+
+	/// Save the state needed to compute a solution to a file, to later use it with `--snapshot`.
+	SaveSnapshot(SaveSnapshotConfig),
+
 	/// Return information about the current version
 	Info(InfoOpts),
 }
@@ -104,6 +110,28 @@ pub(crate) struct MonitorConfig {
 	/// if the scores are equal.
 	#[arg(long, default_value_t = 0)]
 	pub delay: usize,
+
+	/// The maximum transaction fees to risk on the submissions of a round, in plancks.
+	///
+	/// A submission is skipped if its estimated fee would exceed it.
+	#[arg(long)]
+	pub max_fee: Option<Balance>,
+
+	/// The maximum deposit to reserve for a submission, in plancks.
+	///
+	/// A submission is skipped if its deposit would exceed it.
+	#[arg(long)]
+	pub max_deposit: Option<Balance>,
+
+	/// Serve Prometheus metrics on this port.
+	#[arg(long)]
+	pub prometheus_port: Option<u16>,
+
+	/// Expose the Prometheus metrics on all interfaces.
+	///
+	/// Default is local.
+	#[arg(long)]
+	pub prometheus_external: bool,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -116,8 +144,10 @@ pub(crate) struct DryRunConfig {
 	///
 	/// WARNING: Don't use an account with a large stash for this. Based on how the bot is
 	/// configured, it might re-try and lose funds through transaction fees/deposits.
-	#[arg(long, short, env = "SEED")]
-	pub seed_or_path: String,
+	///
+	/// Not needed with `--snapshot`, as no transaction is built then.
+	#[arg(long, short, env = "SEED", required_unless_present = "snapshot")]
+	pub seed_or_path: Option<String>,
 
 	/// The block hash at which scraping happens. If none is provided, the latest head is used.
 	#[arg(long)]
@@ -130,6 +160,14 @@ pub(crate) struct DryRunConfig {
 	/// Force create a new snapshot, else expect one to exist onchain.
 	#[arg(long)]
 	pub force_snapshot: bool,
+
+	/// Read the state from a file written by `save-snapshot`, instead of connecting to a node.
+	#[arg(long, requires = "chain", conflicts_with = "at")]
+	pub snapshot: Option<PathBuf>,
+
+	/// The chain that `snapshot` was saved from.
+	#[arg(long, requires = "snapshot", value_parser = ["polkadot", "kusama", "westend"])]
+	pub chain: Option<String>,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -145,6 +183,55 @@ pub(crate) struct EmergencySolutionConfig {
 
 	/// The number of top backed winners to take. All are taken, if not provided.
 	pub take: Option<usize>,
+
+	/// Read the state from a file written by `save-snapshot`, instead of connecting to a node.
+	#[arg(long, requires = "chain", conflicts_with = "at")]
+	pub snapshot: Option<PathBuf>,
+
+	/// The chain that `snapshot` was saved from.
+	#[arg(long, requires = "snapshot", value_parser = ["polkadot", "kusama", "westend"])]
+	pub chain: Option<String>,
+}
+
+#[derive(Debug, Clone, Parser)]
+#[cfg_attr(test, derive(PartialEq))]
+pub(crate) struct CompareConfig {
+	/// The block hash at which scraping happens. If none is provided, the latest head is used.
+	#[arg(long)]
+	pub at: Option<Hash>,
+
+	/// The number of balancing iterations of the solvers which balance their solution.
+	#[arg(long, default_value_t = 10)]
+	pub iterations: usize,
+
+	/// Force create a new snapshot, else expect one to exist onchain.
+	#[arg(long)]
+	pub force_snapshot: bool,
+
+	/// Read the state from a file written by `save-snapshot`, instead of connecting to a node.
+	#[arg(long, requires = "chain", conflicts_with = "at")]
+	pub snapshot: Option<PathBuf>,
+
+	/// The chain that `snapshot` was saved from.
+	#[arg(long, requires = "snapshot", value_parser = ["polkadot", "kusama", "westend"])]
+	pub chain: Option<String>,
+}
+
+#[derive(Debug, Clone, Parser)]
+#[cfg_attr(test, derive(PartialEq))]
+pub(crate) struct SaveSnapshotConfig {
+	/// The block hash at which scraping happens. If none is provided, the latest head is used.
+	#[arg(long)]
+	pub at: Option<Hash>,
+
+	/// The file to write the state to.
+	#[arg(long)]
+	pub path: PathBuf,
+
+	/// Also save the state of the staking pallets, so that `--force-snapshot` can be used with
+	/// the saved state.
+	#[arg(long)]
+	pub with_staking: bool,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -155,6 +242,18 @@ pub(crate) struct InfoOpts {
 	pub json: bool,
 }
 
+impl Command {
+	/// The chain of the state snapshot that the command runs from, if it runs offline.
+	pub(crate) fn offline_chain(&self) -> Option<&str> {
+		match self {
+			Command::DryRun(DryRunConfig { chain, .. }) |
+			Command::EmergencySolution(EmergencySolutionConfig { chain, .. }) |
+			Command::Compare(CompareConfig { chain, .. }) => chain.as_deref(),
+			Command::Monitor(_) | Command::SaveSnapshot(_) | Command::Info(_) => None,
+		}
+	}
+}
+
 /// Submission strategy to use.
 #[derive(Debug, Copy, Clone)]
 #[cfg_attr(test, derive(PartialEq))]
@@ -181,6 +280,17 @@ pub(crate) enum Solver {
 		#[arg(long, default_value_t = 10)]
 		iterations: usize,
 	},
+	/// Elect the targets with the most approval stake, without any balancing. Fast, but usually
+	/// scores worse than the other solvers.
+	Dpos,
+	/// Run every solver in turn, and keep the best scoring solution found within `time_budget`
+	/// seconds. At least one solution is always computed.
+	Best {
+		#[arg(long, default_value_t = 10)]
+		iterations: usize,
+		#[arg(long, default_value_t = 30)]
+		time_budget: u64,
+	},
 }
 
 /// Custom `impl` to parse `SubmissionStrategy` from CLI.
@@ -247,11 +357,44 @@ mod test_super {
 					solver: Solver::SeqPhragmen { iterations: 10 },
 					submission_strategy: SubmissionStrategy::IfLeading,
 					delay: 12,
+					max_fee: None,
+					max_deposit: None,
+					prometheus_port: None,
+					prometheus_external: false,
 				}),
 			}
 		);
 	}
 
+	#[test]
+	fn cli_monitor_caps_and_metrics_work() {
+		let opt = Opt::try_parse_from([
+			env!("CARGO_PKG_NAME"),
+			"monitor",
+			"--seed-or-path",
+			"//Alice",
+			"--max-fee",
+			"1000000000000",
+			"--max-deposit",
+			"50000000000000",
+			"--prometheus-port",
+			"9999",
+			"--prometheus-external",
+			"seq-phragmen",
+		])
+		.unwrap();
+
+		match opt.command {
+			Command::Monitor(config) => {
+				assert_eq!(config.max_fee, Some(1_000_000_000_000));
+				assert_eq!(config.max_deposit, Some(50_000_000_000_000));
+				assert_eq!(config.prometheus_port, Some(9999));
+				assert!(config.prometheus_external);
+			},
+			_ => panic!("monitor command expected"),
+		}
+	}
+
 	#[test]
 	fn cli_dry_run_works() {
 		let opt = Opt::try_parse_from([
@@ -272,10 +415,12 @@ mod test_super {
 				connection_timeout: 60,
 				request_timeout: 10 * 60,
 				command: Command::DryRun(DryRunConfig {
-					seed_or_path: "//Alice".to_string(),
+					seed_or_path: Some("//Alice".to_string()),
 					at: None,
 					solver: Solver::PhragMMS { iterations: 10 },
 					force_snapshot: false,
+					snapshot: None,
+					chain: None,
 				}),
 			}
 		);
@@ -304,12 +449,141 @@ mod test_super {
 				command: Command::EmergencySolution(EmergencySolutionConfig {
 					take: Some(99),
 					at: None,
-					solver: Solver::PhragMMS { iterations: 1337 }
+					solver: Solver::PhragMMS { iterations: 1337 },
+					snapshot: None,
+					chain: None,
 				}),
 			}
 		);
 	}
 
+	#[test]
+	fn cli_monitor_best_solver_works() {
+		let opt = Opt::try_parse_from([
+			env!("CARGO_PKG_NAME"),
+			"--uri",
+			"hi",
+			"monitor",
+			"--seed-or-path",
+			"//Alice",
+			"best",
+			"--time-budget",
+			"12",
+		])
+		.unwrap();
+
+		assert_eq!(
+			opt,
+			Opt {
+				uri: "hi".to_string(),
+				connection_timeout: 60,
+				request_timeout: 10 * 60,
+				command: Command::Monitor(MonitorConfig {
+					seed_or_path: "//Alice".to_string(),
+					listen: "head".to_string(),
+					solver: Solver::Best { iterations: 10, time_budget: 12 },
+					submission_strategy: SubmissionStrategy::IfLeading,
+					delay: 0,
+					max_fee: None,
+					max_deposit: None,
+					prometheus_port: None,
+					prometheus_external: false,
+				}),
+			}
+		);
+	}
+
+	#[test]
+	fn cli_compare_works() {
+		let opt = Opt::try_parse_from([
+			env!("CARGO_PKG_NAME"),
+			"--uri",
+			"hi",
+			"compare",
+			"--iterations",
+			"20",
+			"--force-snapshot",
+		])
+		.unwrap();
+
+		assert_eq!(
+			opt,
+			Opt {
+				uri: "hi".to_string(),
+				connection_timeout: 60,
+				request_timeout: 10 * 60,
+				command: Command::Compare(CompareConfig {
+					at: None,
+					iterations: 20,
+					force_snapshot: true,
+					snapshot: None,
+					chain: None,
+				}),
+			}
+		);
+	}
+
+	#[test]
+	fn cli_offline_dry_run_works() {
+		let opt = Opt::try_parse_from([
+			env!("CARGO_PKG_NAME"),
+			"dry-run",
+			"--snapshot",
+			"election.snap",
+			"--chain",
+			"kusama",
+			"seq-phragmen",
+		])
+		.unwrap();
+
+		assert_eq!(
+			opt.command,
+			Command::DryRun(DryRunConfig {
+				seed_or_path: None,
+				at: None,
+				solver: Solver::SeqPhragmen { iterations: 10 },
+				force_snapshot: false,
+				snapshot: Some(PathBuf::from("election.snap")),
+				chain: Some("kusama".to_string()),
+			})
+		);
+		assert_eq!(opt.command.offline_chain(), Some("kusama"));
+
+		// a snapshot cannot be used without its chain.
+		assert!(Opt::try_parse_from([
+			env!("CARGO_PKG_NAME"),
+			"dry-run",
+			"--snapshot",
+			"election.snap",
+			"seq-phragmen",
+		])
+		.is_err());
+	}
+
+	#[test]
+	fn cli_save_snapshot_works() {
+		let opt = Opt::try_parse_from([
+			env!("CARGO_PKG_NAME"),
+			"--uri",
+			"hi",
+			"save-snapshot",
+			"--path",
+			"election.snap",
+			"--with-staking",
+		])
+		.unwrap();
+
+		assert_eq!(
+			opt.command,
+			Command::SaveSnapshot(SaveSnapshotConfig {
+				at: None,
+				path: PathBuf::from("election.snap"),
+				with_staking: true,
+			})
+		);
+		assert_eq!(opt.command.offline_chain(), None);
+	}
+
 	#[test]
 	fn cli_info_works() {
 		let opt = Opt::try_parse_from([env!("CARGO_PKG_NAME"), "--uri", "hi", "info"]).unwrap();